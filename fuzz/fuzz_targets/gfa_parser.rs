@@ -0,0 +1,37 @@
+//! Parses arbitrary bytes as GFA with both node identifier types and all parsing modes.
+//!
+//! Parsing may fail, but it must not panic or loop.
+//! If parsing succeeds, the graph must have a stable name.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use pggname::{Graph, algorithms};
+use pggname::algorithms::{NamePolicy, ParseOptions};
+use pggname::graph::{GraphInt, GraphStr};
+
+fn parse<G: Graph>(data: &[u8], options: &ParseOptions) {
+    if let Ok((graph, _)) = algorithms::parse_gfa_bytes_with_options::<G>(data, options) {
+        let _ = algorithms::stable_name(&graph);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    // The first byte selects the options.
+    let Some((&flags, data)) = data.split_first() else {
+        return;
+    };
+    let options = ParseOptions {
+        lenient: flags & 0x01 != 0,
+        pack_sequences: flags & 0x02 != 0,
+        strict: flags & 0x04 != 0,
+        names: match (flags >> 3) & 0x03 {
+            1 => NamePolicy::Reject,
+            2 => NamePolicy::Escape,
+            _ => NamePolicy::Allow,
+        },
+    };
+    parse::<GraphInt>(data, &options);
+    parse::<GraphStr>(data, &options);
+});
@@ -0,0 +1,26 @@
+//! Parses arbitrary GFA, GAF, and VCF header lines, as well as compact `GraphName` strings.
+//!
+//! Parsing may fail, but it must not panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use pggname::GraphName;
+
+fuzz_target!(|data: &[u8]| {
+    let data = String::from_utf8_lossy(data);
+    let lines: Vec<String> = data.lines().map(String::from).collect();
+    for line in lines.iter() {
+        for field in line.split('\t') {
+            let _ = GraphName::parse_gfa_header_field(field);
+        }
+        let _ = GraphName::is_gaf_header_line(line);
+        let _ = GraphName::is_vcf_header_line(line);
+        if let Ok(name) = line.parse::<GraphName>() {
+            assert_eq!(name.to_string().parse::<GraphName>(), Ok(name), "Compact string round trip failed");
+        }
+    }
+    let _ = GraphName::from_header_lines(&lines);
+    let _ = GraphName::from_vcf_header_lines(&lines);
+});
@@ -0,0 +1,26 @@
+//! Parses a `GraphName` from arbitrary GBZ tags.
+//!
+//! Each input line is a tab-separated key-value pair.
+//! Parsing may fail, but it must not panic, and a successful result must survive a round trip through tags.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use gbz::support::Tags;
+
+use pggname::GraphName;
+
+fuzz_target!(|data: &[u8]| {
+    let data = String::from_utf8_lossy(data);
+    let mut tags = Tags::new();
+    for line in data.lines() {
+        let (key, value) = line.split_once('\t').unwrap_or((line, ""));
+        tags.insert(key, value);
+    }
+    if let Ok(name) = GraphName::from_tags(&tags) {
+        let mut copy = Tags::new();
+        name.set_tags(&mut copy);
+        assert_eq!(GraphName::from_tags(&copy), Ok(name), "Tag round trip failed");
+    }
+});
@@ -0,0 +1,67 @@
+/*
+  C API for pggname: stable names for pangenome graphs.
+
+  Strings passed to the functions are NUL-terminated UTF-8 strings.
+  Strings returned by the functions are owned by the caller and must be released with pggname_string_free().
+  Graph name handles are owned by the caller and must be released with pggname_graph_name_free().
+  Functions returning pointers return NULL on failure, and functions returning int return -1.
+  The error message is then available from pggname_last_error().
+*/
+
+#ifndef PGGNAME_H
+#define PGGNAME_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Stable name of a graph together with known subgraph and translation relationships. */
+typedef struct pggname_graph_name pggname_graph_name_t;
+
+/* Errors and memory management. */
+
+/* Message for the last error in the current thread, or NULL. Owned by the library. */
+const char* pggname_last_error(void);
+
+void pggname_string_free(char* value);
+
+/* Naming graphs. */
+
+/* Stable name of a (possibly gzip-compressed) GFA file. */
+char* pggname_hash_gfa_path(const char* path);
+
+/* Stable name of a GBZ file. */
+char* pggname_hash_gbz_path(const char* path);
+
+/* Graph names and relationships. */
+
+pggname_graph_name_t* pggname_graph_name_new(const char* name);
+
+/* Parses newline-separated GFA (H) or GAF (@) header lines. */
+pggname_graph_name_t* pggname_graph_name_from_header(const char* header);
+
+void pggname_graph_name_free(pggname_graph_name_t* name);
+
+/* Returns NULL if the handle does not contain a name. */
+char* pggname_graph_name_name(const pggname_graph_name_t* name);
+
+/* Header lines representing the handle, each ending with a newline. */
+char* pggname_graph_name_gfa_header(const pggname_graph_name_t* name);
+char* pggname_graph_name_gaf_header(const pggname_graph_name_t* name);
+
+/* Queries return 1 for true, 0 for false, and -1 on error. */
+int pggname_is_same(const pggname_graph_name_t* a, const pggname_graph_name_t* b);
+int pggname_is_subgraph_of(const pggname_graph_name_t* a, const pggname_graph_name_t* b);
+int pggname_translates_to(const pggname_graph_name_t* a, const pggname_graph_name_t* b);
+
+/* Multi-line description of the relationship between the graphs. */
+char* pggname_describe_relationship(
+  const pggname_graph_name_t* a, const pggname_graph_name_t* b,
+  const char* a_desc, const char* b_desc
+);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PGGNAME_H */
@@ -1,117 +1,2216 @@
 //! Algorithms for computing stable graph names.
 
-use crate::Graph;
+use crate::{Graph, GraphName};
+use crate::graph::{GraphInt, GraphStr, Orientation};
+use crate::jumps::{JumpPolicy, Jumps};
+#[cfg(feature = "gbz")]
+use crate::graph::{GBZStr, LazyGBZ};
+use crate::translation::{self, SequenceGraph, TranslationInference};
 
-use gbz::Orientation;
+use flate2::read::MultiGzDecoder;
+
+#[cfg(feature = "gbz")]
+use gbz::GBZ;
 
 use sha2::Digest;
 use sha2::digest;
 
-use std::io::BufRead;
+#[cfg(feature = "gbz")]
+use simple_sds::serialize;
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "async")]
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+//-----------------------------------------------------------------------------
+
+/// Builds a graph from the given GFA input.
+///
+/// Returns an error if reading the input fails of if the GFA cannot be parsed.
+/// Passes through errors from the graph methods with the line number.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::Graph;
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+/// use gbz::support;
+/// use std::fs::OpenOptions;
+/// use std::io::BufReader;
+///
+/// let filename = support::get_test_data("example.gfa");
+/// let file = OpenOptions::new().read(true).open(&filename).unwrap();
+/// let reader = BufReader::new(file);
+/// let graph = algorithms::parse_gfa::<GraphInt, _>(reader);
+/// assert!(graph.is_ok());
+///
+/// let graph = graph.unwrap();
+/// let stats = graph.statistics();
+/// assert_eq!(stats.nodes, 12);
+/// assert_eq!(stats.edges, 13);
+/// assert_eq!(stats.sequence_len, 12);
+/// ```
+///
+/// Any [`BufRead`] implementation works, including in-memory data wrapped in a [`std::io::Cursor`]:
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphStr;
+/// use std::io::Cursor;
+///
+/// let gfa = b"S\t11\tACCTT\nS\t12\tTCAAGG\nS\t13\tCTTGATT\nL\t11\t+\t12\t-\t0M\nL\t12\t-\t13\t+\t0M\nL\t11\t+\t13\t+\t0M\n";
+/// let graph = algorithms::parse_gfa::<GraphStr, _>(Cursor::new(&gfa[..])).unwrap();
+/// let name = pggname::stable_name(&graph);
+/// assert_eq!(name, "54b49d18354a34fbd1af9aaac279e1b3ee67b2f68f0ff79f5ebf6c50c8d922a5");
+/// ```
+pub fn parse_gfa<G: Graph, R: BufRead>(reader: R) -> Result<G, String> {
+    let (graph, _) = parse_gfa_with_options(reader, &ParseOptions::default())?;
+    Ok(graph)
+}
+
+/// Options for parsing GFA input.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Record malformed lines in the report and skip them instead of returning an error.
+    ///
+    /// Errors in reading the input and missing nodes are still fatal.
+    pub lenient: bool,
+
+    /// Store node sequences in packed form while parsing.
+    ///
+    /// This reduces memory usage for sequences consisting mostly of upper case `ACGT` without changing the name.
+    /// See [`Graph::pack_sequences`].
+    pub pack_sequences: bool,
+
+    /// Validate the input more strictly.
+    ///
+    /// Trailing whitespace on a line is an error instead of being stripped.
+    /// This includes the carriage return from Windows (CRLF) line endings.
+    /// By default, trailing whitespace is stripped and the stripped lines are counted in the report.
+    /// Only spaces and carriage returns count as trailing whitespace, as a trailing tab delimits an empty field.
+    ///
+    /// The `LN:i` tag on a segment line must match the length of the sequence, unless the sequence is `*`.
+    pub strict: bool,
+
+    /// How to handle segment names with characters outside the portable set.
+    pub names: NamePolicy,
+
+    /// How to handle GFA 1.2 jump lines.
+    ///
+    /// With [`JumpPolicy::Include`], the jumps are collected into [`ParseReport::jumps`] while parsing.
+    /// Segment names in the jumps are handled according to [`ParseOptions::names`].
+    pub jumps: JumpPolicy,
+}
+
+/// Policy for segment names containing characters outside the portable set.
+///
+/// Portable characters are printable ASCII characters other than the comma (`!` to `~`, excluding `,`).
+/// Other characters, such as whitespace, commas, control characters, and non-ASCII bytes, collide with the separators used in GFA path lines, header tags, and text formats derived from the canonical GFA.
+/// The policy applies to segment names in both segment and link lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NamePolicy {
+    /// Use the names as they are.
+    #[default]
+    Allow,
+    /// Treat names with non-portable characters as errors.
+    Reject,
+    /// Percent-encode non-portable characters and `%` as `%XX` with upper case hexadecimal digits.
+    ///
+    /// The encoding is deterministic and injective, but names with `%` change even if they are otherwise portable.
+    /// Graphs with escaped names are named in version [`crate::vectors::PORTABLE_FORMAT_VERSION`] of the canonical format with [`hash_portable`].
+    /// This encoding differs from [`escape_field`], which is used in version [`crate::vectors::ESCAPED_FORMAT_VERSION`].
+    Escape,
+}
+
+impl NamePolicy {
+    /// Returns `true` if the byte is a portable name character.
+    pub fn is_portable(c: u8) -> bool {
+        c.is_ascii_graphic() && c != b','
+    }
+
+    /// Applies the policy to the segment name.
+    ///
+    /// Returns the name, possibly escaped, or the reason why it was rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::algorithms::NamePolicy;
+    ///
+    /// assert_eq!(NamePolicy::Allow.apply(b"chr1 a").unwrap().as_ref(), b"chr1 a");
+    /// assert!(NamePolicy::Reject.apply(b"chr1 a").is_err());
+    /// assert_eq!(NamePolicy::Escape.apply(b"chr1 a,50%").unwrap().as_ref(), b"chr1%20a%2C50%25");
+    /// assert_eq!(NamePolicy::Escape.apply(b"chr1").unwrap().as_ref(), b"chr1");
+    /// ```
+    pub fn apply(self, name: &[u8]) -> Result<Cow<'_, [u8]>, String> {
+        match self {
+            NamePolicy::Allow => Ok(Cow::Borrowed(name)),
+            NamePolicy::Reject => match name.iter().position(|&c| !Self::is_portable(c)) {
+                Some(i) => Err(format!(
+                    "segment name {} contains a non-portable character at position {}",
+                    String::from_utf8_lossy(name), i
+                )),
+                None => Ok(Cow::Borrowed(name)),
+            },
+            NamePolicy::Escape => {
+                if name.iter().all(|&c| Self::is_portable(c) && c != b'%') {
+                    return Ok(Cow::Borrowed(name));
+                }
+                let mut result = Vec::with_capacity(name.len() + 8);
+                for &c in name {
+                    if Self::is_portable(c) && c != b'%' {
+                        result.push(c);
+                    } else {
+                        result.extend_from_slice(format!("%{:02X}", c).as_bytes());
+                    }
+                }
+                Ok(Cow::Owned(result))
+            }
+        }
+    }
+}
+
+impl FromStr for NamePolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "allow" => Ok(NamePolicy::Allow),
+            "reject" => Ok(NamePolicy::Reject),
+            "escape" => Ok(NamePolicy::Escape),
+            _ => Err(format!("Invalid segment name policy: {}", value)),
+        }
+    }
+}
+
+/// Returns the position of the first tab or newline in a node name or a sequence.
+///
+/// Tabs and newlines delimit fields and records in the canonical GFA representation.
+/// If a name or a sequence could contain them, one graph could imitate the canonical representation of another graph with a different structure.
+/// [`Graph::add_node`] therefore rejects such names and sequences in version 1 of the canonical format.
+/// Names and sequences parsed from GFA lines never contain them.
+/// See [`escape_field`] for including them under version 2.
+pub fn find_delimiter(field: &[u8]) -> Option<usize> {
+    memchr::memchr2(b'\t', b'\n', field)
+}
+
+/// Escapes a node name or a sequence for version 2 of the canonical format.
+///
+/// Version 2 percent-encodes tabs, newlines, and `%` as `%09`, `%0A`, and `%25`, and leaves all other bytes unchanged.
+/// The encoding is deterministic and injective, and the escaped field never contains delimiters.
+/// The version is given by [`crate::vectors::ESCAPED_FORMAT_VERSION`].
+///
+/// An escaped name such as `50%25` is also a valid literal name in version 1.
+/// The canonical representation in version 2 therefore starts with [`ESCAPED_FORMAT_HEADER`], and graphs with escaped fields must be hashed with [`hash_escaped`] and written with [`write_canonical_escaped`].
+///
+/// # Examples
+///
+/// ```
+/// use pggname::Graph;
+/// use pggname::algorithms;
+/// use pggname::graph::GraphStr;
+///
+/// assert_eq!(algorithms::escape_field(b"ACGT").as_ref(), b"ACGT");
+/// assert_eq!(algorithms::escape_field(b"a\tb\n50%").as_ref(), b"a%09b%0A50%25");
+///
+/// let mut graph = GraphStr::new();
+/// assert!(graph.add_node(b"a\tb", b"ACGT").is_err());
+/// graph.add_node(&algorithms::escape_field(b"a\tb"), b"ACGT").unwrap();
+/// graph.finalize().unwrap();
+/// ```
+pub fn escape_field(field: &[u8]) -> Cow<'_, [u8]> {
+    let escaped = |c: u8| c == b'\t' || c == b'\n' || c == b'%';
+    if !field.iter().any(|&c| escaped(c)) {
+        return Cow::Borrowed(field);
+    }
+    let mut result = Vec::with_capacity(field.len() + 8);
+    for &c in field {
+        if escaped(c) {
+            result.extend_from_slice(format!("%{:02X}", c).as_bytes());
+        } else {
+            result.push(c);
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Returns a copy of the graph with node names and sequences escaped by [`escape_field`].
+///
+/// The copy is in version 2 of the canonical format and must be hashed with [`hash_escaped`].
+/// Names and sequences parsed from GFA files never contain tabs or newlines, so only `%` changes.
+/// The graph type must support [`Graph::new`], as [`GraphInt`] and [`GraphStr`] do.
+/// Returns an error if the copy cannot be built.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphStr;
+///
+/// let graph: GraphStr = algorithms::parse_gfa_bytes(b"S\t50%\tACGT\nS\tx\tGA\nL\t50%\t+\tx\t-\t*\n").unwrap();
+/// let escaped = algorithms::escape_graph(&graph).unwrap();
+/// let mut canonical: Vec<u8> = Vec::new();
+/// algorithms::write_canonical_escaped(&escaped, &mut canonical).unwrap();
+/// assert_eq!(canonical, b"H\tCF:i:2\nS\t50%25\tACGT\nL\t50%25\t+\tx\t-\nS\tx\tGA\n");
+/// ```
+pub fn escape_graph<G: Graph>(graph: &G) -> Result<G, String> {
+    let mut result = G::new();
+    let names: Vec<Vec<u8>> = graph.node_names().collect();
+    for name in names.iter() {
+        let sequence = graph.sequence(name, Orientation::Forward)
+            .ok_or_else(|| format!("Missing node {}", String::from_utf8_lossy(name)))?;
+        result.add_node(&escape_field(name), &escape_field(&sequence))?;
+    }
+    for name in names.iter() {
+        let source = escape_field(name);
+        for (source_o, dest, dest_o) in graph.edges_of(name).unwrap_or_default() {
+            result.add_edge(&source, source_o, &escape_field(&dest), dest_o)?;
+        }
+    }
+    result.finalize()?;
+    Ok(result)
+}
+
+/// Header line that starts the canonical GFA representation in version 2 of the canonical format.
+///
+/// A representation in version 1 is either empty or starts with a segment line.
+/// Hence a graph with escaped names or sequences never has the same representation as a graph with the escaped text as literal names or sequences.
+pub const ESCAPED_FORMAT_HEADER: &[u8] = b"H\tCF:i:2\n";
+
+/// Header line that starts the canonical GFA representation in version 4 of the canonical format.
+///
+/// Version 4 is for segment names escaped by [`NamePolicy::Escape`].
+/// Like [`ESCAPED_FORMAT_HEADER`], the header separates escaped names from the same text as literal names, and it also separates the two escaping schemes.
+pub const PORTABLE_FORMAT_HEADER: &[u8] = b"H\tCF:i:4\n";
+
+/// Reverses [`escape_field`].
+///
+/// Returns an error if `%` is not followed by two hexadecimal digits.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+///
+/// assert_eq!(algorithms::unescape_field(b"a%09b%0A50%25").unwrap().as_ref(), b"a\tb\n50%");
+/// assert!(algorithms::unescape_field(b"50%").is_err());
+/// ```
+pub fn unescape_field(field: &[u8]) -> Result<Cow<'_, [u8]>, String> {
+    if !field.contains(&b'%') {
+        return Ok(Cow::Borrowed(field));
+    }
+    let mut result = Vec::with_capacity(field.len());
+    let mut i = 0;
+    while i < field.len() {
+        if field[i] == b'%' {
+            let value = field.get(i + 1..i + 3)
+                .and_then(|digits| std::str::from_utf8(digits).ok())
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or(format!("Invalid escape sequence at position {} in {}", i, String::from_utf8_lossy(field)))?;
+            result.push(value);
+            i += 3;
+        } else {
+            result.push(field[i]);
+            i += 1;
+        }
+    }
+    Ok(Cow::Owned(result))
+}
+
+/// Diagnostics collected while parsing GFA input.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    /// Malformed lines that were skipped in lenient mode, as (line number, reason).
+    pub errors: Vec<(usize, String)>,
+
+    /// Number of lines with trailing whitespace that was stripped before parsing.
+    pub trimmed_lines: usize,
+
+    /// Number of segment lines with a name changed by [`NamePolicy::Escape`].
+    ///
+    /// If this is nonzero, the name must be computed in version [`crate::vectors::PORTABLE_FORMAT_VERSION`] of the canonical format with [`hash_portable`].
+    pub escaped_names: usize,
+
+    /// Number of ignored lines by record type, such as `P`, `W`, `H`, `C`, `J`, or `#` for comments.
+    ///
+    /// The record type is the first byte of the line.
+    /// Only segments (`S`) and links (`L`) affect the name, and lines of other types are skipped.
+    /// Empty lines are not counted.
+    pub skipped_records: BTreeMap<u8, usize>,
+
+    /// Jumps collected with [`JumpPolicy::Include`].
+    ///
+    /// The J-lines are still counted in [`ParseReport::skipped_records`], as they are not a part of the graph.
+    /// See [`crate::jumps`] for including them in the name.
+    pub jumps: Jumps,
+}
+
+impl ParseReport {
+    /// Returns `true` if no malformed lines were found.
+    ///
+    /// Stripped trailing whitespace is not considered a problem.
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the total number of skipped lines of other record types.
+    pub fn skipped_lines(&self) -> usize {
+        self.skipped_records.values().sum()
+    }
+
+    /// Returns a summary of the skipped record types, such as `H: 1, P: 12, W: 3`.
+    ///
+    /// Record types that are not printable ASCII characters are shown as hexadecimal escapes.
+    pub fn skipped_summary(&self) -> String {
+        let counts: Vec<String> = self.skipped_records.iter().map(|(record_type, count)| {
+            format!("{}: {}", record_type.escape_ascii(), count)
+        }).collect();
+        counts.join(", ")
+    }
+}
+
+/// Builds a graph from the given GFA input using the given options.
+///
+/// Returns the graph and a report of the diagnostics collected while parsing.
+/// See [`parse_gfa`] for the errors.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::Graph;
+/// use pggname::algorithms::{self, ParseOptions};
+/// use pggname::graph::GraphStr;
+///
+/// let gfa = b"S\t1\tACGT\nS\t2\nS\t3\tGATTACA\nL\t1\t+\t3\t?\t*\nL\t1\t+\t3\t-\t*\n";
+/// assert!(algorithms::parse_gfa::<GraphStr, _>(&gfa[..]).is_err());
+///
+/// let options = ParseOptions { lenient: true, ..Default::default() };
+/// let (graph, report) = algorithms::parse_gfa_with_options::<GraphStr, _>(&gfa[..], &options).unwrap();
+/// assert_eq!(graph.statistics().nodes, 2);
+/// let lines: Vec<usize> = report.errors.iter().map(|(line, _)| *line).collect();
+/// assert_eq!(lines, vec![2, 4]);
+/// ```
+pub fn parse_gfa_with_options<G: Graph, R: BufRead>(reader: R, options: &ParseOptions) -> Result<(G, ParseReport), String> {
+    parse_gfa_with_hooks(reader, options, &Hooks::default())
+}
+
+/// Builds a graph from the given GFA input using the given options and hooks.
+///
+/// The progress callback receives the number of lines processed so far.
+/// Returns an error if the computation is cancelled.
+/// See [`parse_gfa_with_options`] for other details.
+pub fn parse_gfa_with_hooks<G: Graph, R: BufRead>(reader: R, options: &ParseOptions, hooks: &Hooks) -> Result<(G, ParseReport), String> {
+    let _span = tracing::debug_span!("parse_gfa").entered();
+    parse_lines::<G, R>(reader, options, hooks)?.finish()
+}
+
+// Parses all lines of the GFA input and returns the parser before finalization.
+fn parse_lines<'a, G: Graph, R: BufRead>(reader: R, options: &'a ParseOptions, hooks: &Hooks<'a>) -> Result<GfaParser<'a, G>, String> {
+    let mut parser = GfaParser::<G>::new(options, *hooks);
+    for (i, line) in reader.split(b'\n').enumerate() {
+        let line = line.map_err(|e| format!("Error reading GFA line {}: {}", i + 1, e))?;
+        parser.parse_line(&line, i + 1)?;
+    }
+    Ok(parser)
+}
+
+/// Builds a graph from GFA data stored in memory.
+///
+/// This is faster than [`parse_gfa`] with a [`std::io::Cursor`], as the lines are not copied.
+/// Returns an error if the GFA cannot be parsed.
+/// Passes through errors from the graph methods with the line number.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::Graph;
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+///
+/// let gfa = b"S\t11\tACCTT\nS\t12\tTCAAGG\nS\t13\tCTTGATT\nL\t11\t+\t12\t-\t0M\nL\t12\t-\t13\t+\t0M\nL\t11\t+\t13\t+\t0M\n";
+/// let graph = algorithms::parse_gfa_bytes::<GraphInt>(gfa).unwrap();
+/// assert_eq!(graph.statistics().sequence_len, 18);
+/// let name = pggname::stable_name(&graph);
+/// assert_eq!(name, "54b49d18354a34fbd1af9aaac279e1b3ee67b2f68f0ff79f5ebf6c50c8d922a5");
+/// ```
+pub fn parse_gfa_bytes<G: Graph>(data: &[u8]) -> Result<G, String> {
+    let (graph, _) = parse_gfa_bytes_with_options(data, &ParseOptions::default())?;
+    Ok(graph)
+}
+
+/// Builds a graph from GFA data stored in memory using the given options.
+///
+/// Returns the graph and a report of the diagnostics collected while parsing.
+/// See [`parse_gfa_bytes`] for the errors.
+pub fn parse_gfa_bytes_with_options<G: Graph>(data: &[u8], options: &ParseOptions) -> Result<(G, ParseReport), String> {
+    let _span = tracing::debug_span!("parse_gfa", bytes = data.len()).entered();
+    let mut parser = GfaParser::<G>::new(options, Hooks::default());
+    for (i, line) in split_bytes(data, b'\n').enumerate() {
+        parser.parse_line(line, i + 1)?;
+    }
+    parser.finish()
+}
+
+/// An incremental GFA parser for input that arrives in chunks.
+///
+/// Chunk boundaries may fall anywhere, including in the middle of a line.
+/// This is useful when the input is not available as a [`BufRead`], for example in a browser.
+/// The stream should not be used after [`GfaStream::push`] returns an error.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms::{self, GfaStream};
+/// use pggname::graph::GraphInt;
+///
+/// let gfa = b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n";
+/// let mut stream = GfaStream::<GraphInt>::new();
+/// for chunk in gfa.chunks(5) {
+///     stream.push(chunk).unwrap();
+/// }
+/// let graph = stream.finish().unwrap();
+/// assert_eq!(pggname::stable_name(&graph), pggname::stable_name(&algorithms::parse_gfa_bytes::<GraphInt>(gfa).unwrap()));
+/// ```
+pub struct GfaStream<G: Graph> {
+    parser: GfaParser<'static, G>,
+    // The unfinished line at the end of the previous chunk.
+    partial: Vec<u8>,
+    lines: usize,
+}
+
+static DEFAULT_PARSE_OPTIONS: ParseOptions = ParseOptions {
+    lenient: false, pack_sequences: false, strict: false, names: NamePolicy::Allow, jumps: JumpPolicy::Ignore,
+};
+
+impl<G: Graph> GfaStream<G> {
+    /// Creates a new stream with an empty graph.
+    pub fn new() -> Self {
+        GfaStream {
+            parser: GfaParser::new(&DEFAULT_PARSE_OPTIONS, Hooks::default()),
+            partial: Vec::new(),
+            lines: 0,
+        }
+    }
+
+    /// Parses the complete lines in the given chunk.
+    ///
+    /// Returns an error if a line cannot be parsed.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        let mut start = 0;
+        for i in memchr::memchr_iter(b'\n', chunk) {
+            self.lines += 1;
+            if self.partial.is_empty() {
+                self.parser.parse_line(&chunk[start..i], self.lines)?;
+            } else {
+                self.partial.extend_from_slice(&chunk[start..i]);
+                let mut line = std::mem::take(&mut self.partial);
+                self.parser.parse_line(&line, self.lines)?;
+                line.clear();
+                self.partial = line;
+            }
+            start = i + 1;
+        }
+        self.partial.extend_from_slice(&chunk[start..]);
+        Ok(())
+    }
+
+    /// Parses the final line, if it does not end with a newline, and finalizes the graph.
+    ///
+    /// Returns an error if the line cannot be parsed or if the graph cannot be finalized.
+    pub fn finish(mut self) -> Result<G, String> {
+        if !self.partial.is_empty() {
+            self.lines += 1;
+            self.parser.parse_line(&self.partial, self.lines)?;
+        }
+        let (graph, _) = self.parser.finish()?;
+        Ok(graph)
+    }
+}
+
+impl<G: Graph> Default for GfaStream<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A segment or link record that contributes to the graph.
+///
+/// Records borrowed from a GFA line can be made independent of it with [`GfaRecord::into_owned`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GfaRecord<'a> {
+    /// Segment with the given name and sequence.
+    Segment {
+        name: Cow<'a, [u8]>,
+        sequence: Cow<'a, [u8]>,
+    },
+    /// Link between the given oriented segments.
+    Link {
+        source: Cow<'a, [u8]>,
+        source_o: Orientation,
+        dest: Cow<'a, [u8]>,
+        dest_o: Orientation,
+    },
+}
+
+impl<'a> GfaRecord<'a> {
+    /// Returns a segment record with copies of the name and the sequence.
+    pub fn segment(name: &[u8], sequence: &[u8]) -> GfaRecord<'static> {
+        GfaRecord::Segment { name: Cow::Owned(name.to_vec()), sequence: Cow::Owned(sequence.to_vec()) }
+    }
+
+    /// Returns a link record with copies of the segment names.
+    pub fn link(source: &[u8], source_o: Orientation, dest: &[u8], dest_o: Orientation) -> GfaRecord<'static> {
+        GfaRecord::Link { source: Cow::Owned(source.to_vec()), source_o, dest: Cow::Owned(dest.to_vec()), dest_o }
+    }
+
+    /// Parses the record on the given GFA line (without the newline).
+    ///
+    /// Segment names are handled according to the policy.
+    /// Returns [`None`] for empty lines and lines of other types than S and L.
+    /// Returns the reason if the line cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::Orientation;
+    /// use pggname::algorithms::{GfaRecord, NamePolicy};
+    ///
+    /// let record = GfaRecord::parse(b"L\t1\t+\t2\t-\t*", NamePolicy::Allow).unwrap();
+    /// assert_eq!(record, Some(GfaRecord::link(b"1", Orientation::Forward, b"2", Orientation::Reverse)));
+    /// assert_eq!(GfaRecord::parse(b"P\tpath\t1+,2-\t*", NamePolicy::Allow).unwrap(), None);
+    /// assert!(GfaRecord::parse(b"S\t1", NamePolicy::Allow).is_err());
+    /// ```
+    pub fn parse(line: &'a [u8], names: NamePolicy) -> Result<Option<Self>, String> {
+        if line.first() == Some(&b'S') {
+            let mut fields = split_bytes(line, b'\t').skip(1);
+            let (Some(name), Some(sequence)) = (fields.next(), fields.next()) else {
+                return Err(String::from("not enough fields for a segment"));
+            };
+            Ok(Some(GfaRecord::Segment { name: names.apply(name)?, sequence: Cow::Borrowed(sequence) }))
+        } else if line.first() == Some(&b'L') {
+            let mut fields = split_bytes(line, b'\t').skip(1);
+            let (Some(source), Some(source_o), Some(dest), Some(dest_o)) = (fields.next(), fields.next(), fields.next(), fields.next()) else {
+                return Err(String::from("not enough fields for a link"));
+            };
+            let source_o = parse_orientation(source_o)?;
+            let dest_o = parse_orientation(dest_o)?;
+            Ok(Some(GfaRecord::Link { source: names.apply(source)?, source_o, dest: names.apply(dest)?, dest_o }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns a copy of the record that does not borrow the input.
+    pub fn into_owned(self) -> GfaRecord<'static> {
+        match self {
+            GfaRecord::Segment { name, sequence } => GfaRecord::Segment {
+                name: Cow::Owned(name.into_owned()),
+                sequence: Cow::Owned(sequence.into_owned()),
+            },
+            GfaRecord::Link { source, source_o, dest, dest_o } => GfaRecord::Link {
+                source: Cow::Owned(source.into_owned()), source_o,
+                dest: Cow::Owned(dest.into_owned()), dest_o,
+            },
+        }
+    }
+
+    /// Adds the record to the graph.
+    ///
+    /// Passes through errors from the graph methods.
+    pub fn add_to<G: Graph>(&self, graph: &mut G) -> Result<(), String> {
+        match self {
+            GfaRecord::Segment { name, sequence } => graph.add_node(name, sequence),
+            GfaRecord::Link { source, source_o, dest, dest_o } => graph.add_edge(source, *source_o, dest, *dest_o),
+        }
+    }
+}
+
+/// A producer of GFA records that can drive graph construction with [`parse_records`].
+///
+/// The records may come from any source, such as a network stream, a database cursor, or a generator.
+/// Every iterator over `Result<GfaRecord<'static>, String>` is a record source, and [`GfaRecords`] reads the records from GFA input.
+pub trait RecordSource {
+    /// Returns the next record, an error, or [`None`] if there are no more records.
+    fn next_record(&mut self) -> Option<Result<GfaRecord<'static>, String>>;
+}
+
+impl<I: Iterator<Item = Result<GfaRecord<'static>, String>>> RecordSource for I {
+    fn next_record(&mut self) -> Option<Result<GfaRecord<'static>, String>> {
+        self.next()
+    }
+}
+
+/// Builds a graph from the records produced by the source.
+///
+/// The source should produce every segment before any link using it, as with [`parse_gfa`].
+/// Returns an error if the source fails.
+/// Passes through errors from the graph methods with the record number.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::Orientation;
+/// use pggname::algorithms::{self, GfaRecord};
+/// use pggname::graph::GraphInt;
+///
+/// let records = vec![
+///     GfaRecord::segment(b"1", b"ACGT"),
+///     GfaRecord::segment(b"2", b"GA"),
+///     GfaRecord::link(b"1", Orientation::Forward, b"2", Orientation::Forward),
+/// ];
+/// let graph: GraphInt = algorithms::parse_records(records.into_iter().map(Ok)).unwrap();
+/// let gfa = b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n";
+/// assert_eq!(pggname::stable_name(&graph), pggname::stable_name(&algorithms::parse_gfa_bytes::<GraphInt>(gfa).unwrap()));
+/// ```
+pub fn parse_records<G: Graph, S: RecordSource>(mut source: S) -> Result<G, String> {
+    let _span = tracing::debug_span!("parse_records").entered();
+    let mut graph = G::new();
+    let mut count = 0;
+    while let Some(record) = source.next_record() {
+        count += 1;
+        record?.add_to(&mut graph).map_err(|e| format!("Error in GFA record {}: {}", count, e))?;
+    }
+    tracing::debug!(records = count, "read the GFA records");
+    graph.finalize()?;
+    Ok(graph)
+}
+
+/// A [`RecordSource`] that reads segment and link records from GFA input.
+///
+/// Lines of other types are skipped, and trailing whitespace is stripped.
+/// Errors include the line number.
+/// `parse_records(GfaRecords::new(reader))` builds the same graph as [`parse_gfa`] with the same reader.
+pub struct GfaRecords<R: BufRead> {
+    reader: R,
+    line: Vec<u8>,
+    lines: usize,
+}
+
+impl<R: BufRead> GfaRecords<R> {
+    /// Creates a record source over the given reader.
+    pub fn new(reader: R) -> Self {
+        GfaRecords { reader, line: Vec::new(), lines: 0 }
+    }
+}
+
+impl<R: BufRead> Iterator for GfaRecords<R> {
+    type Item = Result<GfaRecord<'static>, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => self.lines += 1,
+                Err(e) => return Some(Err(format!("Error reading GFA line {}: {}", self.lines + 1, e))),
+            }
+            let line = self.line.strip_suffix(b"\n").unwrap_or(&self.line);
+            match GfaRecord::parse(trim_line_end(line), NamePolicy::Allow) {
+                Ok(Some(record)) => return Some(Ok(record.into_owned())),
+                Ok(None) => continue,
+                Err(reason) => return Some(Err(format!("Error parsing GFA line {}: {}", self.lines, reason))),
+            }
+        }
+    }
+}
+
+/// A quick summary of GFA input, computed without building the graph.
+///
+/// See [`peek_gfa`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GfaPeek {
+    /// Number of segment lines, including duplicates.
+    pub segments: usize,
+    /// Number of link lines, including duplicates.
+    pub links: usize,
+    /// Node identifier mode chosen by [`NodeIds::Auto`] based on the node names: [`NodeIds::Integer`] or [`NodeIds::String`].
+    pub node_ids: NodeIds,
+    /// Are some integer node names not in canonical decimal form?
+    ///
+    /// See [`GraphInt::is_canonical_id`].
+    pub noncanonical_ids: bool,
+    /// First segment in canonical order as (name, sequence).
+    pub first: Option<(Vec<u8>, Vec<u8>)>,
+    /// Last segment in canonical order as (name, sequence).
+    pub last: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+// A segment as (name, sequence).
+type SegmentData = (Vec<u8>, Vec<u8>);
+
+// Smallest and largest segments by a sort key.
+struct SegmentRange<K: Ord> {
+    first: Option<(K, Vec<u8>, Vec<u8>)>,
+    last: Option<(K, Vec<u8>, Vec<u8>)>,
+}
+
+impl<K: Ord + Clone> SegmentRange<K> {
+    fn new() -> Self {
+        SegmentRange { first: None, last: None }
+    }
+
+    fn update(&mut self, key: K, name: &[u8], sequence: &[u8]) {
+        if self.first.as_ref().is_none_or(|(first, _, _)| key < *first) {
+            self.first = Some((key.clone(), name.to_vec(), sequence.to_vec()));
+        }
+        if self.last.as_ref().is_none_or(|(last, _, _)| key > *last) {
+            self.last = Some((key, name.to_vec(), sequence.to_vec()));
+        }
+    }
+
+    fn into_segments(self) -> (Option<SegmentData>, Option<SegmentData>) {
+        let segment = |(_, name, sequence)| (name, sequence);
+        (self.first.map(segment), self.last.map(segment))
+    }
+}
+
+/// Summarizes GFA input in a single pass without building the graph.
+///
+/// Detects the node identifier mode, counts the segment and link lines, and finds the first and last segments in canonical order.
+/// Memory usage does not depend on the size of the graph.
+/// The identifier mode is based on the node names only; [`NodeIds::Auto`] may still fall back to string identifiers if the graph cannot be built with integer identifiers.
+/// Returns an error if the input cannot be read or contains invalid segment or link lines.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms::{self, NodeIds};
+///
+/// let gfa = b"S\t2\tC\nS\t10\tG\nS\t1\tA\nL\t1\t+\t2\t+\t*\n";
+/// let peek = algorithms::peek_gfa(&gfa[..]).unwrap();
+/// assert_eq!((peek.segments, peek.links), (3, 1));
+/// assert_eq!(peek.node_ids, NodeIds::Integer);
+/// assert_eq!(peek.first, Some((b"1".to_vec(), b"A".to_vec())));
+/// assert_eq!(peek.last, Some((b"10".to_vec(), b"G".to_vec())));
+/// ```
+pub fn peek_gfa<R: BufRead>(reader: R) -> Result<GfaPeek, String> {
+    let mut result = GfaPeek::default();
+    let mut integer_ids = true;
+    let mut by_id: SegmentRange<usize> = SegmentRange::new();
+    let mut by_name: SegmentRange<Vec<u8>> = SegmentRange::new();
+    let parse_id = |name: &[u8]| str::from_utf8(name).ok().and_then(|name| name.parse::<usize>().ok());
+
+    for record in GfaRecords::new(reader) {
+        match record? {
+            GfaRecord::Segment { name, sequence } => {
+                result.segments += 1;
+                if integer_ids && let Some(id) = parse_id(&name) {
+                    result.noncanonical_ids |= !GraphInt::is_canonical_id(&name);
+                    by_id.update(id, &name, &sequence);
+                } else {
+                    integer_ids = false;
+                }
+                by_name.update(name.to_vec(), &name, &sequence);
+            }
+            GfaRecord::Link { source, dest, .. } => {
+                result.links += 1;
+                if integer_ids && (parse_id(&source).is_none() || parse_id(&dest).is_none()) {
+                    integer_ids = false;
+                }
+            }
+        }
+    }
+
+    (result.first, result.last) = if integer_ids {
+        result.node_ids = NodeIds::Integer;
+        by_id.into_segments()
+    } else {
+        result.node_ids = NodeIds::String;
+        result.noncanonical_ids = false;
+        by_name.into_segments()
+    };
+    Ok(result)
+}
+
+/// Builds a graph from the given asynchronous GFA input.
+///
+/// This is an async variant of [`parse_gfa`] for use with Tokio.
+/// The function yields to the runtime periodically, even if the input is always ready.
+/// Returns an error if reading the input fails or if the GFA cannot be parsed.
+/// Passes through errors from the graph methods with the line number.
+#[cfg(feature = "async")]
+pub async fn parse_gfa_async<G: Graph, R: AsyncBufRead + Unpin>(mut reader: R) -> Result<G, String> {
+    let options = ParseOptions::default();
+    let mut parser = GfaParser::<G>::new(&options, Hooks::default());
+    let mut line: Vec<u8> = Vec::new();
+    let mut line_num = 0;
+    loop {
+        line.clear();
+        let bytes = reader.read_until(b'\n', &mut line).await
+            .map_err(|e| format!("Error reading GFA line {}: {}", line_num + 1, e))?;
+        if bytes == 0 {
+            break;
+        }
+        line_num += 1;
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        parser.parse_line(&line, line_num)?;
+        if line_num.is_multiple_of(ASYNC_YIELD_INTERVAL) {
+            tokio::task::yield_now().await;
+        }
+    }
+    let (graph, _) = parser.finish()?;
+
+    Ok(graph)
+}
+
+// Async functions yield to the runtime after this many lines or nodes.
+#[cfg(feature = "async")]
+const ASYNC_YIELD_INTERVAL: usize = 1024;
+
+// Builds a graph from GFA lines according to the options and collects diagnostics.
+struct GfaParser<'a, G: Graph> {
+    graph: G,
+    options: &'a ParseOptions,
+    hooks: Hooks<'a>,
+    report: ParseReport,
+    lines: usize,
+}
+
+impl<'a, G: Graph> GfaParser<'a, G> {
+    fn new(options: &'a ParseOptions, hooks: Hooks<'a>) -> Self {
+        let mut graph = G::new();
+        if options.pack_sequences {
+            graph.pack_sequences();
+        }
+        GfaParser {
+            graph,
+            options,
+            hooks,
+            report: ParseReport::default(),
+            lines: 0,
+        }
+    }
+
+    // Parses the given line (without the newline).
+    // Trailing whitespace, including the carriage return from a CRLF line ending, is stripped unless in strict mode.
+    // In lenient mode, errors are recorded in the report.
+    fn parse_line(&mut self, line: &[u8], line_num: usize) -> Result<(), String> {
+        self.lines = line_num;
+        self.hooks.checkpoint(Phase::Parsing, line_num)?;
+        if let Some(&record_type) = line.first() && record_type != b'S' && record_type != b'L' {
+            *self.report.skipped_records.entry(record_type).or_default() += 1;
+        }
+        let trimmed = trim_line_end(line);
+        let result = if trimmed.len() == line.len() {
+            self.parse_record(line)
+        } else if self.options.strict {
+            Err(String::from("trailing whitespace or carriage return"))
+        } else {
+            self.report.trimmed_lines += 1;
+            self.parse_record(trimmed)
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(reason) if self.options.lenient => {
+                self.report.errors.push((line_num, reason));
+                Ok(())
+            }
+            Err(reason) => Err(format!("Error parsing GFA line {}: {}", line_num, reason)),
+        }
+    }
+
+    // Parses the record on the given line, with additional validation in strict mode.
+    fn parse_record(&mut self, line: &[u8]) -> Result<(), String> {
+        if self.options.strict && line.first() == Some(&b'S') {
+            check_segment_length(line)?;
+        }
+        if self.options.jumps == JumpPolicy::Include && line.first() == Some(&b'J') {
+            return self.report.jumps.add_line(line, self.options.names);
+        }
+        match GfaRecord::parse(line, self.options.names)? {
+            Some(record) => {
+                if let GfaRecord::Segment { name: Cow::Owned(_), .. } = &record {
+                    self.report.escaped_names += 1;
+                }
+                record.add_to(&mut self.graph)
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Finalizes the graph.
+    fn finish(self) -> Result<(G, ParseReport), String> {
+        let (mut graph, report) = self.into_parts()?;
+        graph.finalize()?;
+        Ok((graph, report))
+    }
+
+    // Returns the graph without finalizing it.
+    fn into_parts(self) -> Result<(G, ParseReport), String> {
+        self.hooks.finish(Phase::Parsing, self.lines)?;
+        tracing::debug!(
+            lines = self.lines, skipped = self.report.skipped_lines(), trimmed = self.report.trimmed_lines, errors = self.report.errors.len(),
+            "parsed the GFA lines"
+        );
+        Ok((self.graph, self.report))
+    }
+}
+
+// Checks that the `LN:i` tag on a segment line, if present, matches the length of the sequence.
+// Segments without a sequence (`*`) are not checked.
+// Returns the reason if the line is invalid.
+fn check_segment_length(line: &[u8]) -> Result<(), String> {
+    let mut fields = split_bytes(line, b'\t').skip(1);
+    let (Some(name), Some(sequence)) = (fields.next(), fields.next()) else {
+        return Ok(());
+    };
+    if sequence == b"*" {
+        return Ok(());
+    }
+    for field in fields {
+        if let Some(value) = field.strip_prefix(b"LN:i:") {
+            let length = str::from_utf8(value).ok().and_then(|value| value.parse::<usize>().ok()).ok_or_else(|| {
+                format!("invalid LN:i tag {} for segment {}", String::from_utf8_lossy(value), String::from_utf8_lossy(name))
+            })?;
+            if length != sequence.len() {
+                return Err(format!(
+                    "segment {} has LN:i:{} but sequence length {}",
+                    String::from_utf8_lossy(name), length, sequence.len()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+// An iterator over the parts of a byte slice separated by the given byte.
+// This behaves like `data.split(|&c| c == separator)`, but the separators are found with SIMD-accelerated `memchr`.
+pub(crate) struct SplitBytes<'a> {
+    rest: Option<&'a [u8]>,
+    separator: u8,
+}
+
+impl<'a> Iterator for SplitBytes<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+        match memchr::memchr(self.separator, rest) {
+            Some(i) => {
+                self.rest = Some(&rest[i + 1..]);
+                Some(&rest[..i])
+            }
+            None => {
+                self.rest = None;
+                Some(rest)
+            }
+        }
+    }
+}
+
+// Strips trailing spaces and carriage returns from a GFA line without the newline.
+// Tabs are kept, as a trailing tab delimits an empty field such as the sequence in `S\t2\t`.
+pub(crate) fn trim_line_end(line: &[u8]) -> &[u8] {
+    let len = line.iter().rposition(|&c| c != b' ' && c != b'\r').map_or(0, |i| i + 1);
+    &line[..len]
+}
+
+// Splits the data by the separator using `memchr`.
+pub(crate) fn split_bytes(data: &[u8], separator: u8) -> SplitBytes<'_> {
+    SplitBytes { rest: Some(data), separator }
+}
+
+//-----------------------------------------------------------------------------
+
+/// Size of the buffer used for accumulating serialized nodes before updating the hasher.
+///
+/// Updating the hasher with large buffers is much faster than updating it once per node.
+pub const HASH_BUFFER_SIZE: usize = 1 << 20;
+
+// A hasher that accumulates the input into a buffer of `HASH_BUFFER_SIZE` bytes before updating the digest.
+pub(crate) struct BufferedHasher<D: Digest> {
+    hasher: D,
+    buffer: Vec<u8>,
+}
+
+impl<D: Digest> BufferedHasher<D> {
+    pub(crate) fn new() -> Self {
+        BufferedHasher { hasher: D::new(), buffer: Vec::with_capacity(HASH_BUFFER_SIZE) }
+    }
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        if self.buffer.len() + bytes.len() > HASH_BUFFER_SIZE {
+            self.hasher.update(&self.buffer);
+            self.buffer.clear();
+        }
+        if bytes.len() >= HASH_BUFFER_SIZE {
+            self.hasher.update(bytes);
+        } else {
+            self.buffer.extend_from_slice(bytes);
+        }
+    }
+
+    pub(crate) fn finalize(mut self) -> String
+        where digest::Output<D>: core::fmt::LowerHex {
+        self.hasher.update(&self.buffer);
+        format!("{:x}", self.hasher.finalize())
+    }
+}
+
+/// Computes the given hash of the canonical GFA representation of the given graph.
+///
+/// The serialized nodes are passed to the hasher in batches of [`HASH_BUFFER_SIZE`] bytes.
+pub fn hash<D: Digest, G: Graph>(graph: &G) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    let _span = tracing::debug_span!("hash").entered();
+    let mut hasher = BufferedHasher::<D>::new();
+    graph.for_each_node(|bytes| hasher.update(bytes));
+    let result = hasher.finalize();
+    tracing::debug!(hash = %result, "hashed the graph");
+    result
+}
+
+/// Computes the given hash of the canonical GFA representation of the given graph using the given hooks.
+///
+/// The progress callback receives the number of nodes hashed so far.
+/// Returns an error if the computation is cancelled.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms::{self, CancellationToken, Hooks, Phase};
+/// use pggname::graph::GraphInt;
+/// use sha2::Sha256;
+/// use std::sync::Mutex;
+///
+/// let gfa = b"S\t1\tACGT\nS\t2\tGATTACA\nL\t1\t+\t2\t-\t*\n";
+/// let graph = algorithms::parse_gfa_bytes::<GraphInt>(gfa).unwrap();
+///
+/// let seen = Mutex::new(Vec::new());
+/// let callback = |phase: Phase, count: usize| seen.lock().unwrap().push((phase, count));
+/// let hooks = Hooks { progress: Some(&callback), interval: 1, ..Default::default() };
+/// let hash = algorithms::hash_with_hooks::<Sha256, _>(&graph, &hooks);
+/// assert_eq!(hash, Ok(pggname::stable_name(&graph)));
+/// assert_eq!(*seen.lock().unwrap(), vec![(Phase::Hashing, 1), (Phase::Hashing, 2), (Phase::Hashing, 2)]);
+///
+/// let token = CancellationToken::new();
+/// token.cancel();
+/// let hooks = Hooks { cancel: Some(&token), ..Default::default() };
+/// assert!(algorithms::hash_with_hooks::<Sha256, _>(&graph, &hooks).is_err());
+/// ```
+pub fn hash_with_hooks<D: Digest, G: Graph>(graph: &G, hooks: &Hooks) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let _span = tracing::debug_span!("hash").entered();
+    let mut hasher = BufferedHasher::<D>::new();
+    let mut nodes = 0;
+    graph.try_for_each_node(|bytes| -> Result<(), String> {
+        nodes += 1;
+        hooks.checkpoint(Phase::Hashing, nodes)?;
+        hasher.update(bytes);
+        Ok(())
+    })?;
+    hooks.finish(Phase::Hashing, nodes)?;
+    let result = hasher.finalize();
+    tracing::debug!(nodes, hash = %result, "hashed the graph");
+    Ok(result)
+}
+
+/// Computes the given hash of the canonical GFA representation of the given graph asynchronously.
+///
+/// This is an async variant of [`hash`] for use with Tokio.
+/// Hashing is CPU-bound, but the function yields to the runtime periodically to avoid blocking other tasks.
+#[cfg(feature = "async")]
+pub async fn hash_async<D: Digest, G: Graph>(graph: &G) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    let mut hasher = BufferedHasher::<D>::new();
+    for (i, bytes) in graph.node_iter().enumerate() {
+        hasher.update(&bytes);
+        if (i + 1).is_multiple_of(ASYNC_YIELD_INTERVAL) {
+            tokio::task::yield_now().await;
+        }
+    }
+    hasher.finalize()
+}
+
+/// Computes the given hash of the canonical GFA representation of the given graph in version 2 of the canonical format.
+///
+/// Use this for graphs with names or sequences escaped by [`escape_field`].
+/// Names escaped by [`NamePolicy::Escape`] use [`hash_portable`] instead.
+/// The hash covers [`ESCAPED_FORMAT_HEADER`] followed by the serialized nodes.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::Graph;
+/// use pggname::algorithms;
+/// use pggname::graph::GraphStr;
+/// use sha2::Sha256;
+///
+/// let mut escaped = GraphStr::new();
+/// escaped.add_node(&algorithms::escape_field(b"a\tb"), b"ACGT").unwrap();
+/// escaped.finalize().unwrap();
+/// let mut literal = GraphStr::new();
+/// literal.add_node(b"a%09b", b"ACGT").unwrap();
+/// literal.finalize().unwrap();
+/// assert_ne!(algorithms::hash_escaped::<Sha256, _>(&escaped), pggname::stable_name(&literal));
+/// ```
+pub fn hash_escaped<D: Digest, G: Graph>(graph: &G) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    let _span = tracing::debug_span!("hash_escaped").entered();
+    hash_with_header::<D, G>(graph, ESCAPED_FORMAT_HEADER)
+}
+
+/// Computes the given hash of the canonical GFA representation of the given graph in version 4 of the canonical format.
+///
+/// Use this for graphs with segment names escaped by [`NamePolicy::Escape`].
+/// The hash covers [`PORTABLE_FORMAT_HEADER`] followed by the serialized nodes.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms::{self, NamePolicy, ParseOptions};
+/// use pggname::graph::GraphStr;
+/// use sha2::Sha256;
+///
+/// let options = ParseOptions { names: NamePolicy::Escape, ..Default::default() };
+/// let (escaped, report) = algorithms::parse_gfa_bytes_with_options::<GraphStr>(b"S\ta b\tACGT\n", &options).unwrap();
+/// assert_eq!(report.escaped_names, 1);
+/// let literal: GraphStr = algorithms::parse_gfa_bytes(b"S\ta%20b\tACGT\n").unwrap();
+/// assert_ne!(algorithms::hash_portable::<Sha256, _>(&escaped), pggname::stable_name(&literal));
+/// assert_ne!(algorithms::hash_portable::<Sha256, _>(&escaped), algorithms::hash_escaped::<Sha256, _>(&escaped));
+/// ```
+pub fn hash_portable<D: Digest, G: Graph>(graph: &G) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    let _span = tracing::debug_span!("hash_portable").entered();
+    hash_with_header::<D, G>(graph, PORTABLE_FORMAT_HEADER)
+}
+
+// Computes the given hash of the header followed by the serialized nodes.
+fn hash_with_header<D: Digest, G: Graph>(graph: &G, header: &[u8]) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    let mut hasher = BufferedHasher::<D>::new();
+    hasher.update(header);
+    graph.for_each_node(|bytes| hasher.update(bytes));
+    let result = hasher.finalize();
+    tracing::debug!(hash = %result, "hashed the graph with a format header");
+    result
+}
+
+/// Computes the stable name (pggname) of the given graph.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::Graph;
+/// use pggname::graph::GBZInt;
+/// use gbz::GBZ;
+/// use gbz::support;
+/// use simple_sds::serialize;
+///
+/// let filename = support::get_test_data("example.gbz");
+/// let gbz: GBZ = serialize::load_from(&filename).unwrap();
+/// let graph = GBZInt { graph: gbz };
+/// let hash = pggname::stable_name(&graph);
+/// assert_eq!(hash, "81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970");
+/// ```
+pub fn stable_name<G: Graph>(graph: &G) -> String {
+    hash::<sha2::Sha256, G>(graph)
+}
+
+/// Domain prefix of the data hashed for the node-table digest.
+///
+/// The canonical GFA representation never contains comment lines.
+/// With the prefix, the node-table digest of a graph without edges is not its stable name.
+pub const NODE_TABLE_DOMAIN: &[u8] = b"#pggname:node-table\n";
+
+/// Domain prefix of the data hashed for the edge-set digest.
+///
+/// See [`NODE_TABLE_DOMAIN`].
+pub const EDGE_SET_DOMAIN: &[u8] = b"#pggname:edge-set\n";
+
+/// Computes the given hash of the node table of the given graph.
+///
+/// The node table consists of the segment lines of the canonical GFA representation in canonical order, without the edges.
+/// The hash covers [`NODE_TABLE_DOMAIN`] followed by the node table.
+/// See [`node_table_digest`].
+pub fn hash_node_table<D: Digest, G: Graph>(graph: &G) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    hash_node_parts::<D, G, _>(graph, NODE_TABLE_DOMAIN, |bytes| {
+        let end = memchr::memchr(b'\n', bytes).map(|i| i + 1).unwrap_or(bytes.len());
+        &bytes[..end]
+    })
+}
+
+// Hashes the domain prefix followed by the given part of each serialized node.
+fn hash_node_parts<D: Digest, G: Graph, F: Fn(&[u8]) -> &[u8]>(graph: &G, domain: &[u8], part: F) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    let mut hasher = BufferedHasher::<D>::new();
+    hasher.update(domain);
+    graph.for_each_node(|bytes| hasher.update(part(bytes)));
+    hasher.finalize()
+}
+
+/// Computes the node-table digest of the given graph: the SHA-256 of the sorted `(id, sequence)` pairs as canonical segment lines, with a domain prefix.
+///
+/// Graphs with the same node identifiers and sequences have the same node-table digest, even if their edges differ.
+/// This is enough for tools that only look up nodes, such as when checking that GAF alignments can be interpreted with a graph.
+/// The digest depends on the type of node identifiers in the same way as the stable name.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let other: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t-\t*\n").unwrap();
+/// assert_ne!(pggname::stable_name(&graph), pggname::stable_name(&other));
+/// assert_eq!(algorithms::node_table_digest(&graph), algorithms::node_table_digest(&other));
+/// ```
+pub fn node_table_digest<G: Graph>(graph: &G) -> String {
+    hash_node_table::<sha2::Sha256, G>(graph)
+}
+
+/// Computes the given hash of the edge set of the given graph.
+///
+/// The edge set consists of the link lines of the canonical GFA representation in canonical order, without the segments.
+/// The hash covers [`EDGE_SET_DOMAIN`] followed by the edge set.
+/// See [`edge_set_digest`].
+pub fn hash_edge_set<D: Digest, G: Graph>(graph: &G) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    hash_node_parts::<D, G, _>(graph, EDGE_SET_DOMAIN, |bytes| {
+        let start = memchr::memchr(b'\n', bytes).map(|i| i + 1).unwrap_or(bytes.len());
+        &bytes[start..]
+    })
+}
+
+/// Computes the edge-set digest of the given graph: the SHA-256 of the canonical link lines, with a domain prefix.
+///
+/// Graphs with the same edges between the same node identifiers have the same edge-set digest, even if the sequences differ.
+/// Together with [`node_table_digest`], this shows whether graphs with different names differ in their nodes, their edges, or both.
+/// The digest depends on the type of node identifiers in the same way as the stable name.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let other: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGC\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// assert_ne!(algorithms::node_table_digest(&graph), algorithms::node_table_digest(&other));
+/// assert_eq!(algorithms::edge_set_digest(&graph), algorithms::edge_set_digest(&other));
+/// ```
+pub fn edge_set_digest<G: Graph>(graph: &G) -> String {
+    hash_edge_set::<sha2::Sha256, G>(graph)
+}
+
+/// Writes the canonical GFA representation of the given graph.
+///
+/// The output is byte-identical to what [`hash`] passes to the hasher, so hashing it with SHA-256 gives the stable name.
+/// This makes it possible to compute other digests of the canonical representation with external tools.
+/// Returns an error if writing fails.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+/// use sha2::{Digest, Sha256};
+///
+/// let gfa = b"S\t2\tGA\nS\t1\tACGT\nL\t1\t+\t2\t-\t*\n";
+/// let graph = algorithms::parse_gfa_bytes::<GraphInt>(gfa).unwrap();
+/// let mut canonical: Vec<u8> = Vec::new();
+/// algorithms::write_canonical(&graph, &mut canonical).unwrap();
+/// assert_eq!(format!("{:x}", Sha256::digest(&canonical)), pggname::stable_name(&graph));
+/// ```
+pub fn write_canonical<G: Graph, W: Write>(graph: &G, mut writer: W) -> Result<(), String> {
+    graph.try_for_each_node(|bytes| writer.write_all(bytes).map_err(|e| format!("Error writing the canonical GFA: {}", e)))?;
+    writer.flush().map_err(|e| format!("Error writing the canonical GFA: {}", e))
+}
+
+/// Writes the canonical GFA representation of the given graph in version 2 of the canonical format.
+///
+/// The output is [`ESCAPED_FORMAT_HEADER`] followed by the output of [`write_canonical`].
+/// Its SHA-256 hash is the same as with [`hash_escaped`].
+/// Returns an error if writing fails.
+pub fn write_canonical_escaped<G: Graph, W: Write>(graph: &G, mut writer: W) -> Result<(), String> {
+    writer.write_all(ESCAPED_FORMAT_HEADER).map_err(|e| format!("Error writing the canonical GFA: {}", e))?;
+    write_canonical(graph, writer)
+}
+
+/// Writes the canonical GFA representation of the given graph in version 4 of the canonical format.
+///
+/// The output is [`PORTABLE_FORMAT_HEADER`] followed by the output of [`write_canonical`].
+/// Its SHA-256 hash is the same as with [`hash_portable`].
+/// Returns an error if writing fails.
+pub fn write_canonical_portable<G: Graph, W: Write>(graph: &G, mut writer: W) -> Result<(), String> {
+    writer.write_all(PORTABLE_FORMAT_HEADER).map_err(|e| format!("Error writing the canonical GFA: {}", e))?;
+    write_canonical(graph, writer)
+}
+
+/// Writes the canonical GFA representation of the given graph and returns the given hash of the bytes that were written.
+///
+/// The output is the same as with [`write_canonical`].
+/// Because the hash is computed over the written bytes, comparing it with the result of [`hash`] confirms that the output is what the internal hasher consumes.
+/// Returns an error if writing fails.
+pub fn write_canonical_with_hash<D: Digest, G: Graph, W: Write>(graph: &G, mut writer: W) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let mut hasher = BufferedHasher::<D>::new();
+    graph.try_for_each_node(|bytes| {
+        writer.write_all(bytes).map_err(|e| format!("Error writing the canonical GFA: {}", e))?;
+        hasher.update(bytes);
+        Ok::<(), String>(())
+    })?;
+    writer.flush().map_err(|e| format!("Error writing the canonical GFA: {}", e))?;
+    Ok(hasher.finalize())
+}
+
+/// Checks that the canonical GFA representation of the graph parses back into a graph with the same name.
+///
+/// The representation is parsed as a graph of type `H`, which should use the same type of node identifiers as the graph.
+/// This guards against asymmetries between the serializer and the parser, such as canonical lines the parser cannot read or reads differently.
+/// Returns the stable name of the graph.
+/// Returns an error if the representation cannot be parsed or if the parsed graph has a different name.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::{GraphInt, GraphStr};
+///
+/// let gfa = b"S\t10\tACGT\nS\t2\tGA\nL\t10\t+\t2\t-\t4M\n";
+/// let graph = algorithms::parse_gfa_bytes::<GraphInt>(gfa).unwrap();
+/// assert_eq!(algorithms::check_round_trip::<_, GraphInt>(&graph), Ok(pggname::stable_name(&graph)));
+///
+/// // String identifiers order the nodes differently.
+/// assert!(algorithms::check_round_trip::<_, GraphStr>(&graph).is_err());
+/// ```
+pub fn check_round_trip<G: Graph, H: Graph>(graph: &G) -> Result<String, String> {
+    let _span = tracing::debug_span!("check_round_trip").entered();
+    let mut hasher = BufferedHasher::<sha2::Sha256>::new();
+    let mut stream = GfaStream::<H>::new();
+    graph.try_for_each_node(|bytes| {
+        hasher.update(bytes);
+        stream.push(bytes).map_err(|e| format!("Round trip: {}", e))
+    })?;
+    let name = hasher.finalize();
+    let parsed = stream.finish().map_err(|e| format!("Round trip: {}", e))?;
+    let parsed_name = stable_name(&parsed);
+    if parsed_name != name {
+        return Err(format!("Round trip: the canonical GFA representation of graph {} parses into graph {}", name, parsed_name));
+    }
+    tracing::debug!(name = %name, "canonical GFA round trip succeeded");
+    Ok(name)
+}
+
+/// Default sampling interval for [`fingerprint`].
+pub const FINGERPRINT_INTERVAL: usize = 1024;
+
+/// Computes a quick fingerprint of the graph from a deterministic sample of canonical node records.
+///
+/// The fingerprint covers the graph statistics, every `interval`-th node record starting from the first one, and the last node record.
+/// Identical graphs always have the same fingerprint, so different fingerprints prove that the graphs are different.
+/// Equal fingerprints are only evidence that the graphs are likely the same, and the full hash must be computed for certainty.
+/// Fingerprints are only comparable when computed with the same interval.
+/// An interval of 0 is treated as 1.
+///
+/// The [`Graph`] trait has no random access to node records, so this still visits and serializes every record in linear time.
+/// Only the sampled records are hashed, which saves the hashing cost of [`hash`] but not the traversal.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+/// use sha2::Sha256;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let other: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGC\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let interval = algorithms::FINGERPRINT_INTERVAL;
+/// assert_ne!(algorithms::fingerprint::<Sha256, _>(&graph, interval), algorithms::fingerprint::<Sha256, _>(&other, interval));
+/// ```
+pub fn fingerprint<D: Digest, G: Graph>(graph: &G, interval: usize) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    let interval = interval.max(1);
+    let stats = graph.statistics();
+    let mut hasher = D::new();
+    hasher.update(format!("{}\t{}\t{}\t{}\n", stats.nodes, stats.edges, stats.sequence_len, interval).as_bytes());
+
+    // The node count identifies the last record, so unsampled records are never copied.
+    let last = (stats.nodes as usize).checked_sub(1);
+    let mut i: usize = 0;
+    graph.for_each_node(|bytes| {
+        if i.is_multiple_of(interval) || Some(i) == last {
+            hasher.update(bytes);
+        }
+        i += 1;
+    });
+
+    let hash = hasher.finalize();
+    format!("{:x}", hash)
+}
+
+//-----------------------------------------------------------------------------
+
+/// How node identifiers are interpreted when naming a graph file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NodeIds {
+    /// Use integer identifiers if possible, fall back to string identifiers.
+    #[default]
+    Auto,
+    /// Use integer identifiers.
+    Integer,
+    /// Use string identifiers.
+    String,
+}
+
+/// Opens a GFA file for reading.
+///
+/// Gzip-compressed files are decompressed transparently.
+/// With the `remote` feature, `http://`, `https://`, and `s3://` URLs are streamed using the `remote` module.
+/// Returns an error if the file cannot be opened.
+pub fn open_gfa<P: AsRef<Path>>(filename: P) -> Result<Box<dyn BufRead>, String> {
+    let filename = filename.as_ref();
+    #[cfg(feature = "remote")]
+    if let Some(url) = filename.to_str() && crate::remote::is_url(url) {
+        return crate::remote::open_url(url);
+    }
+    let mut file = OpenOptions::new().read(true).open(filename)
+        .map_err(|e| format!("Error opening GFA file {}: {}", filename.display(), e))?;
+    let compressed = is_gzip(&mut file)
+        .map_err(|e| format!("Error reading GFA file {}: {}", filename.display(), e))?;
+    if compressed {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+// GFA input that may be parsed twice when `NodeIds::Auto` falls back to string identifiers.
+// Remote inputs are downloaded once and buffered in memory, while local files are reopened for each attempt.
+struct GfaInput<'a> {
+    filename: &'a Path,
+    buffer: Option<Vec<u8>>,
+}
+
+impl<'a> GfaInput<'a> {
+    fn new(filename: &'a Path, node_ids: NodeIds) -> Result<Self, String> {
+        let mut buffer = None;
+        if node_ids == NodeIds::Auto && is_remote(filename) {
+            let mut data = Vec::new();
+            open_gfa(filename)?.read_to_end(&mut data)
+                .map_err(|e| format!("Error reading GFA file {}: {}", filename.display(), e))?;
+            buffer = Some(data);
+        }
+        Ok(GfaInput { filename, buffer })
+    }
+
+    fn open(&self) -> Result<Box<dyn BufRead + '_>, String> {
+        match &self.buffer {
+            Some(data) => Ok(Box::new(data.as_slice())),
+            None => open_gfa(self.filename),
+        }
+    }
+}
+
+// Returns `true` if `open_gfa` would read the file from a remote URL.
+fn is_remote(filename: &Path) -> bool {
+    #[cfg(feature = "remote")]
+    if let Some(url) = filename.to_str() {
+        return crate::remote::is_url(url);
+    }
+    let _ = filename;
+    false
+}
+
+/// Reads the graph name metadata from the header lines at the start of a GFA file.
+///
+/// The file is opened with [`open_gfa`], and reading stops at the first line that is not a header line.
+/// Returns an error if the file cannot be read or if the header cannot be parsed; see [`GraphName::from_header_lines`].
+pub fn read_gfa_metadata<P: AsRef<Path>>(filename: P) -> Result<GraphName, String> {
+    let filename = filename.as_ref();
+    let reader = open_gfa(filename)?;
+    let mut header: Vec<String> = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Error reading {} line {}: {}", filename.display(), i + 1, e))?;
+        if line == "H" || line.starts_with("H\t") {
+            header.push(line);
+        } else {
+            break;
+        }
+    }
+    GraphName::from_header_lines(&header).map_err(|e| format!("Error parsing the header of {}: {}", filename.display(), e))
+}
+
+// Returns `true` if the file starts with the gzip magic number.
+// Rewinds the file to the beginning.
+fn is_gzip(file: &mut File) -> std::io::Result<bool> {
+    use std::io::{Seek, SeekFrom};
+
+    let mut magic = [0u8; 2];
+    let mut len = 0;
+    while len < magic.len() {
+        let bytes = file.read(&mut magic[len..])?;
+        if bytes == 0 {
+            break;
+        }
+        len += bytes;
+    }
+    file.seek(SeekFrom::Start(0))?;
+    Ok(len == magic.len() && magic == [0x1F, 0x8B])
+}
+
+/// Computes the given hash of the graph stored in the given file.
+///
+/// The file may be a GBZ file, a GFA file, or a gzip-compressed GFA file.
+/// The format is detected automatically.
+/// With [`NodeIds::Auto`], GFA files are parsed using integer identifiers if possible, and GBZ graphs always use integer identifiers.
+/// A remote GFA file is then downloaded once and buffered in memory, so that falling back to string identifiers does not download it again.
+/// GBZ files are only recognized with the `gbz` feature.
+///
+/// Returns an error if the file cannot be read or parsed.
+pub fn hash_file<D: Digest, P: AsRef<Path>>(filename: P, node_ids: NodeIds) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let filename = filename.as_ref();
+    #[cfg(feature = "gbz")]
+    if GBZ::is_gbz(filename) {
+        if node_ids == NodeIds::String {
+            let graph: GBZ = serialize::load_from(filename)
+                .map_err(|e| format!("Error loading GBZ file {}: {}", filename.display(), e))?;
+            return Ok(hash::<D, _>(&GBZStr { graph }));
+        } else {
+            // Integer identifiers only need the node records and sequences.
+            let graph = LazyGBZ::load_from(filename)?;
+            return Ok(hash::<D, _>(&graph));
+        }
+    }
+
+    let input = GfaInput::new(filename, node_ids)?;
+    match node_ids {
+        NodeIds::Integer => {
+            let graph: GraphInt = parse_gfa(input.open()?)?;
+            Ok(hash::<D, _>(&graph))
+        }
+        NodeIds::String => {
+            let graph: GraphStr = parse_gfa(input.open()?)?;
+            Ok(hash::<D, _>(&graph))
+        }
+        NodeIds::Auto => {
+            if let Ok(graph) = parse_gfa::<GraphInt, _>(input.open()?) {
+                Ok(hash::<D, _>(&graph))
+            } else {
+                let graph: GraphStr = parse_gfa(input.open()?)?;
+                Ok(hash::<D, _>(&graph))
+            }
+        }
+    }
+}
+
+/// Computes the given hash of the graph stored in the given file, with names and sequences escaped by [`escape_field`].
+///
+/// If some names or sequences contain `%`, the graph is escaped with [`escape_graph`] and hashed in version [`crate::vectors::ESCAPED_FORMAT_VERSION`] of the canonical format with [`hash_escaped`].
+/// Otherwise no field changes, and the result is the same as with [`hash_file`].
+/// Node identifiers are chosen as in [`hash_file`].
+/// GBZ graphs have integer identifiers and sequences without `%`, which are never escaped.
+pub fn hash_file_escaped<D: Digest, P: AsRef<Path>>(filename: P, node_ids: NodeIds) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let filename = filename.as_ref();
+    #[cfg(feature = "gbz")]
+    if GBZ::is_gbz(filename) {
+        return hash_file::<D, _>(filename, node_ids);
+    }
+
+    fn hash_parsed<D: Digest, G: Graph>(graph: G) -> Result<String, String>
+        where digest::Output<D>: core::fmt::LowerHex {
+        let mut escaped = false;
+        graph.for_each_node(|bytes| escaped |= memchr::memchr(b'%', bytes).is_some());
+        if escaped { Ok(hash_escaped::<D, _>(&escape_graph(&graph)?)) } else { Ok(hash::<D, _>(&graph)) }
+    }
+
+    let input = GfaInput::new(filename, node_ids)?;
+    let integer = match node_ids {
+        NodeIds::Integer => Some(parse_gfa::<GraphInt, _>(input.open()?)?),
+        NodeIds::String => None,
+        NodeIds::Auto => parse_gfa::<GraphInt, _>(input.open()?).ok(),
+    };
+    match integer {
+        Some(graph) => hash_parsed::<D, _>(graph),
+        None => hash_parsed::<D, _>(parse_gfa::<GraphStr, _>(input.open()?)?),
+    }
+}
+
+/// Computes the given hash of the graph stored in the given file, with segment names escaped by [`NamePolicy::Escape`].
+///
+/// This reproduces names computed with `--names escape`.
+/// If some names were escaped, the graph is hashed in version [`crate::vectors::PORTABLE_FORMAT_VERSION`] of the canonical format with [`hash_portable`].
+/// Otherwise the result is the same as with [`hash_file`].
+/// Node identifiers are chosen as in [`hash_file`].
+/// GBZ graphs have integer identifiers, which are never escaped.
+pub fn hash_file_portable<D: Digest, P: AsRef<Path>>(filename: P, node_ids: NodeIds) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let filename = filename.as_ref();
+    #[cfg(feature = "gbz")]
+    if GBZ::is_gbz(filename) {
+        return hash_file::<D, _>(filename, node_ids);
+    }
+
+    fn hash_parsed<D: Digest, G: Graph>((graph, report): (G, ParseReport)) -> String
+        where digest::Output<D>: core::fmt::LowerHex {
+        if report.escaped_names > 0 { hash_portable::<D, _>(&graph) } else { hash::<D, _>(&graph) }
+    }
+
+    let options = ParseOptions { names: NamePolicy::Escape, ..Default::default() };
+    let input = GfaInput::new(filename, node_ids)?;
+    let integer = match node_ids {
+        NodeIds::Integer => Some(parse_gfa_with_options::<GraphInt, _>(input.open()?, &options)?),
+        NodeIds::String => None,
+        NodeIds::Auto => parse_gfa_with_options::<GraphInt, _>(input.open()?, &options).ok(),
+    };
+    match integer {
+        Some(parsed) => Ok(hash_parsed::<D, _>(parsed)),
+        None => Ok(hash_parsed::<D, _>(parse_gfa_with_options::<GraphStr, _>(input.open()?, &options)?)),
+    }
+}
+
+/// Computes the stable name (pggname) of the graph stored in the given file.
+///
+/// This is [`hash_file`] with SHA-256 and [`NodeIds::Auto`].
+/// It mirrors the behavior of the `pggname` binary with default options.
+///
+/// # Examples
+///
+/// ```
+/// use gbz::support;
+///
+/// let filename = support::get_test_data("example.gfa");
+/// let name = pggname::stable_name_from_file(&filename);
+/// assert_eq!(name, Ok(String::from("81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970")));
+///
+/// let filename = support::get_test_data("example.gbz");
+/// let name = pggname::stable_name_from_file(&filename);
+/// assert_eq!(name, Ok(String::from("81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970")));
+/// ```
+pub fn stable_name_from_file<P: AsRef<Path>>(filename: P) -> Result<String, String> {
+    hash_file::<sha2::Sha256, P>(filename, NodeIds::Auto)
+}
+
+//-----------------------------------------------------------------------------
+
+/// Timing and size measurements from computing a stable name.
+///
+/// Services embedding the library can export these values as metrics.
+/// The times are measured with [`Instant`], which is not available on `wasm32-unknown-unknown`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NamingReport {
+    /// Time spent reading and parsing the input, or loading a GBZ file.
+    ///
+    /// With [`NodeIds::Auto`], this includes the parsing phase of a failed attempt with integer identifiers.
+    pub parse_time: Duration,
+    /// Time spent finalizing the graph after parsing.
+    ///
+    /// With [`NodeIds::Auto`], this includes the finalization phase of a failed attempt with integer identifiers.
+    pub finalize_time: Duration,
+    /// Time spent serializing and hashing the canonical GFA representation.
+    pub hash_time: Duration,
+    /// Size of the canonical GFA representation in bytes.
+    pub bytes_hashed: u64,
+    /// Number of nodes in the graph.
+    pub nodes: u64,
+    /// Number of edges in the graph.
+    pub edges: u64,
+}
+
+impl NamingReport {
+    /// Returns the total time spent on the phases.
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.finalize_time + self.hash_time
+    }
+}
+
+/// Computes the stable name of the given graph and reports the time spent and the size of the canonical GFA representation.
+///
+/// The parse and finalize times are zero, as the graph already exists.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+///
+/// let gfa = b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n";
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(gfa).unwrap();
+/// let (name, report) = pggname::stable_name_with_report(&graph);
+/// assert_eq!(name, pggname::stable_name(&graph));
+/// let mut canonical: Vec<u8> = Vec::new();
+/// algorithms::write_canonical(&graph, &mut canonical).unwrap();
+/// assert_eq!(report.bytes_hashed, canonical.len() as u64);
+/// assert_eq!((report.nodes, report.edges), (2, 1));
+/// ```
+pub fn stable_name_with_report<G: Graph>(graph: &G) -> (String, NamingReport) {
+    let mut report = NamingReport::default();
+    let name = hash_with_report::<sha2::Sha256, G>(graph, &mut report);
+    (name, report)
+}
+
+/// Computes the stable name of the graph stored in the given file and reports the time spent in each phase.
+///
+/// This is [`stable_name_from_file`] with a [`NamingReport`].
+/// GBZ files are loaded in the parse phase and need no finalization.
+///
+/// # Examples
+///
+/// ```
+/// use gbz::support;
+///
+/// let filename = support::get_test_data("example.gfa");
+/// let (name, report) = pggname::stable_name_from_file_with_report(&filename).unwrap();
+/// assert_eq!(name, "81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970");
+/// assert!(report.nodes > 0 && report.bytes_hashed > 0);
+/// ```
+pub fn stable_name_from_file_with_report<P: AsRef<Path>>(filename: P) -> Result<(String, NamingReport), String> {
+    let filename = filename.as_ref();
+    let mut report = NamingReport::default();
+    #[cfg(feature = "gbz")]
+    if GBZ::is_gbz(filename) {
+        let start = Instant::now();
+        let graph = LazyGBZ::load_from(filename)?;
+        report.parse_time = start.elapsed();
+        let name = hash_with_report::<sha2::Sha256, _>(&graph, &mut report);
+        return Ok((name, report));
+    }
+
+    let start = Instant::now();
+    let input = GfaInput::new(filename, NodeIds::Auto)?;
+    report.parse_time += start.elapsed();
+    if let Ok(graph) = parse_with_report::<GraphInt>(&input, &mut report) {
+        let name = hash_with_report::<sha2::Sha256, _>(&graph, &mut report);
+        return Ok((name, report));
+    }
+    let graph = parse_with_report::<GraphStr>(&input, &mut report)?;
+    let name = hash_with_report::<sha2::Sha256, _>(&graph, &mut report);
+    Ok((name, report))
+}
+
+// Parses and finalizes the graph in the input.
+// Adds the time spent in each phase to the report, so that the times of a failed attempt are counted once.
+fn parse_with_report<G: Graph>(input: &GfaInput, report: &mut NamingReport) -> Result<G, String> {
+    let start = Instant::now();
+    let result = input.open().and_then(parse_unfinalized::<G, _>);
+    report.parse_time += start.elapsed();
+    let mut graph = result?;
+    let start = Instant::now();
+    let result = graph.finalize();
+    report.finalize_time += start.elapsed();
+    result.map(|_| graph)
+}
+
+// Builds a graph from GFA input without finalizing it.
+fn parse_unfinalized<G: Graph, R: BufRead>(reader: R) -> Result<G, String> {
+    let _span = tracing::debug_span!("parse_gfa").entered();
+    let options = ParseOptions::default();
+    let (graph, _) = parse_lines::<G, R>(reader, &options, &Hooks::default())?.into_parts()?;
+    Ok(graph)
+}
+
+// Computes the given hash of the graph and fills in the hashing fields of the report.
+fn hash_with_report<D: Digest, G: Graph>(graph: &G, report: &mut NamingReport) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    let start = Instant::now();
+    let mut hasher = BufferedHasher::<D>::new();
+    let mut bytes_hashed = 0;
+    graph.for_each_node(|bytes| {
+        bytes_hashed += bytes.len() as u64;
+        hasher.update(bytes);
+    });
+    let result = hasher.finalize();
+    report.hash_time = start.elapsed();
+    report.bytes_hashed = bytes_hashed;
+    let stats = graph.statistics();
+    report.nodes = stats.nodes;
+    report.edges = stats.edges;
+    result
+}
+
+//-----------------------------------------------------------------------------
+
+/// Attempts to infer a translation from graph `a` to graph `b`.
+///
+/// Graph A can be translated to graph B, if A is isomorphic to a subgraph of B after breaking the nodes of both graphs into 1 bp pieces.
+/// The inference matches maximal unary paths of A to walks in B, which allows nodes to be chopped or merged differently in the graphs.
+/// Each unary path of A must map to a walk over entire nodes of B, and the placements must be unambiguous.
+///
+/// Returns the verdict together with the mapping from the nodes of A to the nodes of B.
+/// Returns an error if the canonical representation of a graph cannot be interpreted.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::{GBZInt, GraphStr};
+/// use pggname::translation::TranslationVerdict;
+/// use gbz::{GBZ, support};
+/// use simple_sds::serialize;
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// let gbz: GBZ = serialize::load_from(support::get_test_data("translation.gbz")).unwrap();
+/// let gbz_graph = GBZInt { graph: gbz };
+/// let reader = BufReader::new(File::open(support::get_test_data("translation.gfa")).unwrap());
+/// let gfa_graph: GraphStr = algorithms::parse_gfa(reader).unwrap();
+///
+/// // The GBZ graph has chopped the segments, and the GFA graph has an unused segment.
+/// let result = algorithms::infer_translation(&gbz_graph, &gfa_graph).unwrap();
+/// assert_eq!(result.verdict, TranslationVerdict::Subgraph);
+/// ```
+pub fn infer_translation<A: Graph, B: Graph>(a: &A, b: &B) -> Result<TranslationInference, String> {
+    let a = SequenceGraph::new(a)?;
+    let b = SequenceGraph::new(b)?;
+    Ok(translation::infer(&a, &b))
+}
+
+//-----------------------------------------------------------------------------
+
+/// Weakly connected components of a bidirected graph.
+///
+/// Two nodes are in the same component if there is an undirected path between them, ignoring the orientations of the edges.
+/// Components are numbered in the order of their first node in the canonical order.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms::{self, Components};
+/// use pggname::graph::GraphInt;
+///
+/// let gfa = b"S\t1\tA\nS\t2\tC\nS\t3\tG\nS\t4\tT\nL\t1\t+\t3\t-\t*\nL\t4\t-\t2\t+\t*\n";
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(gfa).unwrap();
+/// let components = Components::new(&graph).unwrap();
+/// assert_eq!(components.len(), 2);
+/// assert_eq!(components.component_of(b"3"), Some(0));
+/// assert_eq!(components.component_of(b"4"), Some(1));
+/// assert_eq!(components.members(1).collect::<Vec<_>>(), vec![b"2".as_slice(), b"4".as_slice()]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Components {
+    /// Node names in canonical order.
+    pub nodes: Vec<Vec<u8>>,
+    /// Component identifier for each node.
+    pub membership: Vec<usize>,
+    // Number of nodes in each component.
+    sizes: Vec<usize>,
+    // Node indexes in the byte order of the names.
+    by_name: Vec<usize>,
+}
+
+impl Components {
+    /// Computes the weakly connected components of the graph.
+    ///
+    /// Uses [`Graph::node_names`] and [`Graph::edges_of`].
+    /// Returns an error if an edge points to a node that is not in the graph.
+    pub fn new<G: Graph>(graph: &G) -> Result<Self, String> {
+        let nodes: Vec<Vec<u8>> = graph.node_names().collect();
+        let mut edges: Vec<(usize, Vec<u8>)> = Vec::new();
+        for (i, name) in nodes.iter().enumerate() {
+            let node_edges = graph.edges_of(name)
+                .ok_or(format!("Missing node: {}", String::from_utf8_lossy(name)))?;
+            edges.extend(node_edges.into_iter().map(|(_, dest, _)| (i, dest)));
+        }
+
+        let index: HashMap<&[u8], usize> = nodes.iter().enumerate().map(|(i, name)| (name.as_slice(), i)).collect();
+        let mut parent: Vec<usize> = (0..nodes.len()).collect();
+        for (source, dest) in edges.iter() {
+            let dest = *index.get(dest.as_slice())
+                .ok_or(format!("Edge to a missing node: {}", String::from_utf8_lossy(dest)))?;
+            let (a, b) = (Self::find(&mut parent, *source), Self::find(&mut parent, dest));
+            // Use the smaller index as the root to make the result deterministic.
+            parent[a.max(b)] = a.min(b);
+        }
+
+        // The root of each component is its first node in the canonical order.
+        let mut membership: Vec<usize> = vec![0; nodes.len()];
+        let mut sizes: Vec<usize> = Vec::new();
+        for i in 0..nodes.len() {
+            let root = Self::find(&mut parent, i);
+            if root == i {
+                membership[i] = sizes.len();
+                sizes.push(0);
+            } else {
+                membership[i] = membership[root];
+            }
+            sizes[membership[i]] += 1;
+        }
+
+        let mut by_name: Vec<usize> = (0..nodes.len()).collect();
+        by_name.sort_unstable_by_key(|&i| nodes[i].as_slice());
+
+        Ok(Components { nodes, membership, sizes, by_name })
+    }
+
+    // Finds the root of the set with path halving.
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    /// Returns the number of components.
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Returns `true` if there are no components.
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// Returns the number of nodes in each component.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// Returns the component containing the node, or [`None`] if there is no such node.
+    pub fn component_of(&self, name: &[u8]) -> Option<usize> {
+        // The nodes are in canonical order, which is not necessarily the byte order.
+        let offset = self.by_name.binary_search_by(|&i| self.nodes[i].as_slice().cmp(name)).ok()?;
+        Some(self.membership[self.by_name[offset]])
+    }
+
+    /// Returns an iterator over the names of the nodes in the component in canonical order.
+    pub fn members(&self, component: usize) -> impl Iterator<Item = &[u8]> {
+        self.nodes.iter().zip(self.membership.iter())
+            .filter(move |(_, c)| **c == component)
+            .map(|(name, _)| name.as_slice())
+    }
+
+    /// Computes the given hash of each component of the graph.
+    ///
+    /// The digest of a component is the hash of the canonical records of its nodes in canonical order.
+    /// Because the edges adjacent to a node are within the same component, this is the same as the hash of the subgraph induced by the component.
+    /// With SHA-256, the digest is the stable name of the component.
+    /// Returns an error if the graph is not the one used for computing the components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::algorithms::{self, Components};
+    /// use pggname::graph::GraphInt;
+    /// use sha2::Sha256;
+    ///
+    /// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tA\nS\t2\tC\nS\t3\tG\nL\t1\t+\t3\t+\t*\n").unwrap();
+    /// let components = Components::new(&graph).unwrap();
+    /// let digests = components.digests::<Sha256, _>(&graph).unwrap();
+    ///
+    /// let component: GraphInt = algorithms::parse_gfa_bytes(b"S\t2\tC\n").unwrap();
+    /// assert_eq!(digests[1], pggname::stable_name(&component));
+    /// ```
+    pub fn digests<D: Digest, G: Graph>(&self, graph: &G) -> Result<Vec<String>, String>
+        where digest::Output<D>: core::fmt::LowerHex {
+        let mut hashers: Vec<D> = (0..self.len()).map(|_| D::new()).collect();
+        let mut i = 0;
+        graph.try_for_each_node(|record| {
+            let name = split_bytes(record, b'\t').nth(1).unwrap_or_default();
+            if self.nodes.get(i).is_none_or(|node| node != name) {
+                return Err(String::from("The graph does not match the components"));
+            }
+            hashers[self.membership[i]].update(record);
+            i += 1;
+            Ok(())
+        })?;
+        if i != self.nodes.len() {
+            return Err(String::from("The graph does not match the components"));
+        }
+        Ok(hashers.into_iter().map(|hasher| format!("{:x}", hasher.finalize())).collect())
+    }
+}
+
+/// Components of graph A that appear verbatim in graph B.
+///
+/// See [`match_components`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComponentMatch {
+    /// Pairs `(a, b)` where component `a` of graph A is identical to component `b` of graph B.
+    pub matched: Vec<(usize, usize)>,
+    /// Components of graph A that are not components of graph B.
+    pub unmatched: Vec<usize>,
+}
+
+impl ComponentMatch {
+    /// Returns `true` if every component of graph A is a component of graph B.
+    ///
+    /// Then graph A is a subgraph of graph B.
+    /// If this is not the case, graph A may still be a subgraph of graph B, as a component of A may be a part of a larger component of B.
+    pub fn is_complete(&self) -> bool {
+        self.unmatched.is_empty()
+    }
+}
+
+/// Finds the components of graph A that appear verbatim in graph B using per-component digests.
+///
+/// The digests must be computed with [`Components::digests`] using the same hash function and node identifier type.
+/// Equal digests mean that the components have the same nodes, sequences, and edges.
+/// This gives an exact partial answer to whether A is a subgraph of B without comparing the graphs node by node.
+/// If several components of B have the same digest, the first one is reported.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms::{self, Components};
+/// use pggname::graph::GraphInt;
+/// use sha2::Sha256;
+///
+/// let a: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tA\nS\t2\tC\nS\t3\tG\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let b: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tA\nS\t2\tC\nS\t3\tT\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let digests_a = Components::new(&a).unwrap().digests::<Sha256, _>(&a).unwrap();
+/// let digests_b = Components::new(&b).unwrap().digests::<Sha256, _>(&b).unwrap();
+/// let result = algorithms::match_components(&digests_a, &digests_b);
+/// assert_eq!(result.matched, vec![(0, 0)]);
+/// assert_eq!(result.unmatched, vec![1]);
+/// assert!(!result.is_complete());
+/// ```
+pub fn match_components(a: &[String], b: &[String]) -> ComponentMatch {
+    let mut index: HashMap<&str, usize> = HashMap::with_capacity(b.len());
+    for (i, digest) in b.iter().enumerate() {
+        index.entry(digest.as_str()).or_insert(i);
+    }
+    let mut result = ComponentMatch::default();
+    for (i, digest) in a.iter().enumerate() {
+        match index.get(digest.as_str()) {
+            Some(&j) => result.matched.push((i, j)),
+            None => result.unmatched.push(i),
+        }
+    }
+    result
+}
+
+//-----------------------------------------------------------------------------
+
+/// A token for cancelling long computations, possibly from another thread.
+///
+/// Clones of the token share the same state.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the cancellation of computations using this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Phases of a computation reported to progress callbacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Parsing GFA lines.
+    Parsing,
+    /// Hashing canonical node records.
+    Hashing,
+}
+
+/// Optional hooks for monitoring and cancelling long computations.
+///
+/// Every `interval` items, the computation checks the cancellation token and calls the progress callback with the number of items processed so far.
+/// The callback is also called once at the end of each phase with the total number of items.
+#[derive(Clone, Copy)]
+pub struct Hooks<'a> {
+    /// Progress callback.
+    pub progress: Option<&'a (dyn Fn(Phase, usize) + Sync)>,
+    /// Cancellation token.
+    pub cancel: Option<&'a CancellationToken>,
+    /// Number of items between checkpoints.
+    pub interval: usize,
+}
+
+impl Hooks<'_> {
+    /// Default number of items between checkpoints.
+    pub const DEFAULT_INTERVAL: usize = 1 << 16;
+
+    // Checks for cancellation and reports progress, if this is a checkpoint.
+    fn checkpoint(&self, phase: Phase, count: usize) -> Result<(), String> {
+        if self.interval == 0 || !count.is_multiple_of(self.interval) {
+            return Ok(());
+        }
+        self.finish(phase, count)
+    }
+
+    // Checks for cancellation and reports progress.
+    fn finish(&self, phase: Phase, count: usize) -> Result<(), String> {
+        if let Some(token) = self.cancel && token.is_cancelled() {
+            return Err(String::from("Computation was cancelled"));
+        }
+        if let Some(callback) = self.progress {
+            callback(phase, count);
+        }
+        Ok(())
+    }
+}
+
+impl Default for Hooks<'_> {
+    fn default() -> Self {
+        Hooks {
+            progress: None,
+            cancel: None,
+            interval: Self::DEFAULT_INTERVAL,
+        }
+    }
+}
 
 //-----------------------------------------------------------------------------
 
-/// Builds a graph from the given GFA input.
+/// Parses an orientation from a GFA field (`+` or `-`).
 ///
-/// Returns an error if reading the input fails of if the GFA cannot be parsed.
-/// Passes through errors from the graph methods.
+/// Returns an error if the field is not a valid orientation.
 ///
 /// # Examples
 ///
 /// ```
-/// use pggname::Graph;
+/// use pggname::Orientation;
 /// use pggname::algorithms;
-/// use pggname::graph::GraphInt;
-/// use gbz::support;
-/// use std::fs::OpenOptions;
-/// use std::io::BufReader;
 ///
-/// let filename = support::get_test_data("example.gfa");
-/// let file = OpenOptions::new().read(true).open(&filename).unwrap();
-/// let reader = BufReader::new(file);
-/// let graph = algorithms::parse_gfa::<GraphInt, _>(reader);
-/// assert!(graph.is_ok());
-///
-/// let graph = graph.unwrap();
-/// let (node_count, edge_count, seq_len) = graph.statistics();
-/// assert_eq!(node_count, 12);
-/// assert_eq!(edge_count, 13);
-/// assert_eq!(seq_len, 12);
+/// assert_eq!(algorithms::parse_orientation(b"+"), Ok(Orientation::Forward));
+/// assert_eq!(algorithms::parse_orientation(b"-"), Ok(Orientation::Reverse));
+/// assert!(algorithms::parse_orientation(b"forward").is_err());
 /// ```
-pub fn parse_gfa<G: Graph, R: BufRead>(reader: R) -> Result<G, String> {
-    let mut graph = G::new();
-    for (i, line) in reader.split(b'\n').enumerate() {
-        let line = line.map_err(|e| format!("Error reading GFA line {}: {}", i + 1, e))?;
-        if line.is_empty() {
-            continue;
-        }
-        if line[0] == b'S' {
-            let fields: Vec<&[u8]> = line.split(|&c| c == b'\t').collect();
-            if fields.len() < 3 {
-                return Err(format!("Error parsing GFA line {}: not enough fields for a segment", i + 1));
-            }
-            graph.add_node(fields[1], fields[2])?;
-        } else if line[0] == b'L' {
-            let fields: Vec<&[u8]> = line.split(|&c| c == b'\t').collect();
-            if fields.len() < 5 {
-                return Err(format!("Error parsing GFA line {}: not enough fields for a link", i + 1));
-            }
-            let source_name = fields[1];
-            let source_o = parse_orientation(fields[2])
-                .map_err(|e| format!("Error parsing GFA line {}: {}", i + 1, e))?;
-            let dest_name = fields[3];
-            let dest_o = parse_orientation(fields[4])
-                .map_err(|e| format!("Error parsing GFA line {}: {}", i + 1, e))?;
-            graph.add_edge(source_name, source_o, dest_name, dest_o)?;
-        }
-    }
-    graph.finalize()?;
-
-    Ok(graph)
-}
-
-//-----------------------------------------------------------------------------
-
-/// Computes the given hash of the canonical GFA representation of the given graph.
-pub fn hash<D: Digest, G: Graph>(graph: &G) -> String
-    where digest::Output<D>: core::fmt::LowerHex {
-    let mut hasher = D::new();
-    for bytes in graph.node_iter() {
-        hasher.update(&bytes);
+pub fn parse_orientation(field: &[u8]) -> Result<Orientation, String> {
+    match field {
+        b"+" => Ok(Orientation::Forward),
+        b"-" => Ok(Orientation::Reverse),
+        _ => Err(format!("Invalid orientation: {}", String::from_utf8_lossy(field))),
     }
-    let hash = hasher.finalize();
-    format!("{:x}", hash)
 }
 
-/// Computes the stable name (pggname) of the given graph.
+/// Returns the GFA symbol for the orientation (`+` or `-`).
+///
+/// Use this instead of the [`std::fmt::Display`] implementation of [`Orientation`], which writes `forward` or `reverse` when the `gbz` feature is enabled.
 ///
 /// # Examples
 ///
 /// ```
-/// use pggname::Graph;
-/// use pggname::graph::GBZInt;
-/// use gbz::GBZ;
-/// use gbz::support;
-/// use simple_sds::serialize;
+/// use pggname::Orientation;
+/// use pggname::algorithms;
 ///
-/// let filename = support::get_test_data("example.gbz");
-/// let gbz: GBZ = serialize::load_from(&filename).unwrap();
-/// let graph = GBZInt { graph: gbz };
-/// let hash = pggname::stable_name(&graph);
-/// assert_eq!(hash, "81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970");
+/// assert_eq!(algorithms::orientation_symbol(Orientation::Forward), '+');
+/// assert_eq!(algorithms::orientation_symbol(Orientation::Reverse), '-');
 /// ```
-pub fn stable_name<G: Graph>(graph: &G) -> String {
-    hash::<sha2::Sha256, G>(graph)
-}
-
-//-----------------------------------------------------------------------------
-
-// Parses the orientation from GFA field.
-fn parse_orientation(field: &[u8]) -> Result<Orientation, String> {
-    match field {
-        b"+" => Ok(Orientation::Forward),
-        b"-" => Ok(Orientation::Reverse),
-        _ => Err(format!("Invalid orientation: {}", String::from_utf8_lossy(field))),
+pub fn orientation_symbol(orientation: Orientation) -> char {
+    match orientation {
+        Orientation::Forward => '+',
+        Orientation::Reverse => '-',
     }
 }
 
@@ -120,16 +2219,19 @@ fn parse_orientation(field: &[u8]) -> Result<Orientation, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::graph::{GBZInt, GBZStr, GraphInt, GraphStr};
+    use crate::graph::{GraphInt, GraphStatistics, GraphStr};
+    #[cfg(feature = "gbz")]
+    use crate::graph::{GBZInt, GBZStr};
+    use crate::testing::TempFile;
 
-    use gbz::GBZ;
+    #[cfg(feature = "gbz")]
     use gbz::support;
     use sha2::Sha256;
-    use simple_sds::serialize;
 
-    use std::fs::OpenOptions;
-    use std::io::BufReader;
+    #[cfg(feature = "gbz")]
+    use std::io::Write;
 
+    #[cfg(feature = "gbz")]
     struct TestCase {
         gfa_name: &'static str,
         gbz_name: &'static str,
@@ -139,6 +2241,7 @@ mod tests {
         hash_gbz_str: &'static str,
     }
 
+    #[cfg(feature = "gbz")]
     const TEST_CASES: &[TestCase] = {
         &[
             TestCase {
@@ -160,10 +2263,11 @@ mod tests {
         ]
     };
 
+    #[cfg(feature = "gbz")]
     #[test]
     fn test_gfa() {
         for test_case in TEST_CASES.iter() {
-            let filename = support::get_test_data(&test_case.gfa_name);
+            let filename = support::get_test_data(test_case.gfa_name);
             let file = OpenOptions::new()
                 .read(true)
                 .open(&filename)
@@ -190,10 +2294,332 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_gfa_bytes() {
+        for test_case in TEST_CASES.iter() {
+            let filename = support::get_test_data(test_case.gfa_name);
+            let gfa = std::fs::read(&filename).unwrap();
+
+            let graph_int = parse_gfa_bytes::<GraphInt>(&gfa);
+            if !test_case.hash_gfa_int.is_empty() {
+                assert!(graph_int.is_ok(), "Failed to parse GraphInt {} from bytes", test_case.gfa_name);
+                let hash_int = hash::<Sha256, _>(&graph_int.unwrap());
+                assert_eq!(&hash_int, test_case.hash_gfa_int, "Wrong hash for GraphInt {} from bytes", test_case.gfa_name);
+            } else {
+                assert!(graph_int.is_err(), "GraphInt parsing from bytes should have failed for {}", test_case.gfa_name);
+            }
+
+            let graph_str = parse_gfa_bytes::<GraphStr>(&gfa).unwrap();
+            let hash_str = hash::<Sha256, _>(&graph_str);
+            assert_eq!(&hash_str, test_case.hash_gfa_str, "Wrong hash for GraphStr {} from bytes", test_case.gfa_name);
+
+            // Without the final newline.
+            let graph_str = parse_gfa_bytes::<GraphStr>(&gfa[..gfa.len() - 1]).unwrap();
+            let hash_str = hash::<Sha256, _>(&graph_str);
+            assert_eq!(&hash_str, test_case.hash_gfa_str, "Wrong hash for GraphStr {} from bytes without final newline", test_case.gfa_name);
+        }
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_gfa_stream() {
+        for test_case in TEST_CASES.iter() {
+            let filename = support::get_test_data(test_case.gfa_name);
+            let gfa = std::fs::read(&filename).unwrap();
+
+            for chunk_size in [1, 7, gfa.len()] {
+                let mut stream = GfaStream::<GraphStr>::new();
+                for chunk in gfa[..gfa.len() - 1].chunks(chunk_size) {
+                    stream.push(chunk).unwrap();
+                }
+                let hash_str = hash::<Sha256, _>(&stream.finish().unwrap());
+                assert_eq!(&hash_str, test_case.hash_gfa_str, "Wrong hash for GraphStr {} with chunk size {}", test_case.gfa_name, chunk_size);
+            }
+        }
+
+        let mut stream = GfaStream::<GraphInt>::new();
+        assert!(stream.push(b"S\t1\tA\nS\tx").is_ok(), "Incomplete line was parsed too early");
+        assert!(stream.push(b"\tA\n").is_err(), "Parsed a string identifier as an integer");
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_record_source() {
+        for test_case in TEST_CASES.iter() {
+            let filename = support::get_test_data(test_case.gfa_name);
+            let records = GfaRecords::new(BufReader::new(File::open(&filename).unwrap()));
+            let graph_str: GraphStr = parse_records(records).unwrap();
+            let hash_str = hash::<Sha256, _>(&graph_str);
+            assert_eq!(&hash_str, test_case.hash_gfa_str, "Wrong hash for GraphStr {} from records", test_case.gfa_name);
+        }
+
+        // Records from a generator.
+        let gfa = b"S\t1\tA\nS\t2\tC\nS\t3\tG\nL\t1\t+\t2\t+\t*\nL\t2\t+\t3\t+\t*\n";
+        let records = (1..=3).map(|i: usize| Ok(GfaRecord::segment(i.to_string().as_bytes(), &b"ACG"[i - 1..i])))
+            .chain((1..3).map(|i: usize| Ok(GfaRecord::link(i.to_string().as_bytes(), Orientation::Forward, (i + 1).to_string().as_bytes(), Orientation::Forward))));
+        let graph: GraphInt = parse_records(records).unwrap();
+        assert_eq!(stable_name(&graph), stable_name(&parse_gfa_bytes::<GraphInt>(gfa).unwrap()), "Wrong name from generated records");
+
+        // Errors.
+        let result = parse_records::<GraphInt, _>(GfaRecords::new(&b"S\t1\tA\nH\tVN:Z:1.0\nL\t1\t+\t2\n"[..]));
+        assert_eq!(result.err(), Some(String::from("Error parsing GFA line 3: not enough fields for a link")), "Wrong error for an invalid line");
+        let records = vec![Ok(GfaRecord::segment(b"1", b"A")), Ok(GfaRecord::link(b"1", Orientation::Forward, b"2", Orientation::Forward))];
+        assert!(parse_records::<GraphInt, _>(records.into_iter()).is_err(), "Link to a missing node was accepted");
+        let records = vec![Ok(GfaRecord::segment(b"1", b"A")), Err(String::from("connection lost"))];
+        assert_eq!(parse_records::<GraphInt, _>(records.into_iter()).err(), Some(String::from("connection lost")), "Source error was not passed through");
+    }
+
+    fn node_records<G: Graph>(graph: &G) -> Vec<Vec<u8>> {
+        let mut result = Vec::new();
+        graph.try_for_each_node(|record| {
+            result.push(record.to_vec());
+            Ok::<(), String>(())
+        }).unwrap();
+        result
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_peek() {
+        for test_case in TEST_CASES.iter() {
+            let filename = support::get_test_data(test_case.gfa_name);
+            let peek = peek_gfa(open_gfa(&filename).unwrap()).unwrap();
+            assert!(!peek.noncanonical_ids, "Non-canonical ids in {}", test_case.gfa_name);
+            let nodes = match parse_gfa::<GraphInt, _>(open_gfa(&filename).unwrap()) {
+                Ok(graph) => {
+                    assert_eq!(peek.node_ids, NodeIds::Integer, "Wrong node id mode for {}", test_case.gfa_name);
+                    node_records(&graph)
+                }
+                Err(_) => {
+                    assert_eq!(peek.node_ids, NodeIds::String, "Wrong node id mode for {}", test_case.gfa_name);
+                    node_records(&parse_gfa::<GraphStr, _>(open_gfa(&filename).unwrap()).unwrap())
+                }
+            };
+            let segment = |segment: &Option<(Vec<u8>, Vec<u8>)>| {
+                let (name, sequence) = segment.clone().unwrap();
+                [&b"S\t"[..], &name, b"\t", &sequence, b"\n"].concat()
+            };
+            assert!(nodes.first().unwrap().starts_with(&segment(&peek.first)), "Wrong first segment for {}", test_case.gfa_name);
+            assert!(nodes.last().unwrap().starts_with(&segment(&peek.last)), "Wrong last segment for {}", test_case.gfa_name);
+        }
+
+        let peek = peek_gfa(&b"S\tb\tA\nS\t10\tC\nS\t9\tG\nL\t9\t+\tb\t+\t*\n"[..]).unwrap();
+        assert_eq!((peek.segments, peek.links), (3, 1), "Wrong record counts");
+        assert_eq!(peek.node_ids, NodeIds::String, "String names were not detected");
+        assert_eq!(peek.first, Some((b"10".to_vec(), b"C".to_vec())), "Wrong first segment with string names");
+        assert_eq!(peek.last, Some((b"b".to_vec(), b"A".to_vec())), "Wrong last segment with string names");
+        let peek = peek_gfa(&b"S\t02\tA\nS\t1\tC\n"[..]).unwrap();
+        assert!(peek.noncanonical_ids, "Non-canonical ids were not detected");
+        assert_eq!(peek_gfa(&b""[..]), Ok(GfaPeek { node_ids: NodeIds::Integer, ..Default::default() }), "Wrong summary for empty input");
+        assert!(peek_gfa(&b"S\t1\n"[..]).is_err(), "Accepted an invalid segment line");
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_hooks() {
+        let filename = support::get_test_data("example.gfa");
+        let gfa = std::fs::read(&filename).unwrap();
+        let lines = gfa.iter().filter(|&&c| c == b'\n').count();
+
+        let progress: std::sync::Mutex<Vec<(Phase, usize)>> = std::sync::Mutex::new(Vec::new());
+        let callback = |phase: Phase, count: usize| progress.lock().unwrap().push((phase, count));
+        let hooks = Hooks { progress: Some(&callback), interval: 10, ..Default::default() };
+        let (graph, _) = parse_gfa_with_hooks::<GraphInt, _>(&gfa[..], &ParseOptions::default(), &hooks).unwrap();
+        let hash = hash_with_hooks::<Sha256, _>(&graph, &hooks);
+        assert_eq!(hash.as_deref(), Ok(TEST_CASES[0].hash_gfa_int), "Wrong hash with hooks");
+
+        let mut expected: Vec<(Phase, usize)> = (1..=lines / 10).map(|i| (Phase::Parsing, 10 * i)).collect();
+        expected.push((Phase::Parsing, lines));
+        expected.push((Phase::Hashing, 10));
+        expected.push((Phase::Hashing, 12));
+        assert_eq!(*progress.lock().unwrap(), expected, "Wrong progress reports");
+
+        let token = CancellationToken::new();
+        let hooks = Hooks { cancel: Some(&token), interval: 1, ..Default::default() };
+        let result = parse_gfa_with_hooks::<GraphInt, _>(&gfa[..], &ParseOptions::default(), &hooks);
+        assert!(result.is_ok(), "Parsing failed without cancellation");
+        token.clone().cancel();
+        let result = parse_gfa_with_hooks::<GraphInt, _>(&gfa[..], &ParseOptions::default(), &hooks);
+        assert!(result.is_err(), "Parsing succeeded after cancellation");
+        let result = hash_with_hooks::<Sha256, _>(&graph, &hooks);
+        assert!(result.is_err(), "Hashing succeeded after cancellation");
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_fingerprint() {
+        let filename = support::get_test_data("example.gfa");
+        let gfa = std::fs::read(&filename).unwrap();
+        let graph_int = parse_gfa_bytes::<GraphInt>(&gfa).unwrap();
+        let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+        let gbz_int = GBZInt { graph: gbz };
+
+        for interval in [0, 1, 3, FINGERPRINT_INTERVAL] {
+            let from_gfa = fingerprint::<Sha256, _>(&graph_int, interval);
+            let from_gbz = fingerprint::<Sha256, _>(&gbz_int, interval);
+            assert_eq!(from_gfa, from_gbz, "Different fingerprints for the same graph with interval {}", interval);
+            assert_ne!(from_gfa, TEST_CASES[0].hash_gfa_int, "Fingerprint with interval {} is the name", interval);
+        }
+        assert_eq!(fingerprint::<Sha256, _>(&graph_int, 0), fingerprint::<Sha256, _>(&graph_int, 1), "Interval 0 is not treated as 1");
+
+        // The fingerprint hashes the statistics, the sampled records, and the last record if it was not sampled.
+        let records: Vec<Vec<u8>> = graph_int.node_iter().collect();
+        let stats = graph_int.statistics();
+        for interval in [1, 3, records.len() - 1, records.len(), FINGERPRINT_INTERVAL] {
+            let mut hasher = Sha256::new();
+            hasher.update(format!("{}\t{}\t{}\t{}\n", stats.nodes, stats.edges, stats.sequence_len, interval).as_bytes());
+            for (i, record) in records.iter().enumerate() {
+                if i % interval == 0 || i + 1 == records.len() {
+                    hasher.update(record);
+                }
+            }
+            let expected = format!("{:x}", hasher.finalize());
+            assert_eq!(fingerprint::<Sha256, _>(&graph_int, interval), expected, "Wrong records in the fingerprint with interval {}", interval);
+        }
+
+        // Changing the last node must change the fingerprint.
+        let mut modified = gfa.clone();
+        modified.extend_from_slice(b"S\t100\tA\n");
+        let modified = parse_gfa_bytes::<GraphInt>(&modified).unwrap();
+        assert_ne!(
+            fingerprint::<Sha256, _>(&graph_int, FINGERPRINT_INTERVAL), fingerprint::<Sha256, _>(&modified, FINGERPRINT_INTERVAL),
+            "Adding a node did not change the fingerprint"
+        );
+    }
+
+    #[cfg(feature = "gbz")]
+    // Checks that the digest of each test graph is the hash of the domain prefix and the given part of each serialized node.
+    // Also checks that the digest is not the stable name of the graph consisting of the same part.
+    fn check_partial_digest(kind: &str, domain: &[u8], part: fn(&[u8]) -> &[u8], digest: fn(&GraphStr) -> String, gbz_digest: fn(&GBZStr) -> String) {
+        for test_case in TEST_CASES.iter() {
+            let gfa = std::fs::read(support::get_test_data(test_case.gfa_name)).unwrap();
+            let graph = parse_gfa_bytes::<GraphStr>(&gfa).unwrap();
+            let mut data: Vec<u8> = domain.to_vec();
+            graph.for_each_node(|bytes| data.extend_from_slice(part(bytes)));
+            let result = digest(&graph);
+            assert_eq!(result, format!("{:x}", Sha256::digest(&data)), "Wrong {} digest for {}", kind, test_case.gfa_name);
+            assert_ne!(result, stable_name(&graph), "{} digest is the name for {}", kind, test_case.gfa_name);
+            assert_ne!(result, format!("{:x}", Sha256::digest(&data[domain.len()..])), "No domain prefix in {} digest for {}", kind, test_case.gfa_name);
+            if test_case.hash_gbz_str == test_case.hash_gfa_str {
+                let gbz: GBZ = serialize::load_from(support::get_test_data(test_case.gbz_name)).unwrap();
+                assert_eq!(gbz_digest(&GBZStr { graph: gbz }), result, "Different {} digest for {}", kind, test_case.gbz_name);
+            }
+        }
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_node_table_digest() {
+        fn segments(bytes: &[u8]) -> &[u8] {
+            &bytes[..bytes.iter().position(|&c| c == b'\n').unwrap() + 1]
+        }
+        check_partial_digest("node-table", NODE_TABLE_DOMAIN, segments, node_table_digest::<GraphStr>, node_table_digest::<GBZStr>);
+
+        // A graph without edges is its node table, but the digest is not its name.
+        let graph = parse_gfa_bytes::<GraphInt>(b"S\t1\tACGT\nS\t2\tGA\n").unwrap();
+        assert_ne!(node_table_digest(&graph), stable_name(&graph), "Node-table digest is the name of a graph without edges");
+
+        // Edges do not matter, but node identifiers and sequences do.
+        let gfa = b"S\t1\tACGT\nS\t2\tGA\nS\t3\tT\nL\t1\t+\t2\t+\t*\nL\t2\t+\t3\t+\t*\n";
+        let graph = parse_gfa_bytes::<GraphInt>(gfa).unwrap();
+        let digest = node_table_digest(&graph);
+        let cases: [(&[u8], bool); 4] = [
+            (b"S\t1\tACGT\nS\t2\tGA\nS\t3\tT\n", true),
+            (b"S\t3\tT\nS\t2\tGA\nS\t1\tACGT\nL\t1\t-\t3\t+\t*\n", true),
+            (b"S\t1\tACGT\nS\t2\tGC\nS\t3\tT\nL\t1\t+\t2\t+\t*\nL\t2\t+\t3\t+\t*\n", false),
+            (b"S\t1\tACGT\nS\t2\tGA\nS\t4\tT\nL\t1\t+\t2\t+\t*\nL\t2\t+\t4\t+\t*\n", false),
+        ];
+        for (other, same) in cases {
+            let other = parse_gfa_bytes::<GraphInt>(other).unwrap();
+            assert_eq!(node_table_digest(&other) == digest, same, "Wrong node-table comparison for {:?}", other.nodes.keys().collect::<Vec<_>>());
+        }
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_edge_set_digest() {
+        fn links(bytes: &[u8]) -> &[u8] {
+            &bytes[bytes.iter().position(|&c| c == b'\n').unwrap() + 1..]
+        }
+        check_partial_digest("edge-set", EDGE_SET_DOMAIN, links, edge_set_digest::<GraphStr>, edge_set_digest::<GBZStr>);
+
+        // The edge set of a graph without edges is empty, but the digest is not the name of the empty graph.
+        let graph = parse_gfa_bytes::<GraphInt>(b"S\t1\tACGT\n").unwrap();
+        assert_ne!(edge_set_digest(&graph), stable_name(&GraphInt::new()), "Edge-set digest is the name of the empty graph");
+        assert_ne!(edge_set_digest(&graph), node_table_digest(&GraphInt::new()), "Edge-set digest is the node-table digest of the empty graph");
+
+        // Sequences do not matter, but edges do.
+        let gfa = b"S\t1\tACGT\nS\t2\tGA\nS\t3\tT\nL\t1\t+\t2\t+\t*\nL\t2\t+\t3\t+\t*\n";
+        let graph = parse_gfa_bytes::<GraphInt>(gfa).unwrap();
+        let digest = edge_set_digest(&graph);
+        let cases: [(&[u8], bool); 4] = [
+            (b"S\t1\tA\nS\t2\tGGG\nS\t3\tT\nL\t1\t+\t2\t+\t*\nL\t2\t+\t3\t+\t*\n", true),
+            (b"S\t1\tACGT\nS\t2\tGA\nS\t3\tT\nL\t3\t-\t2\t-\t*\nL\t2\t-\t1\t-\t*\n", true),
+            (b"S\t1\tACGT\nS\t2\tGA\nS\t3\tT\nL\t1\t+\t2\t+\t*\n", false),
+            (b"S\t1\tACGT\nS\t2\tGA\nS\t3\tT\nL\t1\t+\t2\t+\t*\nL\t2\t+\t3\t-\t*\n", false),
+        ];
+        for (gfa, same) in cases {
+            let other = parse_gfa_bytes::<GraphInt>(gfa).unwrap();
+            assert_eq!(edge_set_digest(&other) == digest, same, "Wrong edge-set comparison for {}", String::from_utf8_lossy(gfa));
+        }
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_write_canonical() {
+        for test_case in TEST_CASES.iter() {
+            let gbz: GBZ = serialize::load_from(support::get_test_data(test_case.gbz_name)).unwrap();
+            let graph = GBZStr { graph: gbz };
+            let mut canonical: Vec<u8> = Vec::new();
+            write_canonical(&graph, &mut canonical).unwrap();
+            assert_eq!(format!("{:x}", Sha256::digest(&canonical)), test_case.hash_gbz_str, "Wrong canonical GFA for {}", test_case.gbz_name);
+
+            let mut streamed: Vec<u8> = Vec::new();
+            let hash = write_canonical_with_hash::<Sha256, _, _>(&graph, &mut streamed).unwrap();
+            assert_eq!(streamed, canonical, "Different canonical GFA with hashing for {}", test_case.gbz_name);
+            assert_eq!(hash, test_case.hash_gbz_str, "Wrong hash of the canonical GFA for {}", test_case.gbz_name);
+
+            // The canonical GFA must parse back into the same graph.
+            let parsed = parse_gfa_bytes::<GraphStr>(&canonical).unwrap();
+            assert_eq!(stable_name(&parsed), test_case.hash_gbz_str, "Canonical GFA for {} does not parse back", test_case.gbz_name);
+        }
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_naming_report() {
+        for test_case in TEST_CASES.iter() {
+            for filename in [test_case.gfa_name, test_case.gbz_name] {
+                let path = support::get_test_data(filename);
+                let (name, report) = stable_name_from_file_with_report(&path).unwrap();
+                assert_eq!(name, stable_name_from_file(&path).unwrap(), "Wrong name with a report for {}", filename);
+
+                let graph = LazyGBZ::load_from(support::get_test_data(test_case.gbz_name)).unwrap();
+                let graph_name = stable_name(&graph);
+                let (_, graph_report) = stable_name_with_report(&graph);
+                assert_eq!(graph_report.parse_time + graph_report.finalize_time, Duration::ZERO, "Parse time for an existing graph");
+                if name == graph_name {
+                    let mut canonical: Vec<u8> = Vec::new();
+                    write_canonical(&graph, &mut canonical).unwrap();
+                    let stats = graph.statistics();
+                    assert_eq!(report.bytes_hashed, canonical.len() as u64, "Wrong number of bytes hashed for {}", filename);
+                    assert_eq!((report.nodes, report.edges), (stats.nodes, stats.edges), "Wrong graph size for {}", filename);
+                    assert_eq!(
+                        (graph_report.bytes_hashed, graph_report.nodes, graph_report.edges), (report.bytes_hashed, report.nodes, report.edges),
+                        "Different reports for {} and the graph", filename
+                    );
+                }
+                assert!(report.total_time() >= report.hash_time, "Total time is less than the hash time for {}", filename);
+            }
+        }
+    }
+
+    #[cfg(feature = "gbz")]
     #[test]
     fn test_gbz() {
         for test_case in TEST_CASES.iter() {
-            let filename = support::get_test_data(&test_case.gbz_name);
+            let filename = support::get_test_data(test_case.gbz_name);
             let gbz: GBZ = serialize::load_from(&filename).unwrap();
 
             let gbz_int = GBZInt { graph: gbz.clone() };
@@ -205,6 +2631,382 @@ mod tests {
             assert_eq!(&hash_str, test_case.hash_gbz_str, "Wrong hash for GBZStr {}", test_case.gbz_name);
         }
     }
+
+    #[cfg(all(feature = "async", feature = "gbz"))]
+    #[test]
+    fn test_gfa_async() {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        for test_case in TEST_CASES.iter() {
+            let filename = support::get_test_data(test_case.gfa_name);
+            let gfa = std::fs::read(&filename).unwrap();
+
+            let graph_str: GraphStr = runtime.block_on(parse_gfa_async(&gfa[..])).unwrap();
+            let hash_str = runtime.block_on(hash_async::<Sha256, _>(&graph_str));
+            assert_eq!(&hash_str, test_case.hash_gfa_str, "Wrong async hash for GraphStr {}", test_case.gfa_name);
+
+            let graph_int = runtime.block_on(parse_gfa_async::<GraphInt, _>(&gfa[..]));
+            if !test_case.hash_gfa_int.is_empty() {
+                let hash_int = runtime.block_on(hash_async::<Sha256, _>(&graph_int.unwrap()));
+                assert_eq!(&hash_int, test_case.hash_gfa_int, "Wrong async hash for GraphInt {}", test_case.gfa_name);
+            } else {
+                assert!(graph_int.is_err(), "Async GraphInt parsing should have failed for {}", test_case.gfa_name);
+            }
+        }
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_files() {
+        for test_case in TEST_CASES.iter() {
+            let gfa_file = support::get_test_data(test_case.gfa_name);
+            let expected = if test_case.hash_gfa_int.is_empty() { test_case.hash_gfa_str } else { test_case.hash_gfa_int };
+            let name = stable_name_from_file(&gfa_file);
+            assert_eq!(name.as_deref(), Ok(expected), "Wrong name for GFA file {}", test_case.gfa_name);
+            let hash_str = hash_file::<Sha256, _>(&gfa_file, NodeIds::String);
+            assert_eq!(hash_str.as_deref(), Ok(test_case.hash_gfa_str), "Wrong string hash for GFA file {}", test_case.gfa_name);
+
+            let gbz_file = support::get_test_data(test_case.gbz_name);
+            let name = stable_name_from_file(&gbz_file);
+            assert_eq!(name.as_deref(), Ok(test_case.hash_gbz_int), "Wrong name for GBZ file {}", test_case.gbz_name);
+            let hash_str = hash_file::<Sha256, _>(&gbz_file, NodeIds::String);
+            assert_eq!(hash_str.as_deref(), Ok(test_case.hash_gbz_str), "Wrong string hash for GBZ file {}", test_case.gbz_name);
+        }
+    }
+
+    #[test]
+    fn test_gfa_input() {
+        // A buffered input is parsed from memory, so the fallback to string identifiers does not reopen the file.
+        let gfa = b"S\ta\tACGT\nS\tb\tGA\nL\ta\t+\tb\t+\t*\n";
+        let input = GfaInput { filename: Path::new("missing.gfa"), buffer: Some(gfa.to_vec()) };
+        assert!(parse_gfa::<GraphInt, _>(input.open().unwrap()).is_err(), "Parsed string identifiers as integers");
+        let graph: GraphStr = parse_gfa(input.open().unwrap()).unwrap();
+        let expected: GraphStr = parse_gfa_bytes(gfa).unwrap();
+        assert_eq!(stable_name(&graph), stable_name(&expected), "Wrong graph from a buffered input");
+
+        // Local files are reopened for each attempt.
+        let input = GfaInput::new(Path::new("missing.gfa"), NodeIds::Auto).unwrap();
+        assert!(input.buffer.is_none(), "Buffered a local file");
+        assert!(input.open().is_err(), "Opened a missing local file");
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_compressed_gfa() {
+        let gfa_file = support::get_test_data("example.gfa");
+        let gfa = std::fs::read(&gfa_file).unwrap();
+        let compressed_file = TempFile::new(".gfa.gz");
+        {
+            let file = File::create(&compressed_file).unwrap();
+            let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            encoder.write_all(&gfa).unwrap();
+            encoder.finish().unwrap();
+        }
+        let name = stable_name_from_file(&compressed_file);
+        assert_eq!(name.as_deref(), Ok(TEST_CASES[0].hash_gfa_int), "Wrong name for compressed GFA");
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_trailing_whitespace() {
+        let gfa_file = support::get_test_data("example.gfa");
+        let gfa = std::fs::read(&gfa_file).unwrap();
+        let lines = gfa.split(|&c| c == b'\n').filter(|line| !line.is_empty()).count();
+        for (suffix, description) in [(&b"\r"[..], "CRLF line endings"), (b" ", "trailing spaces"), (b"\t \r", "mixed whitespace")] {
+            let mut modified: Vec<u8> = Vec::new();
+            for line in gfa.split(|&c| c == b'\n').filter(|line| !line.is_empty()) {
+                modified.extend_from_slice(line);
+                modified.extend_from_slice(suffix);
+                modified.push(b'\n');
+            }
+
+            let (graph, report) = parse_gfa_bytes_with_options::<GraphInt>(&modified, &ParseOptions::default()).unwrap();
+            assert_eq!(stable_name(&graph), TEST_CASES[0].hash_gfa_int, "Wrong name with {}", description);
+            assert!(report.is_clean(), "Errors reported with {}", description);
+            assert_eq!(report.trimmed_lines, lines, "Wrong number of trimmed lines with {}", description);
+            let (graph, _) = parse_gfa_with_options::<GraphInt, _>(&modified[..], &ParseOptions::default()).unwrap();
+            assert_eq!(stable_name(&graph), TEST_CASES[0].hash_gfa_int, "Wrong name from a reader with {}", description);
+
+            let strict = ParseOptions { strict: true, ..Default::default() };
+            let result = parse_gfa_bytes_with_options::<GraphInt>(&modified, &strict);
+            assert!(result.is_err(), "Strict mode accepted {}", description);
+            let strict_lenient = ParseOptions { strict: true, lenient: true, ..Default::default() };
+            let (_, report) = parse_gfa_bytes_with_options::<GraphStr>(&modified, &strict_lenient).unwrap();
+            assert_eq!(report.errors.len(), lines, "Wrong number of errors in strict lenient mode with {}", description);
+        }
+
+        let (_, report) = parse_gfa_bytes_with_options::<GraphInt>(&gfa, &ParseOptions::default()).unwrap();
+        assert_eq!(report.trimmed_lines, 0, "Trimmed lines without trailing whitespace");
+    }
+
+    #[test]
+    fn test_empty_sequence() {
+        // The trailing tab delimits an empty sequence and must not be stripped.
+        let expected = b"S\t1\tGA\nL\t1\t+\t2\t+\nS\t2\t\n";
+        for gfa in [&b"S\t1\tGA\nS\t2\t\nL\t1\t+\t2\t+\t*\n"[..], b"S\t1\tGA\r\nS\t2\t\r\nL\t1\t+\t2\t+\t*\r\n", b"S\t1\tGA\nS\t2\t \nL\t1\t+\t2\t+\t*\n"] {
+            let (graph, report) = parse_gfa_bytes_with_options::<GraphInt>(gfa, &ParseOptions::default()).unwrap();
+            assert!(report.is_clean(), "Errors reported for an empty sequence");
+            let mut canonical = Vec::new();
+            graph.for_each_node(|bytes| canonical.extend_from_slice(bytes));
+            assert_eq!(canonical, expected, "Wrong canonical GFA for an empty sequence in {}", String::from_utf8_lossy(gfa).escape_debug());
+            let records: GraphInt = parse_records(GfaRecords::new(gfa)).unwrap();
+            assert_eq!(stable_name(&records), stable_name(&graph), "Wrong name from records for {}", String::from_utf8_lossy(gfa).escape_debug());
+        }
+    }
+
+    #[test]
+    fn test_skipped_records() {
+        let gfa = b"H\tVN:Z:1.1\n# comment\nS\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n\nP\tx\t1+,2+\t*\nW\tsample\t0\tchr\t0\t6\t>1>2\nP\ty\t1+\t*\nC\t1\t+\t2\t+\t0\t*\nJ\t1\t+\t2\t+\t*\n\x01\n";
+        let (graph, report) = parse_gfa_bytes_with_options::<GraphInt>(gfa, &ParseOptions::default()).unwrap();
+        assert_eq!(graph.statistics(), GraphStatistics { nodes: 2, edges: 1, sequence_len: 6 }, "Wrong statistics");
+        assert!(report.is_clean(), "Skipped records were reported as errors");
+        let expected: BTreeMap<u8, usize> = [(b'#', 1), (b'H', 1), (b'P', 2), (b'W', 1), (b'C', 1), (b'J', 1), (0x01, 1)].into_iter().collect();
+        assert_eq!(report.skipped_records, expected, "Wrong skipped records");
+        assert_eq!(report.skipped_lines(), 8, "Wrong number of skipped lines");
+        assert_eq!(report.skipped_summary(), "\\x01: 1, #: 1, C: 1, H: 1, J: 1, P: 2, W: 1", "Wrong summary");
+
+        let (_, report) = parse_gfa_with_options::<GraphInt, _>(&gfa[..], &ParseOptions::default()).unwrap();
+        assert_eq!(report.skipped_records, expected, "Wrong skipped records from a reader");
+    }
+
+    #[test]
+    fn test_segment_length() {
+        let strict = ParseOptions { strict: true, ..Default::default() };
+        let valid = b"S\t1\tACGT\tLN:i:4\nS\t2\t*\tLN:i:10\nS\t3\tGA\tRC:i:5\n";
+        let result = parse_gfa_bytes_with_options::<GraphInt>(valid, &strict);
+        assert!(result.is_ok(), "Strict mode rejected valid LN:i tags: {}", result.unwrap_err());
+
+        for (gfa, node) in [(&b"S\t1\tACGT\nS\t12\tACG\tLN:i:4\n"[..], "12"), (b"S\tx\tACGT\tLN:i:four\n", "x")] {
+            let result = parse_gfa_bytes_with_options::<GraphStr>(gfa, &strict);
+            let message = result.expect_err("Strict mode accepted an invalid LN:i tag");
+            assert!(message.contains(&format!("segment {}", node)), "Error message does not identify the node: {}", message);
+            let result = parse_gfa_bytes::<GraphStr>(gfa);
+            assert!(result.is_ok(), "LN:i tags were checked without strict mode");
+        }
+    }
+
+    #[test]
+    fn test_name_policy() {
+        let gfa = b"S\ta b\tACGT\nS\tc,d\tGA\nS\t50%\tT\nS\t\xC3\xA4\tC\nL\ta b\t+\tc,d\t-\t*\nL\t50%\t+\t\xC3\xA4\t+\t*\n";
+        let escaped = b"S\ta%20b\tACGT\nS\tc%2Cd\tGA\nS\t50%25\tT\nS\t%C3%A4\tC\nL\ta%20b\t+\tc%2Cd\t-\t*\nL\t50%25\t+\t%C3%A4\t+\t*\n";
+
+        let allow: GraphStr = parse_gfa_bytes(gfa).unwrap();
+        assert!(allow.nodes.contains_key(&b"a b"[..]), "Allow policy changed the names");
+
+        let options = ParseOptions { names: NamePolicy::Reject, ..Default::default() };
+        let result = parse_gfa_bytes_with_options::<GraphStr>(gfa, &options);
+        assert!(result.is_err(), "Reject policy accepted non-portable names");
+        let result = parse_gfa_bytes_with_options::<GraphStr>(escaped, &options);
+        assert!(result.is_ok(), "Reject policy rejected portable names: {}", result.unwrap_err());
+
+        let options = ParseOptions { names: NamePolicy::Escape, ..Default::default() };
+        let (graph, report) = parse_gfa_bytes_with_options::<GraphStr>(gfa, &options).unwrap();
+        assert_eq!(report.escaped_names, 4, "Wrong number of escaped names");
+        let truth: GraphStr = parse_gfa_bytes(escaped).unwrap();
+        let name = hash_portable::<Sha256, _>(&graph);
+        assert_eq!(stable_name(&graph), stable_name(&truth), "Wrong canonical GFA with escaped segment names");
+        assert_ne!(name, stable_name(&truth), "Escaped names have the same name as literal names");
+        assert_ne!(name, stable_name(&allow), "Escaping did not change the name");
+        assert_ne!(name, hash_escaped::<Sha256, _>(&graph), "The two escaping schemes use the same format");
+
+        let mut canonical = Vec::new();
+        write_canonical_portable(&graph, &mut canonical).unwrap();
+        assert!(canonical.starts_with(PORTABLE_FORMAT_HEADER), "Missing header in the escaped canonical GFA");
+        assert_eq!(format!("{:x}", Sha256::digest(&canonical)), name, "Wrong hash for the escaped canonical GFA");
+
+        let filename = TempFile::new(".gfa");
+        std::fs::write(&filename, gfa).unwrap();
+        let from_file = hash_file_portable::<Sha256, _>(&filename, NodeIds::Auto);
+        std::fs::write(&filename, escaped).unwrap();
+        let literal = hash_file_portable::<Sha256, _>(&filename, NodeIds::Auto);
+        std::fs::write(&filename, b"S\tx\tACGT\n").unwrap();
+        let portable = hash_file_portable::<Sha256, _>(&filename, NodeIds::Auto);
+        assert_eq!(from_file, Ok(name.clone()), "Wrong escaped name from a file");
+        assert!(literal.is_ok() && literal != Ok(name), "Literal escape sequences were not escaped again");
+        let expected: GraphStr = parse_gfa_bytes(b"S\tx\tACGT\n").unwrap();
+        assert_eq!(portable, Ok(stable_name(&expected)), "Used the escaped format without escaped names");
+
+        let (graph, report) = parse_gfa_bytes_with_options::<GraphInt>(b"S\t1\tA\nS\t2\tC\nL\t1\t+\t2\t+\t*\n", &options).unwrap();
+        assert_eq!(graph.statistics(), GraphStatistics { nodes: 2, edges: 1, sequence_len: 2 }, "Escape policy changed integer names");
+        assert_eq!(report.escaped_names, 0, "Escaped integer names");
+
+        for (value, policy) in [("allow", NamePolicy::Allow), ("reject", NamePolicy::Reject), ("escape", NamePolicy::Escape)] {
+            assert_eq!(value.parse::<NamePolicy>(), Ok(policy), "Wrong policy for {}", value);
+        }
+        assert!("other".parse::<NamePolicy>().is_err(), "Parsed an invalid policy");
+    }
+
+    // Computes the components using BFS over the neighbors.
+    fn components_bfs<G: Graph>(graph: &G, nodes: &[Vec<u8>]) -> Vec<usize> {
+        let index: HashMap<&[u8], usize> = nodes.iter().enumerate().map(|(i, name)| (name.as_slice(), i)).collect();
+        let mut result: Vec<Option<usize>> = vec![None; nodes.len()];
+        let mut count = 0;
+        for start in 0..nodes.len() {
+            if result[start].is_some() {
+                continue;
+            }
+            result[start] = Some(count);
+            let mut stack = vec![start];
+            while let Some(i) = stack.pop() {
+                for o in [Orientation::Forward, Orientation::Reverse] {
+                    for (name, _) in graph.neighbors(&nodes[i], o).unwrap() {
+                        let j = index[name.as_slice()];
+                        if result[j].is_none() {
+                            result[j] = Some(count);
+                            stack.push(j);
+                        }
+                    }
+                }
+            }
+            count += 1;
+        }
+        result.into_iter().map(Option::unwrap).collect()
+    }
+
+    fn check_components<G: Graph>(graph: &G, name: &str) -> Components {
+        let components = Components::new(graph).unwrap();
+        let nodes: Vec<Vec<u8>> = graph.node_iter().map(|record| record.split(|&c| c == b'\t').nth(1).unwrap().to_vec()).collect();
+        assert_eq!(components.nodes, nodes, "Wrong nodes in {}", name);
+        assert_eq!(components.membership, components_bfs(graph, &nodes), "Wrong components in {}", name);
+        assert_eq!(components.sizes().iter().sum::<usize>(), nodes.len(), "Wrong component sizes in {}", name);
+        for (node, &component) in nodes.iter().zip(components.membership.iter()) {
+            assert_eq!(components.component_of(node), Some(component), "Wrong component for a node in {}", name);
+            assert!(components.members(component).any(|member| member == node.as_slice()), "Node is not a member of its component in {}", name);
+        }
+        assert_eq!(components.component_of(b"missing"), None, "Found a component for a missing node in {}", name);
+        components
+    }
+
+    #[test]
+    fn test_components() {
+        #[cfg(feature = "gbz")]
+        {
+            let gfa = std::fs::read(support::get_test_data("example.gfa")).unwrap();
+            let int_graph: GraphInt = parse_gfa_bytes(&gfa).unwrap();
+            let from_gfa = check_components(&int_graph, "GraphInt");
+            let str_graph: GraphStr = parse_gfa_bytes(&gfa).unwrap();
+            check_components(&str_graph, "GraphStr");
+            let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+            let from_gbz = check_components(&GBZInt { graph: gbz }, "GBZInt");
+            assert_eq!(from_gbz.membership, from_gfa.membership, "Different components from GFA and GBZ");
+
+            let gfa = std::fs::read(support::get_test_data("translation.gfa")).unwrap();
+            let graph: GraphStr = parse_gfa_bytes(&gfa).unwrap();
+            check_components(&graph, "GraphStr from translation.gfa");
+        }
+
+        // Components are numbered by their first node, and edges in either orientation connect nodes.
+        let gfa = b"S\t1\tA\nS\t2\tC\nS\t3\tG\nS\t4\tT\nS\t5\tA\nL\t5\t-\t2\t-\t*\nL\t3\t+\t1\t+\t*\nL\t4\t+\t4\t-\t*\n";
+        let graph: GraphInt = parse_gfa_bytes(gfa).unwrap();
+        let components = check_components(&graph, "GraphInt with reverse edges");
+        assert_eq!(components.membership, vec![0, 1, 0, 2, 1], "Wrong components");
+        assert_eq!(components.sizes(), &[2, 2, 1], "Wrong component sizes");
+
+        let empty = Components::new(&GraphInt::new()).unwrap();
+        assert!(empty.is_empty(), "Components in an empty graph");
+    }
+
+    #[cfg(feature = "gbz")]
+    #[test]
+    fn test_component_digests() {
+        // Each component digest is the stable name of the component.
+        let gfa = std::fs::read(support::get_test_data("translation.gfa")).unwrap();
+        let graph: GraphStr = parse_gfa_bytes(&gfa).unwrap();
+        let components = Components::new(&graph).unwrap();
+        let digests = components.digests::<sha2::Sha256, _>(&graph).unwrap();
+        assert_eq!(digests.len(), components.len(), "Wrong number of digests");
+        let records: Vec<Vec<u8>> = graph.node_iter().collect();
+        for (component, digest) in digests.iter().enumerate() {
+            let mut subgraph: Vec<u8> = Vec::new();
+            for (record, _) in records.iter().zip(components.membership.iter()).filter(|(_, c)| **c == component) {
+                subgraph.extend_from_slice(record);
+            }
+            let subgraph: GraphStr = parse_gfa_bytes(&subgraph).unwrap();
+            assert_eq!(*digest, stable_name(&subgraph), "Wrong digest for component {}", component);
+        }
+
+        // A graph with an extra component contains the original graph.
+        let mut extended = gfa.clone();
+        extended.extend_from_slice(b"S\textra\tACGT\n");
+        let extended: GraphStr = parse_gfa_bytes(&extended).unwrap();
+        let extended_digests = Components::new(&extended).unwrap().digests::<sha2::Sha256, _>(&extended).unwrap();
+        let result = match_components(&digests, &extended_digests);
+        assert!(result.is_complete(), "The original graph is not contained in the extended graph");
+        assert_eq!(result.matched.len(), digests.len(), "Wrong number of matched components");
+        let result = match_components(&extended_digests, &digests);
+        let extra = Components::new(&extended).unwrap().component_of(b"extra");
+        assert_eq!(result.unmatched, extra.into_iter().collect::<Vec<_>>(), "Wrong unmatched components");
+
+        // Using the wrong graph is an error.
+        assert!(components.digests::<sha2::Sha256, _>(&extended).is_err(), "Computed digests with the wrong graph");
+        assert!(components.digests::<sha2::Sha256, _>(&GraphStr::new()).is_err(), "Computed digests with an empty graph");
+    }
+
+    #[test]
+    fn test_orientation() {
+        for o in [Orientation::Forward, Orientation::Reverse] {
+            let symbol = orientation_symbol(o).to_string();
+            assert_eq!(parse_orientation(symbol.as_bytes()), Ok(o), "Wrong orientation for symbol {}", symbol);
+            assert_eq!(orientation_symbol(o.flip()), if o == Orientation::Forward { '-' } else { '+' }, "Wrong symbol for {}", o);
+        }
+        for field in [b"".as_slice(), b"*", b"++", b"F", b"forward"] {
+            assert!(parse_orientation(field).is_err(), "Parsed orientation from {:?}", field);
+        }
+        assert_eq!(Orientation::Forward.to_string(), "forward", "Display does not match gbz");
+    }
+
+    #[test]
+    fn test_split_bytes() {
+        for data in [&b""[..], b"a", b"\t", b"a\tbc\t", b"\t\tabc\td", b"no separators"] {
+            let expected: Vec<&[u8]> = data.split(|&c| c == b'\t').collect();
+            let split: Vec<&[u8]> = split_bytes(data, b'\t').collect();
+            assert_eq!(split, expected, "Wrong split for {}", String::from_utf8_lossy(data));
+        }
+    }
+
+    #[test]
+    fn test_delimiters() {
+        // A sequence with a newline would imitate a graph with two nodes.
+        let two_nodes: GraphStr = parse_gfa_bytes(b"S\t1\tA\nS\t2\tC\n").unwrap();
+        let forged = b"A\nS\t2\tC";
+        let mut graph = GraphStr::new();
+        assert!(graph.add_node(b"1", forged).is_err(), "Accepted a sequence with delimiters");
+        assert!(graph.add_node(b"1\t2", b"A").is_err(), "Accepted a name with delimiters");
+        let mut graph = GraphInt::new();
+        assert!(graph.add_node(b"1", forged).is_err(), "Accepted a sequence with delimiters with integer ids");
+
+        let mut graph = GraphStr::new();
+        graph.add_node(b"1", &escape_field(forged)).unwrap();
+        graph.finalize().unwrap();
+        assert_eq!(node_records(&graph), vec![b"S\t1\tA%0AS%092%09C\n".to_vec()], "Wrong escaped canonical representation");
+        assert_ne!(hash_escaped::<Sha256, _>(&graph), stable_name(&two_nodes), "Escaped graph has the same name");
+        let mut literal = GraphStr::new();
+        literal.add_node(b"1", b"A%0AS%092%09C").unwrap();
+        literal.finalize().unwrap();
+        assert_ne!(hash_escaped::<Sha256, _>(&graph), stable_name(&literal), "Escaped graph has the same name as a literal graph");
+
+        for field in [&b""[..], b"ACGT", b"\t", b"%", b"a\tb\nc%d%%", b"%09"] {
+            let escaped = escape_field(field);
+            assert_eq!(find_delimiter(&escaped), None, "Delimiters after escaping {:?}", field);
+            assert_eq!(unescape_field(&escaped).unwrap().as_ref(), field, "Wrong round trip for {:?}", field);
+        }
+        assert!(matches!(escape_field(b"ACGT"), Cow::Borrowed(_)), "Copied a field without escapes");
+        for field in [&b"%"[..], b"%0", b"%GG", b"a%9"] {
+            assert!(unescape_field(field).is_err(), "Unescaped invalid field {:?}", field);
+        }
+    }
+
+    #[test]
+    fn test_buffered_hasher() {
+        let sizes = [0, 1, 1000, HASH_BUFFER_SIZE - 1001, 1, HASH_BUFFER_SIZE, 17, 2 * HASH_BUFFER_SIZE + 3, 5];
+        let mut buffered = BufferedHasher::<Sha256>::new();
+        let mut unbuffered = Sha256::new();
+        for (i, size) in sizes.iter().enumerate() {
+            let bytes = vec![b'A' + i as u8; *size];
+            buffered.update(&bytes);
+            Digest::update(&mut unbuffered, &bytes);
+        }
+        assert_eq!(buffered.finalize(), format!("{:x}", unbuffered.finalize()), "Buffered hash differs from the unbuffered hash");
+    }
 }
 
 //-----------------------------------------------------------------------------
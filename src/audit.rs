@@ -0,0 +1,183 @@
+//! Name stability audit for the GBZ backend.
+//!
+//! This module is only available with the `gbz` feature.
+//! Stable names of GBZ graphs depend on the behavior of the `gbz` crate.
+//! [`support::edge_is_canonical`] decides which edges are listed after each node, and [`GBZ::node_iter`] decides the order of the nodes.
+//! If a new version of the crate changed either of them, the names of GBZ graphs would change silently, and they would no longer match the names of the same graphs in GFA format.
+//!
+//! The audit pins this behavior against golden vectors:
+//!
+//! * [`edge_vectors`] lists edges with the expected answer from [`support::edge_is_canonical`], which must also agree with [`GraphInt::edge_is_canonical`].
+//! * An embedded GBZ graph (the `example.gbz` test graph of the `gbz` crate) has an expected node order and expected names with each GBZ backend.
+//! * The test vectors in [`crate::vectors`] must still produce the expected canonical representations and names.
+//!
+//! [`audit`] runs all checks, and the `audit` subcommand reports the results.
+//!
+//! # Examples
+//!
+//! ```
+//! use pggname::audit;
+//!
+//! for check in audit::audit() {
+//!     assert_eq!(check.result, Ok(()), "Audit check {} failed", check.name);
+//! }
+//! ```
+
+use crate::{Orientation, vectors};
+use crate::graph::{GBZInt, GBZStr, GraphInt, LazyGBZ};
+
+use gbz::GBZ;
+use gbz::support;
+
+use simple_sds::serialize::Serialize;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// An edge with the expected answer to whether it is in its canonical orientation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EdgeVector {
+    /// Source node and orientation.
+    pub from: (usize, Orientation),
+    /// Destination node and orientation.
+    pub to: (usize, Orientation),
+    /// Is the edge in its canonical orientation?
+    pub canonical: bool,
+}
+
+/// Result of a single audit check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditCheck {
+    /// Short unique identifier for the check.
+    pub name: &'static str,
+    /// What the check verifies.
+    pub description: &'static str,
+    /// [`Ok`] if the check passed, or a description of the first mismatch.
+    pub result: Result<(), String>,
+}
+
+impl AuditCheck {
+    /// Returns `true` if the check passed.
+    pub fn passed(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Expected node order in the embedded GBZ graph.
+pub const EXAMPLE_NODE_ORDER: &[usize] = &[11, 12, 13, 14, 15, 16, 17, 21, 22, 23, 24, 25];
+
+/// Expected name of the embedded GBZ graph.
+///
+/// This is also the name of the same graph in GFA format.
+/// All node identifiers have two digits, so the name is the same with integer and string identifiers.
+pub const EXAMPLE_NAME: &str = "81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970";
+
+/// Returns the golden vectors for [`support::edge_is_canonical`].
+pub fn edge_vectors() -> &'static [EdgeVector] {
+    EDGE_VECTORS
+}
+
+/// Returns the embedded GBZ graph in serialized form.
+pub fn example_gbz() -> &'static [u8] {
+    EXAMPLE_GBZ
+}
+
+/// Runs all audit checks and returns the results in a fixed order.
+pub fn audit() -> Vec<AuditCheck> {
+    vec![
+        AuditCheck {
+            name: "edge-canonicality",
+            description: "Canonical edge orientations in the gbz crate",
+            result: check_edges(EDGE_VECTORS),
+        },
+        AuditCheck {
+            name: "gbz-node-order",
+            description: "Node iteration order in the gbz crate",
+            result: load_example().and_then(|graph| check_node_order(&graph, EXAMPLE_NODE_ORDER)),
+        },
+        AuditCheck {
+            name: "gbz-names",
+            description: "Names of the embedded GBZ graph with each GBZ backend",
+            result: check_names(EXAMPLE_NAME),
+        },
+        AuditCheck {
+            name: "test-vectors",
+            description: "Canonical representations and names of the test vectors",
+            result: vectors::test_vectors().iter().try_for_each(|vector| vector.verify()),
+        },
+    ]
+}
+
+//-----------------------------------------------------------------------------
+
+// Checks the edges against both `support::edge_is_canonical` and `GraphInt::edge_is_canonical`.
+fn check_edges(edges: &[EdgeVector]) -> Result<(), String> {
+    for edge in edges.iter() {
+        let description = || format!("({}, {}) -> ({}, {})", edge.from.0, edge.from.1, edge.to.0, edge.to.1);
+        let gbz = support::edge_is_canonical(edge.from, edge.to);
+        if gbz != edge.canonical {
+            return Err(format!("gbz: edge {} is canonical: {}, expected {}", description(), gbz, edge.canonical));
+        }
+        let pggname = GraphInt::edge_is_canonical(edge.from.0, edge.from.1, edge.to.0, edge.to.1);
+        if pggname != edge.canonical {
+            return Err(format!("GraphInt: edge {} is canonical: {}, expected {}", description(), pggname, edge.canonical));
+        }
+    }
+    Ok(())
+}
+
+fn check_node_order(graph: &GBZ, expected: &[usize]) -> Result<(), String> {
+    let order: Vec<usize> = graph.node_iter().collect();
+    if order != expected {
+        return Err(format!("Node order {:?}, expected {:?}", order, expected));
+    }
+    Ok(())
+}
+
+fn check_names(expected: &str) -> Result<(), String> {
+    let graph = load_example()?;
+    let lazy = LazyGBZ::load(&mut &EXAMPLE_GBZ[..]).map_err(|e| format!("Error loading the embedded GBZ graph: {}", e))?;
+    let names = [
+        ("GBZInt", crate::stable_name(&GBZInt { graph: graph.clone() })),
+        ("LazyGBZ", crate::stable_name(&lazy)),
+        ("GBZStr", crate::stable_name(&GBZStr { graph })),
+    ];
+    for (backend, name) in names {
+        if name != expected {
+            return Err(format!("{}: name {}, expected {}", backend, name, expected));
+        }
+    }
+    Ok(())
+}
+
+fn load_example() -> Result<GBZ, String> {
+    GBZ::load(&mut &EXAMPLE_GBZ[..]).map_err(|e| format!("Error loading the embedded GBZ graph: {}", e))
+}
+
+//-----------------------------------------------------------------------------
+
+const EXAMPLE_GBZ: &[u8] = include_bytes!("audit/example.gbz");
+
+const EDGE_VECTORS: &[EdgeVector] = &[
+    // Edges between different nodes are canonical from the smaller identifier.
+    EdgeVector { from: (1, Orientation::Forward), to: (2, Orientation::Forward), canonical: true },
+    EdgeVector { from: (1, Orientation::Forward), to: (2, Orientation::Reverse), canonical: true },
+    EdgeVector { from: (1, Orientation::Reverse), to: (2, Orientation::Forward), canonical: true },
+    EdgeVector { from: (1, Orientation::Reverse), to: (2, Orientation::Reverse), canonical: true },
+    EdgeVector { from: (2, Orientation::Forward), to: (1, Orientation::Forward), canonical: false },
+    EdgeVector { from: (2, Orientation::Forward), to: (1, Orientation::Reverse), canonical: false },
+    EdgeVector { from: (2, Orientation::Reverse), to: (1, Orientation::Forward), canonical: false },
+    EdgeVector { from: (2, Orientation::Reverse), to: (1, Orientation::Reverse), canonical: false },
+    // Identifiers are compared as integers, not as strings.
+    EdgeVector { from: (9, Orientation::Forward), to: (10, Orientation::Forward), canonical: true },
+    EdgeVector { from: (10, Orientation::Reverse), to: (9, Orientation::Reverse), canonical: false },
+    // Self-loops are canonical unless both orientations are reverse.
+    EdgeVector { from: (5, Orientation::Forward), to: (5, Orientation::Forward), canonical: true },
+    EdgeVector { from: (5, Orientation::Forward), to: (5, Orientation::Reverse), canonical: true },
+    EdgeVector { from: (5, Orientation::Reverse), to: (5, Orientation::Forward), canonical: true },
+    EdgeVector { from: (5, Orientation::Reverse), to: (5, Orientation::Reverse), canonical: false },
+];
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,53 @@
+use super::*;
+
+use crate::algorithms;
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn audit_passes() {
+    let checks = audit();
+    assert_eq!(checks.len(), 4, "Wrong number of checks");
+    for check in checks.iter() {
+        assert!(check.passed(), "Audit check {} failed: {:?}", check.name, check.result);
+    }
+}
+
+#[test]
+fn edge_mismatches() {
+    for edge in edge_vectors() {
+        let flipped = EdgeVector { canonical: !edge.canonical, ..*edge };
+        assert!(check_edges(&[flipped]).is_err(), "Accepted a wrong answer for {:?}", edge);
+    }
+
+    // Each edge and its reverse are in canonical orientation, except for self-loops that are their own reverses.
+    for edge in edge_vectors() {
+        let reverse = ((edge.to.0, edge.to.1.flip()), (edge.from.0, edge.from.1.flip()));
+        if reverse == (edge.from, edge.to) {
+            assert!(edge.canonical, "Self-loop {:?} that is its own reverse is not canonical", edge);
+        } else {
+            let canonical = edge_vectors().iter().find(|other| (other.from, other.to) == reverse).map(|other| other.canonical);
+            if let Some(canonical) = canonical {
+                assert_ne!(canonical, edge.canonical, "Edge {:?} and its reverse have the same canonicality", edge);
+            }
+        }
+    }
+}
+
+#[test]
+fn embedded_graph() {
+    let filename = support::get_test_data("example.gbz");
+    assert_eq!(example_gbz(), std::fs::read(&filename).unwrap(), "Embedded graph differs from the test data");
+
+    let gfa = std::fs::read(support::get_test_data("example.gfa")).unwrap();
+    let graph: GraphInt = algorithms::parse_gfa_bytes(&gfa).unwrap();
+    assert_eq!(crate::stable_name(&graph), EXAMPLE_NAME, "Wrong name for the graph in GFA format");
+
+    let graph = load_example().unwrap();
+    assert!(check_node_order(&graph, EXAMPLE_NODE_ORDER).is_ok(), "Wrong node order");
+    assert!(check_node_order(&graph, &EXAMPLE_NODE_ORDER[1..]).is_err(), "Accepted a wrong node order");
+    assert!(check_names(EXAMPLE_NAME).is_ok(), "Wrong names");
+    assert!(check_names(vectors::test_vectors()[0].string.name).is_err(), "Accepted a wrong name");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,57 @@
+//! The `audit` subcommand: checking that this build computes the same names as before.
+//!
+//! Runs the checks in [`pggname::audit`], which pin the behavior of the `gbz` crate that GBZ names depend on, as well as the canonical test vectors.
+//! Each check is reported on its own line.
+//! The exit status is 1 if any check fails, for example after upgrading the `gbz` crate to a version that changes canonical edge orientations or node order.
+
+use getopts::Options;
+
+use pggname::{audit, vectors};
+
+use std::process;
+
+use tracing::error;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    Config::new(args)?;
+
+    println!("pggname {}, canonical format version {}", env!("CARGO_PKG_VERSION"), vectors::CANONICAL_FORMAT_VERSION);
+    let checks = audit::audit();
+    for check in checks.iter() {
+        match &check.result {
+            Ok(()) => println!("ok      {:<18}  {}", check.name, check.description),
+            Err(message) => println!("FAILED  {:<18}  {}: {}", check.name, check.description, message),
+        }
+    }
+
+    let failed = checks.iter().filter(|check| !check.passed()).count();
+    if failed > 0 {
+        error!("{} of {} audit checks failed; names computed with this build may differ from stable names", failed, checks.len());
+        process::exit(1);
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config;
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} audit", &program);
+
+        let opts = Options::new();
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if !matches.free.is_empty() {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        Ok(Config)
+    }
+}
+
+//-----------------------------------------------------------------------------
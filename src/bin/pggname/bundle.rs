@@ -0,0 +1,82 @@
+//! The `bundle` subcommand: naming the graph in an index bundle.
+//!
+//! This subcommand is only available with the `bundle` feature.
+//! Reads a directory or a tarball containing a graph and indexes built from it, locates the graph with [`Bundle::graph_member`], and prints its name without unpacking the bundle.
+//! With `--member`, the graph is chosen explicitly.
+//! With `--expect`, the exit status is 1 if the name differs from the expected name, which can also be a short name or a URI.
+//! The other components of the bundle are listed as well.
+
+use getopts::Options;
+
+use pggname::GraphName;
+use pggname::bundle::{self, Bundle};
+
+use std::process;
+
+use tracing::error;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    if !bundle::is_bundle(&config.bundle) {
+        return Err(format!("{} is not a directory or a tarball", config.bundle));
+    }
+    let bundle = Bundle::open(&config.bundle)?;
+    let member = match &config.member {
+        Some(member) => member.as_str(),
+        None => bundle.graph_member()?,
+    };
+    let name = bundle.stable_name(member)?;
+
+    println!("{}  {}:{}", name, config.bundle, member);
+    for other in bundle.members().iter().filter(|other| *other != member) {
+        println!("  {}", other);
+    }
+
+    if let Some(expected) = &config.expected {
+        let matches = if let Ok(expected) = GraphName::parse_uri(expected) {
+            expected == name
+        } else {
+            !expected.is_empty() && name.starts_with(expected.as_str())
+        };
+        if !matches {
+            error!("{}: the graph is {}, not {}", config.bundle, name, expected);
+            process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    bundle: String,
+    member: Option<String>,
+    expected: Option<String>,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} bundle [options] bundle(/|.tar|.tar.gz)", &program);
+
+        let mut opts = Options::new();
+        opts.optopt("", "member", "name the graph in this file of the bundle", "FILE");
+        opts.optopt("", "expect", "fail unless the graph has this name, short name, or URI", "NAME");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.free.len() != 1 {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let bundle = matches.free[0].clone();
+        let member = matches.opt_str("member");
+        let expected = matches.opt_str("expect");
+
+        Ok(Config { bundle, member, expected })
+    }
+}
+
+//-----------------------------------------------------------------------------
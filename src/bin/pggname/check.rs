@@ -0,0 +1,250 @@
+//! The `check` subcommand: verifying graph names listed in manifest files.
+//!
+//! A manifest consists of lines `name  filename`, as printed by the default command, or self-identifying records printed with `--long-format`.
+//! Records with canonical format versions 1, 2 (names and sequences escaped with `algorithms::escape_field`), 3 (jumps), and 4 (segment names escaped with `--names escape`) are supported, and the names must use SHA-256.
+//! Lines `name  filename` are treated as version 1.
+//! Empty lines and lines starting with `#` are ignored.
+//! The results can be reported as plain text, TAP, or JUnit XML.
+
+use getopts::Options;
+
+use pggname::{jumps, vectors};
+use pggname::algorithms::{self, NodeIds};
+use pggname::record::NameRecord;
+
+use sha2::Sha256;
+
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader};
+use std::process;
+use std::time::Instant;
+
+use tracing::warn;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    let mut results = Vec::new();
+    for manifest in config.manifests.iter() {
+        let entries = read_manifest(manifest)?;
+        for record in entries {
+            let start = Instant::now();
+            let outcome = match compute_name(&record, config.node_ids) {
+                Ok(name) if name == record.name => Outcome::Ok,
+                Ok(name) => Outcome::Mismatch(name),
+                Err(message) => Outcome::Error(message),
+            };
+            let seconds = start.elapsed().as_secs_f64();
+            results.push(CheckResult { input_file: record.path, expected: record.name, outcome, seconds });
+        }
+    }
+
+    match config.format {
+        Format::Text => print_text(&results),
+        Format::Tap => print_tap(&results),
+        Format::JUnit => print_junit(&results),
+    }
+
+    if results.iter().any(|result| result.outcome != Outcome::Ok) {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Text,
+    Tap,
+    JUnit,
+}
+
+struct Config {
+    manifests: Vec<String>,
+    node_ids: NodeIds,
+    format: Format,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} check [options] manifest1 [manifest2 ...]", &program);
+
+        let mut opts = Options::new();
+        opts.optflag("i", "integer-ids", "use integer node identifiers");
+        opts.optflag("s", "string-ids", "use string node identifiers");
+        opts.optopt("f", "format", "output format: text (default), tap, junit", "FORMAT");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+
+        let manifests = if !matches.free.is_empty() {
+            matches.free.clone()
+        } else {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        };
+        let node_ids = if matches.opt_present("i") {
+            NodeIds::Integer
+        } else if matches.opt_present("s") {
+            NodeIds::String
+        } else {
+            NodeIds::Auto
+        };
+        let format = match matches.opt_str("f").as_deref() {
+            None | Some("text") => Format::Text,
+            Some("tap") => Format::Tap,
+            Some("junit") => Format::JUnit,
+            Some(other) => return Err(format!("Unknown output format: {}", other)),
+        };
+
+        Ok(Config { manifests, node_ids, format })
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Outcome {
+    Ok,
+    Mismatch(String),
+    Error(String),
+}
+
+struct CheckResult {
+    input_file: String,
+    expected: String,
+    outcome: Outcome,
+    seconds: f64,
+}
+
+impl CheckResult {
+    fn failure_message(&self) -> Option<String> {
+        match &self.outcome {
+            Outcome::Ok => None,
+            Outcome::Mismatch(name) => Some(format!("expected name {}, computed {}", self.expected, name)),
+            Outcome::Error(message) => Some(message.clone()),
+        }
+    }
+}
+
+// Computes the name of the graph in the format specified by the record.
+fn compute_name(record: &NameRecord, node_ids: NodeIds) -> Result<String, String> {
+    if record.algorithm != NameRecord::ALGORITHM {
+        return Err(format!("Unsupported hash algorithm: {}", record.algorithm));
+    }
+    match record.version {
+        vectors::CANONICAL_FORMAT_VERSION => algorithms::hash_file::<Sha256, _>(&record.path, node_ids),
+        vectors::ESCAPED_FORMAT_VERSION => algorithms::hash_file_escaped::<Sha256, _>(&record.path, node_ids),
+        vectors::JUMP_FORMAT_VERSION => jumps::hash_file::<Sha256, _>(&record.path, node_ids),
+        vectors::PORTABLE_FORMAT_VERSION => algorithms::hash_file_portable::<Sha256, _>(&record.path, node_ids),
+        version => Err(format!("Unsupported canonical format version: {}", version)),
+    }
+}
+
+/// Returns the entries of the manifest as name records.
+///
+/// Lines `name  filename` become records with canonical format version 1.
+pub fn read_manifest(manifest: &str) -> Result<Vec<NameRecord>, String> {
+    let file = OpenOptions::new().read(true).open(manifest)
+        .map_err(|e| format!("Error opening manifest {}: {}", manifest, e))?;
+    let reader = BufReader::new(file);
+
+    let mut result = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Error reading manifest {} line {}: {}", manifest, i + 1, e))?;
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if NameRecord::is_record(&line) {
+            let record = line.parse::<NameRecord>().map_err(|e| format!("Error parsing manifest {} line {}: {}", manifest, i + 1, e))?;
+            result.push(record);
+            continue;
+        }
+        let (name, filename) = line.split_once(char::is_whitespace)
+            .ok_or(format!("Error parsing manifest {} line {}: expected a name and a filename", manifest, i + 1))?;
+        let filename = filename.trim_start();
+        if name.is_empty() || filename.is_empty() || !name.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Error parsing manifest {} line {}: invalid entry", manifest, i + 1));
+        }
+        result.push(NameRecord::new(vectors::CANONICAL_FORMAT_VERSION, &name.to_ascii_lowercase(), filename));
+    }
+
+    Ok(result)
+}
+
+//-----------------------------------------------------------------------------
+
+fn print_text(results: &[CheckResult]) {
+    let mut failures = 0;
+    for result in results.iter() {
+        match &result.outcome {
+            Outcome::Ok => println!("{}: OK", result.input_file),
+            Outcome::Mismatch(_) => {
+                println!("{}: FAILED", result.input_file);
+                failures += 1;
+            }
+            Outcome::Error(message) => {
+                println!("{}: FAILED ({})", result.input_file, message);
+                failures += 1;
+            }
+        }
+    }
+    if failures > 0 {
+        warn!("{} of {} computed names did NOT match", failures, results.len());
+    }
+}
+
+fn print_tap(results: &[CheckResult]) {
+    println!("TAP version 13");
+    println!("1..{}", results.len());
+    for (i, result) in results.iter().enumerate() {
+        if let Some(message) = result.failure_message() {
+            println!("not ok {} - {}", i + 1, result.input_file);
+            println!("  ---");
+            println!("  message: '{}'", message.replace('\'', "''"));
+            println!("  ...");
+        } else {
+            println!("ok {} - {}", i + 1, result.input_file);
+        }
+    }
+}
+
+fn print_junit(results: &[CheckResult]) {
+    let failures = results.iter().filter(|result| result.outcome != Outcome::Ok).count();
+    let total_time: f64 = results.iter().map(|result| result.seconds).sum();
+
+    println!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    println!("<testsuites name=\"pggname\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">", results.len(), failures, total_time);
+    println!("  <testsuite name=\"pggname check\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">", results.len(), failures, total_time);
+    for result in results.iter() {
+        let name = xml_escape(&result.input_file);
+        if let Some(message) = result.failure_message() {
+            println!("    <testcase classname=\"pggname.check\" name=\"{}\" time=\"{:.3}\">", name, result.seconds);
+            println!("      <failure message=\"{}\"/>", xml_escape(&message));
+            println!("    </testcase>");
+        } else {
+            println!("    <testcase classname=\"pggname.check\" name=\"{}\" time=\"{:.3}\"/>", name, result.seconds);
+        }
+    }
+    println!("  </testsuite>");
+    println!("</testsuites>");
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+//-----------------------------------------------------------------------------
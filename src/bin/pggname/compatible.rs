@@ -0,0 +1,128 @@
+//! The `compatible` subcommand: checking whether GAF alignments can be used with a target graph.
+//!
+//! Reads the graph name and relationships from the GAF header and computes the name of the target graph.
+//! Relationships stored in the target graph (GBZ tags or GFA header) are also used.
+//! Reports whether the alignments are to the target graph, to a subgraph of it, or to a graph that can be translated to it, and prints the chain of relationships as evidence.
+//! The exit status is 1 if the alignments are not compatible with the target graph.
+
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::{Graph, GraphName, algorithms};
+use pggname::graph::{GraphInt, GraphStr, GBZInt, GBZStr};
+
+use simple_sds::serialize;
+
+use std::io::BufRead;
+use std::process;
+
+use tracing::warn;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    let alignments = read_header(&config.gaf_file, |line| line.starts_with('@'))?;
+    if !alignments.has_name() {
+        return Err(format!("No graph name (@RN) in the header of {}", config.gaf_file));
+    }
+    let target = target_name(&config)?;
+
+    let compatibility = alignments.compatibility(&target);
+    println!("Alignments: {} ({})", config.gaf_file, alignments.name().unwrap());
+    println!("Target: {} ({})", config.graph_file, target.name().unwrap());
+    println!("Verdict: {}", compatibility);
+    println!();
+    print!("{}", alignments.describe_relationship(
+        &target,
+        &format!("the alignments in {}", config.gaf_file),
+        &format!("the target graph {}", config.graph_file)
+    ));
+
+    if !compatibility.is_compatible() {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+// Returns the computed name of the target graph with the relationships stored in the graph.
+fn target_name(config: &Config) -> Result<GraphName, String> {
+    let (computed, stored) = if GBZ::is_gbz(&config.graph_file) {
+        let graph: GBZ = serialize::load_from(&config.graph_file)
+            .map_err(|e| format!("Error loading GBZ file {}: {}", config.graph_file, e))?;
+        let stored = GraphName::from_gbz(&graph);
+        let computed = if config.string_ids {
+            pggname::stable_name(&GBZStr { graph })
+        } else {
+            pggname::stable_name(&GBZInt { graph })
+        };
+        (computed, stored)
+    } else {
+        let stored = read_header(&config.graph_file, |line| line == "H" || line.starts_with("H\t"))?;
+        let computed = if config.string_ids {
+            gfa_name::<GraphStr>(&config.graph_file)?
+        } else {
+            gfa_name::<GraphInt>(&config.graph_file).or_else(|_| gfa_name::<GraphStr>(&config.graph_file))?
+        };
+        (computed, stored)
+    };
+
+    if let Some(name) = stored.name() && *name != computed {
+        warn!("{}: stale graph name {} in the metadata, computed {}", config.graph_file, name, computed);
+    }
+    let mut result = GraphName::new(computed);
+    result.add_relationships(&stored);
+    Ok(result)
+}
+
+fn gfa_name<G: Graph>(filename: &str) -> Result<String, String> {
+    let graph: G = algorithms::parse_gfa(algorithms::open_gfa(filename)?)?;
+    Ok(pggname::stable_name(&graph))
+}
+
+// Parses the leading header lines of the file.
+fn read_header<F: Fn(&str) -> bool>(filename: &str, is_header: F) -> Result<GraphName, String> {
+    let reader = algorithms::open_gfa(filename)?;
+    let mut header: Vec<String> = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Error reading {} line {}: {}", filename, i + 1, e))?;
+        if !is_header(&line) {
+            break;
+        }
+        header.push(line);
+    }
+    GraphName::from_header_lines(&header).map_err(|e| format!("Error parsing the header of {}: {}", filename, e))
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    gaf_file: String,
+    graph_file: String,
+    string_ids: bool,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} compatible [options] reads.gaf[.gz] target.(gbz|gfa[.gz])", &program);
+
+        let mut opts = Options::new();
+        opts.optflag("s", "string-ids", "use string node identifiers for the target (default: integer identifiers if possible)");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.free.len() != 2 {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let gaf_file = matches.free[0].clone();
+        let graph_file = matches.free[1].clone();
+        let string_ids = matches.opt_present("s");
+
+        Ok(Config { gaf_file, graph_file, string_ids })
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,169 @@
+//! The `crosscheck` subcommand: comparing names computed with different graph backends.
+//!
+//! GFA files are hashed as `GraphInt` and `GraphStr`, and GBZ files as `GBZInt` and `GBZStr`.
+//! With the `gfa-rs` feature, GFA files are also parsed with the alternative backend in [`pggname::gfa_rs`].
+//! The names are grouped by value, and notes explain common reasons for disagreement.
+//! The exit status is 1 if the computed names do not all agree.
+
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::algorithms;
+use pggname::graph::{GraphInt, GraphStr, GBZInt, GBZStr};
+
+use simple_sds::serialize;
+
+use std::process;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    let mut results: Vec<BackendResult> = Vec::new();
+    let mut notes: Vec<String> = Vec::new();
+    for input_file in config.input_files.iter() {
+        if GBZ::is_gbz(input_file) {
+            check_gbz(input_file, &mut results, &mut notes)?;
+        } else {
+            check_gfa(input_file, &mut results, &mut notes)?;
+        }
+    }
+
+    // Assign a label to each distinct name in order of first appearance.
+    let mut names: Vec<&str> = Vec::new();
+    for result in results.iter() {
+        if let Ok(name) = &result.name && !names.contains(&name.as_str()) {
+            names.push(name);
+        }
+    }
+
+    for result in results.iter() {
+        match &result.name {
+            Ok(name) => {
+                let label = names.iter().position(|n| n == name).unwrap();
+                println!("{:<8}  {}  [{}]  {}", result.backend, name, group_label(label), result.input_file);
+            }
+            Err(message) => println!("{:<8}  n/a ({})  {}", result.backend, message, result.input_file),
+        }
+    }
+    println!();
+    if names.len() <= 1 {
+        println!("All computed names agree");
+    } else {
+        println!("Found {} distinct names", names.len());
+    }
+    for note in notes.iter() {
+        println!("Note: {}", note);
+    }
+
+    if names.len() > 1 {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    input_files: Vec<String>,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} crosscheck graph1 [graph2 ...]", &program);
+
+        let opts = Options::new();
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        let input_files = if !matches.free.is_empty() {
+            matches.free.clone()
+        } else {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        };
+
+        Ok(Config { input_files })
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+struct BackendResult {
+    backend: &'static str,
+    input_file: String,
+    name: Result<String, String>,
+}
+
+fn group_label(index: usize) -> String {
+    let mut label = String::new();
+    let mut index = index + 1;
+    while index > 0 {
+        index -= 1;
+        label.insert(0, (b'A' + (index % 26) as u8) as char);
+        index /= 26;
+    }
+    label
+}
+
+fn check_gfa(input_file: &str, results: &mut Vec<BackendResult>, notes: &mut Vec<String>) -> Result<(), String> {
+    let int_name = algorithms::parse_gfa::<GraphInt, _>(algorithms::open_gfa(input_file)?)
+        .map(|graph| pggname::stable_name(&graph));
+    let str_name = algorithms::parse_gfa::<GraphStr, _>(algorithms::open_gfa(input_file)?)
+        .map(|graph| pggname::stable_name(&graph));
+
+    match (&int_name, &str_name) {
+        (Err(_), Ok(_)) => notes.push(format!("{}: node identifiers are not integers, so only string identifiers apply", input_file)),
+        (Ok(int_name), Ok(str_name)) if int_name != str_name => notes.push(format!(
+            "{}: integer identifiers sort nodes numerically and string identifiers lexicographically, which changes the canonical order",
+            input_file
+        )),
+        _ => {}
+    }
+
+    results.push(BackendResult { backend: "GraphInt", input_file: String::from(input_file), name: int_name });
+    results.push(BackendResult { backend: "GraphStr", input_file: String::from(input_file), name: str_name });
+
+    #[cfg(feature = "gfa-rs")]
+    {
+        use pggname::gfa_rs;
+        let int_name = gfa_rs::parse_gfa::<GraphInt, _>(algorithms::open_gfa(input_file)?)
+            .map(|graph| pggname::stable_name(&graph));
+        let str_name = gfa_rs::parse_gfa::<GraphStr, _>(algorithms::open_gfa(input_file)?)
+            .map(|graph| pggname::stable_name(&graph));
+        if results.iter().rev().take(2).zip([&str_name, &int_name]).any(|(native, alt)| native.name.is_ok() && alt.is_ok() && native.name != *alt) {
+            notes.push(format!(
+                "{}: the gfa crate replaces segment sequences with their first valid substring, which changes the name if a sequence contains unexpected characters",
+                input_file
+            ));
+        }
+        results.push(BackendResult { backend: "GfaRsInt", input_file: String::from(input_file), name: int_name });
+        results.push(BackendResult { backend: "GfaRsStr", input_file: String::from(input_file), name: str_name });
+    }
+
+    Ok(())
+}
+
+fn check_gbz(input_file: &str, results: &mut Vec<BackendResult>, notes: &mut Vec<String>) -> Result<(), String> {
+    let graph: GBZ = serialize::load_from(input_file)
+        .map_err(|e| format!("Error loading GBZ file {}: {}", input_file, e))?;
+    if graph.has_translation() {
+        notes.push(format!(
+            "{}: the GBZ graph has a node-to-segment translation; GBZInt uses node identifiers and GBZStr uses segment names",
+            input_file
+        ));
+    }
+
+    let int_graph = GBZInt { graph };
+    results.push(BackendResult {
+        backend: "GBZInt", input_file: String::from(input_file), name: Ok(pggname::stable_name(&int_graph)),
+    });
+    let str_graph = GBZStr { graph: int_graph.graph };
+    results.push(BackendResult {
+        backend: "GBZStr", input_file: String::from(input_file), name: Ok(pggname::stable_name(&str_graph)),
+    });
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,105 @@
+//! The `export` subcommand: writing canonical node and edge records as Arrow or Parquet tables.
+//!
+//! This subcommand is only available with the `arrow` feature.
+//! The tables are written to `PREFIX.nodes.EXT` and `PREFIX.edges.EXT`, where the extension is `arrow` or `parquet`.
+//! See [`pggname::export`] for the schemas.
+
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::{Graph, algorithms, export};
+use pggname::graph::{GBZInt, GraphInt, GraphStr};
+
+use simple_sds::serialize;
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::process;
+
+use tracing::info;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    if GBZ::is_gbz(&config.input_file) {
+        let graph: GBZ = serialize::load_from(&config.input_file)
+            .map_err(|e| format!("Error loading GBZ file {}: {}", config.input_file, e))?;
+        write_tables(&GBZInt { graph }, &config)
+    } else if let Ok(graph) = algorithms::parse_gfa::<GraphInt, _>(algorithms::open_gfa(&config.input_file)?) {
+        write_tables(&graph, &config)
+    } else {
+        let graph: GraphStr = algorithms::parse_gfa(algorithms::open_gfa(&config.input_file)?)?;
+        write_tables(&graph, &config)
+    }
+}
+
+fn write_tables<G: Graph>(graph: &G, config: &Config) -> Result<(), String> {
+    let (nodes, edges) = export::record_batches(graph)?;
+    for (table, batch) in [("nodes", &nodes), ("edges", &edges)] {
+        let filename = format!("{}.{}.{}", config.prefix, table, config.format.extension());
+        let file = File::create(&filename).map_err(|e| format!("Error creating {}: {}", filename, e))?;
+        let writer = BufWriter::new(file);
+        match config.format {
+            Format::Arrow => export::write_arrow(batch, writer)?,
+            Format::Parquet => export::write_parquet(batch, writer)?,
+        }
+        info!("Wrote {} {} to {}", batch.num_rows(), table, filename);
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Arrow,
+    Parquet,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Arrow => "arrow",
+            Format::Parquet => "parquet",
+        }
+    }
+}
+
+struct Config {
+    input_file: String,
+    prefix: String,
+    format: Format,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} export [options] --prefix PREFIX graph", &program);
+
+        let mut opts = Options::new();
+        opts.optopt("", "prefix", "prefix for the output files (required)", "PREFIX");
+        opts.optopt("", "format", "output format: parquet (default), arrow", "FORMAT");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+
+        let prefix = match matches.opt_str("prefix") {
+            Some(prefix) if matches.free.len() == 1 => prefix,
+            _ => {
+                eprintln!("{}", opts.usage(&header));
+                process::exit(1);
+            }
+        };
+        let input_file = matches.free[0].clone();
+        let format = match matches.opt_str("format").as_deref() {
+            None | Some("parquet") => Format::Parquet,
+            Some("arrow") => Format::Arrow,
+            Some(format) => return Err(format!("Invalid output format: {}", format)),
+        };
+
+        Ok(Config { input_file, prefix, format })
+    }
+}
+
+//-----------------------------------------------------------------------------
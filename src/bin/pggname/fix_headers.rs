@@ -0,0 +1,132 @@
+//! The `fix-headers` subcommand: detecting and fixing stale graph names in GFA headers.
+//!
+//! Recomputes the name of the graph and compares it with the `NM:Z:` header field.
+//! Unless `--check` is given, writes the GFA file to stdout with the header updated to the computed name.
+//! Existing relationships are kept, and `--derived-from` records a relationship between the new name and the stale name.
+//! The fields are merged onto an existing header line, and `--gfa-version` declares the target GFA version if the header does not.
+
+use getopts::Options;
+
+use pggname::GraphName;
+use pggname::graph::{GraphInt, GraphStr};
+use pggname::algorithms::{self, NodeIds};
+use pggname::name::{GfaVersion, RelationshipKind, rewrite_gfa_headers};
+
+use sha2::Sha256;
+
+use std::io::{self, BufWriter, Read};
+use std::process;
+
+use tracing::{info, warn};
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    // The input is read once, as the header can only be written after the name has been computed.
+    let mut gfa: Vec<u8> = Vec::new();
+    algorithms::open_gfa(&config.input_file)?.read_to_end(&mut gfa)
+        .map_err(|e| format!("Error reading {}: {}", config.input_file, e))?;
+    let computed = hash_gfa(&gfa, config.node_ids).map_err(|e| format!("{}: {}", config.input_file, e))?;
+
+    let update = |existing: &GraphName| -> Result<GraphName, String> {
+        let stale = match existing.name() {
+            Some(name) if *name == computed => {
+                info!("{}: graph name {} is up to date", config.input_file, computed);
+                None
+            }
+            Some(name) => {
+                warn!("{}: stale graph name {} in the header, computed {}", config.input_file, name, computed);
+                Some(name)
+            }
+            None => {
+                warn!("{}: no graph name in the header, computed {}", config.input_file, computed);
+                None
+            }
+        };
+        let mut name = GraphName::new(computed.clone());
+        name.add_relationships(existing);
+        if let (Some(old), Some(kind)) = (stale, config.derived_from) {
+            name.add_relationship(kind, &computed, old);
+        }
+        Ok(name)
+    };
+
+    if config.check_only {
+        let existing = rewrite_gfa_headers(&gfa[..], io::sink(), update, None)
+            .map_err(|e| format!("Error reading the header of {}: {}", config.input_file, e))?;
+        if existing.name() != Some(&computed) {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let writer = BufWriter::new(io::stdout().lock());
+    rewrite_gfa_headers(&gfa[..], writer, update, config.gfa_version)
+        .map_err(|e| format!("Error updating the header of {}: {}", config.input_file, e))?;
+
+    Ok(())
+}
+
+// Computes the name of the graph in the GFA file, choosing the node identifiers as in `algorithms::hash_file`.
+fn hash_gfa(gfa: &[u8], node_ids: NodeIds) -> Result<String, String> {
+    let integer = match node_ids {
+        NodeIds::Integer => Some(algorithms::parse_gfa_bytes::<GraphInt>(gfa)?),
+        NodeIds::String => None,
+        NodeIds::Auto => algorithms::parse_gfa_bytes::<GraphInt>(gfa).ok(),
+    };
+    match integer {
+        Some(graph) => Ok(algorithms::hash::<Sha256, _>(&graph)),
+        None => Ok(algorithms::hash::<Sha256, _>(&algorithms::parse_gfa_bytes::<GraphStr>(gfa)?)),
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    input_file: String,
+    node_ids: NodeIds,
+    check_only: bool,
+    derived_from: Option<RelationshipKind>,
+    gfa_version: Option<GfaVersion>,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} fix-headers [options] graph.gfa[.gz]", &program);
+
+        let mut opts = Options::new();
+        opts.optflag("i", "integer-ids", "use integer node identifiers");
+        opts.optflag("s", "string-ids", "use string node identifiers");
+        opts.optflag("", "check", "only report stale names; exit with status 1 if the name is missing or stale");
+        opts.optopt(
+            "", "derived-from",
+            "record the stale name as a parent: subgraph (the graph is a subgraph of it) or translation (the graph translates to it)",
+            "RELATION"
+        );
+        opts.optopt("", "gfa-version", "target GFA version: 1.0, 1.1, 1.2", "VERSION");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.free.len() != 1 {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let input_file = matches.free[0].clone();
+        let node_ids = if matches.opt_present("i") {
+            NodeIds::Integer
+        } else if matches.opt_present("s") {
+            NodeIds::String
+        } else {
+            NodeIds::Auto
+        };
+        let check_only = matches.opt_present("check");
+        let derived_from = matches.opt_str("derived-from").map(|relation| relation.parse()).transpose()?;
+        let gfa_version = matches.opt_str("gfa-version").map(|version| version.parse()).transpose()?;
+
+        Ok(Config { input_file, node_ids, check_only, derived_from, gfa_version })
+    }
+}
+
+//-----------------------------------------------------------------------------
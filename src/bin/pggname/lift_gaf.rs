@@ -0,0 +1,114 @@
+//! The `lift-gaf` subcommand: rewriting GAF alignments across a translation.
+//!
+//! Reads alignments to graph A, rewrites the alignment paths using a translation map from A to C, and writes alignments to graph C to stdout.
+//! The `@RN` header line is updated to name graph C, and a `@TL` line records the translation.
+//! With `--tag-records`, each record is also tagged with the short name of graph C as `rn:Z:<short name>`.
+//!
+//! The graph names can also be aliases or name prefixes (see [`crate::resolve`]).
+//! They are resolved using the names in the registry and in the manifests given with `--manifest`.
+//! Names that cannot be resolved are used as they are, while an ambiguous alias or prefix is an error.
+
+use crate::resolve::NameResolver;
+
+use getopts::Options;
+
+use pggname::algorithms;
+use pggname::gaf::{self, GafLifter};
+use pggname::translation::TranslationMap;
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    let map_file = File::open(&config.map_file)
+        .map_err(|e| format!("Error opening translation map {}: {}", config.map_file, e))?;
+    let map = TranslationMap::read(BufReader::new(map_file))?;
+    let lifter = GafLifter::new(&map);
+
+    let reader = algorithms::open_gfa(&config.input_file)?;
+    let mut writer = BufWriter::new(io::stdout().lock());
+    let mut header: Vec<String> = Vec::new();
+    let mut in_header = true;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Error reading {} line {}: {}", config.input_file, i + 1, e))?;
+        if in_header && line.starts_with('@') {
+            header.push(line);
+            continue;
+        }
+        if in_header {
+            write_header(&mut writer, &header, &config)?;
+            in_header = false;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        let mut lifted = lifter.lift_record(&line)
+            .map_err(|e| format!("Error lifting {} line {}: {}", config.input_file, i + 1, e))?;
+        if config.tag_records {
+            lifted = gaf::tag_record(&lifted, &config.to);
+        }
+        writeln!(writer, "{}", lifted).map_err(|e| e.to_string())?;
+    }
+    if in_header {
+        write_header(&mut writer, &header, &config)?;
+    }
+    writer.flush().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn write_header<W: Write>(writer: &mut W, header: &[String], config: &Config) -> Result<(), String> {
+    let lines = gaf::lift_header_lines(header, &config.from, &config.to)?;
+    for line in lines {
+        writeln!(writer, "{}", line).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    input_file: String,
+    from: String,
+    to: String,
+    map_file: String,
+    tag_records: bool,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} lift-gaf [options] --from A --to C --map map.trans reads.gaf", &program);
+
+        let mut opts = Options::new();
+        opts.optopt("", "from", "name of the source graph (required)", "NAME");
+        opts.optopt("", "to", "name of the target graph (required)", "NAME");
+        opts.optopt("", "map", "translation map from the source to the target (required)", "FILE");
+        opts.optflag("", "tag-records", "tag each record with the short name of the target graph (rn:Z:)");
+        opts.optopt("r", "registry", "resolve graph names using the registry (default: pggname.registry)", "FILE");
+        opts.optmulti("", "manifest", "resolve graph name aliases using the manifest (may repeat)", "FILE");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+
+        let (from, to, map_file) = match (matches.opt_str("from"), matches.opt_str("to"), matches.opt_str("map")) {
+            (Some(from), Some(to), Some(map_file)) if matches.free.len() == 1 => (from, to, map_file),
+            _ => {
+                eprintln!("{}", opts.usage(&header));
+                process::exit(1);
+            }
+        };
+        let registry = matches.opt_str("registry").unwrap_or(String::from("pggname.registry"));
+        let resolver = NameResolver::new(Some(&registry), &matches.opt_strs("manifest"))?;
+        let (from, to) = (resolver.resolve_or_literal(&from)?, resolver.resolve_or_literal(&to)?);
+        let input_file = matches.free[0].clone();
+        let tag_records = matches.opt_present("tag-records");
+
+        Ok(Config { input_file, from, to, map_file, tag_records })
+    }
+}
+
+//-----------------------------------------------------------------------------
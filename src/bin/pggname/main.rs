@@ -0,0 +1,880 @@
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::{Graph, GraphName, paths, translation, vectors};
+use pggname::jumps::{JumpPolicy, Jumps};
+use pggname::provenance::{Provenance, ProvenanceInput};
+use pggname::record::NameRecord;
+use pggname::seqcol::{self, SequenceCollection};
+use pggname::sorted::{self, Checkpoint, SortedGfaHasher};
+use pggname::graph::{EdgeStatistics, GraphInt, GraphStr, GBZInt, GBZStr};
+use pggname::algorithms::{self, NamePolicy, NodeIds, ParseOptions, ParseReport};
+
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512_224, Sha512_256, Sha512};
+use sha2::digest;
+
+use simple_sds::serialize;
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::time::Instant;
+use std::{env, io, process};
+
+use tracing::{Level, debug, info, warn};
+
+mod audit;
+#[cfg(feature = "bundle")]
+mod bundle;
+mod check;
+mod compatible;
+mod crosscheck;
+#[cfg(feature = "arrow")]
+mod export;
+mod fix_headers;
+mod lift_gaf;
+mod merge;
+mod peek;
+mod registry;
+mod relate;
+mod resolve;
+#[cfg(feature = "serve-http")]
+mod serve_http;
+mod subset;
+mod summary;
+mod validate_gaf;
+mod vcf;
+#[cfg(feature = "fifo")]
+mod view;
+
+//-----------------------------------------------------------------------------
+
+fn main() -> Result<(), String> {
+    let mut args: Vec<String> = env::args().collect();
+    init_logging(&mut args)?;
+    if args.len() > 1 && args[1] == "audit" {
+        return audit::run(&args);
+    }
+    #[cfg(feature = "bundle")]
+    if args.len() > 1 && args[1] == "bundle" {
+        return bundle::run(&args);
+    }
+    if args.len() > 1 && args[1] == "check" {
+        return check::run(&args);
+    }
+    if args.len() > 1 && args[1] == "compatible" {
+        return compatible::run(&args);
+    }
+    if args.len() > 1 && args[1] == "crosscheck" {
+        return crosscheck::run(&args);
+    }
+    #[cfg(feature = "arrow")]
+    if args.len() > 1 && args[1] == "export" {
+        return export::run(&args);
+    }
+    if args.len() > 1 && args[1] == "fix-headers" {
+        return fix_headers::run(&args);
+    }
+    if args.len() > 1 && args[1] == "lift-gaf" {
+        return lift_gaf::run(&args);
+    }
+    if args.len() > 1 && args[1] == "merge" {
+        return merge::run(&args);
+    }
+    if args.len() > 1 && args[1] == "peek" {
+        return peek::run(&args);
+    }
+    if args.len() > 1 && args[1] == "registry" {
+        return registry::run(&args);
+    }
+    if args.len() > 1 && args[1] == "relate" {
+        return relate::run(&args);
+    }
+    #[cfg(feature = "serve-http")]
+    if args.len() > 1 && args[1] == "serve-http" {
+        return serve_http::run(&args);
+    }
+    if args.len() > 1 && args[1] == "subset" {
+        return subset::run(&args);
+    }
+    if args.len() > 1 && args[1] == "summary" {
+        return summary::run(&args);
+    }
+    if args.len() > 1 && args[1] == "validate-gaf" {
+        return validate_gaf::run(&args);
+    }
+    if args.len() > 1 && args[1] == "vcf" {
+        return vcf::run(&args);
+    }
+    #[cfg(feature = "fifo")]
+    if args.len() > 1 && args[1] == "view" {
+        return view::run(&args);
+    }
+
+    let config = Config::new(&args)?;
+    let mut provenance = Provenance::new(config.node_ids);
+
+    for input_file in config.input_files.iter() {
+        if config.sorted {
+            process_sorted(input_file, &config, &mut provenance)?;
+            continue;
+        }
+        if GBZ::is_gbz(input_file) {
+            let graph = read_gbz(input_file, config.benchmark)?;
+            let mut digests = Digests::default();
+            if config.path_digest || config.composite {
+                digests.paths = Some(paths::path_digest_gbz::<Sha256>(&graph)?);
+            }
+            if config.haplotype_digest {
+                digests.haplotypes = Some(paths::haplotype_digest_gbz::<Sha256>(&graph)?);
+            }
+            if let Some(prefix) = &config.paths_prefix {
+                let (subgraph, _) = paths::path_prefix_subgraph_gbz(&graph, prefix)?;
+                digests.prefix_subgraph = Some(pggname::stable_name(&subgraph));
+            }
+            if config.contigs {
+                let filename = format!("{}.contigs.tsv", input_file);
+                let file = File::create(&filename).map_err(|e| format!("Error creating {}: {}", filename, e))?;
+                let mut writer = BufWriter::new(file);
+                paths::write_contig_digests(&paths::contig_digests_gbz(&graph)?, &mut writer)?;
+                writer.flush().map_err(|e| format!("Error writing {}: {}", filename, e))?;
+            }
+            let string_ids = config.node_ids == NodeIds::String;
+            let graph = if config.both_ids {
+                let (graph, name) = other_name_gbz(graph, string_ids);
+                digests.other_ids = Some(name);
+                graph
+            } else {
+                graph
+            };
+            if !string_ids {
+                let graph = GBZInt { graph };
+                warn_node_ids(&graph, input_file, false, &config);
+                let hash = process(&graph, false, input_file, &digests, &config, &mut provenance)?;
+                if config.store_name && let Some(hash) = hash {
+                    let mut graph = graph;
+                    if let Some(subgraph) = &digests.prefix_subgraph && *subgraph != hash {
+                        // Register the selected subgraph as a subgraph of the full graph.
+                        let mut name = GraphName::new(hash.clone());
+                        name.add_relationships(&GraphName::from_gbz(&graph.graph));
+                        name.add_subgraph(subgraph, &hash);
+                        name.set_tags(graph.graph.tags_mut());
+                    }
+                    let tags = graph.graph.tags_mut();
+                    tags.insert(GraphName::TAG_NAME, &hash);
+                    serialize::serialize_to(&graph.graph, input_file)
+                        .map_err(|e| format!("Error saving GBZ file {}: {}", input_file, e))?;
+                }
+            } else {
+                let graph = GBZStr { graph };
+                warn_node_ids(&graph, input_file, true, &config);
+                process(&graph, true, input_file, &digests, &config, &mut provenance)?;
+            }
+        } else {
+            let mut digests = Digests::default();
+            if config.path_digest || config.composite {
+                digests.paths = Some(paths::path_digest_file::<Sha256, _>(input_file)?);
+            }
+            match config.node_ids {
+                NodeIds::Integer => {
+                    let (graph, report) = read_gfa::<GraphInt>(input_file, &config)?;
+                    print_report(&report, input_file, &config);
+                    process_gfa::<_, GraphStr>(&graph, false, &report, input_file, &mut digests, &config, &mut provenance)?;
+                }
+                NodeIds::String => {
+                    let (graph, report) = read_gfa::<GraphStr>(input_file, &config)?;
+                    print_report(&report, input_file, &config);
+                    process_gfa::<_, GraphInt>(&graph, true, &report, input_file, &mut digests, &config, &mut provenance)?;
+                }
+                NodeIds::Auto => {
+                    let int_result = read_gfa::<GraphInt>(input_file, &config);
+                    if let Ok((graph, report)) = &int_result && report.is_clean() {
+                        print_report(report, input_file, &config);
+                        process_gfa::<_, GraphStr>(graph, false, report, input_file, &mut digests, &config, &mut provenance)?;
+                        continue;
+                    }
+                    // In lenient mode, non-integer identifiers show up as errors instead of failures.
+                    // Use string identifiers unless they do not reduce the number of errors.
+                    let (str_graph, str_report) = read_gfa::<GraphStr>(input_file, &config)?;
+                    match int_result {
+                        Ok((graph, report)) if report.errors.len() <= str_report.errors.len() => {
+                            print_report(&report, input_file, &config);
+                            process_gfa::<_, GraphStr>(&graph, false, &report, input_file, &mut digests, &config, &mut provenance)?;
+                        }
+                        _ => {
+                            print_report(&str_report, input_file, &config);
+                            process_gfa::<_, GraphInt>(&str_graph, true, &str_report, input_file, &mut digests, &config, &mut provenance)?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(filename) = config.provenance.as_ref() {
+        provenance.finish();
+        fs::write(filename, provenance.to_json()).map_err(|e| format!("Error writing {}: {}", filename, e))?;
+    }
+
+    Ok(())
+}
+
+// Sends log messages to stderr at the level given with `--log-level` (default: info).
+// The option is removed from the arguments, so that it can be used with any subcommand.
+fn init_logging(args: &mut Vec<String>) -> Result<(), String> {
+    let mut level = Level::INFO;
+    let mut i = 1;
+    while i < args.len() {
+        let value = if args[i] == "--log-level" && i + 1 < args.len() {
+            let value = args.remove(i + 1);
+            args.remove(i);
+            value
+        } else if let Some(value) = args[i].strip_prefix("--log-level=") {
+            let value = String::from(value);
+            args.remove(i);
+            value
+        } else {
+            i += 1;
+            continue;
+        };
+        level = value.parse().map_err(|_| format!("Invalid log level: {}", value))?;
+    }
+    tracing_subscriber::fmt().with_max_level(level).with_writer(io::stderr).init();
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    input_files: Vec<String>,
+    node_ids: NodeIds,
+    store_name: bool,
+    path_digest: bool,
+    haplotype_digest: bool,
+    composite: bool,
+    seqcol: bool,
+    node_table: bool,
+    edge_set: bool,
+    normalized: bool,
+    refget: bool,
+    contigs: bool,
+    paths_prefix: Option<String>,
+    self_check: bool,
+    edge_stats: bool,
+    confusable_ids: bool,
+    sorted: bool,
+    checkpoint: bool,
+    tmpdir: Option<String>,
+    max_checkpoint: Option<u64>,
+    canonical: bool,
+    verify_stream: bool,
+    fingerprint: bool,
+    provenance: Option<String>,
+    both_ids: bool,
+    uri: bool,
+    long_format: bool,
+    parse_options: ParseOptions,
+    benchmark: bool,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let mut header = format!(
+            "Usage: {} [options] graph1 [graph2 ...]\n       {} audit\n       {} check [options] manifest1 [manifest2 ...]\n       {} compatible [options] reads.gaf[.gz] target\n       {} crosscheck graph1 [graph2 ...]\n       {} fix-headers [options] graph.gfa[.gz]\n       {} lift-gaf [options] --from A --to C --map map.trans reads.gaf\n       {} merge [options] --output merged.gfa part1 part2 [part3 ...]\n       {} peek graph.gfa[.gz]\n       {} registry derive|tree|header [options]\n       {} relate [options] A B\n       {} subset [options] --samples S1,S2,... graph.gbz\n       {} summary [options] graph\n       {} validate-gaf [options] reads.gaf[.gz] graph1 [graph2 ...]\n       {} vcf [options] variants.vcf[.gz]",
+            &program, &program, &program, &program, &program, &program, &program, &program, &program, &program, &program, &program, &program, &program, &program
+        );
+        if cfg!(feature = "arrow") {
+            header.push_str(&format!("\n       {} export [options] --prefix PREFIX graph", &program));
+        }
+        if cfg!(feature = "serve-http") {
+            header.push_str(&format!("\n       {} serve-http [options]", &program));
+        }
+        if cfg!(feature = "bundle") {
+            header.push_str(&format!("\n       {} bundle [options] bundle", &program));
+        }
+        if cfg!(feature = "fifo") {
+            header.push_str(&format!("\n       {} view [options] graph canonical.gfa", &program));
+        }
+
+        let mut opts = Options::new();
+        opts.optflag("i", "integer-ids", "use integer node identifiers");
+        opts.optflag("s", "string-ids", "use string node identifiers");
+        opts.optflag("n", "store-name", "store the name in GBZ tags (not with -s, -b)");
+        opts.optflag("p", "paths", "also print the path-set digest");
+        opts.optflag("H", "haplotypes", "also print the haplotype-set name (GBZ only)");
+        opts.optflag("c", "composite", "also print the composite graph+paths name");
+        opts.optflag("g", "seqcol", "also print the GA4GH seqcol digest of the node sequences");
+        opts.optflag("", "edge-set", "also print the edge-set digest of edges without sequences");
+        opts.optflag("", "node-table", "also print the node-table digest of node identifiers and sequences without edges");
+        opts.optflag("", "normalized", "also print the name of the graph with unary paths merged into single nodes");
+        opts.optflag("r", "refget", "write refget digests of node sequences to <graph>.refget.tsv");
+        opts.optopt("", "paths-prefix", "also print the name of the subgraph covered by paths with this name prefix, such as GRCh38# (GBZ only; stored as a subgraph with -n)", "PREFIX");
+        opts.optflag("", "contigs", "write digests of the subgraphs touched by reference contigs to <graph>.contigs.tsv (GBZ only)");
+        opts.optflag("", "edge-stats", "report edge orientation statistics and warn about unusual distributions");
+        opts.optflag("", "confusable-ids", "warn about string node identifiers that differ only by case or leading zeros");
+        opts.optflag("", "self-check", "verify that the canonical GFA representation parses back into a graph with the same name");
+        opts.optflag("", "sorted", "hash GFA files already in canonical order while reading them, without building the graph (integer identifiers unless -s)");
+        opts.optflag("", "checkpoint", "with --sorted: save checkpoints to <graph>.checkpoint and resume from them");
+        opts.optopt("", "tmpdir", "with --checkpoint: save checkpoints in DIR instead of next to the graph", "DIR");
+        opts.optopt("", "max-checkpoint", "with --checkpoint: skip checkpoints larger than SIZE bytes (suffix K, M, or G)", "SIZE");
+        opts.optflag("", "canonical", "write the canonical GFA representation to stdout instead of printing the name (one graph)");
+        opts.optflag("", "verify-stream", "with --canonical: hash the output while writing it and fail if it differs from the name");
+        opts.optflag("u", "uri", "print the name as a pggname:sha256: URI");
+        opts.optflag("", "long-format", "print self-identifying records: pggname, format version, algorithm, name, and path");
+        opts.optflag("f", "fingerprint", "print a quick fingerprint instead of the name (not with -n)");
+        opts.optopt("", "provenance", "write a provenance document for the named graphs to FILE", "FILE");
+        opts.optflag("", "both-ids", "also print the name with the other type of node identifiers (* if not possible)");
+        opts.optflag("l", "lenient", "skip and report malformed GFA lines");
+        opts.optflag("", "packed", "store GFA node sequences in packed form to save memory");
+        opts.optopt("", "names", "segment names with non-portable characters: allow (default), reject, escape", "POLICY");
+        opts.optflag("", "strict", "reject trailing whitespace, CRLF line endings, and wrong LN:i tags in GFA");
+        opts.optopt("", "jumps", "GFA J-lines: ignore (default) or include in the name", "POLICY");
+        opts.optflag("b", "benchmark", "run benchmarks");
+        opts.optopt("", "log-level", "log messages up to this level: error, warn, info (default), debug, trace (all subcommands)", "LEVEL");
+        let matches = opts.parse(&args[1..]).map_err(|e| e.to_string())?;
+
+        let input_files = if !matches.free.is_empty() {
+            matches.free.clone()
+        } else {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        };
+        let node_ids = if matches.opt_present("i") {
+            NodeIds::Integer
+        } else if matches.opt_present("s") {
+            NodeIds::String
+        } else {
+            NodeIds::Auto
+        };
+        let store_name = matches.opt_present("n");
+        let path_digest = matches.opt_present("p");
+        let haplotype_digest = matches.opt_present("H");
+        let composite = matches.opt_present("c");
+        let seqcol = matches.opt_present("g");
+        let node_table = matches.opt_present("node-table");
+        let edge_set = matches.opt_present("edge-set");
+        let normalized = matches.opt_present("normalized");
+        let refget = matches.opt_present("r");
+        let contigs = matches.opt_present("contigs");
+        let paths_prefix = matches.opt_str("paths-prefix");
+        let self_check = matches.opt_present("self-check");
+        let edge_stats = matches.opt_present("edge-stats");
+        let confusable_ids = matches.opt_present("confusable-ids");
+        let sorted = matches.opt_present("sorted");
+        let checkpoint = matches.opt_present("checkpoint");
+        if checkpoint && !sorted {
+            return Err(String::from("--checkpoint requires --sorted"));
+        }
+        let tmpdir = matches.opt_str("tmpdir");
+        if let Some(dir) = &tmpdir && !fs::metadata(dir).map(|metadata| metadata.is_dir()).unwrap_or(false) {
+            return Err(format!("Temporary directory {} does not exist", dir));
+        }
+        let max_checkpoint = matches.opt_str("max-checkpoint").map(|size| parse_size(&size)).transpose()?;
+        for option in ["tmpdir", "max-checkpoint"] {
+            if matches.opt_present(option) && !checkpoint {
+                return Err(format!("--{} requires --checkpoint", option));
+            }
+        }
+        let graph_options = [
+            "n", "p", "H", "c", "g", "node-table", "edge-set", "normalized", "r", "paths-prefix", "contigs", "self-check", "edge-stats", "confusable-ids", "f", "both-ids", "l", "packed", "names", "strict", "jumps", "b",
+        ];
+        if sorted && let Some(option) = graph_options.iter().find(|option| matches.opt_present(option)) {
+            return Err(format!("Option {} cannot be used with --sorted", option_name(option)));
+        }
+        let canonical = matches.opt_present("canonical");
+        let verify_stream = matches.opt_present("verify-stream");
+        if verify_stream && !canonical {
+            return Err(String::from("--verify-stream requires --canonical"));
+        }
+        // Options that print or store names, which would mix with the canonical GFA.
+        let name_options = ["n", "p", "H", "c", "g", "node-table", "edge-set", "normalized", "paths-prefix", "sorted", "u", "f", "provenance", "both-ids", "b"];
+        if canonical && let Some(option) = name_options.iter().find(|option| matches.opt_present(option)) {
+            return Err(format!("Option {} cannot be used with --canonical", option_name(option)));
+        }
+        if canonical && input_files.len() != 1 {
+            return Err(String::from("--canonical requires a single graph"));
+        }
+        let fingerprint = matches.opt_present("f");
+        let provenance = matches.opt_str("provenance");
+        let both_ids = matches.opt_present("both-ids");
+        let uri = matches.opt_present("u");
+        let long_format = matches.opt_present("long-format");
+        // Options that add columns or change the name, which the records cannot represent.
+        let column_options = ["p", "H", "c", "g", "node-table", "edge-set", "normalized", "paths-prefix", "both-ids", "u", "f", "canonical", "b"];
+        if long_format && let Some(option) = column_options.iter().find(|option| matches.opt_present(option)) {
+            return Err(format!("Option {} cannot be used with --long-format", option_name(option)));
+        }
+        let parse_options = ParseOptions {
+            lenient: matches.opt_present("l"),
+            pack_sequences: matches.opt_present("packed"),
+            strict: matches.opt_present("strict"),
+            names: match matches.opt_str("names") {
+                Some(policy) => policy.parse::<NamePolicy>()?,
+                None => NamePolicy::default(),
+            },
+            jumps: match matches.opt_str("jumps") {
+                Some(policy) => policy.parse::<JumpPolicy>()?,
+                None => JumpPolicy::default(),
+            },
+        };
+        // Fingerprints and the streaming check only cover the graph itself.
+        if parse_options.jumps == JumpPolicy::Include && let Some(option) = ["f", "verify-stream", "b"].iter().find(|option| matches.opt_present(option)) {
+            return Err(format!("Option {} cannot be used with --jumps include", option_name(option)));
+        }
+        let benchmark = matches.opt_present("b");
+
+        Ok(Config { input_files, node_ids, store_name, path_digest, haplotype_digest, composite, seqcol, node_table, edge_set, normalized, refget, contigs, paths_prefix, self_check, edge_stats, confusable_ids, sorted, checkpoint, tmpdir, max_checkpoint, canonical, verify_stream, fingerprint, provenance, both_ids, uri, long_format, parse_options, benchmark })
+    }
+}
+
+// Returns the option as it appears on the command line.
+fn option_name(option: &str) -> String {
+    if option.len() == 1 { format!("-{}", option) } else { format!("--{}", option) }
+}
+
+// Parses a size in bytes with an optional K, M, or G suffix (powers of 1024).
+fn parse_size(value: &str) -> Result<u64, String> {
+    let (number, multiplier) = match value.as_bytes().last() {
+        Some(b'K' | b'k') => (&value[..value.len() - 1], 1 << 10),
+        Some(b'M' | b'm') => (&value[..value.len() - 1], 1 << 20),
+        Some(b'G' | b'g') => (&value[..value.len() - 1], 1 << 30),
+        _ => (value, 1),
+    };
+    number.parse::<u64>().ok().and_then(|n| n.checked_mul(multiplier)).ok_or(format!("Invalid size: {}", value))
+}
+
+//-----------------------------------------------------------------------------
+
+fn print_statistics<G: Graph>(graph: &G, input_file: &str) {
+    let stats = graph.statistics();
+    eprintln!("Graph {}:", input_file);
+    eprintln!("  Nodes:    {}", stats.nodes);
+    eprintln!("  Edges:    {}", stats.edges);
+    eprintln!("  Sequence: {} bp", stats.sequence_len);
+    eprintln!("  Duplicate edges: {}", graph.duplicate_edges());
+    eprintln!();
+}
+
+fn print_edge_statistics(stats: &EdgeStatistics, input_file: &str) {
+    eprintln!("Edges in {}:", input_file);
+    eprintln!("  Forward-forward: {}", stats.forward_forward);
+    eprintln!("  Forward-reverse: {}", stats.forward_reverse);
+    eprintln!("  Reverse-forward: {}", stats.reverse_forward);
+    eprintln!("  Reverse-reverse: {}", stats.reverse_reverse);
+    eprintln!("  Self-loops:      {}", stats.self_loops);
+    eprintln!();
+    for warning in stats.warnings() {
+        warn!("{}: {}", input_file, warning);
+    }
+}
+
+fn print_report(report: &ParseReport, input_file: &str, config: &Config) {
+    if (config.parse_options.strict || config.benchmark) && report.skipped_lines() > 0 {
+        info!("Skipped {} lines of other record types in {} ({})", report.skipped_lines(), input_file, report.skipped_summary());
+    }
+    if report.trimmed_lines > 0 {
+        warn!("Stripped trailing whitespace from {} lines in {}", report.trimmed_lines, input_file);
+    }
+    if config.parse_options.jumps == JumpPolicy::Ignore && let Some(jumps) = report.skipped_records.get(&b'J') {
+        warn!("Ignored {} jump (J) lines in {}; use --jumps include if they should affect the name", jumps, input_file);
+    }
+    if report.is_clean() {
+        return;
+    }
+    warn!("Skipped {} malformed lines in {}", report.errors.len(), input_file);
+    for (line_num, message) in report.errors.iter() {
+        warn!("{} line {}: {}", input_file, line_num, message);
+    }
+}
+
+fn read_gfa<G: Graph>(input_file: &str, config: &Config) -> Result<(G, ParseReport), String> {
+    let start_time = Instant::now();
+
+    let reader = algorithms::open_gfa(input_file)?;
+    let result = algorithms::parse_gfa_with_options::<G, _>(reader, &config.parse_options)?;
+
+    let duration = start_time.elapsed();
+    let seconds = duration.as_secs_f64();
+    if config.benchmark {
+        info!("Parsed {} in {:.3} seconds", input_file, seconds);
+    } else {
+        debug!("Parsed {} in {:.3} seconds", input_file, seconds);
+    }
+
+    Ok(result)
+}
+
+fn read_gbz(input_file: &str, benchmark: bool) -> Result<GBZ, String> {
+    let start_time = Instant::now();
+
+    let graph: GBZ = serialize::load_from(input_file)
+        .map_err(|e| format!("Error loading GBZ file {}: {}", input_file, e))?;
+
+    let duration = start_time.elapsed();
+    let seconds = duration.as_secs_f64();
+    if benchmark {
+        info!("Loaded {} in {:.3} seconds", input_file, seconds);
+    } else {
+        debug!("Loaded {} in {:.3} seconds", input_file, seconds);
+    }
+
+    Ok(graph)
+}
+
+//-----------------------------------------------------------------------------
+
+// Computes the name of a GFA file in canonical order without building the graph, and prints it.
+// With --checkpoint, resumes from <graph>.checkpoint if it matches the file, saves checkpoints there periodically, and removes it when done.
+// The checkpoint is in --tmpdir if given, and it is also removed if hashing fails.
+fn process_sorted(input_file: &str, config: &Config, provenance: &mut Provenance) -> Result<(), String> {
+    let node_ids = if config.node_ids == NodeIds::String { NodeIds::String } else { NodeIds::Integer };
+    // Checkpoints are only used with local files, as other inputs cannot be checked for changes or read again.
+    let input_size = match fs::metadata(input_file) {
+        Ok(metadata) if metadata.is_file() => Some(metadata.len()),
+        _ if config.checkpoint => {
+            warn!("Checkpoints are only supported for local files; hashing {} without them", input_file);
+            None
+        }
+        _ => None,
+    };
+    let checkpointing = config.checkpoint && input_size.is_some();
+    let checkpoint_file = match &config.tmpdir {
+        Some(dir) => {
+            let file_name = std::path::Path::new(input_file).file_name().unwrap_or_default();
+            std::path::Path::new(dir).join(file_name).to_string_lossy().into_owned() + ".checkpoint"
+        }
+        None => format!("{}.checkpoint", input_file),
+    };
+    let temp_file = format!("{}.tmp", checkpoint_file);
+
+    // Removing a stale checkpoint is not worth failing over, as a new one would replace it.
+    let remove_checkpoint = || {
+        for file in [&temp_file, &checkpoint_file] {
+            if fs::exists(file).unwrap_or(false) && let Err(e) = fs::remove_file(file) {
+                warn!("Error removing {}: {}", file, e);
+            }
+        }
+    };
+
+    let mut hasher = SortedGfaHasher::new(node_ids)?;
+    let mut reader = algorithms::open_gfa(input_file)?;
+    if checkpointing && fs::exists(&checkpoint_file).unwrap_or(false) {
+        let text = fs::read_to_string(&checkpoint_file).map_err(|e| format!("Error reading {}: {}", checkpoint_file, e))?;
+        let resumed = text.parse::<Checkpoint>().and_then(|checkpoint| {
+            if Some(checkpoint.input_size) != input_size || checkpoint.node_ids() != node_ids {
+                return Err(String::from("it does not match the input file or the node identifiers"));
+            }
+            SortedGfaHasher::resume(&checkpoint).map(|hasher| (hasher, checkpoint.nodes()))
+        });
+        match resumed {
+            // The checkpoint is only used if the consumed part of the input has not changed.
+            Ok((mut resumed, nodes)) => {
+                if resumed.skip_prefix(&mut reader).map_err(|e| format!("{}: {}", input_file, e))? {
+                    info!("Resuming {} after {} nodes", input_file, nodes);
+                    hasher = resumed;
+                } else {
+                    warn!("Ignoring {}: the input has changed since the checkpoint", checkpoint_file);
+                    remove_checkpoint();
+                    reader = algorithms::open_gfa(input_file)?;
+                }
+            }
+            Err(e) => {
+                warn!("Ignoring {}: {}", checkpoint_file, e);
+                remove_checkpoint();
+            }
+        }
+    }
+
+    let interval = if checkpointing { sorted::CHECKPOINT_INTERVAL } else { 0 };
+    let mut over_limit = false;
+    let save = |checkpoint: &Checkpoint| -> Result<(), String> {
+        let mut checkpoint = checkpoint.clone();
+        checkpoint.input_size = input_size.unwrap_or(0);
+        let text = checkpoint.to_string();
+        if let Some(limit) = config.max_checkpoint && text.len() as u64 > limit {
+            if !over_limit {
+                warn!("Skipping checkpoints for {}: {} bytes exceeds the limit of {} bytes", input_file, text.len(), limit);
+                over_limit = true;
+            }
+            return Ok(());
+        }
+        fs::write(&temp_file, text).map_err(|e| format!("Error writing {}: {}", temp_file, e))?;
+        fs::rename(&temp_file, &checkpoint_file).map_err(|e| format!("Error writing {}: {}", checkpoint_file, e))?;
+        debug!("Saved a checkpoint for {} after {} nodes", input_file, checkpoint.nodes());
+        Ok(())
+    };
+    let start_time = Instant::now();
+    let result = sorted::hash_sorted_gfa(reader, hasher, interval, save);
+    // The checkpoint is kept after an error, as the error may be transient.
+    let hash = result.map_err(|e| format!("{}: {}", input_file, e))?;
+    if checkpointing {
+        remove_checkpoint();
+    }
+    debug!("Hashed {} in {:.3} seconds", input_file, start_time.elapsed().as_secs_f64());
+
+    let mut input = ProvenanceInput::new(input_file);
+    input.name = Some(hash.clone());
+    if config.long_format {
+        println!("{}", NameRecord::new(vectors::CANONICAL_FORMAT_VERSION, &hash, input_file));
+        provenance.add_input(input);
+        return Ok(());
+    }
+    let name = match GraphName::uri_for(&hash) {
+        Some(uri) if config.uri => uri,
+        _ => hash,
+    };
+    println!("{}  {}", name, input_file);
+    provenance.add_input(input);
+    Ok(())
+}
+
+// Secondary digests reported alongside the graph name.
+#[derive(Default)]
+struct Digests {
+    paths: Option<String>,
+    haplotypes: Option<String>,
+    // Name with the other type of node identifiers.
+    other_ids: Option<String>,
+    // Name of the subgraph covered by the paths selected with --paths-prefix.
+    prefix_subgraph: Option<String>,
+    // Jumps included in the name with --jumps include.
+    jumps: Option<Jumps>,
+    // Were some segment names changed by --names escape?
+    escaped: bool,
+}
+
+// Warns if all node names are integers, but the name depends on the type of node identifiers.
+// Integer identifiers are the default, so the warning is only printed for them in strict mode.
+// With --confusable-ids, also warns about string identifiers that differ only by case or leading zeros.
+fn warn_node_ids<G: Graph>(graph: &G, input_file: &str, string_ids: bool, config: &Config) {
+    if (string_ids || config.parse_options.strict) && graph.name_depends_on_node_ids() {
+        let (used, other) = if string_ids { ("string", "integer") } else { ("integer", "string") };
+        warn!("All node names in {} are integers, but the name with {} identifiers differs from the name with {} identifiers; use --both-ids to print both names", input_file, used, other);
+    }
+    if config.confusable_ids {
+        let groups = graph.confusable_ids();
+        for group in groups.iter().take(MAX_CONFUSABLE_GROUPS) {
+            let names: Vec<String> = group.iter().map(|name| String::from_utf8_lossy(name).into_owned()).collect();
+            warn!("{}: node identifiers {} differ only by case or leading zeros and are sorted in {} order", input_file, names.join(", "), GraphStr::ID_ORDER);
+        }
+        if groups.len() > MAX_CONFUSABLE_GROUPS {
+            warn!("{}: {} more groups of confusable node identifiers", input_file, groups.len() - MAX_CONFUSABLE_GROUPS);
+        }
+    }
+}
+
+// Maximum number of groups of confusable node identifiers reported for each graph.
+const MAX_CONFUSABLE_GROUPS: usize = 10;
+
+// Returns the name of the GBZ graph with the other type of node identifiers.
+fn other_name_gbz(graph: GBZ, string_ids: bool) -> (GBZ, String) {
+    if string_ids {
+        let graph = GBZInt { graph };
+        let name = pggname::stable_name(&graph);
+        (graph.graph, name)
+    } else {
+        let graph = GBZStr { graph };
+        let name = pggname::stable_name(&graph);
+        (graph.graph, name)
+    }
+}
+
+// Processes a GFA graph parsed with node identifiers of type `G`, which uses string identifiers if `string_ids` is set.
+// If requested, the name with identifiers of type `O` is computed by parsing the file again.
+// It is `*` if the file cannot be parsed with the other type, or if doing so results in more malformed lines.
+fn process_gfa<G: Graph, O: Graph>(
+    graph: &G, string_ids: bool, report: &ParseReport, input_file: &str, digests: &mut Digests, config: &Config, provenance: &mut Provenance
+) -> Result<Option<String>, String> {
+    warn_node_ids(graph, input_file, string_ids, config);
+    digests.escaped = report.escaped_names > 0;
+    if config.parse_options.jumps == JumpPolicy::Include {
+        debug!("Read {} distinct jumps from {}", report.jumps.len(), input_file);
+        digests.jumps = Some(report.jumps.clone());
+    }
+    if config.both_ids {
+        let name = match read_gfa::<O>(input_file, config) {
+            Ok((other, other_report)) if other_report.errors.len() <= report.errors.len() => {
+                match &digests.jumps {
+                    Some(jumps) => pggname::jumps::stable_name(&other, jumps).unwrap_or(String::from("*")),
+                    None if other_report.escaped_names > 0 => algorithms::hash_portable::<Sha256, _>(&other),
+                    None => pggname::stable_name(&other),
+                }
+            }
+            _ => String::from("*"),
+        };
+        digests.other_ids = Some(name);
+    }
+    process(graph, string_ids, input_file, digests, config, provenance)
+}
+
+// Returns the name of the graph without jumps, in the portable canonical format if some segment names were escaped.
+fn graph_name<G: Graph>(graph: &G, digests: &Digests) -> String {
+    if digests.escaped {
+        algorithms::hash_portable::<Sha256, _>(graph)
+    } else {
+        pggname::stable_name(graph)
+    }
+}
+
+// Prints the name, followed by the requested secondary digests, or the fingerprint.
+// With --canonical, writes the canonical GFA representation to stdout instead.
+// GFA files do not have a GBWT path collection, so the haplotype-set name is printed as `*`.
+// Also writes the node digest table, if requested, and records the named graph in the provenance document.
+// With --self-check, the canonical GFA representation is parsed again using the same type of node identifiers (string if `string_ids` is set).
+fn process<G: Graph>(
+    graph: &G, string_ids: bool, input_file: &str, digests: &Digests, config: &Config, provenance: &mut Provenance
+) -> Result<Option<String>, String> {
+    if config.refget {
+        let filename = format!("{}.refget.tsv", input_file);
+        let file = File::create(&filename).map_err(|e| format!("Error creating {}: {}", filename, e))?;
+        let mut writer = BufWriter::new(file);
+        seqcol::write_node_digests(graph, &mut writer)?;
+        writer.flush().map_err(|e| format!("Error writing {}: {}", filename, e))?;
+    }
+
+    if config.parse_options.strict && graph.duplicate_edges() > 0 {
+        warn!("Removed {} duplicate edges from {}", graph.duplicate_edges(), input_file);
+    }
+
+    if config.edge_stats {
+        print_edge_statistics(&graph.edge_statistics(), input_file);
+    }
+
+    if config.canonical {
+        let writer = BufWriter::new(io::stdout().lock());
+        if let Some(jumps) = &digests.jumps {
+            pggname::jumps::write_canonical(graph, jumps, writer)?;
+        } else if config.verify_stream {
+            let mut writer = writer;
+            if digests.escaped {
+                writer.write_all(algorithms::PORTABLE_FORMAT_HEADER).map_err(|e| format!("Error writing the canonical GFA: {}", e))?;
+            }
+            // The portable format only adds a constant header, so the nodes are checked in the same way.
+            let streamed = algorithms::write_canonical_with_hash::<Sha256, _, _>(graph, writer)?;
+            let hash = pggname::stable_name(graph);
+            if streamed != hash {
+                return Err(format!("The canonical GFA written for {} hashes to {}, but the hasher consumed {}", input_file, streamed, hash));
+            }
+            info!("Verified the canonical GFA for {}: {}", input_file, graph_name(graph, digests));
+        } else if digests.escaped {
+            algorithms::write_canonical_portable(graph, writer)?;
+        } else {
+            algorithms::write_canonical(graph, writer)?;
+        }
+        Ok(None)
+    } else if config.benchmark {
+        print_statistics(graph, input_file);
+        benchmark_all::<G>(graph);
+        Ok(None)
+    } else if config.fingerprint {
+        let fingerprint = algorithms::fingerprint::<Sha256, G>(graph, algorithms::FINGERPRINT_INTERVAL);
+        println!("{}  {}", fingerprint, input_file);
+        Ok(None)
+    } else {
+        let hash = match &digests.jumps {
+            Some(jumps) => pggname::jumps::stable_name(graph, jumps).map_err(|e| format!("{}: {}", input_file, e))?,
+            None => graph_name(graph, digests),
+        };
+        if config.self_check {
+            let result = if string_ids {
+                algorithms::check_round_trip::<_, GraphStr>(graph)
+            } else {
+                algorithms::check_round_trip::<_, GraphInt>(graph)
+            };
+            result.map_err(|e| format!("Self-check failed for {}: {}", input_file, e))?;
+            info!("Self-check passed for {}", input_file);
+        }
+        let mut input = ProvenanceInput::new(input_file);
+        input.name = Some(hash.clone());
+        if config.long_format {
+            let version = match (digests.jumps.is_some(), digests.escaped) {
+                (false, false) => vectors::CANONICAL_FORMAT_VERSION,
+                (false, true) => vectors::PORTABLE_FORMAT_VERSION,
+                (true, false) => vectors::JUMP_FORMAT_VERSION,
+                (true, true) => return Err(format!("{}: there is no canonical format version with both jumps and escaped segment names", input_file)),
+            };
+            println!("{}", NameRecord::new(version, &hash, input_file));
+            provenance.add_input(input);
+            return Ok(Some(hash));
+        }
+        let mut columns = match GraphName::uri_for(&hash) {
+            Some(uri) if config.uri => vec![uri],
+            _ => vec![hash.clone()],
+        };
+        if config.path_digest {
+            input.path_digest = digests.paths.clone();
+            columns.push(digests.paths.clone().unwrap_or_default());
+        }
+        if config.haplotype_digest {
+            input.haplotype_digest = digests.haplotypes.clone();
+            columns.push(digests.haplotypes.clone().unwrap_or(String::from("*")));
+        }
+        if config.composite {
+            let composite = paths::composite_name(&hash, digests.paths.as_deref().unwrap_or_default());
+            input.composite_name = Some(composite.clone());
+            columns.push(composite);
+        }
+        if config.seqcol {
+            let digest = SequenceCollection::new(graph).digest();
+            input.seqcol_digest = Some(digest.clone());
+            columns.push(digest);
+        }
+        if config.node_table {
+            columns.push(pggname::node_table_digest(graph));
+        }
+        if config.edge_set {
+            columns.push(pggname::edge_set_digest(graph));
+        }
+        if config.normalized {
+            let (normalized, _): (GraphInt, _) = translation::normalize(graph)?;
+            if config.self_check {
+                algorithms::check_round_trip::<_, GraphInt>(&normalized)
+                    .map_err(|e| format!("Self-check failed for the normalized graph of {}: {}", input_file, e))?;
+            }
+            columns.push(pggname::stable_name(&normalized));
+        }
+        if config.both_ids {
+            columns.push(digests.other_ids.clone().unwrap_or(String::from("*")));
+        }
+        if config.paths_prefix.is_some() {
+            columns.push(digests.prefix_subgraph.clone().unwrap_or(String::from("*")));
+        }
+        columns.push(String::from(input_file));
+        println!("{}", columns.join("  "));
+        provenance.add_input(input);
+        Ok(Some(hash))
+    }
+}
+
+fn benchmark<D: Digest, G: Graph>(graph: &G, name: &str) 
+    where digest::Output<D>: core::fmt::LowerHex {
+    let start = Instant::now();
+    let hash = algorithms::hash::<D, G>(graph);
+    let duration = start.elapsed();
+    let seconds = duration.as_secs_f64();
+    info!("{}: {} ({:.3} seconds)", name, hash, seconds);
+}
+
+// Hashes the graph with one hasher update per node, for comparison with the buffered updates in `algorithms::hash`.
+fn benchmark_unbuffered<G: Graph>(graph: &G) {
+    let start = Instant::now();
+    let mut hasher = Sha256::new();
+    graph.for_each_node(|bytes| hasher.update(bytes));
+    let hash = hasher.finalize();
+    let duration = start.elapsed();
+    let seconds = duration.as_secs_f64();
+    info!("SHA-256 (per-node updates): {:x} ({:.3} seconds)", hash, seconds);
+}
+
+fn benchmark_all<G: Graph>(graph: &G) {
+    benchmark_unbuffered::<G>(graph);
+    benchmark::<Sha224, G>(graph, "SHA-224");
+    benchmark::<Sha256, G>(graph, "SHA-256");
+    benchmark::<Sha384, G>(graph, "SHA-384");
+    benchmark::<Sha512_224, G>(graph, "SHA-512/224");
+    benchmark::<Sha512_256, G>(graph, "SHA-512/256");
+    benchmark::<Sha512, G>(graph, "SHA-512");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,102 @@
+//! The `merge` subcommand: concatenating per-chromosome graphs into a single named graph.
+//!
+//! Reads the parts with [`GraphMerger`], which requires disjoint node identifiers, and writes the combined graph in the canonical GFA format.
+//! The header records the combined name, each part as a subgraph of the combined graph, and the relationships stored with the parts in GBZ tags or GFA headers.
+//! Stale names stored with the parts are reported.
+//! Prints the names of the parts and the combined graph.
+
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::{Graph, GraphName, algorithms};
+use pggname::graph::{GraphInt, GraphStr, GBZInt, GBZStr};
+use pggname::merge::GraphMerger;
+
+use simple_sds::serialize;
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::process;
+
+use tracing::info;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+    if config.string_ids {
+        merge::<GraphStr>(&config)
+    } else {
+        merge::<GraphInt>(&config)
+    }
+}
+
+fn merge<G: Graph>(config: &Config) -> Result<(), String> {
+    let mut merger = GraphMerger::<G>::new();
+    for input_file in config.input_files.iter() {
+        let name = if GBZ::is_gbz(input_file) {
+            let graph: GBZ = serialize::load_from(input_file)
+                .map_err(|e| format!("Error loading GBZ file {}: {}", input_file, e))?;
+            let metadata = GraphName::from_gbz(&graph);
+            if config.string_ids {
+                merger.add(&GBZStr { graph }, &metadata, input_file)?
+            } else {
+                merger.add(&GBZInt { graph }, &metadata, input_file)?
+            }
+        } else {
+            let metadata = algorithms::read_gfa_metadata(input_file)?;
+            let graph = algorithms::parse_gfa::<G, _>(algorithms::open_gfa(input_file)?)
+                .map_err(|e| format!("{}: {}", input_file, e))?;
+            merger.add(&graph, &metadata, input_file)?
+        };
+        info!("Added {}", input_file);
+        println!("{}  {}", name, input_file);
+    }
+
+    let (graph, name) = merger.finish()?;
+    let file = File::create(&config.output).map_err(|e| format!("Error creating {}: {}", config.output, e))?;
+    let mut writer = BufWriter::new(file);
+    for line in name.to_gfa_header_lines() {
+        writeln!(writer, "{}", line).map_err(|e| format!("Error writing {}: {}", config.output, e))?;
+    }
+    algorithms::write_canonical(&graph, writer).map_err(|e| format!("{}: {}", config.output, e))?;
+    println!("{}  {}", name.name().cloned().unwrap_or_default(), config.output);
+
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    input_files: Vec<String>,
+    output: String,
+    string_ids: bool,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} merge [options] --output merged.gfa part1 part2 [part3 ...]", &program);
+
+        let mut opts = Options::new();
+        opts.optopt("o", "output", "write the combined graph in canonical GFA format to FILE (required)", "FILE");
+        opts.optflag("s", "string-ids", "use string node identifiers (default: integer identifiers)");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        let Some(output) = matches.opt_str("output") else {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        };
+        if matches.free.is_empty() {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let input_files = matches.free.clone();
+        let string_ids = matches.opt_present("s");
+
+        Ok(Config { input_files, output, string_ids })
+    }
+}
+
+//-----------------------------------------------------------------------------
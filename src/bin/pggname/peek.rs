@@ -0,0 +1,71 @@
+//! The `peek` subcommand: a quick look at a GFA file before naming it.
+//!
+//! Reads the file once with [`algorithms::peek_gfa`] without building the graph.
+//! Reports the node identifier mode chosen by default, the number of segment and link lines, the range of node identifiers, and the first and last segments in canonical order.
+//! This makes it possible to confirm the file and the identifier interpretation before starting a long naming job.
+
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::algorithms::{self, NodeIds};
+
+use std::process;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+    if GBZ::is_gbz(&config.graph_file) {
+        return Err(format!("{}: peek only supports GFA files; GBZ graphs always use integer identifiers", config.graph_file));
+    }
+
+    let peek = algorithms::peek_gfa(algorithms::open_gfa(&config.graph_file)?)
+        .map_err(|e| format!("{}: {}", config.graph_file, e))?;
+    let mode = match (peek.node_ids, peek.noncanonical_ids) {
+        (NodeIds::Integer, false) => "integer",
+        (NodeIds::Integer, true) => "integer (some names are not in canonical decimal form; use --string-ids to keep them)",
+        _ => "string",
+    };
+    let segment = |segment: &Option<(Vec<u8>, Vec<u8>)>| match segment {
+        Some((name, sequence)) => format!("S\t{}\t{}", String::from_utf8_lossy(name), String::from_utf8_lossy(sequence)),
+        None => String::from("-"),
+    };
+    let name = |segment: &Option<(Vec<u8>, Vec<u8>)>| segment.as_ref()
+        .map(|(name, _)| String::from_utf8_lossy(name).into_owned()).unwrap_or(String::from("-"));
+
+    println!("File: {}", config.graph_file);
+    println!("Node IDs: {}", mode);
+    println!("Segments: {}", peek.segments);
+    println!("Links: {}", peek.links);
+    println!("ID range: {} .. {}", name(&peek.first), name(&peek.last));
+    println!("First: {}", segment(&peek.first));
+    println!("Last: {}", segment(&peek.last));
+
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    graph_file: String,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} peek graph.gfa[.gz]", &program);
+
+        let opts = Options::new();
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.free.len() != 1 {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+        let graph_file = matches.free[0].clone();
+
+        Ok(Config { graph_file })
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,327 @@
+//! The `registry` subcommand: a lightweight store of graph derivations.
+//!
+//! The registry is a tab-separated text file with one derivation per line: the name of the parent graph, the name of the derived graph, the operation, the relationship tag (`SG` or `TL`), and a Unix timestamp.
+//! Lines starting with `#` are comments.
+//! The header line `# pggname registry version N` declares the metadata version (see [`GraphName::METADATA_VERSION`]), and registries without a version are treated as version 1.
+//! Lines that cannot be parsed are skipped with a warning in registries from a newer version.
+//! A derived graph with relationship `SG` is a subgraph of the parent graph, while `TL` means that it can be translated to the parent graph.
+//!
+//! * `registry derive --from A --to B --op OP` appends a derivation to the registry.
+//!   Operations `clip`, `extract`, and `subset` produce subgraphs, and `chop`, `merge`, `normalize`, and `renumber` produce translations.
+//!   The relationship of other operations must be given with `--relation`.
+//! * `registry import --source GRAPH --format FORMAT REPORT` appends a subgraph derivation from the source graph for each output listed in a `vg chunk` or `odgi extract` report (see [`pggname::extract`]).
+//!   The operation is `chunk` or `extract` followed by the region, such as `chunk chr1:0-1000`.
+//!   The outputs and the source graph are named using manifest entries for the same paths (see [`NameResolver::resolve_file`]) or by reading the files.
+//!   Relative paths in the report are tried as given and then relative to the directory of the report.
+//! * `registry tree NAME` lists the derivations leading to the graph and the derivations starting from it.
+//!   Each output line contains the depth, the parent, the derived graph, the operation, and the relationship tag.
+//! * `registry header NAME` prints the relationships of the graph and its ancestors as GFA header lines.
+//!
+//! Graph names given on the command line can also be aliases or name prefixes (see [`crate::resolve`]).
+//! They are resolved using the names in the registry and in the manifests given with `--manifest`.
+
+use getopts::Options;
+
+use crate::resolve::NameResolver;
+
+use pggname::GraphName;
+use pggname::extract::{self, ReportFormat};
+use pggname::name::RelationshipKind;
+
+use std::collections::BTreeSet;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::{info, warn};
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Header line written at the start of a new registry, followed by the metadata version.
+const HEADER: &str = "# pggname registry";
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+    match config.command {
+        Command::Append(derivations) => {
+            let exists = Path::new(&config.registry).exists();
+            let file = OpenOptions::new().create(true).append(true).open(&config.registry)
+                .map_err(|e| format!("Error opening {}: {}", config.registry, e))?;
+            let mut writer = BufWriter::new(file);
+            if !exists {
+                writeln!(writer, "{} version {}", HEADER, GraphName::METADATA_VERSION).map_err(|e| e.to_string())?;
+            }
+            for derivation in derivations.iter() {
+                writeln!(writer, "{}", derivation).map_err(|e| e.to_string())?;
+            }
+            writer.flush().map_err(|e| format!("Error writing {}: {}", config.registry, e))?;
+        }
+        Command::Tree(name) => {
+            let derivations = read_registry(&config.registry)?;
+            let mut writer = BufWriter::new(io::stdout().lock());
+            writeln!(writer, "# ancestors").map_err(|e| e.to_string())?;
+            for (depth, derivation) in ancestors(&derivations, &name) {
+                writeln!(writer, "{}\t{}", depth, derivation.fields()).map_err(|e| e.to_string())?;
+            }
+            writeln!(writer, "# descendants").map_err(|e| e.to_string())?;
+            for (depth, derivation) in descendants(&derivations, &name) {
+                writeln!(writer, "{}\t{}", depth, derivation.fields()).map_err(|e| e.to_string())?;
+            }
+            writer.flush().map_err(|e| e.to_string())?;
+        }
+        Command::Header(name) => {
+            let derivations = read_registry(&config.registry)?;
+            let mut result = GraphName::new(name.clone());
+            for (_, derivation) in ancestors(&derivations, &name) {
+                result.add_relationship(derivation.relationship, &derivation.to, &derivation.from);
+            }
+            for line in result.to_gfa_header_lines() {
+                println!("{}", line);
+            }
+        }
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+// Returns the default relationship for the operation.
+fn relationship_for(operation: &str) -> Option<RelationshipKind> {
+    match operation {
+        "clip" | "extract" | "subset" => Some(RelationshipKind::Subgraph),
+        "chop" | "merge" | "normalize" | "renumber" => Some(RelationshipKind::Translation),
+        _ => None,
+    }
+}
+
+// A derivation of graph `to` from graph `from`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Derivation {
+    from: String,
+    to: String,
+    operation: String,
+    relationship: RelationshipKind,
+    timestamp: u64,
+}
+
+impl Derivation {
+    fn parse(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 5 {
+            return Err(format!("Expected 5 fields, found {}", fields.len()));
+        }
+        let relationship = RelationshipKind::from_tag(fields[3]).ok_or(format!("Invalid relationship: {}", fields[3]))?;
+        let timestamp = fields[4].parse::<u64>().map_err(|_| format!("Invalid timestamp: {}", fields[4]))?;
+        Ok(Derivation {
+            from: parse_name(fields[0])?,
+            to: parse_name(fields[1])?,
+            operation: String::from(fields[2]),
+            relationship, timestamp,
+        })
+    }
+
+    // Returns the fields shown in query results.
+    fn fields(&self) -> String {
+        format!("{}\t{}\t{}\t{}", self.from, self.to, self.operation, self.relationship.tag())
+    }
+}
+
+impl std::fmt::Display for Derivation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\t{}", self.fields(), self.timestamp)
+    }
+}
+
+// Accepts a stable graph name or a graph name URI.
+fn parse_name(name: &str) -> Result<String, String> {
+    if GraphName::is_stable_name(name) {
+        Ok(String::from(name))
+    } else {
+        GraphName::parse_uri(name)
+    }
+}
+
+// Reads the derivations from the registry.
+// Lines that cannot be parsed are skipped if the registry is from a newer metadata version.
+fn read_registry(filename: &str) -> Result<Vec<Derivation>, String> {
+    let file = File::open(filename).map_err(|e| format!("Error opening {}: {}", filename, e))?;
+    let mut result = Vec::new();
+    let mut version = 1;
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Error reading {} line {}: {}", filename, i + 1, e))?;
+        if let Some(value) = line.strip_prefix(HEADER).and_then(|rest| rest.strip_prefix(" version ")) {
+            version = GraphName::parse_metadata_version(value).map_err(|e| format!("{} line {}: {}", filename, i + 1, e))?;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match Derivation::parse(&line) {
+            Ok(derivation) => result.push(derivation),
+            Err(e) if version > GraphName::METADATA_VERSION => {
+                warn!("{} line {}: skipped a derivation from metadata version {}: {}", filename, i + 1, version, e);
+            }
+            Err(e) => return Err(format!("{} line {}: {}", filename, i + 1, e)),
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the graph names that appear in the registry.
+pub fn known_names(filename: &str) -> Result<BTreeSet<String>, String> {
+    let derivations = read_registry(filename)?;
+    Ok(derivations.into_iter().flat_map(|derivation| [derivation.from, derivation.to]).collect())
+}
+
+/// Returns the relationships from all derivations in the registry.
+pub fn relationships(filename: &str) -> Result<GraphName, String> {
+    let mut result = GraphName::default();
+    for derivation in read_registry(filename)? {
+        result.add_relationship(derivation.relationship, &derivation.to, &derivation.from);
+    }
+    Ok(result)
+}
+
+// Returns the derivations leading to the graph in depth-first order, with the depth of each derivation.
+fn ancestors<'a>(derivations: &'a [Derivation], name: &str) -> Vec<(usize, &'a Derivation)> {
+    derivation_tree(derivations, name, |derivation| (&derivation.to, &derivation.from))
+}
+
+// Returns the derivations starting from the graph in depth-first order, with the depth of each derivation.
+fn descendants<'a>(derivations: &'a [Derivation], name: &str) -> Vec<(usize, &'a Derivation)> {
+    derivation_tree(derivations, name, |derivation| (&derivation.from, &derivation.to))
+}
+
+// Follows the derivations from the graph, using `ends` to determine the (current, next) graphs of each derivation.
+// Each derivation is followed by the derivations continuing from it, and each graph is expanded at most once.
+fn derivation_tree<'a>(derivations: &'a [Derivation], name: &str, ends: fn(&Derivation) -> (&str, &str)) -> Vec<(usize, &'a Derivation)> {
+    let mut result = Vec::new();
+    let mut expanded: BTreeSet<String> = BTreeSet::new();
+    expanded.insert(String::from(name));
+    let mut stack: Vec<(usize, &Derivation)> = Vec::new();
+    let mut next: Option<(usize, &str)> = Some((0, name));
+    loop {
+        // Push in reverse order to process the derivations in registry order.
+        if let Some((depth, curr)) = next {
+            stack.extend(derivations.iter().rev().filter(|derivation| ends(derivation).0 == curr).map(|derivation| (depth + 1, derivation)));
+        }
+        let Some((depth, derivation)) = stack.pop() else {
+            break;
+        };
+        result.push((depth, derivation));
+        let to = ends(derivation).1;
+        next = expanded.insert(String::from(to)).then_some((depth, to));
+    }
+    result
+}
+
+//-----------------------------------------------------------------------------
+
+enum Command {
+    Append(Vec<Derivation>),
+    Tree(String),
+    Header(String),
+}
+
+struct Config {
+    registry: String,
+    command: Command,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!(
+            "Usage: {} registry derive [options] --from A --to B --op OPERATION\n       {} registry import [options] --source GRAPH --format FORMAT REPORT\n       {} registry tree [options] NAME\n       {} registry header [options] NAME",
+            &program, &program, &program, &program
+        );
+
+        let mut opts = Options::new();
+        opts.optopt("r", "registry", "registry file (default: pggname.registry)", "FILE");
+        opts.optopt("", "from", "name of the parent graph (derive)", "NAME");
+        opts.optopt("", "to", "name of the derived graph (derive)", "NAME");
+        opts.optopt("", "op", "operation that produced the derived graph (derive)", "OPERATION");
+        opts.optopt("", "relation", "relationship of the derived graph to the parent: subgraph or translation (derive)", "RELATION");
+        opts.optopt("", "source", "source graph of the extracted subgraphs as a name, an alias, or a file (import)", "GRAPH");
+        opts.optopt("", "format", "report format: vg-chunk (BED from vg chunk) or odgi-extract (import)", "FORMAT");
+        opts.optmulti("", "manifest", "resolve graph name aliases using the manifest (may repeat)", "FILE");
+        let usage = |opts: &Options| -> ! {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        };
+        if args.len() < 3 {
+            usage(&opts);
+        }
+        let matches = opts.parse(&args[3..]).map_err(|e| e.to_string())?;
+        let registry = matches.opt_str("registry").unwrap_or(String::from("pggname.registry"));
+        let resolver = NameResolver::new(Some(&registry), &matches.opt_strs("manifest"))?;
+
+        let command = match args[2].as_str() {
+            "derive" => {
+                if !matches.free.is_empty() {
+                    usage(&opts);
+                }
+                let from = matches.opt_str("from").ok_or(String::from("Missing --from"))?;
+                let to = matches.opt_str("to").ok_or(String::from("Missing --to"))?;
+                let operation = matches.opt_str("op").ok_or(String::from("Missing --op"))?;
+                if operation.is_empty() || operation.contains(['\t', '\n']) {
+                    return Err(format!("Invalid operation: {:?}", operation));
+                }
+                let relationship = match matches.opt_str("relation") {
+                    None => relationship_for(&operation)
+                        .ok_or(format!("Unknown operation {}; specify the relationship with --relation", operation))?,
+                    Some(relation) => relation.parse()?,
+                };
+                let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_err(|e| e.to_string())?.as_secs();
+                Command::Append(vec![Derivation { from: resolver.resolve(&from)?, to: resolver.resolve(&to)?, operation, relationship, timestamp }])
+            }
+            "import" => {
+                if matches.free.len() != 1 {
+                    usage(&opts);
+                }
+                let report = &matches.free[0];
+                let source = matches.opt_str("source").ok_or(String::from("Missing --source"))?;
+                let format: ReportFormat = matches.opt_str("format").ok_or(String::from("Missing --format"))?.parse()?;
+                let from = if Path::new(&source).exists() { resolver.resolve_file(&source)? } else { resolver.resolve(&source)? };
+                let file = File::open(report).map_err(|e| format!("Error opening {}: {}", report, e))?;
+                let graphs = extract::parse_report(BufReader::new(file), format).map_err(|e| format!("{}: {}", report, e))?;
+                let directory = Path::new(report).parent().unwrap_or(Path::new(""));
+                let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_err(|e| e.to_string())?.as_secs();
+                let mut derivations = Vec::new();
+                for graph in graphs {
+                    let path = Path::new(&graph.file);
+                    let filename = if path.is_relative() && !path.exists() && directory.join(path).exists() {
+                        directory.join(path).to_string_lossy().into_owned()
+                    } else {
+                        graph.file.clone()
+                    };
+                    let to = resolver.resolve_file(&filename)?;
+                    if to == from {
+                        warn!("{}: {} covers the entire source graph", report, graph.file);
+                        continue;
+                    }
+                    let operation = format!("{} {}", format.operation(), graph.region);
+                    derivations.push(Derivation { from: from.clone(), to, operation, relationship: RelationshipKind::Subgraph, timestamp });
+                }
+                info!("Importing {} subgraph derivations from {}", derivations.len(), report);
+                Command::Append(derivations)
+            }
+            "tree" | "header" => {
+                if matches.free.len() != 1 {
+                    usage(&opts);
+                }
+                let name = resolver.resolve(&matches.free[0])?;
+                if args[2] == "tree" { Command::Tree(name) } else { Command::Header(name) }
+            }
+            _ => usage(&opts),
+        };
+
+        Ok(Config { registry, command })
+    }
+}
+
+//-----------------------------------------------------------------------------
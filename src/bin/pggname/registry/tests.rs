@@ -0,0 +1,120 @@
+use super::*;
+
+use std::fs;
+
+//-----------------------------------------------------------------------------
+
+// A stable graph name consisting of the given digit.
+fn name(digit: char) -> String {
+    std::iter::repeat_n(digit, GraphName::STABLE_NAME_LEN).collect()
+}
+
+fn derivation(from: char, to: char, operation: &str, relationship: RelationshipKind) -> Derivation {
+    Derivation { from: name(from), to: name(to), operation: String::from(operation), relationship, timestamp: 1760000000 }
+}
+
+// Derivations: 1 -> 2 (chop), 2 -> 3 (clip), 2 -> 4 (subset), 4 -> 2 (normalize).
+fn derivations() -> Vec<Derivation> {
+    vec![
+        derivation('1', '2', "chop", RelationshipKind::Translation),
+        derivation('2', '3', "clip", RelationshipKind::Subgraph),
+        derivation('2', '4', "subset", RelationshipKind::Subgraph),
+        derivation('4', '2', "normalize", RelationshipKind::Translation),
+    ]
+}
+
+// Writes the lines to a temporary registry and returns the filename.
+fn write_registry(test: &str, lines: &[String]) -> String {
+    let filename = std::env::temp_dir().join(format!("pggname-test-registry-{}-{}", test, std::process::id()));
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    fs::write(&filename, contents).unwrap();
+    filename.to_string_lossy().into_owned()
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn operations() {
+    for operation in ["clip", "extract", "subset"] {
+        assert_eq!(relationship_for(operation), Some(RelationshipKind::Subgraph), "Wrong relationship for {}", operation);
+    }
+    for operation in ["chop", "merge", "normalize", "renumber"] {
+        assert_eq!(relationship_for(operation), Some(RelationshipKind::Translation), "Wrong relationship for {}", operation);
+    }
+    assert_eq!(relationship_for("chunk"), None, "Found a default relationship for an unknown operation");
+}
+
+#[test]
+fn parse_derivation() {
+    for original in derivations() {
+        let line = original.to_string();
+        assert_eq!(Derivation::parse(&line), Ok(original), "Wrong derivation for {}", line);
+    }
+
+    let uri = GraphName::uri_for(&name('1')).unwrap();
+    let parsed = Derivation::parse(&format!("{}\t{}\tchunk chr1:0-100\tSG\t0", uri, name('2')));
+    assert_eq!(parsed.map(|derivation| derivation.from), Ok(name('1')), "Wrong parent name from a URI");
+
+    let valid = [name('1'), name('2'), String::from("clip"), String::from("SG"), String::from("0")];
+    for (field, value) in [(0, "A"), (3, "PV"), (4, "now")] {
+        let mut fields = valid.clone();
+        fields[field] = String::from(value);
+        assert!(Derivation::parse(&fields.join("\t")).is_err(), "Accepted an invalid field {}: {}", field, value);
+    }
+    assert!(Derivation::parse(&valid[..4].join("\t")).is_err(), "Accepted a derivation with 4 fields");
+}
+
+#[test]
+fn read_and_query() {
+    let mut lines = vec![format!("{} version {}", HEADER, GraphName::METADATA_VERSION), String::from("# comment"), String::new()];
+    lines.extend(derivations().iter().map(|derivation| derivation.to_string()));
+    let filename = write_registry("read", &lines);
+
+    assert_eq!(read_registry(&filename), Ok(derivations()), "Wrong derivations");
+    let expected: BTreeSet<String> = ['1', '2', '3', '4'].into_iter().map(name).collect();
+    assert_eq!(known_names(&filename), Ok(expected), "Wrong known names");
+
+    let result = relationships(&filename).unwrap();
+    assert!(result.translation_iter().eq([(name('2').as_str(), name('1').as_str()), (name('2').as_str(), name('4').as_str())]), "Wrong translations");
+    assert!(result.subgraph_iter().eq([(name('3').as_str(), name('2').as_str()), (name('4').as_str(), name('2').as_str())]), "Wrong subgraphs");
+
+    let _ = fs::remove_file(&filename);
+}
+
+#[test]
+fn registry_versions() {
+    let invalid = format!("{}\t{}\tclip\tXX\t0", name('1'), name('2'));
+    let valid = derivations()[0].to_string();
+
+    // Invalid lines are errors in current and unversioned registries.
+    let filename = write_registry("current", &[valid.clone(), invalid.clone()]);
+    let result = read_registry(&filename);
+    assert!(result.as_ref().is_err_and(|e| e.ends_with("line 2: Invalid relationship: XX")), "Accepted an invalid line: {:?}", result);
+    let _ = fs::remove_file(&filename);
+
+    // Invalid lines are skipped in registries from a newer version.
+    let header = format!("{} version {}", HEADER, GraphName::METADATA_VERSION + 1);
+    let filename = write_registry("newer", &[header, invalid, valid]);
+    assert_eq!(read_registry(&filename), Ok(vec![derivations()[0].clone()]), "Wrong derivations from a newer registry");
+    let _ = fs::remove_file(&filename);
+}
+
+#[test]
+fn derivation_trees() {
+    let derivations = derivations();
+    let tree = |result: Vec<(usize, &Derivation)>| -> Vec<(usize, String)> {
+        result.into_iter().map(|(depth, derivation)| (depth, derivation.operation.clone())).collect()
+    };
+    let expected = |items: &[(usize, &str)]| -> Vec<(usize, String)> {
+        items.iter().map(|&(depth, operation)| (depth, String::from(operation))).collect()
+    };
+
+    // Each graph is expanded once, so the cycle between 2 and 4 terminates.
+    assert_eq!(tree(descendants(&derivations, &name('1'))), expected(&[(1, "chop"), (2, "clip"), (2, "subset"), (3, "normalize")]), "Wrong descendants of 1");
+    assert_eq!(tree(ancestors(&derivations, &name('3'))), expected(&[(1, "clip"), (2, "chop"), (2, "normalize"), (3, "subset")]), "Wrong ancestors of 3");
+    assert!(ancestors(&derivations, &name('1')).is_empty(), "Found ancestors for the root");
+    assert!(descendants(&derivations, &name('5')).is_empty(), "Found descendants for an unknown graph");
+}
+
+//-----------------------------------------------------------------------------
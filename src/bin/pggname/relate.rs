@@ -0,0 +1,76 @@
+//! The `relate` subcommand: describing the relationship between two graphs.
+//!
+//! Uses the relationships recorded in the registry (see the `registry` subcommand) to determine whether graph A is the same as graph B, a subgraph of it, or a graph that can be translated to it.
+//! Prints the verdict and the chain of relationships as evidence.
+//! The exit status is 1 if graph A is not compatible with graph B.
+//!
+//! The graph names can also be aliases or name prefixes (see [`crate::resolve`]).
+//! They are resolved using the names in the registry and in the manifests given with `--manifest`.
+//! Names that cannot be resolved are used as they are, while an ambiguous alias or prefix is an error.
+
+use crate::registry;
+use crate::resolve::NameResolver;
+
+use getopts::Options;
+
+use pggname::GraphName;
+
+use std::path::Path;
+use std::process;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    let mut from = GraphName::new(config.from.clone());
+    if Path::new(&config.registry).exists() {
+        from.add_relationships(&registry::relationships(&config.registry)?);
+    }
+    let to = GraphName::new(config.to.clone());
+
+    let compatibility = from.compatibility(&to);
+    println!("A: {}", config.from);
+    println!("B: {}", config.to);
+    println!("Verdict: {}", compatibility);
+    println!();
+    print!("{}", from.describe_relationship(&to, "graph A", "graph B"));
+
+    if !compatibility.is_compatible() {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    registry: String,
+    from: String,
+    to: String,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} relate [options] A B", &program);
+
+        let mut opts = Options::new();
+        opts.optopt("r", "registry", "registry file (default: pggname.registry)", "FILE");
+        opts.optmulti("", "manifest", "resolve graph name aliases using the manifest (may repeat)", "FILE");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.free.len() != 2 {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let registry = matches.opt_str("registry").unwrap_or(String::from("pggname.registry"));
+        let resolver = NameResolver::new(Some(&registry), &matches.opt_strs("manifest"))?;
+        let from = resolver.resolve_or_literal(&matches.free[0])?;
+        let to = resolver.resolve_or_literal(&matches.free[1])?;
+
+        Ok(Config { registry, from, to })
+    }
+}
+
+//-----------------------------------------------------------------------------
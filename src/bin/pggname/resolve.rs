@@ -0,0 +1,138 @@
+//! Resolving graph names given on the command line.
+//!
+//! A graph name can be given as a stable graph name, a graph name URI, an alias, or a prefix of a stable name.
+//! Aliases and prefixes are resolved using the names known from the registry (see the `registry` subcommand) and from manifest files (see the `check` subcommand).
+//! The aliases of a manifest entry are the filename, the filename without the directory, and the filename without the directory and the extensions.
+//! For example, entry `NAME  data/example.gfa.gz` has aliases `data/example.gfa.gz`, `example.gfa.gz`, and `example`.
+//!
+//! Aliases take precedence over prefixes.
+//! It is an error if an alias or a prefix matches more than one graph name.
+//! Commands that also accept other graph names, such as `lift-gaf` and `relate`, use names that cannot be resolved as they are.
+//!
+//! Graph files listed in a manifest are resolved using the manifest entry for the same path, which avoids recomputing their names.
+//! Other files are named by reading them, even if their aliases match a manifest entry.
+
+use crate::{check, registry};
+
+use pggname::GraphName;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Graph names and aliases known from the registry and manifest files.
+#[derive(Clone, Debug, Default)]
+pub struct NameResolver {
+    names: BTreeSet<String>,
+    aliases: BTreeMap<String, BTreeSet<String>>,
+    // Graph names for the files listed in the manifests, by normalized path.
+    files: BTreeMap<PathBuf, BTreeSet<String>>,
+}
+
+impl NameResolver {
+    /// Creates a resolver using the names in the registry and the manifests.
+    ///
+    /// A missing registry file is not an error, as the registry is only created by `registry derive`.
+    pub fn new(registry: Option<&str>, manifests: &[String]) -> Result<Self, String> {
+        let mut result = NameResolver::default();
+        if let Some(filename) = registry && Path::new(filename).exists() {
+            result.names.extend(registry::known_names(filename)?);
+        }
+        for manifest in manifests.iter() {
+            for record in check::read_manifest(manifest)? {
+                for alias in Self::aliases_for(&record.path) {
+                    result.aliases.entry(alias).or_default().insert(record.name.clone());
+                }
+                result.files.entry(Self::normalize_path(&record.path)).or_default().insert(record.name.clone());
+                result.names.insert(record.name);
+            }
+        }
+        Ok(result)
+    }
+
+    // Returns the aliases for a manifest entry with the given filename.
+    fn aliases_for(filename: &str) -> Vec<String> {
+        let mut result = vec![String::from(filename)];
+        if let Some(base) = Path::new(filename).file_name().and_then(|name| name.to_str()) {
+            result.push(String::from(base));
+            if let Some((stem, _)) = base.split_once('.') && !stem.is_empty() {
+                result.push(String::from(stem));
+            }
+        }
+        result
+    }
+
+    // Returns the canonical form of the path if the file exists, or the path as given otherwise.
+    fn normalize_path(filename: &str) -> PathBuf {
+        fs::canonicalize(filename).unwrap_or_else(|_| PathBuf::from(filename))
+    }
+
+    /// Returns the stable graph name for the given stable name, URI, alias, or name prefix.
+    pub fn resolve(&self, name: &str) -> Result<String, String> {
+        match self.lookup(name)? {
+            Some(resolved) => Ok(resolved),
+            None if name.contains(':') => GraphName::parse_uri(name),
+            None => Err(format!("Cannot resolve graph name {}: not a stable name, a URI, a known alias, or a prefix of a known name", name)),
+        }
+    }
+
+    /// Returns the stable graph name for the given stable name, URI, alias, or name prefix, or the name itself if it cannot be resolved.
+    ///
+    /// This is for commands that also accept graph names that are not stable names.
+    /// It is still an error if an alias or a prefix matches more than one graph name.
+    pub fn resolve_or_literal(&self, name: &str) -> Result<String, String> {
+        Ok(self.lookup(name)?.unwrap_or_else(|| String::from(name)))
+    }
+
+    // Returns the resolved name, `None` if the name cannot be resolved, or an error if the name is ambiguous.
+    fn lookup(&self, name: &str) -> Result<Option<String>, String> {
+        if GraphName::is_stable_name(name) {
+            return Ok(Some(String::from(name)));
+        }
+        if name.contains(':') {
+            return Ok(GraphName::parse_uri(name).ok());
+        }
+
+        if let Some(names) = self.aliases.get(name) {
+            return Self::unique(name, "alias", names.iter()).map(Some);
+        }
+        let prefix = name.to_ascii_lowercase();
+        if !prefix.is_empty() && prefix.bytes().all(|c| c.is_ascii_hexdigit()) {
+            let matches: Vec<&String> = self.names.iter().filter(|candidate| candidate.starts_with(&prefix)).collect();
+            if !matches.is_empty() {
+                return Self::unique(name, "name prefix", matches.into_iter()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the stable graph name for the given graph file.
+    ///
+    /// Uses the manifest entry for the same file, if there is one, and computes the name from the file otherwise.
+    /// Paths are compared after resolving symbolic links and relative components.
+    /// Aliases are not used, as files with the same base name in different directories are often different graphs.
+    pub fn resolve_file(&self, filename: &str) -> Result<String, String> {
+        if let Some(names) = self.files.get(&Self::normalize_path(filename)) {
+            return Self::unique(filename, "manifest entry", names.iter());
+        }
+        pggname::stable_name_from_file(filename).map_err(|e| format!("Cannot determine the name of {}: {}", filename, e))
+    }
+
+    // Returns the only name in the iterator, or an error listing the candidates.
+    fn unique<'a, I: Iterator<Item = &'a String>>(name: &str, kind: &str, names: I) -> Result<String, String> {
+        let candidates: Vec<&String> = names.collect();
+        if candidates.len() == 1 {
+            Ok(candidates[0].clone())
+        } else {
+            let candidates: Vec<&str> = candidates.into_iter().map(String::as_str).collect();
+            Err(format!("Ambiguous {} {}: matches {}", kind, name, candidates.join(", ")))
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
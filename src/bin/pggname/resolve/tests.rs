@@ -0,0 +1,101 @@
+use super::*;
+
+use gbz::support;
+
+//-----------------------------------------------------------------------------
+
+const FIRST: &str = "1f133f116e8dd98fc07a647a8954038c2bcf07a45759ba94718471fe34ed7a7c";
+const SECOND: &str = "1f13e10f3b362d8a4273059d9aea38a78bd71913418c3f3c9a2b5ea44e86de2c";
+const THIRD: &str = "7f4b28c71ceb808aebd8b8e9fe85e79d0d208ee263ffe9fcdef5ade20534ceb5";
+
+// A resolver with three names, where the first two share a prefix and the same alias.
+fn resolver() -> NameResolver {
+    let mut result = NameResolver::default();
+    for (name, filename) in [(FIRST, "data/example.gfa.gz"), (SECOND, "other/example.gbz"), (THIRD, "data/chopped.gfa")] {
+        for alias in NameResolver::aliases_for(filename) {
+            result.aliases.entry(alias).or_default().insert(String::from(name));
+        }
+        result.files.entry(NameResolver::normalize_path(filename)).or_default().insert(String::from(name));
+        result.names.insert(String::from(name));
+    }
+    result
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn aliases() {
+    assert_eq!(NameResolver::aliases_for("data/example.gfa.gz"), vec!["data/example.gfa.gz", "example.gfa.gz", "example"], "Wrong aliases with a directory");
+    assert_eq!(NameResolver::aliases_for("example"), vec!["example", "example"], "Wrong aliases without extensions");
+    assert_eq!(NameResolver::aliases_for(".hidden"), vec![".hidden", ".hidden"], "Wrong aliases for a hidden file");
+}
+
+#[test]
+fn resolve() {
+    let resolver = resolver();
+    for name in [FIRST, SECOND, THIRD] {
+        assert_eq!(resolver.resolve(name), Ok(String::from(name)), "Wrong resolution for a stable name");
+        let uri = GraphName::uri_for(name).unwrap();
+        assert_eq!(resolver.resolve(&uri), Ok(String::from(name)), "Wrong resolution for a URI");
+    }
+    assert_eq!(resolver.resolve("data/example.gfa.gz"), Ok(String::from(FIRST)), "Wrong resolution for a full alias");
+    assert_eq!(resolver.resolve("example.gbz"), Ok(String::from(SECOND)), "Wrong resolution for a file name");
+    assert_eq!(resolver.resolve("chopped"), Ok(String::from(THIRD)), "Wrong resolution for a stem");
+    assert_eq!(resolver.resolve("7F4B"), Ok(String::from(THIRD)), "Wrong resolution for an uppercase prefix");
+    assert_eq!(resolver.resolve("1f133"), Ok(String::from(FIRST)), "Wrong resolution for a unique prefix");
+
+    // Ambiguous and unknown names.
+    assert!(resolver.resolve("example").is_err(), "Resolved an ambiguous alias");
+    assert!(resolver.resolve("1f13").is_err(), "Resolved an ambiguous prefix");
+    assert!(resolver.resolve("missing").is_err(), "Resolved an unknown alias");
+    assert!(resolver.resolve("abcdef").is_err(), "Resolved an unknown prefix");
+    assert!(resolver.resolve("pggname:invalid").is_err(), "Resolved an invalid URI");
+}
+
+#[test]
+fn resolve_or_literal() {
+    let resolver = resolver();
+    assert_eq!(resolver.resolve_or_literal("chopped"), Ok(String::from(THIRD)), "Wrong resolution for an alias");
+    assert_eq!(resolver.resolve_or_literal("1f133"), Ok(String::from(FIRST)), "Wrong resolution for a prefix");
+    for name in ["missing", "abcdef", "pggname:invalid", "GRCh38"] {
+        assert_eq!(resolver.resolve_or_literal(name), Ok(String::from(name)), "Did not fall back to the literal name {}", name);
+    }
+    assert!(resolver.resolve_or_literal("example").is_err(), "Used an ambiguous alias as a literal name");
+    assert!(resolver.resolve_or_literal("1f13").is_err(), "Used an ambiguous prefix as a literal name");
+
+    // Without any known names, everything except stable names and URIs is used as is.
+    let empty = NameResolver::default();
+    assert_eq!(empty.resolve_or_literal("example"), Ok(String::from("example")), "Did not fall back with an empty resolver");
+    assert_eq!(empty.resolve_or_literal(FIRST), Ok(String::from(FIRST)), "Wrong resolution for a stable name");
+}
+
+#[test]
+fn resolve_file() {
+    let mut resolver = resolver();
+    let directory = std::env::temp_dir().join(format!("pggname-test-resolve-{}", std::process::id()));
+    fs::create_dir_all(directory.join("out")).unwrap();
+    let listed = directory.join("chopped.gfa");
+    let unlisted = directory.join("out").join("chopped.gfa");
+    let gfa = support::get_test_data("example.gfa");
+    fs::copy(&gfa, &listed).unwrap();
+    fs::copy(&gfa, &unlisted).unwrap();
+    let listed_name = listed.to_string_lossy().into_owned();
+    resolver.files.entry(NameResolver::normalize_path(&listed_name)).or_default().insert(String::from(THIRD));
+
+    // Listed files use the manifest entry, also through a different path.
+    let result = resolver.resolve_file(&listed_name);
+    let indirect = resolver.resolve_file(&directory.join("out").join("..").join("chopped.gfa").to_string_lossy());
+    assert_eq!(resolver.resolve_file("data/chopped.gfa"), Ok(String::from(THIRD)), "Wrong name for a listed file");
+
+    // Files with a matching alias are named by reading them.
+    let expected = pggname::stable_name_from_file(&gfa);
+    let by_alias = resolver.resolve_file(&unlisted.to_string_lossy());
+    let missing = resolver.resolve_file("out/example.gbz");
+    let _ = fs::remove_dir_all(&directory);
+    assert_eq!(result, Ok(String::from(THIRD)), "Wrong name for a listed file");
+    assert_eq!(indirect, Ok(String::from(THIRD)), "Wrong name for a listed file through a different path");
+    assert_eq!(by_alias, expected, "Used the alias of a different file");
+    assert!(missing.is_err(), "Used the alias of a missing file");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,451 @@
+//! The `serve-http` subcommand: a small HTTP service for names and relationships.
+//!
+//! This subcommand is only available with the `serve-http` feature.
+//! The service keeps a registry of relationships in memory and supports the following endpoints:
+//!
+//! * `POST /name`: computes the name of the GFA graph in the request body (optionally gzip-compressed).
+//! * `GET /name?path=FILE`: computes the name of a graph stored on the server under the directory given with `--allow-paths`.
+//!   The path is relative to that directory, and paths that resolve outside it as well as URLs are refused.
+//! * `POST /relationships`: adds the relationships in the GFA/GAF header lines in the request body to the registry.
+//! * `GET /reachable?from=A&to=B`: reports whether graph A is a subgraph of graph B and whether A can be translated to B.
+//!   Paths of relationships are limited to `--max-depth` steps, and an optional `max_depth` parameter can lower the limit.
+//!
+//! Responses are JSON objects.
+//! Errors are reported as `{"error": "..."}` with an appropriate status code.
+//!
+//! The request line and each header line are limited to 8 KiB, and a request may have at most 100 header lines.
+//! Longer headers are rejected with status 431.
+//! The request body is limited to `--max-size` bytes, both as received and after decompression.
+//! Each connection is handled in its own thread, and at most `--max-connections` connections are handled at the same time.
+//! Further connections are rejected with status 503.
+//! A connection that does not send or receive data for `--timeout` seconds is closed.
+
+use getopts::Options;
+
+use flate2::read::MultiGzDecoder;
+
+use pggname::GraphName;
+use pggname::name::RelationshipKind;
+use pggname::algorithms;
+use pggname::graph::{GraphInt, GraphStr};
+use pggname::seqcol::json_string;
+
+use std::borrow::Cow;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use std::{fs, process, thread};
+
+use tracing::{error, info, warn};
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Arc::new(Config::new(args)?);
+
+    let listener = TcpListener::bind(&config.bind)
+        .map_err(|e| format!("Error binding to {}: {}", config.bind, e))?;
+    info!("Listening on http://{}", config.bind);
+
+    let registry = Arc::new(Mutex::new(GraphName::default()));
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                error!("Error accepting a connection: {}", e);
+                continue;
+            }
+        };
+        let timeout = Some(config.timeout);
+        if let Err(e) = stream.set_read_timeout(timeout).and_then(|_| stream.set_write_timeout(timeout)) {
+            error!("Error setting timeouts for a connection: {}", e);
+            continue;
+        }
+        let Some(guard) = ConnectionGuard::new(&active, config.max_connections) else {
+            warn!("Rejecting a connection: {} connections are already active", config.max_connections);
+            if let Err(message) = write_response(&mut stream, &Response::error(503, "Too many connections")) {
+                error!("{}", message);
+            }
+            continue;
+        };
+        let config = Arc::clone(&config);
+        let registry = Arc::clone(&registry);
+        thread::spawn(move || {
+            let _guard = guard;
+            if let Err(message) = handle_connection(stream, &config, &registry) {
+                error!("{}", message);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+// Counts the active connections and releases a slot when dropped.
+struct ConnectionGuard {
+    active: Arc<AtomicUsize>,
+}
+
+impl ConnectionGuard {
+    // Returns a guard if there are fewer than `limit` active connections.
+    fn new(active: &Arc<AtomicUsize>, limit: usize) -> Option<Self> {
+        active.fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| (count < limit).then_some(count + 1)).ok()?;
+        Some(ConnectionGuard { active: Arc::clone(active) })
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    bind: String,
+    // Graphs stored under this directory can be named with `GET /name`.
+    allowed_root: Option<PathBuf>,
+    max_size: usize,
+    max_depth: usize,
+    max_connections: usize,
+    timeout: Duration,
+}
+
+impl Config {
+    const DEFAULT_BIND: &'static str = "127.0.0.1:8080";
+    const DEFAULT_MAX_SIZE: usize = 1 << 30;
+    const DEFAULT_MAX_DEPTH: usize = 64;
+    const DEFAULT_MAX_CONNECTIONS: usize = 16;
+    const DEFAULT_TIMEOUT: u64 = 30;
+
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} serve-http [options]", &program);
+
+        let mut opts = Options::new();
+        opts.optopt("", "bind", &format!("address to listen on (default: {})", Self::DEFAULT_BIND), "ADDR");
+        opts.optopt("", "allow-paths", "allow naming graphs stored on the server under this directory", "DIR");
+        opts.optopt("", "max-size", &format!("maximum request body size in bytes (default: {})", Self::DEFAULT_MAX_SIZE), "INT");
+        opts.optopt("", "max-depth", &format!("maximum number of relationships in a reachability path (default: {}, 0 for no limit)", Self::DEFAULT_MAX_DEPTH), "INT");
+        opts.optopt("", "max-connections", &format!("maximum number of connections handled at the same time (default: {})", Self::DEFAULT_MAX_CONNECTIONS), "INT");
+        opts.optopt("", "timeout", &format!("close idle connections after this many seconds (default: {})", Self::DEFAULT_TIMEOUT), "INT");
+        opts.optflag("h", "help", "print this help");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.opt_present("h") || !matches.free.is_empty() {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let bind = matches.opt_str("bind").unwrap_or(String::from(Self::DEFAULT_BIND));
+        let allowed_root = match matches.opt_str("allow-paths") {
+            Some(dir) => Some(fs::canonicalize(&dir).map_err(|e| format!("Invalid --allow-paths {}: {}", dir, e))?),
+            None => None,
+        };
+        let max_size = match matches.opt_str("max-size") {
+            Some(value) => value.parse::<usize>().map_err(|e| format!("Invalid --max-size: {}", e))?,
+            None => Self::DEFAULT_MAX_SIZE,
+        };
+        let max_depth = match matches.opt_str("max-depth") {
+            Some(value) => value.parse::<usize>().map_err(|e| format!("Invalid --max-depth: {}", e))?,
+            None => Self::DEFAULT_MAX_DEPTH,
+        };
+        let max_depth = if max_depth == 0 { usize::MAX } else { max_depth };
+        let max_connections = match matches.opt_str("max-connections") {
+            Some(value) => value.parse::<usize>().map_err(|e| format!("Invalid --max-connections: {}", e))?,
+            None => Self::DEFAULT_MAX_CONNECTIONS,
+        };
+        if max_connections == 0 {
+            return Err(String::from("--max-connections must be positive"));
+        }
+        let timeout = match matches.opt_str("timeout") {
+            Some(value) => value.parse::<u64>().map_err(|e| format!("Invalid --timeout: {}", e))?,
+            None => Self::DEFAULT_TIMEOUT,
+        };
+        if timeout == 0 {
+            return Err(String::from("--timeout must be positive"));
+        }
+        let timeout = Duration::from_secs(timeout);
+
+        Ok(Config { bind, allowed_root, max_size, max_depth, max_connections, timeout })
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn parameter(&self, key: &str) -> Option<&str> {
+        self.query.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn ok(fields: &[(&str, String)]) -> Self {
+        let fields: Vec<String> = fields.iter().map(|(key, value)| format!("{}:{}", json_string(key), value)).collect();
+        Response { status: 200, body: format!("{{{}}}", fields.join(",")) }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Response { status, body: format!("{{\"error\":{}}}", json_string(message)) }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            413 => "Payload Too Large",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, config: &Config, registry: &Mutex<GraphName>) -> Result<(), String> {
+    let response = match read_request(BufReader::new(&stream), config) {
+        Ok(request) => route(&request, config, registry),
+        Err(response) => response,
+    };
+    write_response(&mut stream, &response)
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response) -> Result<(), String> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status, response.reason(), response.body.len(), response.body
+    ).map_err(|e| format!("Error writing the response: {}", e))
+}
+
+// Returns an error response for a read error, treating timeouts as such.
+fn read_error(e: io::Error) -> Response {
+    match e.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Response::error(408, "Timed out while reading the request"),
+        _ => Response::error(400, &e.to_string()),
+    }
+}
+
+// Maximum length of the request line or a header line in bytes.
+const MAX_LINE_LEN: usize = 8192;
+
+// Maximum number of header lines in a request.
+const MAX_HEADERS: usize = 100;
+
+// Reads a line of at most `MAX_LINE_LEN` bytes into the buffer.
+fn read_line<R: BufRead>(reader: &mut R, line: &mut String) -> Result<(), Response> {
+    line.clear();
+    reader.by_ref().take(MAX_LINE_LEN as u64 + 1).read_line(line).map_err(read_error)?;
+    if line.len() > MAX_LINE_LEN {
+        return Err(Response::error(431, &format!("Request line or header exceeds {} bytes", MAX_LINE_LEN)));
+    }
+    Ok(())
+}
+
+fn read_request<R: BufRead>(mut reader: R, config: &Config) -> Result<Request, Response> {
+    let mut line = String::new();
+    read_line(&mut reader, &mut line)?;
+    let mut fields = line.split_whitespace();
+    let (method, target) = match (fields.next(), fields.next()) {
+        (Some(method), Some(target)) => (String::from(method), String::from(target)),
+        _ => return Err(Response::error(400, "Invalid request line")),
+    };
+
+    let mut content_length = 0;
+    let mut headers = 0;
+    loop {
+        read_line(&mut reader, &mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        headers += 1;
+        if headers > MAX_HEADERS {
+            return Err(Response::error(431, &format!("Request has more than {} headers", MAX_HEADERS)));
+        }
+        if let Some((name, value)) = header.split_once(':') && name.eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse::<usize>().map_err(|_| Response::error(400, "Invalid Content-Length"))?;
+        }
+    }
+    if content_length > config.max_size {
+        return Err(Response::error(413, &format!("Request body exceeds {} bytes", config.max_size)));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(read_error)?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query = query.split('&').filter(|pair| !pair.is_empty()).map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (percent_decode(key), percent_decode(value))
+    }).collect();
+
+    Ok(Request { method, path: String::from(path), query, body })
+}
+
+fn route(request: &Request, config: &Config, registry: &Mutex<GraphName>) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/name") => {
+            let data = match decompress(&request.body, config.max_size) {
+                Ok(data) => data,
+                Err(response) => return response,
+            };
+            match name_from_bytes(&data) {
+                Ok(name) => Response::ok(&[("name", json_string(&name))]),
+                Err(message) => Response::error(400, &message),
+            }
+        }
+        ("GET", "/name") => {
+            let Some(root) = &config.allowed_root else {
+                return Response::error(403, "Naming server-side files is not enabled");
+            };
+            let Some(path) = request.parameter("path") else {
+                return Response::error(400, "Missing parameter: path");
+            };
+            let resolved = match resolve_path(root, path) {
+                Ok(resolved) => resolved,
+                Err(response) => return response,
+            };
+            match pggname::stable_name_from_file(&resolved) {
+                Ok(name) => Response::ok(&[("name", json_string(&name)), ("path", json_string(path))]),
+                Err(message) => Response::error(400, &message),
+            }
+        }
+        ("POST", "/relationships") => {
+            let lines: Vec<String> = String::from_utf8_lossy(&request.body).lines()
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect();
+            match GraphName::from_header_lines(&lines) {
+                Ok(name) => {
+                    let mut registry = lock_registry(registry);
+                    registry.add_relationships(&name);
+                    let subgraph = registry.subgraph_iter().count();
+                    let translation = registry.translation_iter().count();
+                    Response::ok(&[("subgraph", subgraph.to_string()), ("translation", translation.to_string())])
+                }
+                Err(message) => Response::error(400, &message),
+            }
+        }
+        ("GET", "/reachable") => {
+            let (Some(from), Some(to)) = (request.parameter("from"), request.parameter("to")) else {
+                return Response::error(400, "Missing parameters: from, to");
+            };
+            let max_depth = match request.parameter("max_depth").map(str::parse::<usize>) {
+                Some(Ok(max_depth)) => max_depth.min(config.max_depth),
+                Some(Err(e)) => return Response::error(400, &format!("Invalid parameter max_depth: {}", e)),
+                None => config.max_depth,
+            };
+            // Query the registry directly instead of copying it.
+            let registry = lock_registry(registry);
+            let subgraph = registry.find_path_within(from, to, RelationshipKind::Subgraph, max_depth).is_some();
+            let translation = registry.find_path_within(from, to, RelationshipKind::Translation, max_depth).is_some();
+            Response::ok(&[
+                ("from", json_string(from)),
+                ("to", json_string(to)),
+                ("subgraph", subgraph.to_string()),
+                ("translation", translation.to_string()),
+            ])
+        }
+        (_, "/name") | (_, "/relationships") | (_, "/reachable") => Response::error(405, "Method not allowed"),
+        _ => Response::error(404, "Not found"),
+    }
+}
+
+// Locks the registry, recovering it if a thread panicked while holding the lock.
+// Each update adds complete relationships, so the registry remains usable.
+fn lock_registry(registry: &Mutex<GraphName>) -> MutexGuard<'_, GraphName> {
+    registry.lock().unwrap_or_else(|poisoned| {
+        warn!("Recovering the registry after a panic in another connection");
+        registry.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+// Resolves a path relative to the allowed root directory.
+// URLs and paths that resolve outside the root, including through symbolic links, are refused.
+fn resolve_path(root: &Path, path: &str) -> Result<PathBuf, Response> {
+    if path.contains("://") {
+        return Err(Response::error(403, "URLs are not allowed"));
+    }
+    let resolved = fs::canonicalize(root.join(path))
+        .map_err(|e| Response::error(404, &format!("Cannot access {}: {}", path, e)))?;
+    if !resolved.starts_with(root) {
+        return Err(Response::error(403, "Path is outside the allowed directory"));
+    }
+    Ok(resolved)
+}
+
+//-----------------------------------------------------------------------------
+
+// Decompresses gzip-compressed data, which may be at most `max_size` bytes after decompression.
+fn decompress(data: &[u8], max_size: usize) -> Result<Cow<'_, [u8]>, Response> {
+    if !data.starts_with(&[0x1F, 0x8B]) {
+        return Ok(Cow::Borrowed(data));
+    }
+    let mut decompressed = Vec::new();
+    MultiGzDecoder::new(data).take(max_size as u64 + 1).read_to_end(&mut decompressed)
+        .map_err(|e| Response::error(400, &format!("Error decompressing the input: {}", e)))?;
+    if decompressed.len() > max_size {
+        return Err(Response::error(413, &format!("Decompressed request body exceeds {} bytes", max_size)));
+    }
+    Ok(Cow::Owned(decompressed))
+}
+
+// Computes the name of a GFA graph, using integer identifiers if possible.
+fn name_from_bytes(data: &[u8]) -> Result<String, String> {
+    if let Ok(graph) = algorithms::parse_gfa_bytes::<GraphInt>(data) {
+        return Ok(pggname::stable_name(&graph));
+    }
+    let graph: GraphStr = algorithms::parse_gfa_bytes(data)?;
+    Ok(pggname::stable_name(&graph))
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => result.push(b' '),
+            b'%' if let Some(byte) = bytes.get(i + 1..i + 3).and_then(hex_byte) => {
+                result.push(byte);
+                i += 2;
+            }
+            c => result.push(c),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,109 @@
+use super::*;
+
+use std::panic::{self, AssertUnwindSafe};
+
+//-----------------------------------------------------------------------------
+
+const GFA: &str = "S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n";
+
+fn config(allowed_root: Option<PathBuf>) -> Config {
+    Config {
+        bind: String::from(Config::DEFAULT_BIND),
+        allowed_root,
+        max_size: 1024,
+        max_depth: Config::DEFAULT_MAX_DEPTH,
+        max_connections: Config::DEFAULT_MAX_CONNECTIONS,
+        timeout: Duration::from_secs(Config::DEFAULT_TIMEOUT),
+    }
+}
+
+fn parse(request: &str) -> Result<Request, Response> {
+    read_request(request.as_bytes(), &config(None))
+}
+
+// Returns the status of the error response, or 200 if the request was parsed.
+fn status(result: Result<Request, Response>) -> u16 {
+    result.map_or_else(|response| response.status, |_| 200)
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn request_parsing() {
+    let request = parse("GET /reachable?from=a%20b&to=c+d&flag HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+    assert_eq!((request.method.as_str(), request.path.as_str()), ("GET", "/reachable"), "Wrong method or path");
+    assert_eq!(request.parameter("from"), Some("a b"), "Wrong percent-decoded parameter");
+    assert_eq!(request.parameter("to"), Some("c d"), "Wrong parameter with a plus sign");
+    assert_eq!(request.parameter("flag"), Some(""), "Wrong parameter without a value");
+    assert!(request.body.is_empty(), "Found a body in a GET request");
+
+    let request = parse("POST /name HTTP/1.1\r\ncontent-length: 5\r\n\r\nS\t1\tA").unwrap();
+    assert_eq!(request.body, b"S\t1\tA", "Wrong request body");
+
+    assert_eq!(status(parse("GET\r\n\r\n")), 400, "Accepted an invalid request line");
+    assert_eq!(status(parse("POST /name HTTP/1.1\r\nContent-Length: x\r\n\r\n")), 400, "Accepted an invalid Content-Length");
+    assert_eq!(status(parse("POST /name HTTP/1.1\r\nContent-Length: 10\r\n\r\nS\t1")), 400, "Accepted a truncated body");
+    assert_eq!(status(parse("POST /name HTTP/1.1\r\nContent-Length: 2048\r\n\r\n")), 413, "Accepted a body over the limit");
+}
+
+#[test]
+fn header_limits() {
+    let long_target = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE_LEN));
+    assert_eq!(status(parse(&long_target)), 431, "Accepted a request line over the limit");
+    let long_header = format!("GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n", "a".repeat(MAX_LINE_LEN));
+    assert_eq!(status(parse(&long_header)), 431, "Accepted a header line over the limit");
+
+    let headers = |count: usize| format!("GET / HTTP/1.1\r\n{}\r\n", "X-Header: value\r\n".repeat(count));
+    assert_eq!(status(parse(&headers(MAX_HEADERS))), 200, "Rejected the maximum number of headers");
+    assert_eq!(status(parse(&headers(MAX_HEADERS + 1))), 431, "Accepted too many headers");
+}
+
+#[test]
+fn server_paths() {
+    let dir = std::env::temp_dir().join(format!("pggname-test-serve-http-{}", std::process::id()));
+    let root = dir.join("root");
+    fs::create_dir_all(root.join("graphs")).unwrap();
+    fs::write(root.join("graphs/graph.gfa"), GFA).unwrap();
+    fs::write(dir.join("outside.gfa"), GFA).unwrap();
+    let root = fs::canonicalize(&root).unwrap();
+
+    let resolved = resolve_path(&root, "graphs/graph.gfa");
+    assert_eq!(resolved.ok(), Some(root.join("graphs/graph.gfa")), "Wrong path inside the root");
+    for (path, expected) in [("../outside.gfa", 403), ("graphs/../../outside.gfa", 403), ("http://localhost/graph.gfa", 403), ("missing.gfa", 404)] {
+        assert_eq!(resolve_path(&root, path).map_err(|response| response.status).err(), Some(expected), "Wrong status for {}", path);
+    }
+    let outside = dir.join("outside.gfa");
+    assert!(resolve_path(&root, &outside.to_string_lossy()).is_err(), "Accepted an absolute path outside the root");
+
+    let registry = Mutex::new(GraphName::default());
+    let request = parse("GET /name?path=graphs/graph.gfa HTTP/1.1\r\n\r\n").unwrap();
+    let response = route(&request, &config(Some(root.clone())), &registry);
+    assert_eq!(response.status, 200, "Could not name a graph inside the root: {}", response.body);
+    let name = name_from_bytes(GFA.as_bytes()).unwrap();
+    assert!(response.body.contains(&name), "Wrong name in the response: {}", response.body);
+    let response = route(&request, &config(None), &registry);
+    assert_eq!(response.status, 403, "Named a server-side file without --allow-paths");
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn poisoned_registry() {
+    let registry = Mutex::new(GraphName::default());
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = registry.lock().unwrap();
+        panic!("Poisoning the registry");
+    }));
+    assert!(registry.is_poisoned(), "The registry was not poisoned");
+
+    let body = "H\tNM:Z:A\tSG:Z:A,B\n";
+    let request = parse(&format!("POST /relationships HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body)).unwrap();
+    let response = route(&request, &config(None), &registry);
+    assert_eq!(response.status, 200, "Could not add relationships after a panic: {}", response.body);
+    let request = parse("GET /reachable?from=A&to=B HTTP/1.1\r\n\r\n").unwrap();
+    let response = route(&request, &config(None), &registry);
+    assert!(response.body.contains("\"subgraph\":true"), "Wrong reachability after a panic: {}", response.body);
+    assert!(!registry.is_poisoned(), "The registry is still poisoned");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,103 @@
+//! The `subset` subcommand: naming the subgraph covered by a set of samples in a GBZ graph.
+//!
+//! Induces the subgraph touched by the paths of the selected samples with [`paths::sample_subgraph_gbz`] and computes its name.
+//! Writes GFA header lines with the name of the subgraph, the subgraph relationship to the full graph, and the relationships stored in the full graph.
+//! With `--gfa`, also writes the subgraph in the canonical GFA format preceded by the same header lines.
+
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::{Graph, GraphName, paths};
+use pggname::graph::GBZInt;
+
+use simple_sds::serialize;
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::process;
+
+use tracing::{info, warn};
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    let graph: GBZ = serialize::load_from(&config.graph_file)
+        .map_err(|e| format!("Error loading GBZ file {}: {}", config.graph_file, e))?;
+    let samples: Vec<&str> = config.samples.iter().map(String::as_str).collect();
+    let (subgraph, paths) = paths::sample_subgraph_gbz(&graph, &samples)?;
+    let stats = subgraph.statistics();
+    info!("Selected {} paths covering {} nodes from {} samples", paths, stats.nodes, samples.len());
+
+    let stored = GraphName::from_gbz(&graph);
+    let graph = GBZInt { graph };
+    let full = pggname::stable_name(&graph);
+    if let Some(name) = stored.name() && *name != full {
+        warn!("{}: stale graph name {} in the tags, computed {}", config.graph_file, name, full);
+    }
+    let sub = pggname::stable_name(&subgraph);
+    if sub == full {
+        warn!("The selected samples cover the entire graph");
+    }
+
+    let mut name = GraphName::new(sub.clone());
+    name.add_relationships(&stored);
+    if sub != full {
+        name.add_subgraph(&sub, &full);
+    }
+    let header = name.to_gfa_header_lines();
+    for line in header.iter() {
+        println!("{}", line);
+    }
+
+    if let Some(filename) = &config.gfa_file {
+        let file = File::create(filename).map_err(|e| format!("Error creating {}: {}", filename, e))?;
+        let mut writer = BufWriter::new(file);
+        for line in header.iter() {
+            writeln!(writer, "{}", line).map_err(|e| format!("Error writing {}: {}", filename, e))?;
+        }
+        subgraph.try_for_each_node(|bytes| writer.write_all(bytes).map_err(|e| format!("Error writing {}: {}", filename, e)))?;
+        writer.flush().map_err(|e| format!("Error writing {}: {}", filename, e))?;
+        info!("Wrote the subgraph to {}", filename);
+    }
+
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    graph_file: String,
+    samples: Vec<String>,
+    gfa_file: Option<String>,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} subset [options] --samples S1,S2,... graph.gbz", &program);
+
+        let mut opts = Options::new();
+        opts.optopt("", "samples", "comma-separated list of samples to select (required)", "LIST");
+        opts.optopt("", "gfa", "also write the subgraph in canonical GFA format to FILE", "FILE");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.free.len() != 1 || !matches.opt_present("samples") {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let graph_file = matches.free[0].clone();
+        let samples: Vec<String> = matches.opt_str("samples").unwrap_or_default()
+            .split(',').filter(|sample| !sample.is_empty()).map(String::from).collect();
+        if samples.is_empty() {
+            return Err(String::from("No samples selected"));
+        }
+        let gfa_file = matches.opt_str("gfa");
+
+        Ok(Config { graph_file, samples, gfa_file })
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,231 @@
+//! The `summary` subcommand: an identity card for a graph.
+//!
+//! Computes the name of the graph and prints it together with the short name, the URI, basic statistics, the relationships stored in the graph metadata, and how the name was computed.
+//! Relationships come from GBZ tags or GFA header lines, and their provenance is shown when available.
+//! If the metadata names the graph differently, the stored name is reported as stale.
+//!
+//! With `--markdown`, the card is a Markdown table and list suitable for data release notes and README files.
+
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::{Graph, GraphName, provenance};
+use pggname::algorithms::{self, NodeIds};
+use pggname::graph::{GraphInt, GraphStr, GBZInt, GBZStr};
+use pggname::name::RelationshipKind;
+
+use simple_sds::serialize;
+
+use std::process;
+use std::time::{Duration, UNIX_EPOCH};
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    let card = if GBZ::is_gbz(&config.graph_file) {
+        let graph: GBZ = serialize::load_from(&config.graph_file)
+            .map_err(|e| format!("Error loading GBZ file {}: {}", config.graph_file, e))?;
+        let metadata = GraphName::from_gbz(&graph);
+        if config.node_ids == NodeIds::String {
+            Card::new(&GBZStr { graph }, NodeIds::String, metadata, &config)
+        } else {
+            Card::new(&GBZInt { graph }, NodeIds::Integer, metadata, &config)
+        }
+    } else {
+        let metadata = algorithms::read_gfa_metadata(&config.graph_file)?;
+        let int_graph = if config.node_ids == NodeIds::String {
+            None
+        } else {
+            let result = algorithms::parse_gfa::<GraphInt, _>(algorithms::open_gfa(&config.graph_file)?);
+            if config.node_ids == NodeIds::Integer {
+                Some(result?)
+            } else {
+                result.ok()
+            }
+        };
+        match int_graph {
+            Some(graph) => Card::new(&graph, NodeIds::Integer, metadata, &config),
+            None => {
+                let graph = algorithms::parse_gfa::<GraphStr, _>(algorithms::open_gfa(&config.graph_file)?)?;
+                Card::new(&graph, NodeIds::String, metadata, &config)
+            }
+        }
+    };
+
+    if config.markdown {
+        print!("{}", card.to_markdown());
+    } else {
+        print!("{}", card.to_text());
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    graph_file: String,
+    node_ids: NodeIds,
+    markdown: bool,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} summary [options] graph.(gbz|gfa[.gz])", &program);
+
+        let mut opts = Options::new();
+        opts.optflag("i", "integer-ids", "use integer node identifiers");
+        opts.optflag("s", "string-ids", "use string node identifiers");
+        opts.optflag("", "markdown", "print the summary as Markdown");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.free.len() != 1 {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let graph_file = matches.free[0].clone();
+        let node_ids = match (matches.opt_present("i"), matches.opt_present("s")) {
+            (true, true) => return Err(String::from("Options -i and -s are mutually exclusive")),
+            (true, false) => NodeIds::Integer,
+            (false, true) => NodeIds::String,
+            (false, false) => NodeIds::Auto,
+        };
+        let markdown = matches.opt_present("markdown");
+
+        Ok(Config { graph_file, node_ids, markdown })
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+// A relationship stored in the graph metadata.
+struct Relationship {
+    description: &'static str,
+    from: String,
+    to: String,
+    provenance: Option<String>,
+}
+
+// The contents of the identity card.
+struct Card {
+    file: String,
+    name: String,
+    stored_name: Option<String>,
+    node_ids: NodeIds,
+    nodes: u64,
+    edges: u64,
+    sequence_len: u64,
+    relationships: Vec<Relationship>,
+}
+
+impl Card {
+    fn new<G: Graph>(graph: &G, node_ids: NodeIds, metadata: GraphName, config: &Config) -> Self {
+        let name = pggname::stable_name(graph);
+        let stats = graph.statistics();
+        let stored_name = metadata.name().filter(|stored| **stored != name).cloned();
+
+        let mut relationships = Vec::new();
+        let kinds = [
+            (RelationshipKind::Subgraph, "is a subgraph of", metadata.subgraph_iter().collect::<Vec<_>>()),
+            (RelationshipKind::Translation, "translates to", metadata.translation_iter().collect::<Vec<_>>()),
+        ];
+        for (kind, description, pairs) in kinds {
+            for (from, to) in pairs {
+                let provenance = metadata.provenance(kind, from, to).map(|provenance| {
+                    let mut result = String::from(provenance.confidence.as_str());
+                    if let Some(tool) = &provenance.tool {
+                        result.push_str(&format!(" by {}", tool));
+                    }
+                    if let Some(seconds) = provenance.timestamp {
+                        match UNIX_EPOCH.checked_add(Duration::from_secs(seconds)) {
+                            Some(time) => result.push_str(&format!(" at {}", provenance::timestamp(time))),
+                            None => result.push_str(&format!(" at {} seconds since the epoch", seconds)),
+                        }
+                    }
+                    result
+                });
+                relationships.push(Relationship { description, from: String::from(from), to: String::from(to), provenance });
+            }
+        }
+
+        Card {
+            file: config.graph_file.clone(),
+            name, stored_name, node_ids,
+            nodes: stats.nodes,
+            edges: stats.edges,
+            sequence_len: stats.sequence_len,
+            relationships,
+        }
+    }
+
+    // Returns the fields of the card as (label, value, is_code) triples.
+    fn fields(&self) -> Vec<(&'static str, String, bool)> {
+        let mut result = vec![
+            ("Name", self.name.clone(), true),
+            ("Short name", String::from(GraphName::short_name_for(&self.name)), true),
+            ("URI", GraphName::uri_for(&self.name).unwrap_or_default(), true),
+        ];
+        if let Some(stored) = &self.stored_name {
+            result.push(("Stale name in metadata", stored.clone(), true));
+        }
+        let node_ids = if self.node_ids == NodeIds::String { "string" } else { "integer" };
+        result.push(("Node IDs", String::from(node_ids), false));
+        result.push(("Nodes", self.nodes.to_string(), false));
+        result.push(("Edges", self.edges.to_string(), false));
+        result.push(("Sequence", format!("{} bp", self.sequence_len), false));
+        result
+    }
+
+    // Describes how the name was computed.
+    fn computed_with(&self) -> String {
+        format!("pggname {} (SHA-256 of the canonical GFA representation)", env!("CARGO_PKG_VERSION"))
+    }
+
+    fn to_text(&self) -> String {
+        let mut result = format!("Graph: {}\n", self.file);
+        for (label, value, _) in self.fields() {
+            result.push_str(&format!("{}: {}\n", label, value));
+        }
+        result.push_str(&format!("Relationships: {}\n", self.relationships.len()));
+        for relationship in self.relationships.iter() {
+            result.push_str(&format!("  {} {} {}", relationship.from, relationship.description, relationship.to));
+            if let Some(provenance) = &relationship.provenance {
+                result.push_str(&format!(" ({})", provenance));
+            }
+            result.push('\n');
+        }
+        result.push_str(&format!("Computed with: {}\n", self.computed_with()));
+        result
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut result = format!("### Graph `{}`\n\n", self.file);
+        result.push_str("| | |\n| --- | --- |\n");
+        for (label, value, code) in self.fields() {
+            if code {
+                result.push_str(&format!("| {} | `{}` |\n", label, value));
+            } else {
+                result.push_str(&format!("| {} | {} |\n", label, value));
+            }
+        }
+        result.push('\n');
+        if !self.relationships.is_empty() {
+            result.push_str("Relationships:\n\n");
+            for relationship in self.relationships.iter() {
+                result.push_str(&format!("* `{}` {} `{}`", relationship.from, relationship.description, relationship.to));
+                if let Some(provenance) = &relationship.provenance {
+                    result.push_str(&format!(" ({})", provenance));
+                }
+                result.push('\n');
+            }
+            result.push('\n');
+        }
+        result.push_str(&format!("Computed with {}.\n", self.computed_with()));
+        result
+    }
+}
+
+//-----------------------------------------------------------------------------
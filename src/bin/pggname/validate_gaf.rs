@@ -0,0 +1,155 @@
+//! The `validate-gaf` subcommand: checking that GAF alignments are consistent with a graph.
+//!
+//! Checks that the `@RN` header line names the graph and validates each record with [`GafValidator`](pggname::gaf::GafValidator).
+//! With multiple graphs, the header must name one of them, and each record is validated against the graph named by its `rn:Z:` field using [`GafValidatorSet`].
+//! Records without the field are validated against the graph named by the header.
+//! Failing records are listed with their line numbers, followed by a summary.
+//! The exit status is 1 if the header does not name the graph or if any record fails.
+
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::{GraphName, algorithms};
+use pggname::gaf::GafValidatorSet;
+use pggname::graph::{GraphInt, GraphStr, GBZInt, GBZStr};
+
+use simple_sds::serialize;
+
+use std::io::BufRead;
+use std::process;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    let mut validators = GafValidatorSet::new();
+    let mut names = Vec::with_capacity(config.graph_files.len());
+    for graph_file in config.graph_files.iter() {
+        names.push(add_graph(&mut validators, graph_file, config.string_ids)?);
+    }
+
+    if !validate(&mut validators, &names, &config)? {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+// Loads the graph, adds it to the collection, and returns its name.
+fn add_graph(validators: &mut GafValidatorSet, graph_file: &str, string_ids: bool) -> Result<String, String> {
+    let result = if GBZ::is_gbz(graph_file) {
+        let graph: GBZ = serialize::load_from(graph_file)
+            .map_err(|e| format!("Error loading GBZ file {}: {}", graph_file, e))?;
+        if string_ids {
+            validators.add(&GBZStr { graph })
+        } else {
+            validators.add(&GBZInt { graph })
+        }
+    } else if string_ids {
+        validators.add(&algorithms::parse_gfa::<GraphStr, _>(algorithms::open_gfa(graph_file)?)?)
+    } else {
+        match algorithms::parse_gfa::<GraphInt, _>(algorithms::open_gfa(graph_file)?) {
+            Ok(graph) => validators.add(&graph),
+            Err(_) => validators.add(&algorithms::parse_gfa::<GraphStr, _>(algorithms::open_gfa(graph_file)?)?),
+        }
+    };
+    result.map_err(|e| format!("{}: {}", graph_file, e))
+}
+
+// Validates the alignments against the graphs and prints the results.
+// Returns `true` if everything is consistent.
+fn validate(validators: &mut GafValidatorSet, names: &[String], config: &Config) -> Result<bool, String> {
+    let reader = algorithms::open_gfa(&config.gaf_file)?;
+    let mut header: Vec<String> = Vec::new();
+    let mut header_ok = None;
+    let (mut aligned, mut unaligned, mut failed) = (0, 0, 0);
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Error reading {} line {}: {}", config.gaf_file, i + 1, e))?;
+        if header_ok.is_none() && line.starts_with('@') {
+            header.push(line);
+            continue;
+        }
+        if header_ok.is_none() {
+            header_ok = Some(check_header(&header, validators, names, config));
+        }
+        if line.is_empty() {
+            continue;
+        }
+        match validators.validate_record(&line) {
+            Ok(true) => aligned += 1,
+            Ok(false) => unaligned += 1,
+            Err(message) => {
+                println!("{} line {}: {}", config.gaf_file, i + 1, message);
+                failed += 1;
+            }
+        }
+    }
+    let header_ok = header_ok.unwrap_or_else(|| check_header(&header, validators, names, config));
+
+    println!();
+    for (graph_file, name) in config.graph_files.iter().zip(names.iter()) {
+        println!("Graph {}: {}", graph_file, name);
+    }
+    println!("Header: {}", if header_ok { "ok" } else { "mismatch" });
+    println!("Records: {} aligned, {} unaligned, {} failed", aligned, unaligned, failed);
+
+    Ok(header_ok && failed == 0)
+}
+
+// Checks that the header names one of the graphs and makes it the default graph.
+// Prints a message if the header does not name any of the graphs.
+fn check_header(header: &[String], validators: &mut GafValidatorSet, names: &[String], config: &Config) -> bool {
+    match GraphName::from_header_lines(header) {
+        Ok(parsed) => match parsed.name() {
+            Some(header_name) if validators.set_default(header_name).is_ok() => true,
+            Some(header_name) if names.len() == 1 => {
+                println!("{}: @RN header names graph {}, but {} is {}", config.gaf_file, header_name, config.graph_files[0], names[0]);
+                false
+            }
+            Some(header_name) => {
+                println!("{}: @RN header names graph {}, which is not one of the given graphs", config.gaf_file, header_name);
+                false
+            }
+            None => {
+                println!("{}: no @RN header line", config.gaf_file);
+                false
+            }
+        },
+        Err(message) => {
+            println!("{}: invalid header: {}", config.gaf_file, message);
+            false
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    gaf_file: String,
+    graph_files: Vec<String>,
+    string_ids: bool,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} validate-gaf [options] reads.gaf[.gz] graph.(gbz|gfa[.gz]) [graph2 ...]", &program);
+
+        let mut opts = Options::new();
+        opts.optflag("s", "string-ids", "use string node identifiers (default: integer identifiers if possible)");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.free.len() < 2 {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let gaf_file = matches.free[0].clone();
+        let graph_files = matches.free[1..].to_vec();
+        let string_ids = matches.opt_present("s");
+
+        Ok(Config { gaf_file, graph_files, string_ids })
+    }
+}
+
+//-----------------------------------------------------------------------------
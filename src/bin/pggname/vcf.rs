@@ -0,0 +1,118 @@
+//! The `vcf` subcommand: reading and writing graph names in VCF headers.
+//!
+//! Without options, prints the graph name stored in the VCF header and the relationships listed with it.
+//! With `--name` or `--graph`, writes the VCF file to stdout with the header annotated with the graph name.
+//! If the graph is a GBZ file, the relationships stored in its tags are also included.
+//! See [`pggname::vcf`] for the header lines.
+
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::{GraphName, algorithms, vcf};
+
+use simple_sds::serialize;
+
+use std::io::{self, BufRead, BufWriter, Write};
+use std::process;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    let reader = algorithms::open_gfa(&config.input_file)?;
+    let mut lines = reader.lines().enumerate();
+    let mut header: Vec<String> = Vec::new();
+    let mut first_record: Option<String> = None;
+    for (i, line) in lines.by_ref() {
+        let line = line.map_err(|e| format!("Error reading {} line {}: {}", config.input_file, i + 1, e))?;
+        if line.starts_with('#') {
+            header.push(line);
+        } else {
+            first_record = Some(line);
+            break;
+        }
+    }
+
+    let name = match (&config.name, &config.graph_file) {
+        (Some(name), _) => GraphName::new(name.clone()),
+        (None, Some(graph_file)) => graph_name(graph_file)?,
+        (None, None) => {
+            let name = GraphName::from_vcf_header_lines(&header)?;
+            let Some(graph_name) = name.name() else {
+                return Err(format!("No graph name in {}", config.input_file));
+            };
+            println!("{}", graph_name);
+            for (from, to) in name.subgraph_iter() {
+                println!("subgraph\t{}\t{}", from, to);
+            }
+            for (from, to) in name.translation_iter() {
+                println!("translation\t{}\t{}", from, to);
+            }
+            return Ok(());
+        }
+    };
+
+    let mut writer = BufWriter::new(io::stdout().lock());
+    for line in vcf::annotate_header_lines(&header, &name) {
+        writeln!(writer, "{}", line).map_err(|e| e.to_string())?;
+    }
+    if let Some(line) = first_record {
+        writeln!(writer, "{}", line).map_err(|e| e.to_string())?;
+    }
+    for (i, line) in lines {
+        let line = line.map_err(|e| format!("Error reading {} line {}: {}", config.input_file, i + 1, e))?;
+        writeln!(writer, "{}", line).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Computes the name of the graph, including the relationships stored in a GBZ file.
+fn graph_name(graph_file: &str) -> Result<GraphName, String> {
+    let name = pggname::stable_name_from_file(graph_file)?;
+    let mut result = GraphName::new(name);
+    if GBZ::is_gbz(graph_file) {
+        let graph: GBZ = serialize::load_from(graph_file)
+            .map_err(|e| format!("Error loading GBZ file {}: {}", graph_file, e))?;
+        result.add_relationships(&GraphName::from_gbz(&graph));
+    }
+    Ok(result)
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    input_file: String,
+    name: Option<String>,
+    graph_file: Option<String>,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} vcf [options] variants.vcf[.gz]", &program);
+
+        let mut opts = Options::new();
+        opts.optopt("", "name", "annotate the header with this graph name", "NAME");
+        opts.optopt("", "graph", "annotate the header with the name of this graph", "FILE");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.free.len() != 1 {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let input_file = matches.free[0].clone();
+        let name = matches.opt_str("name");
+        let graph_file = matches.opt_str("graph");
+        if name.is_some() && graph_file.is_some() {
+            return Err(String::from("Options --name and --graph are mutually exclusive"));
+        }
+
+        Ok(Config { input_file, name, graph_file })
+    }
+}
+
+//-----------------------------------------------------------------------------
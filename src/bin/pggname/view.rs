@@ -0,0 +1,139 @@
+//! The `view` subcommand: the canonical GFA representation as a virtual read-only file.
+//!
+//! This subcommand is only available with the `fifo` feature.
+//! It loads the graph, creates a named pipe with `mkfifo`, and writes the canonical GFA representation to each reader that opens the pipe.
+//! The representation is serialized on demand with [`CanonicalReader`], so it is never materialized on disk or in memory.
+//! External tools can use the pipe as a GFA file path, as long as they read the file sequentially and only once.
+//!
+//! The pipe serves `--count` readers (default 1, 0 for no limit) and is then removed.
+//! A reader that stops early, such as `head`, does not affect the following readers.
+
+use gbz::GBZ;
+
+use getopts::Options;
+
+use pggname::{Graph, algorithms};
+use pggname::graph::{GraphInt, GraphStr, GBZInt, GBZStr};
+use pggname::view::CanonicalReader;
+
+use simple_sds::serialize;
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::process::{self, Command};
+
+use tracing::info;
+
+//-----------------------------------------------------------------------------
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let config = Config::new(args)?;
+
+    if GBZ::is_gbz(&config.graph_file) {
+        let graph: GBZ = serialize::load_from(&config.graph_file)
+            .map_err(|e| format!("Error loading GBZ file {}: {}", config.graph_file, e))?;
+        if config.string_ids {
+            serve(&GBZStr { graph }, &config)
+        } else {
+            serve(&GBZInt { graph }, &config)
+        }
+    } else if config.string_ids {
+        serve(&algorithms::parse_gfa::<GraphStr, _>(algorithms::open_gfa(&config.graph_file)?)?, &config)
+    } else {
+        match algorithms::parse_gfa::<GraphInt, _>(algorithms::open_gfa(&config.graph_file)?) {
+            Ok(graph) => serve(&graph, &config),
+            Err(_) => serve(&algorithms::parse_gfa::<GraphStr, _>(algorithms::open_gfa(&config.graph_file)?)?, &config),
+        }
+    }
+}
+
+// Creates the named pipe, serves the canonical GFA to the readers, and removes the pipe.
+fn serve<G: Graph>(graph: &G, config: &Config) -> Result<(), String> {
+    make_fifo(&config.fifo)?;
+    info!("Serving the canonical GFA for {} at {}", config.graph_file, config.fifo);
+
+    let result = serve_readers(graph, config);
+    let _ = fs::remove_file(&config.fifo);
+    result
+}
+
+fn serve_readers<G: Graph>(graph: &G, config: &Config) -> Result<(), String> {
+    let next = format!("{}.next", config.fifo);
+    let mut readers = 0;
+    while config.count == 0 || readers < config.count {
+        // Opening the pipe blocks until a reader opens it.
+        let mut pipe = OpenOptions::new().write(true).open(Path::new(&config.fifo))
+            .map_err(|e| format!("Error opening {}: {}", config.fifo, e))?;
+        readers += 1;
+
+        // Replace the pipe before writing, so that the reader sees the end of the file when we close it.
+        // Otherwise the next open could succeed while the reader still has the pipe open.
+        if config.count == 0 || readers < config.count {
+            make_fifo(&next)?;
+            fs::rename(&next, &config.fifo).map_err(|e| format!("Error renaming {} to {}: {}", next, config.fifo, e))?;
+        } else {
+            let _ = fs::remove_file(&config.fifo);
+        }
+
+        let mut reader = CanonicalReader::new(graph);
+        match io::copy(&mut reader, &mut pipe) {
+            Ok(bytes) => info!("Reader {}: wrote {} bytes", readers, bytes),
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => {
+                info!("Reader {}: closed after {} bytes", readers, reader.bytes_read());
+            }
+            Err(e) => return Err(format!("Error writing to {}: {}", config.fifo, e)),
+        }
+    }
+    Ok(())
+}
+
+// Creates a named pipe with `mkfifo`.
+fn make_fifo(path: &str) -> Result<(), String> {
+    let status = Command::new("mkfifo").arg(path).status()
+        .map_err(|e| format!("Error starting mkfifo for {}: {}", path, e))?;
+    if !status.success() {
+        return Err(format!("mkfifo failed to create {} with {}", path, status));
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+struct Config {
+    graph_file: String,
+    fifo: String,
+    count: usize,
+    string_ids: bool,
+}
+
+impl Config {
+    fn new(args: &[String]) -> Result<Self, String> {
+        let program = args[0].clone();
+        let header = format!("Usage: {} view [options] graph.(gbz|gfa[.gz]) canonical.gfa", &program);
+
+        let mut opts = Options::new();
+        opts.optflag("s", "string-ids", "use string node identifiers (default: integer identifiers if possible)");
+        opts.optopt("", "count", "serve N readers before removing the pipe (default: 1, 0 for no limit)", "N");
+        let matches = opts.parse(&args[2..]).map_err(|e| e.to_string())?;
+        if matches.free.len() != 2 {
+            eprintln!("{}", opts.usage(&header));
+            process::exit(1);
+        }
+
+        let graph_file = matches.free[0].clone();
+        let fifo = matches.free[1].clone();
+        if Path::new(&fifo).exists() {
+            return Err(format!("{} already exists", fifo));
+        }
+        let count = match matches.opt_str("count") {
+            Some(value) => value.parse::<usize>().map_err(|e| format!("Invalid --count {}: {}", value, e))?,
+            None => 1,
+        };
+        let string_ids = matches.opt_present("s");
+
+        Ok(Config { graph_file, fifo, count, string_ids })
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,232 @@
+//! Naming the graph in an index bundle.
+//!
+//! This module is only available with the `bundle` feature.
+//! Indexes for tools such as `vg giraffe` are often distributed as a bundle: a directory or a tarball containing the graph (`.gbz`) together with indexes built from it (`.dist`, `.min`, `.zipcodes`, and so on).
+//! [`Bundle`] lists the components of such a bundle, locates the graph, and computes its name without unpacking the bundle.
+//! This makes it possible to verify which graph an index bundle was built from.
+//!
+//! Tarballs may be gzip-compressed.
+//! The graph component is a GBZ file or a GFA file, possibly gzip-compressed.
+//! If the bundle contains exactly one GBZ file, it is the graph.
+//! Otherwise the bundle must contain exactly one GFA file, or the graph must be chosen explicitly.
+
+use crate::algorithms;
+use crate::graph::{GraphInt, GraphStr, LazyGBZ};
+
+use flate2::read::MultiGzDecoder;
+
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// An index bundle: a directory or a tarball containing a graph and indexes built from it.
+///
+/// See the [module documentation](self) for details.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::bundle::Bundle;
+/// use gbz::support;
+///
+/// let filename = support::get_test_data("example.gbz");
+/// let dir = std::env::temp_dir().join(format!("pggname-bundle-doctest-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// std::fs::copy(&filename, dir.join("example.gbz")).unwrap();
+/// std::fs::write(dir.join("example.dist"), b"distance index").unwrap();
+///
+/// let bundle = Bundle::open(&dir).unwrap();
+/// assert_eq!(bundle.members(), ["example.dist", "example.gbz"]);
+/// assert_eq!(bundle.graph_member(), Ok("example.gbz"));
+/// let name = bundle.stable_name("example.gbz").unwrap();
+/// assert_eq!(name, "81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970");
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bundle {
+    path: PathBuf,
+    directory: bool,
+    // Relative paths of the regular files in sorted order.
+    members: Vec<String>,
+}
+
+impl Bundle {
+    /// File name extensions of GBZ graphs.
+    pub const GBZ_EXTENSIONS: &'static [&'static str] = &[".gbz"];
+
+    /// File name extensions of GFA graphs.
+    pub const GFA_EXTENSIONS: &'static [&'static str] = &[".gfa", ".gfa.gz"];
+
+    /// Opens a bundle stored as a directory or as a tarball.
+    ///
+    /// Returns an error if the bundle cannot be read.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        let directory = path.is_dir();
+        let mut members = Vec::new();
+        if directory {
+            list_directory(&path, "", &mut members)?;
+        } else {
+            let mut archive = tar::Archive::new(open_file(&path)?);
+            let entries = archive.entries().map_err(|e| format!("Error reading tarball {}: {}", path.display(), e))?;
+            for entry in entries {
+                let entry = entry.map_err(|e| format!("Error reading tarball {}: {}", path.display(), e))?;
+                if entry.header().entry_type().is_file() {
+                    members.push(member_name(&entry, &path)?);
+                }
+            }
+        }
+        members.sort();
+        Ok(Bundle { path, directory, members })
+    }
+
+    /// Returns the relative paths of the files in the bundle in sorted order.
+    pub fn members(&self) -> &[String] {
+        &self.members
+    }
+
+    /// Returns the files in the bundle that are graphs, GBZ files first.
+    pub fn graph_members(&self) -> Vec<&str> {
+        let gbz = self.members.iter().filter(|member| is_gbz_member(member));
+        let gfa = self.members.iter().filter(|member| is_gfa_member(member));
+        gbz.chain(gfa).map(|member| member.as_str()).collect()
+    }
+
+    /// Returns the graph component of the bundle.
+    ///
+    /// Returns an error if there is no graph, or if the choice is ambiguous.
+    pub fn graph_member(&self) -> Result<&str, String> {
+        let graphs = self.graph_members();
+        let gbz: Vec<&str> = graphs.iter().copied().filter(|member| is_gbz_member(member)).collect();
+        let candidates = if gbz.is_empty() { graphs } else { gbz };
+        match candidates.len() {
+            0 => Err(format!("No graph in bundle {}", self.path.display())),
+            1 => Ok(candidates[0]),
+            _ => Err(format!("Multiple graphs in bundle {}: {}", self.path.display(), candidates.join(", "))),
+        }
+    }
+
+    /// Computes the stable name of the graph stored in the given member of the bundle.
+    ///
+    /// GBZ graphs use integer identifiers.
+    /// GFA graphs use integer identifiers if possible and string identifiers otherwise, as in [`algorithms::hash_file`] with [`algorithms::NodeIds::Auto`].
+    /// Returns an error if the member does not exist, if it is not a graph, or if the graph cannot be loaded.
+    pub fn stable_name(&self, member: &str) -> Result<String, String> {
+        let description = format!("{} in bundle {}", member, self.path.display());
+        if !self.members.iter().any(|m| m == member) {
+            return Err(format!("No member {}", description));
+        }
+        if is_gbz_member(member) {
+            let graph = self.with_member(member, |mut reader| {
+                LazyGBZ::load(&mut reader).map_err(|e| format!("Error loading GBZ file {}: {}", description, e))
+            })?;
+            Ok(crate::stable_name(&graph))
+        } else if is_gfa_member(member) {
+            let graph = self.with_member(member, |reader| algorithms::parse_gfa::<GraphInt, _>(gfa_reader(reader)));
+            match graph {
+                Ok(graph) => Ok(crate::stable_name(&graph)),
+                Err(_) => {
+                    let graph = self.with_member(member, |reader| algorithms::parse_gfa::<GraphStr, _>(gfa_reader(reader)))
+                        .map_err(|e| format!("Error parsing GFA file {}: {}", description, e))?;
+                    Ok(crate::stable_name(&graph))
+                }
+            }
+        } else {
+            Err(format!("{} is not a graph", description))
+        }
+    }
+
+    // Calls the function with a reader for the member.
+    fn with_member<T, F: FnOnce(&mut dyn BufRead) -> Result<T, String>>(&self, member: &str, f: F) -> Result<T, String> {
+        if self.directory {
+            let filename = self.path.join(member);
+            let file = File::open(&filename).map_err(|e| format!("Error opening {}: {}", filename.display(), e))?;
+            return f(&mut BufReader::new(file));
+        }
+        let mut archive = tar::Archive::new(open_file(&self.path)?);
+        let entries = archive.entries().map_err(|e| format!("Error reading tarball {}: {}", self.path.display(), e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Error reading tarball {}: {}", self.path.display(), e))?;
+            if entry.header().entry_type().is_file() && member_name(&entry, &self.path)? == member {
+                return f(&mut BufReader::new(entry));
+            }
+        }
+        Err(format!("No member {} in bundle {}", member, self.path.display()))
+    }
+}
+
+/// Returns `true` if the path is a directory or a tarball, possibly gzip-compressed.
+pub fn is_bundle<P: AsRef<Path>>(path: P) -> bool {
+    let path = path.as_ref();
+    if path.is_dir() {
+        return true;
+    }
+    let Ok(mut reader) = open_file(path) else {
+        return false;
+    };
+    // The ustar magic is at offset 257 of the first header block.
+    let mut header = [0u8; 512];
+    reader.read_exact(&mut header).is_ok() && header[257..262] == *b"ustar"
+}
+
+//-----------------------------------------------------------------------------
+
+fn is_gbz_member(member: &str) -> bool {
+    Bundle::GBZ_EXTENSIONS.iter().any(|extension| member.ends_with(extension))
+}
+
+fn is_gfa_member(member: &str) -> bool {
+    Bundle::GFA_EXTENSIONS.iter().any(|extension| member.ends_with(extension))
+}
+
+// Opens the file, decompressing it if necessary.
+fn open_file(path: &Path) -> Result<Box<dyn BufRead>, String> {
+    let file = File::open(path).map_err(|e| format!("Error opening {}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+    let compressed = reader.fill_buf().map_err(|e| format!("Error reading {}: {}", path.display(), e))?.starts_with(&[0x1F, 0x8B]);
+    if compressed {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+// Returns a reader for a GFA member, decompressing it if necessary.
+fn gfa_reader(reader: &mut dyn BufRead) -> Box<dyn BufRead + '_> {
+    let compressed = reader.fill_buf().is_ok_and(|buf| buf.starts_with(&[0x1F, 0x8B]));
+    if compressed {
+        Box::new(BufReader::new(MultiGzDecoder::new(reader)))
+    } else {
+        Box::new(reader)
+    }
+}
+
+// Returns the path of the tarball entry without a leading `./`.
+fn member_name<R: Read>(entry: &tar::Entry<'_, R>, tarball: &Path) -> Result<String, String> {
+    let path = entry.path().map_err(|e| format!("Error reading tarball {}: {}", tarball.display(), e))?;
+    let name = path.to_string_lossy();
+    Ok(String::from(name.strip_prefix("./").unwrap_or(&name)))
+}
+
+// Adds the regular files in the directory and its subdirectories to the list, with paths relative to the bundle.
+fn list_directory(path: &Path, prefix: &str, members: &mut Vec<String>) -> Result<(), String> {
+    let entries = fs::read_dir(path).map_err(|e| format!("Error reading directory {}: {}", path.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Error reading directory {}: {}", path.display(), e))?;
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        let file_type = entry.file_type().map_err(|e| format!("Error reading {}: {}", entry.path().display(), e))?;
+        if file_type.is_dir() {
+            list_directory(&entry.path(), &format!("{}/", name), members)?;
+        } else if entry.path().is_file() {
+            members.push(name);
+        }
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
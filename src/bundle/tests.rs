@@ -0,0 +1,125 @@
+use super::*;
+
+use gbz::support;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+
+use std::io::Write;
+
+//-----------------------------------------------------------------------------
+
+const EXAMPLE_NAME: &str = "81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970";
+
+// A temporary directory that is removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("pggname-bundle-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+// Returns a tarball with the given files.
+fn tarball(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (name, data) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, &data[..]).unwrap();
+    }
+    builder.into_inner().unwrap()
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn example_gbz() -> Vec<u8> {
+    fs::read(support::get_test_data("example.gbz")).unwrap()
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn directory_bundle() {
+    let dir = TempDir::new("dir");
+    fs::create_dir_all(dir.0.join("indexes")).unwrap();
+    fs::write(dir.0.join("graph.gbz"), example_gbz()).unwrap();
+    fs::write(dir.0.join("graph.dist"), b"distance").unwrap();
+    fs::write(dir.0.join("indexes/graph.min"), b"minimizers").unwrap();
+
+    assert!(is_bundle(&dir.0), "Directory is not a bundle");
+    let bundle = Bundle::open(&dir.0).unwrap();
+    assert_eq!(bundle.members(), ["graph.dist", "graph.gbz", "indexes/graph.min"], "Wrong members");
+    assert_eq!(bundle.graph_member(), Ok("graph.gbz"), "Wrong graph member");
+    assert_eq!(bundle.stable_name("graph.gbz"), Ok(String::from(EXAMPLE_NAME)), "Wrong name");
+    assert!(bundle.stable_name("graph.dist").is_err(), "Named an index");
+    assert!(bundle.stable_name("missing.gbz").is_err(), "Named a missing member");
+}
+
+#[test]
+fn tarball_bundle() {
+    let gfa = fs::read(support::get_test_data("example.gfa")).unwrap();
+    let data = tarball(&[
+        ("./graph.gbz", example_gbz()),
+        ("./graph.dist", b"distance".to_vec()),
+        ("./graph.min", b"minimizers".to_vec()),
+    ]);
+    let dir = TempDir::new("tar");
+    for (name, data) in [("bundle.tar", data.clone()), ("bundle.tar.gz", gzip(&data))] {
+        let path = dir.0.join(name);
+        fs::write(&path, data).unwrap();
+        assert!(is_bundle(&path), "{} is not a bundle", name);
+        let bundle = Bundle::open(&path).unwrap();
+        assert_eq!(bundle.members(), ["graph.dist", "graph.gbz", "graph.min"], "Wrong members in {}", name);
+        let member = bundle.graph_member().unwrap();
+        assert_eq!(bundle.stable_name(member), Ok(String::from(EXAMPLE_NAME)), "Wrong name in {}", name);
+    }
+
+    // GFA graphs, possibly compressed.
+    let path = dir.0.join("gfa.tar");
+    fs::write(&path, tarball(&[("graph.gfa.gz", gzip(&gfa)), ("graph.dist", b"distance".to_vec())])).unwrap();
+    let bundle = Bundle::open(&path).unwrap();
+    assert_eq!(bundle.graph_member(), Ok("graph.gfa.gz"), "Wrong GFA member");
+    assert_eq!(bundle.stable_name("graph.gfa.gz"), Ok(String::from(EXAMPLE_NAME)), "Wrong name for a compressed GFA");
+
+    assert!(!is_bundle(support::get_test_data("example.gbz")), "GBZ file is a bundle");
+    assert!(!is_bundle(dir.0.join("missing.tar")), "Missing file is a bundle");
+}
+
+#[test]
+fn graph_selection() {
+    let dir = TempDir::new("select");
+    let path = dir.0.join("bundle.tar");
+
+    // A GBZ file is preferred over GFA files.
+    fs::write(&path, tarball(&[("a.gfa", Vec::new()), ("b.gbz", example_gbz())])).unwrap();
+    let bundle = Bundle::open(&path).unwrap();
+    assert_eq!(bundle.graph_members(), vec!["b.gbz", "a.gfa"], "Wrong graph members");
+    assert_eq!(bundle.graph_member(), Ok("b.gbz"), "GBZ file was not preferred");
+
+    fs::write(&path, tarball(&[("a.gbz", example_gbz()), ("b.gbz", example_gbz())])).unwrap();
+    let bundle = Bundle::open(&path).unwrap();
+    assert!(bundle.graph_member().is_err(), "Chose one of multiple GBZ files");
+    assert_eq!(bundle.stable_name("b.gbz"), Ok(String::from(EXAMPLE_NAME)), "Wrong name for an explicit member");
+
+    fs::write(&path, tarball(&[("graph.dist", b"distance".to_vec())])).unwrap();
+    let bundle = Bundle::open(&path).unwrap();
+    assert!(bundle.graph_member().is_err(), "Found a graph in a bundle without graphs");
+}
+
+//-----------------------------------------------------------------------------
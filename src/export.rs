@@ -0,0 +1,127 @@
+//! Export of canonical node and edge records as Arrow and Parquet tables.
+//!
+//! This module is only available with the `arrow` feature.
+//! The canonical GFA representation of a graph is split into two tables:
+//!
+//! * Nodes: `name` (string), `length` (uint64), and `digest` (string), the refget digest of the sequence.
+//! * Edges: `from` (string), `from_orientation` (string), `to` (string), and `to_orientation` (string), with orientations `+` and `-`.
+//!
+//! The rows are in canonical order, and each edge is listed once in its canonical orientation.
+//! Node names are stored as strings, as in the canonical GFA representation.
+//! Tables can be written in the Arrow IPC file format or as Parquet files.
+
+use crate::Graph;
+use crate::seqcol;
+
+use arrow_array::{ArrayRef, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+
+use arrow_ipc::writer::FileWriter;
+
+use parquet::arrow::ArrowWriter;
+
+use std::io::Write;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Returns the schema of the node table.
+pub fn node_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("length", DataType::UInt64, false),
+        Field::new("digest", DataType::Utf8, false),
+    ])
+}
+
+/// Returns the schema of the edge table.
+pub fn edge_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("from", DataType::Utf8, false),
+        Field::new("from_orientation", DataType::Utf8, false),
+        Field::new("to", DataType::Utf8, false),
+        Field::new("to_orientation", DataType::Utf8, false),
+    ])
+}
+
+/// Builds the node and edge tables for the graph.
+///
+/// Returns an error if the canonical records cannot be parsed or if the tables cannot be built.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::{algorithms, export};
+/// use pggname::graph::GraphInt;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t-\t*\n").unwrap();
+/// let (nodes, edges) = export::record_batches(&graph).unwrap();
+/// assert_eq!(nodes.num_rows(), 2);
+/// assert_eq!(edges.num_rows(), 1);
+/// ```
+pub fn record_batches<G: Graph>(graph: &G) -> Result<(RecordBatch, RecordBatch), String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut lengths: Vec<u64> = Vec::new();
+    let mut digests: Vec<String> = Vec::new();
+    let mut edges: [Vec<String>; 4] = Default::default();
+
+    for record in graph.node_iter() {
+        for line in record.split(|&c| c == b'\n').filter(|line| !line.is_empty()) {
+            let fields: Vec<&[u8]> = line.split(|&c| c == b'\t').collect();
+            match fields[0] {
+                b"S" if fields.len() == 3 => {
+                    names.push(String::from_utf8_lossy(fields[1]).into_owned());
+                    lengths.push(fields[2].len() as u64);
+                    digests.push(seqcol::refget_digest(fields[2]));
+                }
+                b"L" if fields.len() == 5 => {
+                    for (column, field) in edges.iter_mut().zip(fields[1..].iter()) {
+                        column.push(String::from_utf8_lossy(field).into_owned());
+                    }
+                }
+                _ => return Err(format!("Invalid canonical record: {}", String::from_utf8_lossy(line))),
+            }
+        }
+    }
+
+    let nodes: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from(names)),
+        Arc::new(UInt64Array::from(lengths)),
+        Arc::new(StringArray::from(digests)),
+    ];
+    let nodes = RecordBatch::try_new(Arc::new(node_schema()), nodes).map_err(|e| e.to_string())?;
+    let edges: Vec<ArrayRef> = edges.into_iter().map(|column| Arc::new(StringArray::from(column)) as ArrayRef).collect();
+    let edges = RecordBatch::try_new(Arc::new(edge_schema()), edges).map_err(|e| e.to_string())?;
+
+    Ok((nodes, edges))
+}
+
+//-----------------------------------------------------------------------------
+
+/// Writes the record batch in the Arrow IPC file format.
+///
+/// Returns an error if writing fails.
+pub fn write_arrow<W: Write>(batch: &RecordBatch, writer: W) -> Result<(), String> {
+    let result: Result<(), ArrowError> = (|| {
+        let mut writer = FileWriter::try_new(writer, &batch.schema())?;
+        writer.write(batch)?;
+        writer.finish()
+    })();
+    result.map_err(|e| format!("Error writing Arrow table: {}", e))
+}
+
+/// Writes the record batch as a Parquet file.
+///
+/// Returns an error if writing fails.
+pub fn write_parquet<W: Write + Send>(batch: &RecordBatch, writer: W) -> Result<(), String> {
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)
+        .map_err(|e| format!("Error writing Parquet table: {}", e))?;
+    writer.write(batch).map_err(|e| format!("Error writing Parquet table: {}", e))?;
+    writer.close().map_err(|e| format!("Error writing Parquet table: {}", e))?;
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
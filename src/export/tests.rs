@@ -0,0 +1,65 @@
+use super::*;
+
+use crate::algorithms;
+use crate::graph::GraphStr;
+use crate::testing::TempFile;
+
+use arrow_array::Array;
+use arrow_ipc::reader::FileReader;
+
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use std::fs::File;
+use std::io::Cursor;
+
+//-----------------------------------------------------------------------------
+
+const GRAPH: &str = "S\tb\tGA\nS\ta\tACGT\nL\ta\t+\tb\t-\t*\nL\tb\t+\tb\t+\t*\n";
+
+fn batches() -> (RecordBatch, RecordBatch) {
+    let graph: GraphStr = algorithms::parse_gfa_bytes(GRAPH.as_bytes()).unwrap();
+    record_batches(&graph).unwrap()
+}
+
+fn strings(batch: &RecordBatch, column: usize) -> Vec<String> {
+    let array = batch.column(column).as_any().downcast_ref::<StringArray>().unwrap();
+    (0..array.len()).map(|i| String::from(array.value(i))).collect()
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn tables() {
+    let (nodes, edges) = batches();
+    assert_eq!(strings(&nodes, 0), vec!["a", "b"], "Wrong node names");
+    let lengths = nodes.column(1).as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert_eq!(lengths.values().to_vec(), vec![4, 2], "Wrong node lengths");
+    assert_eq!(strings(&nodes, 2)[0], seqcol::refget_digest(b"ACGT"), "Wrong node digest");
+
+    assert_eq!(strings(&edges, 0), vec!["a", "b"], "Wrong edge sources");
+    assert_eq!(strings(&edges, 1), vec!["+", "+"], "Wrong source orientations");
+    assert_eq!(strings(&edges, 2), vec!["b", "b"], "Wrong edge destinations");
+    assert_eq!(strings(&edges, 3), vec!["-", "+"], "Wrong destination orientations");
+}
+
+#[test]
+fn arrow_round_trip() {
+    let (nodes, _) = batches();
+    let mut buffer: Vec<u8> = Vec::new();
+    write_arrow(&nodes, &mut buffer).unwrap();
+    let reader = FileReader::try_new(Cursor::new(buffer), None).unwrap();
+    let read: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+    assert_eq!(read, vec![nodes], "Wrong Arrow round trip");
+}
+
+#[test]
+fn parquet_round_trip() {
+    let (_, edges) = batches();
+    let filename = TempFile::new(".parquet");
+    write_parquet(&edges, File::create(&filename).unwrap()).unwrap();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&filename).unwrap()).unwrap().build().unwrap();
+    let read: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+    assert_eq!(read, vec![edges], "Wrong Parquet round trip");
+}
+
+//-----------------------------------------------------------------------------
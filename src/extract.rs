@@ -0,0 +1,201 @@
+//! Parsing reports from graph extraction tools.
+//!
+//! Tools such as `vg chunk` and `odgi extract` produce subgraphs of a source graph.
+//! Their reports list the output files and the regions they cover, which can be turned into subgraph relationships between each output and the source graph.
+//! The following formats are supported:
+//!
+//! * [`ReportFormat::VgChunk`]: the BED file written by `vg chunk --output-bed`, with fields `path`, `start`, `end`, and `file`.
+//!   The coordinates are kept as in the BED file (0-based, half-open).
+//! * [`ReportFormat::OdgiExtract`]: one output per line, either as `file<TAB>region` or as a file named after the region, as `odgi extract --split-subgraphs` does (`PATH:START-END.og`).
+//!
+//! A region is either a path interval `PATH:START-END` or a node range `START:END`, as used with `vg chunk --node-ranges`.
+//! Empty lines and lines starting with `#` are ignored, as are BED `track` and `browser` lines.
+
+use std::fmt::Display;
+use std::io::BufRead;
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// A region of the source graph covered by an extracted subgraph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Region {
+    /// An interval of a path, with coordinates as given in the report.
+    Path {
+        /// Path name.
+        path: String,
+        /// Start of the interval.
+        start: usize,
+        /// End of the interval.
+        end: usize,
+    },
+    /// A range of node identifiers.
+    Nodes {
+        /// First node in the range.
+        start: usize,
+        /// Last node in the range.
+        end: usize,
+    },
+}
+
+impl Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Region::Path { path, start, end } => write!(f, "{}:{}-{}", path, start, end),
+            Region::Nodes { start, end } => write!(f, "nodes {}-{}", start, end),
+        }
+    }
+}
+
+impl FromStr for Region {
+    type Err = String;
+
+    /// Parses a path interval `PATH:START-END` or a node range `START:END`.
+    ///
+    /// The path name may contain `:`, as the interval starts after the last one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::extract::Region;
+    ///
+    /// let region: Region = "GRCh38#0#chr1:1000-2000".parse().unwrap();
+    /// assert_eq!(region, Region::Path { path: String::from("GRCh38#0#chr1"), start: 1000, end: 2000 });
+    /// let region: Region = "10:250".parse().unwrap();
+    /// assert_eq!(region, Region::Nodes { start: 10, end: 250 });
+    /// assert!("chr1".parse::<Region>().is_err());
+    /// ```
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let number = |field: &str| field.parse::<usize>().map_err(|_| format!("Invalid coordinate {} in region {}", field, value));
+        let Some((prefix, interval)) = value.rsplit_once(':') else {
+            return Err(format!("Invalid region: {}", value));
+        };
+        let (start, end) = if let Some((start, end)) = interval.split_once('-') {
+            (number(start)?, number(end)?)
+        } else if !prefix.is_empty() && prefix.bytes().all(|c| c.is_ascii_digit()) {
+            let (start, end) = (number(prefix)?, number(interval)?);
+            if start > end {
+                return Err(format!("Empty node range: {}", value));
+            }
+            return Ok(Region::Nodes { start, end });
+        } else {
+            return Err(format!("Invalid region: {}", value));
+        };
+        if prefix.is_empty() {
+            return Err(format!("Missing path name in region {}", value));
+        }
+        if start > end {
+            return Err(format!("Empty interval: {}", value));
+        }
+        Ok(Region::Path { path: String::from(prefix), start, end })
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// An extracted subgraph listed in a report.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtractedGraph {
+    /// Output file, as given in the report.
+    pub file: String,
+    /// Region of the source graph covered by the output.
+    pub region: Region,
+}
+
+/// Format of an extraction report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// BED output from `vg chunk`.
+    VgChunk,
+    /// Output files from `odgi extract`.
+    OdgiExtract,
+}
+
+impl ReportFormat {
+    /// Returns the operation recorded for subgraphs produced by the tool.
+    pub fn operation(self) -> &'static str {
+        match self {
+            ReportFormat::VgChunk => "chunk",
+            ReportFormat::OdgiExtract => "extract",
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "vg-chunk" => Ok(ReportFormat::VgChunk),
+            "odgi-extract" => Ok(ReportFormat::OdgiExtract),
+            _ => Err(format!("Unknown report format: {}", value)),
+        }
+    }
+}
+
+/// Parses an extraction report in the given format.
+///
+/// Returns an error with the line number if a line cannot be parsed.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::extract::{self, ExtractedGraph, Region, ReportFormat};
+///
+/// let bed = b"chr1\t0\t1000\tchunk_0_chr1_0_1000.vg\nchr1\t1000\t2000\tchunk_1_chr1_1000_2000.vg\n";
+/// let graphs = extract::parse_report(&bed[..], ReportFormat::VgChunk).unwrap();
+/// assert_eq!(graphs.len(), 2);
+/// assert_eq!(graphs[1].file, "chunk_1_chr1_1000_2000.vg");
+/// assert_eq!(graphs[1].region.to_string(), "chr1:1000-2000");
+///
+/// let log = b"out/chr1:500-900.og\nnodes.gfa\t10:250\n";
+/// let graphs = extract::parse_report(&log[..], ReportFormat::OdgiExtract).unwrap();
+/// assert_eq!(graphs[0].region, Region::Path { path: String::from("chr1"), start: 500, end: 900 });
+/// assert_eq!(graphs[1], ExtractedGraph { file: String::from("nodes.gfa"), region: Region::Nodes { start: 10, end: 250 } });
+/// ```
+pub fn parse_report<R: BufRead>(reader: R, format: ReportFormat) -> Result<Vec<ExtractedGraph>, String> {
+    let mut result = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Error reading line {}: {}", i + 1, e))?;
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() || line.starts_with('#') || line.starts_with("track ") || line.starts_with("browser ") {
+            continue;
+        }
+        let graph = match format {
+            ReportFormat::VgChunk => parse_bed_line(line),
+            ReportFormat::OdgiExtract => parse_output_line(line),
+        };
+        result.push(graph.map_err(|e| format!("Line {}: {}", i + 1, e))?);
+    }
+    Ok(result)
+}
+
+// Parses a BED line with the output file in the fourth field.
+fn parse_bed_line(line: &str) -> Result<ExtractedGraph, String> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    if fields.len() < 4 || fields[3].is_empty() {
+        return Err(String::from("Expected path, start, end, and file"));
+    }
+    let region: Region = format!("{}:{}-{}", fields[0], fields[1], fields[2]).parse()?;
+    Ok(ExtractedGraph { file: String::from(fields[3]), region })
+}
+
+// Parses `file<TAB>region`, or a file named after the region.
+fn parse_output_line(line: &str) -> Result<ExtractedGraph, String> {
+    if let Some((file, region)) = line.split_once('\t') {
+        if file.is_empty() {
+            return Err(String::from("Missing output file"));
+        }
+        return Ok(ExtractedGraph { file: String::from(file), region: region.trim().parse()? });
+    }
+    let name = Path::new(line).file_name().and_then(|name| name.to_str()).unwrap_or(line);
+    let stem = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name);
+    let region = stem.parse().map_err(|_| format!("No region in output file name {}", line))?;
+    Ok(ExtractedGraph { file: String::from(line), region })
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,70 @@
+use super::*;
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn regions() {
+    let cases: [(&str, Option<Region>); 9] = [
+        ("chr1:100-200", Some(Region::Path { path: String::from("chr1"), start: 100, end: 200 })),
+        ("HG002#1#chr1:0-5", Some(Region::Path { path: String::from("HG002#1#chr1"), start: 0, end: 5 })),
+        ("a:b:3-3", Some(Region::Path { path: String::from("a:b"), start: 3, end: 3 })),
+        ("5:9", Some(Region::Nodes { start: 5, end: 9 })),
+        ("chr1", None),
+        (":100-200", None),
+        ("chr1:200-100", None),
+        ("chr1:x-100", None),
+        ("9:5", None),
+    ];
+    for (value, expected) in cases {
+        assert_eq!(value.parse::<Region>().ok(), expected, "Wrong region for {}", value);
+    }
+
+    let region = Region::Path { path: String::from("chr1"), start: 100, end: 200 };
+    assert_eq!(region.to_string(), "chr1:100-200", "Wrong path region string");
+    assert_eq!(region.to_string().parse::<Region>(), Ok(region), "Path region does not round-trip");
+    assert_eq!(Region::Nodes { start: 5, end: 9 }.to_string(), "nodes 5-9", "Wrong node range string");
+}
+
+#[test]
+fn vg_chunk_report() {
+    let bed = b"track name=chunks\n# comment\nchr1\t0\t1000\tchunk_0.vg\n\nchr2\t10\t20\tchunk_1.vg\textra\r\n";
+    let graphs = parse_report(&bed[..], ReportFormat::VgChunk).unwrap();
+    let expected = vec![
+        ExtractedGraph { file: String::from("chunk_0.vg"), region: Region::Path { path: String::from("chr1"), start: 0, end: 1000 } },
+        ExtractedGraph { file: String::from("chunk_1.vg"), region: Region::Path { path: String::from("chr2"), start: 10, end: 20 } },
+    ];
+    assert_eq!(graphs, expected, "Wrong graphs from the BED file");
+
+    let result = parse_report(&b"chr1\t0\t1000\nchr1\t0\t1000\tchunk.vg\n"[..], ReportFormat::VgChunk);
+    assert!(result.as_ref().is_err_and(|e| e.starts_with("Line 1:")), "Missing file was accepted: {:?}", result);
+    let result = parse_report(&b"chr1\t0\t1000\ta.vg\nchr1\tx\t1000\tb.vg\n"[..], ReportFormat::VgChunk);
+    assert!(result.as_ref().is_err_and(|e| e.starts_with("Line 2:")), "Invalid coordinate was accepted: {:?}", result);
+}
+
+#[test]
+fn odgi_extract_report() {
+    let log = b"out/chr1:500-900.og\nchr2:1-2\nnodes.gfa\t10:250\nsub.og\tHG002#1#chr3:7-8\n";
+    let graphs = parse_report(&log[..], ReportFormat::OdgiExtract).unwrap();
+    let expected = vec![
+        ExtractedGraph { file: String::from("out/chr1:500-900.og"), region: Region::Path { path: String::from("chr1"), start: 500, end: 900 } },
+        ExtractedGraph { file: String::from("chr2:1-2"), region: Region::Path { path: String::from("chr2"), start: 1, end: 2 } },
+        ExtractedGraph { file: String::from("nodes.gfa"), region: Region::Nodes { start: 10, end: 250 } },
+        ExtractedGraph { file: String::from("sub.og"), region: Region::Path { path: String::from("HG002#1#chr3"), start: 7, end: 8 } },
+    ];
+    assert_eq!(graphs, expected, "Wrong graphs from the odgi log");
+
+    for log in [&b"graph.og\n"[..], b"\tchr1:1-2\n", b"graph.og\tchr1\n"] {
+        assert!(parse_report(log, ReportFormat::OdgiExtract).is_err(), "Invalid line was accepted: {}", String::from_utf8_lossy(log));
+    }
+}
+
+#[test]
+fn report_formats() {
+    assert_eq!("vg-chunk".parse::<ReportFormat>(), Ok(ReportFormat::VgChunk), "Wrong format for vg-chunk");
+    assert_eq!("odgi-extract".parse::<ReportFormat>(), Ok(ReportFormat::OdgiExtract), "Wrong format for odgi-extract");
+    assert!("vg".parse::<ReportFormat>().is_err(), "Unknown format was accepted");
+    assert_eq!(ReportFormat::VgChunk.operation(), "chunk", "Wrong operation for vg chunk");
+    assert_eq!(ReportFormat::OdgiExtract.operation(), "extract", "Wrong operation for odgi extract");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,329 @@
+//! A C API for computing stable names and querying relationships between graphs.
+//!
+//! The API is intended for tools such as vg and odgi that want to embed graph names without running the `pggname` binary.
+//! The corresponding C header is `include/pggname.h`.
+//!
+//! Conventions:
+//!
+//! * Strings passed to the functions are NUL-terminated UTF-8 strings.
+//! * Strings returned by the functions are owned by the caller and must be released with [`pggname_string_free`].
+//! * Graph name handles are owned by the caller and must be released with [`pggname_graph_name_free`].
+//! * Functions returning pointers return `NULL` on failure, and functions returning `int` return `-1`.
+//!   The error message is then available from [`pggname_last_error`].
+//! * Panics do not unwind into the caller.
+//!   A panic is reported as a failure with the panic message as the error.
+
+use crate::GraphName;
+use crate::algorithms::{self, NodeIds};
+
+use gbz::GBZ;
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+// Returns the message from a panic payload.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    let message = payload.downcast_ref::<&str>().copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause");
+    format!("Panic in pggname: {}", message)
+}
+
+// Runs the function and converts a panic into an error, so that it does not unwind across the C boundary.
+fn catch_panic<T, F: FnOnce() -> Result<T, String>>(f: F) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| Err(panic_message(payload)))
+}
+
+// Stores the error and returns `NULL`, or returns the value as an owned pointer.
+fn string_result(result: Result<String, String>) -> *mut c_char {
+    match result.and_then(|value| CString::new(value).map_err(|e| e.to_string())) {
+        Ok(value) => value.into_raw(),
+        Err(message) => {
+            set_error(message);
+            ptr::null_mut()
+        }
+    }
+}
+
+// Stores the error and returns `-1`, or returns `1` for `true` and `0` for `false`.
+fn bool_result(result: Result<bool, String>) -> c_int {
+    match result {
+        Ok(value) => c_int::from(value),
+        Err(message) => {
+            set_error(message);
+            -1
+        }
+    }
+}
+
+// Stores the error and returns `NULL`, or returns the graph name as an owned handle.
+fn handle_result(result: Result<GraphName, String>) -> *mut GraphName {
+    match result {
+        Ok(name) => Box::into_raw(Box::new(name)),
+        Err(message) => {
+            set_error(message);
+            ptr::null_mut()
+        }
+    }
+}
+
+// Converts a C string to a string slice.
+unsafe fn c_str<'a>(value: *const c_char, what: &str) -> Result<&'a str, String> {
+    if value.is_null() {
+        return Err(format!("{} is NULL", what));
+    }
+    let value = unsafe { CStr::from_ptr(value) };
+    value.to_str().map_err(|_| format!("{} is not valid UTF-8", what))
+}
+
+// Converts a graph name handle to a reference.
+unsafe fn graph_name<'a>(value: *const GraphName, what: &str) -> Result<&'a GraphName, String> {
+    unsafe { value.as_ref() }.ok_or(format!("{} is NULL", what))
+}
+
+// Converts a pair of graph name handles to references.
+unsafe fn graph_name_pair<'a>(a: *const GraphName, b: *const GraphName) -> Result<(&'a GraphName, &'a GraphName), String> {
+    unsafe { Ok((graph_name(a, "first graph name")?, graph_name(b, "second graph name")?)) }
+}
+
+//-----------------------------------------------------------------------------
+
+/// Returns the message for the last error in the current thread, or `NULL` if there has been no error.
+///
+/// The string is owned by the library and remains valid until the next failing call in the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn pggname_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| error.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Releases a string returned by the library.
+///
+/// # Safety
+///
+/// The pointer must be `NULL` or a string returned by this library that has not been released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        let result = catch_panic(|| {
+            drop(unsafe { CString::from_raw(value) });
+            Ok(())
+        });
+        if let Err(message) = result {
+            set_error(message);
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// Computes the stable name of the graph in the given GFA file.
+///
+/// The file may be gzip-compressed.
+/// Node identifiers are interpreted as integers if possible, with a fallback to strings.
+/// Returns `NULL` if the file is a GBZ file or cannot be parsed.
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_hash_gfa_path(path: *const c_char) -> *mut c_char {
+    let result = catch_panic(|| unsafe { c_str(path, "path") }.and_then(|path| {
+        if GBZ::is_gbz(path) {
+            return Err(format!("{} is a GBZ file", path));
+        }
+        algorithms::stable_name_from_file(path)
+    }));
+    string_result(result)
+}
+
+/// Computes the stable name of the graph in the given GBZ file.
+///
+/// Node identifiers are the integer identifiers in the GBZ graph.
+/// Returns `NULL` if the file is not a GBZ file or cannot be loaded.
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_hash_gbz_path(path: *const c_char) -> *mut c_char {
+    let result = catch_panic(|| unsafe { c_str(path, "path") }.and_then(|path| {
+        if !GBZ::is_gbz(path) {
+            return Err(format!("{} is not a GBZ file", path));
+        }
+        algorithms::hash_file::<sha2::Sha256, _>(path, NodeIds::Integer)
+    }));
+    string_result(result)
+}
+
+//-----------------------------------------------------------------------------
+
+/// Creates a graph name handle with the given stable name and no relationships.
+///
+/// # Safety
+///
+/// `name` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_graph_name_new(name: *const c_char) -> *mut GraphName {
+    let result = catch_panic(|| unsafe { c_str(name, "name") }.map(|name| GraphName::new(String::from(name))));
+    handle_result(result)
+}
+
+/// Parses a graph name handle from GFA or GAF header lines.
+///
+/// The lines are separated by newlines, and empty lines are ignored.
+/// Returns `NULL` if the header cannot be parsed.
+///
+/// # Safety
+///
+/// `header` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_graph_name_from_header(header: *const c_char) -> *mut GraphName {
+    let result = catch_panic(|| unsafe { c_str(header, "header") }.and_then(|header| {
+        let lines: Vec<String> = header.lines().filter(|line| !line.is_empty()).map(String::from).collect();
+        GraphName::from_header_lines(&lines)
+    }));
+    handle_result(result)
+}
+
+/// Releases a graph name handle.
+///
+/// # Safety
+///
+/// The pointer must be `NULL` or a handle returned by this library that has not been released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_graph_name_free(name: *mut GraphName) {
+    if !name.is_null() {
+        let result = catch_panic(|| {
+            drop(unsafe { Box::from_raw(name) });
+            Ok(())
+        });
+        if let Err(message) = result {
+            set_error(message);
+        }
+    }
+}
+
+/// Returns the stable name stored in the handle.
+///
+/// Returns `NULL` if the handle is `NULL` or does not contain a name.
+///
+/// # Safety
+///
+/// `name` must be `NULL` or a valid graph name handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_graph_name_name(name: *const GraphName) -> *mut c_char {
+    let result = catch_panic(|| unsafe { graph_name(name, "graph name") }.and_then(|name| {
+        name.name().cloned().ok_or(String::from("The graph has no name"))
+    }));
+    string_result(result)
+}
+
+/// Returns the relationships stored in the handle as GAF header lines.
+///
+/// Each line ends with a newline.
+///
+/// # Safety
+///
+/// `name` must be `NULL` or a valid graph name handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_graph_name_gaf_header(name: *const GraphName) -> *mut c_char {
+    let result = catch_panic(|| unsafe { graph_name(name, "graph name") }.map(|name| {
+        name.to_gaf_header_lines().iter().map(|line| format!("{}\n", line)).collect()
+    }));
+    string_result(result)
+}
+
+/// Returns the relationships stored in the handle as GFA header lines.
+///
+/// Each line ends with a newline.
+///
+/// # Safety
+///
+/// `name` must be `NULL` or a valid graph name handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_graph_name_gfa_header(name: *const GraphName) -> *mut c_char {
+    let result = catch_panic(|| unsafe { graph_name(name, "graph name") }.map(|name| {
+        name.to_gfa_header_lines().iter().map(|line| format!("{}\n", line)).collect()
+    }));
+    string_result(result)
+}
+
+//-----------------------------------------------------------------------------
+
+/// Returns `1` if the handles represent the same graph, `0` if not, and `-1` on error.
+///
+/// # Safety
+///
+/// Both pointers must be `NULL` or valid graph name handles.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_is_same(a: *const GraphName, b: *const GraphName) -> c_int {
+    let result = catch_panic(|| unsafe { graph_name_pair(a, b) }.map(|(a, b)| a.is_same(b)));
+    bool_result(result)
+}
+
+/// Returns `1` if graph `a` is a subgraph of graph `b`, `0` if not, and `-1` on error.
+///
+/// Uses relationships stored in both handles.
+///
+/// # Safety
+///
+/// Both pointers must be `NULL` or valid graph name handles.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_is_subgraph_of(a: *const GraphName, b: *const GraphName) -> c_int {
+    let result = catch_panic(|| unsafe { graph_name_pair(a, b) }.map(|(a, b)| a.is_subgraph_of(b)));
+    bool_result(result)
+}
+
+/// Returns `1` if coordinates in graph `a` can be translated to graph `b`, `0` if not, and `-1` on error.
+///
+/// Uses relationships stored in both handles.
+///
+/// # Safety
+///
+/// Both pointers must be `NULL` or valid graph name handles.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_translates_to(a: *const GraphName, b: *const GraphName) -> c_int {
+    let result = catch_panic(|| unsafe { graph_name_pair(a, b) }.map(|(a, b)| a.translates_to(b)));
+    bool_result(result)
+}
+
+/// Returns a multi-line description of the relationship between graphs `a` and `b`.
+///
+/// `a_desc` and `b_desc` describe the graphs in the output.
+///
+/// # Safety
+///
+/// Both handles must be `NULL` or valid graph name handles, and both descriptions must be valid NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pggname_describe_relationship(
+    a: *const GraphName, b: *const GraphName,
+    a_desc: *const c_char, b_desc: *const c_char
+) -> *mut c_char {
+    let result = catch_panic(|| unsafe {
+        graph_name_pair(a, b).and_then(|(a, b)| {
+            let a_desc = c_str(a_desc, "first description")?;
+            let b_desc = c_str(b_desc, "second description")?;
+            Ok(a.describe_relationship(b, a_desc, b_desc))
+        })
+    });
+    string_result(result)
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,104 @@
+use super::*;
+
+use gbz::support;
+
+//-----------------------------------------------------------------------------
+
+const EXAMPLE_NAME: &str = "81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970";
+
+fn c_string(value: &str) -> CString {
+    CString::new(value).unwrap()
+}
+
+// Takes ownership of a string returned by the library.
+fn take_string(value: *mut c_char) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    let result = unsafe { CStr::from_ptr(value) }.to_str().unwrap().to_string();
+    unsafe { pggname_string_free(value) };
+    Some(result)
+}
+
+fn last_error() -> String {
+    let error = pggname_last_error();
+    assert!(!error.is_null(), "No error message");
+    unsafe { CStr::from_ptr(error) }.to_str().unwrap().to_string()
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn hash_files() {
+    let gfa = c_string(support::get_test_data("example.gfa").to_str().unwrap());
+    let gbz = c_string(support::get_test_data("example.gbz").to_str().unwrap());
+
+    let name = take_string(unsafe { pggname_hash_gfa_path(gfa.as_ptr()) });
+    assert_eq!(name.as_deref(), Some(EXAMPLE_NAME), "Wrong name for the GFA file");
+    let name = take_string(unsafe { pggname_hash_gbz_path(gbz.as_ptr()) });
+    assert_eq!(name.as_deref(), Some(EXAMPLE_NAME), "Wrong name for the GBZ file");
+
+    assert!(take_string(unsafe { pggname_hash_gfa_path(gbz.as_ptr()) }).is_none(), "Hashed a GBZ file as GFA");
+    assert!(last_error().contains("GBZ"), "Wrong error message: {}", last_error());
+    assert!(take_string(unsafe { pggname_hash_gbz_path(gfa.as_ptr()) }).is_none(), "Hashed a GFA file as GBZ");
+    assert!(take_string(unsafe { pggname_hash_gfa_path(ptr::null()) }).is_none(), "Hashed a NULL path");
+}
+
+#[test]
+fn relationships() {
+    let header = c_string("@RN\tC\n@SG\tC\tB\n\n@TL\tB\tA\n");
+    let c = unsafe { pggname_graph_name_from_header(header.as_ptr()) };
+    assert!(!c.is_null(), "Failed to parse the header: {}", last_error());
+    let a_name = c_string("A");
+    let a = unsafe { pggname_graph_name_new(a_name.as_ptr()) };
+
+    assert_eq!(take_string(unsafe { pggname_graph_name_name(c) }).as_deref(), Some("C"), "Wrong graph name");
+    let gfa_header = take_string(unsafe { pggname_graph_name_gfa_header(c) });
+    assert_eq!(gfa_header.as_deref(), Some("H\tNM:Z:C\nH\tMV:Z:1\nH\tSG:Z:C,B\nH\tTL:Z:B,A\n"), "Wrong GFA header");
+    let gaf_header = take_string(unsafe { pggname_graph_name_gaf_header(c) });
+    assert_eq!(gaf_header.as_deref(), Some("@RN\tC\n@MV\t1\n@SG\tC\tB\n@TL\tB\tA\n"), "Wrong GAF header");
+
+    unsafe {
+        assert_eq!(pggname_is_same(c, c), 1, "C is not the same as itself");
+        assert_eq!(pggname_is_same(c, a), 0, "C is the same as A");
+        assert_eq!(pggname_is_subgraph_of(c, a), 0, "C is a subgraph of A");
+        assert_eq!(pggname_translates_to(c, a), 1, "C does not translate to A");
+        assert_eq!(pggname_translates_to(a, c), 0, "A translates to C");
+        assert_eq!(pggname_translates_to(a, ptr::null()), -1, "Queried a NULL handle");
+    }
+
+    let (c_desc, a_desc) = (c_string("sampled"), c_string("original"));
+    let description = take_string(unsafe { pggname_describe_relationship(c, a, c_desc.as_ptr(), a_desc.as_ptr()) }).unwrap();
+    assert!(description.starts_with("Name 1 is for sampled\n"), "Wrong description: {}", description);
+
+    unsafe {
+        pggname_graph_name_free(c);
+        pggname_graph_name_free(a);
+    }
+
+    let invalid = c_string("X\tY\n");
+    assert!(unsafe { pggname_graph_name_from_header(invalid.as_ptr()) }.is_null(), "Parsed an invalid header");
+    assert!(last_error().contains("unknown first field"), "Wrong error message: {}", last_error());
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn panics() {
+    let result: Result<(), String> = catch_panic(|| panic!("Static message"));
+    assert_eq!(result, Err(String::from("Panic in pggname: Static message")), "Wrong error for a static panic message");
+    let result: Result<(), String> = catch_panic(|| panic!("Formatted {}", "message"));
+    assert_eq!(result, Err(String::from("Panic in pggname: Formatted message")), "Wrong error for a formatted panic message");
+
+    let pointer = string_result(catch_panic(|| panic!("String result")));
+    assert!(pointer.is_null(), "Got a string after a panic");
+    assert_eq!(last_error(), "Panic in pggname: String result", "Wrong last error after a panic");
+    let result = bool_result(catch_panic(|| panic!("Bool result")));
+    assert_eq!(result, -1, "Got a boolean after a panic");
+    assert_eq!(last_error(), "Panic in pggname: Bool result", "Wrong last error after a panic");
+    let handle = handle_result(catch_panic(|| panic!("Handle result")));
+    assert!(handle.is_null(), "Got a handle after a panic");
+    assert_eq!(last_error(), "Panic in pggname: Handle result", "Wrong last error after a panic");
+}
+
+//-----------------------------------------------------------------------------
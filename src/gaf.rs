@@ -0,0 +1,488 @@
+//! Rewriting GAF alignments across graph translations.
+//!
+//! A GAF line stores the alignment target as a path of oriented nodes, such as `>1<2>3`, together with path length and path coordinates.
+//! When graph A can be translated to graph C, an alignment to A can be rewritten as an alignment to C using a [`TranslationMap`].
+//! Node visits are replaced with the corresponding visits in C, and path coordinates are adjusted if the path in C starts in the middle of a node.
+//! The sequence of the path does not change, so the rest of the alignment (including CIGAR strings) remains valid.
+//!
+//! Alignment paths given as stable coordinates (e.g. `chr1:100-200`) are not supported.
+//!
+//! The graph name is normally stored in the `@RN` header line.
+//! Because the header is lost when GAF files are concatenated or sharded, each record can also be tagged with the short graph name as an optional field `rn:Z:<short name>`.
+//! See [`tag_record`] and [`record_name`].
+//!
+//! [`GafValidator`] checks that alignment records are consistent with a graph.
+//! [`path_sequence`] returns the sequence of an alignment path in a graph.
+//! [`GafValidatorSet`] does the same for files with records aligned to multiple graphs, which can be listed with [`GraphName::from_gaf`].
+
+use crate::{Graph, GraphName};
+use crate::graph::Orientation;
+use crate::translation::{SequenceGraph, TranslationMap};
+
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Parses an alignment path of the form `>1<2>3` into oriented node names.
+///
+/// Returns an error if the path is empty or not of that form.
+pub fn parse_path(path: &str) -> Result<Vec<(Vec<u8>, Orientation)>, String> {
+    let bytes = path.as_bytes();
+    if bytes.is_empty() || (bytes[0] != b'>' && bytes[0] != b'<') {
+        return Err(format!("Unsupported alignment path: {}", path));
+    }
+
+    let mut result = Vec::new();
+    let mut start = 0;
+    for i in 1..=bytes.len() {
+        if i == bytes.len() || bytes[i] == b'>' || bytes[i] == b'<' {
+            if i == start + 1 {
+                return Err(format!("Empty node name in alignment path: {}", path));
+            }
+            let orientation = if bytes[start] == b'>' { Orientation::Forward } else { Orientation::Reverse };
+            result.push((bytes[start + 1..i].to_vec(), orientation));
+            start = i;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Formats oriented node names as an alignment path of the form `>1<2>3`.
+pub fn format_path(path: &[(Vec<u8>, Orientation)]) -> String {
+    let mut result = String::new();
+    for (node, orientation) in path.iter() {
+        result.push(match orientation {
+            Orientation::Forward => '>',
+            Orientation::Reverse => '<',
+        });
+        result.push_str(&String::from_utf8_lossy(node));
+    }
+    result
+}
+
+/// Returns the sequence of an alignment path in the graph.
+///
+/// The sequence is the concatenation of the node sequences in the given orientations, as returned by [`Graph::sequence`].
+/// Edges between consecutive nodes are not checked; see [`GafValidator`] for that.
+/// Returns an error if a node does not exist.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::gaf;
+/// use pggname::graph::GraphInt;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tGAT\nS\t2\tTACA\nL\t1\t+\t2\t+\n").unwrap();
+/// let path = gaf::parse_path(">1>2").unwrap();
+/// assert_eq!(gaf::path_sequence(&graph, &path), Ok(b"GATTACA".to_vec()));
+/// let path = gaf::parse_path("<2<1").unwrap();
+/// assert_eq!(gaf::path_sequence(&graph, &path), Ok(b"TGTAATC".to_vec()));
+/// ```
+pub fn path_sequence<G: Graph>(graph: &G, path: &[(Vec<u8>, Orientation)]) -> Result<Vec<u8>, String> {
+    let mut result = Vec::new();
+    for (node, orientation) in path.iter() {
+        let sequence = graph.sequence(node, *orientation).ok_or(format!("Node {} does not exist", String::from_utf8_lossy(node)))?;
+        result.extend_from_slice(&sequence);
+    }
+    Ok(result)
+}
+
+//-----------------------------------------------------------------------------
+
+/// An alignment path lifted to the target graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LiftedPath {
+    /// Oriented target nodes visited by the path.
+    pub path: Vec<(Vec<u8>, Orientation)>,
+    /// Offset of the original path in the lifted path.
+    pub offset: usize,
+    /// Total length of the nodes in the lifted path.
+    pub len: usize,
+}
+
+/// Rewrites GAF lines using a translation map.
+///
+/// The lengths of the target nodes are derived from the map.
+/// This assumes that each target node used by the map is covered entirely, which is true for translations produced by this crate.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::gaf::GafLifter;
+/// use pggname::translation::TranslationMap;
+///
+/// // Node 1 (GATTACA) was chopped into nodes 10 (GAT) and 11 (TACA).
+/// let map = TranslationMap::read(b"1\t0\t3\t10\t0\t+\n1\t3\t7\t11\t0\t+\n".as_slice()).unwrap();
+/// let lifter = GafLifter::new(&map);
+/// let line = "read\t4\t0\t4\t+\t>1\t7\t2\t6\t4\t4\t60";
+/// assert_eq!(lifter.lift_record(line).unwrap(), "read\t4\t0\t4\t+\t>10>11\t7\t2\t6\t4\t4\t60");
+/// ```
+pub struct GafLifter<'a> {
+    map: &'a TranslationMap,
+    target_lengths: HashMap<Vec<u8>, usize>,
+}
+
+impl<'a> GafLifter<'a> {
+    // Column indexes in GAF lines.
+    const PATH: usize = 5;
+    const PATH_LEN: usize = 6;
+    const PATH_START: usize = 7;
+    const PATH_END: usize = 8;
+    const MANDATORY_FIELDS: usize = 12;
+
+    /// Creates a new lifter for the given map.
+    pub fn new(map: &'a TranslationMap) -> Self {
+        let mut target_lengths: HashMap<Vec<u8>, usize> = HashMap::new();
+        for interval in map.intervals() {
+            let len = target_lengths.entry(interval.to.clone()).or_default();
+            *len = (*len).max(interval.to_range().end);
+        }
+        GafLifter { map, target_lengths }
+    }
+
+    /// Lifts an alignment path to the target graph.
+    ///
+    /// Returns an error if a node is not covered by the map or if the translated visits do not form a valid path.
+    pub fn lift_path(&self, path: &[(Vec<u8>, Orientation)]) -> Result<LiftedPath, String> {
+        // Visits to target nodes as (node, orientation, interval in the oriented node).
+        let mut visits: Vec<(Vec<u8>, Orientation, usize, usize)> = Vec::new();
+        for (node, orientation) in path.iter() {
+            let intervals = self.map.node_intervals(node);
+            let mut expected_start = 0;
+            for interval in intervals.iter() {
+                if interval.from_range.start != expected_start {
+                    break;
+                }
+                expected_start = interval.from_range.end;
+            }
+            if intervals.is_empty() || expected_start != intervals.last().unwrap().from_range.end {
+                return Err(format!("Node {} is not fully covered by the translation", String::from_utf8_lossy(node)));
+            }
+
+            let mut pieces: Vec<_> = intervals.iter().map(|interval| {
+                let target_o = match interval.orientation {
+                    Orientation::Forward => *orientation,
+                    Orientation::Reverse => orientation.flip(),
+                };
+                let len = self.target_lengths[&interval.to];
+                let range = interval.to_range();
+                let (start, end) = match target_o {
+                    Orientation::Forward => (range.start, range.end),
+                    Orientation::Reverse => (len - range.end, len - range.start),
+                };
+                (interval.to.clone(), target_o, start, end)
+            }).collect();
+            if *orientation == Orientation::Reverse {
+                pieces.reverse();
+            }
+
+            for piece in pieces {
+                if let Some(last) = visits.last_mut() {
+                    if last.0 == piece.0 && last.1 == piece.1 && last.3 == piece.2 {
+                        last.3 = piece.3;
+                        continue;
+                    }
+                    if last.3 != self.target_lengths[&last.0] || piece.2 != 0 {
+                        return Err(format!(
+                            "Translated path is not contiguous between nodes {} and {}",
+                            String::from_utf8_lossy(&last.0), String::from_utf8_lossy(&piece.0)
+                        ));
+                    }
+                }
+                visits.push(piece);
+            }
+        }
+
+        let offset = visits.first().map(|visit| visit.2).unwrap_or(0);
+        let len = visits.iter().map(|visit| self.target_lengths[&visit.0]).sum();
+        let path = visits.into_iter().map(|(node, o, _, _)| (node, o)).collect();
+        Ok(LiftedPath { path, offset, len })
+    }
+
+    /// Lifts a GAF line to the target graph.
+    ///
+    /// The line must not end with a newline.
+    /// Unaligned records (path `*`) are returned unchanged.
+    /// Returns an error if the line cannot be parsed or the path cannot be lifted.
+    pub fn lift_record(&self, line: &str) -> Result<String, String> {
+        let mut fields: Vec<String> = line.split('\t').map(String::from).collect();
+        if fields.len() < Self::MANDATORY_FIELDS {
+            return Err(format!("Expected at least {} fields, found {}", Self::MANDATORY_FIELDS, fields.len()));
+        }
+        if fields[Self::PATH] == "*" {
+            return Ok(String::from(line));
+        }
+
+        let path = parse_path(&fields[Self::PATH])?;
+        let path_len = parse_usize(&fields[Self::PATH_LEN], "path length")?;
+        let path_start = parse_usize(&fields[Self::PATH_START], "path start")?;
+        let path_end = parse_usize(&fields[Self::PATH_END], "path end")?;
+        let lifted = self.lift_path(&path)?;
+        if lifted.offset + path_len > lifted.len {
+            return Err(format!("Path length {} does not match the translated path", path_len));
+        }
+
+        fields[Self::PATH] = format_path(&lifted.path);
+        fields[Self::PATH_LEN] = lifted.len.to_string();
+        fields[Self::PATH_START] = (path_start + lifted.offset).to_string();
+        fields[Self::PATH_END] = (path_end + lifted.offset).to_string();
+        Ok(fields.join("\t"))
+    }
+}
+
+fn parse_usize(field: &str, description: &str) -> Result<usize, String> {
+    field.parse::<usize>().map_err(|_| format!("Invalid {}: {}", description, field))
+}
+
+//-----------------------------------------------------------------------------
+
+/// Rewrites GAF header lines for an alignment lifted from graph `from` to graph `to`.
+///
+/// The reference name (`@RN`) becomes `to`, existing relationships are preserved, and a translation from `from` to `to` is added.
+/// Other header lines are kept as they are.
+/// The lines must not end with a newline.
+///
+/// Returns an error if the header lines cannot be parsed or if they name a graph other than `from`.
+pub fn lift_header_lines(lines: &[String], from: &str, to: &str) -> Result<Vec<String>, String> {
+    let original = GraphName::from_header_lines(lines)?;
+    if let Some(name) = original.name() && name != from {
+        return Err(format!("Alignments are to graph {}, not {}", name, from));
+    }
+
+    let mut target = GraphName::new(String::from(to));
+    target.add_relationships(&original);
+    target.add_translation(from, to);
+    let mut result = target.to_gaf_header_lines();
+    for line in lines.iter() {
+        if !GraphName::is_gaf_header_line(line) {
+            result.push(line.clone());
+        }
+    }
+    Ok(result)
+}
+
+//-----------------------------------------------------------------------------
+
+/// Tags a GAF line with the short form of the graph name as an optional field `rn:Z:<short name>`.
+///
+/// An existing `rn` field is replaced, and other fields are kept as they are.
+/// The line must not end with a newline.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::gaf;
+///
+/// let name = "7f4b28c71ceb808aebd8b8e9fe85e79d0d208ee263ffe9fcdef5ade20534ceb5";
+/// let line = "read\t4\t0\t4\t+\t>1\t7\t2\t6\t4\t4\t60\trn:Z:0123456789abcdef";
+/// let tagged = gaf::tag_record(line, name);
+/// assert_eq!(tagged, "read\t4\t0\t4\t+\t>1\t7\t2\t6\t4\t4\t60\trn:Z:7f4b28c71ceb808a");
+/// assert_eq!(gaf::record_name(&tagged), Some("7f4b28c71ceb808a"));
+/// ```
+pub fn tag_record(line: &str, name: &str) -> String {
+    let mut fields: Vec<&str> = line.split('\t').enumerate()
+        .filter(|(i, field)| *i < GafLifter::MANDATORY_FIELDS || record_name_field(field).is_none())
+        .map(|(_, field)| field)
+        .collect();
+    let field = format!("{}:Z:{}", GraphName::GAF_RECORD_NAME, GraphName::short_name_for(name));
+    fields.push(&field);
+    fields.join("\t")
+}
+
+/// Returns the short graph name stored in the `rn:Z:` field of a GAF line, or [`None`] if there is no such field.
+pub fn record_name(line: &str) -> Option<&str> {
+    line.split('\t').skip(GafLifter::MANDATORY_FIELDS).find_map(record_name_field)
+}
+
+// Returns the value of the field, if it is a graph name field.
+fn record_name_field(field: &str) -> Option<&str> {
+    field.strip_prefix(GraphName::GAF_RECORD_NAME).and_then(|rest| rest.strip_prefix(":Z:"))
+}
+
+//-----------------------------------------------------------------------------
+
+/// Checks that GAF records are consistent with a graph.
+///
+/// A record is consistent if all nodes in the alignment path exist, consecutive visits are connected by edges, the path length is the total length of the visited nodes, and the path interval is within the path.
+/// Unaligned records (path `*`) are always consistent.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::gaf::GafValidator;
+/// use pggname::graph::GraphInt;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tGAT\nS\t2\tTACA\nL\t1\t+\t2\t+\n").unwrap();
+/// let validator = GafValidator::new(&graph).unwrap();
+/// assert_eq!(validator.validate_record("read\t4\t0\t4\t+\t>1>2\t7\t2\t6\t4\t4\t60"), Ok(true));
+/// assert!(validator.validate_record("read\t4\t0\t4\t+\t>2>1\t7\t2\t6\t4\t4\t60").is_err());
+/// ```
+pub struct GafValidator {
+    graph: SequenceGraph,
+    index: HashMap<Vec<u8>, usize>,
+}
+
+impl GafValidator {
+    /// Creates a validator for the graph.
+    ///
+    /// Returns an error if the canonical representation of the graph cannot be interpreted.
+    pub fn new<G: Graph>(graph: &G) -> Result<Self, String> {
+        let graph = SequenceGraph::new(graph)?;
+        let index = graph.names.iter().enumerate().map(|(i, name)| (name.clone(), i)).collect();
+        Ok(GafValidator { graph, index })
+    }
+
+    /// Validates a GAF line.
+    ///
+    /// The line must not end with a newline.
+    /// Returns `true` if the record is aligned and `false` if it is unaligned.
+    /// Returns an error describing the first inconsistency found.
+    pub fn validate_record(&self, line: &str) -> Result<bool, String> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < GafLifter::MANDATORY_FIELDS {
+            return Err(format!("Expected at least {} fields, found {}", GafLifter::MANDATORY_FIELDS, fields.len()));
+        }
+        if fields[GafLifter::PATH] == "*" {
+            return Ok(false);
+        }
+
+        let path = parse_path(fields[GafLifter::PATH])?;
+        let path_len = parse_usize(fields[GafLifter::PATH_LEN], "path length")?;
+        let path_start = parse_usize(fields[GafLifter::PATH_START], "path start")?;
+        let path_end = parse_usize(fields[GafLifter::PATH_END], "path end")?;
+
+        let mut handles = Vec::with_capacity(path.len());
+        for (node, orientation) in path.iter() {
+            let index = *self.index.get(node).ok_or(format!("Node {} does not exist", String::from_utf8_lossy(node)))?;
+            handles.push((index, *orientation));
+        }
+        for (i, pair) in handles.windows(2).enumerate() {
+            if !self.graph.has_edge(pair[0], pair[1]) {
+                return Err(format!("No edge from {} to {}", format_path(&path[i..i + 1]), format_path(&path[i + 1..i + 2])));
+            }
+        }
+
+        let len: usize = handles.iter().map(|handle| self.graph.sequences[handle.0].len()).sum();
+        if path_len != len {
+            return Err(format!("Path length {} does not match the total node length {}", path_len, len));
+        }
+        if path_start > path_end || path_end > path_len {
+            return Err(format!("Invalid path interval {}..{} for path length {}", path_start, path_end, path_len));
+        }
+        Ok(true)
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// Checks GAF records against a collection of graphs.
+///
+/// A GAF file may contain records aligned to different graphs, for example after concatenating files aligned to per-chromosome graphs.
+/// Each record is validated with a [`GafValidator`] for the graph named by its `rn:Z:` field.
+/// Records without the field are validated against the default graph, which is normally the graph named by the `@RN` header line.
+/// If there is no default graph, a collection with a single graph uses it as the default.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::gaf::{self, GafValidatorSet};
+/// use pggname::graph::GraphInt;
+///
+/// let a: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tGAT\nS\t2\tTACA\nL\t1\t+\t2\t+\n").unwrap();
+/// let b: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tGATTACA\n").unwrap();
+/// let mut validators = GafValidatorSet::new();
+/// let a_name = validators.add(&a).unwrap();
+/// let b_name = validators.add(&b).unwrap();
+/// validators.set_default(&a_name).unwrap();
+///
+/// let record = "read\t4\t0\t4\t+\t>1>2\t7\t2\t6\t4\t4\t60";
+/// assert_eq!(validators.validate_record(record), Ok(true));
+/// assert!(validators.validate_record(&gaf::tag_record(record, &b_name)).is_err());
+/// let record = "read\t4\t0\t4\t+\t>1\t7\t2\t6\t4\t4\t60";
+/// assert_eq!(validators.validate_record(&gaf::tag_record(record, &b_name)), Ok(true));
+/// ```
+#[derive(Default)]
+pub struct GafValidatorSet {
+    // Graph names and validators in the order they were added.
+    validators: Vec<(String, GafValidator)>,
+    default: Option<usize>,
+}
+
+impl GafValidatorSet {
+    /// Creates an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a graph to the collection and returns its name.
+    ///
+    /// Returns an error if the graph is already in the collection, if another graph has the same short name, or if the validator cannot be created.
+    pub fn add<G: Graph>(&mut self, graph: &G) -> Result<String, String> {
+        let name = crate::stable_name(graph);
+        let short = GraphName::short_name_for(&name);
+        if let Some((other, _)) = self.validators.iter().find(|(other, _)| GraphName::short_name_for(other) == short) {
+            if *other == name {
+                return Err(format!("Graph {} is already in the collection", name));
+            }
+            return Err(format!("Graphs {} and {} have the same short name", other, name));
+        }
+        let validator = GafValidator::new(graph)?;
+        self.validators.push((name.clone(), validator));
+        Ok(name)
+    }
+
+    /// Returns the number of graphs in the collection.
+    pub fn len(&self) -> usize {
+        self.validators.len()
+    }
+
+    /// Returns `true` if the collection is empty.
+    pub fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    /// Returns `true` if the collection contains a graph with the given name or short name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.find(name).is_some()
+    }
+
+    /// Sets the default graph for records without a graph name field.
+    ///
+    /// The name may be a full name or a short name.
+    /// Returns an error if there is no such graph in the collection.
+    pub fn set_default(&mut self, name: &str) -> Result<(), String> {
+        let index = self.find(name).ok_or(format!("Graph {} is not in the collection", name))?;
+        self.default = Some(index);
+        Ok(())
+    }
+
+    /// Validates a GAF line against the graph it refers to.
+    ///
+    /// The line must not end with a newline.
+    /// Returns `true` if the record is aligned and `false` if it is unaligned.
+    /// Returns an error if the graph is not in the collection, if the record has no graph name field and there is no default graph, or if the record is not consistent with the graph.
+    pub fn validate_record(&self, line: &str) -> Result<bool, String> {
+        let index = match record_name(line) {
+            Some(name) => self.find(name).ok_or(format!("Graph {} is not in the collection", name))?,
+            None => self.default.or(if self.validators.len() == 1 { Some(0) } else { None })
+                .ok_or(String::from("No graph name field and no default graph"))?,
+        };
+        self.validators[index].1.validate_record(line)
+    }
+
+    // Returns the index of the graph with the given name or short name.
+    fn find(&self, name: &str) -> Option<usize> {
+        self.validators.iter().position(|(other, _)| {
+            other == name || (name.len() <= GraphName::SHORT_NAME_LEN && GraphName::short_name_for(other) == name)
+        })
+    }
+}
+
+//-----------------------------------------------------------------------------
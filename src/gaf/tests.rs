@@ -0,0 +1,198 @@
+use super::*;
+
+use crate::algorithms;
+use crate::graph::GraphStr;
+
+//-----------------------------------------------------------------------------
+
+// Nodes 1 (GAT), 2 (T), and 3 (TG) in graph A.
+const SOURCE: &str = "S\t1\tGAT\nS\t2\tT\nS\t3\tTG\nL\t1\t+\t2\t+\nL\t2\t+\t3\t-\n";
+
+// The same sequence GATTCA with nodes a (GA), b (TT), and c (CA) in graph C.
+const TARGET: &str = "S\ta\tGA\nS\tb\tTT\nS\tc\tCA\nL\ta\t+\tb\t+\nL\tb\t+\tc\t+\n";
+
+fn map() -> TranslationMap {
+    let source: GraphStr = algorithms::parse_gfa_bytes(SOURCE.as_bytes()).unwrap();
+    let target: GraphStr = algorithms::parse_gfa_bytes(TARGET.as_bytes()).unwrap();
+    algorithms::infer_translation(&source, &target).unwrap().mapping
+}
+
+fn node(name: &str, orientation: Orientation) -> (Vec<u8>, Orientation) {
+    (name.as_bytes().to_vec(), orientation)
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn paths() {
+    let path = parse_path(">1<22>3").unwrap();
+    let expected = vec![node("1", Orientation::Forward), node("22", Orientation::Reverse), node("3", Orientation::Forward)];
+    assert_eq!(path, expected, "Wrong parsed path");
+    assert_eq!(format_path(&path), ">1<22>3", "Wrong formatted path");
+
+    assert!(parse_path("").is_err(), "Parsed an empty path");
+    assert!(parse_path("chr1:100-200").is_err(), "Parsed a stable path");
+    assert!(parse_path(">1<>3").is_err(), "Parsed a path with an empty node name");
+}
+
+#[test]
+fn lift_paths() {
+    let map = map();
+    let lifter = GafLifter::new(&map);
+
+    // >2<3 = TCA starts in the middle of node b.
+    let lifted = lifter.lift_path(&[node("2", Orientation::Forward), node("3", Orientation::Reverse)]).unwrap();
+    let expected = LiftedPath {
+        path: vec![node("b", Orientation::Forward), node("c", Orientation::Forward)],
+        offset: 1,
+        len: 4,
+    };
+    assert_eq!(lifted, expected, "Wrong lifted path");
+
+    // >3<2<1 = TGAATC in reverse.
+    let lifted = lifter.lift_path(&[node("3", Orientation::Forward), node("2", Orientation::Reverse), node("1", Orientation::Reverse)]).unwrap();
+    let expected = LiftedPath {
+        path: vec![node("c", Orientation::Reverse), node("b", Orientation::Reverse), node("a", Orientation::Reverse)],
+        offset: 0,
+        len: 6,
+    };
+    assert_eq!(lifted, expected, "Wrong lifted reverse path");
+
+    assert!(lifter.lift_path(&[node("4", Orientation::Forward)]).is_err(), "Lifted a missing node");
+    assert!(lifter.lift_path(&[node("1", Orientation::Forward), node("3", Orientation::Forward)]).is_err(), "Lifted a non-contiguous path");
+}
+
+#[test]
+fn path_sequences() {
+    let source: GraphStr = algorithms::parse_gfa_bytes(SOURCE.as_bytes()).unwrap();
+    let target: GraphStr = algorithms::parse_gfa_bytes(TARGET.as_bytes()).unwrap();
+    let map = map();
+    let lifter = GafLifter::new(&map);
+
+    // Lifted paths spell the original sequence starting from the offset.
+    for path in [">1>2<3", ">2<3", "<2<1", ">3<2<1"] {
+        let path = parse_path(path).unwrap();
+        let sequence = path_sequence(&source, &path).unwrap();
+        let lifted = lifter.lift_path(&path).unwrap();
+        let lifted_sequence = path_sequence(&target, &lifted.path).unwrap();
+        assert_eq!(lifted_sequence.len(), lifted.len, "Wrong lifted path length for {}", format_path(&path));
+        assert_eq!(&lifted_sequence[lifted.offset..lifted.offset + sequence.len()], sequence.as_slice(), "Wrong lifted sequence for {}", format_path(&path));
+    }
+    assert_eq!(path_sequence(&source, &parse_path(">3<2<1").unwrap()), Ok(b"TGAATC".to_vec()), "Wrong reverse path sequence");
+
+    assert!(path_sequence(&source, &[node("4", Orientation::Forward)]).is_err(), "Found a sequence for a missing node");
+}
+
+#[test]
+fn lift_records() {
+    let map = map();
+    let lifter = GafLifter::new(&map);
+
+    let line = "read\t3\t0\t3\t+\t>2<3\t3\t0\t3\t3\t3\t60\tcs:Z::3";
+    let expected = "read\t3\t0\t3\t+\t>b>c\t4\t1\t4\t3\t3\t60\tcs:Z::3";
+    assert_eq!(lifter.lift_record(line).unwrap(), expected, "Wrong lifted record");
+
+    let unaligned = "read\t3\t*\t*\t*\t*\t*\t*\t*\t0\t0\t255";
+    assert_eq!(lifter.lift_record(unaligned).unwrap(), unaligned, "Unaligned record was changed");
+
+    assert!(lifter.lift_record("read\t3\t0\t3\t+\t>2<3").is_err(), "Lifted a truncated record");
+    assert!(lifter.lift_record("read\t3\t0\t3\t+\t>2<3\tx\t0\t3\t3\t3\t60").is_err(), "Lifted a record with an invalid path length");
+}
+
+#[test]
+fn header_lines() {
+    let lines = vec![String::from("@HD\tVN:Z:1.0"), String::from("@RN\tA"), String::from("@SG\tA\tB")];
+    let lifted = lift_header_lines(&lines, "A", "C").unwrap();
+    let expected = vec![
+        String::from("@RN\tC"),
+        String::from("@MV\t1"),
+        String::from("@SG\tA\tB"),
+        String::from("@TL\tA\tC"),
+        String::from("@HD\tVN:Z:1.0"),
+    ];
+    assert_eq!(lifted, expected, "Wrong lifted header lines");
+
+    assert!(lift_header_lines(&lines, "B", "C").is_err(), "Lifted header lines for the wrong graph");
+    let lifted = lift_header_lines(&[], "A", "C").unwrap();
+    assert_eq!(lifted, vec![String::from("@RN\tC"), String::from("@MV\t1"), String::from("@TL\tA\tC")], "Wrong header lines without a header");
+}
+
+#[test]
+fn record_tags() {
+    let name = "1f133f116e8dd98fc07a647a8954038c2bcf07a45759ba94718471fe34ed7a7c";
+    let short = GraphName::short_name_for(name);
+    assert_eq!(short, "1f133f116e8dd98f", "Wrong short name");
+    assert_eq!(GraphName::short_name_for("A"), "A", "Wrong short name for a short name");
+
+    let line = "read\t3\t0\t3\t+\t>2<3\t3\t0\t3\t3\t3\t60\tcs:Z::3";
+    assert_eq!(record_name(line), None, "Found a name in an untagged record");
+    let tagged = tag_record(line, name);
+    assert_eq!(tagged, format!("{}\trn:Z:{}", line, short), "Wrong tagged record");
+    assert_eq!(record_name(&tagged), Some(short), "Wrong name in a tagged record");
+
+    // Tagging again replaces the existing tag.
+    let retagged = tag_record(&tagged, "0123456789abcdef0123");
+    assert_eq!(retagged, format!("{}\trn:Z:0123456789abcdef", line), "Wrong retagged record");
+
+    // Mandatory fields are never treated as tags.
+    let unaligned = "rn:Z:x\t3\t*\t*\t*\t*\t*\t*\t*\t0\t0\t255";
+    assert_eq!(record_name(unaligned), None, "Found a name in a mandatory field");
+    assert_eq!(tag_record(unaligned, name), format!("{}\trn:Z:{}", unaligned, short), "Wrong tagged unaligned record");
+}
+
+#[test]
+fn record_validation() {
+    let graph: GraphStr = algorithms::parse_gfa_bytes(SOURCE.as_bytes()).unwrap();
+    let validator = GafValidator::new(&graph).unwrap();
+
+    let tests: Vec<(&str, Result<bool, ()>, &str)> = vec![
+        ("read\t3\t0\t3\t+\t>2<3\t3\t0\t3\t3\t3\t60", Ok(true), "valid record"),
+        ("read\t3\t0\t3\t+\t>3<2\t3\t0\t3\t3\t3\t60", Ok(true), "valid record in reverse"),
+        ("read\t3\t*\t*\t*\t*\t*\t*\t*\t0\t0\t255", Ok(false), "unaligned record"),
+        ("read\t3\t0\t3\t+\t>2>3\t3\t0\t3\t3\t3\t60", Err(()), "wrong orientation"),
+        ("read\t3\t0\t3\t+\t>2<4\t3\t0\t3\t3\t3\t60", Err(()), "missing node"),
+        ("read\t3\t0\t3\t+\t>1>3\t5\t0\t3\t3\t3\t60", Err(()), "missing edge"),
+        ("read\t3\t0\t3\t+\t>2<3\t4\t0\t3\t3\t3\t60", Err(()), "wrong path length"),
+        ("read\t3\t0\t3\t+\t>2<3\t3\t2\t4\t3\t3\t60", Err(()), "interval past the end"),
+        ("read\t3\t0\t3\t+\t>2<3", Err(()), "truncated record"),
+        ("read\t3\t0\t3\t+\tchr1:0-3\t3\t0\t3\t3\t3\t60", Err(()), "stable coordinates"),
+    ];
+    for (line, expected, description) in tests {
+        assert_eq!(validator.validate_record(line).map_err(|_| ()), expected, "Wrong result for {}", description);
+    }
+}
+
+#[test]
+fn multiple_graphs() {
+    let source: GraphStr = algorithms::parse_gfa_bytes(SOURCE.as_bytes()).unwrap();
+    let target: GraphStr = algorithms::parse_gfa_bytes(TARGET.as_bytes()).unwrap();
+    let mut validators = GafValidatorSet::new();
+    assert!(validators.is_empty(), "New collection is not empty");
+    let source_name = validators.add(&source).unwrap();
+    let target_name = validators.add(&target).unwrap();
+    assert_eq!(validators.len(), 2, "Wrong number of graphs");
+    assert!(validators.add(&source).is_err(), "Added the same graph twice");
+    assert!(validators.contains(&source_name), "Full name not found");
+    assert!(validators.contains(GraphName::short_name_for(&target_name)), "Short name not found");
+    assert!(!validators.contains("0123456789abcdef"), "Found a missing graph");
+
+    let source_record = "read\t3\t0\t3\t+\t>2<3\t3\t0\t3\t3\t3\t60";
+    let target_record = "read\t3\t0\t3\t+\t>b>c\t4\t1\t4\t3\t3\t60";
+    assert!(validators.validate_record(source_record).is_err(), "Validated an untagged record without a default graph");
+    assert_eq!(validators.validate_record(&tag_record(source_record, &source_name)), Ok(true), "Source record failed with source tag");
+    assert_eq!(validators.validate_record(&tag_record(target_record, &target_name)), Ok(true), "Target record failed with target tag");
+    assert!(validators.validate_record(&tag_record(source_record, &target_name)).is_err(), "Source record passed with target tag");
+    assert!(validators.validate_record(&tag_record(source_record, "0123456789abcdef")).is_err(), "Validated against a missing graph");
+
+    assert!(validators.set_default("0123456789abcdef").is_err(), "Set a missing graph as the default");
+    validators.set_default(&target_name).unwrap();
+    assert_eq!(validators.validate_record(target_record), Ok(true), "Untagged record failed with the default graph");
+    assert!(validators.validate_record(source_record).is_err(), "Untagged record passed with the wrong default graph");
+
+    // A single graph is the default graph.
+    let mut single = GafValidatorSet::new();
+    single.add(&source).unwrap();
+    assert_eq!(single.validate_record(source_record), Ok(true), "Untagged record failed with a single graph");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,99 @@
+//! Alternative GFA parser backend using the [`gfa`](https://crates.io/crates/gfa) crate.
+//!
+//! This module is only available with the `gfa-rs` feature.
+//! It builds the same graphs as the native parser in [`crate::algorithms`], but the GFA lines are parsed by an independent implementation.
+//! Computing names with both backends is a way to cross-check the canonical serialization.
+//!
+//! The backends differ in the following ways:
+//!
+//! * L-lines must have the overlap field.
+//! * A segment sequence is replaced with the first match of `\*|[A-Za-z=.]+` in it.
+//!   If the sequence contains other characters, the names computed with the backends differ.
+//!
+//! Lines of other types than S and L are ignored, as with the native parser.
+
+use crate::Graph;
+use crate::algorithms::split_bytes;
+use crate::graph::Orientation;
+
+use gfa::gfa::{Line, Orientation as GfaOrientation};
+use gfa::parser::{GFAParser, GFAParserBuilder};
+
+use std::io::BufRead;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Builds a graph from the given GFA input using the `gfa` crate.
+///
+/// Returns an error if the GFA cannot be parsed.
+/// Passes through errors from the graph methods with the line number.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::{algorithms, gfa_rs};
+/// use pggname::graph::GraphInt;
+/// use std::io::Cursor;
+///
+/// let gfa = b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t-\t0M\n";
+/// let graph: GraphInt = gfa_rs::parse_gfa(Cursor::new(gfa)).unwrap();
+/// let native: GraphInt = algorithms::parse_gfa_bytes(gfa).unwrap();
+/// assert_eq!(pggname::stable_name(&graph), pggname::stable_name(&native));
+/// ```
+pub fn parse_gfa<G: Graph, R: BufRead>(reader: R) -> Result<G, String> {
+    let parser = new_parser();
+    let mut graph = G::new();
+    for (i, line) in reader.split(b'\n').enumerate() {
+        let line = line.map_err(|e| format!("Error reading GFA line {}: {}", i + 1, e))?;
+        parse_line(&parser, &mut graph, &line).map_err(|e| format!("Error on GFA line {}: {}", i + 1, e))?;
+    }
+    graph.finalize()?;
+    Ok(graph)
+}
+
+/// Builds a graph from GFA data stored in memory using the `gfa` crate.
+///
+/// See [`parse_gfa`] for details.
+pub fn parse_gfa_bytes<G: Graph>(data: &[u8]) -> Result<G, String> {
+    let parser = new_parser();
+    let mut graph = G::new();
+    for (i, line) in split_bytes(data, b'\n').enumerate() {
+        parse_line(&parser, &mut graph, line).map_err(|e| format!("Error on GFA line {}: {}", i + 1, e))?;
+    }
+    graph.finalize()?;
+    Ok(graph)
+}
+
+//-----------------------------------------------------------------------------
+
+fn new_parser() -> GFAParser<Vec<u8>, ()> {
+    let mut builder = GFAParserBuilder::none();
+    builder.segments(true).links(true);
+    builder.build_bstr_id()
+}
+
+fn parse_line<G: Graph>(parser: &GFAParser<Vec<u8>, ()>, graph: &mut G, line: &[u8]) -> Result<(), String> {
+    if line.is_empty() {
+        return Ok(());
+    }
+    match parser.parse_gfa_line_filtered(line).map_err(|e| e.to_string())? {
+        Some(Line::Segment(segment)) => graph.add_node(&segment.name, &segment.sequence),
+        Some(Line::Link(link)) => graph.add_edge(
+            &link.from_segment, orientation(link.from_orient),
+            &link.to_segment, orientation(link.to_orient)
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn orientation(o: GfaOrientation) -> Orientation {
+    match o {
+        GfaOrientation::Forward => Orientation::Forward,
+        GfaOrientation::Backward => Orientation::Reverse,
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,49 @@
+use super::*;
+
+use crate::algorithms;
+use crate::graph::{GraphInt, GraphStr};
+
+use gbz::support;
+
+use std::fs::File;
+use std::io::BufReader;
+
+//-----------------------------------------------------------------------------
+
+fn check_same_name<G: Graph>(filename: &'static str) {
+    let path = support::get_test_data(filename);
+    let native: G = algorithms::parse_gfa(algorithms::open_gfa(&path).unwrap()).unwrap();
+    let file = File::open(&path).unwrap();
+    let graph: G = parse_gfa(BufReader::new(file)).unwrap();
+    assert_eq!(crate::stable_name(&graph), crate::stable_name(&native), "Different names for {}", filename);
+
+    let data = std::fs::read(&path).unwrap();
+    let graph: G = parse_gfa_bytes(&data).unwrap();
+    assert_eq!(crate::stable_name(&graph), crate::stable_name(&native), "Different names for {} from bytes", filename);
+}
+
+#[test]
+fn same_names() {
+    check_same_name::<GraphInt>("example.gfa");
+    check_same_name::<GraphStr>("example.gfa");
+    check_same_name::<GraphStr>("translation.gfa");
+}
+
+#[test]
+fn invalid_input() {
+    assert!(parse_gfa_bytes::<GraphInt>(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\n").is_err(), "Accepted a link without an overlap");
+    assert!(parse_gfa_bytes::<GraphInt>(b"S\t1\tACGT\nL\t1\t+\t2\t+\t0M\n").is_err(), "Accepted a missing node");
+    assert!(parse_gfa_bytes::<GraphInt>(b"S\tx\tACGT\n").is_err(), "Accepted a string identifier with GraphInt");
+}
+
+#[test]
+fn truncated_sequence() {
+    let gfa = b"S\t1\tAC-GT\n";
+    let graph: GraphInt = parse_gfa_bytes(gfa).unwrap();
+    let native: GraphInt = algorithms::parse_gfa_bytes(gfa).unwrap();
+    let truncated: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tAC\n").unwrap();
+    assert_eq!(crate::stable_name(&graph), crate::stable_name(&truncated), "The sequence was not truncated");
+    assert_ne!(crate::stable_name(&graph), crate::stable_name(&native), "The backends agree on an invalid sequence");
+}
+
+//-----------------------------------------------------------------------------
@@ -13,22 +13,287 @@
 //!
 //! The name of a graph is the SHA-256 of its canonical GFA representation.
 //! The nodes are ordered by their names.
+//! Integer identifiers are ordered numerically, and string identifiers are compared as byte strings (see [`GraphStr::ID_ORDER`]).
 //! Each node is followed by its canonical edges in sorted order.
 //! Edge lines do not include the overlap field, as pangenome graphs do not use it.
 //! Header, path, and walk lines are not included in the hash, and neither are optional fields.
 
-use gbz::{GBZ, Orientation};
-use gbz::support;
-
-use std::collections::BTreeMap;
+use crate::algorithms;
+use crate::packed::PackedSequence;
+use crate::translation::{self, SequenceGraph, TranslationInference};
+
+#[cfg(feature = "gbz")]
+use gbz::{ENDMARKER, GBZ, support};
+#[cfg(feature = "gbz")]
+use gbz::bwt::BWT;
+#[cfg(feature = "gbz")]
+use gbz::headers::{GBWTPayload, GBZPayload, Header};
+#[cfg(feature = "gbz")]
+use gbz::sequences::Sequences;
+#[cfg(feature = "gbz")]
+use gbz::support::Tags;
+
+#[cfg(feature = "gbz")]
+use simple_sds::serialize::{self, Serialize};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use smallvec::SmallVec;
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::hash::Hash;
+use std::io::Write;
+use std::sync::{Arc, OnceLock};
+#[cfg(feature = "gbz")]
+use std::io::{self, Error, ErrorKind, Read};
+#[cfg(feature = "gbz")]
+use std::path::Path;
 
 #[cfg(test)]
 mod tests;
 
 //-----------------------------------------------------------------------------
 
+#[cfg(feature = "gbz")]
+pub use gbz::Orientation;
+
+/// Orientation of a node or a path in a bidirected sequence graph.
+///
+/// This is the same type as `gbz::Orientation` when the `gbz` feature is enabled.
+/// It is also re-exported from the crate root, so that code building graphs does not need to depend on `gbz`.
+/// Use [`crate::algorithms::parse_orientation`] and [`crate::algorithms::orientation_symbol`] for converting between orientations and GFA symbols.
+#[cfg(not(feature = "gbz"))]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Orientation {
+    /// Forward orientation.
+    Forward = 0,
+    /// Reverse or reverse complement orientation.
+    Reverse = 1,
+}
+
+#[cfg(not(feature = "gbz"))]
+impl Orientation {
+    /// Returns the other orientation.
+    #[inline]
+    pub fn flip(&self) -> Orientation {
+        match *self {
+            Self::Forward => Self::Reverse,
+            Self::Reverse => Self::Forward,
+        }
+    }
+}
+
+// Same as the implementation for `gbz::Orientation`, so that the output does not depend on the features.
+#[cfg(not(feature = "gbz"))]
+impl std::fmt::Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Orientation::Forward => write!(f, "forward"),
+            Orientation::Reverse => write!(f, "reverse"),
+        }
+    }
+}
+
+#[cfg(feature = "gbz")]
+pub(crate) use gbz::support::reverse_complement;
+
+// Returns the reverse complement of the sequence, normalized to upper case.
+// Invalid characters are mapped to `N`, as in `gbz::support::reverse_complement`.
+#[cfg(not(feature = "gbz"))]
+pub(crate) fn reverse_complement(sequence: &[u8]) -> Vec<u8> {
+    sequence.iter().rev().map(|&c| match c {
+        b'A' | b'a' => b'T',
+        b'C' | b'c' => b'G',
+        b'G' | b'g' => b'C',
+        b'T' | b't' => b'A',
+        _ => b'N',
+    }).collect()
+}
+
+// Returns the sequence in the given orientation, borrowing it in forward orientation.
+fn oriented_sequence(sequence: &[u8], orientation: Orientation) -> Cow<'_, [u8]> {
+    match orientation {
+        Orientation::Forward => Cow::Borrowed(sequence),
+        Orientation::Reverse => Cow::Owned(reverse_complement(sequence)),
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// Number of edges stored inline in a node before the edge list moves to the heap.
+///
+/// Most nodes in pangenome graphs have at most four canonical edges.
+pub const INLINE_EDGES: usize = 4;
+
+/// A list of canonical edges of a node.
+///
+/// Up to [`INLINE_EDGES`] edges are stored inline, avoiding a separate heap allocation per node.
+pub type EdgeList<T> = SmallVec<[T; INLINE_EDGES]>;
+
+//-----------------------------------------------------------------------------
+
+/// Statistics for a graph: the number of nodes, the number of canonical edges, and total sequence length.
+///
+/// The counts are 64-bit integers on all targets, so the statistics of a large graph do not overflow on 32-bit platforms.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::{Graph, algorithms};
+/// use pggname::graph::{GraphInt, GraphStatistics};
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let stats = graph.statistics();
+/// assert_eq!(stats, GraphStatistics { nodes: 2, edges: 1, sequence_len: 6 });
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GraphStatistics {
+    /// Number of nodes.
+    pub nodes: u64,
+    /// Number of canonical edges.
+    pub edges: u64,
+    /// Total length of node sequences.
+    pub sequence_len: u64,
+}
+
+impl GraphStatistics {
+    /// Adds a node with the given number of canonical edges and sequence length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a count overflows.
+    pub fn add_node(&mut self, edges: usize, sequence_len: usize) {
+        self.nodes = Self::checked_add(self.nodes, 1, "nodes");
+        self.edges = Self::checked_add(self.edges, edges, "edges");
+        self.sequence_len = Self::checked_add(self.sequence_len, sequence_len, "sequence length");
+    }
+
+    fn checked_add(total: u64, value: usize, what: &str) -> u64 {
+        u64::try_from(value).ok()
+            .and_then(|value| total.checked_add(value))
+            .unwrap_or_else(|| panic!("GraphStatistics: overflow in {}", what))
+    }
+}
+
+/// Orientation statistics for the canonical edges of a graph.
+///
+/// Each canonical edge is counted once by the orientations of its source and destination, and self-loops are also counted separately.
+/// Pangenome graphs with topologically sorted node identifiers consist mostly of forward-forward edges.
+/// An unusual distribution, such as all edges being reversed, usually indicates a bug in the tool that wrote the graph.
+/// Such a graph gets a different name than the graph the user expects.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::{Graph, algorithms};
+/// use pggname::graph::{EdgeStatistics, GraphInt};
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tA\nS\t2\tC\nL\t1\t+\t2\t+\t*\nL\t2\t-\t1\t+\t*\nL\t2\t+\t2\t-\t*\n").unwrap();
+/// let stats = graph.edge_statistics();
+/// assert_eq!(stats, EdgeStatistics { forward_forward: 1, forward_reverse: 1, reverse_forward: 1, reverse_reverse: 0, self_loops: 1 });
+/// assert!(stats.warnings().is_empty());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EdgeStatistics {
+    /// Number of edges from a forward node to a forward node.
+    pub forward_forward: u64,
+    /// Number of edges from a forward node to a reverse node.
+    pub forward_reverse: u64,
+    /// Number of edges from a reverse node to a forward node.
+    pub reverse_forward: u64,
+    /// Number of edges from a reverse node to a reverse node.
+    pub reverse_reverse: u64,
+    /// Number of edges from a node to itself.
+    pub self_loops: u64,
+}
+
+impl EdgeStatistics {
+    /// Minimum number of edges for reporting an unusual distribution in [`EdgeStatistics::warnings`].
+    pub const MIN_EDGES: u64 = 10;
+
+    /// Adds a canonical edge with the given orientations.
+    pub fn add_edge(&mut self, source_o: Orientation, dest_o: Orientation, self_loop: bool) {
+        match (source_o, dest_o) {
+            (Orientation::Forward, Orientation::Forward) => self.forward_forward += 1,
+            (Orientation::Forward, Orientation::Reverse) => self.forward_reverse += 1,
+            (Orientation::Reverse, Orientation::Forward) => self.reverse_forward += 1,
+            (Orientation::Reverse, Orientation::Reverse) => self.reverse_reverse += 1,
+        }
+        if self_loop {
+            self.self_loops += 1;
+        }
+    }
+
+    /// Returns the total number of edges.
+    pub fn edges(&self) -> u64 {
+        self.forward_forward + self.forward_reverse + self.reverse_forward + self.reverse_reverse
+    }
+
+    /// Returns descriptions of unusual edge distributions, or an empty vector if the distribution looks normal.
+    ///
+    /// Graphs with fewer than [`EdgeStatistics::MIN_EDGES`] edges are never reported.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut result = Vec::new();
+        let edges = self.edges();
+        if edges < Self::MIN_EDGES {
+            return result;
+        }
+        if self.reverse_reverse == edges {
+            result.push(format!("All {} edges are reverse-reverse; the links may have been written in the wrong orientation", edges));
+        } else if self.forward_forward == 0 {
+            result.push(format!("None of the {} edges are forward-forward; the links may have been written in the wrong orientation", edges));
+        } else if self.reverse_reverse > self.forward_forward {
+            result.push(format!(
+                "More reverse-reverse edges ({}) than forward-forward edges ({}); the node identifiers may be in reverse order",
+                self.reverse_reverse, self.forward_forward
+            ));
+        }
+        if 2 * self.self_loops > edges {
+            result.push(format!("{} of the {} edges are self-loops", self.self_loops, edges));
+        }
+        result
+    }
+}
+
+// Returns the name of the node with the given integer identifier.
+fn id_to_name(id: usize) -> Vec<u8> {
+    id.to_string().into_bytes()
+}
+
+// Returns an error if the sequence of the node contains a tab or a newline.
+fn check_sequence(name: &[u8], sequence: &[u8]) -> Result<(), String> {
+    match algorithms::find_delimiter(sequence) {
+        Some(i) => Err(format!("Sequence of node {} contains a delimiter at position {}", String::from_utf8_lossy(name).escape_debug(), i)),
+        None => Ok(()),
+    }
+}
+
+// Returns `true` if the lexicographic order of the decimal representations of the identifiers is the same as their numerical order.
+// The identifiers must be in increasing order.
+fn decimal_order_matches<I: Iterator<Item = usize>>(ids: I) -> bool {
+    let mut prev = String::new();
+    for id in ids {
+        let curr = id.to_string();
+        if !prev.is_empty() && curr <= prev {
+            return false;
+        }
+        prev = curr;
+    }
+    true
+}
+
+//-----------------------------------------------------------------------------
+
 /// A bidirected sequence graph.
 pub trait Graph {
+    /// Interpretation of node identifiers in the canonical representation of the graph.
+    ///
+    /// The default is [`algorithms::NodeIds::Auto`], which means that the interpretation is not known.
+    const NODE_IDS: algorithms::NodeIds = algorithms::NodeIds::Auto;
+
     /// Creates a new empty graph.
     fn new() -> Self;
 
@@ -36,6 +301,7 @@ pub trait Graph {
     ///
     /// Returns an error if the node already exists with a different sequence.
     /// Returns an error if the name of the node is not valid.
+    /// Returns an error if the name or the sequence contains a tab or a newline; see [`algorithms::find_delimiter`].
     fn add_node(&mut self, name: &[u8], sequence: &[u8]) -> Result<(), String>;
 
     /// Adds an edge to the graph.
@@ -49,11 +315,202 @@ pub trait Graph {
     /// Returns an error if some nodes required by the edges are missing.
     fn finalize(&mut self) -> Result<(), String>;
 
-    /// Returns the number of nodes, the number of edges, and total sequence length in the graph.
-    fn statistics(&self) -> (usize, usize, usize);
+    /// Stores node sequences in packed form, including sequences already in the graph.
+    ///
+    /// Packing reduces memory usage for sequences consisting mostly of upper case `ACGT`.
+    /// It does not change the canonical representation.
+    /// See [`crate::packed`] for details.
+    /// The default implementation does nothing.
+    fn pack_sequences(&mut self) {}
+
+    /// Returns the number of nodes, the number of canonical edges, and total sequence length in the graph.
+    fn statistics(&self) -> GraphStatistics;
+
+    /// Returns orientation statistics for the canonical edges in the graph.
+    ///
+    /// The default implementation uses [`Graph::node_names`] and [`Graph::edges_of`].
+    fn edge_statistics(&self) -> EdgeStatistics {
+        let mut result = EdgeStatistics::default();
+        for name in self.node_names() {
+            for (source_o, dest, dest_o) in self.edges_of(&name).unwrap_or_default() {
+                result.add_edge(source_o, dest_o, dest == name);
+            }
+        }
+        result
+    }
+
+    /// Returns the number of duplicate edges removed by [`Graph::finalize`].
+    ///
+    /// An edge is a duplicate if the same edge, possibly in the other orientation, was already in the graph.
+    /// The default implementation returns `0`.
+    fn duplicate_edges(&self) -> usize {
+        0
+    }
+
+    /// Returns `true` if all node names are integers, but the name of the graph depends on whether they are interpreted as integers or strings.
+    ///
+    /// The names differ if the numerical order of the identifiers is not the same as their lexicographic order, such as with `2` and `10`.
+    /// They also differ if some names in a GFA file were not in canonical decimal form, such as `007` or `+7`, as integer identifiers are written without them.
+    /// This is a common reason why two tools report different names for the same graph.
+    /// The default implementation returns `false`.
+    fn name_depends_on_node_ids(&self) -> bool {
+        false
+    }
+
+    /// Returns groups of string node identifiers that differ only by case or by leading zeros in numbers, such as `Node7`, `node7`, and `node007`.
+    ///
+    /// String identifiers are sorted in [`GraphStr::ID_ORDER`], so such identifiers are not adjacent in the way "natural" ordering would suggest.
+    /// Users often expect them to refer to the same node or to sort together, which is a common source of confusion when names differ.
+    /// Each group is in canonical order, and the groups are ordered by their first identifiers.
+    /// The default implementation returns no groups, as integer identifiers cannot be confused this way.
+    fn confusable_ids(&self) -> Vec<Vec<Vec<u8>>> {
+        Vec::new()
+    }
+
+    /// Returns the canonical edges stored with the node, or [`None`] if there is no such node.
+    ///
+    /// Each edge is `(source orientation, destination name, destination orientation)` with the node as the source.
+    /// These are the edges listed after the node in the canonical GFA representation, in the same order.
+    /// An edge that starts from another node in its canonical orientation is stored with that node.
+    /// Use [`Graph::neighbors`] for all edges adjacent to the node.
+    /// The graph must be finalized.
+    ///
+    /// The default implementation searches the serialized nodes, which takes linear time.
+    fn edges_of(&self, name: &[u8]) -> Option<Vec<(Orientation, Vec<u8>, Orientation)>> {
+        let result = self.try_for_each_node(|bytes| match SerializedNode::parse(bytes) {
+            Some(node) if node.name == name => {
+                Err(node.edges.into_iter().map(|(source_o, dest, dest_o)| (source_o, dest.to_vec(), dest_o)).collect())
+            }
+            _ => Ok(()),
+        });
+        result.err()
+    }
+
+    /// Returns the successors of the node in the given orientation, or [`None`] if there is no such node.
+    ///
+    /// Each successor is `(name, orientation)`, and the successors are in canonical node order without duplicates.
+    /// The predecessors of the node are the successors in the other orientation, with the orientations flipped.
+    /// The graph must be finalized.
+    ///
+    /// The default implementation scans all serialized nodes, which takes linear time.
+    /// The graphs in this crate override it with a lookup proportional to the degree of the node.
+    fn neighbors(&self, name: &[u8], orientation: Orientation) -> Option<Vec<(Vec<u8>, Orientation)>> {
+        let mut found = false;
+        let mut result: Vec<(Vec<u8>, Orientation)> = Vec::new();
+        self.for_each_node(|bytes| {
+            let Some(node) = SerializedNode::parse(bytes) else {
+                return;
+            };
+            found |= node.name == name;
+            for (source_o, dest, dest_o) in node.edges {
+                if node.name == name && source_o == orientation {
+                    result.push((dest.to_vec(), dest_o));
+                }
+                if dest == name && dest_o.flip() == orientation {
+                    result.push((node.name.to_vec(), source_o.flip()));
+                }
+            }
+        });
+        if !found {
+            return None;
+        }
+        // Serialized nodes are in canonical order, but the names may be integers, which sort differently as bytes.
+        let mut order: HashMap<Vec<u8>, usize> = HashMap::new();
+        if result.len() > 1 {
+            let mut rank = 0;
+            self.for_each_node(|bytes| {
+                if let Some(node) = SerializedNode::parse(bytes) {
+                    if result.iter().any(|(neighbor, _)| neighbor == node.name) {
+                        order.insert(node.name.to_vec(), rank);
+                    }
+                    rank += 1;
+                }
+            });
+        }
+        result.sort_by_key(|(name, o)| (order.get(name).copied(), *o));
+        result.dedup();
+        Some(result)
+    }
+
+    /// Returns the sequence of the node in the given orientation, or [`None`] if there is no such node.
+    ///
+    /// In reverse orientation, the sequence is the reverse complement, normalized to upper case with invalid characters mapped to `N`.
+    /// Forward sequences are borrowed from the graph when they are stored as plain bytes.
+    /// The graph must be finalized.
+    ///
+    /// The default implementation searches the serialized nodes, which takes linear time.
+    fn sequence(&self, name: &[u8], orientation: Orientation) -> Option<Cow<'_, [u8]>> {
+        let result = self.try_for_each_node(|bytes| match SerializedNode::parse(bytes) {
+            Some(node) if node.name == name => Err(oriented_sequence(node.sequence, orientation).into_owned()),
+            _ => Ok(()),
+        });
+        result.err().map(Cow::Owned)
+    }
+
+    /// Returns an iterator over node names in canonical order.
+    ///
+    /// The default implementation extracts the names from the serialized nodes.
+    fn node_names(&self) -> impl Iterator<Item=Vec<u8>> {
+        self.node_iter().filter_map(|bytes| SerializedNode::parse(&bytes).map(|node| node.name.to_vec()))
+    }
 
     /// Returns an iterator over serialized nodes in sorted order.
     fn node_iter(&self) -> impl Iterator<Item=Vec<u8>>;
+
+    /// Calls the function with each serialized node in sorted order, stopping at the first error.
+    ///
+    /// Unlike [`Graph::node_iter`], implementations may serialize the nodes into a reused buffer.
+    /// This avoids a heap allocation per node when the serialized nodes are not stored.
+    fn try_for_each_node<E, F: FnMut(&[u8]) -> Result<(), E>>(&self, mut f: F) -> Result<(), E> {
+        for bytes in self.node_iter() {
+            f(&bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Calls the function with each serialized node in sorted order.
+    ///
+    /// See [`Graph::try_for_each_node`] for details.
+    fn for_each_node<F: FnMut(&[u8])>(&self, mut f: F) {
+        let result: Result<(), Infallible> = self.try_for_each_node(|bytes| {
+            f(bytes);
+            Ok(())
+        });
+        let Ok(()) = result;
+    }
+}
+
+// A node parsed from its canonical GFA serialization.
+struct SerializedNode<'a> {
+    name: &'a [u8],
+    sequence: &'a [u8],
+    // Canonical edges as (source orientation, destination name, destination orientation).
+    edges: Vec<(Orientation, &'a [u8], Orientation)>,
+}
+
+impl<'a> SerializedNode<'a> {
+    // Parses the segment line and the link lines starting from the segment.
+    // Returns `None` if the first line is not a segment line.
+    // Other lines are ignored.
+    fn parse(bytes: &'a [u8]) -> Option<Self> {
+        let mut lines = algorithms::split_bytes(bytes, b'\n').filter(|line| !line.is_empty());
+        let mut fields = algorithms::split_bytes(lines.next()?, b'\t');
+        if fields.next() != Some(b"S") {
+            return None;
+        }
+        let (name, sequence) = (fields.next()?, fields.next()?);
+        let mut edges = Vec::new();
+        for line in lines {
+            let fields: Vec<&[u8]> = algorithms::split_bytes(line, b'\t').collect();
+            if fields.len() < 5 || fields[0] != b"L" || fields[1] != name {
+                continue;
+            }
+            if let (Ok(source_o), Ok(dest_o)) = (algorithms::parse_orientation(fields[2]), algorithms::parse_orientation(fields[4])) {
+                edges.push((source_o, fields[3], dest_o));
+            }
+        }
+        Some(SerializedNode { name, sequence, edges })
+    }
 }
 
 //-----------------------------------------------------------------------------
@@ -63,6 +520,18 @@ pub trait Graph {
 pub struct GraphInt {
     /// Nodes in the graph.
     pub nodes: BTreeMap<usize, NodeInt>,
+    /// Are the node sequences stored in packed form?
+    pub packed: bool,
+    /// Number of duplicate edges removed from each node by [`Graph::finalize`].
+    ///
+    /// Only nodes with duplicate edges are included.
+    pub duplicates: BTreeMap<usize, usize>,
+    /// Were some node names not in canonical decimal form, such as `007` or `+7`?
+    ///
+    /// See [`Graph::name_depends_on_node_ids`].
+    pub noncanonical_ids: bool,
+    // Edges stored with other nodes, for `Graph::neighbors`.
+    incoming: IncomingEdges<usize>,
 }
 
 impl GraphInt {
@@ -88,12 +557,26 @@ impl GraphInt {
         }
         Ok(id)
     }
+
+    /// Returns `true` if the node name is a positive integer in canonical decimal form.
+    ///
+    /// A canonical name consists of ASCII digits without leading zeros.
+    /// The value may still be too large for an integer identifier.
+    pub fn is_canonical_id(name: &[u8]) -> bool {
+        name.first().is_some_and(|&c| c != b'0') && name.iter().all(u8::is_ascii_digit)
+    }
 }
 
 impl Graph for GraphInt {
+    const NODE_IDS: algorithms::NodeIds = algorithms::NodeIds::Integer;
+
     fn new() -> Self {
         GraphInt {
             nodes: BTreeMap::new(),
+            packed: false,
+            duplicates: BTreeMap::new(),
+            noncanonical_ids: false,
+            incoming: IncomingEdges::default(),
         }
     }
 
@@ -102,18 +585,22 @@ impl Graph for GraphInt {
             .map_err(|e| format!("Error parsing node name {}: {}", String::from_utf8_lossy(name), e))?
             .parse::<usize>()
             .map_err(|e| format!("Error parsing node name {}: {}", String::from_utf8_lossy(name), e))?;
+        check_sequence(name, sequence)?;
+        self.noncanonical_ids |= !Self::is_canonical_id(name);
         if let Some(node) = self.nodes.get_mut(&id) {
-            if node.seen && sequence != node.sequence {
+            if node.seen && !node.sequence.matches(sequence) {
                 let msg = format!("Node {} already exists with a different sequence", String::from_utf8_lossy(name));
                 return Err(msg);
             }
             // If the node already exists, update its sequence.
-            node.sequence = sequence.to_vec();
+            node.sequence = NodeSequence::new(sequence, self.packed);
             node.seen = true;
             Ok(())
         } else {
             // If the node doesn't exist, create a new one.
-            let node = NodeInt::new(Some(sequence.to_vec()));
+            let mut node = NodeInt::new(None);
+            node.sequence = NodeSequence::new(sequence, self.packed);
+            node.seen = true;
             self.nodes.insert(id, node);
             Ok(())
         }
@@ -122,6 +609,7 @@ impl Graph for GraphInt {
     fn add_edge(&mut self, source_name: &[u8], source_o: Orientation, dest_name: &[u8], dest_o: Orientation) -> Result<(), String> {
         let source_id = Self::parse_id(source_name)?;
         let dest_id = Self::parse_id(dest_name)?;
+        self.noncanonical_ids |= !Self::is_canonical_id(source_name) || !Self::is_canonical_id(dest_name);
 
         // Ensure that the nodes exist.
         self.nodes.entry(source_id).or_insert_with(|| NodeInt::new(None));
@@ -139,32 +627,79 @@ impl Graph for GraphInt {
     }
 
     fn finalize(&mut self) -> Result<(), String> {
-        let mut unseen = 0;
-        for node in self.nodes.values_mut() {
-            node.finalize();
-            if !node.seen {
-                unseen += 1;
-            }
-        }
+        self.incoming = IncomingEdges::default();
+        let unseen = finalize_nodes(&mut self.nodes, &mut self.duplicates, |node| {
+            let duplicates = node.finalize();
+            (node.seen, duplicates)
+        });
         if unseen > 0 {
             return Err(format!("{} nodes required by the edges are missing", unseen));
         }
         Ok(())
     }
 
-    fn statistics(&self) -> (usize, usize, usize) {
-        let mut edge_count = 0;
-        let mut seq_len = 0;
+    fn pack_sequences(&mut self) {
+        self.packed = true;
+        for node in self.nodes.values_mut() {
+            node.sequence.pack();
+        }
+    }
+
+    fn statistics(&self) -> GraphStatistics {
+        let mut result = GraphStatistics::default();
         for node in self.nodes.values() {
-            edge_count += node.edges.len();
-            seq_len += node.sequence.len();
+            result.add_node(node.edges.len(), node.sequence.len());
         }
-        (self.nodes.len(), edge_count, seq_len)
+        result
+    }
+
+    fn duplicate_edges(&self) -> usize {
+        self.duplicates.values().sum()
+    }
+
+    fn name_depends_on_node_ids(&self) -> bool {
+        self.noncanonical_ids || !decimal_order_matches(self.nodes.keys().copied())
+    }
+
+    fn edges_of(&self, name: &[u8]) -> Option<Vec<(Orientation, Vec<u8>, Orientation)>> {
+        let node = self.nodes.get(&Self::parse_id(name).ok()?)?;
+        Some(node.edges.iter().map(|&(source_o, dest_id, dest_o)| (source_o, id_to_name(dest_id), dest_o)).collect())
+    }
+
+    fn neighbors(&self, name: &[u8], orientation: Orientation) -> Option<Vec<(Vec<u8>, Orientation)>> {
+        let id = Self::parse_id(name).ok()?;
+        let node = self.nodes.get(&id)?;
+        let incoming = self.incoming.get(&self.nodes, |node| &node.edges);
+        let mut result: Vec<(usize, Orientation)> = node.edges.iter().chain(incoming.get(&id).into_iter().flatten())
+            .filter(|(source_o, _, _)| *source_o == orientation)
+            .map(|&(_, dest_id, dest_o)| (dest_id, dest_o))
+            .collect();
+        result.sort();
+        result.dedup();
+        Some(result.into_iter().map(|(id, o)| (id_to_name(id), o)).collect())
+    }
+
+    fn sequence(&self, name: &[u8], orientation: Orientation) -> Option<Cow<'_, [u8]>> {
+        let node = self.nodes.get(&Self::parse_id(name).ok()?)?;
+        Some(node.sequence.oriented(orientation))
+    }
+
+    fn node_names(&self) -> impl Iterator<Item=Vec<u8>> {
+        self.nodes.keys().map(|&id| id_to_name(id))
     }
 
     fn node_iter(&self) -> impl Iterator<Item=Vec<u8>> {
         self.nodes.iter().map(|(id, node)| node.serialize(*id))
     }
+
+    fn try_for_each_node<E, F: FnMut(&[u8]) -> Result<(), E>>(&self, mut f: F) -> Result<(), E> {
+        let mut buffer = Vec::new();
+        for (id, node) in self.nodes.iter() {
+            node.serialize_into(*id, &mut buffer);
+            f(&buffer)?;
+        }
+        Ok(())
+    }
 }
 
 //-----------------------------------------------------------------------------
@@ -174,14 +709,67 @@ impl Graph for GraphInt {
 pub struct GraphStr {
     /// Nodes in the graph.
     pub nodes: BTreeMap<Vec<u8>, NodeStr>,
+    /// Are the node sequences stored in packed form?
+    pub packed: bool,
+    /// Number of duplicate edges removed from each node by [`Graph::finalize`].
+    ///
+    /// Only nodes with duplicate edges are included.
+    pub duplicates: BTreeMap<Vec<u8>, usize>,
+    // Edges stored with other nodes, for `Graph::neighbors`.
+    incoming: IncomingEdges<Vec<u8>>,
 }
 
 impl GraphStr {
+    /// Ordering of string node identifiers in the canonical GFA representation.
+    ///
+    /// Identifiers are compared as byte strings, byte by byte, and a proper prefix comes before the longer identifier.
+    /// There is no case folding, Unicode normalization, or interpretation of numbers.
+    /// For example, `B` < `a`, `10` < `9`, and `007` < `7`.
+    /// [`GraphStr::cmp_ids`] implements the ordering.
+    pub const ID_ORDER: &'static str = "byte-lexicographic";
+
+    /// Compares two string node identifiers in the canonical order described by [`GraphStr::ID_ORDER`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::graph::GraphStr;
+    /// use std::cmp::Ordering;
+    ///
+    /// assert_eq!(GraphStr::cmp_ids(b"B", b"a"), Ordering::Less);
+    /// assert_eq!(GraphStr::cmp_ids(b"10", b"9"), Ordering::Less);
+    /// assert_eq!(GraphStr::cmp_ids(b"node", b"node1"), Ordering::Less);
+    /// ```
+    pub fn cmp_ids(a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+        a.cmp(b)
+    }
+
+    // Returns the identifier with ASCII letters in lower case and leading zeros removed from each run of digits.
+    fn natural_key(name: &[u8]) -> Vec<u8> {
+        let mut result = Vec::with_capacity(name.len());
+        let mut i = 0;
+        while i < name.len() {
+            if name[i].is_ascii_digit() {
+                let start = i;
+                while i < name.len() && name[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let digits = &name[start..i];
+                let first = digits.iter().position(|&c| c != b'0').unwrap_or(digits.len() - 1);
+                result.extend_from_slice(&digits[first..]);
+            } else {
+                result.push(name[i].to_ascii_lowercase());
+                i += 1;
+            }
+        }
+        result
+    }
+
     /// Returns `true` if the edge is in its canonical orientation.
     pub fn edge_is_canonical(
         source_name: &[u8], source_o: Orientation, dest_name: &[u8], dest_o: Orientation
     ) -> bool {
-        match source_name.cmp(dest_name) {
+        match Self::cmp_ids(source_name, dest_name) {
             std::cmp::Ordering::Less => true,
             std::cmp::Ordering::Greater => false,
             std::cmp::Ordering::Equal => source_o == Orientation::Forward || dest_o == Orientation::Forward,
@@ -190,26 +778,37 @@ impl GraphStr {
 }
 
 impl Graph for GraphStr {
+    const NODE_IDS: algorithms::NodeIds = algorithms::NodeIds::String;
+
     fn new() -> Self {
         GraphStr {
             nodes: BTreeMap::new(),
+            packed: false,
+            duplicates: BTreeMap::new(),
+            incoming: IncomingEdges::default(),
         }
     }
 
     fn add_node(&mut self, name: &[u8], sequence: &[u8]) -> Result<(), String> {
+        if let Some(i) = algorithms::find_delimiter(name) {
+            return Err(format!("Node name {} contains a delimiter at position {}", String::from_utf8_lossy(name).escape_debug(), i));
+        }
+        check_sequence(name, sequence)?;
         let name = name.to_vec();
         if let Some(node) = self.nodes.get_mut(&name) {
-            if node.seen && sequence != node.sequence {
+            if node.seen && !node.sequence.matches(sequence) {
                 let msg = format!("Node {} already exists with a different sequence", String::from_utf8_lossy(&name));
                 return Err(msg);
             }
             // If the node already exists, update its sequence.
-            node.sequence = sequence.to_vec();
+            node.sequence = NodeSequence::new(sequence, self.packed);
             node.seen = true;
             Ok(())
         } else {
             // If the node doesn't exist, create a new one.
-            let node = NodeStr::new(Some(sequence.to_vec()));
+            let mut node = NodeStr::new(None);
+            node.sequence = NodeSequence::new(sequence, self.packed);
+            node.seen = true;
             self.nodes.insert(name, node);
             Ok(())
         }
@@ -236,43 +835,114 @@ impl Graph for GraphStr {
     }
 
     fn finalize(&mut self) -> Result<(), String> {
-        let mut unseen = 0;
-        for node in self.nodes.values_mut() {
-            node.finalize();
-            if !node.seen {
-                unseen += 1;
-            }
-        }
+        self.incoming = IncomingEdges::default();
+        let unseen = finalize_nodes(&mut self.nodes, &mut self.duplicates, |node| {
+            let duplicates = node.finalize();
+            (node.seen, duplicates)
+        });
         if unseen > 0 {
             return Err(format!("{} nodes required by the edges are missing", unseen));
         }
         Ok(())
     }
 
-    fn statistics(&self) -> (usize, usize, usize) {
-        let mut edge_count = 0;
-        let mut seq_len = 0;
+    fn pack_sequences(&mut self) {
+        self.packed = true;
+        for node in self.nodes.values_mut() {
+            node.sequence.pack();
+        }
+    }
+
+    fn statistics(&self) -> GraphStatistics {
+        let mut result = GraphStatistics::default();
         for node in self.nodes.values() {
-            edge_count += node.edges.len();
-            seq_len += node.sequence.len();
+            result.add_node(node.edges.len(), node.sequence.len());
+        }
+        result
+    }
+
+    fn duplicate_edges(&self) -> usize {
+        self.duplicates.values().sum()
+    }
+
+    fn name_depends_on_node_ids(&self) -> bool {
+        // All names must be valid integer identifiers.
+        // Then the names differ if some identifiers are not canonical or if the orders differ.
+        let mut prev = 0;
+        let mut differ = false;
+        for name in self.nodes.keys() {
+            let Ok(id) = GraphInt::parse_id(name) else {
+                return false;
+            };
+            differ |= id <= prev || !GraphInt::is_canonical_id(name);
+            prev = id;
         }
-        (self.nodes.len(), edge_count, seq_len)
+        differ
+    }
+
+    fn confusable_ids(&self) -> Vec<Vec<Vec<u8>>> {
+        let mut groups: HashMap<Vec<u8>, Vec<Vec<u8>>> = HashMap::new();
+        for name in self.nodes.keys() {
+            groups.entry(Self::natural_key(name)).or_default().push(name.clone());
+        }
+        let mut result: Vec<Vec<Vec<u8>>> = groups.into_values().filter(|group| group.len() > 1).collect();
+        result.sort_unstable();
+        result
+    }
+
+    fn edges_of(&self, name: &[u8]) -> Option<Vec<(Orientation, Vec<u8>, Orientation)>> {
+        let node = self.nodes.get(name)?;
+        Some(node.edges.to_vec())
+    }
+
+    fn neighbors(&self, name: &[u8], orientation: Orientation) -> Option<Vec<(Vec<u8>, Orientation)>> {
+        let node = self.nodes.get(name)?;
+        let incoming = self.incoming.get(&self.nodes, |node| &node.edges);
+        let mut result: Vec<(&[u8], Orientation)> = node.edges.iter().chain(incoming.get(name).into_iter().flatten())
+            .filter(|(source_o, _, _)| *source_o == orientation)
+            .map(|(_, dest_name, dest_o)| (dest_name.as_slice(), *dest_o))
+            .collect();
+        result.sort();
+        result.dedup();
+        Some(result.into_iter().map(|(name, o)| (name.to_vec(), o)).collect())
+    }
+
+    fn sequence(&self, name: &[u8], orientation: Orientation) -> Option<Cow<'_, [u8]>> {
+        let node = self.nodes.get(name)?;
+        Some(node.sequence.oriented(orientation))
+    }
+
+    fn node_names(&self) -> impl Iterator<Item=Vec<u8>> {
+        self.nodes.keys().cloned()
     }
 
     fn node_iter(&self) -> impl Iterator<Item=Vec<u8>> {
         self.nodes.iter().map(|(name, node)| node.serialize(name))
     }
+
+    fn try_for_each_node<E, F: FnMut(&[u8]) -> Result<(), E>>(&self, mut f: F) -> Result<(), E> {
+        let mut buffer = Vec::new();
+        for (name, node) in self.nodes.iter() {
+            node.serialize_into(name, &mut buffer);
+            f(&buffer)?;
+        }
+        Ok(())
+    }
 }
 
 //-----------------------------------------------------------------------------
 
 /// A GBZ wrapper using integer identifiers for the nodes.
+#[cfg(feature = "gbz")]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GBZInt {
     pub graph: GBZ,
 }
 
+#[cfg(feature = "gbz")]
 impl Graph for GBZInt {
+    const NODE_IDS: algorithms::NodeIds = algorithms::NodeIds::Integer;
+
     fn new() -> Self {
         unimplemented!()
     }
@@ -289,12 +959,14 @@ impl Graph for GBZInt {
         Ok(())
     }
 
-    fn statistics(&self) -> (usize, usize, usize) {
-        let node_count = self.graph.nodes();
+    fn name_depends_on_node_ids(&self) -> bool {
+        !decimal_order_matches(self.graph.node_iter())
+    }
 
-        let mut edge_count = 0;
-        let mut seq_len = 0;
+    fn statistics(&self) -> GraphStatistics {
+        let mut result = GraphStatistics::default();
         for source_id in self.graph.node_iter() {
+            let mut edge_count = 0;
             for source_o in [Orientation::Forward, Orientation::Reverse] {
                 for (dest_id, dest_o) in self.graph.successors(source_id, source_o).unwrap() {
                     if support::edge_is_canonical((source_id, source_o), (dest_id, dest_o)) {
@@ -302,10 +974,41 @@ impl Graph for GBZInt {
                     }
                 }
             }
-            seq_len += self.graph.sequence_len(source_id).unwrap_or(0);
+            result.add_node(edge_count, self.graph.sequence_len(source_id).unwrap_or(0));
         }
+        result
+    }
+
+    fn edges_of(&self, name: &[u8]) -> Option<Vec<(Orientation, Vec<u8>, Orientation)>> {
+        let id = GraphInt::parse_id(name).ok()?;
+        let mut edges: Vec<(Orientation, usize, Orientation)> = Vec::new();
+        for source_o in [Orientation::Forward, Orientation::Reverse] {
+            for (dest_id, dest_o) in self.graph.successors(id, source_o)? {
+                if support::edge_is_canonical((id, source_o), (dest_id, dest_o)) {
+                    edges.push((source_o, dest_id, dest_o));
+                }
+            }
+        }
+        edges.sort();
+        edges.dedup();
+        Some(edges.into_iter().map(|(source_o, dest_id, dest_o)| (source_o, id_to_name(dest_id), dest_o)).collect())
+    }
+
+    fn neighbors(&self, name: &[u8], orientation: Orientation) -> Option<Vec<(Vec<u8>, Orientation)>> {
+        let id = GraphInt::parse_id(name).ok()?;
+        let mut result: Vec<(usize, Orientation)> = self.graph.successors(id, orientation)?.collect();
+        result.sort();
+        result.dedup();
+        Some(result.into_iter().map(|(id, o)| (id_to_name(id), o)).collect())
+    }
+
+    fn sequence(&self, name: &[u8], orientation: Orientation) -> Option<Cow<'_, [u8]>> {
+        let id = GraphInt::parse_id(name).ok()?;
+        Some(oriented_sequence(self.graph.sequence(id)?, orientation))
+    }
 
-        (node_count, edge_count, seq_len)
+    fn node_names(&self) -> impl Iterator<Item=Vec<u8>> {
+        self.graph.node_iter().map(id_to_name)
     }
 
     fn node_iter(&self) -> impl Iterator<Item=Vec<u8>> {
@@ -323,17 +1026,257 @@ impl Graph for GBZInt {
             node.serialize(id)
         })
     }
+
+    fn try_for_each_node<E, F: FnMut(&[u8]) -> Result<(), E>>(&self, mut f: F) -> Result<(), E> {
+        let mut node = NodeInt::new(Some(Vec::new()));
+        let mut buffer = Vec::new();
+        for id in self.graph.node_iter() {
+            node.sequence.set(self.graph.sequence(id).unwrap_or(&[]));
+            node.edges.clear();
+            for source_o in [Orientation::Forward, Orientation::Reverse] {
+                for (dest_id, dest_o) in self.graph.successors(id, source_o).unwrap() {
+                    if support::edge_is_canonical((id, source_o), (dest_id, dest_o)) {
+                        node.edges.push((source_o, dest_id, dest_o));
+                    }
+                }
+            }
+            node.finalize();
+            node.serialize_into(id, &mut buffer);
+            f(&buffer)?;
+        }
+        Ok(())
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// A GBZ graph loaded only for naming, using integer identifiers for the nodes.
+///
+/// Only the parts of a GBZ file needed for the canonical representation are loaded: the GBWT node records and the node sequences.
+/// Document array samples and metadata, including path names, are skipped while reading the file.
+/// Node records stay compressed in memory and are decoded one node at a time when the graph is serialized.
+/// The canonical representation is the same as with [`GBZInt`], but peak memory usage is lower.
+///
+/// The node sequences are stored after the GBWT in the file, so the graph cannot be hashed while reading the file.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::graph::LazyGBZ;
+/// use gbz::support;
+///
+/// let filename = support::get_test_data("example.gbz");
+/// let graph = LazyGBZ::load_from(&filename).unwrap();
+/// assert_eq!(pggname::stable_name(&graph), "81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970");
+/// ```
+#[cfg(feature = "gbz")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LazyGBZ {
+    tags: Tags,
+    alphabet_offset: usize,
+    bwt: BWT,
+    sequences: Sequences,
+}
+
+#[cfg(feature = "gbz")]
+impl LazyGBZ {
+    /// Loads the graph from a reader positioned at the start of a serialized GBZ graph.
+    ///
+    /// Returns an error if reading fails or if the data is not a valid GBZ graph.
+    pub fn load<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let header = Header::<GBZPayload>::load(reader)?;
+        header.validate().map_err(|msg| Error::new(ErrorKind::InvalidData, msg))?;
+        let tags = Tags::load(reader)?;
+
+        let gbwt_header = Header::<GBWTPayload>::load(reader)?;
+        gbwt_header.validate().map_err(|msg| Error::new(ErrorKind::InvalidData, msg))?;
+        if !gbwt_header.is_set(GBWTPayload::FLAG_BIDIRECTIONAL) {
+            return Err(Error::new(ErrorKind::InvalidData, "GBZ: The GBWT index is not bidirectional"));
+        }
+        let _ = Tags::load(reader)?;
+        let bwt = BWT::load(reader)?;
+        // Document array samples.
+        let samples = usize::load(reader)?;
+        let sample_bytes = samples.checked_mul(8).ok_or_else(|| {
+            Error::new(ErrorKind::InvalidData, "GBZ: Document array sample count is too large")
+        })? as u64;
+        if io::copy(&mut reader.by_ref().take(sample_bytes), &mut io::sink())? != sample_bytes {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "GBZ: Truncated document array samples"));
+        }
+        serialize::skip_option(reader)?;
+
+        let sequences = Sequences::load(reader)?;
+        let payload = gbwt_header.payload();
+        let records = payload.alphabet_size.checked_sub(payload.offset).and_then(|n| n.checked_sub(1)).ok_or_else(|| {
+            Error::new(ErrorKind::InvalidData, "GBZ: GBWT alphabet offset is not smaller than alphabet size")
+        })?;
+        if sequences.sequences() != records / 2 {
+            return Err(Error::new(ErrorKind::InvalidData, "GBZ: Mismatch between GBWT alphabet size and Sequences sequence count"));
+        }
+
+        Ok(LazyGBZ { tags, alphabet_offset: payload.offset, bwt, sequences })
+    }
+
+    /// Loads the graph from the given GBZ file.
+    ///
+    /// Returns an error if the file cannot be read or if it is not a valid GBZ file.
+    pub fn load_from<P: AsRef<Path>>(filename: P) -> Result<Self, String> {
+        let filename = filename.as_ref();
+        let file = std::fs::File::open(filename)
+            .map_err(|e| format!("Error opening GBZ file {}: {}", filename.display(), e))?;
+        let mut reader = io::BufReader::new(file);
+        Self::load(&mut reader).map_err(|e| format!("Error loading GBZ file {}: {}", filename.display(), e))
+    }
+
+    /// Returns the tags stored in the GBZ container.
+    pub fn tags(&self) -> &Tags {
+        &self.tags
+    }
+
+    // Returns an iterator over the node identifiers in the original graph.
+    fn node_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bwt.id_iter()
+            .filter(|&record_id| record_id != ENDMARKER)
+            .map(|record_id| record_id + self.alphabet_offset)
+            .filter(|&gbwt_node| support::node_orientation(gbwt_node) == Orientation::Forward)
+            .map(support::node_id)
+    }
+
+    // Returns `true` if the node exists.
+    fn has_node(&self, node_id: usize) -> bool {
+        support::encode_node(node_id, Orientation::Forward).checked_sub(self.alphabet_offset)
+            .is_some_and(|record_id| record_id != ENDMARKER && self.bwt.record(record_id).is_some())
+    }
+
+    // Returns the sequence of an existing node.
+    fn node_sequence(&self, node_id: usize) -> &[u8] {
+        let gbwt_node = support::encode_node(node_id, Orientation::Forward);
+        self.sequences.sequence((gbwt_node - self.alphabet_offset - 1) / 2)
+    }
+
+    // Returns the successors of an existing node in the given orientation.
+    fn successors(&self, node_id: usize, orientation: Orientation) -> impl Iterator<Item = (usize, Orientation)> + '_ {
+        let gbwt_node = support::encode_node(node_id, orientation);
+        let record = self.bwt.record(gbwt_node - self.alphabet_offset);
+        let outdegree = record.as_ref().map_or(0, |record| record.outdegree());
+        (0..outdegree).filter_map(move |i| {
+            let successor = record.as_ref()?.successor(i);
+            (successor != ENDMARKER).then(|| (support::node_id(successor), support::node_orientation(successor)))
+        })
+    }
+
+    // Builds the node with its canonical edges in the given reusable node.
+    fn build_node(&self, id: usize, node: &mut NodeInt) {
+        node.sequence.set(self.node_sequence(id));
+        node.edges.clear();
+        for source_o in [Orientation::Forward, Orientation::Reverse] {
+            for (dest_id, dest_o) in self.successors(id, source_o) {
+                if support::edge_is_canonical((id, source_o), (dest_id, dest_o)) {
+                    node.edges.push((source_o, dest_id, dest_o));
+                }
+            }
+        }
+        node.finalize();
+    }
+}
+
+#[cfg(feature = "gbz")]
+impl Graph for LazyGBZ {
+    const NODE_IDS: algorithms::NodeIds = algorithms::NodeIds::Integer;
+
+    fn new() -> Self {
+        unimplemented!()
+    }
+
+    fn add_node(&mut self, _: &[u8], _sequence: &[u8]) -> Result<(), String> {
+        unimplemented!()
+    }
+
+    fn add_edge(&mut self, _: &[u8], _: Orientation, _: &[u8], _: Orientation) -> Result<(), String> {
+        unimplemented!()
+    }
+
+    fn finalize(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn name_depends_on_node_ids(&self) -> bool {
+        !decimal_order_matches(self.node_ids())
+    }
+
+    fn edges_of(&self, name: &[u8]) -> Option<Vec<(Orientation, Vec<u8>, Orientation)>> {
+        let id = GraphInt::parse_id(name).ok().filter(|&id| self.has_node(id))?;
+        let mut node = NodeInt::new(Some(Vec::new()));
+        self.build_node(id, &mut node);
+        Some(node.edges.iter().map(|&(source_o, dest_id, dest_o)| (source_o, id_to_name(dest_id), dest_o)).collect())
+    }
+
+    fn neighbors(&self, name: &[u8], orientation: Orientation) -> Option<Vec<(Vec<u8>, Orientation)>> {
+        let id = GraphInt::parse_id(name).ok().filter(|&id| self.has_node(id))?;
+        let mut result: Vec<(usize, Orientation)> = self.successors(id, orientation).collect();
+        result.sort();
+        result.dedup();
+        Some(result.into_iter().map(|(id, o)| (id_to_name(id), o)).collect())
+    }
+
+    fn sequence(&self, name: &[u8], orientation: Orientation) -> Option<Cow<'_, [u8]>> {
+        let id = GraphInt::parse_id(name).ok().filter(|&id| self.has_node(id))?;
+        Some(oriented_sequence(self.node_sequence(id), orientation))
+    }
+
+    fn statistics(&self) -> GraphStatistics {
+        let mut result = GraphStatistics::default();
+        for source_id in self.node_ids() {
+            let mut edge_count = 0;
+            for source_o in [Orientation::Forward, Orientation::Reverse] {
+                for (dest_id, dest_o) in self.successors(source_id, source_o) {
+                    if support::edge_is_canonical((source_id, source_o), (dest_id, dest_o)) {
+                        edge_count += 1;
+                    }
+                }
+            }
+            result.add_node(edge_count, self.node_sequence(source_id).len());
+        }
+        result
+    }
+
+    fn node_names(&self) -> impl Iterator<Item=Vec<u8>> {
+        self.node_ids().map(id_to_name)
+    }
+
+    fn node_iter(&self) -> impl Iterator<Item=Vec<u8>> {
+        self.node_ids().map(|id| {
+            let mut node = NodeInt::new(Some(Vec::new()));
+            self.build_node(id, &mut node);
+            node.serialize(id)
+        })
+    }
+
+    fn try_for_each_node<E, F: FnMut(&[u8]) -> Result<(), E>>(&self, mut f: F) -> Result<(), E> {
+        let mut node = NodeInt::new(Some(Vec::new()));
+        let mut buffer = Vec::new();
+        for id in self.node_ids() {
+            self.build_node(id, &mut node);
+            node.serialize_into(id, &mut buffer);
+            f(&buffer)?;
+        }
+        Ok(())
+    }
 }
 
 //-----------------------------------------------------------------------------
 
 /// A GBZ wrapper using string names for the nodes.
+#[cfg(feature = "gbz")]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GBZStr {
     pub graph: GBZ,
 }
 
+#[cfg(feature = "gbz")]
 impl Graph for GBZStr {
+    const NODE_IDS: algorithms::NodeIds = algorithms::NodeIds::String;
+
     fn new() -> Self {
         unimplemented!()
     }
@@ -350,12 +1293,14 @@ impl Graph for GBZStr {
         Ok(())
     }
 
-    fn statistics(&self) -> (usize, usize, usize) {
-        let node_count = self.graph.nodes();
+    fn name_depends_on_node_ids(&self) -> bool {
+        !decimal_order_matches(self.graph.node_iter())
+    }
 
-        let mut edge_count = 0;
-        let mut seq_len = 0;
+    fn statistics(&self) -> GraphStatistics {
+        let mut result = GraphStatistics::default();
         for source_id in self.graph.node_iter() {
+            let mut edge_count = 0;
             for source_o in [Orientation::Forward, Orientation::Reverse] {
                 for (dest_id, dest_o) in self.graph.successors(source_id, source_o).unwrap() {
                     if support::edge_is_canonical((source_id, source_o), (dest_id, dest_o)) {
@@ -363,10 +1308,46 @@ impl Graph for GBZStr {
                     }
                 }
             }
-            seq_len += self.graph.sequence_len(source_id).unwrap_or(0);
+            result.add_node(edge_count, self.graph.sequence_len(source_id).unwrap_or(0));
         }
+        result
+    }
+
+    fn edges_of(&self, name: &[u8]) -> Option<Vec<(Orientation, Vec<u8>, Orientation)>> {
+        let id = GraphInt::parse_id(name).ok().filter(|id| id_to_name(*id) == name)?;
+        let mut edges: Vec<(Orientation, Vec<u8>, Orientation)> = Vec::new();
+        for source_o in [Orientation::Forward, Orientation::Reverse] {
+            for (dest_id, dest_o) in self.graph.successors(id, source_o)? {
+                let dest_name = id_to_name(dest_id);
+                if GraphStr::edge_is_canonical(name, source_o, &dest_name, dest_o) {
+                    edges.push((source_o, dest_name, dest_o));
+                }
+            }
+        }
+        edges.sort();
+        edges.dedup();
+        Some(edges)
+    }
 
-        (node_count, edge_count, seq_len)
+    fn neighbors(&self, name: &[u8], orientation: Orientation) -> Option<Vec<(Vec<u8>, Orientation)>> {
+        let id = GraphInt::parse_id(name).ok().filter(|id| id_to_name(*id) == name)?;
+        let mut result: Vec<(Vec<u8>, Orientation)> = self.graph.successors(id, orientation)?
+            .map(|(id, o)| (id_to_name(id), o))
+            .collect();
+        result.sort();
+        result.dedup();
+        Some(result)
+    }
+
+    fn sequence(&self, name: &[u8], orientation: Orientation) -> Option<Cow<'_, [u8]>> {
+        let id = GraphInt::parse_id(name).ok().filter(|id| id_to_name(*id) == name)?;
+        Some(oriented_sequence(self.graph.sequence(id)?, orientation))
+    }
+
+    fn node_names(&self) -> impl Iterator<Item=Vec<u8>> {
+        let mut names: Vec<Vec<u8>> = self.graph.node_iter().map(id_to_name).collect();
+        names.sort();
+        names.into_iter()
     }
 
     fn node_iter(&self) -> impl Iterator<Item=Vec<u8>> {
@@ -392,15 +1373,149 @@ impl Graph for GBZStr {
 
 //-----------------------------------------------------------------------------
 
+/// Sequence associated with a node, either as plain bytes or in packed form.
+///
+/// Equality compares the sequences, regardless of the representation.
+#[derive(Clone, Debug)]
+pub enum NodeSequence {
+    /// The sequence as plain bytes.
+    Plain(Vec<u8>),
+    /// The sequence packed using 2 bits per base.
+    Packed(PackedSequence),
+}
+
+impl NodeSequence {
+    /// Creates a new node sequence, in packed form if requested.
+    ///
+    /// The sequence is stored as plain bytes if packing would not save space (see [`PackedSequence::saves_space`]).
+    pub fn new(sequence: &[u8], packed: bool) -> Self {
+        if packed {
+            Self::packed_or_plain(sequence)
+        } else {
+            NodeSequence::Plain(sequence.to_vec())
+        }
+    }
+
+    // Packs the sequence if that saves space.
+    fn packed_or_plain(sequence: &[u8]) -> Self {
+        let packed = PackedSequence::pack(sequence);
+        if packed.saves_space() {
+            NodeSequence::Packed(packed)
+        } else {
+            NodeSequence::Plain(sequence.to_vec())
+        }
+    }
+
+    /// Returns the length of the sequence.
+    pub fn len(&self) -> usize {
+        match self {
+            NodeSequence::Plain(sequence) => sequence.len(),
+            NodeSequence::Packed(packed) => packed.len(),
+        }
+    }
+
+    /// Returns `true` if the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the sequence is in packed form.
+    pub fn is_packed(&self) -> bool {
+        matches!(self, NodeSequence::Packed(_))
+    }
+
+    /// Replaces the sequence, keeping the current representation.
+    ///
+    /// The existing allocation is reused for plain sequences.
+    /// A packed sequence becomes plain if packing the new sequence would not save space.
+    pub fn set(&mut self, sequence: &[u8]) {
+        match self {
+            NodeSequence::Plain(plain) => {
+                plain.clear();
+                plain.extend_from_slice(sequence);
+            }
+            NodeSequence::Packed(_) => *self = Self::packed_or_plain(sequence),
+        }
+    }
+
+    /// Converts the sequence to packed form, if that saves space.
+    pub fn pack(&mut self) {
+        if let NodeSequence::Plain(sequence) = self {
+            let packed = PackedSequence::pack(sequence);
+            if packed.saves_space() {
+                *self = NodeSequence::Packed(packed);
+            }
+        }
+    }
+
+    /// Returns `true` if the sequence is equal to the given sequence.
+    pub fn matches(&self, sequence: &[u8]) -> bool {
+        match self {
+            NodeSequence::Plain(plain) => plain == sequence,
+            NodeSequence::Packed(packed) => packed.len() == sequence.len() && packed.unpack() == sequence,
+        }
+    }
+
+    /// Appends the sequence to the buffer.
+    pub fn extend_into(&self, buffer: &mut Vec<u8>) {
+        match self {
+            NodeSequence::Plain(sequence) => buffer.extend_from_slice(sequence),
+            NodeSequence::Packed(packed) => packed.unpack_into(buffer),
+        }
+    }
+
+    /// Returns the sequence as plain bytes.
+    pub fn to_vec(&self) -> Vec<u8> {
+        match self {
+            NodeSequence::Plain(sequence) => sequence.clone(),
+            NodeSequence::Packed(packed) => packed.unpack(),
+        }
+    }
+
+    /// Returns the sequence in the given orientation.
+    ///
+    /// See [`Graph::sequence`] for details.
+    pub fn oriented(&self, orientation: Orientation) -> Cow<'_, [u8]> {
+        match self {
+            NodeSequence::Plain(sequence) => oriented_sequence(sequence, orientation),
+            NodeSequence::Packed(packed) => match orientation {
+                Orientation::Forward => Cow::Owned(packed.unpack()),
+                Orientation::Reverse => Cow::Owned(reverse_complement(&packed.unpack())),
+            },
+        }
+    }
+}
+
+impl Default for NodeSequence {
+    fn default() -> Self {
+        NodeSequence::Plain(Vec::new())
+    }
+}
+
+impl PartialEq for NodeSequence {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NodeSequence::Plain(a), NodeSequence::Plain(b)) => a == b,
+            (NodeSequence::Packed(a), NodeSequence::Packed(b)) => a == b,
+            (NodeSequence::Plain(a), NodeSequence::Packed(_)) => other.matches(a),
+            (NodeSequence::Packed(_), NodeSequence::Plain(b)) => self.matches(b),
+        }
+    }
+}
+
+impl Eq for NodeSequence {}
+
+//-----------------------------------------------------------------------------
+
 /// A node with an integer identifier in a bidirected sequence graph.
 ///
 /// The node does not store its identifier, as the user is expected to know it.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NodeInt {
     /// Sequence associated with the node.
-    pub sequence: Vec<u8>,
+    pub sequence: NodeSequence,
     /// Canonical edges as (source orientation, destination node, destination orientation).
-    pub edges: Vec<(Orientation, usize, Orientation)>,
+    pub edges: EdgeList<(Orientation, usize, Orientation)>,
     /// Have we seen the node in the graph?
     pub seen: bool,
 }
@@ -412,51 +1527,61 @@ impl NodeInt {
     pub fn new(sequence: Option<Vec<u8>>) -> Self {
         if let Some(sequence) = sequence {
             NodeInt {
-                sequence,
-                edges: Vec::new(),
+                sequence: NodeSequence::Plain(sequence),
+                edges: EdgeList::new(),
                 seen: true,
             }
         } else {
             NodeInt {
-                sequence: Vec::new(),
-                edges: Vec::new(),
+                sequence: NodeSequence::default(),
+                edges: EdgeList::new(),
                 seen: false,
             }
         }
     }
 
     /// Sorts the edges and removes duplicates.
-    pub fn finalize(&mut self) {
+    ///
+    /// Returns the number of duplicate edges removed.
+    pub fn finalize(&mut self) -> usize {
+        let len = self.edges.len();
         self.edges.sort();
         self.edges.dedup();
+        len - self.edges.len()
     }
 
     /// Serializes the node and its edges in GFA format.
     pub fn serialize(&self, id: usize) -> Vec<u8> {
         let mut result = Vec::new();
-        let name = id.to_string();
+        self.serialize_into(id, &mut result);
+        result
+    }
 
-        result.push(b'S');
-        result.push(b'\t');
-        result.extend_from_slice(name.as_bytes());
-        result.push(b'\t');
-        result.extend_from_slice(&self.sequence);
-        result.push(b'\n');
+    /// Serializes the node and its edges in GFA format into the buffer.
+    ///
+    /// The buffer is cleared first, but its capacity is reused.
+    pub fn serialize_into(&self, id: usize, buffer: &mut Vec<u8>) {
+        buffer.clear();
+
+        buffer.extend_from_slice(b"S\t");
+        let name_start = buffer.len();
+        write!(buffer, "{}", id).unwrap();
+        let name_end = buffer.len();
+        buffer.push(b'\t');
+        self.sequence.extend_into(buffer);
+        buffer.push(b'\n');
 
         for (source_o, dest_id, dest_o) in &self.edges {
-            result.push(b'L');
-            result.push(b'\t');
-            result.extend_from_slice(name.as_bytes());
-            result.push(b'\t');
-            result.push(as_byte(*source_o));
-            result.push(b'\t');
-            result.extend_from_slice(dest_id.to_string().as_bytes());
-            result.push(b'\t');
-            result.push(as_byte(*dest_o));
-            result.push(b'\n');
+            buffer.extend_from_slice(b"L\t");
+            buffer.extend_from_within(name_start..name_end);
+            buffer.push(b'\t');
+            buffer.push(as_byte(*source_o));
+            buffer.push(b'\t');
+            write!(buffer, "{}", dest_id).unwrap();
+            buffer.push(b'\t');
+            buffer.push(as_byte(*dest_o));
+            buffer.push(b'\n');
         }
-
-        result
     }
 }
 
@@ -468,9 +1593,9 @@ impl NodeInt {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NodeStr {
     /// Sequence associated with the node.
-    pub sequence: Vec<u8>,
+    pub sequence: NodeSequence,
     /// Canonical edges as (source orientation, destination node, destination orientation).
-    pub edges: Vec<(Orientation, Vec<u8>, Orientation)>,
+    pub edges: EdgeList<(Orientation, Vec<u8>, Orientation)>,
     /// Have we seen the node in the graph?
     pub seen: bool,
 }
@@ -482,53 +1607,234 @@ impl NodeStr {
     pub fn new(sequence: Option<Vec<u8>>) -> Self {
         if let Some(sequence) = sequence {
             NodeStr {
-                sequence,
-                edges: Vec::new(),
+                sequence: NodeSequence::Plain(sequence),
+                edges: EdgeList::new(),
                 seen: true,
             }
         } else {
             NodeStr {
-                sequence: Vec::new(),
-                edges: Vec::new(),
+                sequence: NodeSequence::default(),
+                edges: EdgeList::new(),
                 seen: false,
             }
         }
     }
 
     /// Sorts the edges and removes duplicates.
-    pub fn finalize(&mut self) {
+    ///
+    /// Returns the number of duplicate edges removed.
+    pub fn finalize(&mut self) -> usize {
+        let len = self.edges.len();
         self.edges.sort();
         self.edges.dedup();
+        len - self.edges.len()
     }
 
     /// Serializes the node and its edges in GFA format.
     pub fn serialize(&self, name: &[u8]) -> Vec<u8> {
         let mut result = Vec::new();
+        self.serialize_into(name, &mut result);
+        result
+    }
 
-        result.extend_from_slice(b"S\t");
-        result.extend_from_slice(name);
-        result.extend_from_slice(b"\t");
-        result.extend_from_slice(&self.sequence);
-        result.extend_from_slice(b"\n");
+    /// Serializes the node and its edges in GFA format into the buffer.
+    ///
+    /// The buffer is cleared first, but its capacity is reused.
+    pub fn serialize_into(&self, name: &[u8], buffer: &mut Vec<u8>) {
+        buffer.clear();
+
+        buffer.extend_from_slice(b"S\t");
+        buffer.extend_from_slice(name);
+        buffer.extend_from_slice(b"\t");
+        self.sequence.extend_into(buffer);
+        buffer.extend_from_slice(b"\n");
 
         for (source_o, dest_name, dest_o) in &self.edges {
-            result.extend_from_slice(b"L\t");
-            result.extend_from_slice(name);
-            result.extend_from_slice(b"\t");
-            result.push(as_byte(*source_o));
-            result.extend_from_slice(b"\t");
-            result.extend_from_slice(dest_name);
-            result.extend_from_slice(b"\t");
-            result.push(as_byte(*dest_o));
-            result.extend_from_slice(b"\n");
+            buffer.extend_from_slice(b"L\t");
+            buffer.extend_from_slice(name);
+            buffer.extend_from_slice(b"\t");
+            buffer.push(as_byte(*source_o));
+            buffer.extend_from_slice(b"\t");
+            buffer.extend_from_slice(dest_name);
+            buffer.extend_from_slice(b"\t");
+            buffer.push(as_byte(*dest_o));
+            buffer.extend_from_slice(b"\n");
         }
+    }
+}
 
-        result
+//-----------------------------------------------------------------------------
+
+/// A cheaply cloneable handle to a finalized graph that can be shared between threads.
+///
+/// All graph implementations in this module are [`Send`] and [`Sync`] once finalized.
+/// The handle stores the graph behind an [`Arc`], so cloning it does not copy the graph.
+/// The stable name and the index used for translation inference are computed on first use and cached.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::{GraphInt, SharedGraph};
+/// use pggname::translation::TranslationVerdict;
+/// use std::thread;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tGATTACA\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let shared = SharedGraph::new(graph);
+/// let subgraph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tGATTACA\n").unwrap();
+///
+/// let handles: Vec<_> = (0..4).map(|_| {
+///     let shared = shared.clone();
+///     let subgraph = subgraph.clone();
+///     thread::spawn(move || {
+///         let result = shared.infer_translation_from(&subgraph).unwrap();
+///         (shared.name().to_string(), result.verdict)
+///     })
+/// }).collect();
+/// for handle in handles {
+///     let (name, verdict) = handle.join().unwrap();
+///     assert_eq!(name, pggname::stable_name(shared.graph()));
+///     assert_eq!(verdict, TranslationVerdict::Subgraph);
+/// }
+/// ```
+pub struct SharedGraph<G: Graph> {
+    inner: Arc<SharedInner<G>>,
+}
+
+struct SharedInner<G: Graph> {
+    graph: G,
+    name: OnceLock<String>,
+    index: OnceLock<Result<SequenceGraph, String>>,
+}
+
+impl<G: Graph> Clone for SharedGraph<G> {
+    fn clone(&self) -> Self {
+        SharedGraph { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<G: Graph> SharedGraph<G> {
+    /// Creates a handle to the given graph.
+    ///
+    /// The graph should already be finalized.
+    pub fn new(graph: G) -> Self {
+        SharedGraph {
+            inner: Arc::new(SharedInner {
+                graph,
+                name: OnceLock::new(),
+                index: OnceLock::new(),
+            }),
+        }
+    }
+
+    /// Returns the graph.
+    pub fn graph(&self) -> &G {
+        &self.inner.graph
+    }
+
+    /// Returns the stable name of the graph.
+    ///
+    /// The name is computed on the first call.
+    pub fn name(&self) -> &str {
+        self.inner.name.get_or_init(|| algorithms::stable_name(&self.inner.graph))
+    }
+
+    /// Infers a translation from the given graph to this graph.
+    ///
+    /// The result tells whether the given graph is a subgraph of this graph, or a translation of a subgraph.
+    /// See [`algorithms::infer_translation`] for details.
+    /// The index for this graph is built on the first call.
+    ///
+    /// Returns an error if the canonical representation of a graph cannot be interpreted.
+    pub fn infer_translation_from<A: Graph>(&self, graph: &A) -> Result<TranslationInference, String> {
+        let index = self.inner.index.get_or_init(|| SequenceGraph::new(&self.inner.graph));
+        let index = index.as_ref().map_err(|e| e.clone())?;
+        let query = SequenceGraph::new(graph)?;
+        Ok(translation::infer(&query, index))
+    }
+
+    /// Returns the number of handles to the graph.
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
     }
 }
 
 //-----------------------------------------------------------------------------
 
+// Canonical edges stored with other nodes, reversed to start from the node they end at.
+// Each edge is (orientation, other node, other orientation), as in the edge lists of the nodes.
+// The index is built on the first call to `Graph::neighbors` and cleared by `Graph::finalize`.
+// It is a cache, so it does not affect equality, and clones start without it.
+#[derive(Debug)]
+struct IncomingEdges<K>(OnceLock<IncomingMap<K>>);
+
+type IncomingMap<K> = HashMap<K, Vec<(Orientation, K, Orientation)>>;
+
+impl<K: Hash + Eq + Clone> IncomingEdges<K> {
+    // Returns the index, building it from the edge lists of the nodes if necessary.
+    fn get<V, F: Fn(&V) -> &EdgeList<(Orientation, K, Orientation)>>(&self, nodes: &BTreeMap<K, V>, edges: F) -> &IncomingMap<K> {
+        self.0.get_or_init(|| {
+            let mut result: IncomingMap<K> = HashMap::new();
+            for (source, node) in nodes.iter() {
+                for (source_o, dest, dest_o) in edges(node).iter() {
+                    result.entry(dest.clone()).or_default().push((dest_o.flip(), source.clone(), source_o.flip()));
+                }
+            }
+            result
+        })
+    }
+}
+
+impl<K> Default for IncomingEdges<K> {
+    fn default() -> Self {
+        IncomingEdges(OnceLock::new())
+    }
+}
+
+impl<K> Clone for IncomingEdges<K> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<K> PartialEq for IncomingEdges<K> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<K> Eq for IncomingEdges<K> {}
+
+// Finalizes the nodes using the given function, which returns `true` if the node has been seen and the number of duplicate edges removed.
+// Adds the numbers of duplicate edges to `duplicates` and returns the number of unseen nodes.
+// With the `parallel` feature, the nodes are finalized using multiple threads.
+fn finalize_nodes<K, V, F>(nodes: &mut BTreeMap<K, V>, duplicates: &mut BTreeMap<K, usize>, finalize: F) -> usize
+    where K: Ord + Clone + Sync, V: Send, F: Fn(&mut V) -> (bool, usize) + Sync + Send {
+    let _span = tracing::debug_span!("finalize", nodes = nodes.len()).entered();
+    #[cfg(feature = "parallel")]
+    let results: Vec<(&K, bool, usize)> = nodes.par_iter_mut()
+        .map(|(key, node)| { let (seen, removed) = finalize(node); (key, seen, removed) })
+        .filter(|(_, seen, removed)| !seen || *removed > 0)
+        .collect();
+    #[cfg(not(feature = "parallel"))]
+    let results: Vec<(&K, bool, usize)> = nodes.iter_mut()
+        .map(|(key, node)| { let (seen, removed) = finalize(node); (key, seen, removed) })
+        .filter(|(_, seen, removed)| !seen || *removed > 0)
+        .collect();
+
+    let mut unseen = 0;
+    for (key, seen, removed) in results {
+        if !seen {
+            unseen += 1;
+        }
+        if removed > 0 {
+            *duplicates.entry(key.clone()).or_default() += removed;
+        }
+    }
+    tracing::debug!(unseen, duplicates = duplicates.values().sum::<usize>(), "finalized the graph");
+    unseen
+}
+
 // Returns the orientation as `+` or `-`.
 fn as_byte(o: Orientation) -> u8 {
     match o {
@@ -1,7 +1,12 @@
+// Keeps the original style of the tests; the lints do not affect their behavior.
+#![allow(clippy::needless_return, clippy::needless_borrow)]
+
 use super::*;
 
+#[cfg(feature = "gbz")]
 use gbz::support;
 use rand::Rng;
+#[cfg(feature = "gbz")]
 use simple_sds::serialize;
 
 use std::collections::BTreeSet;
@@ -16,6 +21,7 @@ fn as_char(o: Orientation) -> char {
     }
 }
 
+#[cfg(feature = "gbz")]
 fn create_gfa_int(id: usize, sequence: &str) -> String {
     format!("S\t{}\t{}\n", id, sequence)
 }
@@ -28,6 +34,7 @@ fn random_orientation(rng: &mut impl Rng) -> Orientation {
     }
 }
 
+#[cfg(feature = "gbz")]
 // Returns a random set of edges to the given nodes.
 // The edges may be in either orientation, and there may be duplicates.
 fn random_edges_int(nodes: &[usize], n: usize) -> Vec<(Orientation, usize, Orientation)> {
@@ -44,6 +51,7 @@ fn random_edges_int(nodes: &[usize], n: usize) -> Vec<(Orientation, usize, Orien
     edges
 }
 
+#[cfg(feature = "gbz")]
 // Returns the canonical GFA L-line for an edge.
 fn gfa_edge_int(from_id: usize, from_o: Orientation, to_id: usize, to_o: Orientation) -> String {
     format!("L\t{}\t{}\t{}\t{}\n", from_id, as_char(from_o), to_id, as_char(to_o))
@@ -86,6 +94,61 @@ const EDGES_PER_NODE: usize = 6;
 
 //-----------------------------------------------------------------------------
 
+#[test]
+fn graph_statistics() {
+    let mut stats = GraphStatistics::default();
+    stats.add_node(2, 10);
+    stats.add_node(0, 5);
+    assert_eq!(stats, GraphStatistics { nodes: 2, edges: 2, sequence_len: 15 }, "Wrong accumulated statistics");
+
+    let mut stats = GraphStatistics { nodes: 1, edges: 0, sequence_len: u64::MAX - 1 };
+    stats.add_node(0, 1);
+    assert_eq!(stats.sequence_len, u64::MAX, "Wrong sequence length at the limit");
+    let result = std::panic::catch_unwind(move || stats.add_node(0, 1));
+    assert!(result.is_err(), "Sequence length overflow was not detected");
+}
+
+#[test]
+fn edge_statistics() {
+    // Every edge is counted once, and the statistics agree between graph types.
+    #[cfg(feature = "gbz")]
+    {
+        let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+        let graph = GBZInt { graph: gbz };
+        let stats = graph.edge_statistics();
+        assert_eq!(stats.edges(), graph.statistics().edges, "Wrong number of edges");
+        assert!(stats.warnings().is_empty(), "Unusual distribution in the example graph: {:?}", stats.warnings());
+        let gfa: GraphStr = algorithms::parse_gfa(algorithms::open_gfa(support::get_test_data("example.gfa")).unwrap()).unwrap();
+        assert_eq!(gfa.edge_statistics(), stats, "Different statistics for GraphStr");
+    }
+
+    // Reversing all edges is reported.
+    let mut gfa = Vec::new();
+    for id in 1..=20 {
+        gfa.extend_from_slice(format!("S\t{}\tA\n", id).as_bytes());
+    }
+    let mut forward = gfa.clone();
+    let mut reversed = gfa;
+    for id in 1..20 {
+        forward.extend_from_slice(format!("L\t{}\t+\t{}\t+\t*\n", id, id + 1).as_bytes());
+        reversed.extend_from_slice(format!("L\t{}\t-\t{}\t-\t*\n", id, id + 1).as_bytes());
+    }
+    let forward: GraphInt = algorithms::parse_gfa_bytes(&forward).unwrap();
+    let stats = forward.edge_statistics();
+    assert_eq!(stats, EdgeStatistics { forward_forward: 19, ..Default::default() }, "Wrong statistics for forward edges");
+    assert!(stats.warnings().is_empty(), "Forward edges were reported");
+    let reversed: GraphInt = algorithms::parse_gfa_bytes(&reversed).unwrap();
+    let stats = reversed.edge_statistics();
+    assert_eq!(stats, EdgeStatistics { reverse_reverse: 19, ..Default::default() }, "Wrong statistics for reversed edges");
+    assert_eq!(stats.warnings().len(), 1, "Reversed edges were not reported");
+
+    // Small graphs and self-loops.
+    let stats = EdgeStatistics { reverse_reverse: EdgeStatistics::MIN_EDGES - 1, ..Default::default() };
+    assert!(stats.warnings().is_empty(), "A small graph was reported");
+    let stats = EdgeStatistics { forward_forward: 6, forward_reverse: 6, self_loops: 7, ..Default::default() };
+    assert_eq!(stats.warnings().len(), 1, "Self-loops were not reported");
+}
+
 #[test]
 fn nodes_seen() {
     let unseen = NodeInt::new(None);
@@ -99,6 +162,7 @@ fn nodes_seen() {
     assert!(seen.seen, "NodeStr with sequence should be seen");
 }
 
+#[cfg(feature = "gbz")]
 #[test]
 fn node_int() {
     let nodes = vec![1, 2, 3, 4, 5];
@@ -194,6 +258,7 @@ fn nodes_and_sequences(int_ids: bool) -> (Vec<String>, Vec<String>) {
     (nodes, sequences)
 }
 
+#[cfg(feature = "gbz")]
 fn parse_node_ids(nodes: &[String]) -> Vec<usize> {
     nodes.iter()
         .map(|s| s.parse::<usize>().unwrap())
@@ -209,6 +274,7 @@ fn add_nodes<G: Graph>(graph: &mut G, nodes: &[String], sequences: &[String]) {
     }
 }
 
+#[cfg(feature = "gbz")]
 // Returns the canonical edge sets for each node.
 fn add_edges_int(graph: &mut GraphInt, nodes: &[String], node_ids: &[usize]) -> Vec<BTreeSet<(Orientation, usize, Orientation)>> {
     let mut canonical_edges: Vec<BTreeSet<(Orientation, usize, Orientation)>> = vec![BTreeSet::new(); NODE_COUNT];
@@ -259,12 +325,13 @@ fn check_statistics<G: Graph, E: Sized>(graph: &G, canonical_edges: &[BTreeSet<E
     let true_node_count = NODE_COUNT;
     let true_edge_count: usize = canonical_edges.iter().map(|edges| edges.len()).sum();
     let true_seq_len: usize = sequences.iter().map(|s| s.len()).sum();
-    let (node_count, edge_count, seq_len) = graph.statistics();
-    assert_eq!(node_count, true_node_count, "Wrong node count in round {}", round);
-    assert_eq!(edge_count, true_edge_count, "Wrong edge count in round {}", round);
-    assert_eq!(seq_len, true_seq_len, "Wrong sequence length in round {}", round);
+    let stats = graph.statistics();
+    assert_eq!(stats.nodes, true_node_count as u64, "Wrong node count in round {}", round);
+    assert_eq!(stats.edges, true_edge_count as u64, "Wrong edge count in round {}", round);
+    assert_eq!(stats.sequence_len, true_seq_len as u64, "Wrong sequence length in round {}", round);
 }
 
+#[cfg(feature = "gbz")]
 fn check_gfa_int(
     graph: &GraphInt,
     node_ids: &[usize], sequences: &[String],
@@ -303,6 +370,7 @@ fn check_gfa_str(
     }
 }
 
+#[cfg(feature = "gbz")]
 #[test]
 fn graph_int_nodes_first() {
     let (nodes, sequences) = nodes_and_sequences(true);
@@ -318,6 +386,7 @@ fn graph_int_nodes_first() {
     }
 }
 
+#[cfg(feature = "gbz")]
 #[test]
 fn graph_int_edges_first() {
     let (nodes, sequences) = nodes_and_sequences(true);
@@ -363,6 +432,7 @@ fn graph_str_edges_first() {
 
 //-----------------------------------------------------------------------------
 
+#[cfg(feature = "gbz")]
 fn gbz_statistics(gbz: &GBZ) -> (usize, usize, usize) {
     let node_count = gbz.nodes();
     let mut edge_count = 0;
@@ -382,6 +452,7 @@ fn gbz_statistics(gbz: &GBZ) -> (usize, usize, usize) {
     (node_count, edge_count, seq_len)
 }
 
+#[cfg(feature = "gbz")]
 #[test]
 fn gbz_int() {
     let filename = support::get_test_data("translation.gbz");
@@ -389,10 +460,10 @@ fn gbz_int() {
     let (true_node_count, true_edge_count, true_seq_len) = gbz_statistics(&gbz);
 
     let graph = GBZInt { graph: gbz.clone() };
-    let (node_count, edge_count, seq_len) = graph.statistics();
-    assert_eq!(node_count, true_node_count, "Wrong node count in GBZInt");
-    assert_eq!(edge_count, true_edge_count, "Wrong edge count in GBZInt");
-    assert_eq!(seq_len, true_seq_len, "Wrong sequence length in GBZInt");
+    let stats = graph.statistics();
+    assert_eq!(stats.nodes, true_node_count as u64, "Wrong node count in GBZInt");
+    assert_eq!(stats.edges, true_edge_count as u64, "Wrong edge count in GBZInt");
+    assert_eq!(stats.sequence_len, true_seq_len as u64, "Wrong sequence length in GBZInt");
 
     let serialized: Vec<Vec<u8>> = graph.node_iter().collect();
     assert_eq!(serialized.len(), true_node_count, "Wrong number of serialized nodes in GBZInt");
@@ -413,6 +484,7 @@ fn gbz_int() {
     }
 }
 
+#[cfg(feature = "gbz")]
 #[test]
 fn gbz_str() {
     let filename = support::get_test_data("translation.gbz");
@@ -420,10 +492,10 @@ fn gbz_str() {
     let (true_node_count, true_edge_count, true_seq_len) = gbz_statistics(&gbz);
 
     let graph = GBZStr { graph: gbz.clone() };
-    let (node_count, edge_count, seq_len) = graph.statistics();
-    assert_eq!(node_count, true_node_count, "Wrong node count in GBZStr");
-    assert_eq!(edge_count, true_edge_count, "Wrong edge count in GBZStr");
-    assert_eq!(seq_len, true_seq_len, "Wrong sequence length in GBZStr");
+    let stats = graph.statistics();
+    assert_eq!(stats.nodes, true_node_count as u64, "Wrong node count in GBZStr");
+    assert_eq!(stats.edges, true_edge_count as u64, "Wrong edge count in GBZStr");
+    assert_eq!(stats.sequence_len, true_seq_len as u64, "Wrong sequence length in GBZStr");
 
     let serialized: Vec<Vec<u8>> = graph.node_iter().collect();
     assert_eq!(serialized.len(), true_node_count, "Wrong number of serialized nodes in GBZStr");
@@ -455,3 +527,492 @@ fn gbz_str() {
 }
 
 //-----------------------------------------------------------------------------
+
+#[cfg(feature = "gbz")]
+fn check_for_each_node<G: Graph>(graph: &G, name: &str) {
+    let expected: Vec<Vec<u8>> = graph.node_iter().collect();
+    let mut serialized: Vec<Vec<u8>> = Vec::new();
+    graph.for_each_node(|bytes| serialized.push(bytes.to_vec()));
+    assert_eq!(serialized, expected, "Wrong serialized nodes from for_each_node in {}", name);
+
+    let mut count = 0;
+    let result: Result<(), usize> = graph.try_for_each_node(|_| {
+        count += 1;
+        if count == 2 { Err(count) } else { Ok(()) }
+    });
+    assert_eq!(result, Err(2), "try_for_each_node did not stop at the first error in {}", name);
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn for_each_node() {
+    let filename_gbz = support::get_test_data("translation.gbz");
+    let gbz: GBZ = serialize::load_from(&filename_gbz).unwrap();
+    let filename = support::get_test_data("example.gfa");
+    let gfa = std::fs::read(&filename).unwrap();
+    let graph: GraphInt = crate::algorithms::parse_gfa_bytes(&gfa).unwrap();
+    check_for_each_node(&graph, "GraphInt");
+    let filename = support::get_test_data("translation.gfa");
+    let gfa = std::fs::read(&filename).unwrap();
+    let graph: GraphStr = crate::algorithms::parse_gfa_bytes(&gfa).unwrap();
+    check_for_each_node(&graph, "GraphStr");
+    check_for_each_node(&GBZInt { graph: gbz.clone() }, "GBZInt");
+    check_for_each_node(&GBZStr { graph: gbz }, "GBZStr");
+    check_for_each_node(&LazyGBZ::load_from(&filename_gbz).unwrap(), "LazyGBZ");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn lazy_gbz() {
+    for name in ["example.gbz", "translation.gbz"] {
+        let filename = support::get_test_data(name);
+        let gbz: GBZ = serialize::load_from(&filename).unwrap();
+        let truth = GBZInt { graph: gbz.clone() };
+        let graph = LazyGBZ::load_from(&filename).unwrap();
+        let tag = crate::GraphName::TAG_NAME;
+        assert_eq!(graph.tags().get(tag), gbz.tags().get(tag), "Wrong name tag in LazyGBZ for {}", name);
+        assert_eq!(graph.statistics(), truth.statistics(), "Wrong statistics in LazyGBZ for {}", name);
+        let serialized: Vec<Vec<u8>> = graph.node_iter().collect();
+        let expected: Vec<Vec<u8>> = truth.node_iter().collect();
+        assert_eq!(serialized, expected, "Wrong serialized nodes in LazyGBZ for {}", name);
+    }
+
+    let filename = support::get_test_data("example.gfa");
+    assert!(LazyGBZ::load_from(&filename).is_err(), "Loaded a GFA file as LazyGBZ");
+
+    // Find the document array sample count and replace it with a value that overflows.
+    let mut data = std::fs::read(support::get_test_data("example.gbz")).unwrap();
+    let mut reader = io::Cursor::new(&data);
+    let _ = Header::<GBZPayload>::load(&mut reader).unwrap();
+    let _ = Tags::load(&mut reader).unwrap();
+    let _ = Header::<GBWTPayload>::load(&mut reader).unwrap();
+    let _ = Tags::load(&mut reader).unwrap();
+    let _ = BWT::load(&mut reader).unwrap();
+    let offset = reader.position() as usize;
+    data[offset..offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+    let result = LazyGBZ::load(&mut data.as_slice());
+    assert_eq!(result.err().map(|e| e.kind()), Some(ErrorKind::InvalidData), "Loaded a GBZ with an overflowing sample count");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn node_sequence() {
+    let original: Vec<u8> = [b"GATTACA".repeat(10), b"gattaca".repeat(10), b"NNNN".to_vec()].concat();
+    let plain = NodeSequence::new(&original, false);
+    let packed = NodeSequence::new(&original, true);
+    assert!(!plain.is_packed() && packed.is_packed(), "Wrong representations");
+    assert_eq!(plain.len(), original.len(), "Wrong plain length");
+    assert_eq!(packed.len(), original.len(), "Wrong packed length");
+    assert_eq!(plain, packed, "Plain and packed sequences are not equal");
+    assert!(packed.matches(&original), "Packed sequence does not match the original");
+    assert!(!packed.matches(&original[1..]), "Packed sequence matches a different sequence");
+    assert_eq!(packed.to_vec(), original, "Wrong unpacked sequence");
+
+    let mut sequence = plain.clone();
+    sequence.pack();
+    assert!(sequence.is_packed(), "Sequence was not packed");
+    sequence.set(b"ACGT");
+    assert!(sequence.is_packed() && sequence.matches(b"ACGT"), "Wrong sequence after set()");
+    sequence.set(b"NANANANA");
+    assert!(!sequence.is_packed() && sequence.matches(b"NANANANA"), "Packed a sequence without saving space");
+    assert!(!NodeSequence::new(b"NANANANA", true).is_packed(), "Packed a sequence without saving space");
+    assert!(NodeSequence::default().is_empty(), "Default sequence is not empty");
+}
+
+fn check_packed<G: Graph>(gfa: &[u8], name: &str) {
+    let plain: G = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    let options = crate::algorithms::ParseOptions { pack_sequences: true, ..Default::default() };
+    let (packed, _): (G, _) = crate::algorithms::parse_gfa_bytes_with_options(gfa, &options).unwrap();
+    assert_eq!(packed.statistics(), plain.statistics(), "Wrong statistics for packed {}", name);
+    let expected: Vec<Vec<u8>> = plain.node_iter().collect();
+    let serialized: Vec<Vec<u8>> = packed.node_iter().collect();
+    assert_eq!(serialized, expected, "Wrong serialized nodes for packed {}", name);
+
+    let mut packed_later = plain;
+    packed_later.pack_sequences();
+    let serialized: Vec<Vec<u8>> = packed_later.node_iter().collect();
+    assert_eq!(serialized, expected, "Wrong serialized nodes for {} packed after parsing", name);
+}
+
+#[test]
+fn packed_sequences() {
+    #[cfg(feature = "gbz")]
+    {
+        let gfa = std::fs::read(support::get_test_data("example.gfa")).unwrap();
+        check_packed::<GraphInt>(&gfa, "GraphInt");
+        let gfa = std::fs::read(support::get_test_data("translation.gfa")).unwrap();
+        check_packed::<GraphStr>(&gfa, "GraphStr");
+    }
+
+    let gfa = b"S\t1\tGATTACA\nS\t2\tacgtNNNN\nS\t1\tGATTACA\nL\t1\t+\t2\t-\t*\n";
+    check_packed::<GraphInt>(gfa, "GraphInt with exceptions");
+    let options = crate::algorithms::ParseOptions { pack_sequences: true, ..Default::default() };
+    let conflict = b"S\t1\tGATTACA\nS\t1\tGATTACC\n";
+    let result = crate::algorithms::parse_gfa_bytes_with_options::<GraphInt>(conflict, &options);
+    assert!(result.is_err(), "Accepted a packed node with two different sequences");
+}
+
+//-----------------------------------------------------------------------------
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn send_sync() {
+    assert_send_sync::<GraphInt>();
+    assert_send_sync::<GraphStr>();
+    #[cfg(feature = "gbz")]
+    assert_send_sync::<GBZInt>();
+    #[cfg(feature = "gbz")]
+    assert_send_sync::<GBZStr>();
+    #[cfg(feature = "gbz")]
+    assert_send_sync::<LazyGBZ>();
+    assert_send_sync::<SharedGraph<GraphInt>>();
+    assert_send_sync::<SharedGraph<GraphStr>>();
+    #[cfg(feature = "gbz")]
+    assert_send_sync::<SharedGraph<GBZInt>>();
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn shared_graph() {
+    let gfa = std::fs::read(support::get_test_data("translation.gfa")).unwrap();
+    let graph: GraphStr = crate::algorithms::parse_gfa_bytes(&gfa).unwrap();
+    let expected_name = crate::algorithms::stable_name(&graph);
+    let shared = SharedGraph::new(graph);
+    assert_eq!(shared.handle_count(), 1, "Wrong handle count for a new graph");
+
+    let gbz: GBZ = serialize::load_from(support::get_test_data("translation.gbz")).unwrap();
+    let query = SharedGraph::new(GBZInt { graph: gbz });
+    let threads: Vec<_> = (0..8).map(|i| {
+        let shared = shared.clone();
+        let query = query.clone();
+        std::thread::spawn(move || {
+            let name = shared.name().to_string();
+            let forward = shared.infer_translation_from(query.graph()).unwrap();
+            let reverse = query.infer_translation_from(shared.graph()).unwrap();
+            (i, name, forward.verdict, reverse.is_translation())
+        })
+    }).collect();
+
+    for thread in threads {
+        let (i, name, verdict, reverse) = thread.join().unwrap();
+        assert_eq!(name, expected_name, "Wrong name in thread {}", i);
+        assert_eq!(verdict, crate::translation::TranslationVerdict::Subgraph, "Wrong verdict in thread {}", i);
+        assert!(!reverse, "Found a translation from the supergraph in thread {}", i);
+    }
+    assert_eq!(shared.handle_count(), 1, "Handles were not released");
+    assert_eq!(shared.name(), expected_name, "Wrong cached name");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn duplicate_edges() {
+    let gfa = b"S\t1\tA\nS\t2\tC\nL\t1\t+\t2\t+\t*\nL\t1\t+\t2\t+\t*\nL\t2\t-\t1\t-\t*\nL\t2\t+\t2\t+\t*\nL\t2\t-\t2\t-\t*\nL\t1\t+\t2\t-\t*\n";
+    let clean = b"S\t1\tA\nS\t2\tC\nL\t1\t+\t2\t+\t*\nL\t2\t+\t2\t+\t*\nL\t1\t+\t2\t-\t*\n";
+
+    let mut graph: GraphInt = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    assert_eq!(graph.duplicate_edges(), 3, "Wrong number of duplicate edges in GraphInt");
+    let expected: BTreeMap<usize, usize> = [(1, 2), (2, 1)].into_iter().collect();
+    assert_eq!(graph.duplicates, expected, "Wrong per-node duplicates in GraphInt");
+    graph.finalize().unwrap();
+    assert_eq!(graph.duplicate_edges(), 3, "Finalizing again changed the number of duplicate edges");
+    let truth: GraphInt = crate::algorithms::parse_gfa_bytes(clean).unwrap();
+    assert_eq!(truth.duplicate_edges(), 0, "Found duplicate edges in a clean GraphInt");
+    assert_eq!(graph.statistics(), truth.statistics(), "Duplicate edges changed the statistics");
+
+    let graph: GraphStr = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    assert_eq!(graph.duplicate_edges(), 3, "Wrong number of duplicate edges in GraphStr");
+    let expected: BTreeMap<Vec<u8>, usize> = [(b"1".to_vec(), 2), (b"2".to_vec(), 1)].into_iter().collect();
+    assert_eq!(graph.duplicates, expected, "Wrong per-node duplicates in GraphStr");
+
+    let mut node = NodeInt::new(Some(b"A".to_vec()));
+    node.edges.push((Orientation::Forward, 2, Orientation::Forward));
+    node.edges.push((Orientation::Forward, 2, Orientation::Forward));
+    assert_eq!(node.finalize(), 1, "Wrong number of duplicates removed from NodeInt");
+}
+
+#[test]
+fn name_depends_on_node_ids() {
+    // (GFA, integer names, names differ)
+    let cases: [(&[u8], bool, bool); 5] = [
+        (b"S\t1\tA\nS\t2\tC\nL\t1\t+\t2\t+\t*\n", true, false),
+        (b"S\t2\tA\nS\t10\tC\nL\t2\t+\t10\t+\t*\n", true, true),
+        (b"S\t1\tA\nS\t02\tC\nL\t1\t+\t02\t+\t*\n", true, true),
+        (b"S\t1\tA\nS\t+2\tC\nL\t1\t+\t+2\t+\t*\n", true, true),
+        (b"S\t2\tA\nS\tx10\tC\nL\t2\t+\tx10\t+\t*\n", false, false),
+    ];
+    for (gfa, integer_names, differ) in cases {
+        let name = String::from_utf8_lossy(gfa);
+        let str_graph: GraphStr = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+        assert_eq!(str_graph.name_depends_on_node_ids(), differ, "Wrong result for GraphStr with {:?}", name);
+        let int_graph = crate::algorithms::parse_gfa_bytes::<GraphInt>(gfa);
+        assert_eq!(int_graph.is_ok(), integer_names, "Wrong parsing result for GraphInt with {:?}", name);
+        if let Ok(int_graph) = int_graph {
+            assert_eq!(int_graph.name_depends_on_node_ids(), differ, "Wrong result for GraphInt with {:?}", name);
+            let same_name = crate::stable_name(&int_graph) == crate::stable_name(&str_graph);
+            assert_eq!(same_name, !differ, "The result does not match the names with {:?}", name);
+        }
+    }
+
+    #[cfg(feature = "gbz")]
+    {
+        let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+        let graph = GBZInt { graph: gbz };
+        assert!(!graph.name_depends_on_node_ids(), "Wrong result for GBZInt");
+        let graph = GBZStr { graph: graph.graph };
+        assert!(!graph.name_depends_on_node_ids(), "Wrong result for GBZStr");
+    }
+}
+
+#[test]
+fn string_id_order() {
+    let mut names: Vec<&[u8]> = vec![b"a", b"B", b"9", b"10", b"007", b"7", b"node", b"node1"];
+    names.sort_by(|a, b| GraphStr::cmp_ids(a, b));
+    let expected: Vec<&[u8]> = vec![b"007", b"10", b"7", b"9", b"B", b"a", b"node", b"node1"];
+    assert_eq!(names, expected, "Wrong order for string identifiers");
+
+    // The canonical GFA follows the same order.
+    let gfa = b"S\tb\tA\nS\tB\tC\nS\t10\tG\nS\t9\tT\nL\tb\t+\tB\t+\t*\n";
+    let graph: GraphStr = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    let order: Vec<Vec<u8>> = graph.nodes.keys().cloned().collect();
+    assert_eq!(order, vec![b"10".to_vec(), b"9".to_vec(), b"B".to_vec(), b"b".to_vec()], "Wrong node order in GraphStr");
+    assert_eq!(graph.edges_of(b"B"), Some(vec![(Orientation::Reverse, b"b".to_vec(), Orientation::Reverse)]), "Wrong canonical edge");
+}
+
+#[test]
+fn confusable_ids() {
+    let gfa = b"S\tnode7\tA\nS\tNode7\tC\nS\tnode007\tG\nS\tx01y0\tT\nS\tx1y00\tA\nS\tx10\tC\nS\t0\tG\nS\t00\tT\n";
+    let graph: GraphStr = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    let groups: Vec<Vec<String>> = graph.confusable_ids().iter()
+        .map(|group| group.iter().map(|name| String::from_utf8_lossy(name).into_owned()).collect())
+        .collect();
+    let expected = vec![
+        vec![String::from("0"), String::from("00")],
+        vec![String::from("Node7"), String::from("node007"), String::from("node7")],
+        vec![String::from("x01y0"), String::from("x1y00")],
+    ];
+    assert_eq!(groups, expected, "Wrong confusable identifiers");
+
+    let gfa = b"S\tnode7\tA\nS\tnode70\tC\nS\tnode17\tG\n";
+    let graph: GraphStr = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    assert!(graph.confusable_ids().is_empty(), "Distinct identifiers reported as confusable");
+}
+
+//-----------------------------------------------------------------------------
+
+// Checks `edges_of` and `neighbors` against the serialized nodes.
+fn check_adjacency<G: Graph>(graph: &G, name: &str) {
+    let parse_o = |field: &[u8]| crate::algorithms::parse_orientation(field).unwrap();
+    let mut nodes: Vec<Vec<u8>> = Vec::new();
+    let mut all_edges: Vec<(Vec<u8>, Orientation, Vec<u8>, Orientation)> = Vec::new();
+    for serialized in graph.node_iter() {
+        let mut lines = serialized.split(|&c| c == b'\n').filter(|line| !line.is_empty());
+        let node = lines.next().unwrap().split(|&c| c == b'\t').nth(1).unwrap().to_vec();
+        let edges: Vec<(Orientation, Vec<u8>, Orientation)> = lines.map(|line| {
+            let fields: Vec<&[u8]> = line.split(|&c| c == b'\t').collect();
+            (parse_o(fields[2]), fields[3].to_vec(), parse_o(fields[4]))
+        }).collect();
+        assert_eq!(graph.edges_of(&node), Some(edges.clone()), "Wrong edges for node {} in {}", String::from_utf8_lossy(&node), name);
+        all_edges.extend(edges.into_iter().map(|(source_o, dest, dest_o)| (node.clone(), source_o, dest, dest_o)));
+        nodes.push(node);
+    }
+
+    assert_eq!(graph.node_names().collect::<Vec<_>>(), nodes, "Wrong node names in {}", name);
+    for node in nodes.iter() {
+        for o in [Orientation::Forward, Orientation::Reverse] {
+            let mut expected: BTreeSet<(Vec<u8>, Orientation)> = BTreeSet::new();
+            for (from, from_o, to, to_o) in all_edges.iter() {
+                if from == node && *from_o == o {
+                    expected.insert((to.clone(), *to_o));
+                }
+                if to == node && to_o.flip() == o {
+                    expected.insert((from.clone(), from_o.flip()));
+                }
+            }
+            let neighbors = graph.neighbors(node, o).unwrap();
+            let truth: Vec<(Vec<u8>, Orientation)> = expected.into_iter().collect();
+            assert_eq!(neighbors.len(), truth.len(), "Wrong number of neighbors for {} in {}", String::from_utf8_lossy(node), name);
+            let found: BTreeSet<(Vec<u8>, Orientation)> = neighbors.into_iter().collect();
+            assert_eq!(found.into_iter().collect::<Vec<_>>(), truth, "Wrong neighbors for {} in {}", String::from_utf8_lossy(node), name);
+        }
+    }
+
+    for missing in [b"".as_slice(), b"0", b"123456789", b"x"] {
+        assert_eq!(graph.edges_of(missing), None, "Found edges for a missing node in {}", name);
+        assert_eq!(graph.neighbors(missing, Orientation::Forward), None, "Found neighbors for a missing node in {}", name);
+    }
+}
+
+#[test]
+fn adjacency() {
+    #[cfg(feature = "gbz")]
+    {
+        let filename_gbz = support::get_test_data("translation.gbz");
+        let gbz: GBZ = serialize::load_from(&filename_gbz).unwrap();
+        check_adjacency(&GBZInt { graph: gbz.clone() }, "GBZInt");
+        check_adjacency(&GBZStr { graph: gbz }, "GBZStr");
+        check_adjacency(&LazyGBZ::load_from(&filename_gbz).unwrap(), "LazyGBZ");
+        for name in ["example.gfa", "translation.gfa"] {
+            let gfa = std::fs::read(support::get_test_data(name)).unwrap();
+            let graph: GraphStr = crate::algorithms::parse_gfa_bytes(&gfa).unwrap();
+            check_adjacency(&graph, &format!("GraphStr from {}", name));
+            if let Ok(graph) = crate::algorithms::parse_gfa_bytes::<GraphInt>(&gfa) {
+                check_adjacency(&graph, &format!("GraphInt from {}", name));
+            }
+        }
+    }
+
+    // Self-loops and edges stored with other nodes.
+    let gfa = b"S\t1\tA\nS\t2\tC\nS\t3\tG\nL\t1\t+\t1\t-\t*\nL\t2\t+\t2\t+\t*\nL\t3\t-\t2\t-\t*\nL\t1\t+\t2\t-\t*\n";
+    let graph: GraphInt = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    check_adjacency(&graph, "GraphInt with self-loops");
+    let node = |name: &str, o: Orientation| (name.as_bytes().to_vec(), o);
+    assert_eq!(
+        graph.neighbors(b"2", Orientation::Forward),
+        Some(vec![node("1", Orientation::Reverse), node("2", Orientation::Forward), node("3", Orientation::Forward)]),
+        "Wrong successors for node 2"
+    );
+    assert_eq!(graph.neighbors(b"2", Orientation::Reverse), Some(vec![node("2", Orientation::Reverse)]), "Wrong predecessors for node 2");
+    assert_eq!(graph.neighbors(b"1", Orientation::Forward), Some(vec![node("1", Orientation::Reverse), node("2", Orientation::Reverse)]), "Wrong successors for node 1");
+    let graph: GraphStr = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    check_adjacency(&graph, "GraphStr with self-loops");
+
+    // The index of incoming edges is rebuilt after adding edges and does not affect equality.
+    let mut graph: GraphInt = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    let copy = graph.clone();
+    assert_eq!(graph.neighbors(b"3", Orientation::Forward), Some(Vec::new()), "Wrong successors for node 3");
+    assert_eq!(graph, copy, "Querying the neighbors changed the graph");
+    graph.add_edge(b"1", Orientation::Forward, b"3", Orientation::Forward).unwrap();
+    graph.finalize().unwrap();
+    assert_eq!(graph.neighbors(b"3", Orientation::Reverse), Some(vec![node("1", Orientation::Reverse), node("2", Orientation::Reverse)]), "Wrong predecessors after adding an edge");
+    check_adjacency(&graph, "GraphInt with an added edge");
+}
+
+// A graph that only implements the required methods of `Graph`.
+// The extra lines are appended to each serialized node.
+struct Minimal<G: Graph> {
+    graph: G,
+    extra: &'static [u8],
+}
+
+impl<G: Graph> Graph for Minimal<G> {
+    fn new() -> Self {
+        Minimal { graph: G::new(), extra: b"" }
+    }
+
+    fn add_node(&mut self, name: &[u8], sequence: &[u8]) -> Result<(), String> {
+        self.graph.add_node(name, sequence)
+    }
+
+    fn add_edge(&mut self, source_name: &[u8], source_o: Orientation, dest_name: &[u8], dest_o: Orientation) -> Result<(), String> {
+        self.graph.add_edge(source_name, source_o, dest_name, dest_o)
+    }
+
+    fn finalize(&mut self) -> Result<(), String> {
+        self.graph.finalize()
+    }
+
+    fn statistics(&self) -> GraphStatistics {
+        self.graph.statistics()
+    }
+
+    fn node_iter(&self) -> impl Iterator<Item=Vec<u8>> {
+        self.graph.node_iter().map(|mut bytes| {
+            bytes.extend_from_slice(self.extra);
+            bytes
+        })
+    }
+}
+
+#[test]
+fn default_adjacency() {
+    #[cfg(feature = "gbz")]
+    {
+        for name in ["example.gfa", "translation.gfa"] {
+            let gfa = std::fs::read(support::get_test_data(name)).unwrap();
+            let graph: Minimal<GraphStr> = crate::algorithms::parse_gfa_bytes(&gfa).unwrap();
+            check_adjacency(&graph, &format!("default GraphStr from {}", name));
+            check_sequences(&graph, &format!("default GraphStr from {}", name));
+            if let Ok(graph) = crate::algorithms::parse_gfa_bytes::<Minimal<GraphInt>>(&gfa) {
+                check_adjacency(&graph, &format!("default GraphInt from {}", name));
+                check_sequences(&graph, &format!("default GraphInt from {}", name));
+            }
+        }
+    }
+
+    // Neighbors are in canonical order, which is numerical for integer identifiers.
+    let gfa = b"S\t2\tA\nS\t10\tC\nS\t9\tG\nL\t2\t+\t10\t+\t*\nL\t2\t+\t9\t-\t*\n";
+    let graph: Minimal<GraphInt> = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    assert_eq!(graph.neighbors(b"2", Orientation::Forward), graph.graph.neighbors(b"2", Orientation::Forward), "Wrong neighbor order");
+}
+
+#[test]
+fn default_edge_statistics() {
+    let gfa = b"S\t1\tA\nS\t2\tC\nS\t3\tG\nL\t1\t+\t1\t-\t*\nL\t2\t-\t1\t-\t*\n";
+    let mut graph: Minimal<GraphInt> = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    let expected = graph.graph.edge_statistics();
+    assert_eq!(graph.edge_statistics(), expected, "Wrong default edge statistics");
+
+    // Lines other than links are not edges.
+    graph.extra = b"C\t1\t+\t3\t+\t0\t*\nJ\t1\t-\t3\t-\t*\n";
+    assert_eq!(graph.edge_statistics(), expected, "Counted other lines as edges");
+    let names: Vec<Vec<u8>> = graph.node_names().collect();
+    assert_eq!(names, graph.graph.node_names().collect::<Vec<_>>(), "Wrong default node names");
+    let components = crate::algorithms::Components::new(&graph).unwrap();
+    assert_eq!(components.sizes(), &[2, 1], "Counted other lines as edges in components");
+}
+
+//-----------------------------------------------------------------------------
+
+#[cfg(feature = "gbz")]
+// Checks `sequence` against the serialized nodes.
+fn check_sequences<G: Graph>(graph: &G, name: &str) {
+    for serialized in graph.node_iter() {
+        let line = serialized.split(|&c| c == b'\n').next().unwrap();
+        let fields: Vec<&[u8]> = line.split(|&c| c == b'\t').collect();
+        let (node, sequence) = (fields[1], fields[2]);
+        assert_eq!(graph.sequence(node, Orientation::Forward).as_deref(), Some(sequence), "Wrong forward sequence for {} in {}", String::from_utf8_lossy(node), name);
+        let reverse = reverse_complement(sequence);
+        assert_eq!(graph.sequence(node, Orientation::Reverse).as_deref(), Some(reverse.as_slice()), "Wrong reverse sequence for {} in {}", String::from_utf8_lossy(node), name);
+    }
+
+    for missing in [b"".as_slice(), b"0", b"123456789", b"x"] {
+        assert_eq!(graph.sequence(missing, Orientation::Forward), None, "Found a sequence for a missing node in {}", name);
+    }
+}
+
+#[test]
+fn oriented_sequences() {
+    #[cfg(feature = "gbz")]
+    {
+        let filename_gbz = support::get_test_data("translation.gbz");
+        let gbz: GBZ = serialize::load_from(&filename_gbz).unwrap();
+        check_sequences(&GBZInt { graph: gbz.clone() }, "GBZInt");
+        check_sequences(&GBZStr { graph: gbz }, "GBZStr");
+        check_sequences(&LazyGBZ::load_from(&filename_gbz).unwrap(), "LazyGBZ");
+        for name in ["example.gfa", "translation.gfa"] {
+            let gfa = std::fs::read(support::get_test_data(name)).unwrap();
+            let mut graph: GraphStr = crate::algorithms::parse_gfa_bytes(&gfa).unwrap();
+            check_sequences(&graph, &format!("GraphStr from {}", name));
+            graph.pack_sequences();
+            check_sequences(&graph, &format!("Packed GraphStr from {}", name));
+            if let Ok(mut graph) = crate::algorithms::parse_gfa_bytes::<GraphInt>(&gfa) {
+                check_sequences(&graph, &format!("GraphInt from {}", name));
+                graph.pack_sequences();
+                check_sequences(&graph, &format!("Packed GraphInt from {}", name));
+            }
+        }
+
+    }
+
+    // Forward sequences are borrowed, and reverse complements are normalized.
+    let gfa = b"S\t1\tGATtaca\nS\t2\tAnRC\nL\t1\t+\t2\t-\t*\n";
+    let graph: GraphInt = crate::algorithms::parse_gfa_bytes(gfa).unwrap();
+    assert!(matches!(graph.sequence(b"1", Orientation::Forward), Some(Cow::Borrowed(b"GATtaca"))), "Forward sequence was not borrowed");
+    assert_eq!(graph.sequence(b"1", Orientation::Reverse).as_deref(), Some(b"TGTAATC".as_slice()), "Wrong reverse complement for node 1");
+    assert_eq!(graph.sequence(b"2", Orientation::Reverse).as_deref(), Some(b"GNNT".as_slice()), "Wrong reverse complement for node 2");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,87 @@
+//! Support for annotating BCF and CRAM headers with graph names.
+//!
+//! Graph-aware callers and aligners often write their output in the binary formats of htslib.
+//! Both formats store a text header that can be read with `bcftools view -h` or `samtools view -H` and replaced with `bcftools reheader` or `samtools reheader`.
+//!
+//! * BCF headers are VCF headers, so the graph name is stored as VCF meta-information lines with [`crate::vcf::annotate_header_lines`].
+//! * CRAM headers (as well as SAM and BAM headers) have no structured place for graph names, so the name is stored in `@CO` comment lines.
+//!   Each line contains the tag `pggname` followed by GFA header fields, as written by [`GraphName::to_gfa_header_lines`], with tabs between the fields:
+//!
+//! ```text
+//! @CO    pggname    NM:Z:NAME
+//! @CO    pggname    MV:Z:1
+//! @CO    pggname    SG:Z:A,B
+//! @CO    pggname    TL:Z:A,B
+//! ```
+//!
+//! Other `@CO` lines are not used.
+
+use crate::GraphName;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Tag that starts the `@CO` comment lines storing graph name data.
+pub const COMMENT_TAG: &str = "pggname";
+
+// Start of the comment lines, before the fields.
+const COMMENT_PREFIX: &str = "@CO\tpggname\t";
+
+/// Returns `true` if the line is a `@CO` comment line storing graph name data.
+pub fn is_comment_line(line: &str) -> bool {
+    line.starts_with(COMMENT_PREFIX)
+}
+
+/// Returns `@CO` comment lines representing the given graph name and relationships.
+///
+/// The lines do not end with a newline.
+pub fn to_comment_lines(name: &GraphName) -> Vec<String> {
+    name.to_gfa_header_lines().iter()
+        .map(|line| format!("{}{}", COMMENT_PREFIX, line.split_once('\t').map_or("", |(_, fields)| fields)))
+        .collect()
+}
+
+/// Parses graph name data from the `@CO` comment lines of a CRAM, SAM, or BAM header.
+///
+/// Other lines are ignored.
+/// The lines must not end with a newline.
+/// Returns an error if the graph name data cannot be parsed.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::{GraphName, hts};
+///
+/// let mut name = GraphName::new(String::from("C"));
+/// name.add_subgraph("C", "B");
+/// let comments = hts::to_comment_lines(&name);
+/// assert_eq!(comments, vec!["@CO\tpggname\tNM:Z:C", "@CO\tpggname\tMV:Z:1", "@CO\tpggname\tSG:Z:C,B"]);
+///
+/// let header = vec![String::from("@HD\tVN:1.6"), String::from("@CO\tcalled with vg")];
+/// let annotated = hts::annotate_sam_header_lines(&header, &name);
+/// assert_eq!(hts::from_comment_lines(&annotated), Ok(name));
+/// ```
+pub fn from_comment_lines(lines: &[String]) -> Result<GraphName, String> {
+    let fields: Vec<String> = lines.iter()
+        .filter_map(|line| line.strip_prefix(COMMENT_PREFIX))
+        .map(|fields| format!("H\t{}", fields))
+        .collect();
+    GraphName::from_header_lines(&fields)
+}
+
+/// Returns the CRAM, SAM, or BAM header lines annotated with the given graph name and relationships.
+///
+/// Existing `pggname` comment lines are removed, and the new lines are appended at the end.
+/// The lines must not end with a newline.
+pub fn annotate_sam_header_lines(lines: &[String], name: &GraphName) -> Vec<String> {
+    let mut result: Vec<String> = lines.iter()
+        .filter(|line| !is_comment_line(line))
+        .cloned()
+        .collect();
+    result.extend(to_comment_lines(name));
+    result
+}
+
+//-----------------------------------------------------------------------------
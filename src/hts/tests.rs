@@ -0,0 +1,48 @@
+use super::*;
+
+use crate::vcf;
+
+//-----------------------------------------------------------------------------
+
+fn lines(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| String::from(*value)).collect()
+}
+
+fn graph_name() -> GraphName {
+    let mut name = GraphName::new(String::from("C"));
+    name.add_subgraph("C", "B");
+    name.add_translation("B", "A");
+    name
+}
+
+#[test]
+fn cram_header() {
+    let name = graph_name();
+    let header = lines(&[
+        "@HD\tVN:1.6\tSO:coordinate", "@SQ\tSN:chr1\tLN:1000", "@CO\tpggname\tNM:Z:X", "@CO\tpggname\tSG:Z:X,Y", "@CO\tcomment",
+    ]);
+    let expected = lines(&[
+        "@HD\tVN:1.6\tSO:coordinate", "@SQ\tSN:chr1\tLN:1000", "@CO\tcomment",
+        "@CO\tpggname\tNM:Z:C", "@CO\tpggname\tMV:Z:1", "@CO\tpggname\tSG:Z:C,B", "@CO\tpggname\tTL:Z:B,A",
+    ]);
+    assert_eq!(from_comment_lines(&header).unwrap().name(), Some(&String::from("X")), "Wrong name from the original header");
+    let annotated = annotate_sam_header_lines(&header, &name);
+    assert_eq!(annotated, expected, "Wrong annotated header");
+    assert_eq!(from_comment_lines(&annotated), Ok(name), "Wrong GraphName from the annotated header");
+
+    // Headers without graph name data.
+    assert_eq!(from_comment_lines(&lines(&["@HD\tVN:1.6", "@CO\tpggname is great"])), Ok(GraphName::default()), "Found graph name data in plain comments");
+    assert!(from_comment_lines(&lines(&["@CO\tpggname\tMV:Z:1\tSG:Z:A"])).is_err(), "Accepted a malformed relationship");
+    assert!(!is_comment_line("@CO\tpggname"), "Accepted a comment line without fields");
+}
+
+#[test]
+fn bcf_header() {
+    // BCF headers use the VCF meta-information lines.
+    let name = graph_name();
+    let header = lines(&["##fileformat=VCFv4.2", "##FILTER=<ID=PASS,Description=\"All filters passed\">", "#CHROM\tPOS\tID"]);
+    let annotated = vcf::annotate_header_lines(&header, &name);
+    assert_eq!(GraphName::from_vcf_header_lines(&annotated), Ok(name), "Wrong GraphName from the annotated BCF header");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,279 @@
+//! Handling of GFA 1.2 jump lines.
+//!
+//! A J-line connects two oriented segments with an optional distance, for example to scaffold over a gap of unknown sequence.
+//! By default, jumps do not affect the name.
+//! Like paths and walks, they are skipped when building the graph, and [`ParseReport::skipped_records`](crate::algorithms::ParseReport::skipped_records) counts them under `J`.
+//! This is appropriate when jumps are annotations, but graphs that differ only in their jumps then have the same name.
+//!
+//! For graphs where jumps are semantically meaningful, [`JumpPolicy::Include`] selects the jump-extended canonical format, version [`JUMP_FORMAT_VERSION`](crate::vectors::JUMP_FORMAT_VERSION).
+//! The jumps can be collected while parsing the graph with [`ParseOptions::jumps`], which stores them in [`ParseReport::jumps`](crate::algorithms::ParseReport::jumps), or read separately with [`Jumps::from_gfa`].
+//! The canonical representation is the canonical GFA representation of the graph followed by the canonical jump lines:
+//!
+//! * Each jump becomes `J`, source segment, source orientation, destination segment, destination orientation, and distance (`*` if unknown), separated by tabs.
+//! * A jump and its reverse (from the destination in the other orientation to the source in the other orientation) are the same jump.
+//!   The lexicographically smaller line is used.
+//! * Optional fields, including the `SC:i` tag for shortcuts, are ignored.
+//! * The lines are sorted in lexicographic order, duplicates are removed, and each line is terminated by a newline.
+//!
+//! The name is the SHA-256 hash of the representation.
+//! A graph without jumps has the same name in both formats, so only the names of graphs with jumps change.
+//! Segment names in J-lines must be the same as in the canonical GFA representation.
+//! With integer identifiers, this means names in canonical decimal form.
+//!
+//! # Examples
+//!
+//! ```
+//! use pggname::algorithms::{self, NamePolicy};
+//! use pggname::graph::GraphInt;
+//! use pggname::jumps::Jumps;
+//!
+//! let gfa = b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\nJ\t2\t-\t1\t-\t100\n";
+//! let graph = algorithms::parse_gfa_bytes::<GraphInt>(gfa).unwrap();
+//! let jumps = Jumps::from_gfa(&gfa[..], NamePolicy::Allow).unwrap();
+//! assert_eq!(jumps.lines().collect::<Vec<_>>(), vec![&b"J\t1\t+\t2\t+\t100"[..]]);
+//!
+//! let name = pggname::jumps::stable_name(&graph, &jumps).unwrap();
+//! assert_ne!(name, pggname::stable_name(&graph));
+//! assert_eq!(pggname::jumps::stable_name(&graph, &Jumps::new()).unwrap(), pggname::stable_name(&graph));
+//! ```
+
+use crate::{Graph, Orientation};
+use crate::algorithms::{self, BufferedHasher, NamePolicy, NodeIds, ParseOptions, split_bytes};
+use crate::graph::{GraphInt, GraphStr};
+
+#[cfg(feature = "gbz")]
+use gbz::GBZ;
+
+use sha2::{Digest, Sha256};
+use sha2::digest;
+
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Policy for GFA 1.2 jump lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JumpPolicy {
+    /// Jumps do not affect the name.
+    #[default]
+    Ignore,
+    /// Include jumps in the name using the jump-extended canonical format.
+    Include,
+}
+
+impl FromStr for JumpPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "ignore" => Ok(JumpPolicy::Ignore),
+            "include" => Ok(JumpPolicy::Include),
+            _ => Err(format!("Invalid jump policy: {}", value)),
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// A set of jumps in canonical form.
+///
+/// See the [module documentation](self) for details.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Jumps {
+    // Canonical jump lines without the newline.
+    lines: BTreeSet<Vec<u8>>,
+}
+
+impl Jumps {
+    /// Creates an empty set of jumps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the J-lines in the given GFA input.
+    ///
+    /// Segment names are handled according to the policy.
+    /// Lines of other types are ignored.
+    /// Returns an error if a J-line cannot be parsed.
+    pub fn from_gfa<R: BufRead>(reader: R, names: NamePolicy) -> Result<Self, String> {
+        let mut result = Jumps::new();
+        for (i, line) in reader.split(b'\n').enumerate() {
+            let line = line.map_err(|e| format!("Error reading GFA line {}: {}", i + 1, e))?;
+            if line.first() == Some(&b'J') {
+                result.add_line(&line, names).map_err(|e| format!("Invalid J-line {}: {}", i + 1, e))?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Adds a jump from the given J-line (without the newline).
+    ///
+    /// Segment names are handled according to the policy.
+    /// Returns the reason if the line cannot be parsed.
+    pub fn add_line(&mut self, line: &[u8], names: NamePolicy) -> Result<(), String> {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut fields = split_bytes(line, b'\t').skip(1);
+        let (Some(from), Some(from_o), Some(to), Some(to_o), Some(distance)) =
+            (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) else {
+            return Err(String::from("not enough fields for a jump"));
+        };
+        let distance = if distance == b"*" {
+            None
+        } else {
+            let value = str::from_utf8(distance).ok().and_then(|value| value.parse::<i64>().ok());
+            Some(value.ok_or_else(|| format!("invalid distance {}", String::from_utf8_lossy(distance)))?)
+        };
+        self.add(
+            &names.apply(from)?, algorithms::parse_orientation(from_o)?,
+            &names.apply(to)?, algorithms::parse_orientation(to_o)?,
+            distance
+        )
+    }
+
+    /// Adds a jump between the given oriented segments with an optional distance.
+    ///
+    /// Returns an error if a segment name is empty or contains a tab or a newline.
+    pub fn add(&mut self, from: &[u8], from_o: Orientation, to: &[u8], to_o: Orientation, distance: Option<i64>) -> Result<(), String> {
+        for name in [from, to] {
+            if name.is_empty() || algorithms::find_delimiter(name).is_some() {
+                return Err(format!("invalid segment name {}", String::from_utf8_lossy(name).escape_debug()));
+            }
+        }
+        let forward = jump_line(from, from_o, to, to_o, distance);
+        let reverse = jump_line(to, to_o.flip(), from, from_o.flip(), distance);
+        self.lines.insert(forward.min(reverse));
+        Ok(())
+    }
+
+    /// Returns the number of distinct jumps.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns `true` if there are no jumps.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Returns an iterator over the canonical jump lines (without newlines) in sorted order.
+    pub fn lines(&self) -> impl Iterator<Item = &[u8]> {
+        self.lines.iter().map(|line| line.as_slice())
+    }
+
+    // Returns the names of the segments referenced by the jumps.
+    fn segments(&self) -> BTreeSet<&[u8]> {
+        let mut result = BTreeSet::new();
+        for line in self.lines.iter() {
+            let fields: Vec<&[u8]> = split_bytes(line, b'\t').collect();
+            result.insert(fields[1]);
+            result.insert(fields[3]);
+        }
+        result
+    }
+}
+
+// Returns the J-line for the jump without the newline.
+fn jump_line(from: &[u8], from_o: Orientation, to: &[u8], to_o: Orientation, distance: Option<i64>) -> Vec<u8> {
+    let mut line = Vec::with_capacity(from.len() + to.len() + 16);
+    line.extend_from_slice(b"J\t");
+    line.extend_from_slice(from);
+    line.extend_from_slice(format!("\t{}\t", algorithms::orientation_symbol(from_o)).as_bytes());
+    line.extend_from_slice(to);
+    line.extend_from_slice(format!("\t{}\t", algorithms::orientation_symbol(to_o)).as_bytes());
+    match distance {
+        Some(distance) => line.extend_from_slice(distance.to_string().as_bytes()),
+        None => line.push(b'*'),
+    }
+    line
+}
+
+//-----------------------------------------------------------------------------
+
+/// Writes the jump-extended canonical representation of the graph.
+///
+/// Returns an error if a jump refers to a segment that is not in the graph, or if writing fails.
+pub fn write_canonical<G: Graph, W: Write>(graph: &G, jumps: &Jumps, mut writer: W) -> Result<(), String> {
+    let error = |e: std::io::Error| format!("Error writing the canonical GFA: {}", e);
+    for_each_chunk(graph, jumps, |bytes| writer.write_all(bytes).map_err(error))?;
+    writer.flush().map_err(error)
+}
+
+/// Computes the given hash of the jump-extended canonical representation of the graph.
+///
+/// Returns an error if a jump refers to a segment that is not in the graph.
+pub fn hash<D: Digest, G: Graph>(graph: &G, jumps: &Jumps) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let mut hasher = BufferedHasher::<D>::new();
+    for_each_chunk(graph, jumps, |bytes| {
+        hasher.update(bytes);
+        Ok(())
+    })?;
+    Ok(hasher.finalize())
+}
+
+/// Computes the stable name of the graph with the given jumps.
+///
+/// This is [`hash`] with SHA-256.
+/// Without jumps, the result is the same as [`crate::stable_name`].
+/// Returns an error if a jump refers to a segment that is not in the graph.
+pub fn stable_name<G: Graph>(graph: &G, jumps: &Jumps) -> Result<String, String> {
+    hash::<Sha256, G>(graph, jumps)
+}
+
+/// Computes the given hash of the graph stored in the given file, including the jumps in it.
+///
+/// GFA files are parsed as in [`algorithms::hash_file`], and the J-lines are collected while parsing with [`JumpPolicy::Include`].
+/// The result is the same as with [`algorithms::hash_file`] if there are no jumps.
+/// GBZ graphs do not have jumps.
+/// Returns an error if the file cannot be read or parsed, or if a jump refers to a segment that is not in the graph.
+pub fn hash_file<D: Digest, P: AsRef<Path>>(filename: P, node_ids: NodeIds) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let filename = filename.as_ref();
+    #[cfg(feature = "gbz")]
+    if GBZ::is_gbz(filename) {
+        return algorithms::hash_file::<D, _>(filename, node_ids);
+    }
+
+    let options = ParseOptions { jumps: JumpPolicy::Include, ..Default::default() };
+    let integer = match node_ids {
+        NodeIds::Integer => Some(algorithms::parse_gfa_with_options::<GraphInt, _>(algorithms::open_gfa(filename)?, &options)?),
+        NodeIds::String => None,
+        NodeIds::Auto => algorithms::parse_gfa_with_options::<GraphInt, _>(algorithms::open_gfa(filename)?, &options).ok(),
+    };
+    let result = match integer {
+        Some((graph, report)) => hash::<D, _>(&graph, &report.jumps),
+        None => {
+            let (graph, report) = algorithms::parse_gfa_with_options::<GraphStr, _>(algorithms::open_gfa(filename)?, &options)?;
+            hash::<D, _>(&graph, &report.jumps)
+        }
+    };
+    result.map_err(|e| format!("{}: {}", filename.display(), e))
+}
+
+// Calls the function with the serialized nodes and then with the canonical jump lines.
+// Checks that all segments referenced by the jumps are in the graph.
+fn for_each_chunk<G: Graph, F: FnMut(&[u8]) -> Result<(), String>>(graph: &G, jumps: &Jumps, mut f: F) -> Result<(), String> {
+    let mut missing = jumps.segments();
+    graph.try_for_each_node(|bytes| {
+        if !missing.is_empty() && let Some(name) = split_bytes(bytes, b'\t').nth(1) {
+            missing.remove(name);
+        }
+        f(bytes)
+    })?;
+    if let Some(name) = missing.first() {
+        return Err(format!("A jump refers to segment {}, which is not in the graph", String::from_utf8_lossy(name)));
+    }
+    for line in jumps.lines() {
+        f(line)?;
+        f(b"\n")?;
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,108 @@
+use super::*;
+
+use crate::graph::{GraphInt, GraphStr};
+use crate::testing::TempFile;
+
+//-----------------------------------------------------------------------------
+
+const GFA: &[u8] = b"S\t1\tACGT\nS\t2\tGA\nS\t3\tT\nL\t1\t+\t2\t+\t*\nJ\t1\t+\t3\t-\t50\tSC:i:1\n";
+
+fn jumps(gfa: &[u8]) -> Jumps {
+    Jumps::from_gfa(gfa, NamePolicy::Allow).unwrap()
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn canonical_jumps() {
+    // The same jump in both orientations, with a different distance, and with an unknown distance.
+    let gfa = b"J\t3\t+\t1\t-\t50\nJ\t1\t+\t3\t-\t50\nJ\t1\t+\t3\t-\t60\nJ\t2\t-\t1\t+\t*\nS\t1\tA\n";
+    let jumps = jumps(gfa);
+    assert_eq!(jumps.len(), 3, "Wrong number of distinct jumps");
+    let expected: Vec<&[u8]> = vec![b"J\t1\t+\t3\t-\t50", b"J\t1\t+\t3\t-\t60", b"J\t1\t-\t2\t+\t*"];
+    assert_eq!(jumps.lines().collect::<Vec<_>>(), expected, "Wrong canonical jump lines");
+
+    let escaped = Jumps::from_gfa(&b"J\ta%b\t+\tc\t+\t*\n"[..], NamePolicy::Escape).unwrap();
+    assert_eq!(escaped.lines().collect::<Vec<_>>(), vec![&b"J\ta%25b\t+\tc\t+\t*"[..]], "Segment names were not escaped");
+
+    for line in [&b"J\t1\t+\t3\t-"[..], b"J\t1\t+\t3\t?\t*", b"J\t1\t+\t3\t-\t1.5", b"J\t\t+\t3\t-\t*"] {
+        let mut jumps = Jumps::new();
+        assert!(jumps.add_line(line, NamePolicy::Allow).is_err(), "Accepted J-line {}", String::from_utf8_lossy(line));
+    }
+}
+
+#[test]
+fn names_with_jumps() {
+    let graph = algorithms::parse_gfa_bytes::<GraphInt>(GFA).unwrap();
+    let plain = crate::stable_name(&graph);
+    assert_eq!(stable_name(&graph, &Jumps::new()), Ok(plain.clone()), "Jumps changed the name without jumps");
+
+    let with_jumps = stable_name(&graph, &jumps(GFA)).unwrap();
+    assert_ne!(with_jumps, plain, "Jumps did not change the name");
+    let reversed = b"S\t1\tACGT\nS\t2\tGA\nS\t3\tT\nL\t1\t+\t2\t+\t*\nJ\t3\t+\t1\t-\t50\n";
+    assert_eq!(stable_name(&graph, &jumps(reversed)), Ok(with_jumps.clone()), "Reversed jump changed the name");
+    let other = b"J\t1\t+\t3\t-\t51\n";
+    assert_ne!(stable_name(&graph, &jumps(other)), Ok(with_jumps.clone()), "The distance did not change the name");
+
+    // The canonical representation is consistent with the name.
+    let mut canonical: Vec<u8> = Vec::new();
+    write_canonical(&graph, &jumps(GFA), &mut canonical).unwrap();
+    assert!(canonical.ends_with(b"S\t3\tT\nJ\t1\t+\t3\t-\t50\n"), "Jump lines were not appended to the canonical GFA");
+    assert_eq!(format!("{:x}", Sha256::digest(&canonical)), with_jumps, "Wrong hash of the canonical representation");
+
+    // String identifiers give the same result here.
+    let graph = algorithms::parse_gfa_bytes::<GraphStr>(GFA).unwrap();
+    assert_eq!(stable_name(&graph, &jumps(GFA)), Ok(with_jumps), "Wrong name with string identifiers");
+}
+
+#[test]
+fn missing_segments() {
+    let graph = algorithms::parse_gfa_bytes::<GraphInt>(GFA).unwrap();
+    assert!(stable_name(&graph, &jumps(b"J\t1\t+\t4\t+\t*\n")).is_err(), "Accepted a jump to a missing segment");
+    // Integer identifiers are written in canonical decimal form.
+    assert!(stable_name(&graph, &jumps(b"J\t01\t+\t3\t+\t*\n")).is_err(), "Accepted a non-canonical integer identifier");
+}
+
+#[test]
+fn file_names() {
+    let filename = TempFile::new(".gfa");
+    std::fs::write(&filename, GFA).unwrap();
+    let graph = algorithms::parse_gfa_bytes::<GraphInt>(GFA).unwrap();
+    let expected = stable_name(&graph, &jumps(GFA)).unwrap();
+    for node_ids in [NodeIds::Auto, NodeIds::Integer, NodeIds::String] {
+        assert_eq!(hash_file::<Sha256, _>(&filename, node_ids), Ok(expected.clone()), "Wrong name from a file with {:?}", node_ids);
+    }
+    std::fs::write(&filename, b"S\t1\tACGT\n").unwrap();
+    let plain = algorithms::hash_file::<Sha256, _>(&filename, NodeIds::Auto).unwrap();
+    assert_eq!(hash_file::<Sha256, _>(&filename, NodeIds::Auto), Ok(plain), "Wrong name from a file without jumps");
+}
+
+#[test]
+fn policy() {
+    assert_eq!("ignore".parse::<JumpPolicy>(), Ok(JumpPolicy::Ignore), "Wrong policy");
+    assert_eq!("include".parse::<JumpPolicy>(), Ok(JumpPolicy::Include), "Wrong policy");
+    assert!("keep".parse::<JumpPolicy>().is_err(), "Accepted an invalid policy");
+    assert_eq!(JumpPolicy::default(), JumpPolicy::Ignore, "Jumps are not ignored by default");
+
+    // Ignored jumps are counted in the parse report.
+    let (_, report) = algorithms::parse_gfa_bytes_with_options::<GraphInt>(GFA, &Default::default()).unwrap();
+    assert_eq!(report.skipped_records.get(&b'J'), Some(&1), "J-lines were not counted");
+    assert!(report.jumps.is_empty(), "Collected ignored jumps");
+
+    // Included jumps are collected while parsing.
+    let options = ParseOptions { jumps: JumpPolicy::Include, ..Default::default() };
+    let (_, report) = algorithms::parse_gfa_with_options::<GraphInt, _>(GFA, &options).unwrap();
+    assert_eq!(report.jumps, jumps(GFA), "Wrong jumps collected while parsing");
+    assert_eq!(report.skipped_records.get(&b'J'), Some(&1), "Included J-lines were not counted");
+
+    // Malformed J-lines are errors, unless the parser is lenient.
+    let gfa = b"S\t1\tA\nJ\t1\t+\t1\t?\t*\nJ\t1\t+\t1\t+\t*\r\n";
+    let result = algorithms::parse_gfa_bytes_with_options::<GraphInt>(gfa, &options);
+    assert!(result.as_ref().is_err_and(|e| e.starts_with("Error parsing GFA line 2:")), "Accepted a malformed J-line: {:?}", result);
+    let lenient = ParseOptions { lenient: true, ..options };
+    let (_, report) = algorithms::parse_gfa_bytes_with_options::<GraphInt>(gfa, &lenient).unwrap();
+    assert_eq!(report.errors.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![2], "Wrong malformed lines");
+    assert_eq!(report.jumps.lines().collect::<Vec<_>>(), vec![&b"J\t1\t+\t1\t+\t*"[..]], "Wrong jumps with a malformed line");
+}
+
+//-----------------------------------------------------------------------------
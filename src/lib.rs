@@ -7,11 +7,63 @@
 //!
 //! The purpose of pggname is to identify only the graph itself.
 //! Hence the canonical GFA representation does not include other information, such as headers, haplotype paths, or metadata.
+//!
+//! # Features
+//!
+//! * `cli` (default): the command line tool, which also enables `gbz`.
+//! * `gbz`: GBZ graphs, GBZ tags, the C API, and the name stability audit in the `audit` module, using the `gbz` and `simple-sds` crates.
+//! * `parallel` (default): multithreaded graph finalization using Rayon.
+//! * `remote`: remote GFA inputs (`http://`, `https://`, `s3://`) in [`algorithms::open_gfa`]; see the `remote` module.
+//! * `petgraph`: building graphs from `petgraph` graphs; see the `petgraph_adapter` module.
+//! * `bundle`: naming the graph in index bundles (directories and tarballs); see the `bundle` module.
+//! * `fifo`: the `view` subcommand, which serves the canonical GFA representation through a named pipe; see the `view` module.
+//!
+//! Tools that only need GFA hashing and [`GraphName`] can use the crate with `default-features = false`.
+//! Unit tests that use GBZ graphs or the test data from the `gbz` crate are only compiled with the `gbz` feature.
+//! Some doctests read the same test data, so the full test suite requires the default features.
 
 pub mod algorithms;
+#[cfg(feature = "gbz")]
+pub mod audit;
+#[cfg(feature = "bundle")]
+pub mod bundle;
+#[cfg(feature = "arrow")]
+pub mod export;
+pub mod extract;
+#[cfg(feature = "gbz")]
+pub mod ffi;
+pub mod gaf;
+#[cfg(feature = "gfa-rs")]
+pub mod gfa_rs;
 pub mod graph;
+pub mod hts;
+pub mod jumps;
+pub mod merge;
 pub mod name;
+pub mod packed;
+pub mod paths;
+#[cfg(feature = "petgraph")]
+pub mod petgraph_adapter;
+pub mod provenance;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod record;
+pub mod seqcol;
+pub mod sketch;
+pub mod sorted;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod translation;
+pub mod vcf;
+pub mod vectors;
+pub mod view;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-pub use algorithms::stable_name;
-pub use graph::Graph;
-pub use name::GraphName;
+pub use algorithms::{
+    edge_set_digest, node_table_digest, stable_name, stable_name_from_file, stable_name_from_file_with_report, stable_name_with_report,
+};
+pub use graph::{Graph, Orientation};
+pub use name::{GraphName, GraphNameBuilder};
@@ -0,0 +1,124 @@
+//! Merging graphs with stored names into a single graph.
+//!
+//! A pangenome graph is often built and distributed as separate per-chromosome graphs.
+//! [`GraphMerger`] concatenates such graphs into one graph, computes the combined name, and records each part as a subgraph of the combined graph.
+//! The parts must have disjoint node identifiers, as graphs built per chromosome usually do.
+//! They must also interpret node identifiers in the same way as the combined graph (see [`Graph::NODE_IDS`]), as the names of the parts would otherwise refer to a different canonical representation.
+//! Relationships stored with the parts are carried over to the combined graph.
+//!
+//! # Examples
+//!
+//! ```
+//! use pggname::{GraphName, algorithms};
+//! use pggname::graph::GraphInt;
+//! use pggname::merge::GraphMerger;
+//!
+//! let chr1: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n").unwrap();
+//! let chr2: GraphInt = algorithms::parse_gfa_bytes(b"S\t3\tTTA\n").unwrap();
+//!
+//! let mut merger = GraphMerger::<GraphInt>::new();
+//! let chr1_name = merger.add(&chr1, &GraphName::default(), "chr1.gfa").unwrap();
+//! let chr2_name = merger.add(&chr2, &GraphName::default(), "chr2.gfa").unwrap();
+//! let (graph, name) = merger.finish().unwrap();
+//!
+//! let combined = pggname::stable_name(&graph);
+//! assert_eq!(name.name(), Some(&combined));
+//! assert!(name.is_subgraph_of(&GraphName::new(combined.clone())));
+//! assert!(name.subgraph_iter().any(|(sub, sup)| sub == chr1_name && sup == combined));
+//! assert!(name.subgraph_iter().any(|(sub, sup)| sub == chr2_name && sup == combined));
+//! ```
+
+use crate::{Graph, GraphName};
+use crate::algorithms::GfaStream;
+
+use tracing::warn;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Concatenates graphs into a single graph and records the parts as subgraphs of the result.
+///
+/// The combined graph uses node identifiers of type `G`.
+/// See the [module documentation](self) for an example.
+pub struct GraphMerger<G: Graph> {
+    stream: GfaStream<G>,
+    // Sum of the node counts of the parts.
+    nodes: u64,
+    // Names of the parts in the order they were added.
+    parts: Vec<String>,
+    // Relationships stored with the parts.
+    relationships: GraphName,
+}
+
+impl<G: Graph> GraphMerger<G> {
+    /// Creates a merger with no parts.
+    pub fn new() -> Self {
+        GraphMerger { stream: GfaStream::new(), nodes: 0, parts: Vec::new(), relationships: GraphName::default() }
+    }
+
+    /// Adds a part to the combined graph and returns its name.
+    ///
+    /// `metadata` is the name and the relationships stored with the part, such as [`GraphName::from_gbz`].
+    /// The relationships are carried over to the combined graph, and conflicts with the relationships from earlier parts are logged as warnings.
+    /// If the stored name differs from the computed name, the stored name is stale and a warning is logged with the given description.
+    /// Returns an error if the part interprets node identifiers differently from the combined graph.
+    /// Returns an error if the part cannot be added to the combined graph.
+    pub fn add<H: Graph>(&mut self, part: &H, metadata: &GraphName, description: &str) -> Result<String, String> {
+        if H::NODE_IDS != G::NODE_IDS {
+            return Err(format!(
+                "Cannot add {}: node identifiers are {:?} in the part but {:?} in the combined graph",
+                description, H::NODE_IDS, G::NODE_IDS
+            ));
+        }
+        let name = crate::stable_name(part);
+        if let Some(stored) = metadata.name() && *stored != name {
+            warn!("{}: stale graph name {}, computed {}", description, stored, name);
+        }
+        part.try_for_each_node(|bytes| self.stream.push(bytes)).map_err(|e| format!("Error adding {}: {}", description, e))?;
+        self.nodes += part.statistics().nodes;
+        for conflict in self.relationships.add_relationships_checked(metadata) {
+            warn!("{}: conflicting relationships: {}", description, conflict);
+        }
+        self.parts.push(name.clone());
+        Ok(name)
+    }
+
+    /// Returns the names of the parts in the order they were added.
+    pub fn parts(&self) -> &[String] {
+        &self.parts
+    }
+
+    /// Finalizes the combined graph and returns it with its name and relationships.
+    ///
+    /// Each part is a subgraph of the combined graph, unless it is the entire graph.
+    /// Returns an error if the graph cannot be finalized or if the parts share nodes.
+    pub fn finish(self) -> Result<(G, GraphName), String> {
+        let graph = self.stream.finish()?;
+        let nodes = graph.statistics().nodes;
+        if nodes != self.nodes {
+            return Err(format!(
+                "The parts share {} nodes; merging requires disjoint node identifiers", self.nodes - nodes
+            ));
+        }
+
+        let combined = crate::stable_name(&graph);
+        let mut name = GraphName::new(combined.clone());
+        name.add_relationships(&self.relationships);
+        for part in self.parts.iter() {
+            if *part != combined {
+                name.add_subgraph(part, &combined);
+            }
+        }
+        Ok((graph, name))
+    }
+}
+
+impl<G: Graph> Default for GraphMerger<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,103 @@
+use super::*;
+
+use crate::algorithms;
+use crate::graph::GraphInt;
+#[cfg(feature = "gbz")]
+use crate::graph::{GBZInt, GBZStr, GraphStr};
+
+#[cfg(feature = "gbz")]
+use gbz::{GBZ, support};
+#[cfg(feature = "gbz")]
+use simple_sds::serialize;
+
+//-----------------------------------------------------------------------------
+
+#[cfg(feature = "gbz")]
+// Splits the GFA into two parts by node identifier, keeping the links within each part.
+fn split_gfa(gfa: &[u8], first: impl Fn(&[u8]) -> bool) -> (Vec<u8>, Vec<u8>) {
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    for line in gfa.split(|&c| c == b'\n') {
+        let fields: Vec<&[u8]> = line.split(|&c| c == b'\t').collect();
+        let target = match fields[0] {
+            b"S" if first(fields[1]) => &mut a,
+            b"S" => &mut b,
+            b"L" if first(fields[1]) && first(fields[3]) => &mut a,
+            b"L" if !first(fields[1]) && !first(fields[3]) => &mut b,
+            _ => continue,
+        };
+        target.extend_from_slice(line);
+        target.push(b'\n');
+    }
+    (a, b)
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn merge_parts() {
+    let gfa = std::fs::read(support::get_test_data("example.gfa")).unwrap();
+    let (a, b) = split_gfa(&gfa, |name| GraphInt::parse_id(name).unwrap() < 18);
+    let a: GraphInt = algorithms::parse_gfa_bytes(&a).unwrap();
+    let b: GraphInt = algorithms::parse_gfa_bytes(&b).unwrap();
+
+    let mut stored = GraphName::new(String::from("stale"));
+    stored.add_translation("X", "Y");
+    let mut merger = GraphMerger::<GraphInt>::new();
+    let a_name = merger.add(&a, &stored, "a").unwrap();
+    let b_name = merger.add(&b, &GraphName::default(), "b").unwrap();
+    assert_eq!(merger.parts(), &[a_name.clone(), b_name.clone()], "Wrong parts");
+    let (graph, name) = merger.finish().unwrap();
+
+    // Links between the parts are lost, so the combined graph is a subgraph of the original.
+    let combined = crate::stable_name(&graph);
+    assert_eq!(name.name(), Some(&combined), "Wrong combined name");
+    let expected_nodes = a.statistics().nodes + b.statistics().nodes;
+    assert_eq!(graph.statistics().nodes, expected_nodes, "Wrong number of nodes");
+    let subgraphs: Vec<(&str, &str)> = name.subgraph_iter().collect();
+    let mut expected = vec![(a_name.as_str(), combined.as_str()), (b_name.as_str(), combined.as_str())];
+    expected.sort();
+    assert_eq!(subgraphs, expected, "Wrong subgraph relationships");
+    assert!(name.translation_iter().eq([("X", "Y")]), "Stored relationships were not carried over");
+
+    // The same graph with string identifiers.
+    let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+    let mut merger = GraphMerger::<GraphStr>::new();
+    let part = merger.add(&GBZStr { graph: gbz }, &GraphName::default(), "gbz").unwrap();
+    let (graph, name) = merger.finish().unwrap();
+    assert_eq!(crate::stable_name(&graph), part, "A single part is not the entire graph");
+    assert_eq!(name.subgraph_iter().count(), 0, "The entire graph was recorded as a subgraph");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn mixed_node_ids() {
+    let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+    let gbz = GBZInt { graph: gbz };
+    let mut merger = GraphMerger::<GraphStr>::new();
+    let result = merger.add(&gbz, &GraphName::default(), "gbz");
+    assert!(result.as_ref().is_err_and(|e| e.starts_with("Cannot add gbz:")), "Integer identifiers were added to a string graph: {:?}", result);
+    assert!(merger.parts().is_empty(), "A rejected part was recorded");
+
+    let a: GraphStr = algorithms::parse_gfa_bytes(b"S\t1\tA\n").unwrap();
+    let mut merger = GraphMerger::<GraphInt>::new();
+    assert!(merger.add(&a, &GraphName::default(), "a").is_err(), "String identifiers were added to an integer graph");
+    assert!(merger.add(&gbz, &GraphName::default(), "gbz").is_ok(), "Integer identifiers were rejected");
+}
+
+#[test]
+fn overlapping_parts() {
+    let a: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tA\nS\t2\tC\nL\t1\t+\t2\t+\t*\n").unwrap();
+    let b: GraphInt = algorithms::parse_gfa_bytes(b"S\t2\tC\nS\t3\tG\n").unwrap();
+    let mut merger = GraphMerger::<GraphInt>::new();
+    merger.add(&a, &GraphName::default(), "a").unwrap();
+    merger.add(&b, &GraphName::default(), "b").unwrap();
+    assert!(merger.finish().is_err(), "Parts sharing a node were merged");
+
+    // A shared node with a different sequence is detected immediately.
+    let c: GraphInt = algorithms::parse_gfa_bytes(b"S\t2\tT\n").unwrap();
+    let mut merger = GraphMerger::<GraphInt>::new();
+    merger.add(&a, &GraphName::default(), "a").unwrap();
+    let result = merger.add(&c, &GraphName::default(), "c");
+    assert!(result.as_ref().is_err_and(|e| e.starts_with("Error adding c:")), "Conflicting sequences were accepted: {:?}", result);
+}
+
+//-----------------------------------------------------------------------------
@@ -11,11 +11,34 @@
 //! We require that graphs A and C are isomorphic (with matching node labels), if we break their nodes into 1 bp pieces.
 //! There is therefore a one-to-one mapping between unary paths in A and C.
 //! We can use this mapping to translate positions in graph A to graph C, and then use these positions in graph B.
+//!
+//! Each relationship may have a [`RelationshipProvenance`] that records whether it was asserted by a tool or verified against the data.
+//! Relationships without provenance are treated as asserted.
+//! Use [`GraphName::subgraph_confidence`] and [`GraphName::translation_confidence`] to decide whether to trust a relationship derived from unverified header claims.
+//!
+//! Provenance can also record that a relationship was checked and found false ([`Confidence::Refuted`]).
+//! This caches the result of an expensive verification, and searches for relationship paths never use refuted relationships.
+//!
+//! # Metadata versions
+//!
+//! Serialized relationship metadata declares the version of the format in which it was written: the [`Tags`] key `pggname_version`, the GFA header field `MV:Z:`, the GAF header line `@MV`, or the VCF meta-information key `pggname_version`.
+//! The version is written whenever there are relationships, and metadata without a version is treated as version 1.
+//! Metadata from a newer version than [`GraphName::METADATA_VERSION`] may contain relationship kinds or value formats this version does not understand.
+//! Such relationships are skipped with a warning instead of failing, so that older tools can still use the rest of the metadata.
+//!
+//! A `GraphName` also has a compact single-string form, such as `name;SG=a,b;TL=c,d`, for command-line arguments and environment variables.
+//! See the [`fmt::Display`] and [`FromStr`] implementations for details.
 
+#[cfg(feature = "gbz")]
 use gbz::GBZ;
+#[cfg(feature = "gbz")]
 use gbz::support::Tags;
 
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
 
 #[cfg(test)]
 mod tests;
@@ -32,13 +55,11 @@ mod tests;
 /// # Examples
 ///
 /// ```
-/// use pggname::GraphName;
+/// use pggname::GraphNameBuilder;
 ///
-/// let parent = GraphName::new(String::from("parent"));
-/// let mut translated = GraphName::new(String::from("translated"));
-/// translated.add_translation_to(&parent);
-/// let mut subgraph = GraphName::new(String::from("subgraph"));
-/// subgraph.make_subgraph_of(&translated);
+/// let parent = GraphNameBuilder::new("parent").build().unwrap();
+/// let translated = GraphNameBuilder::new("translated").translates_to(&parent).build().unwrap();
+/// let subgraph = GraphNameBuilder::new("subgraph").subgraph_of(&translated).build().unwrap();
 ///
 /// assert!(subgraph.is_subgraph_of(&translated));
 /// assert!(!subgraph.is_subgraph_of(&parent));
@@ -50,33 +71,350 @@ pub struct GraphName {
     name: Option<String>,
     subgraph: BTreeMap<String, BTreeSet<String>>,
     translation: BTreeMap<String, BTreeSet<String>>,
+    provenance: BTreeMap<(RelationshipKind, String, String), RelationshipProvenance>,
+}
+
+/// Kind of a relationship between two graphs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RelationshipKind {
+    /// The first graph is a subgraph of the second graph.
+    Subgraph,
+    /// Coordinates in the first graph can be translated to the second graph.
+    Translation,
+}
+
+impl RelationshipKind {
+    /// Returns the GFA/GAF header tag for relationships of this kind.
+    pub fn tag(self) -> &'static str {
+        match self {
+            RelationshipKind::Subgraph => GraphName::GFA_GAF_HEADER_SUBGRAPH,
+            RelationshipKind::Translation => GraphName::GFA_GAF_HEADER_TRANSLATION,
+        }
+    }
+
+    /// Returns the relationship kind for the given GFA/GAF header tag, or [`None`] if the tag is not a relationship tag.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            GraphName::GFA_GAF_HEADER_SUBGRAPH => Some(RelationshipKind::Subgraph),
+            GraphName::GFA_GAF_HEADER_TRANSLATION => Some(RelationshipKind::Translation),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for RelationshipKind {
+    type Err = String;
+
+    /// Parses `subgraph` or `translation`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "subgraph" => Ok(RelationshipKind::Subgraph),
+            "translation" => Ok(RelationshipKind::Translation),
+            _ => Err(format!("Unknown relationship: {}", value)),
+        }
+    }
+}
+
+/// How a relationship between two graphs is known.
+///
+/// Verified and refuted relationships have been checked against the graphs, while asserted relationships have not.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Confidence {
+    /// The relationship was claimed by a tool or copied from a header without checking the graphs.
+    #[default]
+    Asserted,
+    /// The relationship was verified against the graphs.
+    Verified,
+    /// The relationship was checked against the graphs and found to be false.
+    Refuted,
+}
+
+impl Confidence {
+    /// Returns the name of the confidence level used in serialized provenance.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Confidence::Asserted => "asserted",
+            Confidence::Verified => "verified",
+            Confidence::Refuted => "refuted",
+        }
+    }
+
+    /// Returns `true` if the relationship has been checked against the graphs.
+    pub fn is_checked(self) -> bool {
+        self != Confidence::Asserted
+    }
+}
+
+impl FromStr for Confidence {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "asserted" => Ok(Confidence::Asserted),
+            "verified" => Ok(Confidence::Verified),
+            "refuted" => Ok(Confidence::Refuted),
+            _ => Err(format!("Invalid confidence level: {}", value)),
+        }
+    }
+}
+
+/// Whether data for one graph, such as alignments, can be used with another graph.
+///
+/// See [`GraphName::compatibility`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Compatibility {
+    /// The graphs are the same.
+    Same,
+    /// The first graph is a subgraph of the second graph with the given confidence.
+    Subgraph(Confidence),
+    /// Coordinates in the first graph can be translated to the second graph with the given confidence.
+    Translation(Confidence),
+    /// There is no known relationship from the first graph to the second graph.
+    Incompatible,
+}
+
+impl Compatibility {
+    /// Returns `true` if data for the first graph can be used with the second graph.
+    pub fn is_compatible(self) -> bool {
+        self != Compatibility::Incompatible
+    }
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compatibility::Same => write!(f, "same graph"),
+            Compatibility::Subgraph(confidence) => write!(f, "subgraph ({})", confidence.as_str()),
+            Compatibility::Translation(confidence) => write!(f, "translatable ({})", confidence.as_str()),
+            Compatibility::Incompatible => write!(f, "incompatible"),
+        }
+    }
+}
+
+/// Provenance of a relationship between two graphs.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::GraphName;
+/// use pggname::name::{Confidence, RelationshipKind, RelationshipProvenance};
+///
+/// let mut name = GraphName::new(String::from("A"));
+/// let provenance = RelationshipProvenance {
+///     confidence: Confidence::Verified,
+///     tool: Some(String::from("vg-1.60")),
+///     timestamp: Some(1760000000),
+/// };
+/// name.set_provenance(RelationshipKind::Subgraph, "A", "B", provenance.clone()).unwrap();
+/// assert_eq!(name.provenance(RelationshipKind::Subgraph, "A", "B"), Some(&provenance));
+/// assert_eq!(name.subgraph_confidence(&GraphName::new(String::from("B"))), Some(Confidence::Verified));
+///
+/// // A is not a subgraph of C.
+/// let refuted = RelationshipProvenance { confidence: Confidence::Refuted, ..provenance };
+/// name.set_provenance(RelationshipKind::Subgraph, "A", "C", refuted).unwrap();
+/// assert!(!name.is_subgraph_of(&GraphName::new(String::from("C"))));
+/// assert_eq!(name.subgraph_confidence(&GraphName::new(String::from("C"))), Some(Confidence::Refuted));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RelationshipProvenance {
+    /// Whether the relationship was asserted, verified, or refuted.
+    pub confidence: Confidence,
+    /// Tool that asserted or checked the relationship, if known.
+    ///
+    /// The name must not contain `,`, `;`, `=`, tabs, or line breaks.
+    pub tool: Option<String>,
+    /// When the relationship was asserted or checked, as seconds since the Unix epoch, if known.
+    ///
+    /// The timestamp must be at most [`Self::MAX_TIMESTAMP`].
+    pub timestamp: Option<u64>,
+}
+
+impl RelationshipProvenance {
+    /// The largest valid timestamp: 9999-12-31T23:59:59Z.
+    pub const MAX_TIMESTAMP: u64 = 253402300799;
+
+    /// Returns `true` if this provenance should replace the other provenance for the same relationship.
+    ///
+    /// Checked relationships supersede asserted relationships.
+    /// Among checked relationships, a newer result supersedes an older result or a result without a timestamp.
+    pub fn supersedes(&self, other: &RelationshipProvenance) -> bool {
+        match (self.confidence.is_checked(), other.confidence.is_checked()) {
+            (true, false) => true,
+            (true, true) => self.timestamp > other.timestamp,
+            _ => false,
+        }
+    }
+}
+
+/// A conflict found when merging relationships from another source with [`GraphName::add_relationships_checked`].
+///
+/// # Examples
+///
+/// ```
+/// use pggname::GraphName;
+/// use pggname::name::{RelationshipConflict, RelationshipKind};
+///
+/// let mut name = GraphName::new(String::from("A"));
+/// name.add_subgraph("A", "B");
+/// let mut other = GraphName::default();
+/// other.add_subgraph("B", "A");
+/// other.add_translation("C", "C");
+///
+/// let conflicts = name.add_relationships_checked(&other);
+/// assert_eq!(conflicts, vec![
+///     RelationshipConflict::SelfReference { kind: RelationshipKind::Translation, graph: String::from("C") },
+///     RelationshipConflict::MutualSubgraph { first: String::from("A"), second: String::from("B") },
+/// ]);
+/// assert!(name.is_subgraph_of(&GraphName::new(String::from("B"))));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RelationshipConflict {
+    /// A relationship from a graph to itself.
+    SelfReference {
+        /// Kind of the relationship.
+        kind: RelationshipKind,
+        /// Name of the graph.
+        graph: String,
+    },
+    /// Two graphs with different names are subgraphs of each other, which would make them the same graph.
+    MutualSubgraph {
+        /// The smaller name.
+        first: String,
+        /// The larger name.
+        second: String,
+    },
+    /// One source claims the relationship and the other has refuted it.
+    Refuted {
+        /// Kind of the relationship.
+        kind: RelationshipKind,
+        /// The first graph in the relationship.
+        from: String,
+        /// The second graph in the relationship.
+        to: String,
+    },
+}
+
+impl fmt::Display for RelationshipConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = |kind: &RelationshipKind| match kind {
+            RelationshipKind::Subgraph => "is a subgraph of",
+            RelationshipKind::Translation => "translates to",
+        };
+        match self {
+            RelationshipConflict::SelfReference { kind, graph } => write!(f, "graph {} {} itself", graph, description(kind)),
+            RelationshipConflict::MutualSubgraph { first, second } => write!(f, "graphs {} and {} are subgraphs of each other", first, second),
+            RelationshipConflict::Refuted { kind, from, to } => write!(f, "relationship {} {} {} is both claimed and refuted", from, description(kind), to),
+        }
+    }
+}
+
+/// A GFA 1 version that can be declared in the `VN:Z:` header field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GfaVersion {
+    /// GFA 1.0.
+    V1_0,
+    /// GFA 1.1.
+    V1_1,
+    /// GFA 1.2.
+    V1_2,
+}
+
+impl GfaVersion {
+    /// Returns the version as used in the `VN:Z:` header field.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GfaVersion::V1_0 => "1.0",
+            GfaVersion::V1_1 => "1.1",
+            GfaVersion::V1_2 => "1.2",
+        }
+    }
+}
+
+impl FromStr for GfaVersion {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "1.0" => Ok(GfaVersion::V1_0),
+            "1.1" => Ok(GfaVersion::V1_1),
+            "1.2" => Ok(GfaVersion::V1_2),
+            _ => Err(format!("Unsupported GFA version: {}", value)),
+        }
+    }
 }
 
 /// Constants.
+#[cfg_attr(not(feature = "gbz"), allow(dead_code))]
 impl GraphName {
     /// Name of the [`Tags`] key storing the graph name.
-    const TAG_NAME: &'static str = "pggname";
+    pub const TAG_NAME: &'static str = "pggname";
 
     /// Name of the [`Tags`] key storing subgraph relationships.
-    const TAG_SUBGRAPH: &'static str = "subgraph";
+    pub const TAG_SUBGRAPH: &'static str = "subgraph";
 
     /// Name of the [`Tags`] key storing translation relationships.
-    const TAG_TRANSLATION: &'static str = "translation";
+    pub const TAG_TRANSLATION: &'static str = "translation";
 
     /// GFA header tag storing the graph name.
-    const GFA_HEADER_NAME: &'static str = "NM";
+    pub const GFA_HEADER_NAME: &'static str = "NM";
+
+    /// GFA header tag storing the GFA version.
+    pub const GFA_HEADER_VERSION: &'static str = "VN";
 
     /// GAF header tag storing the graph name.
-    const GAF_HEADER_NAME: &'static str = "RN";
+    pub const GAF_HEADER_NAME: &'static str = "RN";
+
+    /// Optional field tag storing the short graph name in GAF records.
+    pub const GAF_RECORD_NAME: &'static str = "rn";
 
     /// GFA/GAF header tag storing subgraph relationships.
-    const GFA_GAF_HEADER_SUBGRAPH: &'static str = "SG";
+    pub const GFA_GAF_HEADER_SUBGRAPH: &'static str = "SG";
 
     /// GFA/GAF header tag storing translation relationships.
-    const GFA_GAF_HEADER_TRANSLATION: &'static str = "TL";
+    pub const GFA_GAF_HEADER_TRANSLATION: &'static str = "TL";
+
+    /// VCF meta-information key storing the graph name.
+    pub const VCF_HEADER_NAME: &'static str = "pggname";
+
+    /// VCF meta-information key storing subgraph relationships.
+    pub const VCF_HEADER_SUBGRAPH: &'static str = "pggname_subgraph";
+
+    /// VCF meta-information key storing translation relationships.
+    pub const VCF_HEADER_TRANSLATION: &'static str = "pggname_translation";
+
+    /// Name of the [`Tags`] key storing relationship provenance.
+    pub const TAG_PROVENANCE: &'static str = "relationship_provenance";
+
+    /// GFA/GAF header tag storing relationship provenance.
+    pub const GFA_GAF_HEADER_PROVENANCE: &'static str = "PV";
+
+    /// VCF meta-information key storing relationship provenance.
+    pub const VCF_HEADER_PROVENANCE: &'static str = "pggname_provenance";
 
+    /// Version of the relationship metadata format written by this version of the library.
+    pub const METADATA_VERSION: usize = 1;
+
+    /// Name of the [`Tags`] key storing the relationship metadata version.
+    pub const TAG_METADATA_VERSION: &'static str = "pggname_version";
+
+    /// GFA/GAF header tag storing the relationship metadata version.
+    pub const GFA_GAF_HEADER_METADATA_VERSION: &'static str = "MV";
+
+    /// VCF meta-information key storing the relationship metadata version.
+    pub const VCF_HEADER_METADATA_VERSION: &'static str = "pggname_version";
+
+    /// URI scheme for graph names.
+    pub const URI_SCHEME: &'static str = "pggname";
+
+    /// Hash algorithm in graph name URIs.
+    pub const URI_ALGORITHM: &'static str = "sha256";
+
+    /// Resolver prefix for [identifiers.org](https://identifiers.org/) style resolvable identifiers.
+    pub const IDENTIFIERS_ORG_RESOLVER: &'static str = "https://identifiers.org/";
+
+    const VCF_HEADER_PREFIX: &'static str = "##";
     const GFA_HEADER_TYPE: &'static str = "H";
-    const GAF_HEADER_PREFIX: &'static str = "@"; 
+    const GAF_HEADER_PREFIX: &'static str = "@";
     const GFA_GAF_FIELD_SEPARATOR: char = '\t';
     const TAG_GFA_RELATIONSHIP_SEPARATOR: char = ',';
     const TAG_RELATIONSHIP_LIST_SEPARATOR: char = ';';
@@ -84,6 +422,393 @@ impl GraphName {
 
 //-----------------------------------------------------------------------------
 
+/// Individual header fields.
+///
+/// These functions build and parse the individual fields used in GFA/GAF header lines, [`Tags`] values, and VCF meta-information lines.
+/// Other tools can use them for writing metadata compatible with `GraphName`.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::GraphName;
+///
+/// let value = GraphName::relationship_value("A", "B");
+/// assert_eq!(value, "A,B");
+/// assert_eq!(GraphName::parse_relationship_value(&value), Some(("A", "B")));
+///
+/// let line = GraphName::gfa_header_line(GraphName::GFA_GAF_HEADER_SUBGRAPH, &value);
+/// assert_eq!(line, "H\tSG:Z:A,B");
+/// assert_eq!(GraphName::parse_gfa_header_field("SG:Z:A,B"), Ok(Some(("SG", "A,B"))));
+///
+/// let line = GraphName::gaf_header_line(GraphName::GFA_GAF_HEADER_SUBGRAPH, &["A", "B"]);
+/// assert_eq!(line, "@SG\tA\tB");
+/// assert!(GraphName::is_gaf_header_line(&line));
+/// ```
+impl GraphName {
+    /// Returns the value representing a relationship from graph `from` to graph `to`.
+    ///
+    /// The same value is used in GFA header fields, [`Tags`] values, and VCF meta-information lines.
+    pub fn relationship_value(from: &str, to: &str) -> String {
+        format!("{}{}{}", from, Self::TAG_GFA_RELATIONSHIP_SEPARATOR, to)
+    }
+
+    /// Parses a relationship value into the names of the graphs.
+    ///
+    /// Returns [`None`] if the value does not consist of two non-empty names.
+    pub fn parse_relationship_value(value: &str) -> Option<(&str, &str)> {
+        let (from, to) = value.split_once(Self::TAG_GFA_RELATIONSHIP_SEPARATOR)?;
+        if from.is_empty() || to.is_empty() || to.contains(Self::TAG_GFA_RELATIONSHIP_SEPARATOR) {
+            return None;
+        }
+        Some((from, to))
+    }
+
+    /// Returns the value representing the provenance of a relationship.
+    ///
+    /// The value is `KIND,FROM,TO,CONFIDENCE,TOOL,TIMESTAMP`, where `KIND` is `SG` or `TL`, and the tool and the timestamp may be empty.
+    /// The same value is used in GFA/GAF header fields, [`Tags`] values, and VCF meta-information lines.
+    pub fn provenance_value(kind: RelationshipKind, from: &str, to: &str, provenance: &RelationshipProvenance) -> String {
+        format!(
+            "{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}",
+            kind.tag(), from, to, provenance.confidence.as_str(),
+            provenance.tool.as_deref().unwrap_or_default(),
+            provenance.timestamp.map(|t| t.to_string()).unwrap_or_default(),
+            sep = Self::TAG_GFA_RELATIONSHIP_SEPARATOR
+        )
+    }
+
+    /// Parses a provenance value into the relationship and its provenance.
+    ///
+    /// Returns an error if the value is malformed.
+    pub fn parse_provenance_value(value: &str) -> Result<(RelationshipKind, &str, &str, RelationshipProvenance), String> {
+        let fields: Vec<&str> = value.split(Self::TAG_GFA_RELATIONSHIP_SEPARATOR).collect();
+        let [kind, from, to, confidence, tool, timestamp] = fields[..] else {
+            return Err(format!("Invalid relationship provenance: {}", value));
+        };
+        let kind = RelationshipKind::from_tag(kind).ok_or(format!("Invalid relationship type in provenance: {}", value))?;
+        if from.is_empty() || to.is_empty() {
+            return Err(format!("Invalid relationship provenance: {}", value));
+        }
+        let provenance = RelationshipProvenance {
+            confidence: confidence.parse()?,
+            tool: (!tool.is_empty()).then(|| String::from(tool)),
+            timestamp: if timestamp.is_empty() {
+                None
+            } else {
+                let timestamp = timestamp.parse::<u64>().ok().filter(|&t| t <= RelationshipProvenance::MAX_TIMESTAMP);
+                Some(timestamp.ok_or(format!("Invalid timestamp in provenance: {}", value))?)
+            },
+        };
+        Ok((kind, from, to, provenance))
+    }
+
+    /// Returns a GFA header field with the given tag and string value.
+    pub fn gfa_header_field(tag: &str, value: &str) -> String {
+        format!("{}:Z:{}", tag, value)
+    }
+
+    /// Returns a GFA header line with a single field with the given tag and string value.
+    ///
+    /// The line does not end with a newline.
+    pub fn gfa_header_line(tag: &str, value: &str) -> String {
+        format!("{}{}{}", Self::GFA_HEADER_TYPE, Self::GFA_GAF_FIELD_SEPARATOR, Self::gfa_header_field(tag, value))
+    }
+
+    /// Returns the GFA header line without the fields used for graph names and relationships.
+    ///
+    /// Returns [`None`] if no other fields remain.
+    /// Lines that are not GFA header lines are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::GraphName;
+    ///
+    /// assert_eq!(GraphName::remove_gfa_header_fields("H\tVN:Z:1.1\tNM:Z:old"), Some(String::from("H\tVN:Z:1.1")));
+    /// assert_eq!(GraphName::remove_gfa_header_fields("H\tNM:Z:old"), None);
+    /// ```
+    pub fn remove_gfa_header_fields(line: &str) -> Option<String> {
+        let mut fields = line.split(Self::GFA_GAF_FIELD_SEPARATOR);
+        if fields.next() != Some(Self::GFA_HEADER_TYPE) {
+            return Some(String::from(line));
+        }
+        let tags = [
+            Self::GFA_HEADER_NAME, Self::GFA_GAF_HEADER_SUBGRAPH, Self::GFA_GAF_HEADER_TRANSLATION,
+            Self::GFA_GAF_HEADER_PROVENANCE, Self::GFA_GAF_HEADER_METADATA_VERSION,
+        ];
+        let remaining: Vec<&str> = fields.filter(|field| {
+            !matches!(Self::parse_gfa_header_field(field), Ok(Some((tag, _))) if tags.contains(&tag))
+        }).collect();
+        if remaining.is_empty() {
+            None
+        } else {
+            Some(format!("{}{}{}", Self::GFA_HEADER_TYPE, Self::GFA_GAF_FIELD_SEPARATOR, remaining.join(&Self::GFA_GAF_FIELD_SEPARATOR.to_string())))
+        }
+    }
+
+    /// Returns the GFA version declared in the `VN:Z:` field of the given header lines, or [`None`] if there is no such field.
+    ///
+    /// Lines that are not GFA header lines are ignored.
+    /// Returns an error if the version is not supported or if the lines declare different versions.
+    pub fn gfa_version(lines: &[String]) -> Result<Option<GfaVersion>, String> {
+        let mut result: Option<GfaVersion> = None;
+        for line in lines {
+            let mut fields = line.split(Self::GFA_GAF_FIELD_SEPARATOR);
+            if fields.next() != Some(Self::GFA_HEADER_TYPE) {
+                continue;
+            }
+            for field in fields {
+                if let Ok(Some((Self::GFA_HEADER_VERSION, value))) = Self::parse_gfa_header_field(field) {
+                    let version: GfaVersion = value.parse()?;
+                    if let Some(previous) = result && previous != version {
+                        return Err(format!("Conflicting GFA versions: {} and {}", previous.as_str(), version.as_str()));
+                    }
+                    result = Some(version);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns the GFA header lines annotated with the given graph name and relationships.
+    ///
+    /// Existing name and relationship fields are removed, and header lines without other fields are dropped.
+    /// The new fields are merged onto the first remaining header line.
+    /// Because a tag may appear only once on a line, additional relationships of the same kind are placed on new header lines after it.
+    /// If there are no header lines, the fields are placed on a new header line at the beginning.
+    ///
+    /// If a target version is given, the header declares it with a `VN:Z:` field.
+    /// Returns an error if the header then declares an unsupported version or a version other than the target version.
+    /// Otherwise the existing `VN:Z:` fields are kept as they are without validation.
+    /// The lines must not end with a newline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::GraphName;
+    /// use pggname::name::GfaVersion;
+    ///
+    /// let header = vec![String::from("H\tVN:Z:1.1\tNM:Z:old")];
+    /// let annotated = GraphName::annotate_gfa_header_lines(&header, &GraphName::new(String::from("new")), None).unwrap();
+    /// assert_eq!(annotated, vec!["H\tVN:Z:1.1\tNM:Z:new"]);
+    ///
+    /// let annotated = GraphName::annotate_gfa_header_lines(&[], &GraphName::new(String::from("new")), Some(GfaVersion::V1_2)).unwrap();
+    /// assert_eq!(annotated, vec!["H\tVN:Z:1.2\tNM:Z:new"]);
+    /// ```
+    pub fn annotate_gfa_header_lines(lines: &[String], name: &GraphName, version: Option<GfaVersion>) -> Result<Vec<String>, String> {
+        let declared = if version.is_some() { Self::gfa_version(lines)? } else { None };
+        if let (Some(declared), Some(target)) = (declared, version) && declared != target {
+            return Err(format!("Header declares GFA version {}, expected {}", declared.as_str(), target.as_str()));
+        }
+
+        // Line `i` gets the `i`th occurrence of each tag.
+        let mut field_lines: Vec<Vec<String>> = Vec::new();
+        let mut occurrences: BTreeMap<&str, usize> = BTreeMap::new();
+        let new_lines = name.to_gfa_header_lines();
+        for line in new_lines.iter() {
+            let field = line.split_once(Self::GFA_GAF_FIELD_SEPARATOR).map_or("", |(_, field)| field);
+            let tag = field.get(0..2).unwrap_or_default();
+            let count = occurrences.entry(tag).or_default();
+            if *count >= field_lines.len() {
+                field_lines.push(Vec::new());
+            }
+            field_lines[*count].push(String::from(field));
+            *count += 1;
+        }
+
+        let mut result: Vec<String> = lines.iter().filter_map(|line| Self::remove_gfa_header_fields(line)).collect();
+        let position = match result.iter().position(|line| line.split(Self::GFA_GAF_FIELD_SEPARATOR).next() == Some(Self::GFA_HEADER_TYPE)) {
+            Some(position) => position,
+            None if field_lines.is_empty() && version.is_none() => return Ok(result),
+            None => {
+                result.insert(0, String::from(Self::GFA_HEADER_TYPE));
+                0
+            }
+        };
+        if declared.is_none() && let Some(version) = version {
+            let field = Self::gfa_header_field(Self::GFA_HEADER_VERSION, version.as_str());
+            let line = &mut result[position];
+            let rest = line.split_off(Self::GFA_HEADER_TYPE.len());
+            *line = format!("{}{}{}{}", Self::GFA_HEADER_TYPE, Self::GFA_GAF_FIELD_SEPARATOR, field, rest);
+        }
+        let mut field_lines = field_lines.into_iter();
+        if let Some(fields) = field_lines.next() {
+            for field in fields {
+                result[position].push(Self::GFA_GAF_FIELD_SEPARATOR);
+                result[position].push_str(&field);
+            }
+        }
+        let extra = field_lines.map(|fields| format!("{}{}{}", Self::GFA_HEADER_TYPE, Self::GFA_GAF_FIELD_SEPARATOR, fields.join("\t")));
+        result.splice(position + 1..position + 1, extra);
+        Ok(result)
+    }
+
+    /// Parses a GFA header field into a tag and a value.
+    ///
+    /// Returns [`None`] if the field is not a string field.
+    /// Returns an error if the field is not a valid typed field.
+    pub fn parse_gfa_header_field(field: &str) -> Result<Option<(&str, &str)>, String> {
+        // The separators are ASCII, so the slices are always at character boundaries.
+        let (tag, field_type, value) = match (field.get(0..2), field.get(2..5), field.get(5..)) {
+            (Some(tag), Some(typed), Some(value)) if typed.starts_with(':') && typed.ends_with(':') => (tag, &typed[1..2], value),
+            _ => return Err(format!("Invalid GFA typed field: {}", field)),
+        };
+        if field_type != "Z" {
+            return Ok(None);
+        }
+        Ok(Some((tag, value)))
+    }
+
+    /// Parses a relationship metadata version.
+    ///
+    /// Returns an error if the value is not a positive integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::GraphName;
+    ///
+    /// assert_eq!(GraphName::parse_metadata_version("2"), Ok(2));
+    /// assert!(GraphName::parse_metadata_version("0").is_err());
+    /// assert!(GraphName::parse_metadata_version("v1").is_err());
+    /// ```
+    pub fn parse_metadata_version(value: &str) -> Result<usize, String> {
+        match value.parse::<usize>() {
+            Ok(version) if version > 0 => Ok(version),
+            _ => Err(format!("Invalid relationship metadata version: {}", value)),
+        }
+    }
+
+    // Passes through the result of parsing a relationship, unless the metadata is from a newer version.
+    // Newer metadata may contain relationships this version does not understand, so they are skipped with a warning.
+    fn check_relationship(result: Result<(), String>, version: usize) -> Result<(), String> {
+        match result {
+            Err(message) if version > Self::METADATA_VERSION => {
+                tracing::warn!("Skipped relationship metadata from version {}: {}", version, message);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Returns a GAF header line with the given tag and values.
+    ///
+    /// The line does not end with a newline.
+    pub fn gaf_header_line(tag: &str, values: &[&str]) -> String {
+        let mut line = format!("{}{}", Self::GAF_HEADER_PREFIX, tag);
+        for value in values {
+            line.push(Self::GFA_GAF_FIELD_SEPARATOR);
+            line.push_str(value);
+        }
+        line
+    }
+
+    /// Returns `true` if the line is a GAF header line used for storing `GraphName` data.
+    pub fn is_gaf_header_line(line: &str) -> bool {
+        line.strip_prefix(Self::GAF_HEADER_PREFIX)
+            .and_then(|rest| rest.split_once(Self::GFA_GAF_FIELD_SEPARATOR))
+            .is_some_and(|(tag, _)| {
+                tag == Self::GAF_HEADER_NAME || tag == Self::GFA_GAF_HEADER_SUBGRAPH ||
+                    tag == Self::GFA_GAF_HEADER_TRANSLATION || tag == Self::GFA_GAF_HEADER_PROVENANCE ||
+                    tag == Self::GFA_GAF_HEADER_METADATA_VERSION
+            })
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// Graph name URIs.
+///
+/// A stable graph name can be cited as a URI of the form `pggname:sha256:HEX`, where `HEX` is the name as 64 lowercase hexadecimal digits.
+/// A resolvable identifier is formed by prepending the URI with a resolver prefix, such as [`GraphName::IDENTIFIERS_ORG_RESOLVER`].
+///
+/// # Examples
+///
+/// ```
+/// use pggname::GraphName;
+///
+/// let name = "54b49d18354a34fbd1af9aaac279e1b3ee67b2f68f0ff79f5ebf6c50c8d922a5";
+/// let uri = GraphName::uri_for(name).unwrap();
+/// assert_eq!(uri, format!("pggname:sha256:{}", name));
+/// assert_eq!(GraphName::parse_uri(&uri), Ok(String::from(name)));
+///
+/// let graph = GraphName::from_uri(&uri).unwrap();
+/// assert_eq!(graph.uri(), Some(uri.clone()));
+/// let url = graph.resolvable_uri(GraphName::IDENTIFIERS_ORG_RESOLVER).unwrap();
+/// assert_eq!(url, format!("https://identifiers.org/{}", uri));
+/// assert_eq!(GraphName::parse_uri(&url), Ok(String::from(name)));
+/// ```
+impl GraphName {
+    /// Number of hexadecimal digits in a stable graph name.
+    pub const STABLE_NAME_LEN: usize = 64;
+
+    /// Returns `true` if the name is a stable graph name: a SHA-256 hash as lowercase hexadecimal digits.
+    pub fn is_stable_name(name: &str) -> bool {
+        name.len() == Self::STABLE_NAME_LEN && name.bytes().all(|c| c.is_ascii_digit() || (b'a'..=b'f').contains(&c))
+    }
+
+    /// Number of hexadecimal digits in a short graph name.
+    pub const SHORT_NAME_LEN: usize = 16;
+
+    /// Returns the short form of the graph name: its first [`Self::SHORT_NAME_LEN`] characters.
+    ///
+    /// Short names are intended for tagging individual records, where the full name would take too much space.
+    /// Names that are not longer than that are returned unchanged.
+    pub fn short_name_for(name: &str) -> &str {
+        name.get(..Self::SHORT_NAME_LEN).unwrap_or(name)
+    }
+
+    /// Returns the URI for the stable graph name, or [`None`] if the name is not a stable graph name.
+    pub fn uri_for(name: &str) -> Option<String> {
+        Self::is_stable_name(name).then(|| format!("{}:{}:{}", Self::URI_SCHEME, Self::URI_ALGORITHM, name))
+    }
+
+    /// Parses a graph name URI and returns the stable graph name.
+    ///
+    /// The URI may be preceded by a resolver prefix ending with `/`.
+    /// The scheme and the algorithm are case-insensitive, and upper case hexadecimal digits are converted to lower case.
+    /// Returns an error if the URI is not a valid graph name URI.
+    pub fn parse_uri(uri: &str) -> Result<String, String> {
+        let compact = uri.rsplit('/').next().unwrap_or_default();
+        let mut fields = compact.splitn(3, ':');
+        let (Some(scheme), Some(algorithm), Some(name)) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(format!("Invalid graph name URI: {}", uri));
+        };
+        if !scheme.eq_ignore_ascii_case(Self::URI_SCHEME) {
+            return Err(format!("Invalid graph name URI scheme: {}", uri));
+        }
+        if !algorithm.eq_ignore_ascii_case(Self::URI_ALGORITHM) {
+            return Err(format!("Unsupported hash algorithm in graph name URI: {}", uri));
+        }
+        let name = name.to_ascii_lowercase();
+        if !Self::is_stable_name(&name) {
+            return Err(format!("Invalid stable graph name in URI: {}", uri));
+        }
+        Ok(name)
+    }
+
+    /// Creates a new `GraphName` with the stable graph name from the URI.
+    ///
+    /// See [`GraphName::parse_uri`] for the accepted forms.
+    pub fn from_uri(uri: &str) -> Result<Self, String> {
+        Ok(Self::new(Self::parse_uri(uri)?))
+    }
+
+    /// Returns the URI for the graph, or [`None`] if the graph does not have a stable name.
+    pub fn uri(&self) -> Option<String> {
+        self.name.as_deref().and_then(Self::uri_for)
+    }
+
+    /// Returns a resolvable identifier for the graph with the given resolver prefix.
+    ///
+    /// Returns [`None`] if the graph does not have a stable name.
+    pub fn resolvable_uri(&self, resolver: &str) -> Option<String> {
+        self.uri().map(|uri| format!("{}{}", resolver, uri))
+    }
+}
+
+//-----------------------------------------------------------------------------
+
 /// Construction.
 impl GraphName {
     /// Creates a new `GraphName` with the given stable graph name.
@@ -92,14 +817,18 @@ impl GraphName {
             name: Some(name),
             subgraph: BTreeMap::new(),
             translation: BTreeMap::new(),
+            provenance: BTreeMap::new(),
         }
     }
 
     /// Parses a `GraphName` from the given tags.
     ///
     /// Returns an error if tag values are malformed.
+    /// Malformed relationships are skipped if the tags declare a newer metadata version.
+    #[cfg(feature = "gbz")]
     pub fn from_tags(tags: &Tags) -> Result<Self, String> {
         let mut result = GraphName::default();
+        let version = tags.get(Self::TAG_METADATA_VERSION).map(String::as_str).map(Self::parse_metadata_version).transpose()?.unwrap_or(1);
 
         if let Some(name_field) = tags.get(Self::TAG_NAME) {
             result.name = Some(String::from(name_field));
@@ -108,123 +837,140 @@ impl GraphName {
         if let Some(subgraph_field) = tags.get(Self::TAG_SUBGRAPH) {
             let relationships: Vec<&str> = subgraph_field.split(Self::TAG_RELATIONSHIP_LIST_SEPARATOR).collect();
             for rel in relationships {
-                let parts: Vec<&str> = rel.split(Self::TAG_GFA_RELATIONSHIP_SEPARATOR).collect();
-                if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
-                    return Err(format!("Invalid subgraph relationship: {}", rel));
-                }
-                result.subgraph
-                    .entry(String::from(parts[0]))
-                    .or_default()
-                    .insert(String::from(parts[1]));
+                let parsed = Self::parse_relationship_value(rel)
+                    .map(|(from, to)| result.add_subgraph(from, to))
+                    .ok_or(format!("Invalid subgraph relationship: {}", rel));
+                Self::check_relationship(parsed, version)?;
             }
         }
 
         if let Some(translation_field) = tags.get(Self::TAG_TRANSLATION) {
             let relationships: Vec<&str> = translation_field.split(Self::TAG_RELATIONSHIP_LIST_SEPARATOR).collect();
             for rel in relationships {
-                let parts: Vec<&str> = rel.split(Self::TAG_GFA_RELATIONSHIP_SEPARATOR).collect();
-                if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
-                    return Err(format!("Invalid translation relationship: {}", rel));
-                }
-                result.translation
-                    .entry(String::from(parts[0]))
-                    .or_default()
-                    .insert(String::from(parts[1]));
+                let parsed = Self::parse_relationship_value(rel)
+                    .map(|(from, to)| result.add_translation(from, to))
+                    .ok_or(format!("Invalid translation relationship: {}", rel));
+                Self::check_relationship(parsed, version)?;
+            }
+        }
+
+        if let Some(provenance_field) = tags.get(Self::TAG_PROVENANCE) {
+            for value in provenance_field.split(Self::TAG_RELATIONSHIP_LIST_SEPARATOR) {
+                Self::check_relationship(result.add_provenance_value(value), version)?;
             }
         }
 
         Ok(result)
     }
 
+    // Parses a provenance value and stores it together with the relationship.
+    fn add_provenance_value(&mut self, value: &str) -> Result<(), String> {
+        let (kind, from, to, provenance) = Self::parse_provenance_value(value)?;
+        self.set_provenance(kind, from, to, provenance)
+    }
+
     /// Parses a `GraphName` from the tags in the given GBZ graph.
     ///
     /// Returns an empty object if the tags cannot be parsed.
+    #[cfg(feature = "gbz")]
     pub fn from_gbz(gbz: &GBZ) -> Self {
         Self::from_tags(gbz.tags()).unwrap_or_default()
     }
 
-    fn typed_field_is_string(field: &str) -> Result<bool, String> {
-        let bytes = field.as_bytes();
-        if field.len() < 5 || bytes[2] != b':' || bytes[4] != b':' {
-            return Err(format!("Invalid GFA typed field: {}", field));
-        }
-        Ok(bytes[3] == b'Z')
-    }
-
-    fn parse_gfa_optional_fields(fields: &[&str], result: &mut GraphName) -> Result<(), String> {
+    fn parse_gfa_optional_fields(fields: &[&str], result: &mut GraphName, version: usize) -> Result<(), String> {
         for &field in fields {
-            if !Self::typed_field_is_string(field)? {
+            let Some((tag, value)) = Self::parse_gfa_header_field(field)? else {
                 continue;
-            }
-            let tag = &field[0..2];
-            let value = &field[5..];
-            match tag {
+            };
+            let parsed = match tag {
                 Self::GFA_HEADER_NAME => {
                     result.name = Some(String::from(value));
+                    Ok(())
                 }
                 Self::GFA_GAF_HEADER_SUBGRAPH => {
-                    let parts: Vec<&str> = value.split(Self::TAG_GFA_RELATIONSHIP_SEPARATOR).collect();
-                    if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
-                        return Err(format!("Invalid subgraph field: {}", field));
-                    }
-                    result.subgraph
-                        .entry(String::from(parts[0]))
-                        .or_default()
-                        .insert(String::from(parts[1]));
+                    Self::parse_relationship_value(value)
+                        .map(|(subgraph, supergraph)| result.add_subgraph(subgraph, supergraph))
+                        .ok_or(format!("Invalid subgraph field: {}", field))
                 }
                 Self::GFA_GAF_HEADER_TRANSLATION => {
-                    let parts: Vec<&str> = value.split(Self::TAG_GFA_RELATIONSHIP_SEPARATOR).collect();
-                    if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
-                        return Err(format!("Invalid translation field: {}", field));
-                    }
-                    result.translation
-                        .entry(String::from(parts[0]))
-                        .or_default()
-                        .insert(String::from(parts[1]));
+                    Self::parse_relationship_value(value)
+                        .map(|(from, to)| result.add_translation(from, to))
+                        .ok_or(format!("Invalid translation field: {}", field))
                 }
-                _ => {}
-            }
+                Self::GFA_GAF_HEADER_PROVENANCE => result.add_provenance_value(value),
+                _ => Ok(()),
+            };
+            Self::check_relationship(parsed, version)?;
         }
         Ok(())
     }
 
-    fn parse_gaf_header_fields(line: &str, fields: &[&str], result: &mut GraphName) -> Result<(), String> {
-        match &fields[0][1..] {
+    fn parse_gaf_header_fields(line: &str, fields: &[&str], result: &mut GraphName, version: usize) -> Result<(), String> {
+        let parsed = match fields[0].strip_prefix(Self::GAF_HEADER_PREFIX).unwrap_or_default() {
             Self::GAF_HEADER_NAME => {
                 if fields.len() != 2 || fields[1].is_empty() {
                     return Err(format!("Invalid GAF name header line: {}", line));
                 }
                 result.name = Some(String::from(fields[1]));
+                Ok(())
             }
             Self::GFA_GAF_HEADER_SUBGRAPH => {
                 if fields.len() != 3 || fields[1].is_empty() || fields[2].is_empty() {
-                    return Err(format!("Invalid GAF subgraph header line: {}", line));
+                    Err(format!("Invalid GAF subgraph header line: {}", line))
+                } else {
+                    result.add_subgraph(fields[1], fields[2]);
+                    Ok(())
                 }
-                result.subgraph
-                    .entry(String::from(fields[1]))
-                    .or_default()
-                    .insert(String::from(fields[2]));
             }
             Self::GFA_GAF_HEADER_TRANSLATION => {
                 if fields.len() != 3 || fields[1].is_empty() || fields[2].is_empty() {
-                    return Err(format!("Invalid GAF translation header line: {}", line));
+                    Err(format!("Invalid GAF translation header line: {}", line))
+                } else {
+                    result.add_translation(fields[1], fields[2]);
+                    Ok(())
+                }
+            }
+            Self::GFA_GAF_HEADER_PROVENANCE => {
+                if fields.len() != 2 {
+                    Err(format!("Invalid GAF provenance header line: {}", line))
+                } else {
+                    result.add_provenance_value(fields[1])
+                }
+            }
+            _ => Ok(()),
+        };
+        Self::check_relationship(parsed, version)
+    }
+
+    // Returns the relationship metadata version declared in the GFA/GAF header lines, or 1 if there is no version.
+    // If the lines declare multiple versions, the highest one is used.
+    fn header_metadata_version(lines: &[String]) -> Result<usize, String> {
+        let mut result = 1;
+        for line in lines {
+            let mut fields = line.split(Self::GFA_GAF_FIELD_SEPARATOR);
+            let first = fields.next().unwrap_or_default();
+            if first == Self::GFA_HEADER_TYPE {
+                for field in fields {
+                    if let Ok(Some((Self::GFA_GAF_HEADER_METADATA_VERSION, value))) = Self::parse_gfa_header_field(field) {
+                        result = result.max(Self::parse_metadata_version(value)?);
+                    }
                 }
-                result.translation
-                    .entry(String::from(fields[1]))
-                    .or_default()
-                    .insert(String::from(fields[2]));
+            } else if first.strip_prefix(Self::GAF_HEADER_PREFIX) == Some(Self::GFA_GAF_HEADER_METADATA_VERSION) {
+                let value = fields.next().unwrap_or_default();
+                result = result.max(Self::parse_metadata_version(value)?);
             }
-            _ => {}
         }
-        Ok(())
+        Ok(result)
     }
 
     /// Parses a `GraphName` from the given GFA/GAF header lines.
     ///
     /// The lines must not end with a newline.
     /// Returns an error if the lines cannot be parsed.
+    /// Malformed relationships are skipped if the lines declare a newer metadata version.
     pub fn from_header_lines(lines: &[String]) -> Result<Self, String> {
         let mut result = GraphName::default();
+        let version = Self::header_metadata_version(lines)?;
 
         for (i, line) in lines.iter().enumerate() {
             let fields: Vec<&str> = line.split(Self::GFA_GAF_FIELD_SEPARATOR).collect();
@@ -232,9 +978,9 @@ impl GraphName {
                 return Err(format!("Error parsing header line {}: not enough fields", i + 1));
             }
             if fields[0] == Self::GFA_HEADER_TYPE {
-                Self::parse_gfa_optional_fields(&fields[1..], &mut result)?;
+                Self::parse_gfa_optional_fields(&fields[1..], &mut result, version)?;
             } else if fields[0].len() == 3 && fields[0].starts_with(Self::GAF_HEADER_PREFIX) {
-                Self::parse_gaf_header_fields(line, &fields, &mut result)?;
+                Self::parse_gaf_header_fields(line, &fields, &mut result, version)?;
             } else {
                 return Err(format!("Error parsing header line {}: unknown first field {}", i + 1, fields[0]));
             }
@@ -243,6 +989,105 @@ impl GraphName {
         Ok(result)
     }
 
+    /// Returns the names of the graphs referenced by a GAF file.
+    ///
+    /// The result contains the `@RN` name from the header and the short names from the `rn:Z:` fields of the records.
+    /// Short names of the header graph are not listed separately.
+    /// A GAF file with records from multiple graphs, such as a concatenation of files aligned to different graphs, returns more than one name.
+    /// Returns an error if reading the input fails or if the header cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::GraphName;
+    ///
+    /// let name = "7f4b28c71ceb808aebd8b8e9fe85e79d0d208ee263ffe9fcdef5ade20534ceb5";
+    /// let gaf = format!(
+    ///     "@RN\t{}\nread1\t3\t0\t3\t+\t>1\t3\t0\t3\t3\t3\t60\trn:Z:7f4b28c71ceb808a\nread2\t3\t0\t3\t+\t>1\t3\t0\t3\t3\t3\t60\trn:Z:e10f3b362d8a4273\n",
+    ///     name
+    /// );
+    /// let names = GraphName::from_gaf(gaf.as_bytes()).unwrap();
+    /// assert_eq!(names.into_iter().collect::<Vec<_>>(), vec![String::from(name), String::from("e10f3b362d8a4273")]);
+    /// ```
+    pub fn from_gaf<R: BufRead>(reader: R) -> Result<BTreeSet<String>, String> {
+        let mut header: Vec<String> = Vec::new();
+        let mut in_header = true;
+        let mut result = BTreeSet::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| format!("Error reading GAF line {}: {}", i + 1, e))?;
+            if in_header && line.starts_with(Self::GAF_HEADER_PREFIX) {
+                header.push(line);
+                continue;
+            }
+            in_header = false;
+            if let Some(name) = crate::gaf::record_name(&line) {
+                result.insert(String::from(name));
+            }
+        }
+        if let Some(name) = Self::from_header_lines(&header)?.name() {
+            result.remove(Self::short_name_for(name));
+            result.insert(name.clone());
+        }
+        Ok(result)
+    }
+
+    /// Parses a `GraphName` from the given VCF meta-information lines.
+    ///
+    /// The graph name is stored as `##pggname=NAME`, and relationships as `##pggname_subgraph=A,B` and `##pggname_translation=A,B`.
+    /// Other lines are ignored.
+    /// The lines must not end with a newline.
+    /// Returns an error if the relationships are malformed.
+    /// Malformed relationships are skipped if the lines declare a newer metadata version with `##pggname_version=N`.
+    pub fn from_vcf_header_lines(lines: &[String]) -> Result<Self, String> {
+        let mut result = GraphName::default();
+        let fields: Vec<(&str, &str, &str)> = lines.iter().filter_map(|line| {
+            line.strip_prefix(Self::VCF_HEADER_PREFIX).and_then(|rest| rest.split_once('=')).map(|(key, value)| (line.as_str(), key, value))
+        }).collect();
+        let mut version = 1;
+        for (_, key, value) in fields.iter() {
+            if *key == Self::VCF_HEADER_METADATA_VERSION {
+                version = version.max(Self::parse_metadata_version(value)?);
+            }
+        }
+
+        for (line, key, value) in fields {
+            let parsed = match key {
+                Self::VCF_HEADER_NAME => {
+                    if value.is_empty() {
+                        return Err(format!("Invalid VCF name header line: {}", line));
+                    }
+                    result.name = Some(String::from(value));
+                    continue;
+                }
+                Self::VCF_HEADER_PROVENANCE => result.add_provenance_value(value),
+                Self::VCF_HEADER_SUBGRAPH | Self::VCF_HEADER_TRANSLATION => {
+                    Self::parse_relationship_value(value).map(|(from, to)| {
+                        if key == Self::VCF_HEADER_SUBGRAPH {
+                            result.add_subgraph(from, to);
+                        } else {
+                            result.add_translation(from, to);
+                        }
+                    }).ok_or(format!("Invalid VCF relationship header line: {}", line))
+                }
+                _ => continue,
+            };
+            Self::check_relationship(parsed, version)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Returns `true` if the line is a VCF meta-information line used for storing `GraphName` data.
+    pub fn is_vcf_header_line(line: &str) -> bool {
+        line.strip_prefix(Self::VCF_HEADER_PREFIX)
+            .and_then(|rest| rest.split_once('='))
+            .is_some_and(|(key, _)| {
+                key == Self::VCF_HEADER_NAME || key == Self::VCF_HEADER_SUBGRAPH ||
+                    key == Self::VCF_HEADER_TRANSLATION || key == Self::VCF_HEADER_PROVENANCE ||
+                    key == Self::VCF_HEADER_METADATA_VERSION
+            })
+    }
+
     /// Adds a new subgraph relationship, if both names are non-empty.
     pub fn add_subgraph(&mut self, subgraph: &str, supergraph: &str) {
         if !subgraph.is_empty() && !supergraph.is_empty() {
@@ -276,6 +1121,14 @@ impl GraphName {
         }
     }
 
+    /// Adds a new relationship of the given kind, if both names are non-empty.
+    pub fn add_relationship(&mut self, kind: RelationshipKind, from: &str, to: &str) {
+        match kind {
+            RelationshipKind::Subgraph => self.add_subgraph(from, to),
+            RelationshipKind::Translation => self.add_translation(from, to),
+        }
+    }
+
     /// Adds a new translation relationship between this graph and the parent graph.
     ///
     /// Also copies all relationships from the parent graph.
@@ -303,6 +1156,79 @@ impl GraphName {
                 entry.insert(to.clone());
             }
         }
+        for (key, provenance) in &other.provenance {
+            match self.provenance.get(key) {
+                Some(existing) if !provenance.supersedes(existing) => {}
+                _ => {
+                    self.provenance.insert(key.clone(), provenance.clone());
+                }
+            }
+        }
+    }
+
+    /// Adds all relationships from another `GraphName` object and reports conflicts between them.
+    ///
+    /// The relationships are merged as with [`GraphName::add_relationships`].
+    /// The report lists self-referential relationships in the other object, pairs of graphs that become subgraphs of each other, and relationships claimed by one object and refuted by the other.
+    /// See [`RelationshipConflict`] for an example.
+    pub fn add_relationships_checked(&mut self, other: &GraphName) -> Vec<RelationshipConflict> {
+        let mut conflicts: BTreeSet<RelationshipConflict> = BTreeSet::new();
+        let relationships: BTreeSet<(RelationshipKind, &str, &str)> = other.subgraph_iter()
+            .map(|(from, to)| (RelationshipKind::Subgraph, from, to))
+            .chain(other.translation_iter().map(|(from, to)| (RelationshipKind::Translation, from, to)))
+            .chain(other.provenance_iter().map(|(kind, from, to, _)| (kind, from, to)))
+            .collect();
+        for (kind, from, to) in relationships.iter() {
+            if from == to && other.claims(*kind, from, to) == Some(true) {
+                conflicts.insert(RelationshipConflict::SelfReference { kind: *kind, graph: String::from(*from) });
+            }
+            if let (Some(ours), Some(theirs)) = (self.claims(*kind, from, to), other.claims(*kind, from, to)) && ours != theirs {
+                conflicts.insert(RelationshipConflict::Refuted { kind: *kind, from: String::from(*from), to: String::from(*to) });
+            }
+        }
+
+        self.add_relationships(other);
+        for (subgraph, supergraph) in other.subgraph_iter() {
+            let kind = RelationshipKind::Subgraph;
+            if subgraph != supergraph && self.claims(kind, subgraph, supergraph) == Some(true) && self.claims(kind, supergraph, subgraph) == Some(true) {
+                let (first, second) = if subgraph < supergraph { (subgraph, supergraph) } else { (supergraph, subgraph) };
+                conflicts.insert(RelationshipConflict::MutualSubgraph { first: String::from(first), second: String::from(second) });
+            }
+        }
+        conflicts.into_iter().collect()
+    }
+
+    // Returns `Some(true)` if the relationship is stored or has provenance that does not refute it, `Some(false)` if it has been refuted, and `None` otherwise.
+    fn claims(&self, kind: RelationshipKind, from: &str, to: &str) -> Option<bool> {
+        if self.is_refuted(kind, from, to) {
+            return Some(false);
+        }
+        let stored = match kind {
+            RelationshipKind::Subgraph => self.subgraph.get(from).is_some_and(|tos| tos.contains(to)),
+            RelationshipKind::Translation => self.translation.get(from).is_some_and(|tos| tos.contains(to)),
+        };
+        if stored || self.provenance(kind, from, to).is_some() { Some(true) } else { None }
+    }
+
+    /// Sets the provenance of a relationship and adds the relationship, if it does not exist.
+    ///
+    /// A refuted relationship is not added, and searches for relationship paths ignore it even if it exists.
+    /// Returns an error if a name is empty or if the tool name contains a separator.
+    pub fn set_provenance(&mut self, kind: RelationshipKind, from: &str, to: &str, provenance: RelationshipProvenance) -> Result<(), String> {
+        if from.is_empty() || to.is_empty() {
+            return Err(String::from("Relationship provenance requires two graph names"));
+        }
+        if let Some(tool) = provenance.tool.as_deref() && (tool.is_empty() || tool.contains([',', ';', '=', '\t', '\n', '\r'])) {
+            return Err(format!("Invalid tool name in relationship provenance: {}", tool.escape_debug()));
+        }
+        if let Some(timestamp) = provenance.timestamp && timestamp > RelationshipProvenance::MAX_TIMESTAMP {
+            return Err(format!("Timestamp {} in relationship provenance is out of range", timestamp));
+        }
+        if provenance.confidence != Confidence::Refuted {
+            self.add_relationship(kind, from, to);
+        }
+        self.provenance.insert((kind, String::from(from), String::from(to)), provenance);
+        Ok(())
     }
 }
 
@@ -310,6 +1236,7 @@ impl GraphName {
 
 /// Export to other formats.
 impl GraphName {
+    #[cfg(feature = "gbz")]
     fn relationships_to_string(relationships: &BTreeMap<String, BTreeSet<String>>) -> String {
         let mut value = String::new();
         for (from, tos) in relationships {
@@ -317,9 +1244,7 @@ impl GraphName {
                 if !value.is_empty() {
                     value.push(Self::TAG_RELATIONSHIP_LIST_SEPARATOR);
                 }
-                value.push_str(from);
-                value.push(Self::TAG_GFA_RELATIONSHIP_SEPARATOR);
-                value.push_str(to);
+                value.push_str(&Self::relationship_value(from, to));
             }
         }
         value
@@ -328,6 +1253,7 @@ impl GraphName {
     /// Writes the data stored in this object to the given tags.
     ///
     /// Clears existing tags if no corresponding data is available.
+    #[cfg(feature = "gbz")]
     pub fn set_tags(&self, tags: &mut Tags) {
         if let Some(name) = &self.name {
             tags.insert(Self::TAG_NAME, name);
@@ -335,6 +1261,12 @@ impl GraphName {
             tags.remove(Self::TAG_NAME);
         }
 
+        if self.has_relationships() {
+            tags.insert(Self::TAG_METADATA_VERSION, &Self::METADATA_VERSION.to_string());
+        } else {
+            tags.remove(Self::TAG_METADATA_VERSION);
+        }
+
         if !self.subgraph.is_empty() {
             let value = Self::relationships_to_string(&self.subgraph);
             tags.insert(Self::TAG_SUBGRAPH, &value);
@@ -348,6 +1280,18 @@ impl GraphName {
         } else {
             tags.remove(Self::TAG_TRANSLATION);
         }
+
+        if !self.provenance.is_empty() {
+            let values: Vec<String> = self.provenance_values().collect();
+            tags.insert(Self::TAG_PROVENANCE, &values.join(&Self::TAG_RELATIONSHIP_LIST_SEPARATOR.to_string()));
+        } else {
+            tags.remove(Self::TAG_PROVENANCE);
+        }
+    }
+
+    // Returns the provenance values in sorted order.
+    fn provenance_values(&self) -> impl Iterator<Item = String> + '_ {
+        self.provenance.iter().map(|((kind, from, to), provenance)| Self::provenance_value(*kind, from, to, provenance))
     }
 
     /// Returns GFA header lines representing this object.
@@ -356,18 +1300,55 @@ impl GraphName {
     pub fn to_gfa_header_lines(&self) -> Vec<String> {
         let mut lines = Vec::new();
         if let Some(name) = &self.name {
-            lines.push(format!("H\t{}:Z:{}", Self::GFA_HEADER_NAME, name));
+            lines.push(Self::gfa_header_line(Self::GFA_HEADER_NAME, name));
+        }
+        if self.has_relationships() {
+            lines.push(Self::gfa_header_line(Self::GFA_GAF_HEADER_METADATA_VERSION, &Self::METADATA_VERSION.to_string()));
+        }
+        for (subgraph, supergraphs) in &self.subgraph {
+            for supergraph in supergraphs {
+                lines.push(Self::gfa_header_line(Self::GFA_GAF_HEADER_SUBGRAPH, &Self::relationship_value(subgraph, supergraph)));
+            }
+        }
+        for (from, tos) in &self.translation {
+            for to in tos {
+                lines.push(Self::gfa_header_line(Self::GFA_GAF_HEADER_TRANSLATION, &Self::relationship_value(from, to)));
+            }
+        }
+        for value in self.provenance_values() {
+            lines.push(Self::gfa_header_line(Self::GFA_GAF_HEADER_PROVENANCE, &value));
+        }
+        lines
+    }
+
+    /// Returns VCF meta-information lines representing this object.
+    ///
+    /// The lines do not end with a newline.
+    pub fn to_vcf_header_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(name) = &self.name {
+            lines.push(format!("{}{}={}", Self::VCF_HEADER_PREFIX, Self::VCF_HEADER_NAME, name));
+        }
+        if self.has_relationships() {
+            lines.push(format!("{}{}={}", Self::VCF_HEADER_PREFIX, Self::VCF_HEADER_METADATA_VERSION, Self::METADATA_VERSION));
         }
         for (subgraph, supergraphs) in &self.subgraph {
             for supergraph in supergraphs {
-                lines.push(format!("H\t{}:Z:{},{}", Self::GFA_GAF_HEADER_SUBGRAPH, subgraph, supergraph));
+                lines.push(format!(
+                    "{}{}={}", Self::VCF_HEADER_PREFIX, Self::VCF_HEADER_SUBGRAPH, Self::relationship_value(subgraph, supergraph)
+                ));
             }
         }
         for (from, tos) in &self.translation {
             for to in tos {
-                lines.push(format!("H\t{}:Z:{},{}", Self::GFA_GAF_HEADER_TRANSLATION, from, to));
+                lines.push(format!(
+                    "{}{}={}", Self::VCF_HEADER_PREFIX, Self::VCF_HEADER_TRANSLATION, Self::relationship_value(from, to)
+                ));
             }
         }
+        for value in self.provenance_values() {
+            lines.push(format!("{}{}={}", Self::VCF_HEADER_PREFIX, Self::VCF_HEADER_PROVENANCE, value));
+        }
         lines
     }
 
@@ -377,18 +1358,24 @@ impl GraphName {
     pub fn to_gaf_header_lines(&self) -> Vec<String> {
         let mut lines = Vec::new();
         if let Some(name) = &self.name {
-            lines.push(format!("@{}\t{}", Self::GAF_HEADER_NAME, name));
+            lines.push(Self::gaf_header_line(Self::GAF_HEADER_NAME, &[name]));
+        }
+        if self.has_relationships() {
+            lines.push(Self::gaf_header_line(Self::GFA_GAF_HEADER_METADATA_VERSION, &[&Self::METADATA_VERSION.to_string()]));
         }
         for (subgraph, supergraphs) in &self.subgraph {
             for supergraph in supergraphs {
-                lines.push(format!("@{}\t{}\t{}", Self::GFA_GAF_HEADER_SUBGRAPH, subgraph, supergraph));
+                lines.push(Self::gaf_header_line(Self::GFA_GAF_HEADER_SUBGRAPH, &[subgraph, supergraph]));
             }
         }
         for (from, tos) in &self.translation {
             for to in tos {
-                lines.push(format!("@{}\t{}\t{}", Self::GFA_GAF_HEADER_TRANSLATION, from, to));
+                lines.push(Self::gaf_header_line(Self::GFA_GAF_HEADER_TRANSLATION, &[from, to]));
             }
         }
+        for value in self.provenance_values() {
+            lines.push(Self::gaf_header_line(Self::GFA_GAF_HEADER_PROVENANCE, &[&value]));
+        }
         lines
     }
 }
@@ -405,6 +1392,11 @@ impl GraphName {
         self.name.is_some()
     }
 
+    /// Returns `true` if there are relationships or relationship provenance.
+    pub fn has_relationships(&self) -> bool {
+        !self.subgraph.is_empty() || !self.translation.is_empty() || !self.provenance.is_empty()
+    }
+
     /// Returns `true` if both objects represent the same graph.
     pub fn is_same(&self, other: &GraphName) -> bool {
         match (&self.name, &other.name) {
@@ -431,120 +1423,254 @@ impl GraphName {
         })
     }
 
-    // Finds a path of subgraph relationships from `from` to `to`, including both.
-    // Uses relationships stored in `self`.
-    fn find_subgraph_path(&self, from: &GraphName, to: &GraphName) -> Option<Vec<String>> {
-        if !from.has_name() || !to.has_name() {
-            return None;
-        }
-        let from_name = from.name().unwrap();
-        let to_name = to.name().unwrap();
+    /// Returns the provenance of a relationship, if available.
+    pub fn provenance(&self, kind: RelationshipKind, from: &str, to: &str) -> Option<&RelationshipProvenance> {
+        self.provenance.get(&(kind, String::from(from), String::from(to)))
+    }
 
-        // Find a shortest path using BFS.
-        let mut predecessor: BTreeMap<String, String> = BTreeMap::new();
-        predecessor.insert(from_name.clone(), String::new());
-        let mut queue: VecDeque<String> = VecDeque::new();
-        queue.push_back(from_name.clone());
-        while let Some(curr) = queue.pop_front() {
-            if curr == *to_name {
-                break;
-            }
-            if let Some(supers) = self.subgraph.get(&curr) {
-                for supergraph in supers {
-                    if !predecessor.contains_key(supergraph) {
-                        predecessor.insert(supergraph.clone(), curr.clone());
-                        queue.push_back(supergraph.clone());
-                    }
-                }
-            }
-        }
-        if !predecessor.contains_key(to_name) {
-            return None;
-        }
+    /// Returns an iterator over relationships with provenance.
+    ///
+    /// The iterator yields tuples `(kind, from, to, provenance)` in sorted order.
+    pub fn provenance_iter(&self) -> impl Iterator<Item = (RelationshipKind, &str, &str, &RelationshipProvenance)> {
+        self.provenance.iter().map(|((kind, from, to), provenance)| (*kind, from.as_str(), to.as_str(), provenance))
+    }
 
-        // Trace back the path.
-        let mut result: Vec<String> = Vec::new();
-        let mut current = to_name.clone();
-        while !current.is_empty() {
-            result.push(current.clone());
-            current = predecessor.get(&current).unwrap().clone();
+    // Returns the confidence of the relationship, or `None` if there is no provenance.
+    fn stored_confidence(&self, kind: RelationshipKind, from: &str, to: &str) -> Option<Confidence> {
+        if self.provenance.is_empty() {
+            return None;
         }
-        result.reverse();
+        self.provenance(kind, from, to).map(|provenance| provenance.confidence)
+    }
 
-        Some(result)
+    /// Returns `true` if the relationship has been checked and found to be false.
+    pub fn is_refuted(&self, kind: RelationshipKind, from: &str, to: &str) -> bool {
+        self.stored_confidence(kind, from, to) == Some(Confidence::Refuted)
     }
 
     // Finds a path of subgraph or translation relationships from `from` to `to`, including both.
     // Each step is a pair `(name, is_translation)`, where `is_translation` indicates whether the step to the next name is a translation.
     // Uses relationships stored in `self`.
-    fn find_path(&self, from: &GraphName, to: &GraphName) -> Option<Vec<(String, bool)>> {
-        if !from.has_name() || !to.has_name() {
+    fn find_path<'a>(&'a self, from: &'a GraphName, to: &'a GraphName) -> Option<Vec<(&'a str, bool)>> {
+        self.find_path_impl(from.name()?, to.name()?, true, false, usize::MAX)
+    }
+
+    /// Finds a shortest path of at most `max_depth` relationships from graph `from` to graph `to`.
+    ///
+    /// Uses relationships stored in this object, which makes it suitable for querying a registry of relationships without copying it.
+    /// With [`RelationshipKind::Subgraph`], the path consists of subgraph relationships.
+    /// With [`RelationshipKind::Translation`], it may also contain translation relationships.
+    /// Refuted relationships are never used.
+    ///
+    /// The path includes both endpoints.
+    /// Each step is a pair `(name, is_translation)`, where `is_translation` indicates whether the step to the next name is a translation.
+    /// Returns [`None`] if there is no such path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::GraphName;
+    /// use pggname::name::RelationshipKind;
+    ///
+    /// let mut registry = GraphName::default();
+    /// registry.add_subgraph("A", "B");
+    /// registry.add_subgraph("B", "C");
+    /// registry.add_translation("C", "D");
+    /// let path = registry.find_path_within("A", "D", RelationshipKind::Translation, 3);
+    /// assert_eq!(path, Some(vec![("A", false), ("B", false), ("C", true), ("D", false)]));
+    /// assert_eq!(registry.find_path_within("A", "D", RelationshipKind::Translation, 2), None);
+    /// assert_eq!(registry.find_path_within("A", "D", RelationshipKind::Subgraph, 3), None);
+    /// ```
+    pub fn find_path_within<'a>(
+        &'a self, from: &'a str, to: &'a str, kind: RelationshipKind, max_depth: usize
+    ) -> Option<Vec<(&'a str, bool)>> {
+        self.find_path_impl(from, to, kind == RelationshipKind::Translation, false, max_depth)
+    }
+
+    // Shared implementation of the path searches.
+    // Refuted relationships are never used, and if `verified_only` is set, only verified relationships are used.
+    // The path may contain at most `max_depth` relationships.
+    // The search borrows the names from `self` and the endpoints instead of cloning them.
+    fn find_path_impl<'a>(
+        &'a self, from_name: &'a str, to_name: &'a str, translations: bool, verified_only: bool, max_depth: usize
+    ) -> Option<Vec<(&'a str, bool)>> {
+        if from_name == to_name {
+            return Some(vec![(from_name, false)]);
+        }
+        // A graph without outgoing relationships cannot reach anything.
+        let has_outgoing = self.subgraph.contains_key(from_name) || (translations && self.translation.contains_key(from_name));
+        if max_depth == 0 || !has_outgoing {
             return None;
         }
-        let from_name = from.name().unwrap();
-        let to_name = to.name().unwrap();
 
-        // Find a shortest path using BFS.
-        let mut predecessor: BTreeMap<String, (String, bool)> = BTreeMap::new();
-        predecessor.insert(from_name.clone(), (String::new(), false));
-        let mut queue: VecDeque<String> = VecDeque::new();
-        queue.push_back(from_name.clone());
-        while let Some(curr) = queue.pop_front() {
-            if curr == *to_name {
+        // Find a shortest path using BFS, stopping as soon as the target is discovered.
+        // The predecessor of `from_name` is `None`.
+        let mut predecessor: BTreeMap<&str, Option<(&str, bool)>> = BTreeMap::new();
+        predecessor.insert(from_name, None);
+        let mut queue: VecDeque<(&str, usize)> = VecDeque::new();
+        queue.push_back((from_name, 0));
+        'search: while let Some((curr, depth)) = queue.pop_front() {
+            if depth >= max_depth {
                 break;
             }
             // Prioritize subgraph relationships.
-            if let Some(neighbors) = self.subgraph.get(&curr) {
-                for next in neighbors {
-                    if !predecessor.contains_key(next) {
-                        predecessor.insert(next.clone(), (curr.clone(), false));
-                        queue.push_back(next.clone());
-                    }
+            let subgraph = self.subgraph.get(curr).into_iter().flatten().map(|next| (next, false));
+            let translation = self.translation.get(curr).into_iter().flatten().map(|next| (next, true));
+            for (next, is_translation) in subgraph.chain(translation.filter(|_| translations)) {
+                let kind = if is_translation { RelationshipKind::Translation } else { RelationshipKind::Subgraph };
+                match self.stored_confidence(kind, curr, next) {
+                    Some(Confidence::Refuted) => continue,
+                    Some(Confidence::Verified) => {}
+                    _ if verified_only => continue,
+                    _ => {}
                 }
-            }
-            // Then consider translation relationships.
-            if let Some(neighbors) = self.translation.get(&curr) {
-                for next in neighbors {
-                    if !predecessor.contains_key(next) {
-                        predecessor.insert(next.clone(), (curr.clone(), true));
-                        queue.push_back(next.clone());
+                if !predecessor.contains_key(next.as_str()) {
+                    predecessor.insert(next, Some((curr, is_translation)));
+                    if next == to_name {
+                        break 'search;
                     }
+                    queue.push_back((next, depth + 1));
                 }
             }
         }
-        if !predecessor.contains_key(to_name) {
-            return None;
-        }
 
         // Trace back the path.
-        let mut result: Vec<(String, bool)> = Vec::new();
-        result.push((from_name.clone(), false));
-        let (mut curr, mut is_translation) = predecessor.get(to_name).unwrap().clone();
-        while !curr.is_empty() {
-            result.push((curr.clone(), is_translation));
-            (curr, is_translation) = predecessor.get(&curr).unwrap().clone();
+        let mut step = *predecessor.get(to_name)?;
+        let mut result: Vec<(&str, bool)> = vec![(to_name, false)];
+        while let Some((prev, is_translation)) = step {
+            result.push((prev, is_translation));
+            step = predecessor[prev];
         }
         result.reverse();
 
         Some(result)
     }
 
+    // Returns an object with the relationships stored in both graphs.
+    // Avoids copying the relationships when only one of the graphs has them.
+    fn merged<'a>(&'a self, other: &'a GraphName) -> Cow<'a, GraphName> {
+        if !other.has_relationships() {
+            Cow::Borrowed(self)
+        } else if !self.has_relationships() {
+            Cow::Borrowed(other)
+        } else {
+            let mut merged = self.clone();
+            merged.add_relationships(other);
+            Cow::Owned(merged)
+        }
+    }
+
     /// Returns `true` if this graph is a subgraph of the given graph.
     ///
     /// Uses relationships stored in both graphs.
+    /// Returns `false` if the relationship has been refuted, even if there is a path through other graphs.
     pub fn is_subgraph_of(&self, other: &GraphName) -> bool {
-        let mut merged = self.clone();
-        merged.add_relationships(other);
-        merged.find_subgraph_path(self, other).is_some()
+        self.is_subgraph_of_within(other, usize::MAX)
+    }
+
+    /// Returns `true` if this graph is a subgraph of the given graph through a path of at most `max_depth` subgraph relationships.
+    ///
+    /// Uses relationships stored in both graphs.
+    /// Returns `false` if the relationship has been refuted, as with [`GraphName::subgraph_confidence`].
+    /// Limiting the depth bounds the cost of the query with large registries of densely connected graphs.
+    pub fn is_subgraph_of_within(&self, other: &GraphName, max_depth: usize) -> bool {
+        self.reaches(other, false, max_depth)
     }
 
     /// Returns `true` if coordinates in this graph can be translated to coordinates in the given graph.
     ///
     /// Uses relationships stored in both graphs.
+    /// Returns `false` if the translation relationship has been refuted, even if there is a path through other graphs.
     pub fn translates_to(&self, other: &GraphName) -> bool {
-        let mut merged = self.clone();
-        merged.add_relationships(other);
-        merged.find_path(self, other).is_some()
+        self.translates_to_within(other, usize::MAX)
+    }
+
+    /// Returns `true` if coordinates in this graph can be translated to the given graph through a path of at most `max_depth` relationships.
+    ///
+    /// Uses relationships stored in both graphs.
+    /// Returns `false` if the translation relationship has been refuted, as with [`GraphName::translation_confidence`].
+    /// Limiting the depth bounds the cost of the query with large registries of densely connected graphs.
+    pub fn translates_to_within(&self, other: &GraphName, max_depth: usize) -> bool {
+        self.reaches(other, true, max_depth)
+    }
+
+    // Shared implementation of the path-based queries.
+    // A refuted relationship between the graphs overrides any path, as in `confidence`.
+    fn reaches(&self, other: &GraphName, translations: bool, max_depth: usize) -> bool {
+        let (Some(from), Some(to)) = (self.name(), other.name()) else {
+            return false;
+        };
+        let merged = self.merged(other);
+        let kind = if translations { RelationshipKind::Translation } else { RelationshipKind::Subgraph };
+        !merged.is_refuted(kind, from, to) && merged.find_path_impl(from, to, translations, false, max_depth).is_some()
+    }
+
+    /// Returns the confidence in this graph being a subgraph of the given graph, or [`None`] if it is not known to be a subgraph.
+    ///
+    /// The result is [`Confidence::Refuted`] if the relationship has been checked and found to be false.
+    /// Otherwise it is [`Confidence::Verified`] if there is a path of verified subgraph relationships between the graphs.
+    /// Uses relationships stored in both graphs.
+    pub fn subgraph_confidence(&self, other: &GraphName) -> Option<Confidence> {
+        self.confidence(other, false)
+    }
+
+    /// Returns the confidence in coordinates in this graph being translatable to the given graph, or [`None`] if they are not known to be.
+    ///
+    /// The result is [`Confidence::Refuted`] if the translation relationship has been checked and found to be false.
+    /// Otherwise it is [`Confidence::Verified`] if there is a path of verified relationships between the graphs.
+    /// Uses relationships stored in both graphs.
+    pub fn translation_confidence(&self, other: &GraphName) -> Option<Confidence> {
+        self.confidence(other, true)
+    }
+
+    /// Returns whether data for this graph, such as alignments, can be used with the given graph.
+    ///
+    /// The graphs are compared in order of preference: the same graph, a subgraph, and a translation.
+    /// Refuted relationships are never used.
+    /// Uses relationships stored in both graphs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::GraphName;
+    /// use pggname::name::{Compatibility, Confidence};
+    ///
+    /// let mut name = GraphName::new(String::from("A"));
+    /// name.add_subgraph("A", "B");
+    /// name.add_translation("B", "C");
+    /// let graph = |name: &str| GraphName::new(String::from(name));
+    /// assert_eq!(name.compatibility(&graph("A")), Compatibility::Same);
+    /// assert_eq!(name.compatibility(&graph("B")), Compatibility::Subgraph(Confidence::Asserted));
+    /// assert_eq!(name.compatibility(&graph("C")), Compatibility::Translation(Confidence::Asserted));
+    /// assert_eq!(name.compatibility(&graph("D")), Compatibility::Incompatible);
+    /// ```
+    pub fn compatibility(&self, other: &GraphName) -> Compatibility {
+        if self.is_same(other) {
+            return Compatibility::Same;
+        }
+        match self.subgraph_confidence(other) {
+            Some(Confidence::Refuted) | None => {}
+            Some(confidence) => return Compatibility::Subgraph(confidence),
+        }
+        match self.translation_confidence(other) {
+            Some(Confidence::Refuted) | None => Compatibility::Incompatible,
+            Some(confidence) => Compatibility::Translation(confidence),
+        }
+    }
+
+    fn confidence(&self, other: &GraphName, translations: bool) -> Option<Confidence> {
+        let merged = self.merged(other);
+        let (from, to) = (self.name()?, other.name()?);
+        let kind = if translations { RelationshipKind::Translation } else { RelationshipKind::Subgraph };
+        if merged.is_refuted(kind, from, to) {
+            Some(Confidence::Refuted)
+        } else if merged.find_path_impl(from, to, translations, true, usize::MAX).is_some() {
+            Some(Confidence::Verified)
+        } else if merged.find_path_impl(from, to, translations, false, usize::MAX).is_some() {
+            Some(Confidence::Asserted)
+        } else {
+            None
+        }
     }
 
     fn append_description(result: &mut String, num: usize, description: &str) {
@@ -578,12 +1704,10 @@ impl GraphName {
     /// * `self_desc`: Description of this graph to use in the output.
     /// * `other_desc`: Description of the other graph to use in the output.
     pub fn describe_relationship(&self, other: &GraphName, self_desc: &str, other_desc: &str) -> String {
-        let mut merged = self.clone();
-        merged.add_relationships(other);
+        let merged = self.merged(other);
 
-        let no_name = String::from("(no name)");
-        let mut from = (self.name.as_ref().unwrap_or(&no_name).clone(), String::from(self_desc));
-        let mut to = (other.name.as_ref().unwrap_or(&no_name).clone(), String::from(other_desc));
+        let mut from = (self.name.as_deref().unwrap_or("(no name)"), self_desc);
+        let mut to = (other.name.as_deref().unwrap_or("(no name)"), other_desc);
         let mut path = merged.find_path(self, other);
         if path.is_none() {
             std::mem::swap(&mut from, &mut to);
@@ -592,14 +1716,14 @@ impl GraphName {
 
         // Graph descriptions and relationships.
         let mut result = String::new();
-        Self::append_description(&mut result, 1, &from.1);
+        Self::append_description(&mut result, 1, from.1);
         if let Some(path) = &path {
             for i in 1..path.len() {
                 Self::append_relationship(&mut result, i, path[i - 1].1);
             }
-            Self::append_description(&mut result, path.len(), &to.1);
+            Self::append_description(&mut result, path.len(), to.1);
         } else {
-            Self::append_description(&mut result, 2, &to.1);
+            Self::append_description(&mut result, 2, to.1);
         }
 
         // Graph names.
@@ -609,8 +1733,8 @@ impl GraphName {
                 Self::append_graph(&mut result, i + 1, name);
             }
         } else {
-            Self::append_graph(&mut result, 1, &from.0);
-            Self::append_graph(&mut result, 2, &to.0);
+            Self::append_graph(&mut result, 1, from.0);
+            Self::append_graph(&mut result, 2, to.0);
         }
 
         result
@@ -618,3 +1742,328 @@ impl GraphName {
 }
 
 //-----------------------------------------------------------------------------
+
+/// Streams a GAF file, replacing the header lines that store [`GraphName`] data with those for the given object.
+///
+/// The identity header lines (`@RN`, `@SG`, `@TL`, `@PV`, and `@MV`) in the leading header are replaced with [`GraphName::to_gaf_header_lines`], or inserted if there are none.
+/// Other header lines are kept in their original order after the new lines, and records are copied byte for byte.
+/// Lines starting with `@` after the first record are considered records.
+///
+/// Returns the metadata parsed from the replaced lines, so that the caller can report a stale name or keep the old relationships.
+/// Returns an error if reading or writing fails or if the old header cannot be parsed.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::{GraphName, name};
+///
+/// let gaf = b"@RN\tA\n@HD\tVN:Z:1.0\nread\t4\t0\t4\t+\t>1\t4\t0\t4\t4\t4\t60\n";
+/// let mut new_name = GraphName::new(String::from("B"));
+/// new_name.add_translation("B", "C");
+/// let mut output: Vec<u8> = Vec::new();
+/// let old = name::rewrite_gaf_headers(&gaf[..], &mut output, &new_name).unwrap();
+/// assert_eq!(old.name(), Some(&String::from("A")));
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "@RN\tB\n@MV\t1\n@TL\tB\tC\n@HD\tVN:Z:1.0\nread\t4\t0\t4\t+\t>1\t4\t0\t4\t4\t4\t60\n"
+/// );
+/// ```
+pub fn rewrite_gaf_headers<R: BufRead, W: Write>(mut reader: R, mut writer: W, name: &GraphName) -> Result<GraphName, String> {
+    let mut identity: Vec<String> = Vec::new();
+    let mut other: Vec<Vec<u8>> = Vec::new();
+    let mut line: Vec<u8> = Vec::new();
+    loop {
+        line.clear();
+        let bytes = reader.read_until(b'\n', &mut line).map_err(|e| format!("Error reading GAF header: {}", e))?;
+        if bytes == 0 || !line.starts_with(b"@") {
+            break;
+        }
+        let text = std::str::from_utf8(&line).ok().map(|text| text.trim_end_matches(['\n', '\r']));
+        match text {
+            Some(text) if GraphName::is_gaf_header_line(text) => identity.push(String::from(text)),
+            _ => other.push(line.clone()),
+        }
+    }
+    let old = GraphName::from_header_lines(&identity)?;
+
+    let write_error = |e: io::Error| format!("Error writing GAF: {}", e);
+    for header_line in name.to_gaf_header_lines() {
+        writeln!(writer, "{}", header_line).map_err(write_error)?;
+    }
+    for header_line in other.iter() {
+        writer.write_all(header_line).map_err(write_error)?;
+    }
+    writer.write_all(&line).map_err(write_error)?;
+    io::copy(&mut reader, &mut writer).map_err(|e| format!("Error copying GAF records: {}", e))?;
+    writer.flush().map_err(write_error)?;
+    Ok(old)
+}
+
+/// Streams a GFA file, replacing the name and relationship fields in the header with those returned by `update`.
+///
+/// `update` is called with the metadata parsed from the original header, and it returns the new metadata.
+/// The fields are merged into the header lines at the start of the file with [`GraphName::annotate_gfa_header_lines`], which also declares the target version if one is given.
+/// If the file has no header, a new header line is inserted at the beginning.
+/// Header lines after the first record lose their name and relationship fields with [`GraphName::remove_gfa_header_fields`], so that they cannot contradict the new header.
+/// All other lines are copied byte for byte.
+///
+/// Returns the metadata parsed from the original header.
+/// Returns an error if reading or writing fails, if the header cannot be parsed or annotated, or if `update` fails.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::{GraphName, name};
+///
+/// let gfa = b"H\tVN:Z:1.1\tNM:Z:A\nS\t1\tACGT\n";
+/// let mut output: Vec<u8> = Vec::new();
+/// let old = name::rewrite_gfa_headers(&gfa[..], &mut output, |old| {
+///     let mut new = GraphName::new(String::from("B"));
+///     new.add_translation("B", old.name().unwrap());
+///     Ok(new)
+/// }, None).unwrap();
+/// assert_eq!(old.name(), Some(&String::from("A")));
+/// let output = String::from_utf8(output).unwrap();
+/// let header: Vec<String> = output.lines().take(1).map(String::from).collect();
+/// let new = GraphName::from_header_lines(&header).unwrap();
+/// assert_eq!(new.name(), Some(&String::from("B")));
+/// assert!(new.translation_iter().eq([("B", "A")]));
+/// assert!(output.ends_with("\nS\t1\tACGT\n"));
+/// ```
+pub fn rewrite_gfa_headers<R: BufRead, W: Write, F: FnOnce(&GraphName) -> Result<GraphName, String>>(
+    mut reader: R, mut writer: W, update: F, version: Option<GfaVersion>
+) -> Result<GraphName, String> {
+    let is_header = |line: &[u8]| line.starts_with(b"H") && matches!(line.get(1), None | Some(b'\t' | b'\n' | b'\r'));
+    let header_text = |line: &[u8], i: usize| {
+        std::str::from_utf8(line).map(|text| String::from(text.trim_end_matches(['\n', '\r'])))
+            .map_err(|_| format!("Invalid UTF-8 on GFA line {}", i + 1))
+    };
+    let read_error = |e: io::Error| format!("Error reading GFA: {}", e);
+    let write_error = |e: io::Error| format!("Error writing GFA: {}", e);
+
+    let mut header: Vec<String> = Vec::new();
+    let mut line: Vec<u8> = Vec::new();
+    let mut i = 0;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).map_err(read_error)? == 0 || !is_header(&line) {
+            break;
+        }
+        header.push(header_text(&line, i)?);
+        i += 1;
+    }
+    let old = GraphName::from_header_lines(&header)?;
+    let name = update(&old)?;
+    for header_line in GraphName::annotate_gfa_header_lines(&header, &name, version)? {
+        writeln!(writer, "{}", header_line).map_err(write_error)?;
+    }
+
+    while !line.is_empty() {
+        if is_header(&line) {
+            if let Some(header_line) = GraphName::remove_gfa_header_fields(&header_text(&line, i)?) {
+                writeln!(writer, "{}", header_line).map_err(write_error)?;
+            }
+        } else {
+            writer.write_all(&line).map_err(write_error)?;
+        }
+        line.clear();
+        reader.read_until(b'\n', &mut line).map_err(read_error)?;
+        i += 1;
+    }
+    writer.flush().map_err(write_error)?;
+    Ok(old)
+}
+
+//-----------------------------------------------------------------------------
+
+/// Writes the compact single-string form of the object.
+///
+/// The string starts with the graph name, which is empty if the name is not available.
+/// It is followed by `;SG=A,B` for each subgraph relationship and `;TL=A,B` for each translation relationship, in sorted order.
+/// Relationship provenance is written as `;PV=VALUE` using [`GraphName::provenance_value`].
+/// An empty object is written as an empty string.
+///
+/// The form uses the same separators as [`Tags`] values.
+/// Names containing `;`, `,`, or `=` cannot be represented.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::GraphName;
+///
+/// let mut name = GraphName::new(String::from("C"));
+/// name.add_subgraph("C", "B");
+/// name.add_translation("B", "A");
+/// assert_eq!(name.to_string(), "C;SG=C,B;TL=B,A");
+/// assert_eq!("C;SG=C,B;TL=B,A".parse::<GraphName>(), Ok(name));
+///
+/// let relationships: GraphName = ";SG=C,B".parse().unwrap();
+/// assert!(!relationships.has_name());
+/// ```
+impl fmt::Display for GraphName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name.as_deref().unwrap_or_default())?;
+        for (tag, relationships) in [(Self::GFA_GAF_HEADER_SUBGRAPH, &self.subgraph), (Self::GFA_GAF_HEADER_TRANSLATION, &self.translation)] {
+            for (from, tos) in relationships {
+                for to in tos {
+                    write!(f, "{}{}={}", Self::TAG_RELATIONSHIP_LIST_SEPARATOR, tag, Self::relationship_value(from, to))?;
+                }
+            }
+        }
+        for value in self.provenance_values() {
+            write!(f, "{}{}={}", Self::TAG_RELATIONSHIP_LIST_SEPARATOR, Self::GFA_GAF_HEADER_PROVENANCE, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the compact single-string form written by the [`fmt::Display`] implementation.
+///
+/// Returns an error if a relationship is malformed or has an unknown tag, or if the name contains a separator.
+impl FromStr for GraphName {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut result = GraphName::default();
+        let mut fields = value.split(Self::TAG_RELATIONSHIP_LIST_SEPARATOR);
+        let name = fields.next().unwrap_or_default();
+        if name.contains(Self::TAG_GFA_RELATIONSHIP_SEPARATOR) || name.contains('=') {
+            return Err(format!("Invalid graph name: {}", name));
+        }
+        if !name.is_empty() {
+            result.name = Some(String::from(name));
+        }
+        for field in fields {
+            if let Some(value) = field.strip_prefix(Self::GFA_GAF_HEADER_PROVENANCE).and_then(|rest| rest.strip_prefix('=')) {
+                result.add_provenance_value(value)?;
+                continue;
+            }
+            let relationship = field.split_once('=').and_then(|(tag, value)| {
+                Self::parse_relationship_value(value).map(|(from, to)| (tag, from, to))
+            });
+            match relationship {
+                Some((Self::GFA_GAF_HEADER_SUBGRAPH, from, to)) => result.add_subgraph(from, to),
+                Some((Self::GFA_GAF_HEADER_TRANSLATION, from, to)) => result.add_translation(from, to),
+                _ => return Err(format!("Invalid graph relationship: {}", field)),
+            }
+        }
+        Ok(result)
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// A builder for [`GraphName`] objects.
+///
+/// The builder validates the names as they are added.
+/// Graph names must be non-empty, and they must not contain `;`, `,`, `=`, tabs, or line breaks.
+/// Such names cannot be stored in [`Tags`], header lines, or the compact single-string form.
+/// The first error is reported by [`GraphNameBuilder::build`].
+///
+/// # Examples
+///
+/// ```
+/// use pggname::GraphNameBuilder;
+///
+/// let parent = GraphNameBuilder::new("parent").build().unwrap();
+/// let target = GraphNameBuilder::new("target").build().unwrap();
+/// let name = GraphNameBuilder::new("graph")
+///     .subgraph_of(&parent)
+///     .translates_to(&target)
+///     .build()
+///     .unwrap();
+/// assert!(name.is_subgraph_of(&parent));
+/// assert!(name.translates_to(&target));
+///
+/// assert!(GraphNameBuilder::new("a;b").build().is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct GraphNameBuilder {
+    result: GraphName,
+    error: Option<String>,
+}
+
+impl GraphNameBuilder {
+    /// Starts building an object for the graph with the given stable name.
+    pub fn new(name: &str) -> Self {
+        let mut builder = GraphNameBuilder {
+            result: GraphName::new(String::from(name)),
+            error: None,
+        };
+        builder.check(name);
+        builder
+    }
+
+    /// Makes this graph a subgraph of the parent graph and copies the relationships from the parent.
+    ///
+    /// The parent graph must have a name.
+    pub fn subgraph_of(mut self, parent: &GraphName) -> Self {
+        if self.check_parent(parent) {
+            self.result.make_subgraph_of(parent);
+        }
+        self
+    }
+
+    /// Adds a translation from this graph to the parent graph and copies the relationships from the parent.
+    ///
+    /// The parent graph must have a name.
+    pub fn translates_to(mut self, parent: &GraphName) -> Self {
+        if self.check_parent(parent) {
+            self.result.add_translation_to(parent);
+        }
+        self
+    }
+
+    /// Adds a subgraph relationship between two other graphs.
+    pub fn subgraph(mut self, subgraph: &str, supergraph: &str) -> Self {
+        if self.check(subgraph) && self.check(supergraph) {
+            self.result.add_subgraph(subgraph, supergraph);
+        }
+        self
+    }
+
+    /// Adds a translation relationship between two other graphs.
+    pub fn translation(mut self, from: &str, to: &str) -> Self {
+        if self.check(from) && self.check(to) {
+            self.result.add_translation(from, to);
+        }
+        self
+    }
+
+    /// Returns the object, or the first error found while building it.
+    pub fn build(self) -> Result<GraphName, String> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.result),
+        }
+    }
+
+    // Validates the name and records the first error.
+    // Returns `true` if the name is valid.
+    fn check(&mut self, name: &str) -> bool {
+        let error = if name.is_empty() {
+            Some(String::from("Empty graph name"))
+        } else if name.contains([';', ',', '=', '\t', '\n', '\r']) {
+            Some(format!("Invalid character in graph name: {}", name.escape_debug()))
+        } else {
+            None
+        };
+        let valid = error.is_none();
+        if self.error.is_none() {
+            self.error = error;
+        }
+        valid
+    }
+
+    fn check_parent(&mut self, parent: &GraphName) -> bool {
+        match parent.name() {
+            Some(name) => self.check(name),
+            None => {
+                self.error.get_or_insert(String::from("Parent graph has no name"));
+                false
+            }
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
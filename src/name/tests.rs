@@ -42,12 +42,15 @@ fn from_parents() -> GraphName {
 // In the following functions, the first returned value also contains fields (e.g. tags, rows)
 // unrelated to GraphName, while the second only contains those derived from GraphName.
 
+#[cfg(feature = "gbz")]
 fn tags() -> (Tags, Tags) {
     let mut all_tags = Tags::new();
     let mut name_tags = Tags::new();
     all_tags.insert(gbz::SOURCE_KEY, gbz::SOURCE_VALUE);
     all_tags.insert(GraphName::TAG_NAME, NAME);
     name_tags.insert(GraphName::TAG_NAME, NAME);
+    all_tags.insert(GraphName::TAG_METADATA_VERSION, "1");
+    name_tags.insert(GraphName::TAG_METADATA_VERSION, "1");
 
     let mut subgraph_value = String::new();
     for (subgraph, supergraph) in SUBGRAPH.iter() {
@@ -80,6 +83,7 @@ fn gfa_header_lines() -> (Vec<String>, Vec<String>) {
     let mut all_lines = Vec::new();
     all_lines.push(String::from("H\tVN:Z:1.1"));
     all_lines.push(format!("H\t{}:Z:{}", GraphName::GFA_HEADER_NAME, NAME));
+    all_lines.push(format!("H\t{}:Z:{}", GraphName::GFA_GAF_HEADER_METADATA_VERSION, GraphName::METADATA_VERSION));
 
     for (subgraph, supergraph) in SUBGRAPH.iter() {
         all_lines.push(format!(
@@ -109,6 +113,7 @@ fn gaf_header_lines() -> (Vec<String>, Vec<String>) {
     let mut all_lines = Vec::new();
     all_lines.push(String::from("@HD\tVN:Z:1.0"));
     all_lines.push(format!("@{}\t{}", GraphName::GAF_HEADER_NAME, NAME));
+    all_lines.push(format!("@{}\t{}", GraphName::GFA_GAF_HEADER_METADATA_VERSION, GraphName::METADATA_VERSION));
 
     for (subgraph, supergraph) in SUBGRAPH.iter() {
         all_lines.push(format!(
@@ -132,6 +137,24 @@ fn gaf_header_lines() -> (Vec<String>, Vec<String>) {
     (all_lines, name_lines)
 }
 
+fn vcf_header_lines() -> (Vec<String>, Vec<String>) {
+    let mut all_lines = Vec::new();
+    all_lines.push(String::from("##fileformat=VCFv4.2"));
+    all_lines.push(format!("##{}={}", GraphName::VCF_HEADER_NAME, NAME));
+    all_lines.push(format!("##{}={}", GraphName::VCF_HEADER_METADATA_VERSION, GraphName::METADATA_VERSION));
+
+    for (subgraph, supergraph) in SUBGRAPH.iter() {
+        all_lines.push(format!("##{}={},{}", GraphName::VCF_HEADER_SUBGRAPH, subgraph, supergraph));
+    }
+
+    for (from, to) in TRANSLATION.iter() {
+        all_lines.push(format!("##{}={},{}", GraphName::VCF_HEADER_TRANSLATION, from, to));
+    }
+
+    let name_lines: Vec<String> = all_lines.iter().skip(1).cloned().collect();
+    (all_lines, name_lines)
+}
+
 fn expected_description_lines(steps: usize, has_path: bool) -> usize {
     let mut lines = 2; // Descriptions of both names.
     lines += steps; // One line per step.
@@ -158,17 +181,20 @@ fn graph_name_empty() {
     assert!(!default.has_name(), "Expected has_name() to be false in default GraphName");
     assert!(default.name().is_none(), "Expected no name in default GraphName");
 
-    let empty_tags = Tags::new();
-    let from_tags = GraphName::from_tags(&empty_tags);
-    assert!(from_tags.is_ok(), "Failed to build GraphName from empty tags: {}", from_tags.unwrap_err());
-    let from_tags = from_tags.unwrap();
-    assert!(!from_tags.has_name(), "Expected has_name() to be false in GraphName built from empty tags");
-    assert!(from_tags.name().is_none(), "Expected no name in GraphName built from empty tags");
-    assert!(!from_tags.is_same(&default), "GraphNames with missing names should not be the same");
-    let mut to_tags = Tags::new();
-    default.set_tags(&mut to_tags);
-    assert_eq!(to_tags, empty_tags, "Expected no tags to be written from default GraphName");
-    test_describe_relationship(&default, &from_tags, "default", "from_tags", 0, false);
+    #[cfg(feature = "gbz")]
+    {
+        let empty_tags = Tags::new();
+        let from_tags = GraphName::from_tags(&empty_tags);
+        assert!(from_tags.is_ok(), "Failed to build GraphName from empty tags: {}", from_tags.unwrap_err());
+        let from_tags = from_tags.unwrap();
+        assert!(!from_tags.has_name(), "Expected has_name() to be false in GraphName built from empty tags");
+        assert!(from_tags.name().is_none(), "Expected no name in GraphName built from empty tags");
+        assert!(!from_tags.is_same(&default), "GraphNames with missing names should not be the same");
+        let mut to_tags = Tags::new();
+        default.set_tags(&mut to_tags);
+        assert_eq!(to_tags, empty_tags, "Expected no tags to be written from default GraphName");
+        test_describe_relationship(&default, &from_tags, "default", "from_tags", 0, false);
+    }
 
     let empty_header_lines = Vec::new();
     let from_headers = GraphName::from_header_lines(&empty_header_lines);
@@ -204,6 +230,7 @@ fn graph_name_manual() {
     }
 }
 
+#[cfg(feature = "gbz")]
 #[test]
 fn graph_name_tags() {
     let (all_tags, name_tags) = tags();
@@ -226,6 +253,7 @@ fn graph_name_tags() {
     assert!(!to_tags.contains_key(GraphName::TAG_NAME), "Graph name tag was not cleared");
     assert!(!to_tags.contains_key(GraphName::TAG_SUBGRAPH), "Subgraph tag was not cleared");
     assert!(!to_tags.contains_key(GraphName::TAG_TRANSLATION), "Translation tag was not cleared");
+    assert!(!to_tags.contains_key(GraphName::TAG_METADATA_VERSION), "Metadata version tag was not cleared");
 }
 
 #[test]
@@ -262,6 +290,51 @@ fn graph_name_gaf() {
     assert_eq!(to_headers, name_headers, "GAF header lines written from GraphName do not match expected header lines");
 }
 
+#[test]
+fn graph_name_vcf() {
+    let (all_headers, name_headers) = vcf_header_lines();
+    let from_headers = GraphName::from_vcf_header_lines(&all_headers);
+    assert!(from_headers.is_ok(), "Failed to build GraphName from VCF header lines: {}", from_headers.unwrap_err());
+    let from_headers = from_headers.unwrap();
+    assert_eq!(from_headers, manual(), "GraphName built from VCF header lines is not equal to manual GraphName");
+
+    let to_headers = from_headers.to_vcf_header_lines();
+    assert_eq!(to_headers, name_headers, "VCF header lines written from GraphName do not match expected header lines");
+    assert!(to_headers.iter().all(|line| GraphName::is_vcf_header_line(line)), "Written lines are not recognized as VCF header lines");
+    assert!(!GraphName::is_vcf_header_line(&all_headers[0]), "Unrelated line recognized as a VCF header line");
+
+    let invalid = vec![format!("##{}=A", GraphName::VCF_HEADER_SUBGRAPH)];
+    assert!(GraphName::from_vcf_header_lines(&invalid).is_err(), "Parsed an invalid VCF relationship line");
+}
+
+#[test]
+fn header_fields() {
+    assert_eq!(GraphName::parse_relationship_value("A,B"), Some(("A", "B")), "Wrong relationship");
+    for value in ["A", "A,", ",B", "A,B,C", ""] {
+        assert_eq!(GraphName::parse_relationship_value(value), None, "Accepted relationship {:?}", value);
+    }
+
+    assert_eq!(GraphName::parse_gfa_header_field("NM:Z:A"), Ok(Some(("NM", "A"))), "Wrong string field");
+    assert_eq!(GraphName::parse_gfa_header_field("VN:i:1"), Ok(None), "Wrong integer field");
+    assert!(GraphName::parse_gfa_header_field("NM:A").is_err(), "Accepted a malformed field");
+    assert_eq!(GraphName::parse_gfa_header_field("NM:Z:ä"), Ok(Some(("NM", "ä"))), "Wrong non-ASCII value");
+    for field in ["", "NM:Z", "äb:Z:A", "Nä:Z:A", "NM:ä:A", "NM:Zä", "NMäZ:A", "NM::"] {
+        assert!(GraphName::parse_gfa_header_field(field).is_err(), "Accepted malformed field {:?}", field);
+    }
+    for line in ["H\tNM:Zä", "ä\tA"] {
+        assert!(GraphName::from_header_lines(&[String::from(line)]).is_err(), "Accepted header line {:?}", line);
+    }
+    assert_eq!(GraphName::from_header_lines(&[String::from("@ä\tA")]), Ok(GraphName::default()), "Unknown GAF header line not ignored");
+
+    let name = manual();
+    let gfa: Vec<String> = name.to_gfa_header_lines();
+    assert_eq!(gfa[0], GraphName::gfa_header_line(GraphName::GFA_HEADER_NAME, NAME), "Wrong GFA name line");
+    let gaf: Vec<String> = name.to_gaf_header_lines();
+    assert_eq!(gaf[0], GraphName::gaf_header_line(GraphName::GAF_HEADER_NAME, &[NAME]), "Wrong GAF name line");
+    assert!(gaf.iter().all(|line| GraphName::is_gaf_header_line(line)), "Relationship lines not recognized");
+    assert!(!GraphName::is_gaf_header_line("@HD\tVN:Z:1.0"), "Other header line recognized");
+}
+
 #[test]
 fn graph_name_subgraph() {
     let a = manual();
@@ -385,4 +458,643 @@ fn graph_name_translation() {
     }
 }
 
+#[test]
+fn relationship_paths() {
+    let a = manual();
+    let name = |value: &str| GraphName::new(String::from(value));
+
+    let subgraph_path = |from, to| {
+        let path = a.find_path_within(from, to, RelationshipKind::Subgraph, usize::MAX)?;
+        Some(path.into_iter().map(|(name, _)| name).collect::<Vec<_>>())
+    };
+    assert_eq!(subgraph_path("C", "E"), Some(vec!["C", "D", "E"]), "Wrong subgraph path from C to E");
+    assert_eq!(subgraph_path("A", "C"), None, "Found a subgraph path from A to C");
+    assert_eq!(subgraph_path("A", "A"), Some(vec!["A"]), "Wrong subgraph path from A to itself");
+
+    let expected = vec![("A", false), ("B", true), ("C", true), ("F", false)];
+    assert_eq!(a.find_path(&name("A"), &name("F")), Some(expected), "Wrong path from A to F");
+    assert_eq!(a.find_path(&name("F"), &name("A")), None, "Found a path from F to A");
+    assert_eq!(a.find_path(&GraphName::default(), &name("A")), None, "Found a path from a graph without a name");
+
+    let mut f = name("F");
+    f.add_relationships(&a);
+    let description = name("A").describe_relationship(&f, "source", "target");
+    let names: Vec<&str> = description.lines().skip_while(|line| !line.starts_with("With graph names")).skip(1).collect();
+    assert_eq!(names, vec!["1\tA", "2\tB", "3\tC", "4\tF"], "Wrong graph names in the description");
+}
+
+#[test]
+fn depth_limits() {
+    let a = manual();
+    let name = |value: &str| GraphName::new(String::from(value));
+
+    // A -> B -> C -> F uses three relationships.
+    let expected = vec![("A", false), ("B", true), ("C", true), ("F", false)];
+    assert_eq!(a.find_path_within("A", "F", RelationshipKind::Translation, 3), Some(expected), "Wrong path from A to F within 3");
+    assert_eq!(a.find_path_within("A", "F", RelationshipKind::Translation, 2), None, "Found a path from A to F within 2");
+    assert_eq!(a.find_path_within("A", "A", RelationshipKind::Subgraph, 0), Some(vec![("A", false)]), "Wrong path from A to itself within 0");
+    assert_eq!(a.find_path_within("C", "E", RelationshipKind::Subgraph, 1), None, "Found a subgraph path from C to E within 1");
+    assert_eq!(a.find_path_within("X", "F", RelationshipKind::Translation, usize::MAX), None, "Found a path from an unknown graph");
+
+    assert!(a.translates_to_within(&name("F"), 3), "A does not translate to F within 3");
+    assert!(!a.translates_to_within(&name("F"), 2), "A translates to F within 2");
+    assert!(a.translates_to(&name("F")), "A does not translate to F");
+    let mut c = name("C");
+    c.add_relationships(&a);
+    assert!(c.is_subgraph_of_within(&name("E"), 2), "C is not a subgraph of E within 2");
+    assert!(!c.is_subgraph_of_within(&name("E"), 1), "C is a subgraph of E within 1");
+    assert!(!GraphName::default().is_subgraph_of_within(&name("E"), 2), "A graph without a name is a subgraph of E");
+
+    // A dense registry, where each graph in a layer is a subgraph of each graph in the next layer.
+    let (layers, width) = (20, 30);
+    let mut registry = GraphName::default();
+    for layer in 0..layers - 1 {
+        for i in 0..width {
+            for j in 0..width {
+                registry.add_subgraph(&format!("{}-{}", layer, i), &format!("{}-{}", layer + 1, j));
+            }
+        }
+    }
+    let last = format!("{}-0", layers - 1);
+    let path = registry.find_path_within("0-0", &last, RelationshipKind::Subgraph, layers);
+    assert_eq!(path.map(|path| path.len()), Some(layers), "Wrong path length in a dense registry");
+    assert_eq!(registry.find_path_within("0-0", &last, RelationshipKind::Subgraph, layers - 2), None, "Found a path that is too long");
+    assert_eq!(registry.find_path_within(&last, "0-0", RelationshipKind::Subgraph, usize::MAX), None, "Found a path backwards");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn compact_string() {
+    let name = manual();
+    let value = name.to_string();
+    assert_eq!(value, "A;SG=A,B;SG=C,D;SG=D,E;TL=B,C;TL=C,F", "Wrong compact string");
+    assert_eq!(value.parse::<GraphName>(), Ok(name), "Round trip failed");
+
+    assert_eq!(GraphName::default().to_string(), "", "Wrong compact string for an empty object");
+    assert_eq!("".parse::<GraphName>(), Ok(GraphName::default()), "Wrong object from an empty string");
+    assert_eq!("A".parse::<GraphName>(), Ok(GraphName::new(String::from("A"))), "Wrong object with only a name");
+
+    let mut relationships = GraphName::default();
+    relationships.add_translation("B", "C");
+    assert_eq!(relationships.to_string(), ";TL=B,C", "Wrong compact string without a name");
+    assert_eq!(";TL=B,C".parse::<GraphName>(), Ok(relationships), "Wrong object without a name");
+
+    for value in ["SG=A,B", "A,B", "A;SG=A", "A;XX=A,B", "A;SG=A,", "A;", "A;;SG=A,B", "A;sg=A,B"] {
+        assert!(value.parse::<GraphName>().is_err(), "Accepted compact string {:?}", value);
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn builder() {
+    let f = GraphNameBuilder::new("F").build().unwrap();
+    let e = GraphNameBuilder::new("E").build().unwrap();
+    let d = GraphNameBuilder::new("D").subgraph_of(&e).build().unwrap();
+    let c = GraphNameBuilder::new("C").subgraph_of(&d).translates_to(&f).build().unwrap();
+    let b = GraphNameBuilder::new("B").translates_to(&c).build().unwrap();
+    let a = GraphNameBuilder::new(NAME).subgraph_of(&b).build().unwrap();
+    assert_eq!(a, from_parents(), "Builder and mutation produced different objects");
+
+    let mut builder = GraphNameBuilder::new(NAME);
+    for (subgraph, supergraph) in SUBGRAPH.iter() {
+        builder = builder.subgraph(subgraph, supergraph);
+    }
+    for (from, to) in TRANSLATION.iter() {
+        builder = builder.translation(from, to);
+    }
+    assert_eq!(builder.build(), Ok(manual()), "Wrong object from explicit relationships");
+
+    for name in ["", "A;B", "A,B", "A=B", "A\tB", "A\nB"] {
+        assert!(GraphNameBuilder::new(name).build().is_err(), "Accepted graph name {:?}", name);
+        assert!(GraphNameBuilder::new(NAME).subgraph("B", name).build().is_err(), "Accepted subgraph {:?}", name);
+        assert!(GraphNameBuilder::new(NAME).translation(name, "B").build().is_err(), "Accepted translation {:?}", name);
+    }
+    let unnamed = GraphName::default();
+    let result = GraphNameBuilder::new(NAME).subgraph_of(&unnamed).translates_to(&f).build();
+    assert_eq!(result, Err(String::from("Parent graph has no name")), "Wrong error for an unnamed parent");
+    let result = GraphNameBuilder::new("A;B").subgraph_of(&unnamed).build();
+    assert!(result.is_err_and(|e| e.starts_with("Invalid character")), "The first error was not reported");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn uris() {
+    let name = "54b49d18354a34fbd1af9aaac279e1b3ee67b2f68f0ff79f5ebf6c50c8d922a5";
+    let uri = format!("pggname:sha256:{}", name);
+    assert!(GraphName::is_stable_name(name), "Stable name not recognized");
+    assert_eq!(GraphName::uri_for(name), Some(uri.clone()), "Wrong URI");
+    assert_eq!(GraphName::uri_for("A"), None, "Created a URI for an arbitrary name");
+    assert_eq!(GraphName::uri_for(&name.to_ascii_uppercase()), None, "Created a URI for an upper case name");
+
+    for value in [uri.clone(), uri.to_ascii_uppercase(), format!("https://identifiers.org/{}", uri), format!("PGGNAME:SHA256:{}", name)] {
+        assert_eq!(GraphName::parse_uri(&value), Ok(String::from(name)), "Wrong name from URI {}", value);
+    }
+    let invalid = [
+        String::from(""), String::from(name), format!("pggname:{}", name), format!("pgg:sha256:{}", name),
+        format!("pggname:sha512:{}", name), format!("pggname:sha256:{}0", name), format!("pggname:sha256:{}", &name[1..]),
+        format!("pggname:sha256:{}g", &name[1..]), format!("{}/x", uri),
+    ];
+    for value in invalid {
+        assert!(GraphName::parse_uri(&value).is_err(), "Accepted URI {:?}", value);
+    }
+
+    let graph = GraphName::from_uri(&uri).unwrap();
+    assert_eq!(graph.name().map(String::as_str), Some(name), "Wrong name from URI");
+    assert_eq!(graph.uri(), Some(uri.clone()), "Wrong URI for the graph");
+    assert_eq!(graph.resolvable_uri("https://example.org/"), Some(format!("https://example.org/{}", uri)), "Wrong resolvable URI");
+    assert_eq!(GraphName::default().uri(), None, "Created a URI without a name");
+    assert_eq!(manual().resolvable_uri(GraphName::IDENTIFIERS_ORG_RESOLVER), None, "Created a URI for an arbitrary name");
+}
+
+//-----------------------------------------------------------------------------
+
+
+#[test]
+fn provenance() {
+    let verified = RelationshipProvenance { confidence: Confidence::Verified, tool: Some(String::from("vg")), timestamp: Some(1760000000) };
+    let asserted = RelationshipProvenance::default();
+
+    // Values.
+    let value = GraphName::provenance_value(RelationshipKind::Subgraph, "A", "B", &verified);
+    assert_eq!(value, "SG,A,B,verified,vg,1760000000", "Wrong provenance value");
+    assert_eq!(GraphName::parse_provenance_value(&value), Ok((RelationshipKind::Subgraph, "A", "B", verified.clone())), "Wrong parsed value");
+    let value = GraphName::provenance_value(RelationshipKind::Translation, "B", "C", &asserted);
+    assert_eq!(value, "TL,B,C,asserted,,", "Wrong provenance value without tool and timestamp");
+    assert_eq!(GraphName::parse_provenance_value(&value), Ok((RelationshipKind::Translation, "B", "C", asserted.clone())), "Wrong parsed value");
+    for invalid in ["", "SG,A,B,verified,vg", "XX,A,B,verified,,", "SG,,B,verified,,", "SG,A,B,trusted,,", "SG,A,B,verified,,x", "SG,A,B,verified,,,", "SG,A,B,verified,,253402300800", "SG,A,B,verified,,18446744073709551615"] {
+        assert!(GraphName::parse_provenance_value(invalid).is_err(), "Accepted provenance value {:?}", invalid);
+    }
+
+    // Setting provenance.
+    let mut name = manual();
+    assert_eq!(name.provenance(RelationshipKind::Subgraph, "A", "B"), None, "Provenance without setting it");
+    assert!(name.set_provenance(RelationshipKind::Subgraph, "A", "B", verified.clone()).is_ok(), "Could not set provenance");
+    assert!(name.set_provenance(RelationshipKind::Translation, "B", "C", asserted.clone()).is_ok(), "Could not set provenance");
+    assert_eq!(name.provenance(RelationshipKind::Subgraph, "A", "B"), Some(&verified), "Wrong subgraph provenance");
+    assert_eq!(name.provenance(RelationshipKind::Translation, "B", "C"), Some(&asserted), "Wrong translation provenance");
+    assert_eq!(name.provenance_iter().count(), 2, "Wrong number of relationships with provenance");
+    let bad_tool = RelationshipProvenance { tool: Some(String::from("a,b")), ..Default::default() };
+    assert!(name.set_provenance(RelationshipKind::Subgraph, "A", "X", bad_tool).is_err(), "Accepted an invalid tool name");
+    let bad_time = RelationshipProvenance { timestamp: Some(RelationshipProvenance::MAX_TIMESTAMP + 1), ..Default::default() };
+    assert!(name.set_provenance(RelationshipKind::Subgraph, "A", "X", bad_time).is_err(), "Accepted an out-of-range timestamp");
+    let max_time = GraphName::provenance_value(RelationshipKind::Subgraph, "A", "B", &RelationshipProvenance { timestamp: Some(RelationshipProvenance::MAX_TIMESTAMP), ..Default::default() });
+    assert!(GraphName::parse_provenance_value(&max_time).is_ok(), "Rejected the largest valid timestamp");
+    assert!(name.set_provenance(RelationshipKind::Subgraph, "", "X", asserted.clone()).is_err(), "Accepted an empty graph name");
+
+    let mut new_relationship = GraphName::new(String::from("X"));
+    new_relationship.set_provenance(RelationshipKind::Subgraph, "X", "Y", asserted.clone()).unwrap();
+    assert!(new_relationship.is_subgraph_of(&GraphName::new(String::from("Y"))), "Setting provenance did not add the relationship");
+
+    // Confidence.
+    let graph = |name: &str| GraphName::new(String::from(name));
+    assert_eq!(name.subgraph_confidence(&graph("B")), Some(Confidence::Verified), "Wrong confidence for a verified subgraph");
+    assert_eq!(graph("C").subgraph_confidence(&name), None, "Confidence for an unrelated graph");
+    let mut c = graph("C");
+    c.add_relationships(&name);
+    assert_eq!(c.subgraph_confidence(&graph("E")), Some(Confidence::Asserted), "Wrong confidence for an asserted subgraph");
+    assert_eq!(name.translation_confidence(&graph("B")), Some(Confidence::Verified), "Wrong confidence for a verified path");
+    assert_eq!(name.translation_confidence(&graph("C")), Some(Confidence::Asserted), "Wrong confidence for a partially verified path");
+
+    // Merging keeps the stronger evidence.
+    let mut merged = manual();
+    merged.set_provenance(RelationshipKind::Subgraph, "A", "B", asserted.clone()).unwrap();
+    merged.add_relationships(&name);
+    assert_eq!(merged.provenance(RelationshipKind::Subgraph, "A", "B"), Some(&verified), "Merging lost verified provenance");
+    let mut other = manual();
+    other.set_provenance(RelationshipKind::Subgraph, "A", "B", asserted.clone()).unwrap();
+    merged.add_relationships(&other);
+    assert_eq!(merged.provenance(RelationshipKind::Subgraph, "A", "B"), Some(&verified), "Merging replaced verified provenance");
+
+    // Serialization.
+    #[cfg(feature = "gbz")]
+    {
+        let mut tags = Tags::new();
+        name.set_tags(&mut tags);
+        assert_eq!(GraphName::from_tags(&tags), Ok(name.clone()), "Provenance was not preserved in tags");
+    }
+    let lines = name.to_gfa_header_lines();
+    assert!(lines.contains(&String::from("H\tPV:Z:SG,A,B,verified,vg,1760000000")), "Missing GFA provenance line");
+    assert_eq!(GraphName::from_header_lines(&lines), Ok(name.clone()), "Provenance was not preserved in GFA");
+    let lines = name.to_gaf_header_lines();
+    assert!(lines.iter().all(|line| GraphName::is_gaf_header_line(line)), "Unrecognized GAF header line");
+    assert_eq!(GraphName::from_header_lines(&lines), Ok(name.clone()), "Provenance was not preserved in GAF");
+    let lines = name.to_vcf_header_lines();
+    assert!(lines.iter().all(|line| GraphName::is_vcf_header_line(line)), "Unrecognized VCF header line");
+    assert_eq!(GraphName::from_vcf_header_lines(&lines), Ok(name.clone()), "Provenance was not preserved in VCF");
+    let compact = name.to_string();
+    assert!(compact.ends_with(";PV=SG,A,B,verified,vg,1760000000;PV=TL,B,C,asserted,,"), "Wrong compact form: {}", compact);
+    assert_eq!(compact.parse::<GraphName>(), Ok(name.clone()), "Provenance was not preserved in the compact form");
+    assert!("A;PV=SG,A,B,maybe,,".parse::<GraphName>().is_err(), "Accepted invalid provenance in the compact form");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn negative_assertions() {
+    let refuted = |timestamp| RelationshipProvenance { confidence: Confidence::Refuted, tool: Some(String::from("vg")), timestamp };
+    let verified = |timestamp| RelationshipProvenance { confidence: Confidence::Verified, tool: Some(String::from("vg")), timestamp };
+    let graph = |name: &str| GraphName::new(String::from(name));
+
+    // Refuting a relationship does not add it, and the relationship is not used even if asserted.
+    let mut name = graph("A");
+    name.set_provenance(RelationshipKind::Subgraph, "A", "B", refuted(Some(100))).unwrap();
+    assert_eq!(name.subgraph_iter().count(), 0, "Refuting a relationship added it");
+    assert!(name.is_refuted(RelationshipKind::Subgraph, "A", "B"), "Relationship was not refuted");
+    assert!(!name.is_refuted(RelationshipKind::Translation, "A", "B"), "Wrong kind of relationship was refuted");
+    name.add_subgraph("A", "B");
+    name.add_subgraph("B", "C");
+    assert!(!name.is_subgraph_of(&graph("B")), "Used a refuted relationship");
+    assert!(!name.translates_to(&graph("C")), "Used a refuted relationship in a path");
+    assert_eq!(name.subgraph_confidence(&graph("B")), Some(Confidence::Refuted), "Wrong confidence for a refuted relationship");
+    assert_eq!(name.subgraph_confidence(&graph("C")), None, "Wrong confidence for an unreachable graph");
+    assert_eq!(name.translation_confidence(&graph("B")), None, "Wrong confidence for a refuted subgraph as a translation");
+
+    // The refutation is also used when it is stored in the other graph.
+    let mut b = graph("B");
+    b.set_provenance(RelationshipKind::Subgraph, "A", "B", refuted(None)).unwrap();
+    assert!(manual().is_subgraph_of(&graph("B")), "Relationship not found");
+    assert!(!manual().is_subgraph_of(&b), "Ignored a refutation in the other graph");
+
+    // Newer results from verification supersede older ones.
+    assert!(refuted(Some(200)).supersedes(&verified(Some(100))), "A newer refutation did not supersede verification");
+    assert!(!refuted(Some(100)).supersedes(&verified(Some(200))), "An older refutation superseded verification");
+    assert!(verified(Some(100)).supersedes(&refuted(None)), "A timestamp did not supersede a missing timestamp");
+    assert!(refuted(None).supersedes(&RelationshipProvenance::default()), "Refutation did not supersede an assertion");
+    assert!(!RelationshipProvenance::default().supersedes(&refuted(None)), "An assertion superseded a refutation");
+    let mut merged = graph("A");
+    merged.set_provenance(RelationshipKind::Subgraph, "A", "B", verified(Some(50))).unwrap();
+    merged.add_relationships(&name);
+    assert!(merged.is_refuted(RelationshipKind::Subgraph, "A", "B"), "Merging lost a newer refutation");
+    assert!(!merged.is_subgraph_of(&graph("B")), "Used a relationship with a newer refutation");
+
+    // A refuted relationship is not implied by a path through other graphs.
+    let mut indirect = graph("A");
+    indirect.add_subgraph("A", "X");
+    indirect.add_subgraph("X", "B");
+    indirect.add_translation("A", "Y");
+    indirect.add_translation("Y", "B");
+    indirect.set_provenance(RelationshipKind::Subgraph, "A", "B", refuted(None)).unwrap();
+    indirect.set_provenance(RelationshipKind::Translation, "A", "B", refuted(None)).unwrap();
+    for max_depth in [2, usize::MAX] {
+        assert!(!indirect.is_subgraph_of_within(&graph("B"), max_depth), "Used a path for a refuted subgraph relationship");
+        assert!(!indirect.translates_to_within(&graph("B"), max_depth), "Used a path for a refuted translation relationship");
+    }
+    assert!(!indirect.is_subgraph_of(&graph("B")), "Used a path for a refuted subgraph relationship");
+    assert_eq!(indirect.subgraph_confidence(&graph("B")), Some(Confidence::Refuted), "Wrong subgraph confidence with a path");
+    assert!(!indirect.translates_to(&graph("B")), "Used a path for a refuted translation relationship");
+    assert_eq!(indirect.translation_confidence(&graph("B")), Some(Confidence::Refuted), "Wrong translation confidence with a path");
+    assert!(indirect.is_subgraph_of(&graph("X")), "Refutation blocked another relationship");
+    assert_eq!(indirect.subgraph_confidence(&graph("X")), Some(Confidence::Asserted), "Wrong confidence for another relationship");
+
+    // Serialization.
+    let lines = name.to_gfa_header_lines();
+    assert!(lines.contains(&String::from("H\tPV:Z:SG,A,B,refuted,vg,100")), "Missing GFA refutation line");
+    assert_eq!(GraphName::from_header_lines(&lines), Ok(name.clone()), "Refutation was not preserved in GFA");
+    assert_eq!(name.to_string().parse::<GraphName>(), Ok(name.clone()), "Refutation was not preserved in the compact form");
+}
+
+#[test]
+fn compatibility() {
+    let graph = |name: &str| GraphName::new(String::from(name));
+    let name = manual();
+    let tests = [
+        ("A", Compatibility::Same),
+        ("B", Compatibility::Subgraph(Confidence::Asserted)),
+        ("C", Compatibility::Translation(Confidence::Asserted)),
+        ("E", Compatibility::Translation(Confidence::Asserted)),
+        ("F", Compatibility::Translation(Confidence::Asserted)),
+        ("G", Compatibility::Incompatible),
+    ];
+    for (other, expected) in tests {
+        assert_eq!(name.compatibility(&graph(other)), expected, "Wrong compatibility with {}", other);
+    }
+    assert_eq!(graph("B").compatibility(&name), Compatibility::Incompatible, "Relationships were used in the wrong direction");
+    assert_eq!(GraphName::default().compatibility(&GraphName::default()), Compatibility::Incompatible, "Graphs without names are compatible");
+
+    // Verified relationships are reported as such, and refuted relationships are not used.
+    let mut name = graph("A");
+    let verified = RelationshipProvenance { confidence: Confidence::Verified, ..Default::default() };
+    name.set_provenance(RelationshipKind::Subgraph, "A", "B", verified).unwrap();
+    name.add_translation("A", "C");
+    assert_eq!(name.compatibility(&graph("B")), Compatibility::Subgraph(Confidence::Verified), "Wrong compatibility for a verified subgraph");
+    let refuted = RelationshipProvenance { confidence: Confidence::Refuted, ..Default::default() };
+    name.set_provenance(RelationshipKind::Translation, "A", "C", refuted).unwrap();
+    assert_eq!(name.compatibility(&graph("C")), Compatibility::Incompatible, "Used a refuted translation");
+    assert_eq!(Compatibility::Subgraph(Confidence::Verified).to_string(), "subgraph (verified)", "Wrong description");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn annotate_gfa_header() {
+    assert_eq!(GraphName::remove_gfa_header_fields("S\t1\tACGT"), Some(String::from("S\t1\tACGT")), "Changed a segment line");
+    assert_eq!(GraphName::remove_gfa_header_fields("H\tVN:Z:1.1"), Some(String::from("H\tVN:Z:1.1")), "Changed an unrelated header line");
+    assert_eq!(
+        GraphName::remove_gfa_header_fields("H\tNM:Z:old\tVN:Z:1.1\tSG:Z:old,B\tTL:Z:old,C\tPV:Z:SG,old,B,asserted,,"),
+        Some(String::from("H\tVN:Z:1.1")), "Did not remove name and relationship fields"
+    );
+    assert_eq!(GraphName::remove_gfa_header_fields("H\tNM:Z:old"), None, "Kept an empty header line");
+
+    let name = manual();
+    let fields = |name: &GraphName| -> Vec<String> {
+        name.to_gfa_header_lines().iter().map(|line| String::from(&line[2..])).collect()
+    };
+    let all = fields(&name);
+    let (sg, tl): (Vec<&String>, Vec<&String>) = all.iter().filter(|f| !f.starts_with("NM") && !f.starts_with("MV")).partition(|f| f.starts_with("SG"));
+    let header = vec![String::from("H\tNM:Z:old\tVN:Z:1.1"), String::from("H\tSG:Z:old,B"), String::from("H\tXX:i:1")];
+    let expected = vec![
+        format!("H\tVN:Z:1.1\tNM:Z:{}\tMV:Z:1\t{}\t{}", NAME, sg[0], tl[0]),
+        format!("H\t{}\t{}", sg[1], tl[1]),
+        format!("H\t{}", sg[2]),
+        String::from("H\tXX:i:1"),
+    ];
+    let annotated = GraphName::annotate_gfa_header_lines(&header, &name, None).unwrap();
+    assert_eq!(annotated, expected, "Wrong annotated header");
+    assert_eq!(GraphName::from_header_lines(&annotated), Ok(name.clone()), "The annotated header does not contain the name");
+    let annotated = GraphName::annotate_gfa_header_lines(&header, &name, Some(GfaVersion::V1_1)).unwrap();
+    assert_eq!(annotated, expected, "Wrong annotated header with a matching version");
+    assert!(GraphName::annotate_gfa_header_lines(&header, &name, Some(GfaVersion::V1_2)).is_err(), "Accepted a conflicting version");
+
+    // Without header lines.
+    let annotated = GraphName::annotate_gfa_header_lines(&[], &name, None).unwrap();
+    assert_eq!(annotated[0], format!("H\tNM:Z:{}\tMV:Z:1\t{}\t{}", NAME, sg[0], tl[0]), "Wrong header from empty input");
+    assert_eq!(GraphName::from_header_lines(&annotated), Ok(name.clone()), "The new header does not contain the name");
+    assert_eq!(GraphName::annotate_gfa_header_lines(&[], &GraphName::default(), None), Ok(Vec::new()), "Created an empty header line");
+    let header = vec![String::from("H\tXX:i:1")];
+    assert_eq!(
+        GraphName::annotate_gfa_header_lines(&header, &GraphName::default(), Some(GfaVersion::V1_0)),
+        Ok(vec![String::from("H\tVN:Z:1.0\tXX:i:1")]), "Did not declare the target version"
+    );
+
+    // Without a target version, the declared version is not validated.
+    let header = vec![String::from("H\tVN:Z:2.0"), String::from("H\tVN:Z:1.0\tNM:Z:old")];
+    assert_eq!(
+        GraphName::annotate_gfa_header_lines(&header, &GraphName::new(String::from("new")), None),
+        Ok(vec![String::from("H\tVN:Z:2.0\tNM:Z:new"), String::from("H\tVN:Z:1.0")]), "Validated the version without a target"
+    );
+    assert!(GraphName::annotate_gfa_header_lines(&header, &GraphName::default(), Some(GfaVersion::V1_0)).is_err(), "Accepted an unsupported version");
+
+    // Versions.
+    assert_eq!(GraphName::gfa_version(&[]), Ok(None), "Found a version without header lines");
+    for version in [GfaVersion::V1_0, GfaVersion::V1_1, GfaVersion::V1_2] {
+        let header = vec![format!("H\tVN:Z:{}", version.as_str()), String::from("S\tVN:Z:2.0")];
+        assert_eq!(GraphName::gfa_version(&header), Ok(Some(version)), "Wrong version {}", version.as_str());
+    }
+    assert!(GraphName::gfa_version(&[String::from("H\tVN:Z:2.0")]).is_err(), "Accepted GFA 2");
+    assert!(GraphName::gfa_version(&[String::from("H\tVN:Z:1.0"), String::from("H\tVN:Z:1.1")]).is_err(), "Accepted conflicting versions");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn metadata_versions() {
+    let mut expected = GraphName::new(String::from("A"));
+    expected.add_subgraph("A", "B");
+    let future = GraphName::METADATA_VERSION + 1;
+
+    // Values.
+    assert_eq!(GraphName::parse_metadata_version("1"), Ok(1), "Wrong parsed version");
+    for invalid in ["", "0", "-1", "x", "1.0"] {
+        assert!(GraphName::parse_metadata_version(invalid).is_err(), "Accepted metadata version {:?}", invalid);
+    }
+
+    // GFA header lines.
+    let lines = |version: Option<usize>| {
+        let mut lines = vec![String::from("H\tNM:Z:A\tSG:Z:A,B")];
+        if let Some(version) = version {
+            lines.push(format!("H\tMV:Z:{}", version));
+        }
+        lines.push(String::from("H\tPV:Z:XX,A,C,asserted,,\tSG:Z:A"));
+        lines
+    };
+    assert_eq!(GraphName::from_header_lines(&lines(Some(future))), Ok(expected.clone()), "Did not skip unknown GFA relationships");
+    assert!(GraphName::from_header_lines(&lines(None)).is_err(), "Accepted unknown GFA relationships without a version");
+    assert!(GraphName::from_header_lines(&lines(Some(GraphName::METADATA_VERSION))).is_err(), "Accepted unknown GFA relationships from the current version");
+    assert!(GraphName::from_header_lines(&[String::from("H\tNM:Z:A\tMV:Z:0")]).is_err(), "Accepted an invalid GFA metadata version");
+
+    // GAF header lines.
+    let lines = |version: Option<usize>| {
+        let mut lines = vec![String::from("@RN\tA"), String::from("@SG\tA\tB")];
+        if let Some(version) = version {
+            lines.push(format!("@MV\t{}", version));
+        }
+        lines.push(String::from("@TL\tA"));
+        lines
+    };
+    assert_eq!(GraphName::from_header_lines(&lines(Some(future))), Ok(expected.clone()), "Did not skip unknown GAF relationships");
+    assert!(GraphName::from_header_lines(&lines(None)).is_err(), "Accepted malformed GAF relationships without a version");
+
+    // VCF header lines.
+    let lines = |version: Option<usize>| {
+        let mut lines = vec![String::from("##pggname=A"), String::from("##pggname_subgraph=A,B")];
+        if let Some(version) = version {
+            lines.push(format!("##pggname_version={}", version));
+        }
+        lines.push(String::from("##pggname_provenance=SG,A,B,certain,,"));
+        lines
+    };
+    assert_eq!(GraphName::from_vcf_header_lines(&lines(Some(future))), Ok(expected.clone()), "Did not skip unknown VCF relationships");
+    assert!(GraphName::from_vcf_header_lines(&lines(None)).is_err(), "Accepted unknown VCF provenance without a version");
+    assert!(GraphName::from_vcf_header_lines(&[String::from("##pggname_version=x")]).is_err(), "Accepted an invalid VCF metadata version");
+
+    // Tags.
+    #[cfg(feature = "gbz")]
+    {
+        let mut tags = Tags::new();
+        tags.insert(GraphName::TAG_NAME, "A");
+        tags.insert(GraphName::TAG_SUBGRAPH, "A,B;C");
+        assert!(GraphName::from_tags(&tags).is_err(), "Accepted malformed tags without a version");
+        tags.insert(GraphName::TAG_METADATA_VERSION, &future.to_string());
+        assert_eq!(GraphName::from_tags(&tags), Ok(expected.clone()), "Did not skip malformed tags");
+    }
+
+    // The version is only written with relationships.
+    assert!(!GraphName::new(String::from("A")).to_gfa_header_lines().iter().any(|line| line.contains("MV:Z:")), "Wrote a version without relationships");
+    let lines = expected.to_gfa_header_lines();
+    assert!(lines.contains(&format!("H\tMV:Z:{}", GraphName::METADATA_VERSION)), "Missing GFA metadata version");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn rewrite_gaf() {
+    let records = "read1\t4\t0\t4\t+\t>1\t4\t0\t4\t4\t4\t60\r\n@read2\t4\t0\t4\t+\t>2\t4\t0\t4\t4\t4\t60";
+    let old = manual();
+
+    // Replace the identity lines and keep the other header lines and the records.
+    let mut input = old.to_gaf_header_lines().join("\n");
+    input.insert_str(0, "@HD\tVN:Z:1.0\n");
+    input.push_str("\n@CO\tcomment\n");
+    input.push_str(records);
+    let mut output: Vec<u8> = Vec::new();
+    let replaced = rewrite_gaf_headers(input.as_bytes(), &mut output, &from_parents()).unwrap();
+    assert_eq!(replaced, old, "Wrong replaced metadata");
+    let mut expected = from_parents().to_gaf_header_lines().join("\n");
+    expected.push_str("\n@HD\tVN:Z:1.0\n@CO\tcomment\n");
+    expected.push_str(records);
+    assert_eq!(String::from_utf8(output).unwrap(), expected, "Wrong rewritten GAF");
+
+    // Insert the lines into a file without a header.
+    let mut output: Vec<u8> = Vec::new();
+    let replaced = rewrite_gaf_headers(records.as_bytes(), &mut output, &old).unwrap();
+    assert_eq!(replaced, GraphName::default(), "Found metadata in a file without a header");
+    let mut expected = old.to_gaf_header_lines().join("\n");
+    expected.push('\n');
+    expected.push_str(records);
+    assert_eq!(String::from_utf8(output).unwrap(), expected, "Wrong GAF with inserted headers");
+
+    // Header only.
+    let mut output: Vec<u8> = Vec::new();
+    rewrite_gaf_headers(&b"@RN\tA\n"[..], &mut output, &GraphName::new(String::from("B"))).unwrap();
+    assert_eq!(output, b"@RN\tB\n", "Wrong header-only GAF");
+
+    // Invalid header.
+    let mut output: Vec<u8> = Vec::new();
+    assert!(rewrite_gaf_headers(&b"@RN\tA\n@SG\tA\n"[..], &mut output, &old).is_err(), "Accepted a malformed relationship");
+}
+
+#[test]
+fn graphs_in_gaf() {
+    let long = "7f4b28c71ceb808aebd8b8e9fe85e79d0d208ee263ffe9fcdef5ade20534ceb5";
+    let record = "read\t4\t0\t4\t+\t>1\t4\t0\t4\t4\t4\t60";
+    let names = |gaf: &str| GraphName::from_gaf(gaf.as_bytes()).unwrap().into_iter().collect::<Vec<_>>();
+
+    assert!(names("").is_empty(), "Found names in an empty file");
+    assert_eq!(names(&format!("{}\n", record)), Vec::<String>::new(), "Found names without a header or tags");
+    assert_eq!(names(&format!("@RN\t{}\n{}\n", long, record)), vec![String::from(long)], "Wrong names with a header");
+
+    // Tags with the short header name are not listed separately.
+    let gaf = format!("@RN\t{}\n{}\trn:Z:{}\n{}\trn:Z:B\n{}\trn:Z:A\n{}\trn:Z:B\n", long, record, &long[..16], record, record, record);
+    assert_eq!(names(&gaf), vec![String::from(long), String::from("A"), String::from("B")], "Wrong names with tagged records");
+
+    // Lines starting with @ after the first record are not header lines.
+    let gaf = format!("{}\trn:Z:A\n@RN\tC\n", record);
+    assert_eq!(names(&gaf), vec![String::from("A")], "Wrong names with a late header line");
+
+    assert!(GraphName::from_gaf(&b"@RN\tA\n@SG\tA\n"[..]).is_err(), "Accepted a malformed header");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn rewrite_gfa() {
+    let records = "S\t1\tACGT\r\nS\t2\tGA\nL\t1\t+\t2\t+\t*";
+    let rewrite = |input: &str, name: &GraphName, version: Option<GfaVersion>| -> Result<(GraphName, String), String> {
+        let mut output: Vec<u8> = Vec::new();
+        let old = rewrite_gfa_headers(input.as_bytes(), &mut output, |_| Ok(name.clone()), version)?;
+        Ok((old, String::from_utf8(output).unwrap()))
+    };
+    let name = from_parents();
+    let header = GraphName::annotate_gfa_header_lines(&[], &name, None).unwrap();
+
+    // No header lines.
+    let (old, output) = rewrite(records, &name, None).unwrap();
+    assert_eq!(old, GraphName::default(), "Found metadata in a file without a header");
+    assert_eq!(output, format!("{}\n{}", header.join("\n"), records), "Wrong GFA with an inserted header");
+    let (_, output) = rewrite(records, &GraphName::default(), Some(GfaVersion::V1_1)).unwrap();
+    assert_eq!(output, format!("H\tVN:Z:1.1\n{}", records), "Wrong GFA with a version header");
+
+    // One header line with a stale name.
+    let input = format!("H\tVN:Z:1.0\tNM:Z:old\n{}", records);
+    let (old, output) = rewrite(&input, &GraphName::new(String::from("new")), None).unwrap();
+    assert_eq!(old, GraphName::new(String::from("old")), "Wrong replaced metadata");
+    assert_eq!(output, format!("H\tVN:Z:1.0\tNM:Z:new\n{}", records), "Wrong GFA with one header line");
+    assert!(rewrite(&input, &name, Some(GfaVersion::V1_2)).is_err(), "Accepted a conflicting GFA version");
+    let input = format!("H\tVN:Z:2.0\n{}", records);
+    let (_, output) = rewrite(&input, &GraphName::new(String::from("new")), None).unwrap();
+    assert_eq!(output, format!("H\tVN:Z:2.0\tNM:Z:new\n{}", records), "Validated the GFA version without a target");
+
+    // Multiple header lines, including one after the first record.
+    let mut input = manual().to_gfa_header_lines();
+    input.insert(0, String::from("H\tVN:Z:1.1"));
+    let input = format!("{}\n{}\nH\tNM:Z:{}\tCO:Z:late\nH\tSG:Z:A,B\n", input.join("\n"), records, NAME);
+    let (old, output) = rewrite(&input, &name, None).unwrap();
+    assert_eq!(old, manual(), "Wrong replaced metadata from multiple lines");
+    let expected = GraphName::annotate_gfa_header_lines(&[String::from("H\tVN:Z:1.1")], &name, None).unwrap();
+    assert_eq!(output, format!("{}\n{}\nH\tCO:Z:late\n", expected.join("\n"), records), "Wrong GFA with multiple header lines");
+
+    // The new metadata is derived from the old metadata.
+    let input = format!("H\tNM:Z:old\tSG:Z:old,A\n{}", records);
+    let mut output: Vec<u8> = Vec::new();
+    let old = rewrite_gfa_headers(input.as_bytes(), &mut output, |old| {
+        let mut new = GraphName::new(String::from("new"));
+        new.add_relationships(old);
+        new.add_translation("new", "old");
+        Ok(new)
+    }, None).unwrap();
+    assert_eq!(old.name(), Some(&String::from("old")), "Wrong old metadata from an update");
+    let mut expected = GraphName::new(String::from("new"));
+    expected.add_subgraph("old", "A");
+    expected.add_translation("new", "old");
+    let expected = GraphName::annotate_gfa_header_lines(&[String::from("H")], &expected, None).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), format!("{}\n{}", expected.join("\n"), records), "Wrong GFA from an update");
+    let failed = rewrite_gfa_headers(input.as_bytes(), io::sink(), |_| Err(String::from("failed")), None);
+    assert_eq!(failed, Err(String::from("failed")), "An update error was not returned");
+
+    // Invalid header.
+    assert!(rewrite("H\tMV:Z:1\tSG:Z:A\n", &name, None).is_err(), "Accepted a malformed relationship");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn relationship_kinds() {
+    for kind in [RelationshipKind::Subgraph, RelationshipKind::Translation] {
+        assert_eq!(RelationshipKind::from_tag(kind.tag()), Some(kind), "Wrong kind for tag {}", kind.tag());
+    }
+    assert_eq!(RelationshipKind::from_tag(GraphName::GFA_GAF_HEADER_PROVENANCE), None, "Accepted a non-relationship tag");
+    assert_eq!("subgraph".parse::<RelationshipKind>(), Ok(RelationshipKind::Subgraph), "Wrong kind for subgraph");
+    assert_eq!("translation".parse::<RelationshipKind>(), Ok(RelationshipKind::Translation), "Wrong kind for translation");
+    assert!("SG".parse::<RelationshipKind>().is_err(), "Parsed a tag as a relationship kind");
+
+    let mut name = GraphName::default();
+    name.add_relationship(RelationshipKind::Subgraph, "A", "B");
+    name.add_relationship(RelationshipKind::Translation, "B", "C");
+    name.add_relationship(RelationshipKind::Translation, "", "C");
+    assert!(name.subgraph_iter().eq([("A", "B")]), "Wrong subgraph relationships");
+    assert!(name.translation_iter().eq([("B", "C")]), "Wrong translation relationships");
+}
+
+#[test]
+fn relationship_conflicts() {
+    let refuted = RelationshipProvenance { confidence: Confidence::Refuted, tool: Some(String::from("vg")), timestamp: Some(1760000000) };
+    let verified = RelationshipProvenance { confidence: Confidence::Verified, ..refuted.clone() };
+
+    // Merging consistent relationships reports nothing and is the same as the unchecked merge.
+    let mut checked = manual();
+    let mut unchecked = manual();
+    assert!(checked.add_relationships_checked(&from_parents()).is_empty(), "Found conflicts in consistent relationships");
+    unchecked.add_relationships(&from_parents());
+    assert_eq!(checked, unchecked, "Checked merge differs from the unchecked merge");
+
+    // A relationship claimed by one source and refuted by the other, in both directions.
+    let mut claimed = GraphName::new(String::from("A"));
+    claimed.add_subgraph("A", "B");
+    claimed.set_provenance(RelationshipKind::Translation, "A", "C", verified).unwrap();
+    let mut refuting = GraphName::default();
+    refuting.set_provenance(RelationshipKind::Subgraph, "A", "B", refuted.clone()).unwrap();
+    let mut other_claims = GraphName::default();
+    other_claims.set_provenance(RelationshipKind::Translation, "A", "C", refuted).unwrap();
+    let expected = |kind, to: &str| vec![RelationshipConflict::Refuted { kind, from: String::from("A"), to: String::from(to) }];
+    assert_eq!(claimed.clone().add_relationships_checked(&refuting), expected(RelationshipKind::Subgraph, "B"), "Missed a refuted subgraph");
+    assert_eq!(claimed.clone().add_relationships_checked(&other_claims), expected(RelationshipKind::Translation, "C"), "Missed a refuted translation");
+    assert_eq!(refuting.clone().add_relationships_checked(&claimed), expected(RelationshipKind::Subgraph, "B"), "Missed a claim of a refuted subgraph");
+
+    // Mutual subgraphs are reported once, and mutual translations are not conflicts.
+    let mut both = GraphName::default();
+    both.add_subgraph("A", "B");
+    both.add_subgraph("B", "A");
+    both.add_translation("A", "C");
+    both.add_translation("C", "A");
+    let conflicts = GraphName::default().add_relationships_checked(&both);
+    assert_eq!(
+        conflicts, vec![RelationshipConflict::MutualSubgraph { first: String::from("A"), second: String::from("B") }],
+        "Wrong conflicts for mutual relationships"
+    );
+    assert_eq!(conflicts[0].to_string(), "graphs A and B are subgraphs of each other", "Wrong conflict description");
+}
+
 //-----------------------------------------------------------------------------
@@ -0,0 +1,146 @@
+//! Packed storage for node sequences.
+//!
+//! A [`PackedSequence`] stores the bases `A`, `C`, `G`, and `T` using 2 bits each.
+//! Case is folded before packing, and lower case regions (such as soft-masked repeats) are stored as runs.
+//! All other characters, such as `N`, are stored in a list of exception runs.
+//! The original sequence is always recovered exactly, which makes the packed representation suitable for computing stable names.
+//!
+//! For sequences consisting mostly of `ACGT` in long runs of the same case, the packed representation takes roughly a quarter of the space of the plain sequence.
+//! Use [`PackedSequence::saves_space`] to check whether packing is worthwhile for a given sequence.
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// A sequence packed using 2 bits per base, with exceptions for other characters.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::packed::PackedSequence;
+///
+/// let packed = PackedSequence::pack(b"GATTACANNNNacgt");
+/// assert_eq!(packed.len(), 15);
+/// assert_eq!(packed.exceptions(), 1); // One run of N.
+/// assert_eq!(packed.lower_case_runs(), 1);
+/// assert_eq!(packed.unpack(), b"GATTACANNNNacgt");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PackedSequence {
+    // Length of the sequence.
+    len: usize,
+    // Bases packed 4 per byte, starting from the low-order bits.
+    // Exception positions are encoded as `A`.
+    data: Vec<u8>,
+    // Runs of other characters as (start, length, upper case character), in sorted order.
+    exceptions: Vec<(usize, usize, u8)>,
+    // Runs of lower case characters as (start, length), in sorted order.
+    lower_case: Vec<(usize, usize)>,
+}
+
+impl PackedSequence {
+    /// Number of bases packed in a byte.
+    pub const BASES_PER_BYTE: usize = 4;
+
+    // Decoding table from 2-bit codes to bases.
+    const DECODE: [u8; 4] = [b'A', b'C', b'G', b'T'];
+
+    // Returns the 2-bit code for the upper case base, or `None` if the character must be stored as an exception.
+    fn encode(c: u8) -> Option<u8> {
+        match c {
+            b'A' => Some(0),
+            b'C' => Some(1),
+            b'G' => Some(2),
+            b'T' => Some(3),
+            _ => None,
+        }
+    }
+
+    /// Packs the sequence.
+    pub fn pack(sequence: &[u8]) -> Self {
+        let mut data = vec![0; sequence.len().div_ceil(Self::BASES_PER_BYTE)];
+        let mut exceptions: Vec<(usize, usize, u8)> = Vec::new();
+        let mut lower_case: Vec<(usize, usize)> = Vec::new();
+        for (i, &c) in sequence.iter().enumerate() {
+            if c.is_ascii_lowercase() {
+                match lower_case.last_mut() {
+                    Some((start, len)) if *start + *len == i => *len += 1,
+                    _ => lower_case.push((i, 1)),
+                }
+            }
+            let c = c.to_ascii_uppercase();
+            match Self::encode(c) {
+                Some(code) => data[i / Self::BASES_PER_BYTE] |= code << (2 * (i % Self::BASES_PER_BYTE)),
+                None => match exceptions.last_mut() {
+                    Some((start, len, prev)) if *prev == c && *start + *len == i => *len += 1,
+                    _ => exceptions.push((i, 1, c)),
+                },
+            }
+        }
+        exceptions.shrink_to_fit();
+        lower_case.shrink_to_fit();
+        PackedSequence { len: sequence.len(), data, exceptions, lower_case }
+    }
+
+    /// Returns `true` if the packed sequence takes less space than the plain sequence.
+    ///
+    /// This is the case when the sequence consists mostly of `ACGT` in long runs of the same case.
+    pub fn saves_space(&self) -> bool {
+        self.size_in_bytes() < self.len
+    }
+
+    /// Returns the length of the sequence.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of exception runs in the sequence.
+    pub fn exceptions(&self) -> usize {
+        self.exceptions.len()
+    }
+
+    /// Returns the number of lower case runs in the sequence.
+    pub fn lower_case_runs(&self) -> usize {
+        self.lower_case.len()
+    }
+
+    /// Returns the approximate size of the packed sequence in bytes, excluding the struct itself.
+    pub fn size_in_bytes(&self) -> usize {
+        self.data.capacity()
+            + self.exceptions.capacity() * std::mem::size_of::<(usize, usize, u8)>()
+            + self.lower_case.capacity() * std::mem::size_of::<(usize, usize)>()
+    }
+
+    /// Appends the original sequence to the buffer.
+    pub fn unpack_into(&self, buffer: &mut Vec<u8>) {
+        let offset = buffer.len();
+        buffer.reserve(self.len);
+        for (i, &byte) in self.data.iter().enumerate() {
+            let count = (self.len - i * Self::BASES_PER_BYTE).min(Self::BASES_PER_BYTE);
+            for j in 0..count {
+                buffer.push(Self::DECODE[((byte >> (2 * j)) & 3) as usize]);
+            }
+        }
+        for &(start, len, c) in self.exceptions.iter() {
+            buffer[offset + start..offset + start + len].fill(c);
+        }
+        for &(start, len) in self.lower_case.iter() {
+            buffer[offset + start..offset + start + len].make_ascii_lowercase();
+        }
+    }
+
+    /// Returns the original sequence.
+    pub fn unpack(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.len);
+        self.unpack_into(&mut result);
+        result
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,83 @@
+use super::*;
+
+use rand::Rng;
+
+//-----------------------------------------------------------------------------
+
+fn check_roundtrip(sequence: &[u8], exceptions: usize, lower_case_runs: usize) {
+    let packed = PackedSequence::pack(sequence);
+    let name = String::from_utf8_lossy(sequence);
+    assert_eq!(packed.len(), sequence.len(), "Wrong length for {}", name);
+    assert_eq!(packed.is_empty(), sequence.is_empty(), "Wrong emptiness for {}", name);
+    assert_eq!(packed.exceptions(), exceptions, "Wrong number of exception runs for {}", name);
+    assert_eq!(packed.lower_case_runs(), lower_case_runs, "Wrong number of lower case runs for {}", name);
+    assert_eq!(packed.unpack(), sequence, "Wrong unpacked sequence for {}", name);
+
+    let mut buffer = b"prefix".to_vec();
+    packed.unpack_into(&mut buffer);
+    assert_eq!(&buffer[..6], b"prefix", "Unpacking overwrote the buffer for {}", name);
+    assert_eq!(&buffer[6..], sequence, "Wrong sequence appended to the buffer for {}", name);
+}
+
+#[test]
+fn empty_sequence() {
+    check_roundtrip(b"", 0, 0);
+    assert_eq!(PackedSequence::pack(b""), PackedSequence::default(), "Empty sequence is not the default");
+}
+
+#[test]
+fn bases_only() {
+    check_roundtrip(b"A", 0, 0);
+    check_roundtrip(b"ACGT", 0, 0);
+    check_roundtrip(b"GATTACA", 0, 0);
+    check_roundtrip(b"TTTTTTTTT", 0, 0);
+}
+
+#[test]
+fn exceptions() {
+    check_roundtrip(b"N", 1, 0);
+    check_roundtrip(b"*", 1, 0);
+    check_roundtrip(b"ACGTNNNNNNACGT", 1, 0);
+    check_roundtrip(b"NNAANN", 2, 0);
+    check_roundtrip(b"NRNN", 3, 0);
+}
+
+#[test]
+fn lower_case() {
+    check_roundtrip(b"acgt", 0, 1);
+    check_roundtrip(b"ACGTacgtACGT", 0, 1);
+    check_roundtrip(b"aCgT", 0, 2);
+    check_roundtrip(b"acgtnnnnacgt", 1, 1);
+    check_roundtrip(b"ACGTNnNACGT", 1, 1);
+}
+
+#[test]
+fn random_sequences() {
+    let mut rng = rand::rng();
+    let alphabet = b"ACGTACGTACGTacgtNnR";
+    for len in 0..100 {
+        let sequence: Vec<u8> = (0..len).map(|_| alphabet[rng.random_range(0..alphabet.len())]).collect();
+        let packed = PackedSequence::pack(&sequence);
+        assert_eq!(packed.unpack(), sequence, "Wrong unpacked sequence for {}", String::from_utf8_lossy(&sequence));
+    }
+}
+
+#[test]
+fn packed_size() {
+    let sequence: Vec<u8> = b"GATTACA".iter().cycle().take(1000).copied().collect();
+    let packed = PackedSequence::pack(&sequence);
+    assert_eq!(packed.size_in_bytes(), 250, "Wrong packed size");
+    assert!(packed.saves_space(), "Packing a plain sequence did not save space");
+
+    let masked: Vec<u8> = sequence.iter().take(500).copied().chain(sequence[500..].to_ascii_lowercase()).collect();
+    let packed = PackedSequence::pack(&masked);
+    assert_eq!(packed.size_in_bytes(), 250 + 16, "Wrong packed size for a soft-masked sequence");
+    assert!(packed.saves_space(), "Packing a soft-masked sequence did not save space");
+
+    let unknown = vec![b'N'; 1000];
+    assert!(PackedSequence::pack(&unknown).saves_space(), "Packing a run of N did not save space");
+    let alternating: Vec<u8> = b"NA".iter().cycle().take(1000).copied().collect();
+    assert!(!PackedSequence::pack(&alternating).saves_space(), "Packing alternating N and A saved space");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,562 @@
+//! Digests of the path collection stored with a graph.
+//!
+//! The graph name identifies only the graph itself.
+//! Two files with the same graph may still contain different sets of paths.
+//! The path-set digest is a secondary identifier computed over the paths in a canonical representation.
+//!
+//! Each path is represented as a single line:
+//!
+//! * Named paths (GFA P-lines) become `P`, path name, and the path as a walk (e.g. `>1<2>3`).
+//! * Walks (GFA W-lines) become `W`, sample name, haplotype, contig name, start, end, and the walk.
+//!
+//! Fields are separated by tabs.
+//! P-line overlaps and optional fields are ignored.
+//! The lines are sorted in lexicographic order, and the digest is the hash of the sorted lines, each terminated by a newline.
+//!
+//! GBZ paths are represented in the same way as in the GFA output of `gbunzip`: generic paths become P-lines named after the contig, and other paths become W-lines.
+//! Segment names are used when the GBZ graph has a node-to-segment translation.
+//! Hence a GBZ graph and a GFA file extracted from it have the same path-set digest.
+//!
+//! # Haplotype-set names
+//!
+//! The haplotype-set name is a digest of the GBWT path collection in a GBZ or GBWT file.
+//! Unlike the path-set digest, it always uses GBWT node identifiers, as indexes built for the GBWT depend on them.
+//! Each path is represented as a line with sample name, contig name, haplotype/phase, fragment, and the path as a walk over node identifiers.
+//! Generic paths use the sample name `_gbwt_ref`.
+//! The lines are sorted and hashed in the same way as for the path-set digest.
+//!
+//! # Per-contig digests
+//!
+//! Workflows that split a graph by chromosome can verify each chunk separately.
+//! For each contig of a reference sample in a GBZ graph, [`contig_digests_gbz`] computes the stable name of the subgraph induced by the nodes visited by the reference paths of that contig.
+//! The subgraph uses GBWT node identifiers.
+//! Similarly, [`path_prefix_subgraph_gbz`] selects the subgraph touched by paths with a given name prefix, such as the paths of a reference sample.
+//! [`sample_subgraph_gbz`] selects the subgraph touched by the paths of a set of samples, such as a cohort.
+//!
+//! # Composite names
+//!
+//! Some tools depend on both the graph and the paths embedded in it.
+//! The composite name identifies the combination as the SHA-256 hash of the graph name concatenated with the path-set digest, both as lowercase hexadecimal strings.
+
+use crate::algorithms::{self, split_bytes};
+#[cfg(feature = "gbz")]
+use crate::Graph;
+#[cfg(feature = "gbz")]
+use crate::graph::GraphInt;
+
+#[cfg(feature = "gbz")]
+use gbz::{GBWT, GBZ, Metadata, Orientation, PathName, GENERIC_SAMPLE};
+#[cfg(feature = "gbz")]
+use gbz::support;
+
+use sha2::Digest;
+use sha2::digest;
+
+#[cfg(feature = "gbz")]
+use simple_sds::serialize;
+
+#[cfg(feature = "gbz")]
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Computes the given hash of the canonical path records in the given GFA input.
+///
+/// Returns an error if a P-line or a W-line cannot be parsed.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::paths;
+/// use sha2::Sha256;
+///
+/// let gfa = b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\nP\tx\t1+,2+\t*\n";
+/// let same = b"P\tx\t1+,2+\t0M\nS\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n";
+/// let digest = paths::path_digest_gfa::<Sha256, _>(gfa.as_slice()).unwrap();
+/// assert_eq!(paths::path_digest_gfa::<Sha256, _>(same.as_slice()), Ok(digest));
+/// ```
+pub fn path_digest_gfa<D: Digest, R: BufRead>(reader: R) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let mut records: Vec<Vec<u8>> = Vec::new();
+    for (i, line) in reader.split(b'\n').enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        let line = algorithms::trim_line_end(&line);
+        let record = match line.first() {
+            Some(b'P') => p_line_record(line),
+            Some(b'W') => w_line_record(line),
+            _ => continue,
+        };
+        let record = record.map_err(|e| format!("Error parsing GFA line {}: {}", i + 1, e))?;
+        records.push(record);
+    }
+    Ok(digest_records::<D>(records))
+}
+
+/// Computes the given hash of the canonical path records in the given GBZ graph.
+///
+/// Returns an error if the graph does not have path metadata.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::paths;
+/// use gbz::{GBZ, support};
+/// use sha2::Sha256;
+/// use simple_sds::serialize;
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+/// let reader = BufReader::new(File::open(support::get_test_data("example.gfa")).unwrap());
+/// let digest = paths::path_digest_gbz::<Sha256>(&gbz).unwrap();
+/// assert_eq!(paths::path_digest_gfa::<Sha256, _>(reader), Ok(digest));
+/// ```
+#[cfg(feature = "gbz")]
+pub fn path_digest_gbz<D: Digest>(gbz: &GBZ) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let metadata = gbz.metadata().ok_or(String::from("The GBZ graph does not have path metadata"))?;
+    let generic_sample = metadata.sample_id(GENERIC_SAMPLE);
+
+    let mut records: Vec<Vec<u8>> = Vec::with_capacity(metadata.paths());
+    for (path_id, path_name) in metadata.path_iter().enumerate() {
+        let (walk, len) = gbz_walk(gbz, path_id)?;
+        let mut record: Vec<u8> = Vec::new();
+        if Some(path_name.sample()) == generic_sample {
+            record.extend_from_slice(b"P\t");
+            record.extend_from_slice(metadata.contig_name(path_name.contig()).as_bytes());
+        } else {
+            record.extend_from_slice(b"W\t");
+            record.extend_from_slice(metadata.sample_name(path_name.sample()).as_bytes());
+            let fields = format!(
+                "\t{}\t{}\t{}\t{}",
+                path_name.phase(), metadata.contig_name(path_name.contig()),
+                path_name.fragment(), path_name.fragment() + len
+            );
+            record.extend_from_slice(fields.as_bytes());
+        }
+        record.push(b'\t');
+        record.extend_from_slice(&walk);
+        records.push(record);
+    }
+
+    Ok(digest_records::<D>(records))
+}
+
+/// Computes the given hash of the canonical path records in the given file.
+///
+/// The file may be a GBZ file, a GFA file, or a gzip-compressed GFA file.
+/// GBZ files are only recognized with the `gbz` feature.
+/// Returns an error if the file cannot be read or parsed.
+pub fn path_digest_file<D: Digest, P: AsRef<Path>>(filename: P) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let filename = filename.as_ref();
+    #[cfg(feature = "gbz")]
+    if GBZ::is_gbz(filename) {
+        let graph: GBZ = serialize::load_from(filename)
+            .map_err(|e| format!("Error loading GBZ file {}: {}", filename.display(), e))?;
+        return path_digest_gbz::<D>(&graph);
+    }
+    path_digest_gfa::<D, _>(algorithms::open_gfa(filename)?)
+}
+
+//-----------------------------------------------------------------------------
+
+/// Computes the given hash of the GBWT path collection in the given GBZ graph.
+///
+/// This is the haplotype-set name of the graph.
+/// Returns an error if the graph does not have path metadata.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::paths;
+/// use gbz::{GBWT, GBZ, support};
+/// use sha2::Sha256;
+/// use simple_sds::serialize;
+///
+/// let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+/// let gbwt: GBWT = serialize::load_from(support::get_test_data("example.gbwt")).unwrap();
+/// let name = paths::haplotype_digest_gbz::<Sha256>(&gbz).unwrap();
+/// assert_eq!(paths::haplotype_digest_gbwt::<Sha256>(&gbwt), Ok(name));
+/// ```
+#[cfg(feature = "gbz")]
+pub fn haplotype_digest_gbz<D: Digest>(gbz: &GBZ) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let metadata = gbz.metadata().ok_or(String::from("The GBZ graph does not have path metadata"))?;
+    haplotype_digest::<D, _, _>(metadata, |path_id| gbz.path(path_id, Orientation::Forward))
+}
+
+/// Computes the given hash of the path collection in the given GBWT index.
+///
+/// This is the haplotype-set name of the index.
+/// Returns an error if the index does not have path metadata.
+#[cfg(feature = "gbz")]
+pub fn haplotype_digest_gbwt<D: Digest>(gbwt: &GBWT) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let metadata = gbwt.metadata().ok_or(String::from("The GBWT index does not have path metadata"))?;
+    haplotype_digest::<D, _, _>(metadata, |path_id| {
+        let sequence_id = if gbwt.is_bidirectional() { support::encode_path(path_id, Orientation::Forward) } else { path_id };
+        gbwt.sequence(sequence_id).map(|iter| iter.map(support::decode_node))
+    })
+}
+
+/// Computes the given hash of the GBWT path collection in the given GBZ or GBWT file.
+///
+/// Returns an error if the file cannot be loaded or if it does not have path metadata.
+#[cfg(feature = "gbz")]
+pub fn haplotype_digest_file<D: Digest, P: AsRef<Path>>(filename: P) -> Result<String, String>
+    where digest::Output<D>: core::fmt::LowerHex {
+    let filename = filename.as_ref();
+    if GBZ::is_gbz(filename) {
+        let graph: GBZ = serialize::load_from(filename)
+            .map_err(|e| format!("Error loading GBZ file {}: {}", filename.display(), e))?;
+        haplotype_digest_gbz::<D>(&graph)
+    } else {
+        let index: GBWT = serialize::load_from(filename)
+            .map_err(|e| format!("Error loading GBWT file {}: {}", filename.display(), e))?;
+        haplotype_digest_gbwt::<D>(&index)
+    }
+}
+
+#[cfg(feature = "gbz")]
+fn haplotype_digest<D, F, I>(metadata: &Metadata, path: F) -> Result<String, String>
+    where D: Digest, digest::Output<D>: core::fmt::LowerHex,
+    F: Fn(usize) -> Option<I>, I: Iterator<Item = (usize, Orientation)> {
+    let mut records: Vec<Vec<u8>> = Vec::with_capacity(metadata.paths());
+    for (path_id, path_name) in metadata.path_iter().enumerate() {
+        let mut record = format!(
+            "{}\t{}\t{}\t{}\t",
+            metadata.sample_name(path_name.sample()), metadata.contig_name(path_name.contig()),
+            path_name.phase(), path_name.fragment()
+        ).into_bytes();
+        let iter = path(path_id).ok_or(format!("Missing path {}", path_id))?;
+        for (node_id, orientation) in iter {
+            record.push(match orientation {
+                Orientation::Forward => b'>',
+                Orientation::Reverse => b'<',
+            });
+            record.extend_from_slice(node_id.to_string().as_bytes());
+        }
+        records.push(record);
+    }
+    Ok(digest_records::<D>(records))
+}
+
+//-----------------------------------------------------------------------------
+
+/// Stable name of the subgraph touched by the reference paths of a contig.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContigDigest {
+    /// Reference sample name.
+    pub sample: String,
+    /// Contig name.
+    pub contig: String,
+    /// Number of reference paths (fragments) for the contig.
+    pub paths: usize,
+    /// Number of nodes in the subgraph.
+    pub nodes: usize,
+    /// Stable name of the subgraph.
+    pub name: String,
+}
+
+/// Header line of the per-contig digest table.
+pub const CONTIG_DIGEST_HEADER: &str = "#sample\tcontig\tpaths\tnodes\tname";
+
+/// Computes the stable name of the subgraph touched by each reference contig in the GBZ graph.
+///
+/// The reference samples are the generic sample and the samples listed in the `reference_samples` GBWT tag.
+/// For each (sample, contig) pair, the subgraph consists of the nodes visited by the paths and all edges between them.
+/// Node names are GBWT node identifiers, as in [`crate::graph::GBZInt`].
+/// The results are sorted by sample name and contig name.
+/// Returns an error if the graph does not have path metadata.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::paths;
+/// use gbz::{GBZ, support};
+/// use simple_sds::serialize;
+///
+/// let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+/// let digests = paths::contig_digests_gbz(&gbz).unwrap();
+/// let contigs: Vec<&str> = digests.iter().map(|digest| digest.contig.as_str()).collect();
+/// assert_eq!(contigs, vec!["A", "B"]);
+/// assert_eq!(digests[0].nodes, 5);
+/// ```
+#[cfg(feature = "gbz")]
+pub fn contig_digests_gbz(gbz: &GBZ) -> Result<Vec<ContigDigest>, String> {
+    let metadata = gbz.metadata().ok_or(String::from("The GBZ graph does not have path metadata"))?;
+    let reference_samples = gbz.reference_sample_ids(true);
+
+    // Nodes visited by the paths of each (sample, contig) pair, and the number of paths.
+    let mut contigs: BTreeMap<(String, String), (BTreeSet<usize>, usize)> = BTreeMap::new();
+    for (path_id, path_name) in metadata.path_iter().enumerate() {
+        if !reference_samples.contains(&path_name.sample()) {
+            continue;
+        }
+        let key = (metadata.sample_name(path_name.sample()), metadata.contig_name(path_name.contig()));
+        let (nodes, paths) = contigs.entry(key).or_default();
+        let iter = gbz.path(path_id, Orientation::Forward).ok_or(format!("Missing path {}", path_id))?;
+        nodes.extend(iter.map(|(node_id, _)| node_id));
+        *paths += 1;
+    }
+
+    let mut result = Vec::with_capacity(contigs.len());
+    for ((sample, contig), (nodes, paths)) in contigs {
+        let subgraph = induced_subgraph_gbz(gbz, &nodes)?;
+        let name = algorithms::stable_name(&subgraph);
+        result.push(ContigDigest { sample, contig, paths, nodes: nodes.len(), name });
+    }
+
+    Ok(result)
+}
+
+/// Returns the subgraph touched by the paths whose names start with the given prefix.
+///
+/// Path names are matched in the same form as in GFA output: generic paths are named after the contig, and other paths use the PanSN form `sample#haplotype#contig`.
+/// For example, prefix `GRCh38#` selects all paths of sample `GRCh38`.
+/// The subgraph consists of the nodes visited by the matching paths and all edges between them.
+/// Node names are GBWT node identifiers, as in [`crate::graph::GBZInt`].
+/// Returns the subgraph and the number of matching paths.
+/// Returns an error if the graph does not have path metadata or if no path matches.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::paths;
+/// use gbz::{GBZ, support};
+/// use simple_sds::serialize;
+///
+/// let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+/// let (subgraph, paths) = paths::path_prefix_subgraph_gbz(&gbz, "sample#1#").unwrap();
+/// assert_eq!(paths, 2);
+/// assert!(pggname::Graph::statistics(&subgraph).nodes > 0);
+/// assert!(paths::path_prefix_subgraph_gbz(&gbz, "other#").is_err());
+/// ```
+#[cfg(feature = "gbz")]
+pub fn path_prefix_subgraph_gbz(gbz: &GBZ, prefix: &str) -> Result<(GraphInt, usize), String> {
+    let metadata = gbz.metadata().ok_or(String::from("The GBZ graph does not have path metadata"))?;
+    let generic_sample = metadata.sample_id(GENERIC_SAMPLE);
+
+    let (subgraph, paths) = selected_paths_subgraph_gbz(gbz, |path_id, path_name| {
+        let name = if Some(path_name.sample()) == generic_sample {
+            metadata.contig_name(path_name.contig())
+        } else {
+            metadata.pan_sn_path(path_id).unwrap_or_default()
+        };
+        name.starts_with(prefix)
+    })?;
+    if paths == 0 {
+        return Err(format!("No paths with prefix {}", prefix));
+    }
+
+    Ok((subgraph, paths))
+}
+
+/// Returns the subgraph touched by the paths of the given samples.
+///
+/// The subgraph consists of the nodes visited by the haplotype paths of the samples and all edges between them.
+/// This can be used for naming per-cohort graphs derived from a larger graph.
+/// Node names are GBWT node identifiers, as in [`crate::graph::GBZInt`].
+/// Returns the subgraph and the number of selected paths.
+/// Returns an error if the graph does not have path metadata or if a sample is not in the graph.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::paths;
+/// use gbz::{GBZ, support};
+/// use simple_sds::serialize;
+///
+/// let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+/// let (subgraph, paths) = paths::sample_subgraph_gbz(&gbz, &["sample"]).unwrap();
+/// assert_eq!(paths, 4);
+/// assert!(pggname::Graph::statistics(&subgraph).nodes > 0);
+/// assert!(paths::sample_subgraph_gbz(&gbz, &["sample", "other"]).is_err());
+/// ```
+#[cfg(feature = "gbz")]
+pub fn sample_subgraph_gbz(gbz: &GBZ, samples: &[&str]) -> Result<(GraphInt, usize), String> {
+    let metadata = gbz.metadata().ok_or(String::from("The GBZ graph does not have path metadata"))?;
+    let mut sample_ids: BTreeSet<usize> = BTreeSet::new();
+    for sample in samples.iter() {
+        let sample_id = metadata.sample_id(sample).ok_or(format!("No sample {} in the graph", sample))?;
+        sample_ids.insert(sample_id);
+    }
+
+    selected_paths_subgraph_gbz(gbz, |_, path_name| sample_ids.contains(&path_name.sample()))
+}
+
+// Returns the subgraph touched by the paths selected by the predicate and the number of selected paths.
+// The predicate receives the path identifier and the path name.
+#[cfg(feature = "gbz")]
+fn selected_paths_subgraph_gbz<F: Fn(usize, &PathName) -> bool>(gbz: &GBZ, select: F) -> Result<(GraphInt, usize), String> {
+    let metadata = gbz.metadata().ok_or(String::from("The GBZ graph does not have path metadata"))?;
+    let mut nodes: BTreeSet<usize> = BTreeSet::new();
+    let mut paths = 0;
+    for (path_id, path_name) in metadata.path_iter().enumerate() {
+        if !select(path_id, path_name) {
+            continue;
+        }
+        let iter = gbz.path(path_id, Orientation::Forward).ok_or(format!("Missing path {}", path_id))?;
+        nodes.extend(iter.map(|(node_id, _)| node_id));
+        paths += 1;
+    }
+
+    Ok((induced_subgraph_gbz(gbz, &nodes)?, paths))
+}
+
+// Returns the subgraph induced by the given nodes, with GBWT node identifiers as node names.
+#[cfg(feature = "gbz")]
+fn induced_subgraph_gbz(gbz: &GBZ, nodes: &BTreeSet<usize>) -> Result<GraphInt, String> {
+    let mut subgraph = GraphInt::new();
+    for &node_id in nodes.iter() {
+        let sequence = gbz.sequence(node_id).ok_or(format!("Missing node {}", node_id))?;
+        subgraph.add_node(node_id.to_string().as_bytes(), sequence)?;
+    }
+    for &node_id in nodes.iter() {
+        for orientation in [Orientation::Forward, Orientation::Reverse] {
+            let successors = gbz.successors(node_id, orientation).ok_or(format!("Missing node {}", node_id))?;
+            for (next_id, next_o) in successors.filter(|(next_id, _)| nodes.contains(next_id)) {
+                subgraph.add_edge(node_id.to_string().as_bytes(), orientation, next_id.to_string().as_bytes(), next_o)?;
+            }
+        }
+    }
+    subgraph.finalize()?;
+    Ok(subgraph)
+}
+
+/// Writes the per-contig digests as a tab-separated table.
+///
+/// The first line is [`CONTIG_DIGEST_HEADER`].
+/// Each subsequent line contains the sample name, the contig name, the number of paths, the number of nodes, and the stable name of the subgraph.
+pub fn write_contig_digests<W: Write>(digests: &[ContigDigest], mut writer: W) -> Result<(), String> {
+    writeln!(writer, "{}", CONTIG_DIGEST_HEADER).map_err(|e| e.to_string())?;
+    for digest in digests.iter() {
+        writeln!(
+            writer, "{}\t{}\t{}\t{}\t{}",
+            digest.sample, digest.contig, digest.paths, digest.nodes, digest.name
+        ).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+/// Returns the composite name for the given graph name and path-set digest.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::paths;
+/// use sha2::{Digest, Sha256};
+///
+/// let composite = paths::composite_name("1234", "abcd");
+/// assert_eq!(composite, format!("{:x}", Sha256::digest(b"1234abcd")));
+/// ```
+pub fn composite_name(graph_name: &str, path_digest: &str) -> String {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(graph_name.as_bytes());
+    hasher.update(path_digest.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Computes the composite name for the graph and the paths stored in the given file.
+///
+/// The graph name is computed using [`crate::stable_name_from_file`] and the path-set digest using SHA-256.
+/// Returns an error if the file cannot be read or parsed.
+///
+/// # Examples
+///
+/// ```
+/// use gbz::support;
+///
+/// let from_gfa = pggname::paths::composite_name_from_file(support::get_test_data("example.gfa")).unwrap();
+/// let from_gbz = pggname::paths::composite_name_from_file(support::get_test_data("example.gbz")).unwrap();
+/// assert_eq!(from_gfa, from_gbz);
+/// ```
+pub fn composite_name_from_file<P: AsRef<Path>>(filename: P) -> Result<String, String> {
+    let graph_name = algorithms::stable_name_from_file(&filename)?;
+    let path_digest = path_digest_file::<sha2::Sha256, _>(&filename)?;
+    Ok(composite_name(&graph_name, &path_digest))
+}
+
+//-----------------------------------------------------------------------------
+
+fn digest_records<D: Digest>(mut records: Vec<Vec<u8>>) -> String
+    where digest::Output<D>: core::fmt::LowerHex {
+    records.sort_unstable();
+    let mut hasher = D::new();
+    for record in records.iter() {
+        hasher.update(record);
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+// Converts a P-line into a canonical record.
+fn p_line_record(line: &[u8]) -> Result<Vec<u8>, String> {
+    let fields: Vec<&[u8]> = split_bytes(line, b'\t').collect();
+    if fields.len() < 3 || fields[1].is_empty() {
+        return Err(String::from("not enough fields for a path"));
+    }
+
+    let mut record: Vec<u8> = Vec::from(&b"P\t"[..]);
+    record.extend_from_slice(fields[1]);
+    record.push(b'\t');
+    for step in split_bytes(fields[2], b',') {
+        let (name, orientation) = match step.split_last() {
+            Some((b'+', name)) if !name.is_empty() => (name, b'>'),
+            Some((b'-', name)) if !name.is_empty() => (name, b'<'),
+            _ => return Err(format!("invalid path step: {}", String::from_utf8_lossy(step))),
+        };
+        record.push(orientation);
+        record.extend_from_slice(name);
+    }
+    Ok(record)
+}
+
+// Converts a W-line into a canonical record.
+fn w_line_record(line: &[u8]) -> Result<Vec<u8>, String> {
+    let fields: Vec<&[u8]> = split_bytes(line, b'\t').collect();
+    if fields.len() < 7 {
+        return Err(String::from("not enough fields for a walk"));
+    }
+    if fields[6].first().is_none_or(|&c| c != b'>' && c != b'<') {
+        return Err(format!("invalid walk: {}", String::from_utf8_lossy(fields[6])));
+    }
+    Ok(fields[..7].join(&b'\t'))
+}
+
+// Returns the path as a walk and its length in bp.
+#[cfg(feature = "gbz")]
+fn gbz_walk(gbz: &GBZ, path_id: usize) -> Result<(Vec<u8>, usize), String> {
+    let mut walk: Vec<u8> = Vec::new();
+    let mut len = 0;
+    let mut push = |name: &[u8], orientation: Orientation| {
+        walk.push(match orientation {
+            Orientation::Forward => b'>',
+            Orientation::Reverse => b'<',
+        });
+        walk.extend_from_slice(name);
+    };
+    if let Some(iter) = gbz.segment_path(path_id, Orientation::Forward) {
+        for (segment, orientation) in iter {
+            push(segment.name, orientation);
+            len += segment.sequence.len();
+        }
+    } else {
+        let iter = gbz.path(path_id, Orientation::Forward).ok_or(format!("Missing path {}", path_id))?;
+        for (node_id, orientation) in iter {
+            push(node_id.to_string().as_bytes(), orientation);
+            len += gbz.sequence_len(node_id).unwrap_or(0);
+        }
+    }
+    Ok((walk, len))
+}
+
+//-----------------------------------------------------------------------------
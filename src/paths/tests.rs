@@ -0,0 +1,173 @@
+use super::*;
+
+#[cfg(feature = "gbz")]
+use gbz::support;
+
+use sha2::Sha256;
+
+#[cfg(feature = "gbz")]
+use std::fs::File;
+#[cfg(feature = "gbz")]
+use std::io::BufReader;
+
+//-----------------------------------------------------------------------------
+
+const GRAPH: &str = "S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n";
+
+fn gfa_digest(paths: &[&str]) -> Result<String, String> {
+    let mut gfa = String::from(GRAPH);
+    for path in paths.iter() {
+        gfa.push_str(path);
+        gfa.push('\n');
+    }
+    path_digest_gfa::<Sha256, _>(gfa.as_bytes())
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn gfa_paths() {
+    let p_line = "P\tx\t1+,2-\t*";
+    let w_line = "W\tsample\t1\tx\t0\t6\t>1<2";
+    let digest = gfa_digest(&[p_line, w_line]).unwrap();
+    assert_eq!(gfa_digest(&[w_line, p_line]), Ok(digest.clone()), "Path order changed the digest");
+    assert_eq!(gfa_digest(&["P\tx\t1+,2-\t4M\tXX:Z:tag", w_line]), Ok(digest.clone()), "Overlaps changed the digest");
+    assert_ne!(gfa_digest(&[p_line]), Ok(digest.clone()), "Missing walk did not change the digest");
+    assert_ne!(gfa_digest(&["P\tx\t1+,2+\t*", w_line]), Ok(digest.clone()), "Orientation did not change the digest");
+    assert_ne!(gfa_digest(&[]), Ok(digest), "No paths gave the same digest");
+
+    assert!(gfa_digest(&["P\tx"]).is_err(), "Parsed a P-line without steps");
+    assert!(gfa_digest(&["P\tx\t1*\t*"]).is_err(), "Parsed a P-line with an invalid step");
+    assert!(gfa_digest(&["W\tsample\t1\tx\t0\t6"]).is_err(), "Parsed a W-line without a walk");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn gbz_and_gfa_paths() {
+    for (name, gbz_file, gfa_file) in [("example", "example.gbz", "example.gfa"), ("translation", "translation.gbz", "translation.gfa")] {
+        let gbz_file = support::get_test_data(gbz_file);
+        let gfa_file = support::get_test_data(gfa_file);
+        let gbz: GBZ = serialize::load_from(&gbz_file).unwrap();
+        let from_gbz = path_digest_gbz::<Sha256>(&gbz).unwrap();
+        let reader = BufReader::new(File::open(&gfa_file).unwrap());
+        let from_gfa = path_digest_gfa::<Sha256, _>(reader).unwrap();
+        assert_eq!(from_gbz, from_gfa, "Different path digests for {} GBZ and GFA", name);
+        assert_eq!(path_digest_file::<Sha256, _>(&gbz_file), Ok(from_gbz.clone()), "Wrong path digest for {} GBZ file", name);
+        assert_eq!(path_digest_file::<Sha256, _>(&gfa_file), Ok(from_gfa), "Wrong path digest for {} GFA file", name);
+    }
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn haplotype_sets() {
+    let mut names = Vec::new();
+    for (gbz_file, gbwt_file) in [("example.gbz", "example.gbwt"), ("translation.gbz", "translation.gbwt")] {
+        let gbz_file = support::get_test_data(gbz_file);
+        let gbwt_file = support::get_test_data(gbwt_file);
+        let gbz: GBZ = serialize::load_from(&gbz_file).unwrap();
+        let from_gbz = haplotype_digest_gbz::<Sha256>(&gbz).unwrap();
+        let gbwt: GBWT = serialize::load_from(&gbwt_file).unwrap();
+        assert_eq!(haplotype_digest_gbwt::<Sha256>(&gbwt), Ok(from_gbz.clone()), "Different haplotype-set names for GBZ and GBWT");
+        assert_eq!(haplotype_digest_file::<Sha256, _>(&gbz_file), Ok(from_gbz.clone()), "Wrong haplotype-set name for GBZ file");
+        assert_eq!(haplotype_digest_file::<Sha256, _>(&gbwt_file), Ok(from_gbz.clone()), "Wrong haplotype-set name for GBWT file");
+        assert_ne!(path_digest_gbz::<Sha256>(&gbz), Ok(from_gbz.clone()), "Haplotype-set name is the same as path-set digest");
+        names.push(from_gbz);
+    }
+    assert_ne!(names[0], names[1], "Different graphs have the same haplotype-set name");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn composite_names() {
+    let graph_name = crate::stable_name_from_file(support::get_test_data("example.gfa")).unwrap();
+    let path_digest = path_digest_file::<Sha256, _>(support::get_test_data("example.gfa")).unwrap();
+    let composite = composite_name(&graph_name, &path_digest);
+    assert_eq!(composite_name_from_file(support::get_test_data("example.gfa")), Ok(composite.clone()), "Wrong composite name for a file");
+    assert_ne!(composite, graph_name, "Composite name is the graph name");
+    assert_ne!(composite, path_digest, "Composite name is the path-set digest");
+
+    // Same graph, different paths.
+    let other = composite_name(&graph_name, &gfa_digest(&[]).unwrap());
+    assert_ne!(composite, other, "Different path sets have the same composite name");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn contig_digests() {
+    let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+    let digests = contig_digests_gbz(&gbz).unwrap();
+    let graph: GraphInt = algorithms::parse_gfa(BufReader::new(File::open(support::get_test_data("example.gfa")).unwrap())).unwrap();
+
+    // The generic paths in the GFA file list the nodes of each contig.
+    let expected = [("A", vec!["11", "12", "14", "15", "17"]), ("B", vec!["21", "22", "24", "25"])];
+    assert_eq!(digests.len(), expected.len(), "Wrong number of contigs");
+    for (digest, (contig, nodes)) in digests.iter().zip(expected.iter()) {
+        assert_eq!(digest.sample, GENERIC_SAMPLE, "Wrong sample for contig {}", contig);
+        assert_eq!(digest.contig, *contig, "Wrong contig name");
+        assert_eq!(digest.paths, 1, "Wrong number of paths for contig {}", contig);
+        assert_eq!(digest.nodes, nodes.len(), "Wrong number of nodes for contig {}", contig);
+        let nodes: Vec<Vec<u8>> = nodes.iter().map(|node| node.as_bytes().to_vec()).collect();
+        let (subgraph, _): (GraphInt, _) = crate::translation::extract(&graph, &nodes).unwrap();
+        assert_eq!(digest.name, algorithms::stable_name(&subgraph), "Wrong digest for contig {}", contig);
+    }
+    assert_ne!(digests[0].name, digests[1].name, "Different contigs have the same digest");
+
+    let mut table: Vec<u8> = Vec::new();
+    write_contig_digests(&digests, &mut table).unwrap();
+    let table = String::from_utf8(table).unwrap();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3, "Wrong number of lines in the table");
+    assert_eq!(lines[0], CONTIG_DIGEST_HEADER, "Wrong header line");
+    assert_eq!(lines[1], format!("{}\tA\t1\t5\t{}", GENERIC_SAMPLE, digests[0].name), "Wrong table line");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn path_prefix_subgraphs() {
+    let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+    let graph: GraphInt = algorithms::parse_gfa(BufReader::new(File::open(support::get_test_data("example.gfa")).unwrap())).unwrap();
+    let full = algorithms::stable_name(&graph);
+
+    // Generic paths are matched by contig name.
+    let (subgraph, paths) = path_prefix_subgraph_gbz(&gbz, "A").unwrap();
+    assert_eq!(paths, 1, "Wrong number of paths for a generic path");
+    let digests = contig_digests_gbz(&gbz).unwrap();
+    assert_eq!(algorithms::stable_name(&subgraph), digests[0].name, "Generic path subgraph differs from the contig digest");
+
+    // All paths cover the entire graph.
+    let (subgraph, paths) = path_prefix_subgraph_gbz(&gbz, "").unwrap();
+    assert_eq!(paths, 6, "Wrong number of paths for an empty prefix");
+    assert_eq!(algorithms::stable_name(&subgraph), full, "All paths do not cover the graph");
+
+    // PanSN names.
+    let (sample, paths) = path_prefix_subgraph_gbz(&gbz, "sample#").unwrap();
+    assert_eq!(paths, 4, "Wrong number of paths for a sample");
+    let (haplotype, paths) = path_prefix_subgraph_gbz(&gbz, "sample#1#").unwrap();
+    assert_eq!(paths, 2, "Wrong number of paths for a haplotype");
+    assert!(haplotype.statistics().nodes <= sample.statistics().nodes, "Haplotype subgraph is larger than the sample subgraph");
+    assert!(path_prefix_subgraph_gbz(&gbz, "sample#3#").is_err(), "Found paths for a missing haplotype");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn sample_subgraphs() {
+    let gbz: GBZ = serialize::load_from(support::get_test_data("example.gbz")).unwrap();
+
+    // Samples select the same paths as the corresponding PanSN prefixes.
+    let (subgraph, paths) = sample_subgraph_gbz(&gbz, &["sample"]).unwrap();
+    let (expected, expected_paths) = path_prefix_subgraph_gbz(&gbz, "sample#").unwrap();
+    assert_eq!(paths, expected_paths, "Wrong number of paths for a sample");
+    assert_eq!(algorithms::stable_name(&subgraph), algorithms::stable_name(&expected), "Wrong subgraph for a sample");
+
+    // Generic paths and all samples.
+    let (_, paths) = sample_subgraph_gbz(&gbz, &[GENERIC_SAMPLE]).unwrap();
+    assert_eq!(paths, 2, "Wrong number of generic paths");
+    let (subgraph, paths) = sample_subgraph_gbz(&gbz, &["sample", GENERIC_SAMPLE]).unwrap();
+    let (expected, expected_paths) = path_prefix_subgraph_gbz(&gbz, "").unwrap();
+    assert_eq!(paths, expected_paths, "Wrong number of paths for all samples");
+    assert_eq!(algorithms::stable_name(&subgraph), algorithms::stable_name(&expected), "Wrong subgraph for all samples");
+
+    assert!(sample_subgraph_gbz(&gbz, &["sample", "missing"]).is_err(), "Found a missing sample");
+}
+
+//-----------------------------------------------------------------------------
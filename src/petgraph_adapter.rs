@@ -0,0 +1,83 @@
+//! Building graphs from [`petgraph`](https://crates.io/crates/petgraph) graphs.
+//!
+//! This module is only available with the `petgraph` feature.
+//! Graph transformations are often prototyped with `petgraph`, where node weights and edge weights can be arbitrary types.
+//! [`GraphStr::from_petgraph`] builds a graph that can be named from such a graph, with closures that interpret the weights:
+//!
+//! * The node closure returns the name and the sequence of a node.
+//! * The edge closure returns the orientations of the source and the destination of an edge.
+//!   An edge from `source` to `target` becomes the GFA edge `L source o1 target o2`.
+//!
+//! Both directed and undirected graphs are supported.
+//! In an undirected graph, the endpoints of an edge are in the order they were added.
+
+use crate::Graph;
+use crate::graph::{GraphStr, Orientation};
+
+use petgraph::EdgeType;
+use petgraph::graph::{IndexType, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+impl GraphStr {
+    /// Builds a graph from a `petgraph` graph.
+    ///
+    /// `node` returns the name and the sequence of each node, and `edge` returns the orientations of the endpoints of each edge.
+    /// See the [module documentation](crate::petgraph_adapter) for details.
+    /// Returns an error if two nodes have the same name, or if the graph cannot be finalized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::Orientation;
+    /// use pggname::graph::GraphStr;
+    ///
+    /// let mut graph = petgraph::Graph::<&str, bool>::new();
+    /// let a = graph.add_node("ACGT");
+    /// let b = graph.add_node("GA");
+    /// graph.add_edge(a, b, false);
+    ///
+    /// let graph = GraphStr::from_petgraph(
+    ///     &graph,
+    ///     |index, sequence| ((index.index() + 1).to_string(), *sequence),
+    ///     |reverse| if *reverse { (Orientation::Forward, Orientation::Reverse) } else { (Orientation::Forward, Orientation::Forward) },
+    /// ).unwrap();
+    /// let gfa = b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n";
+    /// let other: GraphStr = pggname::algorithms::parse_gfa_bytes(gfa).unwrap();
+    /// assert_eq!(pggname::stable_name(&graph), pggname::stable_name(&other));
+    /// ```
+    pub fn from_petgraph<N, E, Ty, Ix, Name, Seq, NodeFn, EdgeFn>(
+        graph: &petgraph::Graph<N, E, Ty, Ix>, mut node: NodeFn, mut edge: EdgeFn
+    ) -> Result<Self, String>
+    where
+        Ty: EdgeType, Ix: IndexType,
+        Name: AsRef<[u8]>, Seq: AsRef<[u8]>,
+        NodeFn: FnMut(NodeIndex<Ix>, &N) -> (Name, Seq),
+        EdgeFn: FnMut(&E) -> (Orientation, Orientation),
+    {
+        let mut result = GraphStr::new();
+        let mut names: Vec<Name> = Vec::with_capacity(graph.node_count());
+        for index in graph.node_indices() {
+            let (name, sequence) = node(index, &graph[index]);
+            result.add_node(name.as_ref(), sequence.as_ref())?;
+            names.push(name);
+        }
+        if result.nodes.len() != names.len() {
+            return Err(format!("{} nodes share a name with another node", names.len() - result.nodes.len()));
+        }
+        for e in graph.edge_references() {
+            let (source_o, dest_o) = edge(e.weight());
+            let source = names[e.source().index()].as_ref();
+            let dest = names[e.target().index()].as_ref();
+            result.add_edge(source, source_o, dest, dest_o)?;
+        }
+        result.finalize()?;
+        Ok(result)
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,63 @@
+use super::*;
+
+use crate::algorithms;
+
+use gbz::support;
+
+use petgraph::Undirected;
+
+//-----------------------------------------------------------------------------
+
+// Converts a GFA graph with string identifiers into a petgraph graph.
+fn to_petgraph(graph: &GraphStr) -> petgraph::Graph<(String, Vec<u8>), (Orientation, Orientation)> {
+    let mut result = petgraph::Graph::new();
+    let mut indices = std::collections::BTreeMap::new();
+    for (name, node) in graph.nodes.iter() {
+        let index = result.add_node((String::from_utf8(name.clone()).unwrap(), node.sequence.to_vec()));
+        indices.insert(name.clone(), index);
+    }
+    for (name, node) in graph.nodes.iter() {
+        for (source_o, dest, dest_o) in node.edges.iter() {
+            result.add_edge(indices[name], indices[dest], (*source_o, *dest_o));
+        }
+    }
+    result
+}
+
+#[test]
+fn same_names() {
+    for filename in ["example.gfa", "translation.gfa"] {
+        let path = support::get_test_data(filename);
+        let original: GraphStr = algorithms::parse_gfa(algorithms::open_gfa(&path).unwrap()).unwrap();
+        let petgraph = to_petgraph(&original);
+        let graph = GraphStr::from_petgraph(&petgraph, |_, (name, sequence)| (name.clone(), sequence.clone()), |o| *o).unwrap();
+        assert_eq!(crate::stable_name(&graph), crate::stable_name(&original), "Different names for {}", filename);
+    }
+}
+
+#[test]
+fn undirected_graph() {
+    let mut graph = petgraph::Graph::<&str, (), Undirected>::new_undirected();
+    let a = graph.add_node("ACGT");
+    let b = graph.add_node("GA");
+    let c = graph.add_node("T");
+    graph.add_edge(b, a, ());
+    graph.add_edge(b, c, ());
+    let forward = |_: &()| (Orientation::Forward, Orientation::Forward);
+    let graph = GraphStr::from_petgraph(&graph, |index, sequence| (format!("n{}", index.index()), *sequence), forward).unwrap();
+
+    let gfa = b"S\tn0\tACGT\nS\tn1\tGA\nS\tn2\tT\nL\tn1\t+\tn0\t+\t*\nL\tn1\t+\tn2\t+\t*\n";
+    let expected: GraphStr = algorithms::parse_gfa_bytes(gfa).unwrap();
+    assert_eq!(crate::stable_name(&graph), crate::stable_name(&expected));
+}
+
+#[test]
+fn duplicate_names() {
+    let mut graph = petgraph::Graph::<&str, ()>::new();
+    graph.add_node("A");
+    graph.add_node("A");
+    let result = GraphStr::from_petgraph(&graph, |_, sequence| ("x", *sequence), |_| (Orientation::Forward, Orientation::Forward));
+    assert!(result.is_err(), "Nodes with the same name were merged");
+}
+
+//-----------------------------------------------------------------------------
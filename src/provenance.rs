@@ -0,0 +1,195 @@
+//! Provenance documents for workflow run records.
+//!
+//! A provenance document records the inputs that were named, their names, the naming algorithm, the tool version, and when the run started and ended.
+//! It is a JSON-LD document using the [schema.org](https://schema.org/) vocabulary, with the run described as a `CreateAction`:
+//!
+//! ```text
+//! {
+//!   "@context": {"@vocab": "https://schema.org/"},
+//!   "@type": "CreateAction",
+//!   "schemaVersion": "1",
+//!   "instrument": {"@type": "SoftwareApplication", "name": "pggname", "softwareVersion": "0.2.2"},
+//!   "algorithm": {"canonicalForm": "gfa", "hash": "SHA-256", "nodeIds": "auto"},
+//!   "startTime": "2026-01-01T12:00:00Z",
+//!   "endTime": "2026-01-01T12:00:05Z",
+//!   "object": [
+//!     {"@type": "MediaObject", "contentUrl": "graph.gfa", "contentSize": 1234, "identifier": "...", ...}
+//!   ]
+//! }
+//! ```
+//!
+//! The document is schema-stable: the keys are always present and in the same order, and missing values are `null`.
+//! Each input has the keys `contentUrl`, `contentSize`, `identifier` (the stable name), `pathDigest`, `haplotypeDigest`, `compositeName`, and `seqcolDigest`.
+//! The schema version is incremented if the structure changes.
+
+use crate::algorithms::NodeIds;
+use crate::seqcol::json_string;
+
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Version of the provenance document schema.
+pub const SCHEMA_VERSION: &str = "1";
+
+/// An input file in a provenance document.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProvenanceInput {
+    /// Path to the file.
+    pub path: String,
+    /// File size in bytes, if known.
+    pub size: Option<u64>,
+    /// Stable name of the graph.
+    pub name: Option<String>,
+    /// Path-set digest.
+    pub path_digest: Option<String>,
+    /// Haplotype-set name.
+    pub haplotype_digest: Option<String>,
+    /// Composite graph+paths name.
+    pub composite_name: Option<String>,
+    /// GA4GH seqcol digest of the node sequences.
+    pub seqcol_digest: Option<String>,
+}
+
+impl ProvenanceInput {
+    /// Creates a new input with the given path, using the size of the file if it exists.
+    pub fn new(path: &str) -> Self {
+        ProvenanceInput {
+            path: String::from(path),
+            size: fs::metadata(path).ok().map(|metadata| metadata.len()),
+            ..Default::default()
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// A provenance document for a single run.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms::NodeIds;
+/// use pggname::provenance::{Provenance, ProvenanceInput};
+///
+/// let mut provenance = Provenance::new(NodeIds::Auto);
+/// let mut input = ProvenanceInput::new("graph.gfa");
+/// input.name = Some(String::from("54b49d18354a34fbd1af9aaac279e1b3ee67b2f68f0ff79f5ebf6c50c8d922a5"));
+/// provenance.add_input(input);
+/// provenance.finish();
+///
+/// let json = provenance.to_json();
+/// assert!(json.contains("\"identifier\": \"54b49d18354a34fbd1af9aaac279e1b3ee67b2f68f0ff79f5ebf6c50c8d922a5\""));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Provenance {
+    /// How node identifiers were interpreted.
+    pub node_ids: NodeIds,
+    /// When the run started.
+    pub start_time: SystemTime,
+    /// When the run ended.
+    pub end_time: Option<SystemTime>,
+    /// Input files in the order they were processed.
+    pub inputs: Vec<ProvenanceInput>,
+}
+
+impl Provenance {
+    /// Creates a new document for a run starting now.
+    pub fn new(node_ids: NodeIds) -> Self {
+        Provenance { node_ids, start_time: SystemTime::now(), end_time: None, inputs: Vec::new() }
+    }
+
+    /// Adds an input file to the document.
+    pub fn add_input(&mut self, input: ProvenanceInput) {
+        self.inputs.push(input);
+    }
+
+    /// Marks the run as ended now.
+    pub fn finish(&mut self) {
+        self.end_time = Some(SystemTime::now());
+    }
+
+    /// Returns the document as JSON-LD.
+    pub fn to_json(&self) -> String {
+        let node_ids = match self.node_ids {
+            NodeIds::Auto => "auto",
+            NodeIds::Integer => "integer",
+            NodeIds::String => "string",
+        };
+        let inputs: Vec<String> = self.inputs.iter().map(|input| {
+            format!(
+                "    {{\"@type\": \"MediaObject\", \"contentUrl\": {}, \"contentSize\": {}, \"identifier\": {}, \"pathDigest\": {}, \"haplotypeDigest\": {}, \"compositeName\": {}, \"seqcolDigest\": {}}}",
+                json_string(&input.path),
+                input.size.map(|size| size.to_string()).unwrap_or(String::from("null")),
+                optional_string(&input.name),
+                optional_string(&input.path_digest),
+                optional_string(&input.haplotype_digest),
+                optional_string(&input.composite_name),
+                optional_string(&input.seqcol_digest),
+            )
+        }).collect();
+        let inputs = if inputs.is_empty() { String::from("[]") } else { format!("[\n{}\n  ]", inputs.join(",\n")) };
+
+        let mut result = String::from("{\n");
+        result.push_str("  \"@context\": {\"@vocab\": \"https://schema.org/\"},\n");
+        result.push_str("  \"@type\": \"CreateAction\",\n");
+        result.push_str(&format!("  \"schemaVersion\": {},\n", json_string(SCHEMA_VERSION)));
+        result.push_str(&format!(
+            "  \"instrument\": {{\"@type\": \"SoftwareApplication\", \"name\": \"pggname\", \"softwareVersion\": {}}},\n",
+            json_string(env!("CARGO_PKG_VERSION"))
+        ));
+        result.push_str(&format!(
+            "  \"algorithm\": {{\"canonicalForm\": \"gfa\", \"hash\": \"SHA-256\", \"nodeIds\": {}}},\n",
+            json_string(node_ids)
+        ));
+        result.push_str(&format!("  \"startTime\": {},\n", json_string(&timestamp(self.start_time))));
+        result.push_str(&format!("  \"endTime\": {},\n", optional_string(&self.end_time.map(timestamp))));
+        result.push_str(&format!("  \"object\": {}\n", inputs));
+        result.push_str("}\n");
+        result
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+fn optional_string(value: &Option<String>) -> String {
+    value.as_deref().map(json_string).unwrap_or(String::from("null"))
+}
+
+/// Formats the time as an RFC 3339 timestamp in UTC with second precision.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::provenance;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(1760000000);
+/// assert_eq!(provenance::timestamp(time), "2025-10-09T08:53:20Z");
+/// ```
+pub fn timestamp(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+
+    // Converts days since the epoch to a civil date (Howard Hinnant's algorithm).
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, seconds / 3600, (seconds / 60) % 60, seconds % 60
+    )
+}
+
+//-----------------------------------------------------------------------------
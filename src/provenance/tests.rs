@@ -0,0 +1,50 @@
+use super::*;
+
+use std::time::Duration;
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn timestamps() {
+    assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z", "Wrong timestamp for the epoch");
+    let leap_day = UNIX_EPOCH + Duration::from_secs(951_782_400 + 3661);
+    assert_eq!(timestamp(leap_day), "2000-02-29T01:01:01Z", "Wrong timestamp for a leap day");
+    let new_year = UNIX_EPOCH + Duration::from_secs(1_767_225_599);
+    assert_eq!(timestamp(new_year), "2025-12-31T23:59:59Z", "Wrong timestamp for the end of a year");
+}
+
+#[test]
+fn document() {
+    let mut provenance = Provenance::new(NodeIds::Integer);
+    provenance.start_time = UNIX_EPOCH;
+    provenance.add_input(ProvenanceInput {
+        path: String::from("graph.gfa"),
+        size: Some(42),
+        name: Some(String::from("abc")),
+        seqcol_digest: Some(String::from("xyz")),
+        ..Default::default()
+    });
+    provenance.add_input(ProvenanceInput { path: String::from("missing\".gfa"), ..Default::default() });
+
+    let expected = format!(concat!(
+        "{{\n",
+        "  \"@context\": {{\"@vocab\": \"https://schema.org/\"}},\n",
+        "  \"@type\": \"CreateAction\",\n",
+        "  \"schemaVersion\": \"1\",\n",
+        "  \"instrument\": {{\"@type\": \"SoftwareApplication\", \"name\": \"pggname\", \"softwareVersion\": \"{}\"}},\n",
+        "  \"algorithm\": {{\"canonicalForm\": \"gfa\", \"hash\": \"SHA-256\", \"nodeIds\": \"integer\"}},\n",
+        "  \"startTime\": \"1970-01-01T00:00:00Z\",\n",
+        "  \"endTime\": null,\n",
+        "  \"object\": [\n",
+        "    {{\"@type\": \"MediaObject\", \"contentUrl\": \"graph.gfa\", \"contentSize\": 42, \"identifier\": \"abc\", \"pathDigest\": null, \"haplotypeDigest\": null, \"compositeName\": null, \"seqcolDigest\": \"xyz\"}},\n",
+        "    {{\"@type\": \"MediaObject\", \"contentUrl\": \"missing\\\".gfa\", \"contentSize\": null, \"identifier\": null, \"pathDigest\": null, \"haplotypeDigest\": null, \"compositeName\": null, \"seqcolDigest\": null}}\n",
+        "  ]\n",
+        "}}\n",
+    ), env!("CARGO_PKG_VERSION"));
+    assert_eq!(provenance.to_json(), expected, "Wrong provenance document");
+
+    let empty = Provenance { start_time: UNIX_EPOCH, ..Provenance::new(NodeIds::Auto) };
+    assert!(empty.to_json().contains("  \"object\": []\n"), "Wrong object list for an empty document");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,175 @@
+//! Python bindings using PyO3.
+//!
+//! This module is only available with the `python` feature.
+//! The extension module can be built with [maturin](https://www.maturin.rs/) using the `pyproject.toml` file in the repository.
+//!
+//! ```python
+//! import pggname
+//!
+//! name = pggname.stable_name("graph.gfa")
+//! graph = pggname.GraphName.from_header_lines(["@RN\tC", "@SG\tC\tB"])
+//! parent = pggname.GraphName("B")
+//! assert graph.is_subgraph_of(parent)
+//! ```
+
+use crate::{GraphName, vectors};
+use crate::algorithms::{self, NodeIds};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+//-----------------------------------------------------------------------------
+
+/// Computes the stable name of the graph in the given GFA or GBZ file.
+///
+/// `node_ids` is `"auto"`, `"int"`, or `"str"`.
+#[pyfunction]
+#[pyo3(signature = (path, node_ids = "auto"))]
+fn stable_name(path: &str, node_ids: &str) -> PyResult<String> {
+    let node_ids = match node_ids {
+        "auto" => NodeIds::Auto,
+        "int" => NodeIds::Integer,
+        "str" => NodeIds::String,
+        _ => return Err(PyValueError::new_err(format!("Invalid node identifier type: {}", node_ids))),
+    };
+    algorithms::hash_file::<sha2::Sha256, _>(path, node_ids).map_err(PyValueError::new_err)
+}
+
+/// Computes the stable name of a graph given as GFA bytes.
+#[pyfunction]
+fn stable_name_from_bytes(data: &[u8]) -> PyResult<String> {
+    if let Ok(graph) = algorithms::parse_gfa_bytes::<crate::graph::GraphInt>(data) {
+        return Ok(algorithms::stable_name(&graph));
+    }
+    let graph: crate::graph::GraphStr = algorithms::parse_gfa_bytes(data).map_err(PyValueError::new_err)?;
+    Ok(algorithms::stable_name(&graph))
+}
+
+/// Returns the golden test vectors as a JSON string.
+///
+/// See the `vectors` module of the Rust crate for the format.
+#[pyfunction]
+fn test_vectors_json() -> String {
+    vectors::to_json(vectors::test_vectors())
+}
+
+//-----------------------------------------------------------------------------
+
+/// Stable name of a graph together with known subgraph and translation relationships.
+#[pyclass(name = "GraphName", module = "pggname", eq)]
+#[derive(Clone, PartialEq)]
+struct PyGraphName {
+    inner: GraphName,
+}
+
+#[pymethods]
+impl PyGraphName {
+    #[new]
+    #[pyo3(signature = (name = None))]
+    fn new(name: Option<String>) -> Self {
+        let inner = name.map(GraphName::new).unwrap_or_default();
+        PyGraphName { inner }
+    }
+
+    /// Parses the object from GFA or GAF header lines without trailing newlines.
+    #[staticmethod]
+    fn from_header_lines(lines: Vec<String>) -> PyResult<Self> {
+        let inner = GraphName::from_header_lines(&lines).map_err(PyValueError::new_err)?;
+        Ok(PyGraphName { inner })
+    }
+
+    /// Parses the object from the compact single-string form, such as `name;SG=a,b;TL=c,d`.
+    #[staticmethod]
+    fn parse(value: &str) -> PyResult<Self> {
+        let inner = value.parse::<GraphName>().map_err(PyValueError::new_err)?;
+        Ok(PyGraphName { inner })
+    }
+
+    /// Returns the compact single-string form.
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    /// Stable name of the graph, or `None`.
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.inner.name().cloned()
+    }
+
+    /// Subgraph relationships as `(subgraph, supergraph)` pairs.
+    fn subgraphs(&self) -> Vec<(String, String)> {
+        self.inner.subgraph_iter().map(|(from, to)| (String::from(from), String::from(to))).collect()
+    }
+
+    /// Translation relationships as `(from, to)` pairs.
+    fn translations(&self) -> Vec<(String, String)> {
+        self.inner.translation_iter().map(|(from, to)| (String::from(from), String::from(to))).collect()
+    }
+
+    fn add_subgraph(&mut self, subgraph: &str, supergraph: &str) {
+        self.inner.add_subgraph(subgraph, supergraph);
+    }
+
+    fn add_translation(&mut self, from: &str, to: &str) {
+        self.inner.add_translation(from, to);
+    }
+
+    fn make_subgraph_of(&mut self, parent: &PyGraphName) {
+        self.inner.make_subgraph_of(&parent.inner);
+    }
+
+    fn add_translation_to(&mut self, parent: &PyGraphName) {
+        self.inner.add_translation_to(&parent.inner);
+    }
+
+    fn add_relationships(&mut self, other: &PyGraphName) {
+        self.inner.add_relationships(&other.inner);
+    }
+
+    /// GFA header lines representing the object, without trailing newlines.
+    fn to_gfa_header_lines(&self) -> Vec<String> {
+        self.inner.to_gfa_header_lines()
+    }
+
+    /// GAF header lines representing the object, without trailing newlines.
+    fn to_gaf_header_lines(&self) -> Vec<String> {
+        self.inner.to_gaf_header_lines()
+    }
+
+    fn is_same(&self, other: &PyGraphName) -> bool {
+        self.inner.is_same(&other.inner)
+    }
+
+    fn is_subgraph_of(&self, other: &PyGraphName) -> bool {
+        self.inner.is_subgraph_of(&other.inner)
+    }
+
+    fn translates_to(&self, other: &PyGraphName) -> bool {
+        self.inner.translates_to(&other.inner)
+    }
+
+    fn describe_relationship(&self, other: &PyGraphName, self_desc: &str, other_desc: &str) -> String {
+        self.inner.describe_relationship(&other.inner, self_desc, other_desc)
+    }
+
+    fn __repr__(&self) -> String {
+        match self.inner.name() {
+            Some(name) => format!("GraphName('{}')", name),
+            None => String::from("GraphName()"),
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+#[pymodule]
+#[pyo3(name = "pggname")]
+fn python_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(stable_name, module)?)?;
+    module.add_function(wrap_pyfunction!(stable_name_from_bytes, module)?)?;
+    module.add_function(wrap_pyfunction!(test_vectors_json, module)?)?;
+    module.add_class::<PyGraphName>()?;
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
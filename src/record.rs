@@ -0,0 +1,125 @@
+//! Self-identifying name records.
+//!
+//! The default output of the command line tool is `name  path`, which does not say how the name was computed.
+//! A [`NameRecord`] is a single tab-separated line that also identifies the format, the version of the canonical GFA format, and the hash algorithm:
+//!
+//! ```text
+//! pggname<TAB>v1<TAB>sha256<TAB>HEX<TAB>PATH
+//! ```
+//!
+//! The first field is always `pggname`, and the version is given as `v` followed by a number, such as [`crate::vectors::CANONICAL_FORMAT_VERSION`].
+//! The path is the rest of the line, so it may contain spaces and tabs.
+//! Manifests consisting of such records remain unambiguous if new canonical format versions or hash algorithms are introduced.
+//! The command line tool prints them with `--long-format`, and the `check` subcommand accepts them in manifests.
+//!
+//! # Examples
+//!
+//! ```
+//! use pggname::record::NameRecord;
+//!
+//! let name = "54b49d18354a34fbd1af9aaac279e1b3ee67b2f68f0ff79f5ebf6c50c8d922a5";
+//! let record = NameRecord::new(1, name, "graphs/example graph.gfa");
+//! let line = record.to_string();
+//! assert_eq!(line, format!("pggname\tv1\tsha256\t{}\tgraphs/example graph.gfa", name));
+//! assert!(NameRecord::is_record(&line));
+//! assert_eq!(line.parse::<NameRecord>(), Ok(record));
+//! ```
+
+use crate::GraphName;
+
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// A self-identifying record of a graph name.
+///
+/// See the [module documentation](self) for details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameRecord {
+    /// Version of the canonical GFA format.
+    pub version: u32,
+    /// Hash algorithm in lower case, such as `sha256`.
+    pub algorithm: String,
+    /// The name as lowercase hexadecimal digits.
+    pub name: String,
+    /// Path to the graph.
+    pub path: String,
+}
+
+impl NameRecord {
+    /// The first field of each record.
+    pub const MAGIC: &'static str = "pggname";
+
+    /// Hash algorithm used for stable graph names.
+    pub const ALGORITHM: &'static str = GraphName::URI_ALGORITHM;
+
+    /// Creates a record for a stable graph name computed with [`Self::ALGORITHM`].
+    pub fn new(version: u32, name: &str, path: &str) -> Self {
+        NameRecord {
+            version,
+            algorithm: String::from(Self::ALGORITHM),
+            name: String::from(name),
+            path: String::from(path),
+        }
+    }
+
+    /// Returns `true` if the line looks like a name record, as opposed to a `name  path` line.
+    ///
+    /// This only checks the first field.
+    pub fn is_record(line: &str) -> bool {
+        line.strip_prefix(Self::MAGIC).is_some_and(|rest| rest.starts_with('\t'))
+    }
+
+    /// Returns `true` if the name is a stable graph name computed with [`Self::ALGORITHM`].
+    pub fn is_stable_name(&self) -> bool {
+        self.algorithm == Self::ALGORITHM && GraphName::is_stable_name(&self.name)
+    }
+}
+
+impl fmt::Display for NameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\tv{}\t{}\t{}\t{}", Self::MAGIC, self.version, self.algorithm, self.name, self.path)
+    }
+}
+
+impl FromStr for NameRecord {
+    type Err = String;
+
+    /// Parses a record from a line without the newline.
+    ///
+    /// The algorithm and the name are converted to lower case.
+    /// Unknown versions and algorithms are accepted, but a [`Self::ALGORITHM`] name must be a stable graph name.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut fields = line.splitn(5, '\t');
+        let (Some(magic), Some(version), Some(algorithm), Some(name), Some(path)) =
+            (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) else {
+            return Err(format!("Expected 5 fields in name record: {}", line));
+        };
+        if magic != Self::MAGIC {
+            return Err(format!("Name record does not start with {}: {}", Self::MAGIC, line));
+        }
+        let version = version.strip_prefix('v').and_then(|version| version.parse::<u32>().ok())
+            .ok_or(format!("Invalid canonical format version {} in name record", version))?;
+        let algorithm = algorithm.to_ascii_lowercase();
+        if algorithm.is_empty() || !algorithm.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_') {
+            return Err(format!("Invalid hash algorithm {} in name record", algorithm));
+        }
+        let name = name.to_ascii_lowercase();
+        if name.is_empty() || !name.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid name {} in name record", name));
+        }
+        if algorithm == Self::ALGORITHM && !GraphName::is_stable_name(&name) {
+            return Err(format!("Invalid stable graph name {} in name record", name));
+        }
+        if path.is_empty() {
+            return Err(String::from("Missing path in name record"));
+        }
+        Ok(NameRecord { version, algorithm, name, path: String::from(path) })
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,47 @@
+use super::*;
+
+//-----------------------------------------------------------------------------
+
+const NAME: &str = "81b160c814182a12aaf95fd458e191590e95fb13c71e1c2f61ff827f605cf970";
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn round_trip() {
+    for (version, path) in [(1, "example.gfa"), (3, "graphs/with spaces\tand tabs.gfa")] {
+        let record = NameRecord::new(version, NAME, path);
+        assert!(record.is_stable_name(), "Not a stable name: {}", record);
+        let line = record.to_string();
+        assert!(NameRecord::is_record(&line), "Not a record: {}", line);
+        assert_eq!(line.parse::<NameRecord>(), Ok(record), "Wrong record after parsing {}", line);
+    }
+
+    // Case is normalized, and unknown algorithms are accepted.
+    let line = format!("pggname\tv1\tSHA256\t{}\tgraph.gbz", NAME.to_ascii_uppercase());
+    assert_eq!(line.parse::<NameRecord>(), Ok(NameRecord::new(1, NAME, "graph.gbz")), "Case was not normalized");
+    let record: NameRecord = "pggname\tv7\tblake3\tabcdef\tgraph.gbz".parse().unwrap();
+    assert_eq!((record.version, record.algorithm.as_str()), (7, "blake3"), "Wrong version or algorithm");
+    assert!(!record.is_stable_name(), "A BLAKE3 name is a stable name");
+}
+
+#[test]
+fn invalid_records() {
+    assert!(!NameRecord::is_record(&format!("{}  example.gfa", NAME)), "A name and a path is a record");
+    assert!(!NameRecord::is_record("pggnamex\tv1"), "Wrong magic is a record");
+
+    let invalid = [
+        format!("pggname\tv1\tsha256\t{}", NAME),
+        format!("pggname\tv1\tsha256\t{}\t", NAME),
+        format!("pggnam\tv1\tsha256\t{}\tgraph.gfa", NAME),
+        format!("pggname\t1\tsha256\t{}\tgraph.gfa", NAME),
+        format!("pggname\tvx\tsha256\t{}\tgraph.gfa", NAME),
+        format!("pggname\tv1\t\t{}\tgraph.gfa", NAME),
+        format!("pggname\tv1\tsha256\t{}\tgraph.gfa", &NAME[..60]),
+        String::from("pggname\tv1\tblake3\tnot-hex\tgraph.gfa"),
+    ];
+    for line in invalid.iter() {
+        assert!(line.parse::<NameRecord>().is_err(), "Parsed an invalid record: {}", line);
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,110 @@
+//! Reading GFA input from remote URLs without a local copy.
+//!
+//! This module is only available with the `remote` feature.
+//! The data is streamed from an external program, so that the crate does not need an HTTP or TLS implementation:
+//!
+//! * `http://` and `https://` URLs are read with `curl`.
+//! * `s3://` URLs are read with the AWS CLI (`aws s3 cp URL -`), which uses the standard AWS credentials and configuration.
+//!
+//! The programs must be in `PATH`.
+//! Gzip-compressed input is decompressed transparently.
+//! If the program fails, reading the input returns an error at the end of the stream, so a partial download never produces a name.
+//!
+//! [`crate::algorithms::open_gfa`] uses this module for remote URLs, which makes them available wherever GFA files are accepted.
+
+use flate2::read::MultiGzDecoder;
+
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, ChildStdout, Command, Stdio};
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// URL schemes supported by [`open_url`].
+pub const SCHEMES: &[&str] = &["http://", "https://", "s3://"];
+
+/// Returns `true` if the input is a URL with a supported scheme.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::remote;
+///
+/// assert!(remote::is_url("https://example.org/graph.gfa.gz"));
+/// assert!(remote::is_url("s3://bucket/graph.gfa"));
+/// assert!(!remote::is_url("graph.gfa"));
+/// ```
+pub fn is_url(input: &str) -> bool {
+    SCHEMES.iter().any(|scheme| input.starts_with(scheme))
+}
+
+/// Opens a remote URL for reading.
+///
+/// Returns an error if the URL scheme is not supported or if the program for reading it cannot be started.
+/// Errors from the program are reported when reading reaches the end of the stream.
+pub fn open_url(url: &str) -> Result<Box<dyn BufRead>, String> {
+    let mut command = if url.starts_with("s3://") {
+        let mut command = Command::new("aws");
+        command.args(["s3", "cp", "--no-progress", url, "-"]);
+        command
+    } else if is_url(url) {
+        let mut command = Command::new("curl");
+        command.args(["--silent", "--show-error", "--fail", "--location", url]);
+        command
+    } else {
+        return Err(format!("Unsupported URL: {}", url));
+    };
+    tracing::debug!(url, "streaming a remote input");
+    open_command(&mut command, url)
+}
+
+// Reads the output of the command, decompressing it if necessary.
+fn open_command(command: &mut Command, description: &str) -> Result<Box<dyn BufRead>, String> {
+    let mut child = command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::inherit()).spawn()
+        .map_err(|e| format!("Error starting {:?} for {}: {}", command.get_program(), description, e))?;
+    let stdout = child.stdout.take().ok_or(format!("No output from the program for {}", description))?;
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut reader = BufReader::new(ChildReader { child, stdout, program, finished: false });
+    let compressed = reader.fill_buf().map_err(|e| format!("Error reading {}: {}", description, e))?.starts_with(&[0x1F, 0x8B]);
+    if compressed {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+// The output of a child process that checks the exit status at the end of the output.
+struct ChildReader {
+    child: Child,
+    stdout: ChildStdout,
+    program: String,
+    finished: bool,
+}
+
+impl Read for ChildReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes = self.stdout.read(buf)?;
+        if bytes == 0 && !buf.is_empty() && !self.finished {
+            self.finished = true;
+            let status = self.child.wait()?;
+            if !status.success() {
+                return Err(io::Error::other(format!("{} failed with {}", self.program, status)));
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+impl Drop for ChildReader {
+    fn drop(&mut self) {
+        // Stop a download that was abandoned before the end.
+        if !self.finished {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,70 @@
+use super::*;
+
+use crate::algorithms;
+use crate::graph::GraphInt;
+use crate::testing::TempFile;
+
+use gbz::support;
+
+use std::io::Write;
+
+//-----------------------------------------------------------------------------
+
+fn read_all(mut reader: Box<dyn BufRead>) -> Result<Vec<u8>, String> {
+    let mut result = Vec::new();
+    reader.read_to_end(&mut result).map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn urls() {
+    for url in ["http://example.org/graph.gfa", "https://example.org/graph.gfa.gz", "s3://bucket/key/graph.gfa"] {
+        assert!(is_url(url), "Did not recognize {}", url);
+    }
+    for input in ["graph.gfa", "/data/graph.gfa", "ftp://example.org/graph.gfa", "file:///data/graph.gfa"] {
+        assert!(!is_url(input), "Recognized {} as a URL", input);
+    }
+    assert!(open_url("graph.gfa").is_err(), "Opened a local file as a URL");
+}
+
+#[test]
+fn command_output() {
+    let filename = support::get_test_data("example.gfa");
+    let truth = std::fs::read(&filename).unwrap();
+    let reader = open_command(Command::new("cat").arg(&filename), "example.gfa").unwrap();
+    assert_eq!(read_all(reader), Ok(truth.clone()), "Wrong output from the command");
+
+    // Compressed output is decompressed.
+    let compressed = TempFile::new(".gfa.gz");
+    {
+        let file = std::fs::File::create(&compressed).unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        encoder.write_all(&truth).unwrap();
+        encoder.finish().unwrap();
+    }
+    let reader = open_command(Command::new("cat").arg(compressed.path()), "example.gfa.gz").unwrap();
+    let result = read_all(reader);
+    assert_eq!(result, Ok(truth.clone()), "Compressed output was not decompressed");
+
+    // The graph can be built from the stream.
+    let reader = open_command(Command::new("cat").arg(&filename), "example.gfa").unwrap();
+    let graph: GraphInt = algorithms::parse_gfa(reader).unwrap();
+    assert_eq!(crate::stable_name(&graph), crate::stable_name_from_file(&filename).unwrap(), "Wrong name from the stream");
+}
+
+#[test]
+fn command_failure() {
+    // A failure after partial output is an error at the end of the stream.
+    let filename = support::get_test_data("example.gfa");
+    let script = format!("cat '{}'; exit 3", filename.display());
+    let reader = open_command(Command::new("sh").args(["-c", &script]), "example.gfa").unwrap();
+    assert!(read_all(reader).is_err(), "Failure after partial output was not reported");
+    let reader = open_command(Command::new("sh").args(["-c", &script]), "example.gfa").unwrap();
+    assert!(algorithms::parse_gfa::<GraphInt, _>(reader).is_err(), "Built a graph from a failed download");
+
+    assert!(open_command(&mut Command::new("pggname-no-such-program"), "nothing").is_err(), "Started a missing program");
+}
+
+//-----------------------------------------------------------------------------
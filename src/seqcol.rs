@@ -0,0 +1,258 @@
+//! GA4GH sequence collection (seqcol) digests over the node sequences of a graph.
+//!
+//! A [sequence collection](https://ga4gh.github.io/seqcol-spec/) is a set of named sequences.
+//! Here the collection consists of the nodes of the graph in canonical order.
+//! Each node contributes its name, its sequence length, and the [refget](https://ga4gh.github.io/refget/) digest of its sequence.
+//!
+//! The digests follow the seqcol specification:
+//!
+//! * The refget digest of a sequence is `SQ.` followed by [`sha512t24u`] of the sequence in upper case.
+//! * The level 1 digest of an attribute is `sha512t24u` of the attribute array in canonical JSON (RFC 8785).
+//! * The top-level digest is `sha512t24u` of the canonical JSON object mapping the inherent attributes `names` and `sequences` to their level 1 digests.
+//!
+//! Unlike the stable graph name, the seqcol digest ignores edges.
+//! Two graphs with the same nodes but different edges have the same seqcol digest.
+//!
+//! # Node digest tables
+//!
+//! [`write_node_digests`] writes a sidecar table with the refget MD5 and `sha512t24u` digests of each node sequence.
+//! The nodes can then be resolved through sequence retrieval services that support refget.
+
+use crate::Graph;
+
+use md5::Md5;
+
+use sha2::{Digest, Sha512};
+
+use std::io::Write;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Returns the GA4GH `sha512t24u` digest of the data.
+///
+/// The digest consists of the first 24 bytes of the SHA-512 hash encoded in base64url without padding.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::seqcol;
+///
+/// assert_eq!(seqcol::sha512t24u(b"ACGT"), "aKF498dAxcJAqme6QYQ7EZ07-fiw8Kw2");
+/// ```
+pub fn sha512t24u(data: &[u8]) -> String {
+    let digest = Sha512::digest(data);
+    base64url(&digest[..24])
+}
+
+/// Returns the refget digest of the sequence.
+///
+/// The sequence is converted to upper case before hashing.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::seqcol;
+///
+/// assert_eq!(seqcol::refget_digest(b"acgt"), "SQ.aKF498dAxcJAqme6QYQ7EZ07-fiw8Kw2");
+/// ```
+pub fn refget_digest(sequence: &[u8]) -> String {
+    format!("SQ.{}", sha512t24u(&sequence.to_ascii_uppercase()))
+}
+
+/// Returns the refget MD5 digest of the sequence as a lowercase hexadecimal string.
+///
+/// The sequence is converted to upper case before hashing.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::seqcol;
+///
+/// assert_eq!(seqcol::refget_md5(b"acgt"), "f1f8f4bf413b16ad135722aa4591043e");
+/// ```
+pub fn refget_md5(sequence: &[u8]) -> String {
+    format!("{:x}", Md5::digest(sequence.to_ascii_uppercase()))
+}
+
+// Base64url encoding without padding.
+fn base64url(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let value = ((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | (bytes[2] as u32);
+        for i in 0..=chunk.len() {
+            result.push(ALPHABET[((value >> (18 - 6 * i)) & 0x3F) as usize] as char);
+        }
+    }
+    result
+}
+
+//-----------------------------------------------------------------------------
+
+/// A sequence collection built from the nodes of a graph.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+/// use pggname::seqcol::SequenceCollection;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let collection = SequenceCollection::new(&graph);
+/// assert_eq!(collection.names, vec!["1", "2"]);
+/// assert_eq!(collection.lengths, vec![4, 2]);
+///
+/// // Edges do not affect the digest.
+/// let nodes: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\n").unwrap();
+/// assert_eq!(SequenceCollection::new(&nodes).digest(), collection.digest());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SequenceCollection {
+    /// Node names in canonical order.
+    pub names: Vec<String>,
+    /// Sequence lengths.
+    pub lengths: Vec<usize>,
+    /// Refget digests of the sequences.
+    pub sequences: Vec<String>,
+}
+
+impl SequenceCollection {
+    /// Builds the collection from the nodes of the graph.
+    pub fn new<G: Graph>(graph: &G) -> Self {
+        let mut result = SequenceCollection::default();
+        for_each_node(graph, |name, sequence| {
+            result.names.push(String::from_utf8_lossy(name).into_owned());
+            result.lengths.push(sequence.len());
+            result.sequences.push(refget_digest(sequence));
+        });
+        result
+    }
+
+    /// Returns the number of sequences in the collection.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns `true` if the collection is empty.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the level 1 digest of the `names` attribute.
+    pub fn names_digest(&self) -> String {
+        sha512t24u(string_array(&self.names).as_bytes())
+    }
+
+    /// Returns the level 1 digest of the `lengths` attribute.
+    pub fn lengths_digest(&self) -> String {
+        let values: Vec<String> = self.lengths.iter().map(|len| len.to_string()).collect();
+        sha512t24u(format!("[{}]", values.join(",")).as_bytes())
+    }
+
+    /// Returns the level 1 digest of the `sequences` attribute.
+    pub fn sequences_digest(&self) -> String {
+        sha512t24u(string_array(&self.sequences).as_bytes())
+    }
+
+    /// Returns the top-level seqcol digest of the collection.
+    pub fn digest(&self) -> String {
+        let object = format!(
+            "{{\"names\":{},\"sequences\":{}}}",
+            json_string(&self.names_digest()), json_string(&self.sequences_digest())
+        );
+        sha512t24u(object.as_bytes())
+    }
+
+    /// Returns the level 1 representation of the collection as canonical JSON.
+    pub fn level1_json(&self) -> String {
+        format!(
+            "{{\"lengths\":{},\"names\":{},\"sequences\":{}}}",
+            json_string(&self.lengths_digest()), json_string(&self.names_digest()), json_string(&self.sequences_digest())
+        )
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// Header line of the node digest table.
+pub const NODE_DIGEST_HEADER: &str = "#name\tlength\tmd5\tsha512t24u";
+
+/// Writes the refget digests of the node sequences as a tab-separated table.
+///
+/// The first line is [`NODE_DIGEST_HEADER`].
+/// Each subsequent line contains the name of a node, its sequence length, and the MD5 and `sha512t24u` digests of the sequence.
+/// The nodes are listed in canonical order.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::{algorithms, seqcol};
+/// use pggname::graph::GraphInt;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\n").unwrap();
+/// let mut table: Vec<u8> = Vec::new();
+/// seqcol::write_node_digests(&graph, &mut table).unwrap();
+/// let expected = "#name\tlength\tmd5\tsha512t24u\n1\t4\tf1f8f4bf413b16ad135722aa4591043e\taKF498dAxcJAqme6QYQ7EZ07-fiw8Kw2\n";
+/// assert_eq!(String::from_utf8(table).unwrap(), expected);
+/// ```
+pub fn write_node_digests<G: Graph, W: Write>(graph: &G, mut writer: W) -> Result<(), String> {
+    writeln!(writer, "{}", NODE_DIGEST_HEADER).map_err(|e| e.to_string())?;
+    let mut result = Ok(());
+    for_each_node(graph, |name, sequence| {
+        if result.is_ok() {
+            let upper = sequence.to_ascii_uppercase();
+            result = writeln!(
+                writer, "{}\t{}\t{:x}\t{}",
+                String::from_utf8_lossy(name), sequence.len(), Md5::digest(&upper), sha512t24u(&upper)
+            ).map_err(|e| e.to_string());
+        }
+    });
+    result
+}
+
+// Calls the function with the name and sequence of each node in canonical order.
+fn for_each_node<G: Graph, F: FnMut(&[u8], &[u8])>(graph: &G, mut f: F) {
+    graph.for_each_node(|record| {
+        let line = record.split(|&c| c == b'\n').next().unwrap_or_default();
+        let mut fields = line.split(|&c| c == b'\t').skip(1);
+        let name = fields.next().unwrap_or_default();
+        let sequence = fields.next().unwrap_or_default();
+        f(name, sequence);
+    });
+}
+
+//-----------------------------------------------------------------------------
+
+// Serializes the strings as a canonical JSON array.
+fn string_array(values: &[String]) -> String {
+    let values: Vec<String> = values.iter().map(|value| json_string(value)).collect();
+    format!("[{}]", values.join(","))
+}
+
+/// Serializes the string as a canonical JSON string, including the quotes.
+pub fn json_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\u{08}' => result.push_str("\\b"),
+            '\u{0C}' => result.push_str("\\f"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+//-----------------------------------------------------------------------------
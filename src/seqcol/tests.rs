@@ -0,0 +1,46 @@
+use super::*;
+
+use crate::algorithms;
+use crate::graph::GraphStr;
+
+//-----------------------------------------------------------------------------
+
+// The sequences of the `base.fa` example from the seqcol specification as graph nodes.
+const BASE: &str = "S\tchrX\tTTGGGGAA\nS\tchr1\tGGAA\nS\tchr2\tGCGC\n";
+
+#[test]
+fn digests() {
+    assert_eq!(sha512t24u(b""), "z4PhNX7vuL3xVChQ1m2AB9Yg5AULVxXc", "Wrong digest for empty input");
+    assert_eq!(refget_digest(b"ACGT"), refget_digest(b"acgt"), "Refget digest depends on case");
+    assert_eq!(base64url(b"f"), "Zg", "Wrong encoding with one byte");
+    assert_eq!(base64url(b"fo"), "Zm8", "Wrong encoding with two bytes");
+    assert_eq!(base64url(b"foo"), "Zm9v", "Wrong encoding with three bytes");
+    assert_eq!(base64url(&[0xFB, 0xFF]), "-_8", "Wrong URL-safe characters");
+    assert_eq!(json_string("a\"b\\c\u{01}"), "\"a\\\"b\\\\c\\u0001\"", "Wrong JSON escaping");
+}
+
+#[test]
+fn collection() {
+    let graph: GraphStr = algorithms::parse_gfa_bytes(BASE.as_bytes()).unwrap();
+    let collection = SequenceCollection::new(&graph);
+    assert_eq!(collection.len(), 3, "Wrong number of sequences");
+    assert_eq!(collection.names, vec!["chr1", "chr2", "chrX"], "Wrong canonical order");
+    assert_eq!(collection.lengths, vec![4, 4, 8], "Wrong lengths");
+    assert_eq!(collection.sequences[0], "SQ.YBbVX0dLKG1ieEDCiMmkrTZFt_Z5Vdaj", "Wrong refget digest");
+}
+
+#[test]
+fn specification_example() {
+    // The collection in the order of the FASTA file.
+    let collection = SequenceCollection {
+        names: vec![String::from("chrX"), String::from("chr1"), String::from("chr2")],
+        lengths: vec![8, 4, 4],
+        sequences: vec![refget_digest(b"TTGGGGAA"), refget_digest(b"GGAA"), refget_digest(b"GCGC")],
+    };
+    assert_eq!(collection.names_digest(), "Fw1r9eRxfOZD98KKrhlYQNEdSRHoVxAG", "Wrong names digest");
+    assert_eq!(collection.lengths_digest(), "cGRMZIb3AVgkcAfNv39RN7hnT5Chk7RX", "Wrong lengths digest");
+    assert_eq!(collection.sequences_digest(), "0uDQVLuHaOZi1u76LjV__yrVUIz9Bwhr", "Wrong sequences digest");
+    assert_eq!(collection.digest(), "XZlrcEGi6mlopZ2uD8ObHkQB1d0oDwKk", "Wrong top-level digest");
+}
+
+//-----------------------------------------------------------------------------
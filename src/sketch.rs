@@ -0,0 +1,314 @@
+//! Sketches of canonical GFA representations for finding likely relationships between graphs.
+//!
+//! Computing the name of every graph in a large collection and verifying every potential relationship is expensive.
+//! Sketches are small summaries that can be used for estimating how much of graph A is contained in graph B.
+//! Graph pairs with high estimated containment are likely to be subgraphs or duplicates of each other, and they can then be verified exactly.
+//!
+//! The elements of a sketch are the lines of the canonical GFA representation of the graph.
+//! Each S-line and L-line is a separate element, as the node record of a subgraph may have fewer edges than the corresponding record in the supergraph.
+//! Elements are hashed into 64-bit integers using the first 8 bytes of their SHA-256 hash, which makes the sketches stable across platforms.
+//!
+//! Two kinds of sketches are supported:
+//!
+//! * [`MinHashSketch`] stores the smallest hash values (bottom-k MinHash) and estimates containment directly.
+//! * [`HyperLogLog`] estimates cardinalities, and containment is estimated using the inclusion-exclusion principle.
+
+use crate::Graph;
+
+use sha2::{Digest, Sha256};
+
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Returns the hash value of a sketch element.
+pub fn element_hash(element: &[u8]) -> u64 {
+    let digest = Sha256::digest(element);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+// Calls the function for the hash of each line in the canonical representation.
+fn for_each_element<G: Graph, F: FnMut(u64)>(graph: &G, mut f: F) {
+    graph.for_each_node(|record| {
+        for line in record.split(|&c| c == b'\n').filter(|line| !line.is_empty()) {
+            f(element_hash(line));
+        }
+    });
+}
+
+//-----------------------------------------------------------------------------
+
+/// A bottom-k MinHash sketch.
+///
+/// The sketch stores the `k` smallest distinct hash values of the elements.
+/// If the set has at most `k` elements, the sketch is exact.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+/// use pggname::sketch::MinHashSketch;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let subgraph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\n").unwrap();
+/// let sketch = MinHashSketch::new(&graph, 1000);
+/// let sub_sketch = MinHashSketch::new(&subgraph, 1000);
+/// assert_eq!(sub_sketch.containment(&sketch), Some(1.0));
+/// assert!(sketch.containment(&sub_sketch).unwrap() < 1.0);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MinHashSketch {
+    size: usize,
+    // Sorted hash values.
+    hashes: Vec<u64>,
+}
+
+impl MinHashSketch {
+    /// Header line of the text format.
+    pub const HEADER: &'static str = "pggname-minhash";
+
+    /// Default sketch size.
+    pub const DEFAULT_SIZE: usize = 1024;
+
+    /// Builds a sketch of the given size for the graph.
+    pub fn new<G: Graph>(graph: &G, size: usize) -> Self {
+        Self::from_hashes(size, |insert| for_each_element(graph, insert))
+    }
+
+    // Builds a sketch of the given size from the hash values passed to the callback.
+    fn from_hashes<F: FnOnce(&mut dyn FnMut(u64))>(size: usize, source: F) -> Self {
+        let mut smallest: BTreeSet<u64> = BTreeSet::new();
+        let mut insert = |hash: u64| {
+            if smallest.len() < size {
+                smallest.insert(hash);
+            } else if let Some(&max) = smallest.last() && hash < max && smallest.insert(hash) {
+                smallest.pop_last();
+            }
+        };
+        source(&mut insert);
+        MinHashSketch { size, hashes: smallest.into_iter().collect() }
+    }
+
+    /// Returns the maximum number of hash values in the sketch.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the hash values in the sketch in sorted order.
+    pub fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    /// Returns `true` if the sketch contains all elements of the set.
+    pub fn is_exact(&self) -> bool {
+        self.hashes.len() < self.size
+    }
+
+    /// Estimates the number of distinct elements in the set.
+    pub fn cardinality(&self) -> f64 {
+        if self.is_exact() || self.hashes.is_empty() {
+            return self.hashes.len() as f64;
+        }
+        let max = *self.hashes.last().unwrap() as f64 / u64::MAX as f64;
+        (self.hashes.len() - 1) as f64 / max
+    }
+
+    /// Estimates the fraction of the elements of this set that are also in the other set.
+    ///
+    /// Uses the hash values of this sketch that are within the range covered by the other sketch.
+    /// Returns [`None`] if there are no such values, which happens if either sketch is empty.
+    pub fn containment(&self, other: &MinHashSketch) -> Option<f64> {
+        let threshold = if other.is_exact() { u64::MAX } else { *other.hashes.last()? };
+        let mut total = 0;
+        let mut shared = 0;
+        for hash in self.hashes.iter().take_while(|&&hash| hash <= threshold) {
+            total += 1;
+            if other.hashes.binary_search(hash).is_ok() {
+                shared += 1;
+            }
+        }
+        if total == 0 { None } else { Some(shared as f64 / total as f64) }
+    }
+
+    /// Estimates the Jaccard similarity between the sets.
+    ///
+    /// Returns [`None`] if both sketches are empty.
+    pub fn jaccard(&self, other: &MinHashSketch) -> Option<f64> {
+        let size = self.size.min(other.size);
+        let union = Self::from_hashes(size, |insert| {
+            self.hashes.iter().chain(other.hashes.iter()).for_each(|&hash| insert(hash));
+        });
+        if union.hashes.is_empty() {
+            return None;
+        }
+        let shared = union.hashes.iter()
+            .filter(|hash| self.hashes.binary_search(hash).is_ok() && other.hashes.binary_search(hash).is_ok())
+            .count();
+        Some(shared as f64 / union.hashes.len() as f64)
+    }
+
+    /// Writes the sketch in a text format.
+    ///
+    /// The first line contains the header and the sketch size, separated by a tab.
+    /// Each subsequent line contains a hash value as a 16-digit hexadecimal number.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), String> {
+        writeln!(writer, "{}\t{}", Self::HEADER, self.size).map_err(|e| e.to_string())?;
+        for hash in self.hashes.iter() {
+            writeln!(writer, "{:016x}", hash).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Reads a sketch in the text format.
+    ///
+    /// Returns an error if the input cannot be parsed.
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, String> {
+        let mut lines = reader.lines();
+        let header = lines.next().ok_or(String::from("Missing sketch header"))?.map_err(|e| e.to_string())?;
+        let size = header.strip_prefix(Self::HEADER).and_then(|rest| rest.strip_prefix('\t'))
+            .and_then(|size| size.parse::<usize>().ok())
+            .ok_or(format!("Invalid sketch header: {}", header))?;
+
+        let mut hashes = Vec::new();
+        for line in lines {
+            let line = line.map_err(|e| e.to_string())?;
+            let hash = u64::from_str_radix(&line, 16).map_err(|_| format!("Invalid hash value: {}", line))?;
+            if hashes.last().is_some_and(|&prev| prev >= hash) {
+                return Err(String::from("Hash values are not in sorted order"));
+            }
+            hashes.push(hash);
+        }
+        if hashes.len() > size {
+            return Err(format!("Sketch has {} values but size {}", hashes.len(), size));
+        }
+
+        Ok(MinHashSketch { size, hashes })
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// A HyperLogLog sketch for estimating the number of distinct elements.
+///
+/// The sketch uses `2^precision` one-byte registers.
+/// Sketches with the same precision can be merged to estimate the cardinality of the union.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+/// use pggname::sketch::HyperLogLog;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t1\tACGT\nS\t2\tGA\nL\t1\t+\t2\t+\t*\n").unwrap();
+/// let sketch = HyperLogLog::new(&graph, HyperLogLog::DEFAULT_PRECISION).unwrap();
+/// assert_eq!(sketch.cardinality().round(), 3.0);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Default precision.
+    pub const DEFAULT_PRECISION: u8 = 12;
+
+    /// Minimum supported precision.
+    pub const MIN_PRECISION: u8 = 4;
+
+    /// Maximum supported precision.
+    pub const MAX_PRECISION: u8 = 18;
+
+    /// Creates an empty sketch with the given precision.
+    ///
+    /// Returns an error if the precision is not supported.
+    pub fn empty(precision: u8) -> Result<Self, String> {
+        if !(Self::MIN_PRECISION..=Self::MAX_PRECISION).contains(&precision) {
+            return Err(format!(
+                "HyperLogLog precision must be between {} and {}", Self::MIN_PRECISION, Self::MAX_PRECISION
+            ));
+        }
+        Ok(HyperLogLog { precision, registers: vec![0; 1 << precision] })
+    }
+
+    /// Builds a sketch with the given precision for the graph.
+    ///
+    /// Returns an error if the precision is not supported.
+    pub fn new<G: Graph>(graph: &G, precision: u8) -> Result<Self, String> {
+        let mut result = Self::empty(precision)?;
+        for_each_element(graph, |hash| result.insert(hash));
+        Ok(result)
+    }
+
+    /// Returns the precision of the sketch.
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Inserts a hash value into the sketch.
+    pub fn insert(&mut self, hash: u64) {
+        let index = (hash >> (64 - self.precision)) as usize;
+        let rest = hash << self.precision;
+        let rank = (rest.leading_zeros() as u8).min(64 - self.precision) + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Merges the other sketch into this one.
+    ///
+    /// Returns an error if the precisions differ.
+    pub fn merge(&mut self, other: &HyperLogLog) -> Result<(), String> {
+        if self.precision != other.precision {
+            return Err(format!("Cannot merge HyperLogLog sketches with precisions {} and {}", self.precision, other.precision));
+        }
+        for (register, &value) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *register = (*register).max(value);
+        }
+        Ok(())
+    }
+
+    /// Estimates the number of distinct elements in the set.
+    pub fn cardinality(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self.registers.iter().map(|&value| 2.0f64.powi(-(value as i32))).sum();
+        let estimate = alpha * m * m / sum;
+
+        // Use linear counting for small cardinalities.
+        let zeros = self.registers.iter().filter(|&&value| value == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        }
+    }
+
+    /// Estimates the fraction of the elements of this set that are also in the other set.
+    ///
+    /// The estimate is based on the inclusion-exclusion principle and clamped to the interval from 0 to 1.
+    /// Returns an error if the precisions differ.
+    pub fn containment(&self, other: &HyperLogLog) -> Result<f64, String> {
+        let mut union = self.clone();
+        union.merge(other)?;
+        let own = self.cardinality();
+        if own == 0.0 {
+            return Ok(0.0);
+        }
+        let intersection = own + other.cardinality() - union.cardinality();
+        Ok((intersection / own).clamp(0.0, 1.0))
+    }
+}
+
+//-----------------------------------------------------------------------------
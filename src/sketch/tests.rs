@@ -0,0 +1,119 @@
+use super::*;
+
+#[cfg(feature = "gbz")]
+use crate::algorithms;
+#[cfg(feature = "gbz")]
+use crate::graph::GraphInt;
+
+#[cfg(feature = "gbz")]
+use gbz::support;
+
+//-----------------------------------------------------------------------------
+
+#[cfg(feature = "gbz")]
+// Returns the example graph and a subgraph without the nodes of the second component.
+fn graphs() -> (GraphInt, GraphInt) {
+    let gfa = std::fs::read(support::get_test_data("example.gfa")).unwrap();
+    let graph: GraphInt = algorithms::parse_gfa_bytes(&gfa).unwrap();
+    let lines: Vec<&[u8]> = gfa.split(|&c| c == b'\n')
+        .filter(|line| !line.starts_with(b"S\t2") && !line.starts_with(b"L\t2"))
+        .collect();
+    let subgraph: GraphInt = algorithms::parse_gfa_bytes(&lines.join(&b'\n')).unwrap();
+    (graph, subgraph)
+}
+
+#[cfg(feature = "gbz")]
+// Number of S-lines and L-lines in the canonical representation.
+fn element_count<G: Graph>(graph: &G) -> usize {
+    let stats = graph.statistics();
+    (stats.nodes + stats.edges) as usize
+}
+
+//-----------------------------------------------------------------------------
+
+#[cfg(feature = "gbz")]
+#[test]
+fn minhash_exact() {
+    let (graph, subgraph) = graphs();
+    let sketch = MinHashSketch::new(&graph, MinHashSketch::DEFAULT_SIZE);
+    let sub_sketch = MinHashSketch::new(&subgraph, MinHashSketch::DEFAULT_SIZE);
+    assert!(sketch.is_exact(), "Sketch of a small graph is not exact");
+    assert_eq!(sketch.cardinality(), element_count(&graph) as f64, "Wrong cardinality");
+    assert_eq!(sub_sketch.cardinality(), element_count(&subgraph) as f64, "Wrong cardinality for the subgraph");
+
+    assert_eq!(sketch.containment(&sketch), Some(1.0), "Graph is not contained in itself");
+    assert_eq!(sub_sketch.containment(&sketch), Some(1.0), "Subgraph is not contained in the graph");
+    let expected = element_count(&subgraph) as f64 / element_count(&graph) as f64;
+    assert_eq!(sketch.containment(&sub_sketch), Some(expected), "Wrong containment for the graph in the subgraph");
+    assert_eq!(sketch.jaccard(&sub_sketch), Some(expected), "Wrong Jaccard similarity");
+
+    let empty = MinHashSketch::new(&GraphInt::new(), 16);
+    assert_eq!(empty.containment(&sketch), None, "Containment for an empty sketch");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn minhash_sampled() {
+    let (graph, subgraph) = graphs();
+    let size = 8;
+    let sketch = MinHashSketch::new(&graph, size);
+    let sub_sketch = MinHashSketch::new(&subgraph, size);
+    assert!(!sketch.is_exact(), "Small sketch is exact");
+    assert_eq!(sketch.hashes().len(), size, "Wrong number of hash values");
+    assert!(sketch.hashes().windows(2).all(|pair| pair[0] < pair[1]), "Hash values are not sorted");
+
+    // Sampled hash values of the subgraph are also in the graph.
+    assert_eq!(sub_sketch.containment(&sketch), Some(1.0), "Subgraph is not contained in the graph");
+    let containment = sketch.containment(&sub_sketch).unwrap();
+    assert!(containment < 1.0, "Graph is contained in the subgraph");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn minhash_text_format() {
+    let (graph, _) = graphs();
+    let sketch = MinHashSketch::new(&graph, 8);
+    let mut buffer: Vec<u8> = Vec::new();
+    sketch.write(&mut buffer).unwrap();
+    assert_eq!(MinHashSketch::read(buffer.as_slice()), Ok(sketch), "Wrong sketch after a round trip");
+
+    assert!(MinHashSketch::read(b"minhash\t8\n".as_slice()).is_err(), "Accepted an invalid header");
+    assert!(MinHashSketch::read(b"pggname-minhash\t8\n2\n1\n".as_slice()).is_err(), "Accepted unsorted values");
+    assert!(MinHashSketch::read(b"pggname-minhash\t1\n1\n2\n".as_slice()).is_err(), "Accepted too many values");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn hyperloglog() {
+    let (graph, subgraph) = graphs();
+    let sketch = HyperLogLog::new(&graph, HyperLogLog::DEFAULT_PRECISION).unwrap();
+    let sub_sketch = HyperLogLog::new(&subgraph, HyperLogLog::DEFAULT_PRECISION).unwrap();
+    assert_eq!(sketch.cardinality().round(), element_count(&graph) as f64, "Wrong cardinality");
+    assert_eq!(sub_sketch.cardinality().round(), element_count(&subgraph) as f64, "Wrong cardinality for the subgraph");
+
+    let containment = sub_sketch.containment(&sketch).unwrap();
+    assert!(containment > 0.95, "Subgraph is not contained in the graph: {}", containment);
+    let containment = sketch.containment(&sub_sketch).unwrap();
+    assert!(containment < 0.95, "Graph is contained in the subgraph: {}", containment);
+
+    let mut merged = sub_sketch.clone();
+    merged.merge(&sketch).unwrap();
+    assert_eq!(merged, sketch, "Merging a subgraph sketch changed the sketch");
+
+    let other = HyperLogLog::new(&graph, 10).unwrap();
+    assert!(merged.merge(&other).is_err(), "Merged sketches with different precisions");
+    assert!(HyperLogLog::empty(2).is_err(), "Created a sketch with too low precision");
+}
+
+#[test]
+fn many_elements() {
+    let mut sketch = HyperLogLog::empty(HyperLogLog::DEFAULT_PRECISION).unwrap();
+    let count = 100000;
+    for i in 0..count {
+        sketch.insert(element_hash(format!("element {}", i).as_bytes()));
+    }
+    let error = (sketch.cardinality() - count as f64).abs() / count as f64;
+    assert!(error < 0.05, "Relative error too large: {}", error);
+}
+
+//-----------------------------------------------------------------------------
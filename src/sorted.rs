@@ -0,0 +1,531 @@
+//! Hashing GFA files that are already in canonical order, with checkpoints for resuming interrupted runs.
+//!
+//! If a GFA file already contains the canonical GFA representation of a graph, its stable name can be computed while reading the file, without building the graph.
+//! [`SortedGfaHasher`] validates the order of the records and hashes them with SHA-256.
+//! Memory usage depends only on the number of nodes that have been referenced by links but not seen yet.
+//!
+//! The input must consist of canonical S-lines and L-lines, as written by the `pggname` tool with `subset --gfa`.
+//! Lines of other types (headers, paths, walks, and comments) are ignored.
+//! Records with optional fields, links with overlaps, and out-of-order or non-canonical records are errors.
+//!
+//! # Checkpoints
+//!
+//! A [`Checkpoint`] captures the state of the hasher before a segment line: the SHA-256 state, the number of input bytes consumed, and the state needed for validating the rest of the input.
+//! It also stores a SHA-256 digest of the consumed input, which is used for checking that the input has not changed before resuming.
+//! Checkpoints are written as text with [`std::fmt::Display`] and read back with [`std::str::FromStr`].
+//! A job that was interrupted can resume from the latest checkpoint with [`SortedGfaHasher::resume`].
+//! [`hash_sorted_gfa`] writes checkpoints periodically and skips the part of the input that was already hashed, after checking it against the digest.
+//!
+//! # Examples
+//!
+//! ```
+//! use pggname::algorithms::{self, NodeIds};
+//! use pggname::graph::GraphInt;
+//! use pggname::sorted::{self, Checkpoint, SortedGfaHasher};
+//!
+//! let gfa = b"S\t1\tA\nL\t1\t+\t2\t+\nS\t2\tC\nL\t2\t+\t3\t-\nS\t3\tG\n";
+//! let graph: GraphInt = algorithms::parse_gfa_bytes(gfa).unwrap();
+//!
+//! // Hash the input and save a checkpoint before every segment after the first.
+//! let mut checkpoints: Vec<String> = Vec::new();
+//! let save = |checkpoint: &Checkpoint| { checkpoints.push(checkpoint.to_string()); Ok(()) };
+//! let name = sorted::hash_sorted_gfa(&gfa[..], SortedGfaHasher::new(NodeIds::Integer).unwrap(), 1, save).unwrap();
+//! assert_eq!(name, pggname::stable_name(&graph));
+//!
+//! // Resume from the first checkpoint.
+//! let checkpoint: Checkpoint = checkpoints[0].parse().unwrap();
+//! assert_eq!(checkpoint.nodes(), 1);
+//! let hasher = SortedGfaHasher::resume(&checkpoint).unwrap();
+//! let resumed = sorted::hash_sorted_gfa(&gfa[..], hasher, 0, |_| Ok(())).unwrap();
+//! assert_eq!(resumed, name);
+//! ```
+
+use crate::Orientation;
+use crate::algorithms::{self, NodeIds};
+use crate::graph::GraphInt;
+
+use sha2::compress256;
+use sha2::digest::generic_array::GenericArray;
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::{self, BufRead, Read};
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// SHA-256 with a state that can be saved and restored.
+///
+/// The `sha2` crate does not expose the internal state of its hashers, so this uses the compression function directly.
+/// The result is the same as with `sha2::Sha256`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResumableSha256 {
+    state: [u32; 8],
+    // Bytes that do not fill a complete block yet.
+    buffer: Vec<u8>,
+    // Total number of bytes hashed.
+    length: u64,
+}
+
+impl ResumableSha256 {
+    /// Size of a SHA-256 block in bytes.
+    pub const BLOCK_SIZE: usize = 64;
+
+    const INITIAL_STATE: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+
+    /// Creates a new hasher.
+    pub fn new() -> Self {
+        ResumableSha256 { state: Self::INITIAL_STATE, buffer: Vec::with_capacity(Self::BLOCK_SIZE), length: 0 }
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        compress256(&mut self.state, std::slice::from_ref(GenericArray::from_slice(block)));
+    }
+
+    /// Adds the bytes to the hashed data.
+    pub fn update(&mut self, mut bytes: &[u8]) {
+        self.length += bytes.len() as u64;
+        if !self.buffer.is_empty() {
+            let needed = Self::BLOCK_SIZE - self.buffer.len();
+            if bytes.len() < needed {
+                self.buffer.extend_from_slice(bytes);
+                return;
+            }
+            self.buffer.extend_from_slice(&bytes[..needed]);
+            let block = std::mem::take(&mut self.buffer);
+            self.compress(&block);
+            self.buffer = block;
+            self.buffer.clear();
+            bytes = &bytes[needed..];
+        }
+        let mut blocks = bytes.chunks_exact(Self::BLOCK_SIZE);
+        for block in blocks.by_ref() {
+            self.compress(block);
+        }
+        self.buffer.extend_from_slice(blocks.remainder());
+    }
+
+    /// Returns the hash as lowercase hexadecimal digits.
+    pub fn finalize(mut self) -> String {
+        let bits = self.length.wrapping_mul(8);
+        let mut padding = vec![0x80];
+        let padded = (self.buffer.len() + 1 + 8).next_multiple_of(Self::BLOCK_SIZE);
+        padding.resize(padded - self.buffer.len() - 8, 0);
+        padding.extend_from_slice(&bits.to_be_bytes());
+        self.update(&padding);
+        self.state.iter().map(|word| format!("{:08x}", word)).collect()
+    }
+}
+
+impl Default for ResumableSha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// Suggested number of nodes between checkpoints in [`hash_sorted_gfa`].
+pub const CHECKPOINT_INTERVAL: usize = 1 << 20;
+
+// Sort key for node names: the integer value for integer identifiers, or the name itself for string identifiers.
+type NodeKey = (usize, Vec<u8>);
+
+/// The state of a [`SortedGfaHasher`] before a segment line.
+///
+/// The text format has one `key<TAB>value` pair per line, starting with the format version.
+/// See the [module documentation](self) for an example.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Size of the input file when the checkpoint was written, or `0` if not known.
+    ///
+    /// The hasher does not use this; it is for detecting that the input has changed before resuming.
+    pub input_size: u64,
+    node_ids: NodeIds,
+    offset: u64,
+    // SHA-256 digest of the first `offset` bytes of the input.
+    prefix: String,
+    lines: usize,
+    nodes: usize,
+    hasher: ResumableSha256,
+    last_node: Option<Vec<u8>>,
+    pending: Vec<Vec<u8>>,
+}
+
+impl Checkpoint {
+    /// Format version written in the first line of a checkpoint.
+    pub const VERSION: usize = 2;
+
+    const HEADER: &'static str = "pggname-checkpoint";
+
+    /// Returns the number of input bytes consumed before the checkpoint.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the number of nodes hashed before the checkpoint.
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// Returns the SHA-256 digest of the input bytes consumed before the checkpoint.
+    pub fn prefix_digest(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns the node identifier mode of the hasher.
+    pub fn node_ids(&self) -> NodeIds {
+        self.node_ids
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hex_decode(value: &str) -> Result<Vec<u8>, String> {
+    if !value.len().is_multiple_of(2) {
+        return Err(format!("Invalid hexadecimal value: {}", value));
+    }
+    (0..value.len()).step_by(2).map(|i| {
+        u8::from_str_radix(value.get(i..i + 2).unwrap_or_default(), 16).map_err(|_| format!("Invalid hexadecimal value: {}", value))
+    }).collect()
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}\t{}", Self::HEADER, Self::VERSION)?;
+        let node_ids = if self.node_ids == NodeIds::String { "string" } else { "integer" };
+        writeln!(f, "node_ids\t{}", node_ids)?;
+        writeln!(f, "input_size\t{}", self.input_size)?;
+        writeln!(f, "offset\t{}", self.offset)?;
+        writeln!(f, "prefix\t{}", self.prefix)?;
+        writeln!(f, "lines\t{}", self.lines)?;
+        writeln!(f, "nodes\t{}", self.nodes)?;
+        let state: Vec<String> = self.hasher.state.iter().map(|word| format!("{:08x}", word)).collect();
+        writeln!(f, "state\t{}", state.join(","))?;
+        writeln!(f, "length\t{}", self.hasher.length)?;
+        writeln!(f, "buffer\t{}", hex_encode(&self.hasher.buffer))?;
+        if let Some(name) = &self.last_node {
+            writeln!(f, "last_node\t{}", hex_encode(name))?;
+        }
+        for name in self.pending.iter() {
+            writeln!(f, "pending\t{}", hex_encode(name))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Checkpoint {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut lines = value.lines();
+        let version = lines.next().and_then(|line| line.strip_prefix(Self::HEADER)).and_then(|rest| rest.strip_prefix('\t'));
+        match version {
+            Some(version) if version == Self::VERSION.to_string() => {}
+            Some(version) => return Err(format!("Unsupported checkpoint version: {}", version)),
+            None => return Err(String::from("Not a pggname checkpoint")),
+        }
+
+        let mut result = Checkpoint {
+            input_size: 0, node_ids: NodeIds::Integer, offset: 0, prefix: String::new(), lines: 0, nodes: 0,
+            hasher: ResumableSha256::new(), last_node: None, pending: Vec::new(),
+        };
+        let number = |key: &str, value: &str| value.parse::<u64>().map_err(|_| format!("Invalid checkpoint {}: {}", key, value));
+        for line in lines {
+            let (key, value) = line.split_once('\t').ok_or(format!("Invalid checkpoint line: {}", line))?;
+            match key {
+                "node_ids" => result.node_ids = match value {
+                    "integer" => NodeIds::Integer,
+                    "string" => NodeIds::String,
+                    _ => return Err(format!("Invalid checkpoint node_ids: {}", value)),
+                },
+                "input_size" => result.input_size = number(key, value)?,
+                "offset" => result.offset = number(key, value)?,
+                "prefix" => {
+                    if value.len() != 64 || !value.bytes().all(|c| c.is_ascii_digit() || (b'a'..=b'f').contains(&c)) {
+                        return Err(format!("Invalid checkpoint prefix: {}", value));
+                    }
+                    result.prefix = String::from(value);
+                }
+                "lines" => result.lines = number(key, value)? as usize,
+                "nodes" => result.nodes = number(key, value)? as usize,
+                "state" => {
+                    let words: Vec<u32> = value.split(',').map(|word| u32::from_str_radix(word, 16))
+                        .collect::<Result<_, _>>().map_err(|_| format!("Invalid checkpoint state: {}", value))?;
+                    result.hasher.state = words.try_into().map_err(|_| format!("Invalid checkpoint state: {}", value))?;
+                }
+                "length" => result.hasher.length = number(key, value)?,
+                "buffer" => result.hasher.buffer = hex_decode(value)?,
+                "last_node" => result.last_node = Some(hex_decode(value)?),
+                "pending" => result.pending.push(hex_decode(value)?),
+                _ => return Err(format!("Unknown checkpoint key: {}", key)),
+            }
+        }
+        if result.prefix.is_empty() {
+            return Err(String::from("Missing input digest in the checkpoint"));
+        }
+        if result.hasher.buffer.len() >= ResumableSha256::BLOCK_SIZE || result.hasher.buffer.len() as u64 != result.hasher.length % ResumableSha256::BLOCK_SIZE as u64 {
+            return Err(String::from("Inconsistent hasher state in the checkpoint"));
+        }
+        Ok(result)
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// A hasher that validates and hashes GFA lines in canonical order.
+///
+/// See the [module documentation](self) for the accepted input.
+#[derive(Clone, Debug)]
+pub struct SortedGfaHasher {
+    node_ids: NodeIds,
+    hasher: ResumableSha256,
+    offset: u64,
+    // Digest of all consumed input, for checkpoints.
+    input: ResumableSha256,
+    // Input bytes that must be skipped before the next line, with the expected digest of the skipped bytes.
+    skip: Option<(u64, String)>,
+    lines: usize,
+    nodes: usize,
+    // The current node as (key, name).
+    current: Option<(NodeKey, Vec<u8>)>,
+    // The last edge of the current node as (source orientation, destination, destination orientation).
+    last_edge: Option<(Orientation, NodeKey, Orientation)>,
+    // Destinations of links that have not been seen yet, with their names.
+    pending: BTreeSet<(NodeKey, Vec<u8>)>,
+}
+
+impl SortedGfaHasher {
+    /// Creates a hasher for the given node identifier mode.
+    ///
+    /// Returns an error with [`NodeIds::Auto`], as the mode must be known before reading the input.
+    pub fn new(node_ids: NodeIds) -> Result<Self, String> {
+        if node_ids == NodeIds::Auto {
+            return Err(String::from("Hashing sorted GFA requires integer or string node identifiers"));
+        }
+        Ok(SortedGfaHasher {
+            node_ids,
+            hasher: ResumableSha256::new(),
+            offset: 0, input: ResumableSha256::new(), skip: None, lines: 0, nodes: 0,
+            current: None, last_edge: None, pending: BTreeSet::new(),
+        })
+    }
+
+    /// Creates a hasher that continues from the checkpoint.
+    ///
+    /// The first [`Checkpoint::offset`] bytes of the input must be skipped with [`SortedGfaHasher::skip_prefix`] before the remaining lines.
+    /// [`hash_sorted_gfa`] does this automatically.
+    /// Returns an error if the checkpoint contains node names that are invalid in its node identifier mode.
+    pub fn resume(checkpoint: &Checkpoint) -> Result<Self, String> {
+        let node = |name: &Vec<u8>| Self::key_for(checkpoint.node_ids, name).map(|key| (key, name.clone()));
+        Ok(SortedGfaHasher {
+            node_ids: checkpoint.node_ids,
+            hasher: checkpoint.hasher.clone(),
+            offset: checkpoint.offset,
+            input: ResumableSha256::new(),
+            skip: Some((checkpoint.offset, checkpoint.prefix.clone())),
+            lines: checkpoint.lines,
+            nodes: checkpoint.nodes,
+            current: checkpoint.last_node.as_ref().map(node).transpose()?,
+            last_edge: None,
+            pending: checkpoint.pending.iter().map(node).collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn key_for(node_ids: NodeIds, name: &[u8]) -> Result<NodeKey, String> {
+        if name.is_empty() {
+            return Err(String::from("Empty node name"));
+        }
+        if node_ids == NodeIds::String {
+            return Ok((0, name.to_vec()));
+        }
+        if !GraphInt::is_canonical_id(name) {
+            return Err(format!("Node name {} is not a canonical integer identifier", String::from_utf8_lossy(name)));
+        }
+        GraphInt::parse_id(name).map(|id| (id, Vec::new()))
+    }
+
+    /// Skips the part of the input that was hashed before the checkpoint this hasher was resumed from.
+    ///
+    /// Returns `false` if the input ends before the checkpoint or if the skipped bytes differ from the input of the checkpoint.
+    /// In that case, the checkpoint cannot be used with this input.
+    /// Does nothing if there is nothing to skip.
+    /// Returns an error if reading the input fails.
+    pub fn skip_prefix<R: Read>(&mut self, reader: &mut R) -> Result<bool, String> {
+        let Some((len, digest)) = self.skip.take() else {
+            return Ok(true);
+        };
+        let mut input = ResumableSha256::new();
+        let mut buffer = vec![0; 1 << 16];
+        let mut remaining = len;
+        while remaining > 0 {
+            let chunk = remaining.min(buffer.len() as u64) as usize;
+            let bytes = match reader.read(&mut buffer[..chunk]) {
+                Ok(bytes) => bytes,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(format!("Error skipping the hashed input: {}", e)),
+            };
+            if bytes == 0 {
+                return Ok(false);
+            }
+            input.update(&buffer[..bytes]);
+            remaining -= bytes as u64;
+        }
+        let matches = input.clone().finalize() == digest;
+        self.input = input;
+        Ok(matches)
+    }
+
+    /// Returns the number of input bytes consumed so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the number of nodes hashed so far.
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// Returns a checkpoint with the current state.
+    ///
+    /// The checkpoint is only valid before a segment line, as the state of the current node is not saved.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            input_size: 0,
+            node_ids: self.node_ids,
+            offset: self.offset,
+            prefix: self.input.clone().finalize(),
+            lines: self.lines,
+            nodes: self.nodes,
+            hasher: self.hasher.clone(),
+            last_node: self.current.as_ref().map(|(_, name)| name.clone()),
+            pending: self.pending.iter().map(|(_, name)| name.clone()).collect(),
+        }
+    }
+
+    /// Processes the next line of input, including the newline if there is one.
+    ///
+    /// Returns an error with the line number if the line is not valid canonical GFA in the expected order.
+    pub fn push_line(&mut self, line: &[u8]) -> Result<(), String> {
+        self.offset += line.len() as u64;
+        self.input.update(line);
+        self.lines += 1;
+        let content = line.strip_suffix(b"\n").unwrap_or(line);
+        self.process(content).map_err(|e| format!("Sorted GFA line {}: {}", self.lines, e))
+    }
+
+    fn process(&mut self, line: &[u8]) -> Result<(), String> {
+        if line.ends_with(b"\r") {
+            return Err(String::from("CRLF line ending"));
+        }
+        let fields: Vec<&[u8]> = line.split(|&c| c == b'\t').collect();
+        match fields[0] {
+            b"S" => self.segment(&fields)?,
+            b"L" => self.link(&fields)?,
+            _ => return Ok(()),
+        }
+        self.hasher.update(line);
+        self.hasher.update(b"\n");
+        Ok(())
+    }
+
+    fn segment(&mut self, fields: &[&[u8]]) -> Result<(), String> {
+        if fields.len() != 3 {
+            return Err(format!("Expected 3 fields in a segment line, found {}", fields.len()));
+        }
+        let key = Self::key_for(self.node_ids, fields[1])?;
+        if let Some((prev, name)) = &self.current && key <= *prev {
+            return Err(format!("Segment {} is not after segment {}", String::from_utf8_lossy(fields[1]), String::from_utf8_lossy(name)));
+        }
+        while let Some(first) = self.pending.first() && first.0 <= key {
+            let (pending, name) = self.pending.pop_first().unwrap();
+            if pending < key {
+                return Err(format!("Missing segment {} before segment {}", String::from_utf8_lossy(&name), String::from_utf8_lossy(fields[1])));
+            }
+        }
+        self.current = Some((key, fields[1].to_vec()));
+        self.last_edge = None;
+        self.nodes += 1;
+        Ok(())
+    }
+
+    fn link(&mut self, fields: &[&[u8]]) -> Result<(), String> {
+        if fields.len() != 5 {
+            return Err(format!("Expected 5 fields in a link line, found {}", fields.len()));
+        }
+        let Some((source, name)) = &self.current else {
+            return Err(String::from("Link before the first segment"));
+        };
+        if fields[1] != name.as_slice() {
+            return Err(format!("Link from {} is not after its source segment", String::from_utf8_lossy(fields[1])));
+        }
+        let source_o = algorithms::parse_orientation(fields[2])?;
+        let dest = Self::key_for(self.node_ids, fields[3])?;
+        let dest_o = algorithms::parse_orientation(fields[4])?;
+        let canonical = dest > *source || (dest == *source && (source_o == Orientation::Forward || dest_o == Orientation::Forward));
+        if !canonical {
+            return Err(String::from("Link is not in canonical orientation"));
+        }
+        let edge = (source_o, dest, dest_o);
+        if self.last_edge.as_ref().is_some_and(|last| edge <= *last) {
+            return Err(String::from("Links of the segment are not sorted"));
+        }
+        if edge.1 != *source {
+            self.pending.insert((edge.1.clone(), fields[3].to_vec()));
+        }
+        self.last_edge = Some(edge);
+        Ok(())
+    }
+
+    /// Finishes hashing and returns the stable name.
+    ///
+    /// Returns an error if some links refer to segments that were not in the input.
+    pub fn finish(self) -> Result<String, String> {
+        if let Some((_, name)) = self.pending.first() {
+            return Err(format!("Missing segment {} at the end of the input", String::from_utf8_lossy(name)));
+        }
+        Ok(self.hasher.finalize())
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// Computes the stable name of sorted GFA input, saving a checkpoint every `interval` nodes.
+///
+/// The reader must start at the beginning of the input.
+/// A resumed hasher skips the bytes it has already consumed without validating or hashing them, but the skipped bytes must match the digest in the checkpoint.
+/// The checkpoints are passed to `save` before segment lines, and an error from `save` stops hashing.
+/// With `interval == 0`, no checkpoints are saved.
+/// Returns an error if reading the input fails or if the input is not valid sorted GFA.
+pub fn hash_sorted_gfa<R: BufRead, F: FnMut(&Checkpoint) -> Result<(), String>>(
+    mut reader: R, mut hasher: SortedGfaHasher, interval: usize, mut save: F
+) -> Result<String, String> {
+    let _span = tracing::debug_span!("hash_sorted_gfa", offset = hasher.offset()).entered();
+    if !hasher.skip_prefix(&mut reader)? {
+        return Err(format!("The first {} bytes of the input do not match the checkpoint", hasher.offset()));
+    }
+
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let len = reader.read_until(b'\n', &mut line).map_err(|e| format!("Error reading sorted GFA: {}", e))?;
+        if len == 0 {
+            break;
+        }
+        if interval > 0 && line.starts_with(b"S\t") && hasher.nodes().is_multiple_of(interval) && hasher.nodes() > 0 {
+            save(&hasher.checkpoint())?;
+        }
+        hasher.push_line(&line)?;
+    }
+    tracing::debug!(nodes = hasher.nodes(), "hashed the sorted GFA");
+    hasher.finish()
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,156 @@
+use super::*;
+
+#[cfg(feature = "gbz")]
+use crate::{Graph, algorithms};
+#[cfg(feature = "gbz")]
+use crate::graph::{GraphInt, GraphStr};
+
+#[cfg(feature = "gbz")]
+use gbz::support;
+
+use sha2::{Digest, Sha256};
+
+//-----------------------------------------------------------------------------
+
+#[cfg(feature = "gbz")]
+// Returns the canonical GFA representation of the graph.
+fn canonical_gfa<G: Graph>(graph: &G) -> Vec<u8> {
+    let mut result = Vec::new();
+    graph.for_each_node(|bytes| result.extend_from_slice(bytes));
+    result
+}
+
+fn hash(gfa: &[u8], node_ids: NodeIds) -> Result<String, String> {
+    hash_sorted_gfa(gfa, SortedGfaHasher::new(node_ids).unwrap(), 0, |_| Ok(()))
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn resumable_sha256() {
+    let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 251) as u8).collect();
+    for len in [0, 1, 55, 56, 63, 64, 65, 119, 120, 128, 1000] {
+        let truth = format!("{:x}", Sha256::digest(&data[..len]));
+        let mut hasher = ResumableSha256::new();
+        hasher.update(&data[..len]);
+        assert_eq!(hasher.finalize(), truth, "Wrong hash for {} bytes", len);
+
+        // Updates in pieces of different sizes.
+        for piece in [1, 3, 64, 100] {
+            let mut hasher = ResumableSha256::default();
+            for chunk in data[..len].chunks(piece) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize(), truth, "Wrong hash for {} bytes in pieces of {}", len, piece);
+        }
+    }
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn sorted_gfa_names() {
+    for filename in ["example.gfa", "translation.gfa"] {
+        let gfa = std::fs::read(support::get_test_data(filename)).unwrap();
+        if let Ok(graph) = algorithms::parse_gfa_bytes::<GraphInt>(&gfa) {
+            let canonical = canonical_gfa(&graph);
+            assert_eq!(hash(&canonical, NodeIds::Integer), Ok(crate::stable_name(&graph)), "Wrong integer name for {}", filename);
+        }
+        let graph: GraphStr = algorithms::parse_gfa_bytes(&gfa).unwrap();
+        let canonical = canonical_gfa(&graph);
+        assert_eq!(hash(&canonical, NodeIds::String), Ok(crate::stable_name(&graph)), "Wrong string name for {}", filename);
+
+        // Headers, paths, and a missing final newline do not change the name.
+        let mut extended = b"H\tVN:Z:1.1\n".to_vec();
+        extended.extend_from_slice(&canonical);
+        extended.extend_from_slice(b"P\tx\t1+\t*");
+        assert_eq!(hash(&extended, NodeIds::String), Ok(crate::stable_name(&graph)), "Ignored lines changed the name for {}", filename);
+    }
+    assert!(SortedGfaHasher::new(NodeIds::Auto).is_err(), "Accepted automatic node identifiers");
+}
+
+#[test]
+fn sorted_gfa_errors() {
+    let cases: &[(&[u8], &str)] = &[
+        (b"S\t2\tA\nS\t1\tC\n", "segments out of order"),
+        (b"S\t1\tA\nS\t1\tA\n", "duplicate segment"),
+        (b"S\t01\tA\n", "non-canonical identifier"),
+        (b"S\t1\tA\tLN:i:1\n", "optional field"),
+        (b"S\t1\tA\r\n", "CRLF line ending"),
+        (b"L\t1\t+\t2\t+\n", "link before segments"),
+        (b"S\t1\tA\nS\t2\tC\nL\t1\t+\t2\t+\n", "link after another segment"),
+        (b"S\t1\tA\nL\t1\t+\t2\t+\t*\nS\t2\tC\n", "link with an overlap"),
+        (b"S\t2\tA\nL\t2\t+\t1\t+\nS\t3\tC\n", "non-canonical link"),
+        (b"S\t1\tA\nL\t1\t-\t1\t-\n", "non-canonical self-loop"),
+        (b"S\t1\tA\nL\t1\t+\t3\t+\nL\t1\t+\t2\t+\nS\t2\tC\nS\t3\tG\n", "unsorted links"),
+        (b"S\t1\tA\nL\t1\t+\t2\t+\nS\t3\tG\n", "missing segment"),
+        (b"S\t1\tA\nL\t1\t+\t2\t+\n", "missing final segment"),
+    ];
+    for (gfa, description) in cases {
+        assert!(hash(gfa, NodeIds::Integer).is_err(), "Accepted sorted GFA with {}", description);
+    }
+    assert!(hash(b"S\t10\tA\nS\t9\tC\n", NodeIds::String).is_ok(), "Rejected string identifiers in string order");
+    assert!(hash(b"S\t10\tA\nS\t9\tC\n", NodeIds::Integer).is_err(), "Accepted integer identifiers in string order");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn checkpoints() {
+    let gfa = std::fs::read(support::get_test_data("example.gfa")).unwrap();
+    let graph: GraphInt = algorithms::parse_gfa_bytes(&gfa).unwrap();
+    let canonical = canonical_gfa(&graph);
+    let truth = crate::stable_name(&graph);
+
+    let mut saved: Vec<String> = Vec::new();
+    let name = hash_sorted_gfa(&canonical[..], SortedGfaHasher::new(NodeIds::Integer).unwrap(), 2, |checkpoint| {
+        saved.push(checkpoint.to_string());
+        Ok(())
+    });
+    assert_eq!(name, Ok(truth.clone()), "Wrong name with checkpoints");
+    let nodes = graph.statistics().nodes as usize;
+    assert_eq!(saved.len(), (nodes - 1) / 2, "Wrong number of checkpoints");
+
+    for (i, text) in saved.iter().enumerate() {
+        let checkpoint: Checkpoint = text.parse().unwrap();
+        assert_eq!(checkpoint.nodes(), 2 * (i + 1), "Wrong number of nodes in checkpoint {}", i);
+        assert_eq!(&checkpoint.to_string(), text, "Checkpoint {} did not round-trip", i);
+        let hasher = SortedGfaHasher::resume(&checkpoint).unwrap();
+        let resumed = hash_sorted_gfa(&canonical[..], hasher, 0, |_| Ok(()));
+        assert_eq!(resumed, Ok(truth.clone()), "Wrong name resumed from checkpoint {}", i);
+    }
+
+    // Checkpoints after resuming, starting with the same checkpoint again, are the same as without interruptions.
+    let mut resaved: Vec<String> = Vec::new();
+    let hasher = SortedGfaHasher::resume(&saved[0].parse().unwrap()).unwrap();
+    let _ = hash_sorted_gfa(&canonical[..], hasher, 2, |checkpoint| {
+        resaved.push(checkpoint.to_string());
+        Ok(())
+    });
+    assert_eq!(resaved, saved, "Wrong checkpoints after resuming");
+
+    // Errors.
+    let result = hash_sorted_gfa(&canonical[..], SortedGfaHasher::new(NodeIds::Integer).unwrap(), 1, |_| Err(String::from("disk full")));
+    assert_eq!(result, Err(String::from("disk full")), "Save error was not passed through");
+    let checkpoint: Checkpoint = saved[0].parse().unwrap();
+    let hasher = SortedGfaHasher::resume(&checkpoint).unwrap();
+    assert!(hash_sorted_gfa(&canonical[..10], hasher, 0, |_| Ok(())).is_err(), "Resumed from a checkpoint beyond the input");
+    let mut changed = canonical.clone();
+    changed[4] = if changed[4] == b'A' { b'C' } else { b'A' };
+    let mut hasher = SortedGfaHasher::resume(&checkpoint).unwrap();
+    assert_eq!(hasher.skip_prefix(&mut &changed[..]), Ok(false), "Accepted a changed prefix");
+    let hasher = SortedGfaHasher::resume(&checkpoint).unwrap();
+    assert!(hash_sorted_gfa(&changed[..], hasher, 0, |_| Ok(())).is_err(), "Resumed with a changed prefix");
+    let mut hasher = SortedGfaHasher::resume(&checkpoint).unwrap();
+    let mut reader = &canonical[..];
+    assert_eq!(hasher.skip_prefix(&mut reader), Ok(true), "Rejected an unchanged prefix");
+    assert_eq!(reader.len() as u64, canonical.len() as u64 - checkpoint.offset(), "Wrong number of skipped bytes");
+    assert_eq!(hash_sorted_gfa(reader, hasher, 0, |_| Ok(())), Ok(truth.clone()), "Wrong name after skipping the prefix");
+    let prefix_line = format!("prefix\t{}\n", checkpoint.prefix_digest());
+    assert!(saved[0].replace(&prefix_line, "").parse::<Checkpoint>().is_err(), "Accepted a checkpoint without an input digest");
+    assert!(saved[0].replace(&prefix_line, "prefix\tabc\n").parse::<Checkpoint>().is_err(), "Accepted an invalid input digest");
+    assert!("".parse::<Checkpoint>().is_err(), "Accepted an empty checkpoint");
+    assert!("pggname-checkpoint\t2\n".parse::<Checkpoint>().is_err(), "Accepted an unknown version");
+    assert!(saved[0].replace("buffer\t", "buffer\t00").parse::<Checkpoint>().is_err(), "Accepted an inconsistent buffer");
+    assert!(format!("{}unknown\t1\n", saved[0]).parse::<Checkpoint>().is_err(), "Accepted an unknown key");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,231 @@
+//! Generators for property-based tests and other test helpers.
+//!
+//! This module is only compiled in tests.
+//! [`TempFile`] provides temporary files that are removed even if the test fails.
+//! The generators create random graphs as [`TestGraph`] objects, which can be written as GFA in many equivalent ways:
+//! with the records in any order, with segments and links interleaved, with duplicate records, and with links in either orientation.
+//! All layouts of the same graph must have the same stable name.
+//!
+//! [`TestGraph::reference_gfa`] is an independent implementation of the canonical GFA representation, which the library must match.
+
+use crate::Orientation;
+use crate::algorithms::orientation_symbol;
+
+use proptest::collection::{btree_set, vec};
+use proptest::prelude::*;
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Maximum number of nodes in a generated graph.
+pub const MAX_NODES: usize = 24;
+
+/// Maximum number of edges in a generated graph.
+pub const MAX_EDGES: usize = 48;
+
+/// A graph generated for testing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestGraph {
+    /// Are the node names integers?
+    pub integer_names: bool,
+    /// Nodes as (name, sequence).
+    pub nodes: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Edges as (source node index, source orientation, destination node index, destination orientation).
+    ///
+    /// There may be duplicates, including the same edge in the other orientation.
+    pub edges: Vec<(usize, Orientation, usize, Orientation)>,
+}
+
+// Sort key for node names: the integer value for integer names, or the name itself for string names.
+type NameKey = (usize, Vec<u8>);
+
+/// A GFA record in a [`TestGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Record {
+    /// Segment line for the node with the given index.
+    Segment(usize),
+    /// Link line for the edge with the given index, possibly written in the other orientation.
+    Link(usize, bool),
+}
+
+impl TestGraph {
+    /// Returns the GFA line for the record, including the newline.
+    pub fn line(&self, record: Record) -> Vec<u8> {
+        let mut line = Vec::new();
+        match record {
+            Record::Segment(i) => {
+                let (name, sequence) = &self.nodes[i];
+                line.extend_from_slice(b"S\t");
+                line.extend_from_slice(name);
+                line.push(b'\t');
+                line.extend_from_slice(sequence);
+            }
+            Record::Link(i, flip) => {
+                let (mut from, mut from_o, mut to, mut to_o) = self.edges[i];
+                if flip {
+                    (from, from_o, to, to_o) = (to, to_o.flip(), from, from_o.flip());
+                }
+                line.extend_from_slice(b"L\t");
+                line.extend_from_slice(&self.nodes[from].0);
+                line.extend_from_slice(if from_o == Orientation::Forward { b"\t+\t" } else { b"\t-\t" });
+                line.extend_from_slice(&self.nodes[to].0);
+                line.extend_from_slice(if to_o == Orientation::Forward { b"\t+\t*" } else { b"\t-\t*" });
+            }
+        }
+        line.push(b'\n');
+        line
+    }
+
+    /// Returns the GFA representation with the given records in the given order.
+    pub fn gfa(&self, records: &[Record]) -> Vec<u8> {
+        records.iter().flat_map(|record| self.line(*record)).collect()
+    }
+
+    /// Returns the records in the natural order: segments first, then links.
+    pub fn records(&self) -> Vec<Record> {
+        let segments = (0..self.nodes.len()).map(Record::Segment);
+        let links = (0..self.edges.len()).map(|i| Record::Link(i, false));
+        segments.chain(links).collect()
+    }
+
+    // Returns the sort key for the node name.
+    fn key(&self, index: usize) -> NameKey {
+        let name = &self.nodes[index].0;
+        if self.integer_names {
+            (String::from_utf8_lossy(name).parse().unwrap(), Vec::new())
+        } else {
+            (0, name.clone())
+        }
+    }
+
+    /// Returns the canonical GFA representation, computed independently of the library.
+    pub fn reference_gfa(&self) -> Vec<u8> {
+        let mut edges: BTreeSet<(NameKey, Orientation, NameKey, Orientation)> = BTreeSet::new();
+        for &(from, from_o, to, to_o) in self.edges.iter() {
+            let (from, to) = (self.key(from), self.key(to));
+            let canonical = from < to || (from == to && (from_o == Orientation::Forward || to_o == Orientation::Forward));
+            if canonical {
+                edges.insert((from, from_o, to, to_o));
+            } else {
+                edges.insert((to, to_o.flip(), from, from_o.flip()));
+            }
+        }
+
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by_key(|&i| self.key(i));
+        let mut result = Vec::new();
+        for i in order {
+            result.extend(self.line(Record::Segment(i)));
+            let key = self.key(i);
+            for (from, from_o, to, to_o) in edges.iter().filter(|edge| edge.0 == key) {
+                let name = |key: &NameKey| if self.integer_names { key.0.to_string().into_bytes() } else { key.1.clone() };
+                let line = format!(
+                    "L\t{}\t{}\t{}\t{}\n",
+                    String::from_utf8_lossy(&name(from)), orientation_symbol(*from_o),
+                    String::from_utf8_lossy(&name(to)), orientation_symbol(*to_o)
+                );
+                result.extend_from_slice(line.as_bytes());
+            }
+        }
+        result
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+fn arb_orientation() -> impl Strategy<Value = Orientation> {
+    prop_oneof![Just(Orientation::Forward), Just(Orientation::Reverse)]
+}
+
+fn arb_sequence() -> impl Strategy<Value = Vec<u8>> {
+    vec(prop_oneof![4 => Just(b'A'), 4 => Just(b'C'), 4 => Just(b'G'), 4 => Just(b'T'), 1 => Just(b'N')], 1..20)
+}
+
+fn arb_names(integer_names: bool) -> BoxedStrategy<Vec<Vec<u8>>> {
+    if integer_names {
+        btree_set(1..1000usize, 1..=MAX_NODES)
+            .prop_map(|ids| ids.into_iter().map(|id| id.to_string().into_bytes()).collect())
+            .prop_shuffle()
+            .boxed()
+    } else {
+        btree_set("[A-Za-z0-9_.]{1,6}", 1..=MAX_NODES)
+            .prop_map(|names| names.into_iter().map(String::into_bytes).collect())
+            .prop_shuffle()
+            .boxed()
+    }
+}
+
+/// Returns a strategy for generating graphs with integer or string names.
+pub fn arb_graph(integer_names: bool) -> impl Strategy<Value = TestGraph> {
+    arb_names(integer_names).prop_flat_map(move |names| {
+        let n = names.len();
+        let nodes = vec(arb_sequence(), n).prop_map(move |sequences| names.clone().into_iter().zip(sequences).collect::<Vec<_>>());
+        let edges = vec((0..n, arb_orientation(), 0..n, arb_orientation()), 0..=MAX_EDGES);
+        (nodes, edges).prop_map(move |(nodes, edges)| TestGraph { integer_names, nodes, edges })
+    })
+}
+
+/// Returns a strategy for generating a graph together with a random layout of its GFA records.
+///
+/// The layout contains every record in a random order.
+/// Some records are duplicated, and links may be written in the other orientation.
+pub fn arb_layout(integer_names: bool) -> impl Strategy<Value = (TestGraph, Vec<Record>)> {
+    arb_graph(integer_names).prop_flat_map(|graph| {
+        let records = graph.records();
+        let n = records.len();
+        let flips = vec(any::<bool>(), n);
+        let duplicates = vec(0..n, 0..=n / 2);
+        (Just(graph), Just(records), flips, duplicates).prop_flat_map(|(graph, records, flips, duplicates)| {
+            let mut layout: Vec<Record> = records.iter().zip(flips.iter()).map(|(record, &flip)| match record {
+                Record::Link(i, _) => Record::Link(*i, flip),
+                segment => *segment,
+            }).collect();
+            let copies: Vec<Record> = duplicates.iter().map(|&i| layout[i]).collect();
+            layout.extend(copies);
+            (Just(graph), Just(layout).prop_shuffle())
+        })
+    })
+}
+
+//-----------------------------------------------------------------------------
+
+/// A temporary file that is removed when dropped, even if an assertion fails.
+///
+/// Each instance has a unique path, so tests running in parallel do not share files.
+/// The file itself is not created.
+pub struct TempFile(PathBuf);
+
+impl TempFile {
+    /// Returns a new temporary file with the given suffix, such as `.gfa.gz`.
+    pub fn new(suffix: &str) -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        TempFile(std::env::temp_dir().join(format!("pggname-test-{}-{}{}", std::process::id(), id, suffix)))
+    }
+
+    /// Returns the path to the file.
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempFile {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,119 @@
+use super::*;
+
+use crate::Graph;
+use crate::algorithms::{self, GfaStream, ParseOptions};
+use crate::graph::{GraphInt, GraphStr};
+
+use sha2::{Digest, Sha256};
+
+//-----------------------------------------------------------------------------
+
+const CASES: u32 = 64;
+
+// Returns the canonical GFA representation produced by the library.
+fn canonical_gfa<G: Graph>(graph: &G) -> Vec<u8> {
+    let mut result = Vec::new();
+    graph.for_each_node(|record| result.extend_from_slice(record));
+    result
+}
+
+fn check_reference<G: Graph>(graph: &TestGraph) -> Result<(), TestCaseError> {
+    let parsed: G = algorithms::parse_gfa_bytes(&graph.gfa(&graph.records())).map_err(TestCaseError::fail)?;
+    let reference = graph.reference_gfa();
+    let canonical = canonical_gfa(&parsed);
+    prop_assert_eq!(String::from_utf8_lossy(&canonical), String::from_utf8_lossy(&reference));
+    prop_assert_eq!(algorithms::stable_name(&parsed), format!("{:x}", Sha256::digest(&reference)));
+    Ok(())
+}
+
+fn check_layout<G: Graph>(graph: &TestGraph, layout: &[Record], chunk_size: usize) -> Result<(), TestCaseError> {
+    let expected: G = algorithms::parse_gfa_bytes(&graph.gfa(&graph.records())).map_err(TestCaseError::fail)?;
+    let expected = algorithms::stable_name(&expected);
+    let gfa = graph.gfa(layout);
+
+    let from_bytes: G = algorithms::parse_gfa_bytes(&gfa).map_err(TestCaseError::fail)?;
+    prop_assert_eq!(algorithms::stable_name(&from_bytes), expected.clone(), "Wrong name from bytes");
+
+    let from_reader: G = algorithms::parse_gfa(&gfa[..]).map_err(TestCaseError::fail)?;
+    prop_assert_eq!(algorithms::stable_name(&from_reader), expected.clone(), "Wrong name from a reader");
+
+    let mut stream = GfaStream::<G>::new();
+    for chunk in gfa.chunks(chunk_size) {
+        stream.push(chunk).map_err(TestCaseError::fail)?;
+    }
+    let from_stream = stream.finish().map_err(TestCaseError::fail)?;
+    prop_assert_eq!(algorithms::stable_name(&from_stream), expected.clone(), "Wrong name from a stream with chunk size {}", chunk_size);
+
+    let options = ParseOptions { pack_sequences: true, ..Default::default() };
+    let (packed, _) = algorithms::parse_gfa_bytes_with_options::<G>(&gfa, &options).map_err(TestCaseError::fail)?;
+    prop_assert_eq!(algorithms::stable_name(&packed), expected, "Wrong name with packed sequences");
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn reference_int(graph in arb_graph(true)) {
+        check_reference::<GraphInt>(&graph)?;
+    }
+
+    #[test]
+    fn reference_str(graph in arb_graph(false)) {
+        check_reference::<GraphStr>(&graph)?;
+    }
+
+    #[test]
+    fn layout_int((graph, layout) in arb_layout(true), chunk_size in 1..64usize) {
+        check_layout::<GraphInt>(&graph, &layout, chunk_size)?;
+    }
+
+    #[test]
+    fn layout_str((graph, layout) in arb_layout(false), chunk_size in 1..64usize) {
+        check_layout::<GraphStr>(&graph, &layout, chunk_size)?;
+    }
+
+    #[test]
+    fn integer_names_as_strings(graph in arb_graph(true)) {
+        // String names are sorted differently, but the nodes and edges must be the same.
+        let gfa = graph.gfa(&graph.records());
+        let int_graph: GraphInt = algorithms::parse_gfa_bytes(&gfa).map_err(TestCaseError::fail)?;
+        let str_graph: GraphStr = algorithms::parse_gfa_bytes(&gfa).map_err(TestCaseError::fail)?;
+        prop_assert_eq!(int_graph.statistics(), str_graph.statistics());
+    }
+
+    #[test]
+    fn round_trip_int(graph in arb_graph(true)) {
+        let parsed: GraphInt = algorithms::parse_gfa_bytes(&graph.gfa(&graph.records())).map_err(TestCaseError::fail)?;
+        prop_assert_eq!(algorithms::check_round_trip::<_, GraphInt>(&parsed), Ok(algorithms::stable_name(&parsed)));
+    }
+
+    #[test]
+    fn round_trip_str(graph in arb_graph(false)) {
+        let parsed: GraphStr = algorithms::parse_gfa_bytes(&graph.gfa(&graph.records())).map_err(TestCaseError::fail)?;
+        prop_assert_eq!(algorithms::check_round_trip::<_, GraphStr>(&parsed), Ok(algorithms::stable_name(&parsed)));
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn temp_files() {
+    let first = TempFile::new(".gfa");
+    let second = TempFile::new(".gfa");
+    assert_ne!(first.path(), second.path(), "Temporary files share a path");
+    assert!(first.path().to_string_lossy().ends_with(".gfa"), "Wrong suffix in {}", first.path().display());
+
+    let path = first.path().to_path_buf();
+    std::fs::write(&first, b"S\t1\tA\n").unwrap();
+    let result = std::panic::catch_unwind(move || {
+        let _file = first;
+        panic!("Failing assertion");
+    });
+    assert!(result.is_err(), "The closure did not panic");
+    assert!(!path.exists(), "The temporary file was not removed after a panic");
+}
+
+//-----------------------------------------------------------------------------
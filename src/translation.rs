@@ -0,0 +1,1552 @@
+//! Coordinate translations between graphs.
+//!
+//! Graph A can be translated to graph B, if A is isomorphic to a subgraph C of B after breaking the nodes of both graphs into 1 bp pieces.
+//! See [`crate::name`] for the definition.
+//! A translation is described as a [`TranslationMap`], which consists of [`TranslationInterval`] objects mapping intervals of nodes in A to intervals of nodes in B.
+//! Translation maps can be produced by [`chop`], [`renumber`], [`normalize`], and [`extract`], which transform a graph, or by inferring the translation between existing graphs.
+//! Translations are defined over the bases of the nodes, so graphs with nodes without a sequence (`*` in GFA) are rejected.
+//!
+//! # Text format
+//!
+//! A translation map is stored as a tab-separated text file.
+//! Lines starting with `#` are comments, and empty lines are ignored.
+//! Each other line describes a [`TranslationInterval`] with six fields:
+//!
+//! 1. Source node name.
+//! 2. Start of the interval in the source node (0-based, inclusive).
+//! 3. End of the interval in the source node (0-based, exclusive).
+//! 4. Target node name.
+//! 5. Starting offset of the interval in the target node (0-based).
+//! 6. Relative orientation: `+` or `-`.
+//!
+//! Offsets are relative to the forward orientation of the nodes.
+//! The intervals of a source node must not overlap.
+//!
+//! For example, the following map (with tabs shown as spaces) chops node `s11` into nodes `1` and `2` and reverses node `s12`:
+//!
+//! ```text
+//! # pggname translation map
+//! s11  0  2  1  0  +
+//! s11  2  3  2  0  +
+//! s12  0  1  3  0  -
+//! ```
+//!
+//! # Transformations
+//!
+//! A [`Transformation`] applies one of the transforms and returns a [`RenameRecord`] with the old and new names, their relationship, and the translation map.
+//! Pipelines composing several transformations can collect the records as an audit trail of graph identities.
+//!
+//! # Inference
+//!
+//! The inference works with any [`Graph`] implementation, as it only uses the canonical GFA representation of the graphs.
+//! It decomposes graph A into maximal unary paths (unitigs) and places each unitig as a walk in graph B.
+//! Unitigs with a unique placement act as anchors, and placements are extended from them to adjacent unitigs.
+//! The result is then verified by checking that all nodes in B are used at most once and that all edges of A exist in B.
+
+use crate::{Graph, GraphName, algorithms};
+use crate::name::RelationshipKind;
+use crate::graph::{self, GraphInt, Orientation};
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::io::{BufRead, Write};
+use std::ops::Range;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// A mapping from an interval of a node in graph A to an interval of a node in graph B.
+///
+/// Offsets are relative to the forward orientation of the nodes.
+/// If the orientation is [`Orientation::Reverse`], the interval maps to the reverse complement of the target interval.
+/// Then the first position of the source interval maps to the last position of the target interval.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranslationInterval {
+    /// Name of the source node.
+    pub from: Vec<u8>,
+    /// Interval of the source node.
+    pub from_range: Range<usize>,
+    /// Name of the target node.
+    pub to: Vec<u8>,
+    /// Starting offset of the interval in the target node.
+    pub to_offset: usize,
+    /// Relative orientation of the source and the target intervals.
+    pub orientation: Orientation,
+}
+
+impl TranslationInterval {
+    /// Returns the length of the interval.
+    pub fn len(&self) -> usize {
+        self.from_range.len()
+    }
+
+    /// Returns `true` if the interval is empty.
+    pub fn is_empty(&self) -> bool {
+        self.from_range.is_empty()
+    }
+
+    /// Returns the interval of the target node.
+    pub fn to_range(&self) -> Range<usize> {
+        self.to_offset..self.to_offset + self.len()
+    }
+}
+
+/// An interval of a node visited in a specific orientation.
+///
+/// The range is relative to the forward orientation of the node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeInterval {
+    /// Name of the node.
+    pub node: Vec<u8>,
+    /// Interval of the node.
+    pub range: Range<usize>,
+    /// Orientation of the visit.
+    pub orientation: Orientation,
+}
+
+/// A path used as a coordinate system in [`TranslationMap::write_chain`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainPath<'a> {
+    /// Name of the path, used as the sequence name in the chain.
+    pub name: &'a str,
+    /// Oriented nodes visited by the path.
+    pub nodes: &'a [(Vec<u8>, Orientation)],
+}
+
+/// A BED record.
+///
+/// Only the first three fields are interpreted.
+/// The remaining fields (name, score, strand, and so on) are stored as strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BedRecord {
+    /// Sequence name.
+    pub chrom: String,
+    /// Start of the interval (0-based, inclusive).
+    pub start: usize,
+    /// End of the interval (0-based, exclusive).
+    pub end: usize,
+    /// Remaining fields.
+    pub fields: Vec<String>,
+}
+
+impl BedRecord {
+    /// Parses a tab-separated BED line.
+    ///
+    /// Returns an error if there are fewer than three fields or if the interval is invalid.
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut fields = line.trim_end_matches(['\r', '\n']).split('\t');
+        let chrom = fields.next().filter(|chrom| !chrom.is_empty()).ok_or(String::from("Missing sequence name"))?;
+        let start = fields.next().ok_or(String::from("Missing interval start"))?;
+        let start = start.parse::<usize>().map_err(|_| format!("Invalid interval start: {}", start))?;
+        let end = fields.next().ok_or(String::from("Missing interval end"))?;
+        let end = end.parse::<usize>().map_err(|_| format!("Invalid interval end: {}", end))?;
+        if end < start {
+            return Err(format!("Invalid interval: {}..{}", start, end));
+        }
+        Ok(BedRecord {
+            chrom: String::from(chrom),
+            start, end,
+            fields: fields.map(String::from).collect(),
+        })
+    }
+}
+
+impl std::fmt::Display for BedRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\t{}\t{}", self.chrom, self.start, self.end)?;
+        for field in self.fields.iter() {
+            write!(f, "\t{}", field)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of translation inference between graphs A and B.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranslationVerdict {
+    /// Graphs A and B are translations of each other.
+    Equivalent,
+    /// Graph A can be translated to a proper subgraph of graph B.
+    Subgraph,
+    /// No translation was found, for the given reason.
+    NotFound(String),
+}
+
+/// The result of translation inference from graph A to graph B.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranslationInference {
+    /// The verdict.
+    pub verdict: TranslationVerdict,
+    /// Mapping from the nodes of A to the nodes of B.
+    ///
+    /// The mapping is empty if no translation was found.
+    pub mapping: TranslationMap,
+}
+
+impl TranslationInference {
+    /// Returns `true` if graph A can be translated to graph B.
+    pub fn is_translation(&self) -> bool {
+        !matches!(self.verdict, TranslationVerdict::NotFound(_))
+    }
+
+    fn not_found(reason: String) -> Self {
+        TranslationInference {
+            verdict: TranslationVerdict::NotFound(reason),
+            mapping: TranslationMap::default(),
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// A mapping from the nodes of graph A to the nodes of graph B.
+///
+/// The map consists of non-overlapping [`TranslationInterval`] objects.
+/// The intervals of each source node are stored contiguously in increasing order of offsets.
+/// See the [module documentation](self) for the text format.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::Orientation;
+/// use pggname::translation::{TranslationInterval, TranslationMap};
+///
+/// let intervals = vec![
+///     TranslationInterval { from: b"a".to_vec(), from_range: 0..2, to: b"1".to_vec(), to_offset: 0, orientation: Orientation::Forward },
+///     TranslationInterval { from: b"a".to_vec(), from_range: 2..5, to: b"2".to_vec(), to_offset: 0, orientation: Orientation::Reverse },
+/// ];
+/// let map = TranslationMap::new(intervals).unwrap();
+/// assert_eq!(map.translate(b"a", 1), Some((b"1".as_slice(), 1, Orientation::Forward)));
+/// assert_eq!(map.translate(b"a", 2), Some((b"2".as_slice(), 2, Orientation::Reverse)));
+/// assert_eq!(map.translate(b"a", 5), None);
+///
+/// let mut buffer: Vec<u8> = Vec::new();
+/// map.write(&mut buffer).unwrap();
+/// let copy = TranslationMap::read(buffer.as_slice()).unwrap();
+/// assert_eq!(copy, map);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TranslationMap {
+    intervals: Vec<TranslationInterval>,
+    // Source node name to the range of its intervals.
+    index: HashMap<Vec<u8>, Range<usize>>,
+}
+
+impl TranslationMap {
+    /// Header line written at the start of the text format.
+    pub const HEADER: &'static str = "# pggname translation map";
+
+    /// Creates a translation map from the given intervals.
+    ///
+    /// The source nodes are listed in order of first appearance, and the intervals of each node are sorted by offset.
+    /// Returns an error if an interval is empty or if the intervals of a source node overlap.
+    pub fn new(intervals: Vec<TranslationInterval>) -> Result<Self, String> {
+        let mut order: Vec<Vec<u8>> = Vec::new();
+        let mut by_node: HashMap<Vec<u8>, Vec<TranslationInterval>> = HashMap::new();
+        for interval in intervals {
+            if interval.is_empty() {
+                return Err(format!("Empty interval for node {}", String::from_utf8_lossy(&interval.from)));
+            }
+            by_node.entry(interval.from.clone()).or_insert_with(|| {
+                order.push(interval.from.clone());
+                Vec::new()
+            }).push(interval);
+        }
+
+        let mut sorted = Vec::new();
+        for node in order {
+            let mut node_intervals = by_node.remove(&node).unwrap();
+            node_intervals.sort_by_key(|interval| interval.from_range.start);
+            for pair in node_intervals.windows(2) {
+                if pair[0].from_range.end > pair[1].from_range.start {
+                    return Err(format!("Overlapping intervals for node {}", String::from_utf8_lossy(&node)));
+                }
+            }
+            sorted.extend(node_intervals);
+        }
+
+        Ok(Self::from_sorted(sorted))
+    }
+
+    // Creates a translation map from intervals that are already grouped and sorted.
+    pub(crate) fn from_sorted(intervals: Vec<TranslationInterval>) -> Self {
+        let mut index: HashMap<Vec<u8>, Range<usize>> = HashMap::new();
+        let mut start = 0;
+        for i in 1..=intervals.len() {
+            if i == intervals.len() || intervals[i].from != intervals[start].from {
+                index.insert(intervals[start].from.clone(), start..i);
+                start = i;
+            }
+        }
+        TranslationMap { intervals, index }
+    }
+
+    /// Returns the number of intervals in the map.
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Returns `true` if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Returns all intervals in the map.
+    pub fn intervals(&self) -> &[TranslationInterval] {
+        &self.intervals
+    }
+
+    /// Returns the intervals for the given source node, sorted by offset.
+    pub fn node_intervals(&self, node: &[u8]) -> &[TranslationInterval] {
+        match self.index.get(node) {
+            Some(range) => &self.intervals[range.clone()],
+            None => &[],
+        }
+    }
+
+    /// Translates a position in a source node.
+    ///
+    /// Returns the target node, the offset in the target node, and the relative orientation.
+    /// Both offsets are relative to the forward orientation of the nodes.
+    /// Returns [`None`] if the position is not covered by the map.
+    pub fn translate(&self, node: &[u8], offset: usize) -> Option<(&[u8], usize, Orientation)> {
+        let intervals = self.node_intervals(node);
+        let i = intervals.partition_point(|interval| interval.from_range.end <= offset);
+        let interval = intervals.get(i)?;
+        if !interval.from_range.contains(&offset) {
+            return None;
+        }
+        let relative = offset - interval.from_range.start;
+        let target_offset = match interval.orientation {
+            Orientation::Forward => interval.to_offset + relative,
+            Orientation::Reverse => interval.to_offset + interval.len() - 1 - relative,
+        };
+        Some((&interval.to, target_offset, interval.orientation))
+    }
+
+    /// Translates an interval of a source node.
+    ///
+    /// The interval is split at the boundaries of the translation intervals, which usually correspond to node boundaries in either graph.
+    /// Returns the translations of the parts of the interval covered by the map, sorted by source offset.
+    /// Parts of the interval not covered by the map are omitted.
+    pub fn translate_interval(&self, node: &[u8], range: Range<usize>) -> Vec<TranslationInterval> {
+        let intervals = self.node_intervals(node);
+        let first = intervals.partition_point(|interval| interval.from_range.end <= range.start);
+        let mut result = Vec::new();
+        for interval in intervals[first..].iter().take_while(|interval| interval.from_range.start < range.end) {
+            let start = range.start.max(interval.from_range.start);
+            let end = range.end.min(interval.from_range.end);
+            if start >= end {
+                continue;
+            }
+            let to_offset = match interval.orientation {
+                Orientation::Forward => interval.to_offset + (start - interval.from_range.start),
+                Orientation::Reverse => interval.to_offset + (interval.from_range.end - end),
+            };
+            result.push(TranslationInterval {
+                from: interval.from.clone(),
+                from_range: start..end,
+                to: interval.to.clone(),
+                to_offset,
+                orientation: interval.orientation,
+            });
+        }
+        result
+    }
+
+    /// Lifts an interval of a path in graph A to graph B.
+    ///
+    /// The path is given as a sequence of oriented nodes, and the interval is in path coordinates.
+    /// Returns the intervals of the target nodes in path order, split at node boundaries.
+    /// The orientation of each interval is the orientation in which the lifted path visits the target node.
+    /// Node lengths for graph A are required for handling reverse orientations; see [`node_lengths`].
+    ///
+    /// Returns an error if the interval extends past the end of the path, if the length of a node is missing, or if a part of the interval is not covered by the map.
+    pub fn lift_path_interval(
+        &self, path: &[(Vec<u8>, Orientation)], lengths: &HashMap<Vec<u8>, usize>, range: Range<usize>
+    ) -> Result<Vec<NodeInterval>, String> {
+        let mut result = Vec::new();
+        let mut path_offset = 0;
+        for (node, o) in path.iter() {
+            if path_offset >= range.end {
+                break;
+            }
+            let len = *lengths.get(node).ok_or(format!("Missing length for node {}", String::from_utf8_lossy(node)))?;
+            let (start, end) = (range.start.max(path_offset), range.end.min(path_offset + len));
+            path_offset += len;
+            if start >= end {
+                continue;
+            }
+
+            // Interval of the node in forward orientation.
+            let (start, end) = (start - (path_offset - len), end - (path_offset - len));
+            let local = forward_range(*o, start..end, len);
+            let mut pieces = self.translate_interval(node, local.clone());
+            if pieces.iter().map(|piece| piece.len()).sum::<usize>() != local.len() {
+                return Err(format!("Interval {}..{} of node {} is not covered by the translation", local.start, local.end, String::from_utf8_lossy(node)));
+            }
+            if *o == Orientation::Reverse {
+                pieces.reverse();
+            }
+            for piece in pieces {
+                let orientation = match piece.orientation {
+                    Orientation::Forward => *o,
+                    Orientation::Reverse => o.flip(),
+                };
+                result.push(NodeInterval { range: piece.to_range(), node: piece.to, orientation });
+            }
+        }
+        if path_offset < range.end {
+            return Err(format!("Interval {}..{} extends past the end of the path ({} bp)", range.start, range.end, path_offset));
+        }
+        Ok(result)
+    }
+
+    /// Lifts a BED record anchored on a path in graph A to node-anchored BED records in graph B.
+    ///
+    /// The record is interpreted as an interval of the given path; see [`Self::lift_path_interval`].
+    /// The result contains one record for each target node interval, with the node name as the sequence name.
+    /// If the record has a strand field, it is flipped for target nodes visited in reverse orientation.
+    /// Other fields are copied from the original record.
+    pub fn lift_bed_record(
+        &self, record: &BedRecord, path: &[(Vec<u8>, Orientation)], lengths: &HashMap<Vec<u8>, usize>
+    ) -> Result<Vec<BedRecord>, String> {
+        let intervals = self.lift_path_interval(path, lengths, record.start..record.end)?;
+        let mut result = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            let mut fields = record.fields.clone();
+            if interval.orientation == Orientation::Reverse && fields.len() >= 3 {
+                fields[2] = match fields[2].as_str() {
+                    "+" => String::from("-"),
+                    "-" => String::from("+"),
+                    other => String::from(other),
+                };
+            }
+            result.push(BedRecord {
+                chrom: String::from_utf8_lossy(&interval.node).into_owned(),
+                start: interval.range.start,
+                end: interval.range.end,
+                fields,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Writes the map in the text format.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), String> {
+        writeln!(writer, "{}", Self::HEADER).map_err(|e| e.to_string())?;
+        for interval in self.intervals.iter() {
+            writer.write_all(&interval.from).map_err(|e| e.to_string())?;
+            write!(writer, "\t{}\t{}\t", interval.from_range.start, interval.from_range.end).map_err(|e| e.to_string())?;
+            writer.write_all(&interval.to).map_err(|e| e.to_string())?;
+            let o = algorithms::orientation_symbol(interval.orientation);
+            writeln!(writer, "\t{}\t{}", interval.to_offset, o).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the map as a UCSC chain file over path coordinates.
+    ///
+    /// The source path is in graph A and the target path is in graph B, and the chain lifts coordinates on the source path to the target path.
+    /// The source path is the reference and the target path is the query, and both are on the forward strand.
+    /// Each node visit on the source path is translated with the map, and the translated intervals are placed on the first following visit to the same target node in the same orientation.
+    /// Parts of the source path that do not translate to the target path become gaps in the chain.
+    /// Node lengths for both graphs are required; see [`node_lengths`].
+    ///
+    /// Returns an error if the length of a node is missing or if no part of the source path translates to the target path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::{algorithms, translation, Orientation};
+    /// use pggname::graph::GraphStr;
+    /// use pggname::translation::ChainPath;
+    ///
+    /// let source: GraphStr = algorithms::parse_gfa_bytes(b"S\ta\tGA\nS\tb\tT\nL\ta\t+\tb\t+\n").unwrap();
+    /// let target: GraphStr = algorithms::parse_gfa_bytes(b"S\tx\tGAT\n").unwrap();
+    /// let map = algorithms::infer_translation(&source, &target).unwrap().mapping;
+    /// let source_path = vec![(b"a".to_vec(), Orientation::Forward), (b"b".to_vec(), Orientation::Forward)];
+    /// let target_path = vec![(b"x".to_vec(), Orientation::Forward)];
+    /// let mut chain: Vec<u8> = Vec::new();
+    /// map.write_chain(
+    ///     &mut chain,
+    ///     ChainPath { name: "A", nodes: &source_path }, ChainPath { name: "B", nodes: &target_path },
+    ///     &translation::node_lengths(&source).unwrap(), &translation::node_lengths(&target).unwrap()
+    /// ).unwrap();
+    /// assert_eq!(chain, b"chain 3 A 3 + 0 3 B 3 + 0 3 1\n3\n\n");
+    /// ```
+    pub fn write_chain<W: Write>(
+        &self, mut writer: W, source: ChainPath, target: ChainPath,
+        source_lengths: &HashMap<Vec<u8>, usize>, target_lengths: &HashMap<Vec<u8>, usize>
+    ) -> Result<(), String> {
+        let (source_offsets, source_len) = path_offsets(source.nodes, source_lengths)?;
+        let (target_offsets, target_len) = path_offsets(target.nodes, target_lengths)?;
+        let mut visits: HashMap<(&[u8], Orientation), Vec<usize>> = HashMap::new();
+        for (i, (node, o)) in target.nodes.iter().enumerate() {
+            visits.entry((node.as_slice(), *o)).or_default().push(i);
+        }
+
+        // Ungapped blocks as (source start, target start, length).
+        let mut blocks: Vec<(usize, usize, usize)> = Vec::new();
+        let (mut next_visit, mut target_end) = (0, 0);
+        for ((node, o), &offset) in source.nodes.iter().zip(source_offsets.iter()) {
+            let len = source_lengths[node];
+            let mut pieces = self.translate_interval(node, 0..len);
+            if *o == Orientation::Reverse {
+                pieces.reverse();
+            }
+            for piece in pieces {
+                let orientation = if piece.orientation == Orientation::Forward { *o } else { o.flip() };
+                let source_start = offset + forward_range(*o, piece.from_range.clone(), len).start;
+                let Some(candidates) = visits.get(&(piece.to.as_slice(), orientation)) else {
+                    continue;
+                };
+                let first = candidates.partition_point(|&visit| visit < next_visit);
+                let found = candidates[first..].iter().find_map(|&visit| {
+                    let node_len = target_lengths[&piece.to];
+                    let start = target_offsets[visit] + forward_range(orientation, piece.to_range(), node_len).start;
+                    (start >= target_end).then_some((visit, start))
+                });
+                let Some((visit, target_start)) = found else {
+                    continue;
+                };
+                (next_visit, target_end) = (visit, target_start + piece.len());
+                match blocks.last_mut() {
+                    Some(last) if last.0 + last.2 == source_start && last.1 + last.2 == target_start => last.2 += piece.len(),
+                    _ => blocks.push((source_start, target_start, piece.len())),
+                }
+            }
+        }
+
+        let (Some(first), Some(last)) = (blocks.first(), blocks.last()) else {
+            return Err(format!("No part of path {} translates to path {}", source.name, target.name));
+        };
+        let score: usize = blocks.iter().map(|block| block.2).sum();
+        writeln!(
+            writer, "chain {} {} {} + {} {} {} {} + {} {} 1",
+            score, source.name, source_len, first.0, last.0 + last.2, target.name, target_len, first.1, last.1 + last.2
+        ).map_err(|e| e.to_string())?;
+        for pair in blocks.windows(2) {
+            let (block, next) = (pair[0], pair[1]);
+            let (source_gap, target_gap) = (next.0 - (block.0 + block.2), next.1 - (block.1 + block.2));
+            writeln!(writer, "{}\t{}\t{}", block.2, source_gap, target_gap).map_err(|e| e.to_string())?;
+        }
+        writeln!(writer, "{}\n", last.2).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Writes the map as a UCSC chain file over node coordinates.
+    ///
+    /// Each node is treated as a separate sequence, with the node name as the sequence name and coordinates relative to the start of the node.
+    /// Graph A is the reference and graph B is the query, and each interval becomes a chain with a single ungapped block.
+    /// Standard liftover tools can then translate node-anchored annotations from A to B.
+    /// See [`Self::write_chain`] for a chain over path coordinates.
+    /// Node lengths are required for the chain headers; see [`node_lengths`].
+    ///
+    /// Returns an error if the length of a node is missing or if an interval extends past the end of a node.
+    pub fn write_node_chain<W: Write>(
+        &self, mut writer: W,
+        source_lengths: &HashMap<Vec<u8>, usize>, target_lengths: &HashMap<Vec<u8>, usize>
+    ) -> Result<(), String> {
+        for (i, interval) in self.intervals.iter().enumerate() {
+            let source_len = *source_lengths.get(&interval.from)
+                .ok_or(format!("Missing length for source node {}", String::from_utf8_lossy(&interval.from)))?;
+            let target_len = *target_lengths.get(&interval.to)
+                .ok_or(format!("Missing length for target node {}", String::from_utf8_lossy(&interval.to)))?;
+            let to_range = interval.to_range();
+            if interval.from_range.end > source_len || to_range.end > target_len {
+                return Err(format!(
+                    "Interval from node {} to node {} extends past the end of a node",
+                    String::from_utf8_lossy(&interval.from), String::from_utf8_lossy(&interval.to)
+                ));
+            }
+            // Query coordinates on the reverse strand are relative to the reverse complement.
+            let (strand, query_range) = match interval.orientation {
+                Orientation::Forward => ('+', to_range),
+                Orientation::Reverse => ('-', target_len - to_range.end..target_len - to_range.start),
+            };
+
+            write!(writer, "chain {} ", interval.len()).map_err(|e| e.to_string())?;
+            writer.write_all(&interval.from).map_err(|e| e.to_string())?;
+            write!(writer, " {} + {} {} ", source_len, interval.from_range.start, interval.from_range.end).map_err(|e| e.to_string())?;
+            writer.write_all(&interval.to).map_err(|e| e.to_string())?;
+            writeln!(writer, " {} {} {} {} {}", target_len, strand, query_range.start, query_range.end, i + 1).map_err(|e| e.to_string())?;
+            writeln!(writer, "{}\n", interval.len()).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Reads a map in the text format.
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, String> {
+        let mut intervals = Vec::new();
+        for (i, line) in reader.split(b'\n').enumerate() {
+            let line = line.map_err(|e| e.to_string())?;
+            let line = line.strip_suffix(b"\r").unwrap_or(&line);
+            if line.is_empty() || line[0] == b'#' {
+                continue;
+            }
+            let interval = parse_interval(line).map_err(|e| format!("Error parsing translation map line {}: {}", i + 1, e))?;
+            intervals.push(interval);
+        }
+        Self::new(intervals)
+    }
+}
+
+// Returns the starting offset of each node visit on the path and the length of the path.
+fn path_offsets(path: &[(Vec<u8>, Orientation)], lengths: &HashMap<Vec<u8>, usize>) -> Result<(Vec<usize>, usize), String> {
+    let mut offsets = Vec::with_capacity(path.len());
+    let mut offset = 0;
+    for (node, _) in path.iter() {
+        offsets.push(offset);
+        offset += *lengths.get(node).ok_or(format!("Missing length for node {}", String::from_utf8_lossy(node)))?;
+    }
+    Ok((offsets, offset))
+}
+
+/// Returns the lengths of the nodes in the graph, indexed by node name.
+///
+/// Returns an error if the canonical representation of the graph cannot be interpreted or if a node has no sequence (`*` in GFA).
+pub fn node_lengths<G: Graph>(graph: &G) -> Result<HashMap<Vec<u8>, usize>, String> {
+    let graph = SequenceGraph::new(graph)?;
+    Ok(graph.names.into_iter().zip(graph.sequences.iter().map(|sequence| sequence.len())).collect())
+}
+
+fn parse_interval(line: &[u8]) -> Result<TranslationInterval, String> {
+    let fields: Vec<&[u8]> = line.split(|&c| c == b'\t').collect();
+    if fields.len() != 6 {
+        return Err(format!("expected 6 fields, found {}", fields.len()));
+    }
+    let parse_offset = |field: &[u8]| -> Result<usize, String> {
+        std::str::from_utf8(field).ok().and_then(|s| s.parse::<usize>().ok())
+            .ok_or(format!("invalid offset: {}", String::from_utf8_lossy(field)))
+    };
+    let start = parse_offset(fields[1])?;
+    let end = parse_offset(fields[2])?;
+    if end < start {
+        return Err(String::from("interval end is before the start"));
+    }
+    let orientation = match fields[5] {
+        b"+" => Orientation::Forward,
+        b"-" => Orientation::Reverse,
+        _ => return Err(format!("invalid orientation: {}", String::from_utf8_lossy(fields[5]))),
+    };
+    Ok(TranslationInterval {
+        from: fields[0].to_vec(),
+        from_range: start..end,
+        to: fields[3].to_vec(),
+        to_offset: parse_offset(fields[4])?,
+        orientation,
+    })
+}
+
+//-----------------------------------------------------------------------------
+
+/// Chops the nodes of the graph into pieces of at most `max_len` bp.
+///
+/// The nodes of the new graph are numbered consecutively starting from 1 in canonical order, with the pieces of each node numbered in order.
+/// Returns the new graph and the translation from the original graph to the new graph.
+/// Returns an error if `max_len` is 0, if a node is empty or has no sequence (`*` in GFA), or if the graph cannot be built.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::graph::GraphInt;
+/// use pggname::translation;
+/// use pggname::algorithms;
+///
+/// let gfa = b"S\ta\tGATTACA\nS\tb\tC\nL\ta\t+\tb\t+\n";
+/// let graph: pggname::graph::GraphStr = algorithms::parse_gfa_bytes(gfa).unwrap();
+/// let (chopped, map): (GraphInt, _) = translation::chop(&graph, 4).unwrap();
+/// assert_eq!(pggname::graph::Graph::statistics(&chopped).nodes, 3);
+/// assert_eq!(map.translate(b"a", 5), Some((b"2".as_slice(), 1, pggname::Orientation::Forward)));
+/// ```
+pub fn chop<A: Graph, B: Graph>(graph: &A, max_len: usize) -> Result<(B, TranslationMap), String> {
+    if max_len == 0 {
+        return Err(String::from("Maximum node length must be positive"));
+    }
+    let graph = SequenceGraph::new(graph)?;
+
+    let mut result = B::new();
+    let mut intervals = Vec::new();
+    // First and last piece of each original node.
+    let mut pieces: Vec<(usize, usize)> = Vec::with_capacity(graph.len());
+    let mut next_id = 1;
+    for (name, sequence) in graph.names.iter().zip(graph.sequences.iter()) {
+        if sequence.is_empty() {
+            return Err(format!("Cannot chop empty node {}", String::from_utf8_lossy(name)));
+        }
+        let first = next_id;
+        for (i, piece) in sequence.chunks(max_len).enumerate() {
+            let id = next_id.to_string().into_bytes();
+            result.add_node(&id, piece)?;
+            let start = i * max_len;
+            intervals.push(TranslationInterval {
+                from: name.clone(),
+                from_range: start..start + piece.len(),
+                to: id,
+                to_offset: 0,
+                orientation: Orientation::Forward,
+            });
+            if next_id > first {
+                let prev = (next_id - 1).to_string().into_bytes();
+                result.add_edge(&prev, Orientation::Forward, &intervals.last().unwrap().to, Orientation::Forward)?;
+            }
+            next_id += 1;
+        }
+        pieces.push((first, next_id - 1));
+    }
+
+    for &(from, to) in graph.edges.iter() {
+        let source = match from.1 {
+            Orientation::Forward => pieces[from.0].1,
+            Orientation::Reverse => pieces[from.0].0,
+        };
+        let dest = match to.1 {
+            Orientation::Forward => pieces[to.0].0,
+            Orientation::Reverse => pieces[to.0].1,
+        };
+        result.add_edge(source.to_string().as_bytes(), from.1, dest.to_string().as_bytes(), to.1)?;
+    }
+    result.finalize()?;
+
+    Ok((result, TranslationMap::from_sorted(intervals)))
+}
+
+/// Renumbers the nodes of the graph consecutively starting from 1 in canonical order.
+///
+/// Returns the new graph and the translation from the original graph to the new graph.
+/// Returns an error if a node is empty or has no sequence (`*` in GFA), or if the graph cannot be built.
+pub fn renumber<A: Graph, B: Graph>(graph: &A) -> Result<(B, TranslationMap), String> {
+    chop(graph, usize::MAX)
+}
+
+/// Merges the maximal unary paths (unitigs) of the graph into single nodes.
+///
+/// This is the inverse of [`chop`]: graphs that differ only in how their unary paths have been chopped into nodes have the same normalized graph.
+/// Each unitig is oriented so that its sequence is lexicographically no greater than its reverse complement.
+/// A circular unitig becomes a node with a self-loop, and its sequence is the lexicographically smallest rotation of the cycle in either orientation.
+/// If the rotation starts in the middle of an original node, that node is translated as two intervals.
+/// The nodes of the new graph are numbered consecutively starting from 1 in the order of their sequences.
+/// Returns the new graph and the translation from the original graph to the new graph.
+/// Returns an error if a node has no sequence (`*` in GFA), as merging it would make `*` part of a sequence, or if the graph cannot be built.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::graph::GraphInt;
+/// use pggname::{algorithms, translation};
+///
+/// let gfa = b"S\ta\tGATTACA\nS\tb\tC\nL\ta\t+\tb\t+\n";
+/// let graph: pggname::graph::GraphStr = algorithms::parse_gfa_bytes(gfa).unwrap();
+/// let (chopped, _): (GraphInt, _) = translation::chop(&graph, 2).unwrap();
+/// let (normalized, map): (GraphInt, _) = translation::normalize(&chopped).unwrap();
+/// assert_eq!(pggname::graph::Graph::statistics(&normalized).nodes, 1);
+/// assert_eq!(map.translate(b"3", 1), Some((b"1".as_slice(), 5, pggname::Orientation::Forward)));
+///
+/// let (direct, _): (GraphInt, _) = translation::normalize(&graph).unwrap();
+/// assert_eq!(pggname::stable_name(&direct), pggname::stable_name(&normalized));
+/// ```
+pub fn normalize<A: Graph, B: Graph>(graph: &A) -> Result<(B, TranslationMap), String> {
+    let graph = SequenceGraph::new(graph)?;
+
+    // Orient the unitigs canonically and sort them by sequence.
+    // Circular unitigs also store the rotation of the sequence.
+    let mut unitigs: Vec<(Vec<u8>, Vec<Handle>, Option<usize>)> = Vec::new();
+    for unitig in graph.unitigs() {
+        let reverse = reverse_walk(&unitig);
+        let (forward_seq, reverse_seq) = (graph.walk_sequence(&unitig), graph.walk_sequence(&reverse));
+        if graph.is_circular(&unitig) {
+            let (forward_rot, reverse_rot) = (least_rotation(&forward_seq), least_rotation(&reverse_seq));
+            let forward_seq = [&forward_seq[forward_rot..], &forward_seq[..forward_rot]].concat();
+            let reverse_seq = [&reverse_seq[reverse_rot..], &reverse_seq[..reverse_rot]].concat();
+            if reverse_seq < forward_seq {
+                unitigs.push((reverse_seq, reverse, Some(reverse_rot)));
+            } else {
+                unitigs.push((forward_seq, unitig, Some(forward_rot)));
+            }
+        } else if reverse_seq < forward_seq {
+            unitigs.push((reverse_seq, reverse, None));
+        } else {
+            unitigs.push((forward_seq, unitig, None));
+        }
+    }
+    unitigs.sort();
+
+    // Position of each original node as (unitig, offset in the unitig, orientation in the unitig).
+    let mut result = B::new();
+    let mut positions: Vec<(usize, usize, Orientation)> = vec![(0, 0, Orientation::Forward); graph.len()];
+    let mut internal: BTreeSet<(Handle, Handle)> = BTreeSet::new();
+    for (i, (sequence, unitig, rotation)) in unitigs.iter().enumerate() {
+        let name = (i + 1).to_string();
+        result.add_node(name.as_bytes(), sequence)?;
+        let rotation = rotation.unwrap_or(0);
+        let mut offset = 0;
+        for &handle in unitig.iter() {
+            let rotated = if sequence.is_empty() { 0 } else { (offset + sequence.len() - rotation) % sequence.len() };
+            positions[handle.0] = (i, rotated, handle.1);
+            offset += graph.sequences[handle.0].len();
+        }
+        let mut pairs: Vec<(Handle, Handle)> = unitig.windows(2).map(|pair| (pair[0], pair[1])).collect();
+        if unitigs[i].2.is_some() {
+            pairs.push((*unitig.last().unwrap(), unitig[0]));
+            result.add_edge(name.as_bytes(), Orientation::Forward, name.as_bytes(), Orientation::Forward)?;
+        }
+        for (from, to) in pairs {
+            internal.insert((from, to));
+            internal.insert((flip(to), flip(from)));
+        }
+    }
+
+    // Edges that are not within a unitig connect unitig ends.
+    for &(from, to) in graph.edges.iter() {
+        if internal.contains(&(from, to)) {
+            continue;
+        }
+        let (source, source_o) = (positions[from.0].0, if from.1 == positions[from.0].2 { Orientation::Forward } else { Orientation::Reverse });
+        let (dest, dest_o) = (positions[to.0].0, if to.1 == positions[to.0].2 { Orientation::Forward } else { Orientation::Reverse });
+        result.add_edge((source + 1).to_string().as_bytes(), source_o, (dest + 1).to_string().as_bytes(), dest_o)?;
+    }
+    result.finalize()?;
+
+    let mut intervals = Vec::with_capacity(graph.len());
+    for (node, (name, sequence)) in graph.names.iter().zip(graph.sequences.iter()).enumerate() {
+        if sequence.is_empty() {
+            continue;
+        }
+        let (unitig, offset, orientation) = positions[node];
+        let to = (unitig + 1).to_string().into_bytes();
+        // A node that wraps around the start of a circular unitig is split into two intervals.
+        let len = sequence.len();
+        let fits = (unitigs[unitig].0.len() - offset).min(len);
+        let parts = match orientation {
+            Orientation::Forward => [(0..fits, offset), (fits..len, 0)],
+            Orientation::Reverse => [(0..len - fits, 0), (len - fits..len, offset)],
+        };
+        for (from_range, to_offset) in parts {
+            if !from_range.is_empty() {
+                intervals.push(TranslationInterval { from: name.clone(), from_range, to: to.clone(), to_offset, orientation });
+            }
+        }
+    }
+
+    Ok((result, TranslationMap::from_sorted(intervals)))
+}
+
+/// Returns the name of the graph with a translation relationship to the name of its normalized graph.
+///
+/// The normalized graph is built with [`normalize`].
+/// Graphs that differ only in how their unary paths have been chopped have translation relationships to the same normalized name, which makes them recognizable as equivalent.
+/// No relationship is recorded if the graph is already normalized.
+/// Returns an error if a node has no sequence or if the normalized graph cannot be built.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::graph::{GraphInt, GraphStr};
+/// use pggname::{algorithms, translation};
+///
+/// let graph: GraphStr = algorithms::parse_gfa_bytes(b"S\ta\tGATTACA\nS\tb\tC\nL\ta\t+\tb\t+\n").unwrap();
+/// let (chopped, _): (GraphInt, _) = translation::chop(&graph, 3).unwrap();
+/// let original = translation::normalized_name(&graph).unwrap();
+/// let copy = translation::normalized_name(&chopped).unwrap();
+/// assert_ne!(original.name(), copy.name());
+/// let (_, normalized) = original.translation_iter().next().unwrap();
+/// assert_eq!(copy.translation_iter().next().unwrap().1, normalized);
+/// ```
+pub fn normalized_name<G: Graph>(graph: &G) -> Result<GraphName, String> {
+    let (normalized, _): (GraphInt, _) = normalize(graph)?;
+    let name = algorithms::stable_name(graph);
+    let normalized = algorithms::stable_name(&normalized);
+    let mut result = GraphName::new(name.clone());
+    if normalized != name {
+        result.add_translation(&name, &normalized);
+    }
+    Ok(result)
+}
+
+//-----------------------------------------------------------------------------
+
+/// Extracts the subgraph induced by the given nodes.
+///
+/// The new graph contains the given nodes with their original names and all edges between them.
+/// Returns the new graph and the translation from the original graph to the new graph, which covers the extracted nodes.
+/// Returns an error if a node does not exist or if the graph cannot be built.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::graph::GraphStr;
+/// use pggname::{Graph, algorithms, translation};
+///
+/// let gfa = b"S\ta\tGAT\nS\tb\tT\nS\tc\tA\nL\ta\t+\tb\t+\nL\tb\t+\tc\t+\n";
+/// let graph: GraphStr = algorithms::parse_gfa_bytes(gfa).unwrap();
+/// let (subgraph, map): (GraphStr, _) = translation::extract(&graph, &[b"a".to_vec(), b"b".to_vec()]).unwrap();
+/// assert_eq!(subgraph.statistics().edges, 1);
+/// assert_eq!(map.translate(b"c", 0), None);
+/// ```
+pub fn extract<A: Graph, B: Graph>(graph: &A, nodes: &[Vec<u8>]) -> Result<(B, TranslationMap), String> {
+    let graph = SequenceGraph::new(graph)?;
+    let index: HashMap<&[u8], usize> = graph.names.iter().enumerate().map(|(i, name)| (name.as_slice(), i)).collect();
+    let mut selected = vec![false; graph.len()];
+    for node in nodes {
+        let i = *index.get(node.as_slice()).ok_or(format!("Missing node {}", String::from_utf8_lossy(node)))?;
+        selected[i] = true;
+    }
+
+    let mut result = B::new();
+    let mut intervals = Vec::new();
+    for (index, (name, sequence)) in graph.names.iter().zip(graph.sequences.iter()).enumerate() {
+        if !selected[index] {
+            continue;
+        }
+        result.add_node(name, sequence)?;
+        if !sequence.is_empty() {
+            intervals.push(TranslationInterval {
+                from: name.clone(),
+                from_range: 0..sequence.len(),
+                to: name.clone(),
+                to_offset: 0,
+                orientation: Orientation::Forward,
+            });
+        }
+    }
+    for &(from, to) in graph.edges.iter().filter(|(from, to)| selected[from.0] && selected[to.0]) {
+        result.add_edge(&graph.names[from.0], from.1, &graph.names[to.0], to.1)?;
+    }
+    result.finalize()?;
+
+    Ok((result, TranslationMap::from_sorted(intervals)))
+}
+
+/// A transformation that produces a new graph from an existing graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transformation {
+    /// Chops the nodes into pieces of at most the given length with [`chop`].
+    Chop(usize),
+    /// Extracts the subgraph induced by the given nodes with [`extract`].
+    Extract(Vec<Vec<u8>>),
+    /// Merges unary paths into single nodes with [`normalize`].
+    Merge,
+    /// Renumbers the nodes with [`renumber`].
+    Renumber,
+}
+
+impl Transformation {
+    /// Returns the relationship between the new graph and the original graph.
+    pub fn relationship(&self) -> RelationshipKind {
+        match self {
+            Transformation::Extract(_) => RelationshipKind::Subgraph,
+            _ => RelationshipKind::Translation,
+        }
+    }
+
+    /// Applies the transformation to the graph.
+    ///
+    /// Returns the new graph and a record of the change in identity.
+    /// Returns an error if the transformation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pggname::graph::{GraphInt, GraphStr};
+    /// use pggname::name::RelationshipKind;
+    /// use pggname::translation::Transformation;
+    /// use pggname::{GraphName, algorithms};
+    ///
+    /// let graph: GraphStr = algorithms::parse_gfa_bytes(b"S\ta\tGATTACA\nS\tb\tC\nL\ta\t+\tb\t+\n").unwrap();
+    /// let (chopped, record): (GraphInt, _) = Transformation::Chop(4).apply(&graph).unwrap();
+    /// assert_eq!(record.old_name, pggname::stable_name(&graph));
+    /// assert_eq!(record.new_name, pggname::stable_name(&chopped));
+    /// assert_eq!(record.relationship, RelationshipKind::Translation);
+    ///
+    /// let mut name = GraphName::new(record.new_name.clone());
+    /// record.record(&mut name);
+    /// assert_eq!(name.translation_iter().next(), Some((record.new_name.as_str(), record.old_name.as_str())));
+    /// ```
+    pub fn apply<A: Graph, B: Graph>(&self, graph: &A) -> Result<(B, RenameRecord), String> {
+        let (result, mapping): (B, TranslationMap) = match self {
+            Transformation::Chop(max_len) => chop(graph, *max_len)?,
+            Transformation::Extract(nodes) => extract(graph, nodes)?,
+            Transformation::Merge => normalize(graph)?,
+            Transformation::Renumber => renumber(graph)?,
+        };
+        let record = RenameRecord {
+            old_name: algorithms::stable_name(graph),
+            new_name: algorithms::stable_name(&result),
+            relationship: self.relationship(),
+            mapping,
+        };
+        Ok((result, record))
+    }
+}
+
+/// A record of the change in identity caused by a [`Transformation`].
+///
+/// A pipeline that applies several transformations can collect the records as an audit trail.
+/// The [`Display`](std::fmt::Display) form is a tab-separated line with the old name, the new name, and the relationship tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenameRecord {
+    /// Name of the original graph.
+    pub old_name: String,
+    /// Name of the new graph.
+    pub new_name: String,
+    /// Relationship from the new graph to the original graph.
+    pub relationship: RelationshipKind,
+    /// Translation from the original graph to the new graph.
+    pub mapping: TranslationMap,
+}
+
+impl RenameRecord {
+    /// Records the relationship from the new name to the old name in the given object.
+    ///
+    /// Does nothing if the names are identical.
+    pub fn record(&self, name: &mut GraphName) {
+        if self.old_name == self.new_name {
+            return;
+        }
+        match self.relationship {
+            RelationshipKind::Subgraph => name.add_subgraph(&self.new_name, &self.old_name),
+            RelationshipKind::Translation => name.add_translation(&self.new_name, &self.old_name),
+        }
+    }
+}
+
+impl std::fmt::Display for RenameRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\t{}\t{}", self.old_name, self.new_name, self.relationship.tag())
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+// An oriented node as (node index, orientation).
+pub(crate) type Handle = (usize, Orientation);
+
+fn flip(handle: Handle) -> Handle {
+    (handle.0, handle.1.flip())
+}
+
+// Returns the starting offset of the lexicographically smallest rotation of the sequence.
+fn least_rotation(sequence: &[u8]) -> usize {
+    let n = sequence.len();
+    let (mut i, mut j, mut k) = (0, 1, 0);
+    while i < n && j < n && k < n {
+        let (a, b) = (sequence[(i + k) % n], sequence[(j + k) % n]);
+        if a == b {
+            k += 1;
+            continue;
+        }
+        if a > b {
+            i += k + 1;
+        } else {
+            j += k + 1;
+        }
+        if i == j {
+            j += 1;
+        }
+        k = 0;
+    }
+    i.min(j)
+}
+
+// Returns the walk in the other orientation.
+fn reverse_walk(walk: &[Handle]) -> Vec<Handle> {
+    walk.iter().rev().map(|&handle| flip(handle)).collect()
+}
+
+fn orientation_index(o: Orientation) -> usize {
+    match o {
+        Orientation::Forward => 0,
+        Orientation::Reverse => 1,
+    }
+}
+
+// A bidirected sequence graph with dense node indexes, built from canonical node records.
+pub(crate) struct SequenceGraph {
+    pub names: Vec<Vec<u8>>,
+    pub sequences: Vec<Vec<u8>>,
+    // Successors of each node in both orientations.
+    successors: Vec<[Vec<Handle>; 2]>,
+    // Canonical edges.
+    pub edges: Vec<(Handle, Handle)>,
+}
+
+impl SequenceGraph {
+    // Builds the graph from the canonical GFA representation of the given graph.
+    // Returns an error if a node has no sequence (`*` in GFA), as translations are defined over the bases.
+    pub fn new<G: Graph>(graph: &G) -> Result<Self, String> {
+        let mut names: Vec<Vec<u8>> = Vec::new();
+        let mut sequences: Vec<Vec<u8>> = Vec::new();
+        let mut index: HashMap<Vec<u8>, usize> = HashMap::new();
+        let mut edges: Vec<(Vec<u8>, Orientation, Vec<u8>, Orientation)> = Vec::new();
+        for record in graph.node_iter() {
+            for line in record.split(|&c| c == b'\n').filter(|line| !line.is_empty()) {
+                let fields: Vec<&[u8]> = line.split(|&c| c == b'\t').collect();
+                match fields[0] {
+                    b"S" if fields.len() == 3 => {
+                        if fields[2] == b"*" {
+                            return Err(format!("Node {} has no sequence", String::from_utf8_lossy(fields[1])));
+                        }
+                        index.insert(fields[1].to_vec(), names.len());
+                        names.push(fields[1].to_vec());
+                        sequences.push(fields[2].to_vec());
+                    }
+                    b"L" if fields.len() == 5 => {
+                        let source_o = if fields[2] == b"+" { Orientation::Forward } else { Orientation::Reverse };
+                        let dest_o = if fields[4] == b"+" { Orientation::Forward } else { Orientation::Reverse };
+                        edges.push((fields[1].to_vec(), source_o, fields[3].to_vec(), dest_o));
+                    }
+                    _ => return Err(format!("Invalid canonical record: {}", String::from_utf8_lossy(line))),
+                }
+            }
+        }
+
+        let mut successors: Vec<[Vec<Handle>; 2]> = vec![[Vec::new(), Vec::new()]; names.len()];
+        let mut edge_list: Vec<(Handle, Handle)> = Vec::with_capacity(edges.len());
+        for (source, source_o, dest, dest_o) in edges.iter() {
+            let from = (*index.get(source).ok_or(format!("Missing node {}", String::from_utf8_lossy(source)))?, *source_o);
+            let to = (*index.get(dest).ok_or(format!("Missing node {}", String::from_utf8_lossy(dest)))?, *dest_o);
+            edge_list.push((from, to));
+            successors[from.0][orientation_index(from.1)].push(to);
+            // Self-loops such as 1+ -> 1- are their own reverses.
+            if flip(to) != from {
+                successors[to.0][orientation_index(to.1.flip())].push(flip(from));
+            }
+        }
+
+        Ok(SequenceGraph { names, sequences, successors, edges: edge_list })
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn successors(&self, handle: Handle) -> &[Handle] {
+        &self.successors[handle.0][orientation_index(handle.1)]
+    }
+
+    pub fn has_edge(&self, from: Handle, to: Handle) -> bool {
+        self.successors(from).contains(&to)
+    }
+
+    pub fn sequence(&self, handle: Handle) -> Cow<'_, [u8]> {
+        match handle.1 {
+            Orientation::Forward => Cow::Borrowed(&self.sequences[handle.0]),
+            Orientation::Reverse => Cow::Owned(graph::reverse_complement(&self.sequences[handle.0])),
+        }
+    }
+
+    // Returns the next handle in a unary path, if the path can be extended.
+    fn unary_successor(&self, handle: Handle) -> Option<Handle> {
+        let successors = self.successors(handle);
+        if successors.len() != 1 || successors[0].0 == handle.0 {
+            return None;
+        }
+        let next = successors[0];
+        if self.successors(flip(next)).len() != 1 {
+            return None;
+        }
+        Some(next)
+    }
+
+    // Returns `true` if the unitig is a cycle: the last node is the unary predecessor of the first node.
+    // A single node is a cycle if its only edge is a self-loop that preserves the orientation.
+    fn is_circular(&self, unitig: &[Handle]) -> bool {
+        let (first, last) = (unitig[0], *unitig.last().unwrap());
+        if unitig.len() == 1 {
+            return self.successors(first) == [first] && self.successors(flip(first)) == [flip(first)];
+        }
+        self.unary_successor(last) == Some(first)
+    }
+
+    // Decomposes the graph into maximal unary paths.
+    pub fn unitigs(&self) -> Vec<Vec<Handle>> {
+        let mut result = Vec::new();
+        let mut visited = vec![false; self.len()];
+        for node in 0..self.len() {
+            if visited[node] {
+                continue;
+            }
+
+            // Find the start of the unitig.
+            let mut in_unitig: BTreeSet<usize> = BTreeSet::new();
+            in_unitig.insert(node);
+            let mut start = (node, Orientation::Forward);
+            while let Some(prev) = self.unary_successor(flip(start)).map(flip) {
+                if in_unitig.contains(&prev.0) {
+                    break;
+                }
+                in_unitig.insert(prev.0);
+                start = prev;
+            }
+
+            // Walk forward.
+            let mut unitig = vec![start];
+            visited[start.0] = true;
+            let mut curr = start;
+            while let Some(next) = self.unary_successor(curr) {
+                if visited[next.0] {
+                    break;
+                }
+                visited[next.0] = true;
+                unitig.push(next);
+                curr = next;
+            }
+            result.push(unitig);
+        }
+        result
+    }
+
+    // Returns the sequence spelled by the walk.
+    pub fn walk_sequence(&self, walk: &[Handle]) -> Vec<u8> {
+        let mut result = Vec::new();
+        for &handle in walk {
+            result.extend_from_slice(&self.sequence(handle));
+        }
+        result
+    }
+
+    // Returns up to `limit` walks starting from `start` that spell `sequence` exactly.
+    fn walks_from(&self, start: Handle, sequence: &[u8], limit: usize, result: &mut Vec<Vec<Handle>>) {
+        let mut path: Vec<Handle> = Vec::new();
+        let mut stack: Vec<(Handle, usize, usize)> = vec![(start, 0, 0)];
+        while let Some((handle, offset, depth)) = stack.pop() {
+            path.truncate(depth);
+            let node_seq = self.sequence(handle);
+            if node_seq.is_empty() || !sequence[offset..].starts_with(&node_seq) {
+                continue;
+            }
+            path.push(handle);
+            let next_offset = offset + node_seq.len();
+            if next_offset == sequence.len() {
+                result.push(path.clone());
+                if result.len() >= limit {
+                    return;
+                }
+                continue;
+            }
+            for &next in self.successors(handle) {
+                stack.push((next, next_offset, depth + 1));
+            }
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+// Anchor candidates are indexed by this many bases.
+const ANCHOR_PREFIX: usize = 32;
+
+// Places the unitigs of graph A as walks in graph B.
+struct Placer<'a> {
+    a: &'a SequenceGraph,
+    b: &'a SequenceGraph,
+    unitigs: Vec<Vec<Handle>>,
+    unitig_sequences: Vec<Vec<u8>>,
+    // For each node in A: (unitig, is first node, is last node).
+    unitig_of: Vec<(usize, bool, bool)>,
+    // Oriented nodes of B by sequence prefix.
+    index: HashMap<Vec<u8>, Vec<Handle>>,
+    // Walk in B for each unitig, in the orientation of the unitig.
+    placements: Vec<Option<Vec<Handle>>>,
+}
+
+impl<'a> Placer<'a> {
+    fn new(a: &'a SequenceGraph, b: &'a SequenceGraph) -> Self {
+        let unitigs = a.unitigs();
+        let unitig_sequences: Vec<Vec<u8>> = unitigs.iter().map(|unitig| a.walk_sequence(unitig)).collect();
+        let mut unitig_of = vec![(0, false, false); a.len()];
+        for (i, unitig) in unitigs.iter().enumerate() {
+            for (j, handle) in unitig.iter().enumerate() {
+                unitig_of[handle.0] = (i, j == 0, j + 1 == unitig.len());
+            }
+        }
+
+        let mut index: HashMap<Vec<u8>, Vec<Handle>> = HashMap::new();
+        for node in 0..b.len() {
+            for o in [Orientation::Forward, Orientation::Reverse] {
+                let sequence = b.sequence((node, o));
+                let len = sequence.len().min(ANCHOR_PREFIX);
+                index.entry(sequence[..len].to_vec()).or_default().push((node, o));
+            }
+        }
+
+        // Unitigs consisting of empty nodes are placed trivially.
+        let placements = unitig_sequences.iter().map(|sequence| sequence.is_empty().then(Vec::new)).collect();
+        Placer { a, b, unitigs, unitig_sequences, unitig_of, index, placements }
+    }
+
+    // Returns the sequence of the unitig in the given orientation.
+    fn oriented_sequence(&self, unitig: usize, o: Orientation) -> Cow<'_, [u8]> {
+        match o {
+            Orientation::Forward => Cow::Borrowed(&self.unitig_sequences[unitig]),
+            Orientation::Reverse => Cow::Owned(graph::reverse_complement(&self.unitig_sequences[unitig])),
+        }
+    }
+
+    // Tries to find a unique placement for the unitig without context.
+    fn anchor(&self, unitig: usize) -> Option<Vec<Handle>> {
+        let sequence = &self.unitig_sequences[unitig];
+        let mut walks = Vec::new();
+        for len in 1..=sequence.len().min(ANCHOR_PREFIX) {
+            if let Some(candidates) = self.index.get(&sequence[..len]) {
+                for &start in candidates {
+                    self.b.walks_from(start, sequence, 2, &mut walks);
+                    if walks.len() > 1 {
+                        return None;
+                    }
+                }
+            }
+        }
+        walks.pop()
+    }
+
+    // Places the unitig in the given orientation with the given walk.
+    fn place(&mut self, unitig: usize, o: Orientation, walk: Vec<Handle>) {
+        let walk = match o {
+            Orientation::Forward => walk,
+            Orientation::Reverse => walk.into_iter().rev().map(flip).collect(),
+        };
+        self.placements[unitig] = Some(walk);
+    }
+
+    // Extends the placements from the given unitig to adjacent unitigs.
+    fn propagate(&mut self, unitig: usize) {
+        let (a, b) = (self.a, self.b);
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        queue.push_back(unitig);
+        while let Some(curr) = queue.pop_front() {
+            for o in [Orientation::Forward, Orientation::Reverse] {
+                // The last node of the unitig in orientation `o` and its image in B.
+                let walk = self.placements[curr].as_ref().unwrap();
+                let (a_last, b_last) = match o {
+                    Orientation::Forward => (*self.unitigs[curr].last().unwrap(), *walk.last().unwrap()),
+                    Orientation::Reverse => (flip(self.unitigs[curr][0]), flip(walk[0])),
+                };
+                for &next in a.successors(a_last) {
+                    let (next_unitig, is_first, is_last) = self.unitig_of[next.0];
+                    if self.placements[next_unitig].is_some() {
+                        continue;
+                    }
+                    let next_o = if is_first && next == self.unitigs[next_unitig][0] {
+                        Orientation::Forward
+                    } else if is_last && next == flip(*self.unitigs[next_unitig].last().unwrap()) {
+                        Orientation::Reverse
+                    } else {
+                        continue;
+                    };
+                    let sequence = self.oriented_sequence(next_unitig, next_o).into_owned();
+                    let mut walks = Vec::new();
+                    for &start in b.successors(b_last) {
+                        b.walks_from(start, &sequence, 2, &mut walks);
+                        if walks.len() > 1 {
+                            break;
+                        }
+                    }
+                    if walks.len() == 1 {
+                        self.place(next_unitig, next_o, walks.pop().unwrap());
+                        queue.push_back(next_unitig);
+                    }
+                }
+            }
+        }
+    }
+
+    // Places all unitigs that can be placed unambiguously.
+    // Returns the number of unplaced unitigs.
+    fn place_all(&mut self) -> usize {
+        loop {
+            let mut progress = false;
+            for unitig in 0..self.unitigs.len() {
+                if self.placements[unitig].is_some() {
+                    continue;
+                }
+                if let Some(walk) = self.anchor(unitig) {
+                    self.place(unitig, Orientation::Forward, walk);
+                    self.propagate(unitig);
+                    progress = true;
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+        self.placements.iter().filter(|placement| placement.is_none()).count()
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+// Position of a base in graph B: (oriented node, offset in the oriented node).
+type Position = (Handle, usize);
+
+// Images of the first and the last base of a node in graph A, in the orientation the node has in its unitig.
+#[derive(Clone, Copy)]
+struct NodeImage {
+    orientation: Orientation,
+    first: Position,
+    last: Position,
+}
+
+// Infers a translation from graph A to graph B.
+pub(crate) fn infer(a: &SequenceGraph, b: &SequenceGraph) -> TranslationInference {
+    let mut placer = Placer::new(a, b);
+    let unplaced = placer.place_all();
+    if unplaced > 0 {
+        return TranslationInference::not_found(format!("Could not place {} unitigs unambiguously in the target graph", unplaced));
+    }
+
+    // Build the mapping and record the images of the nodes.
+    let mut mapping: Vec<(usize, TranslationInterval)> = Vec::new();
+    let mut images: Vec<Option<NodeImage>> = vec![None; a.len()];
+    let mut used = vec![false; b.len()];
+    let mut used_edges: BTreeSet<(Handle, Handle)> = BTreeSet::new();
+    for (unitig, walk) in placer.unitigs.iter().zip(placer.placements.iter()) {
+        let walk = walk.as_ref().unwrap();
+        for &(node, _) in walk.iter() {
+            if used[node] {
+                return TranslationInference::not_found(format!(
+                    "Node {} of the target graph is used more than once", String::from_utf8_lossy(&b.names[node])
+                ));
+            }
+            used[node] = true;
+        }
+        for pair in walk.windows(2) {
+            used_edges.insert(canonical_edge(pair[0], pair[1]));
+        }
+        if walk.is_empty() {
+            continue;
+        }
+
+        let mut b_iter = walk.iter().map(|&handle| (handle, b.sequences[handle.0].len()));
+        let (mut b_handle, mut b_len) = b_iter.next().unwrap();
+        let mut b_start = 0;
+        let mut unitig_offset = 0;
+        for &a_handle in unitig.iter() {
+            let a_len = a.sequences[a_handle.0].len();
+            let a_start = unitig_offset;
+            let a_end = a_start + a_len;
+            let mut first = None;
+            while unitig_offset < a_end {
+                if unitig_offset >= b_start + b_len {
+                    b_start += b_len;
+                    (b_handle, b_len) = b_iter.next().unwrap();
+                    continue;
+                }
+                let end = a_end.min(b_start + b_len);
+                mapping.push((a_handle.0, interval(a, b, (a_handle, a_start, a_len), (b_handle, b_start, b_len), unitig_offset..end)));
+                if first.is_none() {
+                    first = Some((b_handle, unitig_offset - b_start));
+                }
+                if end == a_end {
+                    images[a_handle.0] = Some(NodeImage {
+                        orientation: a_handle.1,
+                        first: first.unwrap(),
+                        last: (b_handle, end - 1 - b_start),
+                    });
+                }
+                unitig_offset = end;
+            }
+        }
+    }
+
+    // Every edge of A must map to consecutive positions in a node of B or to an edge of B.
+    // Empty nodes have no images, so edges through them connect their non-empty neighbors.
+    for node in 0..a.len() {
+        if images[node].is_none() {
+            continue;
+        }
+        for o in [Orientation::Forward, Orientation::Reverse] {
+            for next in nonempty_successors(a, &images, (node, o)) {
+                let (from, from_offset) = end_position(b, &images, (node, o), true);
+                let (to, to_offset) = end_position(b, &images, next, false);
+                if from == to && from_offset + 1 == to_offset {
+                    continue;
+                }
+                if from_offset + 1 != b.sequences[from.0].len() || to_offset != 0 || !b.has_edge(from, to) {
+                    return TranslationInference::not_found(format!(
+                        "Edge from {} to {} does not exist in the target graph",
+                        String::from_utf8_lossy(&a.names[node]), String::from_utf8_lossy(&a.names[next.0])
+                    ));
+                }
+                used_edges.insert(canonical_edge(from, to));
+            }
+        }
+    }
+
+    mapping.sort_by_key(|(node, interval)| (*node, interval.from_range.start));
+    let mapping = TranslationMap::from_sorted(mapping.into_iter().map(|(_, interval)| interval).collect());
+    let verdict = if used.iter().all(|&x| x) && used_edges.len() == b.edges.len() {
+        TranslationVerdict::Equivalent
+    } else {
+        TranslationVerdict::Subgraph
+    };
+    TranslationInference { verdict, mapping }
+}
+
+// Returns the mapping for an interval of a unitig.
+// The nodes covering the interval are given as (handle, start in unitig, length).
+fn interval(
+    a: &SequenceGraph, b: &SequenceGraph,
+    a_node: (Handle, usize, usize), b_node: (Handle, usize, usize),
+    range: Range<usize>
+) -> TranslationInterval {
+    let (a_handle, a_start, a_len) = a_node;
+    let (b_handle, b_start, b_len) = b_node;
+    let from_range = forward_range(a_handle.1, range.start - a_start..range.end - a_start, a_len);
+    let to_range = forward_range(b_handle.1, range.start - b_start..range.end - b_start, b_len);
+    TranslationInterval {
+        from: a.names[a_handle.0].clone(),
+        from_range,
+        to: b.names[b_handle.0].clone(),
+        to_offset: to_range.start,
+        orientation: if a_handle.1 == b_handle.1 { Orientation::Forward } else { Orientation::Reverse },
+    }
+}
+
+// Converts a range in an oriented node to the forward orientation.
+fn forward_range(o: Orientation, range: Range<usize>, len: usize) -> Range<usize> {
+    match o {
+        Orientation::Forward => range,
+        Orientation::Reverse => len - range.end..len - range.start,
+    }
+}
+
+// Returns the successors of the oriented node of graph A, replacing empty nodes with their successors.
+fn nonempty_successors(a: &SequenceGraph, images: &[Option<NodeImage>], handle: Handle) -> Vec<Handle> {
+    let mut result = Vec::new();
+    let mut visited: BTreeSet<Handle> = BTreeSet::new();
+    let mut stack: Vec<Handle> = a.successors(handle).to_vec();
+    while let Some(next) = stack.pop() {
+        if images[next.0].is_some() {
+            result.push(next);
+        } else if visited.insert(next) {
+            stack.extend_from_slice(a.successors(next));
+        }
+    }
+    result.sort();
+    result.dedup();
+    result
+}
+
+// Returns the image of the last base (if `last`) or the first base of the oriented node of graph A.
+// The node must not be empty.
+fn end_position(b: &SequenceGraph, images: &[Option<NodeImage>], handle: Handle, last: bool) -> Position {
+    let image = images[handle.0].unwrap();
+    if handle.1 == image.orientation {
+        if last { image.last } else { image.first }
+    } else {
+        let (position, offset) = if last { image.first } else { image.last };
+        (flip(position), b.sequences[position.0].len() - 1 - offset)
+    }
+}
+
+// Returns the edge in a canonical orientation.
+fn canonical_edge(from: Handle, to: Handle) -> (Handle, Handle) {
+    let reverse = (flip(to), flip(from));
+    (from, to).min(reverse)
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,514 @@
+use super::*;
+
+use crate::algorithms;
+use crate::name::RelationshipKind;
+use crate::graph::{GraphInt, GraphStatistics, GraphStr};
+#[cfg(feature = "gbz")]
+use crate::gaf;
+#[cfg(feature = "gbz")]
+use crate::graph::GBZInt;
+
+#[cfg(feature = "gbz")]
+use gbz::{GBZ, support};
+#[cfg(feature = "gbz")]
+use simple_sds::serialize;
+
+#[cfg(feature = "gbz")]
+use std::fs::File;
+#[cfg(feature = "gbz")]
+use std::io::BufReader;
+
+//-----------------------------------------------------------------------------
+
+// A small graph with a bubble.
+const TARGET: &str = "S\t1\tGAT\nS\t2\tT\nS\t3\tA\nS\t4\tCA\nL\t1\t+\t2\t+\nL\t1\t+\t3\t+\nL\t2\t+\t4\t+\nL\t3\t+\t4\t+\n";
+
+// The same graph with node 4 in reverse orientation.
+const REVERSED: &str = "S\t1\tGAT\nS\t2\tT\nS\t3\tA\nS\t4\tTG\nL\t1\t+\t2\t+\nL\t1\t+\t3\t+\nL\t2\t+\t4\t-\nL\t3\t+\t4\t-\n";
+
+// The same graph with an additional node.
+const SUPERGRAPH: &str = "S\t1\tGAT\nS\t2\tT\nS\t3\tA\nS\t4\tCA\nS\t5\tG\nL\t1\t+\t2\t+\nL\t1\t+\t3\t+\nL\t2\t+\t4\t+\nL\t3\t+\t4\t+\nL\t4\t+\t5\t+\n";
+
+// The same graph with the nodes chopped differently.
+const CHOPPED: &str = "S\ta\tGA\nS\tb\tT\nS\tc\tT\nS\td\tA\nS\te\tC\nS\tf\tA\nL\ta\t+\tb\t+\nL\tb\t+\tc\t+\nL\tb\t+\td\t+\nL\tc\t+\te\t+\nL\td\t+\te\t+\nL\te\t+\tf\t+\n";
+
+// A graph with a different sequence.
+const DIFFERENT: &str = "S\ta\tGA\nS\tb\tT\nS\tc\tT\nS\td\tG\nS\te\tC\nS\tf\tA\nL\ta\t+\tb\t+\nL\tb\t+\tc\t+\nL\tb\t+\td\t+\nL\tc\t+\te\t+\nL\td\t+\te\t+\nL\te\t+\tf\t+\n";
+
+fn parse(gfa: &str) -> GraphStr {
+    algorithms::parse_gfa_bytes(gfa.as_bytes()).unwrap()
+}
+
+fn interval(from: &str, from_range: Range<usize>, to: &str, to_offset: usize, orientation: Orientation) -> TranslationInterval {
+    TranslationInterval {
+        from: from.as_bytes().to_vec(),
+        from_range,
+        to: to.as_bytes().to_vec(),
+        to_offset,
+        orientation,
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn chopped_equivalent() {
+    let result = algorithms::infer_translation(&parse(CHOPPED), &parse(TARGET)).unwrap();
+    assert_eq!(result.verdict, TranslationVerdict::Equivalent, "Wrong verdict");
+    let expected = vec![
+        interval("a", 0..2, "1", 0, Orientation::Forward),
+        interval("b", 0..1, "1", 2, Orientation::Forward),
+        interval("c", 0..1, "2", 0, Orientation::Forward),
+        interval("d", 0..1, "3", 0, Orientation::Forward),
+        interval("e", 0..1, "4", 0, Orientation::Forward),
+        interval("f", 0..1, "4", 1, Orientation::Forward),
+    ];
+    assert_eq!(result.mapping.intervals(), expected.as_slice(), "Wrong mapping");
+
+    // Translation works in both directions.
+    let result = algorithms::infer_translation(&parse(TARGET), &parse(CHOPPED)).unwrap();
+    assert_eq!(result.verdict, TranslationVerdict::Equivalent, "Wrong verdict in the other direction");
+    let expected = vec![
+        interval("1", 0..2, "a", 0, Orientation::Forward),
+        interval("1", 2..3, "b", 0, Orientation::Forward),
+        interval("2", 0..1, "c", 0, Orientation::Forward),
+        interval("3", 0..1, "d", 0, Orientation::Forward),
+        interval("4", 0..1, "e", 0, Orientation::Forward),
+        interval("4", 1..2, "f", 0, Orientation::Forward),
+    ];
+    assert_eq!(result.mapping.intervals(), expected.as_slice(), "Wrong mapping in the other direction");
+}
+
+#[test]
+fn reverse_orientation() {
+    let result = algorithms::infer_translation(&parse(CHOPPED), &parse(REVERSED)).unwrap();
+    assert_eq!(result.verdict, TranslationVerdict::Equivalent, "Wrong verdict");
+    let expected = vec![
+        interval("e", 0..1, "4", 1, Orientation::Reverse),
+        interval("f", 0..1, "4", 0, Orientation::Reverse),
+    ];
+    assert_eq!(&result.mapping.intervals()[4..], &expected, "Wrong mapping for the reversed node");
+}
+
+#[test]
+fn subgraph() {
+    let result = algorithms::infer_translation(&parse(CHOPPED), &parse(SUPERGRAPH)).unwrap();
+    assert_eq!(result.verdict, TranslationVerdict::Subgraph, "Wrong verdict");
+    assert_eq!(result.mapping.len(), 6, "Wrong number of intervals");
+
+    let result = algorithms::infer_translation(&parse(SUPERGRAPH), &parse(CHOPPED)).unwrap();
+    assert!(!result.is_translation(), "Found a translation from a supergraph");
+    assert!(result.mapping.is_empty(), "Non-empty mapping without a translation");
+}
+
+#[test]
+fn different_sequence() {
+    let result = algorithms::infer_translation(&parse(DIFFERENT), &parse(TARGET)).unwrap();
+    assert!(!result.is_translation(), "Found a translation for a different sequence");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn real_graphs() {
+    let gbz: GBZ = serialize::load_from(support::get_test_data("translation.gbz")).unwrap();
+    let gbz_graph = GBZInt { graph: gbz };
+    let reader = BufReader::new(File::open(support::get_test_data("translation.gfa")).unwrap());
+    let gfa_graph: GraphStr = algorithms::parse_gfa(reader).unwrap();
+
+    let result = algorithms::infer_translation(&gbz_graph, &gbz_graph).unwrap();
+    assert_eq!(result.verdict, TranslationVerdict::Equivalent, "GBZ graph is not equivalent to itself");
+
+    // The GFA graph contains a segment that is not in the GBZ graph.
+    let result = algorithms::infer_translation(&gbz_graph, &gfa_graph).unwrap();
+    assert_eq!(result.verdict, TranslationVerdict::Subgraph, "Wrong verdict from GBZ to GFA");
+    let expected = vec![
+        interval("1", 0..2, "s11", 0, Orientation::Forward),
+        interval("2", 0..1, "s11", 2, Orientation::Forward),
+    ];
+    assert_eq!(&result.mapping.intervals()[..2], &expected, "Wrong mapping for the chopped segment");
+
+    let result = algorithms::infer_translation(&gfa_graph, &gbz_graph).unwrap();
+    assert!(!result.is_translation(), "Found a translation from GFA to GBZ");
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn map_translate() {
+    let intervals = vec![
+        interval("x", 3..5, "2", 1, Orientation::Reverse),
+        interval("x", 0..3, "1", 0, Orientation::Forward),
+        interval("y", 1..2, "3", 0, Orientation::Forward),
+    ];
+    let map = TranslationMap::new(intervals).unwrap();
+    assert_eq!(map.len(), 3, "Wrong number of intervals");
+    assert_eq!(map.node_intervals(b"x").len(), 2, "Wrong number of intervals for x");
+    assert_eq!(map.node_intervals(b"x")[0].from_range, 0..3, "Intervals are not sorted");
+
+    assert_eq!(map.translate(b"x", 2), Some((b"1".as_slice(), 2, Orientation::Forward)));
+    assert_eq!(map.translate(b"x", 3), Some((b"2".as_slice(), 2, Orientation::Reverse)));
+    assert_eq!(map.translate(b"x", 4), Some((b"2".as_slice(), 1, Orientation::Reverse)));
+    assert_eq!(map.translate(b"x", 5), None, "Translated a position past the end");
+    assert_eq!(map.translate(b"y", 0), None, "Translated an unmapped position");
+    assert_eq!(map.translate(b"z", 0), None, "Translated a missing node");
+
+    let overlapping = vec![
+        interval("x", 0..3, "1", 0, Orientation::Forward),
+        interval("x", 2..4, "2", 0, Orientation::Forward),
+    ];
+    assert!(TranslationMap::new(overlapping).is_err(), "Accepted overlapping intervals");
+    let empty = vec![interval("x", 2..2, "1", 0, Orientation::Forward)];
+    assert!(TranslationMap::new(empty).is_err(), "Accepted an empty interval");
+}
+
+#[test]
+fn map_text_format() {
+    let map = algorithms::infer_translation(&parse(CHOPPED), &parse(REVERSED)).unwrap().mapping;
+    let mut buffer: Vec<u8> = Vec::new();
+    map.write(&mut buffer).unwrap();
+    let text = String::from_utf8(buffer.clone()).unwrap();
+    assert!(text.starts_with(TranslationMap::HEADER), "Missing header");
+    assert!(text.contains("e\t0\t1\t4\t1\t-\n"), "Missing reverse interval");
+    let copy = TranslationMap::read(buffer.as_slice()).unwrap();
+    assert_eq!(copy, map, "Wrong map after a round trip");
+
+    assert!(TranslationMap::read(b"x\t0\t1\t1\t0\n".as_slice()).is_err(), "Accepted a line with too few fields");
+    assert!(TranslationMap::read(b"x\t0\t1\t1\t0\t*\n".as_slice()).is_err(), "Accepted an invalid orientation");
+    assert!(TranslationMap::read(b"x\t2\t1\t1\t0\t+\n".as_slice()).is_err(), "Accepted an invalid interval");
+}
+
+#[test]
+fn chop_and_renumber() {
+    let graph = parse(TARGET);
+    let (chopped, map): (GraphInt, _) = chop(&graph, 2).unwrap();
+    assert_eq!(chopped.statistics(), GraphStatistics { nodes: 5, edges: 5, sequence_len: 7 }, "Wrong statistics for the chopped graph");
+    let expected = vec![
+        interval("1", 0..2, "1", 0, Orientation::Forward),
+        interval("1", 2..3, "2", 0, Orientation::Forward),
+        interval("2", 0..1, "3", 0, Orientation::Forward),
+        interval("3", 0..1, "4", 0, Orientation::Forward),
+        interval("4", 0..2, "5", 0, Orientation::Forward),
+    ];
+    assert_eq!(map.intervals(), expected.as_slice(), "Wrong translation for chopping");
+
+    // Inference should find the same translation.
+    let inferred = algorithms::infer_translation(&graph, &chopped).unwrap();
+    assert_eq!(inferred.verdict, TranslationVerdict::Equivalent, "Chopped graph is not equivalent");
+    assert_eq!(inferred.mapping, map, "Inferred translation differs from the chopping translation");
+
+    let (renumbered, map): (GraphInt, _) = renumber(&parse(CHOPPED)).unwrap();
+    assert_eq!(renumbered.statistics(), GraphStatistics { nodes: 6, edges: 6, sequence_len: 7 }, "Wrong statistics for the renumbered graph");
+    assert_eq!(map.translate(b"f", 0), Some((b"6".as_slice(), 0, Orientation::Forward)), "Wrong translation for renumbering");
+
+    assert!(chop::<GraphStr, GraphInt>(&graph, 0).is_err(), "Chopped with maximum length 0");
+
+    // A segment without a sequence does not become a 1 bp node with sequence `*`.
+    let missing = parse("S\ta\tGATTACA\nS\tb\t*\nL\ta\t+\tb\t+\n");
+    let expected = Some(String::from("Node b has no sequence"));
+    assert_eq!(chop::<GraphStr, GraphInt>(&missing, 4).err(), expected, "Chopped a graph with a missing sequence");
+    assert_eq!(renumber::<GraphStr, GraphInt>(&missing).err(), expected, "Renumbered a graph with a missing sequence");
+}
+
+#[test]
+fn normalization() {
+    // Differently chopped and oriented versions of the same graph have the same normalized graph.
+    let (normalized, map): (GraphInt, _) = normalize(&parse(CHOPPED)).unwrap();
+    assert_eq!(normalized.statistics(), GraphStatistics { nodes: 4, edges: 4, sequence_len: 7 }, "Wrong statistics for the normalized graph");
+    let inferred = algorithms::infer_translation(&parse(CHOPPED), &normalized).unwrap();
+    assert_eq!(inferred.verdict, TranslationVerdict::Equivalent, "Normalized graph is not equivalent");
+    assert_eq!(inferred.mapping, map, "Inferred translation differs from the normalization translation");
+    let expected = algorithms::stable_name(&normalized);
+    for gfa in [TARGET, REVERSED] {
+        let (other, _): (GraphInt, _) = normalize(&parse(gfa)).unwrap();
+        assert_eq!(algorithms::stable_name(&other), expected, "Wrong normalized graph for\n{}", gfa);
+    }
+    let (chopped, _): (GraphInt, _) = chop(&parse(TARGET), 1).unwrap();
+    let (other, _): (GraphInt, _) = normalize(&chopped).unwrap();
+    assert_eq!(algorithms::stable_name(&other), expected, "Wrong normalized graph after chopping");
+
+    // A node with a self-loop is a circular unitig after chopping.
+    let cycle = parse("S\ta\tTTTACCC\nL\ta\t+\ta\t+\n");
+    let (chopped, _): (GraphInt, _) = chop(&cycle, 3).unwrap();
+    let (direct, _): (GraphInt, _) = normalize(&cycle).unwrap();
+    let (other, _): (GraphInt, _) = normalize(&chopped).unwrap();
+    assert_eq!(other.statistics(), GraphStatistics { nodes: 1, edges: 1, sequence_len: 7 }, "Wrong statistics for a normalized cycle");
+    assert_eq!(algorithms::stable_name(&other), algorithms::stable_name(&direct), "Wrong normalized graph for a cycle");
+
+    // The names have translation relationships to the same normalized name.
+    let name = normalized_name(&parse(CHOPPED)).unwrap();
+    assert_eq!(name.name(), Some(&algorithms::stable_name(&parse(CHOPPED))), "Wrong name for the original graph");
+    assert_eq!(name.translation_iter().collect::<Vec<_>>(), vec![(name.name().unwrap().as_str(), expected.as_str())], "Wrong translation relationship");
+    let name = normalized_name(&normalized).unwrap();
+    assert_eq!(name.translation_iter().count(), 0, "Normalized graph has a translation relationship");
+
+    // A segment without a sequence is not merged into the unitig as `*`.
+    let missing = parse("S\ta\tACGT\nS\tb\t*\nL\ta\t+\tb\t+\n");
+    let result: Result<(GraphInt, _), String> = normalize(&missing);
+    assert_eq!(result.err(), Some(String::from("Node b has no sequence")), "Normalized a graph with a missing sequence");
+    assert!(normalized_name(&missing).is_err(), "Found a normalized name for a graph with a missing sequence");
+}
+
+// Checks that the translation maps each base of the source graph to the same base in the target graph.
+fn check_bases<A: Graph, B: Graph>(source: &A, target: &B, map: &TranslationMap, nodes: &[&str]) {
+    for node in nodes {
+        let sequence = source.sequence(node.as_bytes(), Orientation::Forward).unwrap();
+        for (offset, &base) in sequence.iter().enumerate() {
+            let (to, to_offset, orientation) = map.translate(node.as_bytes(), offset).unwrap();
+            let target_seq = target.sequence(to, orientation).unwrap();
+            let target_offset = if orientation == Orientation::Forward { to_offset } else { target_seq.len() - 1 - to_offset };
+            assert_eq!(target_seq[target_offset], base, "Wrong base for {} at offset {}", node, offset);
+        }
+    }
+}
+
+#[test]
+fn circular_normalization() {
+    // The same cycle ACGTTAGC with different labels, node boundaries, starting points, and orientations.
+    let first = build(&[("a", "ACG"), ("b", "TTA"), ("c", "GC")], &[("a", "b"), ("b", "c"), ("c", "a")]);
+    let second = build(&[("y", "TAGCA"), ("x", "CGT")], &[("y", "x"), ("x", "y")]);
+    let third = build(&[("q", "CTAA"), ("p", "CGTG")], &[("q", "p"), ("p", "q")]);
+    let single = build(&[("z", "TTAGCACG")], &[("z", "z")]);
+
+    let (normalized, map): (GraphInt, _) = normalize(&first).unwrap();
+    assert_eq!(normalized.statistics(), GraphStatistics { nodes: 1, edges: 1, sequence_len: 8 }, "Wrong statistics for a normalized cycle");
+    assert_eq!(normalized.sequence(b"1", Orientation::Forward).unwrap().as_ref(), b"AACGTGCT", "Wrong rotation for a cycle");
+    check_bases(&first, &normalized, &map, &["a", "b", "c"]);
+    let expected = algorithms::stable_name(&normalized);
+
+    let (other, map): (GraphInt, _) = normalize(&second).unwrap();
+    assert_eq!(algorithms::stable_name(&other), expected, "Wrong normalized graph for a relabelled cycle");
+    check_bases(&second, &other, &map, &["x", "y"]);
+    let (other, map): (GraphInt, _) = normalize(&third).unwrap();
+    assert_eq!(algorithms::stable_name(&other), expected, "Wrong normalized graph for a reversed cycle");
+    check_bases(&third, &other, &map, &["p", "q"]);
+    let (other, map): (GraphInt, _) = normalize(&single).unwrap();
+    assert_eq!(algorithms::stable_name(&other), expected, "Wrong normalized graph for a single-node cycle");
+    assert_eq!(map.node_intervals(b"z").len(), 2, "The rotation did not split the node");
+    check_bases(&single, &other, &map, &["z"]);
+}
+
+#[test]
+fn rename_records() {
+    let graph = parse(CHOPPED);
+    let original = algorithms::stable_name(&graph);
+
+    let (subgraph, record): (GraphStr, _) = Transformation::Extract(vec![b"a".to_vec(), b"b".to_vec(), b"d".to_vec()]).apply(&graph).unwrap();
+    assert_eq!(subgraph.statistics(), GraphStatistics { nodes: 3, edges: 2, sequence_len: 4 }, "Wrong statistics for the subgraph");
+    assert_eq!(record.old_name, original, "Wrong old name for extraction");
+    assert_eq!(record.new_name, algorithms::stable_name(&subgraph), "Wrong new name for extraction");
+    assert_eq!(record.relationship, RelationshipKind::Subgraph, "Wrong relationship for extraction");
+    assert_eq!(record.mapping.translate(b"d", 0), Some((b"d".as_slice(), 0, Orientation::Forward)), "Wrong mapping for an extracted node");
+    assert_eq!(record.mapping.translate(b"c", 0), None, "Mapping for a removed node");
+    assert_eq!(record.to_string(), format!("{}\t{}\tSG", record.old_name, record.new_name), "Wrong text form");
+    assert!(Transformation::Extract(vec![b"x".to_vec()]).apply::<GraphStr, GraphStr>(&graph).is_err(), "Extracted a missing node");
+
+    // Compose transformations and record the audit trail.
+    let mut trail = Vec::new();
+    let (merged, record): (GraphInt, _) = Transformation::Merge.apply(&graph).unwrap();
+    trail.push(record);
+    let (chopped, record): (GraphInt, _) = Transformation::Chop(1).apply(&merged).unwrap();
+    trail.push(record);
+    let (_, record): (GraphInt, _) = Transformation::Renumber.apply(&chopped).unwrap();
+    trail.push(record);
+    for pair in trail.windows(2) {
+        assert_eq!(pair[0].new_name, pair[1].old_name, "Broken audit trail");
+    }
+    assert!(trail.iter().all(|record| record.relationship == RelationshipKind::Translation), "Wrong relationship in the audit trail");
+
+    let mut name = GraphName::new(trail[2].new_name.clone());
+    for record in trail.iter() {
+        record.record(&mut name);
+    }
+    // Renumbering a graph with consecutive integer names does not change the name.
+    assert_eq!(trail[2].old_name, trail[2].new_name, "Renumbering changed the name");
+    assert_eq!(name.translation_iter().count(), 2, "Wrong number of relationships");
+    let target = GraphName::new(original);
+    assert!(name.translates_to(&target), "The final graph does not translate to the original graph");
+}
+
+#[test]
+fn chain_export() {
+    let source = parse(CHOPPED);
+    let target = parse(REVERSED);
+    let map = algorithms::infer_translation(&source, &target).unwrap().mapping;
+    let source_lengths = node_lengths(&source).unwrap();
+    let target_lengths = node_lengths(&target).unwrap();
+    assert_eq!(target_lengths.get(b"1".as_slice()), Some(&3), "Wrong length for node 1");
+
+    let mut buffer: Vec<u8> = Vec::new();
+    map.write_node_chain(&mut buffer, &source_lengths, &target_lengths).unwrap();
+    let text = String::from_utf8(buffer).unwrap();
+    let chains: Vec<&str> = text.split("\n\n").filter(|chain| !chain.is_empty()).collect();
+    assert_eq!(chains.len(), map.len(), "Wrong number of chains");
+    assert_eq!(chains[0], "chain 2 a 2 + 0 2 1 3 + 0 2 1\n2", "Wrong forward chain");
+    assert_eq!(chains[4], "chain 1 e 1 + 0 1 4 2 - 0 1 5\n1", "Wrong reverse chain");
+
+    let mut buffer: Vec<u8> = Vec::new();
+    assert!(map.write_node_chain(&mut buffer, &HashMap::new(), &target_lengths).is_err(), "Wrote a chain without source lengths");
+}
+
+// Builds a path from node names and orientations such as `["a+", "b-"]`.
+fn path(visits: &[&str]) -> Vec<(Vec<u8>, Orientation)> {
+    visits.iter().map(|visit| {
+        let (name, o) = visit.split_at(visit.len() - 1);
+        (name.as_bytes().to_vec(), if o == "+" { Orientation::Forward } else { Orientation::Reverse })
+    }).collect()
+}
+
+// Writes a chain over path coordinates.
+fn path_chain<A: Graph, B: Graph>(map: &TranslationMap, source: (&A, &[(Vec<u8>, Orientation)]), target: (&B, &[(Vec<u8>, Orientation)])) -> Result<String, String> {
+    let mut buffer: Vec<u8> = Vec::new();
+    map.write_chain(
+        &mut buffer,
+        ChainPath { name: "source", nodes: source.1 }, ChainPath { name: "target", nodes: target.1 },
+        &node_lengths(source.0).unwrap(), &node_lengths(target.0).unwrap()
+    )?;
+    Ok(String::from_utf8(buffer).unwrap())
+}
+
+#[test]
+fn path_chain_export() {
+    let source = parse(CHOPPED);
+    let target = parse(REVERSED);
+    let map = algorithms::infer_translation(&source, &target).unwrap().mapping;
+
+    // The same path in both graphs, with a target node in reverse orientation.
+    let source_path = path(&["a+", "b+", "c+", "e+", "f+"]);
+    let target_path = path(&["1+", "2+", "4-"]);
+    let chain = path_chain(&map, (&source, &source_path), (&target, &target_path));
+    assert_eq!(chain, Ok(String::from("chain 6 source 6 + 0 6 target 6 + 0 6 1\n6\n\n")), "Wrong chain for the same path");
+
+    // The source path takes the other branch of the bubble.
+    let source_path = path(&["a+", "b+", "d+", "e+", "f+"]);
+    let chain = path_chain(&map, (&source, &source_path), (&target, &target_path));
+    assert_eq!(chain, Ok(String::from("chain 5 source 6 + 0 6 target 6 + 0 6 1\n3\t1\t1\n2\n\n")), "Wrong chain with a gap");
+
+    // Both paths in reverse orientation.
+    let source_path = path(&["f-", "e-", "c-", "b-", "a-"]);
+    let target_path = path(&["4+", "2-", "1-"]);
+    let chain = path_chain(&map, (&source, &source_path), (&target, &target_path));
+    assert_eq!(chain, Ok(String::from("chain 6 source 6 + 0 6 target 6 + 0 6 1\n6\n\n")), "Wrong chain for reverse paths");
+
+    // Errors.
+    let chain = path_chain(&map, (&source, &path(&["a+"])), (&target, &path(&["4+"])));
+    assert!(chain.is_err(), "Wrote a chain without aligned blocks");
+    let chain = path_chain(&map, (&source, &path(&["x+"])), (&target, &target_path));
+    assert!(chain.is_err(), "Wrote a chain with a missing node");
+}
+
+#[cfg(feature = "gbz")]
+#[test]
+fn path_chain_real_graph() {
+    // Path A in the GBZ graph and in the GFA graph with the chopped segment.
+    let gbz: GBZ = serialize::load_from(support::get_test_data("translation.gbz")).unwrap();
+    let path_id = gbz.metadata().unwrap().find_path(&gbz::FullPathName::generic("A")).unwrap();
+    let source_path: Vec<(Vec<u8>, Orientation)> = gbz.path(path_id, Orientation::Forward).unwrap()
+        .map(|(node, o)| (node.to_string().into_bytes(), o)).collect();
+    let source = GBZInt { graph: gbz };
+    let reader = BufReader::new(File::open(support::get_test_data("translation.gfa")).unwrap());
+    let target: GraphStr = algorithms::parse_gfa(reader).unwrap();
+    let target_path = path(&["s11+", "s12+", "s14+", "s15+", "s17+"]);
+    let map = algorithms::infer_translation(&source, &target).unwrap().mapping;
+
+    let source_seq = gaf::path_sequence(&source, &source_path).unwrap();
+    let target_seq = gaf::path_sequence(&target, &target_path).unwrap();
+    assert_eq!(source_seq, target_seq, "The paths have different sequences");
+    let len = source_seq.len();
+    assert!(source_path.len() > target_path.len(), "The source path is not chopped");
+    let expected = format!("chain {} source {} + 0 {} target {} + 0 {} 1\n{}\n\n", len, len, len, len, len, len);
+    assert_eq!(path_chain(&map, (&source, &source_path), (&target, &target_path)), Ok(expected), "Wrong chain for path A");
+}
+
+#[test]
+fn interval_translation() {
+    let map = algorithms::infer_translation(&parse(TARGET), &parse(CHOPPED)).unwrap().mapping;
+    let expected = vec![
+        interval("1", 1..2, "a", 1, Orientation::Forward),
+        interval("1", 2..3, "b", 0, Orientation::Forward),
+    ];
+    assert_eq!(map.translate_interval(b"1", 1..3), expected, "Wrong translation for a split interval");
+    assert!(map.translate_interval(b"1", 3..4).is_empty(), "Translated an interval past the end");
+
+    let map = algorithms::infer_translation(&parse(REVERSED), &parse(TARGET)).unwrap().mapping;
+    let expected = vec![interval("4", 1..2, "4", 0, Orientation::Reverse)];
+    assert_eq!(map.translate_interval(b"4", 1..2), expected, "Wrong translation for a reverse interval");
+}
+
+#[test]
+fn path_liftover() {
+    let map = algorithms::infer_translation(&parse(REVERSED), &parse(CHOPPED)).unwrap().mapping;
+    let lengths = node_lengths(&parse(REVERSED)).unwrap();
+    // GAT T CA
+    let path: Vec<(Vec<u8>, Orientation)> = vec![
+        (b"1".to_vec(), Orientation::Forward),
+        (b"2".to_vec(), Orientation::Forward),
+        (b"4".to_vec(), Orientation::Reverse),
+    ];
+
+    let lifted = map.lift_path_interval(&path, &lengths, 1..5).unwrap();
+    let expected = vec![
+        NodeInterval { node: b"a".to_vec(), range: 1..2, orientation: Orientation::Forward },
+        NodeInterval { node: b"b".to_vec(), range: 0..1, orientation: Orientation::Forward },
+        NodeInterval { node: b"c".to_vec(), range: 0..1, orientation: Orientation::Forward },
+        NodeInterval { node: b"e".to_vec(), range: 0..1, orientation: Orientation::Forward },
+    ];
+    assert_eq!(lifted, expected, "Wrong lifted path interval");
+    assert!(map.lift_path_interval(&path, &lengths, 4..7).is_err(), "Lifted an interval past the end of the path");
+
+    let record = BedRecord::parse("path\t3\t6\tfeature\t0\t-").unwrap();
+    assert_eq!(record.to_string(), "path\t3\t6\tfeature\t0\t-", "Wrong BED record after a round trip");
+    let lifted: Vec<String> = map.lift_bed_record(&record, &path, &lengths).unwrap().iter().map(|record| record.to_string()).collect();
+    let expected = vec!["c\t0\t1\tfeature\t0\t-", "e\t0\t1\tfeature\t0\t-", "f\t0\t1\tfeature\t0\t-"];
+    assert_eq!(lifted, expected, "Wrong lifted BED records");
+
+    assert!(BedRecord::parse("path\t3").is_err(), "Parsed a BED record without an end");
+    assert!(BedRecord::parse("path\t3\t2").is_err(), "Parsed a BED record with an invalid interval");
+}
+
+//-----------------------------------------------------------------------------
+
+// Builds a graph with string identifiers from nodes and forward edges.
+fn build(nodes: &[(&str, &str)], edges: &[(&str, &str)]) -> GraphStr {
+    let mut graph = GraphStr::new();
+    for (name, sequence) in nodes {
+        graph.add_node(name.as_bytes(), sequence.as_bytes()).unwrap();
+    }
+    for (from, to) in edges {
+        graph.add_edge(from.as_bytes(), Orientation::Forward, to.as_bytes(), Orientation::Forward).unwrap();
+    }
+    graph.finalize().unwrap();
+    graph
+}
+
+#[test]
+fn empty_nodes() {
+    // CHOPPED with an empty node between a and b.
+    let nodes = [("a", "GA"), ("x", ""), ("b", "T"), ("c", "T"), ("d", "A"), ("e", "C"), ("f", "A")];
+    let edges = [("a", "x"), ("x", "b"), ("b", "c"), ("b", "d"), ("c", "e"), ("d", "e"), ("e", "f")];
+    let graph = build(&nodes, &edges);
+    let result = algorithms::infer_translation(&graph, &parse(TARGET)).unwrap();
+    assert_eq!(result.verdict, TranslationVerdict::Equivalent, "Wrong verdict with an empty node");
+    assert!(result.mapping.intervals().iter().all(|interval| interval.from != b"x"), "Mapped an empty node");
+
+    // An isolated empty node.
+    let mut nodes = nodes.to_vec();
+    nodes.push(("z", ""));
+    let graph = build(&nodes, &edges);
+    let result = algorithms::infer_translation(&graph, &parse(TARGET)).unwrap();
+    assert_eq!(result.verdict, TranslationVerdict::Equivalent, "Wrong verdict with an isolated empty node");
+
+    // An empty node bypassing the bubble implies an edge that does not exist in the target.
+    let nodes = [("1", "GAT"), ("2", "T"), ("3", "A"), ("4", "CA"), ("5", "")];
+    let edges = [("1", "2"), ("1", "3"), ("2", "4"), ("3", "4"), ("1", "5"), ("5", "4")];
+    let result = algorithms::infer_translation(&build(&nodes, &edges), &parse(TARGET)).unwrap();
+    assert!(!result.is_translation(), "Found a translation through an empty node");
+}
+
+#[test]
+fn missing_sequences() {
+    // A segment without a sequence is not a 1 bp node with sequence `*`.
+    let graph = parse("S\ta\tGAT\nS\tb\t*\nL\ta\t+\tb\t+\n");
+    assert_eq!(node_lengths(&graph), Err(String::from("Node b has no sequence")), "Wrong result for a node without a sequence");
+    let result = algorithms::infer_translation(&graph, &parse(TARGET));
+    assert!(result.is_err(), "Inferred a translation from a graph with a missing sequence");
+}
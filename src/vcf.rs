@@ -0,0 +1,46 @@
+//! Support for annotating VCF files with graph names.
+//!
+//! Variant calls made by graph genotypers depend on the graph they were called against.
+//! The graph name and its relationships are stored as VCF meta-information lines:
+//!
+//! ```text
+//! ##pggname=NAME
+//! ##pggname_subgraph=A,B
+//! ##pggname_translation=A,B
+//! ```
+//!
+//! See [`GraphName::from_vcf_header_lines`] and [`GraphName::to_vcf_header_lines`] for reading and writing the lines.
+
+use crate::GraphName;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Returns the VCF header lines annotated with the given graph name and relationships.
+///
+/// Existing `pggname` lines are removed.
+/// The new lines are inserted before the `#CHROM` line, or at the end if there is no such line.
+/// The lines must not end with a newline.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::{GraphName, vcf};
+///
+/// let header = vec![String::from("##fileformat=VCFv4.2"), String::from("##pggname=old"), String::from("#CHROM\tPOS")];
+/// let annotated = vcf::annotate_header_lines(&header, &GraphName::new(String::from("new")));
+/// assert_eq!(annotated, vec!["##fileformat=VCFv4.2", "##pggname=new", "#CHROM\tPOS"]);
+/// ```
+pub fn annotate_header_lines(lines: &[String], name: &GraphName) -> Vec<String> {
+    let mut result: Vec<String> = lines.iter()
+        .filter(|line| !GraphName::is_vcf_header_line(line))
+        .cloned()
+        .collect();
+    let position = result.iter().position(|line| line.starts_with("#CHROM")).unwrap_or(result.len());
+    result.splice(position..position, name.to_vcf_header_lines());
+    result
+}
+
+//-----------------------------------------------------------------------------
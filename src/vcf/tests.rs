@@ -0,0 +1,31 @@
+use super::*;
+
+//-----------------------------------------------------------------------------
+
+fn lines(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| String::from(*value)).collect()
+}
+
+#[test]
+fn annotate() {
+    let mut name = GraphName::new(String::from("C"));
+    name.add_subgraph("C", "B");
+    name.add_translation("B", "A");
+
+    let header = lines(&["##fileformat=VCFv4.2", "##pggname=X", "##pggname_subgraph=X,Y", "##contig=<ID=chr1>", "#CHROM\tPOS\tID"]);
+    let expected = lines(&[
+        "##fileformat=VCFv4.2", "##contig=<ID=chr1>",
+        "##pggname=C", "##pggname_version=1", "##pggname_subgraph=C,B", "##pggname_translation=B,A",
+        "#CHROM\tPOS\tID",
+    ]);
+    let annotated = annotate_header_lines(&header, &name);
+    assert_eq!(annotated, expected, "Wrong annotated header");
+    assert_eq!(GraphName::from_vcf_header_lines(&annotated), Ok(name.clone()), "Wrong GraphName from the annotated header");
+
+    // Without a #CHROM line.
+    let annotated = annotate_header_lines(&lines(&["##fileformat=VCFv4.2"]), &name);
+    assert_eq!(annotated.len(), 5, "Wrong number of lines without #CHROM");
+    assert_eq!(annotated[1], "##pggname=C", "Wrong position without #CHROM");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,372 @@
+//! Golden test vectors for independent implementations of the stable graph name.
+//!
+//! Each [`TestVector`] is a miniature GFA graph together with its canonical GFA representation and stable name.
+//! The vectors cover the parts of the canonical format where implementations are most likely to disagree:
+//! edge orientations, self-loops, duplicate records, ignored record types and fields, and the order of integer and string identifiers.
+//!
+//! The vectors are tagged with the version of the canonical format they were generated for.
+//! The base version is [`CANONICAL_FORMAT_VERSION`], and the extensions [`ESCAPED_FORMAT_VERSION`], [`JUMP_FORMAT_VERSION`], and [`PORTABLE_FORMAT_VERSION`] have vectors of their own.
+//! If the canonical format ever changes, the vectors for the old version remain available.
+//!
+//! Implementations in other languages can use the JSON representation from [`to_json`].
+//! The same data is in `test-vectors.json` in the repository.
+//!
+//! # Examples
+//!
+//! ```
+//! use pggname::{Graph, algorithms, vectors};
+//! use pggname::graph::GraphStr;
+//!
+//! for vector in vectors::test_vectors_for(vectors::CANONICAL_FORMAT_VERSION) {
+//!     let graph: GraphStr = algorithms::parse_gfa_bytes(vector.gfa.as_bytes()).unwrap();
+//!     assert_eq!(pggname::stable_name(&graph), vector.string.name);
+//! }
+//! ```
+
+use crate::{Graph, algorithms, jumps, seqcol};
+use crate::algorithms::{NamePolicy, ParseOptions};
+use crate::graph::{GraphInt, GraphStr};
+use crate::jumps::JumpPolicy;
+
+use sha2::Sha256;
+
+#[cfg(test)]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// Version of the canonical GFA format implemented by this crate.
+pub const CANONICAL_FORMAT_VERSION: u32 = 1;
+
+/// Version of the canonical GFA format with names and sequences escaped by [`algorithms::escape_field`].
+///
+/// Version 1 rejects names and sequences containing tabs or newlines, while version 2 escapes them.
+/// Version 2 starts the canonical representation with [`algorithms::ESCAPED_FORMAT_HEADER`]; see [`algorithms::escape_graph`] and [`algorithms::hash_escaped`].
+pub const ESCAPED_FORMAT_VERSION: u32 = 2;
+
+/// Version of the canonical GFA format extended with GFA 1.2 jump lines; see [`crate::jumps`].
+///
+/// Graphs without jumps have the same canonical representation as with version 1.
+pub const JUMP_FORMAT_VERSION: u32 = 3;
+
+/// Version of the canonical GFA format with segment names escaped by [`algorithms::NamePolicy::Escape`].
+///
+/// The policy percent-encodes all characters outside the portable set, which is a different encoding from version 2.
+/// Version 4 starts the canonical representation with [`algorithms::PORTABLE_FORMAT_HEADER`]; see [`algorithms::hash_portable`].
+pub const PORTABLE_FORMAT_VERSION: u32 = 4;
+
+/// Expected canonical GFA representation and stable name for one type of node identifiers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Expected {
+    /// Canonical GFA representation.
+    pub canonical: &'static str,
+    /// Stable name: the SHA-256 hash of the canonical representation as a lowercase hexadecimal string.
+    pub name: &'static str,
+}
+
+/// A miniature graph with its expected canonical representation and stable name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TestVector {
+    /// Version of the canonical format.
+    pub version: u32,
+    /// Short unique identifier for the vector.
+    pub id: &'static str,
+    /// What the vector tests.
+    pub description: &'static str,
+    /// Input graph in GFA format.
+    pub gfa: &'static str,
+    /// Expected result with integer identifiers, or [`None`] if some node names are not integers.
+    pub integer: Option<Expected>,
+    /// Expected result with string identifiers.
+    pub string: Expected,
+}
+
+impl TestVector {
+    /// Returns the expected result with the default identifier type.
+    ///
+    /// Integer identifiers are used if all node names are integers.
+    pub fn expected(&self) -> Expected {
+        self.integer.unwrap_or(self.string)
+    }
+
+    /// Checks that this crate produces the expected results for the vector.
+    ///
+    /// The graph is parsed and named according to the version of the canonical format.
+    /// Returns an error describing the first mismatch.
+    pub fn verify(&self) -> Result<(), String> {
+        match (self.canonical::<GraphInt>(), self.integer) {
+            (Ok(result), Some(expected)) => self.check(result, expected, "integer")?,
+            (Err(_), None) => {},
+            (Ok(_), None) => return Err(format!("{}: parsed with integer identifiers", self.id)),
+            (Err(e), Some(_)) => return Err(format!("{}: {}", self.id, e)),
+        }
+        let result = self.canonical::<GraphStr>().map_err(|e| format!("{}: {}", self.id, e))?;
+        self.check(result, self.string, "string")
+    }
+
+    // Returns the canonical representation and the stable name for the version of the vector.
+    fn canonical<G: Graph>(&self) -> Result<(Vec<u8>, String), String> {
+        let mut options = ParseOptions::default();
+        match self.version {
+            JUMP_FORMAT_VERSION => options.jumps = JumpPolicy::Include,
+            PORTABLE_FORMAT_VERSION => options.names = NamePolicy::Escape,
+            _ => {},
+        }
+        let (graph, report) = algorithms::parse_gfa_bytes_with_options::<G>(self.gfa.as_bytes(), &options)?;
+
+        let mut canonical: Vec<u8> = Vec::new();
+        let name = match self.version {
+            ESCAPED_FORMAT_VERSION => {
+                let escaped = algorithms::escape_graph(&graph)?;
+                algorithms::write_canonical_escaped(&escaped, &mut canonical)?;
+                algorithms::hash_escaped::<Sha256, _>(&escaped)
+            },
+            JUMP_FORMAT_VERSION => {
+                jumps::write_canonical(&graph, &report.jumps, &mut canonical)?;
+                jumps::stable_name(&graph, &report.jumps)?
+            },
+            PORTABLE_FORMAT_VERSION => {
+                algorithms::write_canonical_portable(&graph, &mut canonical)?;
+                algorithms::hash_portable::<Sha256, _>(&graph)
+            },
+            _ => {
+                algorithms::write_canonical(&graph, &mut canonical)?;
+                algorithms::stable_name(&graph)
+            },
+        };
+        Ok((canonical, name))
+    }
+
+    fn check(&self, result: (Vec<u8>, String), expected: Expected, ids: &str) -> Result<(), String> {
+        let (canonical, name) = result;
+        if canonical != expected.canonical.as_bytes() {
+            return Err(format!("{}: wrong canonical representation with {} identifiers", self.id, ids));
+        }
+        if name != expected.name {
+            return Err(format!("{}: wrong name with {} identifiers: {}", self.id, ids, name));
+        }
+        Ok(())
+    }
+
+    // Serializes the vector as a JSON object with the given indentation.
+    fn json_object(&self, indent: &str) -> String {
+        let expected = |expected: Option<Expected>| match expected {
+            Some(expected) => format!(
+                "{{\"canonical\": {}, \"name\": {}}}",
+                seqcol::json_string(expected.canonical), seqcol::json_string(expected.name)
+            ),
+            None => String::from("null"),
+        };
+        let fields = [
+            format!("\"version\": {}", self.version),
+            format!("\"id\": {}", seqcol::json_string(self.id)),
+            format!("\"description\": {}", seqcol::json_string(self.description)),
+            format!("\"gfa\": {}", seqcol::json_string(self.gfa)),
+            format!("\"integer\": {}", expected(self.integer)),
+            format!("\"string\": {}", expected(Some(self.string))),
+        ];
+        let separator = format!(",\n{}  ", indent);
+        format!("{{\n{}  {}\n{}}}", indent, fields.join(&separator), indent)
+    }
+}
+
+//-----------------------------------------------------------------------------
+
+/// Returns all test vectors for all versions of the canonical format.
+pub fn test_vectors() -> &'static [TestVector] {
+    TEST_VECTORS
+}
+
+/// Returns the test vectors for the given version of the canonical format.
+pub fn test_vectors_for(version: u32) -> impl Iterator<Item = &'static TestVector> {
+    TEST_VECTORS.iter().filter(move |vector| vector.version == version)
+}
+
+/// Serializes the test vectors as a JSON array.
+///
+/// Each vector is an object with fields `version`, `id`, `description`, `gfa`, `integer`, and `string`.
+/// The expected results `integer` and `string` are objects with fields `canonical` and `name`, and `integer` may be `null`.
+pub fn to_json(vectors: &[TestVector]) -> String {
+    let objects: Vec<String> = vectors.iter().map(|vector| format!("  {}", vector.json_object("  "))).collect();
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+//-----------------------------------------------------------------------------
+
+const TEST_VECTORS: &[TestVector] = &[
+    TestVector {
+        version: 1,
+        id: "empty",
+        description: "An empty graph has an empty canonical representation",
+        gfa: "",
+        integer: Some(Expected {
+            canonical: "",
+            name: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        }),
+        string: Expected {
+            canonical: "",
+            name: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        },
+    },
+    TestVector {
+        version: 1,
+        id: "single-node",
+        description: "A single node without edges",
+        gfa: "S\t1\tGATTACA\n",
+        integer: Some(Expected {
+            canonical: "S\t1\tGATTACA\n",
+            name: "fbcdc3daf7ee2bca614f5353a5723751fb5e799293942f38026735e43f0e55cb",
+        }),
+        string: Expected {
+            canonical: "S\t1\tGATTACA\n",
+            name: "fbcdc3daf7ee2bca614f5353a5723751fb5e799293942f38026735e43f0e55cb",
+        },
+    },
+    TestVector {
+        version: 1,
+        id: "gfa-spec-example",
+        description: "The example graph from the GFA specification, with header and path lines and overlaps",
+        gfa: "H\tVN:Z:1.0\nS\t11\tACCTT\nS\t12\tTCAAGG\nS\t13\tCTTGATT\nL\t11\t+\t12\t-\t0M\nL\t12\t-\t13\t+\t0M\nL\t11\t+\t13\t+\t0M\nP\t14\t11+,12-,13+\t0M,0M\n",
+        integer: Some(Expected {
+            canonical: "S\t11\tACCTT\nL\t11\t+\t12\t-\nL\t11\t+\t13\t+\nS\t12\tTCAAGG\nL\t12\t-\t13\t+\nS\t13\tCTTGATT\n",
+            name: "54b49d18354a34fbd1af9aaac279e1b3ee67b2f68f0ff79f5ebf6c50c8d922a5",
+        }),
+        string: Expected {
+            canonical: "S\t11\tACCTT\nL\t11\t+\t12\t-\nL\t11\t+\t13\t+\nS\t12\tTCAAGG\nL\t12\t-\t13\t+\nS\t13\tCTTGATT\n",
+            name: "54b49d18354a34fbd1af9aaac279e1b3ee67b2f68f0ff79f5ebf6c50c8d922a5",
+        },
+    },
+    TestVector {
+        version: 1,
+        id: "edge-orientation",
+        description: "Links written from the larger node are flipped to start from the smaller node",
+        gfa: "S\t1\tAC\nS\t2\tGT\nL\t2\t-\t1\t-\t*\nL\t2\t+\t1\t-\t*\nL\t1\t-\t2\t+\t*\n",
+        integer: Some(Expected {
+            canonical: "S\t1\tAC\nL\t1\t+\t2\t+\nL\t1\t+\t2\t-\nL\t1\t-\t2\t+\nS\t2\tGT\n",
+            name: "95d7fcb9a02f219001f666845c631fd883c97f50cf2754813e75aada02a88367",
+        }),
+        string: Expected {
+            canonical: "S\t1\tAC\nL\t1\t+\t2\t+\nL\t1\t+\t2\t-\nL\t1\t-\t2\t+\nS\t2\tGT\n",
+            name: "95d7fcb9a02f219001f666845c631fd883c97f50cf2754813e75aada02a88367",
+        },
+    },
+    TestVector {
+        version: 1,
+        id: "self-loops",
+        description: "A self-loop is canonical if at least one side is in forward orientation",
+        gfa: "S\t1\tA\nL\t1\t-\t1\t-\t*\nL\t1\t+\t1\t-\t*\nL\t1\t-\t1\t+\t*\n",
+        integer: Some(Expected {
+            canonical: "S\t1\tA\nL\t1\t+\t1\t+\nL\t1\t+\t1\t-\nL\t1\t-\t1\t+\n",
+            name: "190435ee125fc48312ad8acaf54dfe57f9e1801ae9d41fccb0879f72474f7661",
+        }),
+        string: Expected {
+            canonical: "S\t1\tA\nL\t1\t+\t1\t+\nL\t1\t+\t1\t-\nL\t1\t-\t1\t+\n",
+            name: "190435ee125fc48312ad8acaf54dfe57f9e1801ae9d41fccb0879f72474f7661",
+        },
+    },
+    TestVector {
+        version: 1,
+        id: "duplicate-records",
+        description: "Duplicate segments and links, including links in the other orientation, are listed once",
+        gfa: "S\t1\tAC\nS\t2\tGT\nL\t1\t+\t2\t+\t*\nS\t1\tAC\nL\t2\t-\t1\t-\t*\nL\t1\t+\t2\t+\t*\n",
+        integer: Some(Expected {
+            canonical: "S\t1\tAC\nL\t1\t+\t2\t+\nS\t2\tGT\n",
+            name: "9fbbf89e3b226ea7b2254fd204dcbc2668312067d4038c4d331df63f9db06f26",
+        }),
+        string: Expected {
+            canonical: "S\t1\tAC\nL\t1\t+\t2\t+\nS\t2\tGT\n",
+            name: "9fbbf89e3b226ea7b2254fd204dcbc2668312067d4038c4d331df63f9db06f26",
+        },
+    },
+    TestVector {
+        version: 1,
+        id: "ignored-records",
+        description: "Header, comment, path, walk, and containment lines, overlaps, and optional fields do not affect the name",
+        gfa: "H\tVN:Z:1.1\n# comment\nS\t1\tACGT\tLN:i:4\tRC:i:10\nS\t2\tGA\nL\t1\t+\t2\t+\t4M\tID:Z:e1\nP\tx\t1+,2+\t*\nW\tsample\t0\tchr\t0\t6\t>1>2\nC\t1\t+\t2\t+\t0\t*\n",
+        integer: Some(Expected {
+            canonical: "S\t1\tACGT\nL\t1\t+\t2\t+\nS\t2\tGA\n",
+            name: "693495acbd73a4396815be215baec193d83879baaca7cc2587d43a1e9f7a5930",
+        }),
+        string: Expected {
+            canonical: "S\t1\tACGT\nL\t1\t+\t2\t+\nS\t2\tGA\n",
+            name: "693495acbd73a4396815be215baec193d83879baaca7cc2587d43a1e9f7a5930",
+        },
+    },
+    TestVector {
+        version: 1,
+        id: "integer-order",
+        description: "Integer identifiers are sorted numerically and string identifiers lexicographically, which changes the name",
+        gfa: "S\t2\tA\nS\t10\tC\nS\t9\tG\nL\t2\t+\t10\t+\t*\nL\t9\t+\t2\t-\t*\n",
+        integer: Some(Expected {
+            canonical: "S\t2\tA\nL\t2\t+\t9\t-\nL\t2\t+\t10\t+\nS\t9\tG\nS\t10\tC\n",
+            name: "ffd92484acec7734db192424c1f56a885a20506a75d7bb576f5d4a4ad660ed4a",
+        }),
+        string: Expected {
+            canonical: "S\t10\tC\nL\t10\t-\t2\t-\nS\t2\tA\nL\t2\t+\t9\t-\nS\t9\tG\n",
+            name: "10a2dc860a39c7de97cf5a8f023a3b81b189dbf9f4cae85ccfc8b6a16302a3a5",
+        },
+    },
+    TestVector {
+        version: 1,
+        id: "string-names",
+        description: "Node names that are not integers are compared as byte strings",
+        gfa: "S\tchr1.a\tACGT\nS\tB\tGG\nS\t_x\tT\nL\tchr1.a\t+\tB\t+\t*\nL\t_x\t-\tB\t-\t*\n",
+        integer: None,
+        string: Expected {
+            canonical: "S\tB\tGG\nL\tB\t+\t_x\t+\nL\tB\t-\tchr1.a\t-\nS\t_x\tT\nS\tchr1.a\tACGT\n",
+            name: "97e4fd66f4819e83d8837e37fe6fc859f481c8afbfc24931e96155b35e5afb21",
+        },
+    },
+    TestVector {
+        version: 1,
+        id: "sequence-case",
+        description: "Sequences are used as they are, including lower case bases and IUPAC codes",
+        gfa: "S\t1\tacgtNNRY\nS\t2\tn\nL\t1\t+\t2\t+\t*\n",
+        integer: Some(Expected {
+            canonical: "S\t1\tacgtNNRY\nL\t1\t+\t2\t+\nS\t2\tn\n",
+            name: "e6f826cca84615b0d5eb4eef0d81a6e6e7512be46d8ee8a666f164fc124e4520",
+        }),
+        string: Expected {
+            canonical: "S\t1\tacgtNNRY\nL\t1\t+\t2\t+\nS\t2\tn\n",
+            name: "e6f826cca84615b0d5eb4eef0d81a6e6e7512be46d8ee8a666f164fc124e4520",
+        },
+    },
+    TestVector {
+        version: 2,
+        id: "escaped-percent",
+        description: "In version 2, percent signs in names and sequences are escaped and the representation starts with a header",
+        gfa: "S\t50%\tACGT\nS\tx\tG%A\nL\t50%\t+\tx\t-\t*\n",
+        integer: None,
+        string: Expected {
+            canonical: "H\tCF:i:2\nS\t50%25\tACGT\nL\t50%25\t+\tx\t-\nS\tx\tG%25A\n",
+            name: "7ea236c0f650e07ccd8827858ab50b521ccbaecad6ce811ea798d055b57eb493",
+        },
+    },
+    TestVector {
+        version: 3,
+        id: "jumps",
+        description: "In version 3, jumps are flipped to start from the smaller node and listed after the segments",
+        gfa: "S\t1\tACGT\nS\t2\tGA\nS\t3\tT\nL\t1\t+\t2\t+\t*\nJ\t3\t+\t1\t-\t50\tSC:i:1\nJ\t2\t-\t1\t-\t*\n",
+        integer: Some(Expected {
+            canonical: "S\t1\tACGT\nL\t1\t+\t2\t+\nS\t2\tGA\nS\t3\tT\nJ\t1\t+\t2\t+\t*\nJ\t1\t+\t3\t-\t50\n",
+            name: "582901f007a3e2d7a7b424a4249b07be9962c1473e395500396ab6ba64fadf2c",
+        }),
+        string: Expected {
+            canonical: "S\t1\tACGT\nL\t1\t+\t2\t+\nS\t2\tGA\nS\t3\tT\nJ\t1\t+\t2\t+\t*\nJ\t1\t+\t3\t-\t50\n",
+            name: "582901f007a3e2d7a7b424a4249b07be9962c1473e395500396ab6ba64fadf2c",
+        },
+    },
+    TestVector {
+        version: 4,
+        id: "portable-names",
+        description: "In version 4, non-portable characters in segment names are percent-encoded and the representation starts with a header",
+        gfa: "S\ta b\tAC\nS\tc,d\tG\nL\ta b\t+\tc,d\t-\t*\n",
+        integer: None,
+        string: Expected {
+            canonical: "H\tCF:i:4\nS\ta%20b\tAC\nL\ta%20b\t+\tc%2Cd\t-\nS\tc%2Cd\tG\n",
+            name: "29d4ef62ea7ad4348f96d4e91f197e80d44c4fcdeb4ba3d1c11e0795030bf81a",
+        },
+    },
+];
+
+//-----------------------------------------------------------------------------
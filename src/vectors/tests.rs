@@ -0,0 +1,49 @@
+use super::*;
+
+use sha2::{Digest, Sha256};
+
+use std::collections::BTreeSet;
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn verify_vectors() {
+    for vector in test_vectors() {
+        assert_eq!(vector.verify(), Ok(()), "Verification failed for {}", vector.id);
+    }
+}
+
+#[test]
+fn names_match_canonical() {
+    for vector in test_vectors() {
+        for expected in vector.integer.iter().chain(std::iter::once(&vector.string)) {
+            let hash = format!("{:x}", Sha256::digest(expected.canonical.as_bytes()));
+            assert_eq!(hash, expected.name, "Name does not match the canonical representation for {}", vector.id);
+        }
+    }
+}
+
+#[test]
+fn vector_metadata() {
+    let ids: BTreeSet<&str> = test_vectors().iter().map(|vector| vector.id).collect();
+    assert_eq!(ids.len(), test_vectors().len(), "Vector identifiers are not unique");
+    let versions = [CANONICAL_FORMAT_VERSION, ESCAPED_FORMAT_VERSION, JUMP_FORMAT_VERSION, PORTABLE_FORMAT_VERSION];
+    for version in versions {
+        assert!(test_vectors_for(version).count() > 0, "No vectors for version {}", version);
+    }
+    assert!(test_vectors().iter().all(|vector| versions.contains(&vector.version)), "Vectors for an unknown version");
+
+    let differ = test_vectors().iter().find(|vector| vector.id == "integer-order").unwrap();
+    assert_ne!(differ.expected(), differ.string, "Default result should use integer identifiers");
+    let strings = test_vectors().iter().find(|vector| vector.id == "string-names").unwrap();
+    assert_eq!(strings.expected(), strings.string, "Default result should use string identifiers");
+}
+
+#[test]
+fn json_file() {
+    let json = to_json(test_vectors());
+    assert_eq!(json, include_str!("../../test-vectors.json"), "test-vectors.json is out of date");
+    assert!(json.starts_with("[\n  {\n    \"version\": 1,\n    \"id\": \"empty\","), "Unexpected JSON layout");
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,103 @@
+//! Reading the canonical GFA representation of a graph as a stream.
+//!
+//! The canonical GFA representation of a large graph may take hundreds of gigabytes, even if the graph itself is a compact GBZ file.
+//! [`CanonicalReader`] exposes it as a read-only byte stream that serializes the nodes on demand, one node at a time.
+//! It implements [`Read`] and [`BufRead`], so it can be passed to any code that reads GFA, including [`crate::algorithms::parse_gfa`].
+//!
+//! With the `fifo` feature, the `view` subcommand of the command line tool serves the stream through a named pipe, so that external tools expecting a GFA file path can read the canonical form without materializing it.
+
+use crate::Graph;
+
+use std::io::{self, BufRead, Read};
+
+#[cfg(all(test, feature = "gbz"))]
+mod tests;
+
+//-----------------------------------------------------------------------------
+
+/// A read-only byte stream over the canonical GFA representation of a graph.
+///
+/// The stream is produced in chunks, each of which contains the segment line for a node followed by the canonical link lines starting from it.
+/// Only the current chunk is kept in memory.
+///
+/// # Examples
+///
+/// ```
+/// use pggname::algorithms;
+/// use pggname::graph::GraphInt;
+/// use pggname::view::CanonicalReader;
+/// use std::io::Read;
+///
+/// let graph: GraphInt = algorithms::parse_gfa_bytes(b"S\t2\tGA\nS\t1\tACGT\nL\t2\t-\t1\t-\t*\n").unwrap();
+/// let mut reader = CanonicalReader::new(&graph);
+/// let mut canonical = String::new();
+/// reader.read_to_string(&mut canonical).unwrap();
+/// assert_eq!(canonical, "S\t1\tACGT\nL\t1\t+\t2\t+\nS\t2\tGA\n");
+/// assert_eq!(reader.bytes_read(), canonical.len() as u64);
+/// ```
+pub struct CanonicalReader<'a> {
+    nodes: Box<dyn Iterator<Item = Vec<u8>> + 'a>,
+    chunk: Vec<u8>,
+    offset: usize,
+    bytes_read: u64,
+}
+
+impl<'a> CanonicalReader<'a> {
+    /// Creates a reader over the canonical GFA representation of the graph.
+    pub fn new<G: Graph>(graph: &'a G) -> Self {
+        CanonicalReader { nodes: Box::new(graph.node_iter()), chunk: Vec::new(), offset: 0, bytes_read: 0 }
+    }
+
+    /// Returns the rest of the current chunk, or the next chunk if the current one has been consumed.
+    ///
+    /// Returns [`None`] at the end of the stream.
+    pub fn next_chunk(&mut self) -> Option<Vec<u8>> {
+        let result = if self.offset < self.chunk.len() {
+            self.chunk.split_off(self.offset)
+        } else {
+            self.nodes.next()?
+        };
+        self.chunk.clear();
+        self.offset = 0;
+        self.bytes_read += result.len() as u64;
+        Some(result)
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+}
+
+impl Read for CanonicalReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl BufRead for CanonicalReader<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.offset >= self.chunk.len() {
+            match self.nodes.next() {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.offset = 0;
+                }
+                None => return Ok(&[]),
+            }
+        }
+        Ok(&self.chunk[self.offset..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let amt = amt.min(self.chunk.len() - self.offset);
+        self.offset += amt;
+        self.bytes_read += amt as u64;
+    }
+}
+
+//-----------------------------------------------------------------------------
@@ -0,0 +1,78 @@
+use super::*;
+
+use crate::algorithms;
+use crate::graph::{GBZInt, GraphStr};
+
+use gbz::{GBZ, support};
+use simple_sds::serialize;
+
+//-----------------------------------------------------------------------------
+
+fn example() -> GBZInt {
+    let filename = support::get_test_data("example.gbz");
+    let graph: GBZ = serialize::load_from(&filename).unwrap();
+    GBZInt { graph }
+}
+
+fn canonical<G: Graph>(graph: &G) -> Vec<u8> {
+    let mut result: Vec<u8> = Vec::new();
+    algorithms::write_canonical(graph, &mut result).unwrap();
+    result
+}
+
+//-----------------------------------------------------------------------------
+
+#[test]
+fn read_all() {
+    let graph = example();
+    let expected = canonical(&graph);
+    for buffer_size in [1, 7, 4096] {
+        let mut reader = CanonicalReader::new(&graph);
+        let mut result: Vec<u8> = Vec::new();
+        let mut buffer = vec![0; buffer_size];
+        loop {
+            let len = reader.read(&mut buffer).unwrap();
+            if len == 0 {
+                break;
+            }
+            result.extend_from_slice(&buffer[..len]);
+        }
+        assert_eq!(result, expected, "Wrong canonical GFA with buffer size {}", buffer_size);
+        assert_eq!(reader.bytes_read(), expected.len() as u64, "Wrong number of bytes read with buffer size {}", buffer_size);
+    }
+}
+
+#[test]
+fn chunks() {
+    let graph = example();
+    let expected = canonical(&graph);
+
+    // A partially consumed chunk is returned first.
+    let mut reader = CanonicalReader::new(&graph);
+    let mut result = vec![0; 3];
+    reader.read_exact(&mut result).unwrap();
+    let mut chunks = 0;
+    while let Some(chunk) = reader.next_chunk() {
+        assert!(!chunk.is_empty(), "Empty chunk");
+        result.extend_from_slice(&chunk);
+        chunks += 1;
+    }
+    assert_eq!(result, expected, "Wrong canonical GFA from chunks");
+    assert_eq!(chunks, 12, "Wrong number of chunks");
+    assert_eq!(reader.bytes_read(), expected.len() as u64, "Wrong number of bytes read from chunks");
+    assert!(reader.fill_buf().unwrap().is_empty(), "Data after the end of the stream");
+}
+
+#[test]
+fn parse_back() {
+    let graph = example();
+    let parsed: GraphStr = algorithms::parse_gfa(CanonicalReader::new(&graph)).unwrap();
+    assert_eq!(crate::stable_name(&parsed), crate::stable_name(&graph), "Wrong name after parsing the stream");
+
+    let empty = GraphStr::new();
+    let mut reader = CanonicalReader::new(&empty);
+    assert_eq!(reader.next_chunk(), None, "Chunk from an empty graph");
+    assert_eq!(reader.bytes_read(), 0, "Bytes read from an empty graph");
+}
+
+//-----------------------------------------------------------------------------
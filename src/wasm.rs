@@ -0,0 +1,111 @@
+//! JavaScript API for WebAssembly builds.
+//!
+//! This module is only available with the `wasm` feature.
+//! GBZ support and file access are not available in the browser, so WebAssembly builds should disable the default features:
+//!
+//! ```text
+//! wasm-pack build --target web -- --no-default-features --features wasm
+//! ```
+//!
+//! The GFA input is passed to a [`NameHasher`] in chunks, for example from a `ReadableStream`:
+//!
+//! ```text
+//! const hasher = new NameHasher();
+//! for await (const chunk of file.stream()) {
+//!     hasher.update(chunk);
+//! }
+//! const name = hasher.finish();
+//! const ok = matchesGafReference(gafHeader, name);
+//! ```
+
+use crate::GraphName;
+use crate::algorithms::{self, GfaStream};
+use crate::graph::{GraphInt, GraphStr};
+
+use wasm_bindgen::prelude::*;
+
+//-----------------------------------------------------------------------------
+
+/// Computes the stable name of a GFA graph given in chunks.
+///
+/// Node identifiers are interpreted as integers if possible, with a fallback to strings, as in [`algorithms::NodeIds::Auto`].
+#[wasm_bindgen]
+pub struct NameHasher {
+    // Dropped when a node identifier is not an integer.
+    int: Option<GfaStream<GraphInt>>,
+    str: GfaStream<GraphStr>,
+}
+
+#[wasm_bindgen]
+impl NameHasher {
+    /// Creates a new hasher.
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        NameHasher { int: Some(GfaStream::new()), str: GfaStream::new() }
+    }
+
+    /// Parses the next chunk of the GFA input.
+    ///
+    /// Throws an error if the input cannot be parsed.
+    pub fn update(&mut self, chunk: &[u8]) -> Result<(), JsError> {
+        if let Some(int) = self.int.as_mut() && int.push(chunk).is_err() {
+            self.int = None;
+        }
+        self.str.push(chunk).map_err(|e| JsError::new(&e))
+    }
+
+    /// Finishes parsing and returns the stable name of the graph.
+    ///
+    /// Throws an error if the input cannot be parsed.
+    pub fn finish(self) -> Result<String, JsError> {
+        if let Some(int) = self.int && let Ok(graph) = int.finish() {
+            return Ok(algorithms::stable_name(&graph));
+        }
+        let graph = self.str.finish().map_err(|e| JsError::new(&e))?;
+        Ok(algorithms::stable_name(&graph))
+    }
+}
+
+/// Computes the stable name of a GFA graph given as a single buffer.
+///
+/// Throws an error if the input cannot be parsed.
+#[wasm_bindgen(js_name = stableName)]
+pub fn stable_name(data: &[u8]) -> Result<String, JsError> {
+    let mut hasher = NameHasher::new();
+    hasher.update(data)?;
+    hasher.finish()
+}
+
+//-----------------------------------------------------------------------------
+
+/// Returns the reference name (`@RN`) claimed by the GAF header, or `undefined`.
+///
+/// Throws an error if the header cannot be parsed.
+#[wasm_bindgen(js_name = gafReferenceName)]
+pub fn gaf_reference_name(header: &str) -> Result<Option<String>, JsError> {
+    let claimed = parse_header(header)?;
+    Ok(claimed.name().cloned())
+}
+
+/// Returns `true` if the graph with the given name can be used as the reference for the GAF file.
+///
+/// This is the case if the graph is the reference claimed by the header, or if the header states that the claimed reference is a subgraph of it.
+/// Throws an error if the header cannot be parsed.
+#[wasm_bindgen(js_name = matchesGafReference)]
+pub fn matches_gaf_reference(header: &str, name: &str) -> Result<bool, JsError> {
+    let claimed = parse_header(header)?;
+    let graph = GraphName::new(String::from(name));
+    Ok(claimed.is_same(&graph) || claimed.is_subgraph_of(&graph))
+}
+
+// Parses the header lines at the start of the GAF input.
+fn parse_header(header: &str) -> Result<GraphName, JsError> {
+    let lines: Vec<String> = header.lines()
+        .take_while(|line| line.starts_with('@'))
+        .map(String::from)
+        .collect();
+    GraphName::from_header_lines(&lines).map_err(|e| JsError::new(&e))
+}
+
+//-----------------------------------------------------------------------------